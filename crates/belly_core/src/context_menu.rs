@@ -0,0 +1,330 @@
+use std::iter::once;
+
+use bevy::{ecs::entity::Entities, prelude::*};
+use tagstr::*;
+
+use crate::{eml::build::ElementContextData, input::PointerInput, touch::touch_position};
+use crate::{ElementBuilderRegistry, Variant};
+
+pub(crate) struct ContextMenuPlugin;
+impl Plugin for ContextMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OpenContextMenu>()
+            .add_system_to_stage(CoreStage::PostUpdate, context_menu_system);
+    }
+}
+
+/// The tag of the registered widget opened by the secondary press over
+/// the element, set by the `context-menu` param:
+/// ```rust,ignore
+/// commands.add(eml! {
+///     <div c:file context-menu="filemenu"/>
+/// });
+/// ```
+/// The widget is spawned at the pointer, it gets the [`ContextMenuPopup`]
+/// component to find out the element it is opened for.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextMenu(pub Tag);
+
+impl ContextMenu {
+    pub fn from_variant(value: Variant) -> Option<ContextMenu> {
+        match value {
+            Variant::String(tag) => Some(ContextMenu(tag.as_tag())),
+            variant => variant.take::<ContextMenu>(),
+        }
+    }
+}
+
+/// The element shown as the context menu of the element, set by the
+/// `<contextmenu>` widget. Unlike the widget built by [`ContextMenu`],
+/// the element is hidden when the menu is closed and shown again by the
+/// next secondary press.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextMenuElement(pub Entity);
+
+/// The press on the element inside of the context menu doesn't close
+/// the menu, like the press on the item opening the submenu.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct KeepContextMenuOpen;
+
+/// Inserted into the root of the opened context menu.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ContextMenuPopup {
+    /// The element the menu is opened for.
+    pub owner: Entity,
+    /// The pointer position the menu is anchored at.
+    pub pos: Vec2,
+}
+
+/// Keeps the context menu opened, if any. The menu is closed when some
+/// element inside it is pressed, when the pointer is pressed outside of
+/// it and when the `Escape` key is pressed.
+#[derive(Resource, Default)]
+pub struct OpenContextMenu {
+    popup: Option<Entity>,
+    /// The popup is the [`ContextMenuElement`], it is hidden on close.
+    reused: bool,
+    close: bool,
+}
+
+impl OpenContextMenu {
+    pub fn get(&self) -> Option<Entity> {
+        self.popup
+    }
+
+    /// Closes the menu at the end of the frame.
+    pub fn close(&mut self) {
+        self.close = true;
+    }
+}
+
+enum MenuSource {
+    Tag(Tag),
+    Element(Entity),
+}
+
+fn contains(node: &Node, transform: &GlobalTransform, point: Vec2) -> bool {
+    let position = transform.translation().truncate();
+    let min = position - node.size() * 0.5;
+    let max = position + node.size() * 0.5;
+    (min.x..max.x).contains(&point.x) && (min.y..max.y).contains(&point.y)
+}
+
+pub fn context_menu_system(
+    mut commands: Commands,
+    mut menu: ResMut<OpenContextMenu>,
+    mut pointer: EventReader<PointerInput>,
+    mouse: Res<Input<MouseButton>>,
+    touches: Res<Touches>,
+    keyboard: Res<Input<KeyCode>>,
+    windows: Res<Windows>,
+    registry: Res<ElementBuilderRegistry>,
+    entities: &Entities,
+    owners: Query<(Option<&ContextMenu>, Option<&ContextMenuElement>)>,
+    keep_open: Query<(), With<KeepContextMenuOpen>>,
+    parents: Query<&Parent>,
+    children: Query<&Children>,
+    nodes: Query<(&Node, &GlobalTransform, Option<&ComputedVisibility>)>,
+) {
+    if matches!(menu.popup, Some(popup) if !entities.contains(popup)) {
+        menu.popup = None;
+    }
+    let mut open = None;
+    for event in pointer.iter() {
+        if event.secondary_press() {
+            // the menu of the closest ancestor is opened
+            let owner = event
+                .entities
+                .iter()
+                .flat_map(|e| once(*e).chain(parents.iter_ancestors(*e)))
+                .find_map(|e| match owners.get(e) {
+                    Ok((_, Some(element))) => Some((e, MenuSource::Element(element.0))),
+                    Ok((Some(menu), _)) => Some((e, MenuSource::Tag(menu.0))),
+                    _ => None,
+                });
+            if let Some((owner, source)) = owner {
+                open = Some((owner, source, event.pos));
+            }
+        } else if let (true, Some(popup)) = (event.pressed(), menu.popup) {
+            let inside =
+                |e: &&Entity| **e == popup || parents.iter_ancestors(**e).any(|a| a == popup);
+            if let Some(pressed) = event.entities.iter().find(inside) {
+                if !keep_open.contains(*pressed) {
+                    menu.close = true;
+                }
+            }
+        }
+    }
+    if let Some(popup) = menu.popup {
+        let pressed = mouse.get_just_pressed().next().is_some() || touches.any_just_pressed();
+        let pointer = windows
+            .get_primary()
+            .and_then(|window| window.cursor_position())
+            .or_else(|| touches.first_pressed_position())
+            .map(|position| touch_position(&windows, position));
+        // the submenus are placed outside of the popup node
+        let outside = match pointer {
+            Some(pointer) => !once(popup)
+                .chain(children.iter_descendants(popup))
+                .filter_map(|e| nodes.get(e).ok())
+                .filter(|(_, _, visibility)| visibility.map(|v| v.is_visible()).unwrap_or(true))
+                .any(|(node, transform, _)| contains(node, transform, pointer)),
+            None => true,
+        };
+        if (pressed && outside) || keyboard.just_pressed(KeyCode::Escape) {
+            menu.close = true;
+        }
+    }
+    if menu.close || open.is_some() {
+        match menu.popup.take() {
+            Some(popup) if menu.reused && entities.contains(popup) => {
+                commands
+                    .entity(popup)
+                    .remove::<ContextMenuPopup>()
+                    .insert(Visibility { is_visible: false });
+                commands.add(move |world: &mut World| {
+                    if let Some(mut style) = world.get_mut::<Style>(popup) {
+                        style.display = Display::None;
+                    }
+                });
+            }
+            Some(popup) if !menu.reused => commands.entity(popup).despawn_recursive(),
+            _ => {}
+        }
+    }
+    menu.close = false;
+
+    let Some((owner, source, pos)) = open else {
+        return;
+    };
+    let popup = match source {
+        MenuSource::Tag(tag) => {
+            let Some(builder) = registry.get_builder(tag) else {
+                error!("Unknown context menu tag: {}", tag.as_str());
+                return;
+            };
+            let popup = commands.spawn_empty().id();
+            commands.add(move |world: &mut World| {
+                builder.build(world, ElementContextData::new(popup));
+                place_popup(world, popup, pos);
+            });
+            popup
+        }
+        MenuSource::Element(popup) if entities.contains(popup) => {
+            commands
+                .entity(popup)
+                .insert(Visibility { is_visible: true });
+            commands.add(move |world: &mut World| place_popup(world, popup, pos));
+            popup
+        }
+        MenuSource::Element(_) => return,
+    };
+    commands.entity(popup).insert((
+        ContextMenuPopup { owner, pos },
+        ZIndex::Global(i32::MAX - 1),
+    ));
+    menu.reused = matches!(source, MenuSource::Element(_));
+    menu.popup = Some(popup);
+}
+
+fn place_popup(world: &mut World, popup: Entity, pos: Vec2) {
+    let Some(mut style) = world.get_mut::<Style>(popup) else {
+        return;
+    };
+    style.display = Display::Flex;
+    style.position_type = PositionType::Absolute;
+    style.position.left = Val::Px(pos.x);
+    style.position.top = Val::Px(pos.y);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::PointerInputData;
+
+    #[test]
+    fn close_context_menu() {
+        let mut app = App::new();
+        app.add_event::<PointerInput>()
+            .init_resource::<OpenContextMenu>()
+            .init_resource::<ElementBuilderRegistry>()
+            .init_resource::<Input<MouseButton>>()
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<Touches>()
+            .init_resource::<Windows>()
+            .add_system(context_menu_system);
+        let spawn_menu = |app: &mut App| {
+            let popup = app.world.spawn_empty().id();
+            let item = app.world.spawn_empty().id();
+            app.world.entity_mut(popup).push_children(&[item]);
+            app.world.resource_mut::<OpenContextMenu>().popup = Some(popup);
+            (popup, item)
+        };
+
+        let (popup, _) = spawn_menu(&mut app);
+        app.update();
+        assert_eq!(app.world.resource::<OpenContextMenu>().get(), Some(popup));
+
+        app.world
+            .resource_mut::<Input<KeyCode>>()
+            .press(KeyCode::Escape);
+        app.update();
+        assert_eq!(app.world.resource::<OpenContextMenu>().get(), None);
+        assert!(app.world.get_entity(popup).is_none());
+
+        app.world.resource_mut::<Input<KeyCode>>().clear();
+        let (popup, item) = spawn_menu(&mut app);
+        app.world
+            .resource_mut::<Events<PointerInput>>()
+            .send(PointerInput {
+                entities: vec![item],
+                pos: Vec2::ZERO,
+                delta: Vec2::ZERO,
+                data: PointerInputData::Pressed { presses: 0 },
+            });
+        app.update();
+        assert!(
+            app.world.get_entity(popup).is_none(),
+            "Should close the menu when the item is pressed"
+        );
+    }
+
+    #[test]
+    fn reuse_context_menu_element() {
+        let mut app = App::new();
+        app.add_event::<PointerInput>()
+            .init_resource::<OpenContextMenu>()
+            .init_resource::<ElementBuilderRegistry>()
+            .init_resource::<Input<MouseButton>>()
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<Touches>()
+            .init_resource::<Windows>()
+            .add_system(context_menu_system);
+        let popup = app.world.spawn(Style::default()).id();
+        let submenu = app.world.spawn(KeepContextMenuOpen).id();
+        let item = app.world.spawn_empty().id();
+        app.world.entity_mut(popup).push_children(&[submenu, item]);
+        let owner = app.world.spawn(ContextMenuElement(popup)).id();
+        let press = |app: &mut App, entity: Entity, data: PointerInputData| {
+            app.world
+                .resource_mut::<Events<PointerInput>>()
+                .send(PointerInput {
+                    entities: vec![entity],
+                    pos: Vec2::new(10., 20.),
+                    delta: Vec2::ZERO,
+                    data,
+                });
+            app.update();
+        };
+
+        press(&mut app, owner, PointerInputData::SecondaryPress);
+        assert_eq!(app.world.resource::<OpenContextMenu>().get(), Some(popup));
+        assert_eq!(
+            app.world.get::<ContextMenuPopup>(popup).unwrap().owner,
+            owner
+        );
+        let style = app.world.get::<Style>(popup).unwrap();
+        assert_eq!(style.position.left, Val::Px(10.));
+        assert_eq!(style.display, Display::Flex);
+
+        press(&mut app, submenu, PointerInputData::Pressed { presses: 0 });
+        assert_eq!(
+            app.world.resource::<OpenContextMenu>().get(),
+            Some(popup),
+            "Should keep the menu open when the submenu item is pressed"
+        );
+
+        press(&mut app, item, PointerInputData::Pressed { presses: 0 });
+        assert_eq!(app.world.resource::<OpenContextMenu>().get(), None);
+        assert!(
+            app.world.get_entity(popup).is_some(),
+            "Should hide the context menu element instead of despawning it"
+        );
+        assert!(!app.world.get::<Visibility>(popup).unwrap().is_visible);
+        assert_eq!(
+            app.world.get::<Style>(popup).unwrap().display,
+            Display::None
+        );
+        assert!(app.world.get::<ContextMenuPopup>(popup).is_none());
+    }
+}
@@ -0,0 +1,302 @@
+use std::{any::Any, sync::Arc};
+
+use bevy::{ecs::entity::Entities, prelude::*};
+
+use crate::{
+    input::{self, PointerInput},
+    relations::Signal,
+    tags, Elements, States,
+};
+
+pub(crate) struct DragAndDropPlugin;
+impl Plugin for DragAndDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DragEvent>()
+            .init_resource::<DragAndDrop>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                drag_system
+                    .label(input::Label::DragAndDrop)
+                    .after(input::Label::Signals),
+            );
+    }
+}
+
+/// Allows to drag the element, set by the `draggable` param.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Draggable;
+
+/// Allows to drop the dragged elements onto the element, set by the
+/// `droppable` param.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Droppable;
+
+/// The value carried by the dragged element to the drop target. The
+/// `drag-payload` param sets the `String` payload, any other value is
+/// inserted from the code:
+/// ```rust,ignore
+/// commands.entity(card).insert(DragPayload::new(CardId(7)));
+/// ```
+#[derive(Component, Clone)]
+pub struct DragPayload(Arc<dyn Any + Send + Sync>);
+
+impl DragPayload {
+    pub fn new<T: Any + Send + Sync>(value: T) -> DragPayload {
+        DragPayload(Arc::new(value))
+    }
+
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragEventData {
+    /// The dragging of the source is started.
+    Start,
+    /// The dragged element entered the drop target.
+    Over,
+    /// The dragged element left the drop target.
+    Leave,
+    /// The dragged element is dropped onto the target.
+    Drop,
+    /// The dragging of the source is over.
+    End { dropped: bool },
+}
+
+/// The stage of the drag-and-drop. `Start` and `End` are sent to the
+/// dragged element, the rest are sent to the drop target.
+pub struct DragEvent {
+    pub entities: [Entity; 1],
+    /// The dragged element.
+    pub source: Entity,
+    pub payload: Option<DragPayload>,
+    pub data: DragEventData,
+}
+
+impl DragEvent {
+    pub fn started(&self) -> bool {
+        self.data == DragEventData::Start
+    }
+    pub fn over(&self) -> bool {
+        self.data == DragEventData::Over
+    }
+    pub fn left(&self) -> bool {
+        self.data == DragEventData::Leave
+    }
+    pub fn dropped(&self) -> bool {
+        self.data == DragEventData::Drop
+    }
+    pub fn ended(&self) -> bool {
+        matches!(self.data, DragEventData::End { .. })
+    }
+    pub fn payload<T: Any>(&self) -> Option<&T> {
+        self.payload.as_ref().and_then(|p| p.get())
+    }
+}
+
+impl Signal for DragEvent {
+    fn sources(&self) -> &[Entity] {
+        &self.entities
+    }
+}
+
+/// The element being dragged.
+#[derive(Clone)]
+pub struct Dragging {
+    pub source: Entity,
+    pub payload: Option<DragPayload>,
+    /// The pointer position.
+    pub pos: Vec2,
+    /// The offset of the pointer from the center of the source
+    /// at the moment the dragging is started.
+    pub offset: Vec2,
+    /// The drop target under the pointer.
+    pub over: Option<Entity>,
+}
+
+/// Keeps the drag-and-drop going on, if any.
+#[derive(Resource, Default)]
+pub struct DragAndDrop(Option<Dragging>);
+
+impl DragAndDrop {
+    pub fn get(&self) -> Option<&Dragging> {
+        self.0.as_ref()
+    }
+}
+
+fn enabled(states: Option<&States>) -> bool {
+    !states.map_or(false, |s| s.contains(&tags::disabled()))
+}
+
+/// Turns the pointer dragging of the [`Draggable`] elements into the
+/// [`DragEvent`]s. The source gets the `:dragging` state while it is
+/// dragged, the drop target under the pointer gets the `:dragover` state.
+pub fn drag_system(
+    mut state: ResMut<DragAndDrop>,
+    mut pointer: EventReader<PointerInput>,
+    mut elements: Elements,
+    entities: &Entities,
+    draggable: Query<
+        (
+            Option<&DragPayload>,
+            Option<&States>,
+            Option<&GlobalTransform>,
+        ),
+        With<Draggable>,
+    >,
+    droppable: Query<Option<&States>, With<Droppable>>,
+    mut events: EventWriter<DragEvent>,
+) {
+    if matches!(&state.0, Some(drag) if !entities.contains(drag.source)) {
+        if let Some(over) = state.0.take().and_then(|drag| drag.over) {
+            elements.set_state(over, tags::dragover(), false);
+        }
+    }
+    for event in pointer.iter() {
+        if event.drag_start() && state.0.is_none() {
+            let source = event.entities.iter().find_map(|e| {
+                let (payload, states, transform) = draggable.get(*e).ok()?;
+                enabled(states).then(|| (*e, payload.cloned(), transform))
+            });
+            let Some((source, payload, transform)) = source else {
+                continue;
+            };
+            let center = transform.map_or(event.pos, |t| t.translation().truncate());
+            elements.set_state(source, tags::dragging(), true);
+            events.send(DragEvent {
+                entities: [source],
+                source,
+                payload: payload.clone(),
+                data: DragEventData::Start,
+            });
+            state.0 = Some(Dragging {
+                source,
+                payload,
+                pos: event.pos,
+                offset: event.pos - center,
+                over: None,
+            });
+            continue;
+        }
+        if !event.dragging() && !event.drag_stop() {
+            continue;
+        }
+        let Some(drag) = state.0.as_mut() else {
+            continue;
+        };
+        drag.pos = event.pos;
+        let over = event
+            .entities
+            .iter()
+            .copied()
+            .find(|e| *e != drag.source && droppable.get(*e).map_or(false, enabled));
+        let send = |events: &mut EventWriter<DragEvent>, target, data| {
+            events.send(DragEvent {
+                entities: [target],
+                source: drag.source,
+                payload: drag.payload.clone(),
+                data,
+            })
+        };
+        let stop = event.drag_stop();
+        if over != drag.over {
+            if let Some(left) = drag.over {
+                elements.set_state(left, tags::dragover(), false);
+                send(&mut events, left, DragEventData::Leave);
+            }
+            if let Some(over) = over {
+                if !stop {
+                    elements.set_state(over, tags::dragover(), true);
+                }
+                send(&mut events, over, DragEventData::Over);
+            }
+        }
+        if !stop {
+            drag.over = over;
+            continue;
+        }
+        if let Some(target) = over {
+            if drag.over == over {
+                elements.set_state(target, tags::dragover(), false);
+            }
+            send(&mut events, target, DragEventData::Drop);
+        }
+        elements.set_state(drag.source, tags::dragging(), false);
+        let dropped = over.is_some();
+        send(&mut events, drag.source, DragEventData::End { dropped });
+        state.0 = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{input::PointerInputData, Element};
+
+    fn pointer(entities: Vec<Entity>, data: PointerInputData) -> PointerInput {
+        PointerInput {
+            entities,
+            pos: Vec2::new(10., 10.),
+            delta: Vec2::ONE,
+            data,
+        }
+    }
+
+    #[test]
+    fn drag_and_drop() {
+        let mut app = App::new();
+        app.add_event::<PointerInput>()
+            .add_event::<DragEvent>()
+            .init_resource::<DragAndDrop>()
+            .add_system(drag_system);
+        let card = app
+            .world
+            .spawn((Element::default(), Draggable, DragPayload::new(7_u32)))
+            .id();
+        let slot = app.world.spawn((Element::default(), Droppable)).id();
+        let send = |app: &mut App, event: PointerInput| {
+            app.world.resource_mut::<Events<PointerInput>>().send(event);
+            app.update();
+            let events = app.world.resource::<Events<DragEvent>>();
+            events
+                .iter_current_update_events()
+                .map(|e| (e.entities[0], e.data, e.payload::<u32>().copied()))
+                .collect::<Vec<_>>()
+        };
+        let states = |app: &App, entity: Entity| app.world.get::<States>(entity).cloned();
+
+        assert_eq!(
+            send(&mut app, pointer(vec![card], PointerInputData::DragStart)),
+            vec![(card, DragEventData::Start, Some(7))]
+        );
+        assert!(states(&app, card).unwrap().contains(&tags::dragging()));
+
+        let drag = PointerInputData::Drag { from: vec![card] };
+        assert_eq!(
+            send(&mut app, pointer(vec![slot], drag.clone())),
+            vec![(slot, DragEventData::Over, Some(7))]
+        );
+        assert!(states(&app, slot).unwrap().contains(&tags::dragover()));
+        assert_eq!(
+            send(&mut app, pointer(vec![], drag)),
+            vec![(slot, DragEventData::Leave, Some(7))],
+            "Should leave the target when the pointer is over nothing"
+        );
+        assert!(!states(&app, slot).unwrap().contains(&tags::dragover()));
+
+        assert_eq!(
+            send(
+                &mut app,
+                pointer(vec![card, slot], PointerInputData::DragStop)
+            ),
+            vec![
+                (slot, DragEventData::Over, Some(7)),
+                (slot, DragEventData::Drop, Some(7)),
+                (card, DragEventData::End { dropped: true }, Some(7)),
+            ]
+        );
+        assert!(!states(&app, card).unwrap().contains(&tags::dragging()));
+        assert!(app.world.resource::<DragAndDrop>().get().is_none());
+    }
+}
@@ -1,5 +1,5 @@
 use bevy::ecs::query::WorldQuery;
-use bevy::ecs::system::SystemParam;
+use bevy::ecs::system::{Command, SystemParam};
 use bevy::utils::{HashMap, HashSet};
 use smallvec::SmallVec;
 use std::ops::Deref;
@@ -84,22 +84,29 @@ pub enum DisplayElement {
     // InlineBlock,
 }
 
+/// The element data used by the styling systems: the display mode, the
+/// inline styles and the content container. The data selectors are matched
+/// against lives in the separate components, so the systems interested only
+/// in the element state or classes don't observe every restyle:
+/// - [`ElementTag`] holds the tag names of the element,
+/// - [`ElementId`] holds the `id` of the element, if any,
+/// - [`Classes`] holds the classes of the element,
+/// - [`States`] holds the states (`:hover`, `:focus`, ...) of the element.
+///
+/// Mutating the [`Element`] marks it for restyle. Use the [`Elements`]
+/// system param to access all the element components at once.
+///
+/// The `names`, `aliases`, `id`, `classes` and `state` fields of the
+/// previous versions live in the components listed above, see the
+/// migration notes in the README.
 #[derive(Component, Default)]
 pub struct Element {
-    pub names: SmallVec<[Tag; 2]>,
-    pub aliases: SmallVec<[Tag; 2]>,
-    pub id: Option<Tag>,
-    pub classes: HashSet<Tag>,
-    pub state: HashSet<Tag>,
     pub display: DisplayElement,
     pub content: Option<Entity>,
     pub styles: HashMap<Tag, PropertyValue>,
 }
 
 impl Element {
-    pub fn is_virtual(&self) -> bool {
-        self.names.len() == 0
-    }
     pub fn inline() -> Element {
         Element {
             display: DisplayElement::Inline,
@@ -107,13 +114,93 @@ impl Element {
         }
     }
     pub fn invalidate(&mut self) {}
+}
+
+/// The tag names of the element. Elements without names (text nodes,
+/// content containers) are virtual: selectors are not matched against them.
+#[derive(Component, Default, Debug, Clone)]
+pub struct ElementTag {
+    pub names: SmallVec<[Tag; 2]>,
+    pub aliases: SmallVec<[Tag; 2]>,
+}
+
+impl ElementTag {
+    pub fn is_virtual(&self) -> bool {
+        self.names.len() == 0
+    }
+
+    pub fn has(&self, tag: &Tag) -> bool {
+        self.names
+            .iter()
+            .chain(self.aliases.iter())
+            .any(|t| t == tag)
+    }
+}
+
+/// The `id` of the element.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Deref)]
+pub struct ElementId(pub Tag);
+
+/// The classes of the element.
+#[derive(Component, Default, Debug, Clone, Deref, DerefMut)]
+pub struct Classes(pub HashSet<Tag>);
+
+/// The states of the element, like `hover` or `focus`.
+/// Use [`Elements::set_state`] to change the state and restyle the element.
+#[derive(Component, Default, Debug, Clone, Deref, DerefMut)]
+pub struct States(pub HashSet<Tag>);
+
+impl States {
     pub fn focused(&self) -> bool {
-        self.state.contains(&tags::focus())
+        self.contains(&tags::focus())
     }
 
     pub fn hovered(&self) -> bool {
-        self.state.contains(&tags::hover())
+        self.contains(&tags::hover())
     }
+
+    pub fn disabled(&self) -> bool {
+        self.contains(&tags::disabled())
+    }
+
+    pub fn truncated(&self) -> bool {
+        self.contains(&tags::truncated())
+    }
+}
+
+/// The tooltip texts of the element, populated from the `tooltip` and
+/// `disabled-reason` params. The `disabled_reason` is shown instead of
+/// the `text` while the element is `:disabled`.
+#[derive(Component, Default, Debug, Clone, PartialEq)]
+pub struct Tooltip {
+    pub text: Option<String>,
+    pub disabled_reason: Option<String>,
+}
+
+/// Matches elements with any of the styling related components changed.
+pub type ElementChanged = Or<(
+    Changed<Element>,
+    Changed<ElementTag>,
+    Changed<ElementId>,
+    Changed<Classes>,
+    Changed<States>,
+)>;
+
+/// Marks the root of the frozen subtree: styles of the frozen elements
+/// are not recomputed and invalidations are suspended until the subtree
+/// is unfrozen with [`Elements::unfreeze`].
+#[derive(Component, Default)]
+pub struct Frozen;
+
+/// Read-only view of all the element components.
+#[derive(WorldQuery)]
+pub struct ElementRef {
+    pub entity: Entity,
+    pub element: &'static Element,
+    pub tag: Option<&'static ElementTag>,
+    pub id: Option<&'static ElementId>,
+    pub classes: Option<&'static Classes>,
+    pub states: Option<&'static States>,
 }
 
 #[derive(WorldQuery)]
@@ -121,6 +208,74 @@ impl Element {
 pub struct ElementsQuery {
     pub entity: Entity,
     element: &'static mut Element,
+    pub tag: Option<&'static ElementTag>,
+    pub id: Option<&'static ElementId>,
+    pub classes: Option<&'static mut Classes>,
+    pub states: Option<&'static mut States>,
+}
+
+macro_rules! impl_element_accessors {
+    ($item:ident) => {
+        impl $item<'_> {
+            pub fn is_virtual(&self) -> bool {
+                self.tag.map(|t| t.is_virtual()).unwrap_or(true)
+            }
+            pub fn names(&self) -> impl Iterator<Item = &Tag> {
+                self.tag.into_iter().flat_map(|t| t.names.iter())
+            }
+            pub fn has_tag(&self, tag: &Tag) -> bool {
+                self.tag.map(|t| t.has(tag)).unwrap_or(false)
+            }
+            pub fn element_id(&self) -> Option<Tag> {
+                self.id.map(|id| id.0)
+            }
+            pub fn has_class(&self, class: &Tag) -> bool {
+                self.classes
+                    .as_ref()
+                    .map(|c| c.contains(class))
+                    .unwrap_or(false)
+            }
+            pub fn has_state(&self, state: &Tag) -> bool {
+                self.states
+                    .as_ref()
+                    .map(|s| s.contains(state))
+                    .unwrap_or(false)
+            }
+            pub fn focused(&self) -> bool {
+                self.has_state(&tags::focus())
+            }
+            pub fn hovered(&self) -> bool {
+                self.has_state(&tags::hover())
+            }
+            #[deprecated(note = "use `ElementTag::aliases`")]
+            pub fn aliases(&self) -> impl Iterator<Item = &Tag> {
+                self.tag.into_iter().flat_map(|t| t.aliases.iter())
+            }
+            #[deprecated(note = "use `ElementId` or `element_id`")]
+            pub fn id(&self) -> Option<Tag> {
+                self.element_id()
+            }
+            #[deprecated(note = "use `Classes` or `has_class`")]
+            pub fn class_list(&self) -> impl Iterator<Item = &Tag> {
+                self.classes.as_ref().into_iter().flat_map(|c| c.iter())
+            }
+            #[deprecated(note = "use `States` or `has_state`")]
+            pub fn state(&self) -> impl Iterator<Item = &Tag> {
+                self.states.as_ref().into_iter().flat_map(|s| s.iter())
+            }
+        }
+    };
+}
+
+impl_element_accessors!(ElementRefItem);
+impl_element_accessors!(ElementsQueryItem);
+impl_element_accessors!(ElementsQueryReadOnlyItem);
+
+impl Deref for ElementRefItem<'_> {
+    type Target = Element;
+    fn deref(&self) -> &Self::Target {
+        self.element
+    }
 }
 
 impl<'w, 's> Deref for Elements<'w, 's> {
@@ -164,10 +319,19 @@ pub struct Elements<'w, 's> {
     roots: Query<'w, 's, Entity, (With<Element>, Without<Parent>)>,
     elements: Query<'w, 's, ElementsQuery, ()>,
     children: Query<'w, 's, ChildrenQuery, ()>,
+    frozen: Query<'w, 's, (), With<Frozen>>,
+    commands: Commands<'w, 's>,
 }
 
 impl<'w, 's> Elements<'w, 's> {
     pub fn invalidate(&mut self, tree: Entity) {
+        if self.frozen.contains(tree) {
+            return;
+        }
+        self.invalidate_subtree(tree);
+    }
+
+    fn invalidate_subtree(&mut self, tree: Entity) {
         if let Ok(mut element) = self.elements.get_mut(tree) {
             element.invalidate();
         }
@@ -186,15 +350,130 @@ impl<'w, 's> Elements<'w, 's> {
             .for_each(|e| self.invalidate(*e));
     }
 
+    /// Suspends style updates of the `tree` and all its descendants. The
+    /// subtree is rendered into the [`FrozenSnapshot`](crate::FrozenSnapshot)
+    /// image, which is shown instead of the live subtree until it is unfrozen.
+    pub fn freeze(&mut self, tree: Entity) {
+        if let Some(mut entity) = self.commands.get_entity(tree) {
+            entity.insert(Frozen);
+        }
+    }
+
+    /// Resumes style updates of the `tree` frozen by [`Elements::freeze`]
+    /// and invalidates it, so all the changes made while the subtree was
+    /// frozen are applied at once. The `Frozen` marker is removed by the
+    /// command queued after the one inserting it, so the subtree frozen
+    /// and unfrozen within the same frame stays live.
+    pub fn unfreeze(&mut self, tree: Entity) {
+        let Some(mut entity) = self.commands.get_entity(tree) else {
+            return;
+        };
+        entity.remove::<Frozen>();
+        self.invalidate_subtree(tree);
+    }
+
+    pub fn is_frozen(&self, entity: Entity) -> bool {
+        self.frozen.contains(entity)
+    }
+
+    /// Adds or removes the `state` of the element and invalidates it
+    /// when the state actually changes.
     pub fn set_state(&mut self, entity: Entity, state: Tag, value: bool) {
-        if let Ok(mut element) = self.elements.get_mut(entity) {
-            if !value && element.state.contains(&state) {
-                element.state.remove(&state);
-                self.invalidate(entity);
-            } else if value && !element.state.contains(&state) {
-                element.state.insert(state);
-                self.invalidate(entity);
+        let Ok(mut element) = self.elements.get_mut(entity) else { return };
+        let changed = match element.states.as_mut() {
+            Some(states) if value => !states.contains(&state) && states.insert(state),
+            Some(states) => states.contains(&state) && states.remove(&state),
+            None => {
+                self.commands.add(MergeTag::<States>::new(entity, state, value));
+                value
+            }
+        };
+        if changed {
+            self.invalidate(entity);
+        }
+    }
+
+    /// Adds or removes the `class` of the element and invalidates it
+    /// when the class list actually changes.
+    pub fn set_class(&mut self, entity: Entity, class: Tag, value: bool) {
+        let Ok(mut element) = self.elements.get_mut(entity) else { return };
+        let changed = match element.classes.as_mut() {
+            Some(classes) if value => !classes.contains(&class) && classes.insert(class),
+            Some(classes) => classes.contains(&class) && classes.remove(&class),
+            None => {
+                self.commands.add(MergeTag::<Classes>::new(entity, class, value));
+                value
             }
+        };
+        if changed {
+            self.invalidate(entity);
         }
     }
 }
+
+/// Adds or removes the tag of the [`Classes`] or [`States`] component
+/// missing at the time [`Elements`] is used. The component is inserted
+/// by the first command, the later ones change it, so all the tags set
+/// within the same frame are kept.
+struct MergeTag<C> {
+    entity: Entity,
+    tag: Tag,
+    value: bool,
+    marker: std::marker::PhantomData<C>,
+}
+
+impl<C> MergeTag<C> {
+    fn new(entity: Entity, tag: Tag, value: bool) -> MergeTag<C> {
+        MergeTag {
+            entity,
+            tag,
+            value,
+            marker: Default::default(),
+        }
+    }
+}
+
+impl<C> Command for MergeTag<C>
+where
+    C: Component + Default + DerefMut<Target = HashSet<Tag>>,
+{
+    fn write(self, world: &mut World) {
+        let Some(mut entity) = world.get_entity_mut(self.entity) else {
+            return;
+        };
+        if !entity.contains::<C>() {
+            entity.insert(C::default());
+        }
+        let mut tags = entity.get_mut::<C>().unwrap();
+        if self.value {
+            tags.insert(self.tag);
+        } else {
+            tags.remove(&self.tag);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn merge_missing_tags() {
+        let mut app = App::new();
+        let entity = app.world.spawn(Element::default()).id();
+        app.add_system(move |mut elements: Elements| {
+            elements.set_state(entity, tags::hover(), true);
+            elements.set_state(entity, tags::active(), true);
+            elements.set_class(entity, "a".as_tag(), true);
+            elements.set_class(entity, "b".as_tag(), true);
+            elements.set_class(entity, "a".as_tag(), false);
+        });
+        app.update();
+        let states = app.world.get::<States>(entity).unwrap();
+        assert!(states.contains(&tags::hover()));
+        assert!(states.contains(&tags::active()));
+        let classes = app.world.get::<Classes>(entity).unwrap();
+        assert!(!classes.contains(&"a".as_tag()));
+        assert!(classes.contains(&"b".as_tag()));
+    }
+}
@@ -1,27 +1,47 @@
-use std::sync::Arc;
+use std::{
+    path::Path,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
 
 use bevy::{
-    asset::{AssetLoader, LoadedAsset},
+    asset::{AssetLoader, AssetPath, HandleId, LoadedAsset},
     prelude::*,
     reflect::TypeUuid,
-    utils::HashMap,
+    utils::{HashMap, HashSet},
 };
 use tagstr::*;
 
-use crate::{eml::Param, Element, ElementBuilderRegistry, PropertyExtractor, PropertyTransformer};
+use crate::{
+    eml::Param, Classes, Element, ElementBuilderRegistry, ElementBundle, ElementId, ElementTag,
+    PropertyExtractor, PropertyTransformer,
+};
 
 use super::{
     build::{ElementContextData, Slots},
+    handlers::EventHandlers,
     parse,
 };
 
+#[derive(Clone)]
 pub enum EmlNode {
     Element(EmlElement),
     Text(String),
     Slot(Tag, Vec<EmlNode>),
+    /// The slot of the template, `<slot define="name">`, with the fallback
+    /// content used when the slot is not filled.
+    Define(Tag, Vec<EmlNode>),
+    /// The root of the document included with `<include src="...">`.
+    Include(AssetPath<'static>),
+    /// The siblings spliced into the parent element: `<fragment>` or
+    /// the several roots of the document or the template.
+    Fragment(Vec<EmlNode>),
+    /// The placeholder of the element failed to parse, rendered as
+    /// the message of the problem with the `eml-error` class.
+    Error(String),
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct EmlElement {
     pub(crate) name: Tag,
     pub(crate) params: HashMap<String, String>,
@@ -34,6 +54,81 @@ impl EmlElement {
     }
 }
 
+/// The way the whitespace of the text content of the `.eml` assets is
+/// handled. The mode is set per element with the `whitespace` param and
+/// is inherited by the descendants:
+/// ```xml
+/// <span whitespace="collapse">HP: <strong>42</strong> / 100</span>
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Whitespace {
+    /// Collapses the whitespace into the single space and trims the text,
+    /// the whitespace-only text is dropped.
+    #[default]
+    Trim,
+    /// Collapses the whitespace into the single space, the spaces
+    /// around the inline text runs are kept. The whitespace-only text
+    /// is dropped if it breaks the line (the indentation of the markup).
+    Collapse,
+    /// Keeps the text as is.
+    Preserve,
+}
+
+impl Whitespace {
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            Whitespace::Trim => text.split_whitespace().collect::<Vec<_>>().join(" "),
+            Whitespace::Collapse => {
+                if text.trim().is_empty() && text.contains('\n') {
+                    return String::new();
+                }
+                let mut result = String::with_capacity(text.len());
+                for ch in text.chars() {
+                    if !ch.is_whitespace() {
+                        result.push(ch);
+                    } else if !result.ends_with(' ') {
+                        result.push(' ');
+                    }
+                }
+                result
+            }
+            Whitespace::Preserve => text.to_string(),
+        }
+    }
+}
+
+impl FromStr for Whitespace {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "trim" => Ok(Whitespace::Trim),
+            "collapse" => Ok(Whitespace::Collapse),
+            "preserve" => Ok(Whitespace::Preserve),
+            _ => Err(format!(
+                "Invalid whitespace mode '{s}', expected trim, collapse or preserve"
+            )),
+        }
+    }
+}
+
+/// The [`Whitespace`] mode of the text not covered by the `whitespace`
+/// param, applied to the assets loaded after the change:
+/// ```rust,ignore
+/// app.world.resource::<EmlWhitespace>().set(Whitespace::Collapse);
+/// ```
+#[derive(Resource, Default, Clone)]
+pub struct EmlWhitespace(Arc<RwLock<Whitespace>>);
+
+impl EmlWhitespace {
+    pub fn get(&self) -> Whitespace {
+        *self.0.read().unwrap()
+    }
+
+    pub fn set(&self, whitespace: Whitespace) {
+        *self.0.write().unwrap() = whitespace;
+    }
+}
+
 #[derive(Component)]
 pub struct EmlScene {
     asset: Handle<EmlAsset>,
@@ -49,6 +144,9 @@ impl EmlScene {
 #[uuid = "f8d22a65-d671-4fa6-ae8f-0dccdb387ddd"]
 pub struct EmlAsset {
     root: Arc<EmlNode>,
+    templates: Arc<HashMap<Tag, EmlNode>>,
+    /// The documents included into this one.
+    includes: Arc<Vec<Handle<EmlAsset>>>,
 }
 
 impl EmlAsset {
@@ -56,6 +154,91 @@ impl EmlAsset {
         // let node = E
         walk(&self.root, world, Some(parent));
     }
+
+    /// Writes the `<template name="...">` declared in the asset into the
+    /// `parent` entity. The slots of the template are filled with the
+    /// entities inserted into the [`Slots`] resource before the call,
+    /// the fallback content is used for the rest of the slots:
+    /// ```rust,ignore
+    /// commands.add(move |world: &mut World| {
+    ///     let title = world.spawn(TextBundle::from_section("Inventory", default())).id();
+    ///     world.resource::<Slots>().insert(tag!("title"), vec![title]);
+    ///     let card = world.spawn_empty().id();
+    ///     world.resource::<Assets<EmlAsset>>().get(&cards).unwrap().clone()
+    ///         .write_template("card", world, card);
+    /// });
+    /// ```
+    /// Returns `false` if there is no template with the `name`.
+    pub fn write_template(&self, name: &str, world: &mut World, parent: Entity) -> bool {
+        let Some(template) = self.templates.get(&name.as_tag()) else {
+            return false;
+        };
+        walk(template, world, Some(parent));
+        true
+    }
+
+    pub fn has_template(&self, name: &str) -> bool {
+        self.templates.contains_key(&name.as_tag())
+    }
+
+    /// Returns `true` if the `asset` is included into this one directly
+    /// or by any of the included documents.
+    pub fn includes(&self, asset: HandleId, assets: &Assets<EmlAsset>) -> bool {
+        let mut visited = HashSet::default();
+        let mut queue: Vec<_> = self.includes.iter().map(|h| h.id()).collect();
+        while let Some(id) = queue.pop() {
+            if id == asset {
+                return true;
+            }
+            if !visited.insert(id) {
+                continue;
+            }
+            if let Some(included) = assets.get(&Handle::weak(id)) {
+                queue.extend(included.includes.iter().map(|h| h.id()));
+            }
+        }
+        false
+    }
+}
+
+fn walk_children(nodes: &[EmlNode], world: &mut World) -> Vec<Entity> {
+    let mut entities = vec![];
+    for node in nodes.iter() {
+        walk_child(node, world, &mut entities);
+    }
+    entities
+}
+
+/// Fragments (and the included documents with several roots) are
+/// spliced into the `entities` of the parent.
+fn walk_child(node: &EmlNode, world: &mut World, entities: &mut Vec<Entity>) {
+    match node {
+        EmlNode::Define(name, fallback) => match world.resource::<Slots>().remove(*name) {
+            Some(content) => entities.extend(content),
+            None => entities.extend(walk_children(fallback, world)),
+        },
+        EmlNode::Fragment(nodes) => entities.extend(walk_children(nodes, world)),
+        EmlNode::Include(path) => {
+            if let Some(root) = included(path, world) {
+                walk_child(&root, world, entities);
+            }
+        }
+        node => entities.extend(walk(node, world, None)),
+    }
+}
+
+/// The root of the included document, `None` if the document
+/// is not loaded yet or includes itself.
+fn included(path: &AssetPath<'static>, world: &World) -> Option<Arc<EmlNode>> {
+    let id = HandleId::from(path.clone());
+    let assets = world.resource::<Assets<EmlAsset>>();
+    // the scene is written again when the included asset is loaded
+    let asset = assets.get(&Handle::weak(id))?;
+    if asset.includes(id, assets) {
+        error!("{} includes itself", path.path().display());
+        return None;
+    }
+    Some(asset.root.clone())
 }
 
 fn walk(node: &EmlNode, world: &mut World, parent: Option<Entity>) -> Option<Entity> {
@@ -72,13 +255,40 @@ fn walk(node: &EmlNode, world: &mut World, parent: Option<Entity>) -> Option<Ent
         }
         EmlNode::Slot(name, elements) => {
             let slots = world.resource::<Slots>().clone();
-            let entities: Vec<Entity> = elements
-                .iter()
-                .filter_map(|e| walk(e, world, None))
-                .collect();
+            let entities = walk_children(elements, world);
             slots.insert(*name, entities);
             None
         }
+        EmlNode::Define(..) => {
+            error!("Slot definitions should be used inside the parent elements");
+            None
+        }
+        EmlNode::Include(path) => {
+            let root = included(path, world)?;
+            walk(&root, world, parent)
+        }
+        EmlNode::Fragment(nodes) => {
+            let children = walk_children(nodes, world);
+            let entity = parent.unwrap_or_else(|| world.spawn_empty().id());
+            let mut entity = world.entity_mut(entity);
+            if !entity.contains::<Element>() {
+                entity.insert(ElementBundle::default());
+            }
+            entity.push_children(&children);
+            Some(entity.id())
+        }
+        EmlNode::Error(message) => {
+            let entity = parent.unwrap_or_else(|| world.spawn_empty().id());
+            world.entity_mut(entity).insert((
+                TextBundle {
+                    text: Text::from_section(message, Default::default()),
+                    ..default()
+                },
+                Element::inline(),
+                Classes([tag!("eml-error")].into_iter().collect()),
+            ));
+            Some(entity)
+        }
         EmlNode::Element(elem) => {
             let Some(builder) = world
                 .resource::<ElementBuilderRegistry>()
@@ -89,16 +299,30 @@ fn walk(node: &EmlNode, world: &mut World, parent: Option<Entity>) -> Option<Ent
             };
             let entity = parent.unwrap_or_else(|| world.spawn_empty().id());
             let mut context = ElementContextData::new(entity);
+            let mut signals = vec![];
             for (name, value) in elem.params.iter() {
+                if let Some(signal) = name.strip_prefix("on:") {
+                    signals.push((signal, value));
+                    continue;
+                }
                 let attr = Param::new(name, value.clone().into());
                 context.params.add(attr);
             }
-            for child in elem.children.iter() {
-                if let Some(entity) = walk(child, world, None) {
-                    context.children.push(entity);
+            context.children = walk_children(&elem.children, world);
+            builder.build(world, context);
+            let handlers = world.resource::<EventHandlers>().clone();
+            for (signal, name) in signals {
+                let Some(handler) = handlers.get(name) else {
+                    error!("Unknown handler {name}");
+                    continue;
+                };
+                if builder
+                    .connect_handler(world, entity, signal, handler)
+                    .is_none()
+                {
+                    error!("<{}> has no {signal} signal", elem.name);
                 }
             }
-            builder.build(world, context);
             Some(entity)
         }
     }
@@ -109,6 +333,8 @@ pub(crate) struct EmlLoader {
     pub(crate) registry: ElementBuilderRegistry,
     pub(crate) transformer: PropertyTransformer,
     pub(crate) extractor: PropertyExtractor,
+    pub(crate) handlers: EventHandlers,
+    pub(crate) whitespace: EmlWhitespace,
 }
 
 impl AssetLoader for EmlLoader {
@@ -123,13 +349,29 @@ impl AssetLoader for EmlLoader {
     ) -> bevy::utils::BoxedFuture<'a, Result<(), bevy::asset::Error>> {
         Box::pin(async move {
             let source = std::str::from_utf8(bytes)?;
+            let dir = load_context.path().parent().unwrap_or(Path::new(""));
 
-            match parse::parse(source, self) {
-                Ok(root) => {
+            match parse::parse(source, dir, self) {
+                Ok(document) => {
+                    let path = load_context.path().to_str().unwrap();
+                    for err in document.errors.iter() {
+                        error!("Error parsing {}:\n\n{}", path, err);
+                    }
+                    let includes = document
+                        .includes
+                        .iter()
+                        .map(|path| load_context.get_handle(path.clone()))
+                        .collect();
                     let asset = EmlAsset {
-                        root: Arc::new(root),
+                        root: Arc::new(document.root),
+                        templates: Arc::new(document.templates),
+                        includes: Arc::new(includes),
                     };
-                    load_context.set_default_asset(LoadedAsset::new(asset));
+                    let mut asset = LoadedAsset::new(asset);
+                    for path in document.includes {
+                        asset = asset.with_dependency(path);
+                    }
+                    load_context.set_default_asset(asset);
                     Ok(())
                 }
                 Err(err) => {
@@ -143,34 +385,68 @@ impl AssetLoader for EmlLoader {
     }
 }
 
+/// Writes the content of the loaded asset into the scenes. Scenes
+/// spawned or switched to another asset are written as soon as the
+/// asset is loaded, scenes of the modified asset are written from
+/// scratch: the content is despawned and the root element is reset.
+/// Scenes including the loaded or modified asset are written again too.
 pub fn update_eml_scene(
-    scenes: Query<(Entity, &EmlScene, Option<&Children>)>,
+    scenes: Query<(Entity, &EmlScene)>,
+    changed: Query<Entity, Changed<EmlScene>>,
     mut events: EventReader<AssetEvent<EmlAsset>>,
     assets: Res<Assets<EmlAsset>>,
     mut commands: Commands,
 ) {
+    let mut rebuild: HashSet<Entity> = changed.iter().collect();
     for event in events.iter() {
-        if let AssetEvent::Created { handle } = event {
-            let asset = assets.get(handle).unwrap();
-            for (entity, _, _) in scenes.iter().filter(|(_, s, _)| &s.asset == handle) {
-                let asset = asset.clone();
-                commands.add(move |world: &mut World| {
-                    asset.write(world, entity);
-                });
-            }
-        } else if let AssetEvent::Modified { handle } = event {
-            let asset = assets.get(handle).unwrap();
-            for (entity, _, children) in scenes.iter().filter(|(_, s, _)| &s.asset == handle) {
-                if let Some(children) = children {
-                    for ch in children.iter() {
-                        commands.entity(*ch).despawn_recursive();
-                    }
-                }
-                let asset = asset.clone();
-                commands.add(move |world: &mut World| {
-                    asset.write(world, entity);
-                });
-            }
-        }
+        let (AssetEvent::Created { handle } | AssetEvent::Modified { handle }) = event else {
+            continue;
+        };
+        rebuild.extend(
+            scenes
+                .iter()
+                .filter(|(_, scene)| {
+                    &scene.asset == handle
+                        || assets
+                            .get(&scene.asset)
+                            .map(|asset| asset.includes(handle.id(), &assets))
+                            .unwrap_or(false)
+                })
+                .map(|(entity, _)| entity),
+        );
+    }
+    for entity in rebuild {
+        let Ok((_, scene)) = scenes.get(entity) else {
+            continue;
+        };
+        let Some(asset) = assets.get(&scene.asset) else {
+            continue;
+        };
+        let asset = asset.clone();
+        commands.entity(entity).despawn_descendants();
+        commands.add(move |world: &mut World| {
+            // the root element is built over the scene entity, things
+            // left from the previous content would be merged otherwise
+            world
+                .entity_mut(entity)
+                .remove_intersection::<(Element, Classes, ElementId, ElementTag)>();
+            asset.write(world, entity);
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn whitespace_modes() {
+        let text = "\n  HP:  ";
+        assert_eq!(Whitespace::Trim.apply(text), "HP:");
+        assert_eq!(Whitespace::Collapse.apply(text), " HP: ");
+        assert_eq!(Whitespace::Preserve.apply(text), text);
+        assert_eq!(Whitespace::Collapse.apply("\n    "), "");
+        assert_eq!(Whitespace::Collapse.apply("  "), " ");
+        assert!("pre".parse::<Whitespace>().is_err());
     }
 }
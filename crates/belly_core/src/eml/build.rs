@@ -12,9 +12,19 @@ use std::{
 };
 use tagstr::*;
 
+use crate::context_menu::ContextMenu;
+use crate::dnd::{DragEvent, DragPayload, Draggable, Droppable};
+use crate::ess::ScrollEvent;
+use crate::follow::Follow;
+use crate::input::{FocusTrap, NavTarget, Navigation, PointerInput};
+use crate::shortcut::{Shortcut, ShortcutScope};
+use crate::touch::{PinchEvent, Zoomable};
 use crate::{
-    eml::Params, eml::StyleParams, eml::Variant, ess::StyleRule, ess::StyleSheetParser, tags,
-    ConnectionTo, Element, PropertyExtractor, PropertyTransformer, Signal,
+    eml::handlers::EventHandler, eml::Params, eml::StyleParams, eml::Variant,
+    ess::transform_inline_style, ess::PropertyKeyword, ess::PropertyValue, ess::StyleRule,
+    ess::StyleSheetParser, input::TabIndex, relations::ConnectionId, tags, Classes, ConnectionTo,
+    Element, ElementBundle, ElementId, ElementTag, PropertyExtractor, PropertyTransformer, Signal,
+    States, Tooltip,
 };
 
 pub struct BuildPligin;
@@ -29,6 +39,12 @@ impl Plugin for BuildPligin {
 
 pub trait FromWorldAndParam {
     fn from_world_and_param(world: &mut World, param: Variant) -> Self;
+
+    /// Checks if the value written in the markup could be converted.
+    #[allow(unused_variables)]
+    fn validate_param(value: &str) -> Result<(), String> {
+        Ok(())
+    }
 }
 
 impl<T: TryFrom<Variant, Error = impl std::fmt::Display> + Default + 'static> FromWorldAndParam
@@ -41,6 +57,35 @@ impl<T: TryFrom<Variant, Error = impl std::fmt::Display> + Default + 'static> Fr
             Self::default()
         }
     }
+
+    fn validate_param(value: &str) -> Result<(), String> {
+        validate_param::<T>(value)
+    }
+}
+
+/// Checks if the value written in the markup could be converted to `T`.
+pub fn validate_param<T>(value: &str) -> Result<(), String>
+where
+    T: TryFrom<Variant, Error = impl std::fmt::Display>,
+{
+    T::try_from(Variant::String(value.to_string()))
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// The param accepted by the widget. Params of the elements of the
+/// `.eml` assets are checked against the schema when the asset is
+/// loaded, so the missing or invalid param is reported as the parse
+/// error.
+#[derive(Clone, Copy, Debug)]
+pub struct ParamSchema {
+    pub name: &'static str,
+    /// The name of the type the value is converted to.
+    pub type_name: &'static str,
+    /// The value used when the param is not set.
+    pub default: Option<&'static str>,
+    pub required: bool,
+    pub validate: fn(&str) -> Result<(), String>,
 }
 
 pub fn entity_from_world_and_param(world: &mut World, param: Variant) -> Entity {
@@ -57,6 +102,22 @@ pub trait Widget: Sized + Component + 'static {
         &[]
     }
 
+    fn params() -> &'static [ParamSchema] {
+        &[]
+    }
+
+    /// Connects the `handler` to the signal of the `source` by the name of
+    /// the signal. Returns `None` if the widget has no such signal.
+    #[allow(unused_variables)]
+    fn connect_handler(
+        world: &mut World,
+        source: Entity,
+        signal: &str,
+        handler: EventHandler,
+    ) -> Option<ConnectionId> {
+        None
+    }
+
     #[allow(unused_variables)]
     fn construct_component(world: &mut World, params: &mut Params) -> Option<Self> {
         None
@@ -116,31 +177,112 @@ pub trait WidgetBuilder: Widget {
             Some(Variant::String(s)) if &s == "pass" => Some(FocusPolicy::Pass),
             _ => None,
         };
+        let draggable = matches!(ctx.param(tag!("draggable")), Some(Variant::Bool(true)));
+        let droppable = matches!(ctx.param(tag!("droppable")), Some(Variant::Bool(true)));
+        if draggable {
+            ctx.insert(Draggable);
+        }
+        if droppable {
+            ctx.insert(Droppable);
+        }
+        let context_menu = ctx
+            .param(tag!("context-menu"))
+            .and_then(ContextMenu::from_variant);
+        let has_context_menu = context_menu.is_some();
+        if let Some(menu) = context_menu {
+            ctx.insert(menu);
+        }
+        if let Some(Variant::Bool(true)) = ctx.param(tag!("zoomable")) {
+            ctx.insert(Zoomable);
+        }
+        if let Some(payload) = ctx
+            .param(tag!("drag-payload"))
+            .and_then(|v| v.try_get::<String>())
+        {
+            ctx.insert(DragPayload::new(payload));
+        }
+        // the pointer should hit the element to drag it, to drop onto it
+        // or to open its context menu
+        let focus_policy = match focus_policy {
+            None if draggable || droppable || has_context_menu => Some(FocusPolicy::Block),
+            policy => policy,
+        };
         if let Some(policy) = focus_policy {
             ctx.insert(policy);
             ctx.insert(Interaction::default());
         }
+        if let Some(index) = ctx
+            .param(tag!("tab-index"))
+            .and_then(|v| v.try_get::<i32>())
+        {
+            ctx.insert(TabIndex(index));
+        }
+        let mut navigation = Navigation::default();
+        for (param, target) in [
+            ("nav-up", &mut navigation.up),
+            ("nav-down", &mut navigation.down),
+            ("nav-left", &mut navigation.left),
+            ("nav-right", &mut navigation.right),
+        ] {
+            *target = ctx.param(param.as_tag()).and_then(NavTarget::from_variant);
+        }
+        if !navigation.is_empty() {
+            ctx.insert(navigation);
+        }
+        if let Some(follow) = Follow::from_params(
+            ctx.param(tag!("follow")),
+            ctx.param(tag!("follow-offset")),
+            ctx.param(tag!("follow-clamp")),
+        ) {
+            ctx.insert(follow);
+        }
+        if let Some(shortcut) = ctx.try_param::<Shortcut>("shortcut") {
+            ctx.insert(shortcut);
+        }
+        if matches!(ctx.param(tag!("shortcut-scope")), Some(Variant::Bool(true))) {
+            ctx.insert(ShortcutScope);
+        }
+        if matches!(ctx.param(tag!("focus-trap")), Some(Variant::Bool(true))) {
+            ctx.insert(FocusTrap);
+        }
+        let disabled = matches!(ctx.param(tags::disabled()), Some(Variant::Bool(true)));
+        let tooltip = Tooltip {
+            text: ctx.param(tag!("tooltip")).and_then(|v| v.try_get()),
+            disabled_reason: ctx.param(tag!("disabled-reason")).and_then(|v| v.try_get()),
+        };
+        if tooltip != Tooltip::default() {
+            ctx.insert(tooltip);
+        }
+        let reset_style = matches!(ctx.param(tag!("reset-style")), Some(Variant::Bool(true)));
         let id = ctx.id();
         let classes = ctx.classes();
-        let styles = ctx.styles().transform(|tag, variant| {
-            if ctx.extractor.is_compound_property(tag) {
-                match ctx.extractor.extract(tag, variant) {
-                    Ok(mut props) => props.drain().collect(),
-                    Err(e) => {
-                        error!("Ignoring property {}: {}", tag, e);
-                        vec![]
-                    }
-                }
-            } else {
-                match ctx.transformer.transform(tag, variant) {
-                    Ok(variant) => vec![(tag, variant)],
-                    Err(e) => {
-                        error!("Ignoring property {}: {}", tag, e);
-                        vec![]
-                    }
+        // the `style` declarations are overridden by the `s:` params
+        let mut styles = match ctx.param(tags::style()) {
+            Some(Variant::String(style)) => {
+                StyleSheetParser::new(ctx.transformer.clone(), ctx.extractor.clone())
+                    .parse_declarations(&style)
+            }
+            Some(style) => {
+                error!("Ignoring style param, expected string, got {style:?}");
+                HashMap::default()
+            }
+            None => HashMap::default(),
+        };
+        styles.extend(ctx.styles().transform(|tag, variant| {
+            match transform_inline_style(&ctx.transformer, &ctx.extractor, tag, variant) {
+                Ok(styles) => styles,
+                Err(e) => {
+                    error!("Ignoring property {}: {}", tag, e);
+                    vec![]
                 }
             }
-        });
+        }));
+        if reset_style {
+            // the element doesn't inherit anything from the parents
+            styles
+                .entry(tag!("all"))
+                .or_insert_with(|| PropertyValue::new(PropertyKeyword::Initial));
+        }
         let entity = ctx.entity();
         ctx.commands.add(move |world: &mut World| {
             world
@@ -148,12 +290,26 @@ pub trait WidgetBuilder: Widget {
                 .send(RequestReadyEvent(entity));
         });
         ctx.update_element(move |element| {
-            element.names = names;
-            element.aliases = aliases;
-            element.id = id;
-            element.classes.extend(classes);
             element.styles.extend(styles);
         });
+        ctx.commands.add(move |world: &mut World| {
+            let mut entity = world.entity_mut(entity);
+            entity.insert(ElementTag { names, aliases });
+            if let Some(id) = id {
+                entity.insert(ElementId(id));
+            }
+            if let Some(mut element_classes) = entity.get_mut::<Classes>() {
+                element_classes.extend(classes);
+            } else {
+                entity.insert(Classes(classes));
+            }
+            if !entity.contains::<States>() {
+                entity.insert(States::default());
+            }
+            if disabled {
+                entity.get_mut::<States>().unwrap().insert(tags::disabled());
+            }
+        });
     }
 
     fn as_builder() -> ElementBuilder {
@@ -162,6 +318,8 @@ pub trait WidgetBuilder: Widget {
             styles_func: Self::styles,
             names_func: Self::names,
             aliases_func: Self::aliases,
+            params_func: Self::params,
+            connect_func: Self::connect_handler,
         }
     }
 }
@@ -249,6 +407,15 @@ impl<'w, 's> ElementContext<'w, 's> {
         self.data.params.drop_variant(key)
     }
 
+    /// Takes the `param` converted to `T`. The error is logged
+    /// if the value can't be converted.
+    pub fn try_param<T>(&mut self, param: &str) -> Option<T>
+    where
+        T: TryFrom<Variant, Error = impl std::fmt::Display>,
+    {
+        self.data.params.try_get(param)
+    }
+
     pub fn params(&mut self) -> Params {
         mem::take(&mut self.data.params)
     }
@@ -293,6 +460,8 @@ pub struct ElementBuilder {
     styles_func: fn() -> &'static str,
     aliases_func: Names,
     names_func: Names,
+    params_func: fn() -> &'static [ParamSchema],
+    connect_func: fn(&mut World, Entity, &str, EventHandler) -> Option<ConnectionId>,
 }
 
 impl ElementBuilder {
@@ -311,10 +480,31 @@ impl ElementBuilder {
     pub fn styles(&self) -> &'static str {
         (self.styles_func)()
     }
+
+    pub fn params(&self) -> &'static [ParamSchema] {
+        (self.params_func)()
+    }
+
+    pub fn connect_handler(
+        &self,
+        world: &mut World,
+        source: Entity,
+        signal: &str,
+        handler: EventHandler,
+    ) -> Option<ConnectionId> {
+        (self.connect_func)(world, source, signal, handler)
+    }
+}
+
+enum Build {
+    /// Builds the single root element over the entity.
+    Root(Box<dyn FnOnce(&mut World, Entity) + Sync + Send>),
+    /// Builds the roots of the fragment and returns them.
+    Fragment(Box<dyn FnOnce(&mut World) -> Vec<Entity> + Sync + Send>),
 }
 
 pub struct ElementsBuilder {
-    pub builder: Box<dyn FnOnce(&mut World, Entity) + Sync + Send>,
+    build: Build,
 }
 
 impl ElementsBuilder {
@@ -323,21 +513,59 @@ impl ElementsBuilder {
         T: FnOnce(&mut World, Entity) + Sync + Send + 'static,
     {
         ElementsBuilder {
-            builder: Box::new(builder),
+            build: Build::Root(Box::new(builder)),
         }
     }
 
+    /// The builder of the several root elements, this is what the
+    /// `<fragment>` root of the `eml!` macro expands to. The roots are
+    /// spliced into the content of the parent element, no wrapping
+    /// element is spawned.
+    pub fn fragment<T>(builder: T) -> Self
+    where
+        T: FnOnce(&mut World) -> Vec<Entity> + Sync + Send + 'static,
+    {
+        ElementsBuilder {
+            build: Build::Fragment(Box::new(builder)),
+        }
+    }
+
+    pub fn is_fragment(&self) -> bool {
+        matches!(self.build, Build::Fragment(_))
+    }
+
+    /// Builds the elements over the `entity`. The roots of the fragment
+    /// become the children of the `entity`.
     pub fn with_entity(self, entity: Entity) -> impl FnOnce(&mut World) {
-        move |world: &mut World| {
-            (self.builder)(world, entity);
+        move |world: &mut World| match self.build {
+            Build::Root(build) => build(world, entity),
+            Build::Fragment(build) => {
+                let roots = build(world);
+                let mut entity = world.entity_mut(entity);
+                if !entity.contains::<Element>() {
+                    entity.insert(ElementBundle::default());
+                }
+                entity.push_children(&roots);
+            }
+        }
+    }
+
+    /// Builds the elements and returns the root entities.
+    pub fn build(self, world: &mut World) -> Vec<Entity> {
+        match self.build {
+            Build::Root(build) => {
+                let entity = world.spawn_empty().id();
+                build(world, entity);
+                vec![entity]
+            }
+            Build::Fragment(build) => build(world),
         }
     }
 }
 
 impl Command for ElementsBuilder {
     fn write(self, world: &mut World) {
-        let entity = world.spawn_empty().id();
-        self.with_entity(entity)(world);
+        self.build(world);
     }
 }
 
@@ -395,7 +623,100 @@ impl DefaultDescriptor {
         world: &mut World,
         source: Entity,
         target: ConnectionTo<C, ReadyEvent>,
-    ) {
+    ) -> ConnectionId {
+        target.all().from(source).write(world)
+    }
+
+    pub fn scroll<C: Component>(
+        &self,
+        world: &mut World,
+        source: Entity,
+        target: ConnectionTo<C, ScrollEvent>,
+    ) -> ConnectionId {
+        target.all().from(source).write(world)
+    }
+
+    pub fn double_click<C: Component>(
+        &self,
+        world: &mut World,
+        source: Entity,
+        target: ConnectionTo<C, PointerInput>,
+    ) -> ConnectionId {
+        target
+            .filter(|e| e.double_click())
+            .from(source)
+            .write(world)
+    }
+
+    pub fn long_press<C: Component>(
+        &self,
+        world: &mut World,
+        source: Entity,
+        target: ConnectionTo<C, PointerInput>,
+    ) -> ConnectionId {
+        target.filter(|e| e.long_press()).from(source).write(world)
+    }
+
+    pub fn secondary_press<C: Component>(
+        &self,
+        world: &mut World,
+        source: Entity,
+        target: ConnectionTo<C, PointerInput>,
+    ) -> ConnectionId {
+        target.filter(|e| e.secondary_press()).from(source).write(world)
+    }
+
+    pub fn dragstart<C: Component>(
+        &self,
+        world: &mut World,
+        source: Entity,
+        target: ConnectionTo<C, DragEvent>,
+    ) -> ConnectionId {
+        target.filter(|e| e.started()).from(source).write(world)
+    }
+
+    pub fn dragover<C: Component>(
+        &self,
+        world: &mut World,
+        source: Entity,
+        target: ConnectionTo<C, DragEvent>,
+    ) -> ConnectionId {
+        target.filter(|e| e.over()).from(source).write(world)
+    }
+
+    pub fn dragleave<C: Component>(
+        &self,
+        world: &mut World,
+        source: Entity,
+        target: ConnectionTo<C, DragEvent>,
+    ) -> ConnectionId {
+        target.filter(|e| e.left()).from(source).write(world)
+    }
+
+    pub fn drop<C: Component>(
+        &self,
+        world: &mut World,
+        source: Entity,
+        target: ConnectionTo<C, DragEvent>,
+    ) -> ConnectionId {
+        target.filter(|e| e.dropped()).from(source).write(world)
+    }
+
+    pub fn dragend<C: Component>(
+        &self,
+        world: &mut World,
+        source: Entity,
+        target: ConnectionTo<C, DragEvent>,
+    ) -> ConnectionId {
+        target.filter(|e| e.ended()).from(source).write(world)
+    }
+
+    pub fn pinch<C: Component>(
+        &self,
+        world: &mut World,
+        source: Entity,
+        target: ConnectionTo<C, PinchEvent>,
+    ) -> ConnectionId {
         target.all().from(source).write(world)
     }
 }
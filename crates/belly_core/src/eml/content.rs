@@ -1,9 +1,12 @@
-use bevy::prelude::*;
+use bevy::{asset::Asset, ecs::query::ReadOnlyWorldQuery, prelude::*};
 use std::any::TypeId;
 
 use crate::{
     relations::{
-        bind::{BindableSource, BindableTarget, FromComponent, FromResourceWithTransformer},
+        bind::{
+            BindableSource, BindableTarget, FromAssetWithTransformer, FromComponent, FromComputed,
+            FromQueryWithTransformer, FromResourceWithTransformer,
+        },
         *,
     },
     to, Element, ElementsBuilder,
@@ -57,6 +60,28 @@ impl<R: Component, S: BindableTarget + Clone + Default + IntoContent + std::fmt:
     }
 }
 
+impl<
+        Q: ReadOnlyWorldQuery + 'static,
+        F: ReadOnlyWorldQuery + 'static,
+        S: BindableTarget + BindableSource + Default + IntoContent + std::fmt::Debug,
+    > IntoContent for FromComputed<Q, F, S>
+{
+    fn into_content(self, parent: Entity, world: &mut World) -> Vec<Entity> {
+        let bind = self >> to!(parent, BindContent<S>:value);
+        bind.write(world);
+        world
+            .entity_mut(parent)
+            .insert(NodeBundle::default())
+            .insert(BindContent {
+                value: S::default(),
+            });
+        let systems_ref = world.get_resource_or_insert_with(RelationsSystems::default);
+        let mut systems = systems_ref.0.write().unwrap();
+        systems.add_custom_system(TypeId::of::<BindContent<S>>(), bind_content_system::<S>);
+        vec![parent]
+    }
+}
+
 impl<
         R: Resource,
         S: BindableSource,
@@ -79,6 +104,51 @@ impl<
     }
 }
 
+impl<
+        A: Asset,
+        S: BindableSource,
+        T: BindableTarget + Clone + Default + IntoContent + std::fmt::Debug,
+    > IntoContent for FromAssetWithTransformer<A, S, T>
+{
+    fn into_content(self, parent: Entity, world: &mut World) -> Vec<Entity> {
+        let bind = self >> to!(parent, BindContent<T>:value);
+        bind.write(world);
+        world
+            .entity_mut(parent)
+            .insert(NodeBundle::default())
+            .insert(BindContent {
+                value: T::default(),
+            });
+        let systems_ref = world.get_resource_or_insert_with(RelationsSystems::default);
+        let mut systems = systems_ref.0.write().unwrap();
+        systems.add_custom_system(TypeId::of::<BindContent<T>>(), bind_content_system::<T>);
+        vec![parent]
+    }
+}
+
+impl<
+        Q: ReadOnlyWorldQuery + 'static,
+        F: ReadOnlyWorldQuery + 'static,
+        S: BindableSource,
+        T: BindableTarget + Clone + Default + IntoContent + std::fmt::Debug,
+    > IntoContent for FromQueryWithTransformer<Q, F, S, T>
+{
+    fn into_content(self, parent: Entity, world: &mut World) -> Vec<Entity> {
+        let bind = self >> to!(parent, BindContent<T>:value);
+        bind.write(world);
+        world
+            .entity_mut(parent)
+            .insert(NodeBundle::default())
+            .insert(BindContent {
+                value: T::default(),
+            });
+        let systems_ref = world.get_resource_or_insert_with(RelationsSystems::default);
+        let mut systems = systems_ref.0.write().unwrap();
+        systems.add_custom_system(TypeId::of::<BindContent<T>>(), bind_content_system::<T>);
+        vec![parent]
+    }
+}
+
 fn bind_content_system<T: BindableTarget + IntoContent + Clone + std::fmt::Debug>(
     mut commands: Commands,
     binds: Query<(Entity, &BindContent<T>), Changed<BindContent<T>>>,
@@ -104,9 +174,7 @@ impl<T: Iterator, F: Fn(T::Item) -> ElementsBuilder> IntoContent for ExpandEleme
     fn into_content(self, _parent: Entity, world: &mut World) -> Vec<Entity> {
         let mut result = vec![];
         for builder in self {
-            let entity = world.spawn_empty().id();
-            result.push(entity.clone());
-            builder.with_entity(entity)(world);
+            result.extend(builder.build(world));
         }
         result
     }
@@ -116,9 +184,7 @@ impl IntoContent for Vec<ElementsBuilder> {
     fn into_content(self, _parent: Entity, world: &mut World) -> Vec<Entity> {
         let mut result = vec![];
         for builder in self {
-            let entity = world.spawn_empty().id();
-            result.push(entity.clone());
-            builder.with_entity(entity)(world);
+            result.extend(builder.build(world));
         }
         result
     }
@@ -126,6 +192,10 @@ impl IntoContent for Vec<ElementsBuilder> {
 
 impl IntoContent for ElementsBuilder {
     fn into_content(self, parent: Entity, world: &mut World) -> Vec<Entity> {
+        if self.is_fragment() {
+            // the roots of the fragment are the content
+            return self.build(world);
+        }
         self.with_entity(parent)(world);
         vec![parent]
     }
@@ -164,3 +234,29 @@ pub trait ExpandElementsExt: Iterator {
 }
 
 impl<I: Iterator> ExpandElementsExt for I {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fragment() -> ElementsBuilder {
+        ElementsBuilder::fragment(|world: &mut World| {
+            vec![world.spawn_empty().id(), world.spawn_empty().id()]
+        })
+    }
+
+    #[test]
+    fn fragment_splices_roots() {
+        let mut world = World::new();
+        let node = world.spawn_empty().id();
+        let content = fragment().into_content(node, &mut world);
+        assert_eq!(content.len(), 2);
+        assert!(world.get::<Children>(node).is_none());
+
+        let parent = world.spawn_empty().id();
+        fragment().with_entity(parent)(&mut world);
+        let children = world.get::<Children>(parent).unwrap();
+        assert_eq!(children.len(), 2);
+        assert!(world.get::<Element>(parent).is_some());
+    }
+}
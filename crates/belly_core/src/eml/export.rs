@@ -0,0 +1,216 @@
+use std::fmt::Write;
+
+use bevy::{ecs::world::EntityRef, prelude::*};
+
+use crate::{
+    context_menu::ContextMenu,
+    dnd::{DragPayload, Draggable, Droppable},
+    input::{FocusTrap, NavTarget, Navigation, TabIndex},
+    shortcut::{Shortcut, ShortcutScope},
+    tags,
+    touch::Zoomable,
+    Classes, Element, ElementId, ElementTag, States, Tooltip,
+};
+
+/// Exports the live element tree back to the markup:
+/// ```rust,ignore
+/// info!("{}", world.entity(body).to_eml_string());
+/// ```
+/// The element is written with its `id`, classes, inline styles as `s:`
+/// params and the params kept by the element (`tooltip`, `disabled`, ...).
+/// The params consumed by the widgets are not kept, so the elements spawned
+/// by the widgets are written as well. Text nodes are written as the text,
+/// virtual elements are replaced with their children, entities which are
+/// not elements are skipped with their descendants.
+pub trait ToEmlExtension {
+    fn to_eml_string(&self) -> String;
+}
+
+impl<'w> ToEmlExtension for EntityRef<'w> {
+    fn to_eml_string(&self) -> String {
+        let mut eml = String::new();
+        write_entity(self.world(), self.id(), 0, &mut eml);
+        eml
+    }
+}
+
+fn write_entity(world: &World, entity: Entity, depth: usize, eml: &mut String) {
+    let entity = world.entity(entity);
+    if !entity.contains::<Element>() {
+        return;
+    }
+    let name = entity.get::<ElementTag>().and_then(|tag| {
+        // the component name goes first for the derived widgets
+        tag.names
+            .iter()
+            .find(|name| !name.as_str().chars().any(char::is_uppercase))
+            .or_else(|| tag.names.first())
+            .copied()
+    });
+    let Some(name) = name else {
+        match entity.get::<Text>() {
+            Some(text) if !entity.contains::<ElementTag>() => {
+                let text: String = text.sections.iter().map(|s| s.value.as_str()).collect();
+                let _ = writeln!(eml, "{:indent$}{}", "", escape(&text), indent = depth * 4);
+            }
+            _ => write_children(world, entity.id(), depth, eml),
+        }
+        return;
+    };
+    let _ = write!(eml, "{:indent$}<{name}", "", indent = depth * 4);
+    if let Some(id) = entity.get::<ElementId>() {
+        write_param(eml, "id", id.0.as_str());
+    }
+    if let Some(classes) = entity.get::<Classes>() {
+        let mut classes: Vec<_> = classes.iter().map(|c| c.as_str()).collect();
+        if !classes.is_empty() {
+            classes.sort();
+            write_param(eml, "class", &classes.join(" "));
+        }
+    }
+    if let Some(tooltip) = entity.get::<Tooltip>() {
+        if let Some(text) = &tooltip.text {
+            write_param(eml, "tooltip", text);
+        }
+        if let Some(reason) = &tooltip.disabled_reason {
+            write_param(eml, "disabled-reason", reason);
+        }
+    }
+    if let Some(states) = entity.get::<States>() {
+        if states.contains(&tags::disabled()) {
+            write_param(eml, "disabled", "true");
+        }
+    }
+    if let Some(index) = entity.get::<TabIndex>() {
+        write_param(eml, "tab-index", &index.0.to_string());
+    }
+    if entity.contains::<Draggable>() {
+        write_param(eml, "draggable", "true");
+    }
+    if entity.contains::<Droppable>() {
+        write_param(eml, "droppable", "true");
+    }
+    if let Some(menu) = entity.get::<ContextMenu>() {
+        write_param(eml, "context-menu", menu.0.as_str());
+    }
+    if entity.contains::<Zoomable>() {
+        write_param(eml, "zoomable", "true");
+    }
+    if let Some(payload) = entity.get::<DragPayload>().and_then(|p| p.get::<String>()) {
+        write_param(eml, "drag-payload", payload);
+    }
+    if let Some(shortcut) = entity.get::<Shortcut>() {
+        write_param(eml, "shortcut", &shortcut.to_string());
+    }
+    if entity.contains::<ShortcutScope>() {
+        write_param(eml, "shortcut-scope", "true");
+    }
+    if entity.contains::<FocusTrap>() {
+        write_param(eml, "focus-trap", "true");
+    }
+    if let Some(navigation) = entity.get::<Navigation>() {
+        for (param, target) in [
+            ("nav-up", &navigation.up),
+            ("nav-down", &navigation.down),
+            ("nav-left", &navigation.left),
+            ("nav-right", &navigation.right),
+        ] {
+            // the entity targets are set from the code and can't be exported
+            if let Some(NavTarget::Id(id)) = target {
+                write_param(eml, param, &format!("#{}", id));
+            }
+        }
+    }
+    let element = entity.get::<Element>().unwrap();
+    let mut styles: Vec<_> = element
+        .styles
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str(), value.source()?)))
+        .collect();
+    styles.sort();
+    for (name, value) in styles {
+        write_param(eml, &format!("s:{name}"), value);
+    }
+    let mut children = String::new();
+    write_children(world, entity.id(), depth + 1, &mut children);
+    if children.is_empty() {
+        eml.push_str("/>\n");
+    } else {
+        let _ = write!(
+            eml,
+            ">\n{children}{:indent$}</{name}>\n",
+            "",
+            indent = depth * 4
+        );
+    }
+}
+
+fn write_children(world: &World, entity: Entity, depth: usize, eml: &mut String) {
+    if let Some(children) = world.get::<Children>(entity) {
+        for child in children.iter() {
+            write_entity(world, *child, depth, eml);
+        }
+    }
+}
+
+fn write_param(eml: &mut String, name: &str, value: &str) {
+    let _ = write!(eml, " {name}=\"{}\"", escape(value).replace('"', "&quot;"));
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ess::PropertyValue, TextElementBundle};
+    use tagstr::*;
+
+    #[test]
+    fn export_element_tree() {
+        let mut world = World::new();
+        let element = || {
+            let mut element = Element::default();
+            element.styles.insert(
+                tag!("width"),
+                PropertyValue::new(Val::Px(10.)).with_source("10px"),
+            );
+            element
+        };
+        let text = world
+            .spawn(TextElementBundle::default())
+            .insert(Text::from_section("a < b", default()))
+            .id();
+        let empty = world
+            .spawn(element())
+            .insert(ElementTag {
+                names: [tag!("Label"), tag!("label")].into_iter().collect(),
+                aliases: default(),
+            })
+            .id();
+        let root = world
+            .spawn(element())
+            .insert((
+                ElementTag {
+                    names: [tag!("div")].into_iter().collect(),
+                    aliases: default(),
+                },
+                ElementId(tag!("root")),
+                Classes([tag!("b"), tag!("a")].into_iter().collect()),
+            ))
+            .push_children(&[text, empty])
+            .id();
+        assert_eq!(
+            world.entity(root).to_eml_string(),
+            concat!(
+                "<div id=\"root\" class=\"a b\" s:width=\"10px\">\n",
+                "    a &lt; b\n",
+                "    <label s:width=\"10px\"/>\n",
+                "</div>\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::{Arc, Mutex, RwLock};
+
+use bevy::{ecs::event::Event, prelude::*, utils::HashMap};
+
+/// The handler of the signal wired from the markup. Receives the
+/// source of the signal.
+pub type EventHandler = Arc<dyn Fn(&mut World, Entity) + Send + Sync>;
+
+/// Handlers available to the `on:` params of the `.eml` assets by name:
+/// ```xml
+/// <button on:press="menu:start_game">"Start"</button>
+/// ```
+#[derive(Resource, Default, Clone)]
+pub struct EventHandlers(Arc<RwLock<HashMap<String, EventHandler>>>);
+
+impl EventHandlers {
+    pub fn insert(&self, name: impl Into<String>, handler: EventHandler) {
+        self.0.write().unwrap().insert(name.into(), handler);
+    }
+
+    pub fn get(&self, name: &str) -> Option<EventHandler> {
+        self.0.read().unwrap().get(name).cloned()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.read().unwrap().contains_key(name)
+    }
+}
+
+pub trait RegisterHandlerExtension {
+    /// Registers the callback receiving the source of the signal.
+    fn register_handler<F>(&mut self, name: impl Into<String>, handler: F) -> &mut Self
+    where
+        F: Fn(&mut World, Entity) + Send + Sync + 'static;
+
+    /// Registers the system running every time the signal is emitted.
+    fn register_handler_system<P, S>(&mut self, name: impl Into<String>, system: S) -> &mut Self
+    where
+        S: IntoSystem<(), (), P>;
+
+    /// Registers the handler sending the copy of the `event`.
+    fn register_handler_event<E>(&mut self, name: impl Into<String>, event: E) -> &mut Self
+    where
+        E: Event + Clone;
+}
+
+impl RegisterHandlerExtension for App {
+    fn register_handler<F>(&mut self, name: impl Into<String>, handler: F) -> &mut Self
+    where
+        F: Fn(&mut World, Entity) + Send + Sync + 'static,
+    {
+        self.world
+            .get_resource_or_insert_with(EventHandlers::default)
+            .insert(name, Arc::new(handler));
+        self
+    }
+
+    fn register_handler_system<P, S>(&mut self, name: impl Into<String>, system: S) -> &mut Self
+    where
+        S: IntoSystem<(), (), P>,
+    {
+        let mut system = IntoSystem::into_system(system);
+        system.initialize(&mut self.world);
+        let system = Mutex::new(system);
+        self.register_handler(name, move |world, _| {
+            let mut system = system.lock().unwrap();
+            system.run((), world);
+            system.apply_buffers(world);
+        })
+    }
+
+    fn register_handler_event<E>(&mut self, name: impl Into<String>, event: E) -> &mut Self
+    where
+        E: Event + Clone,
+    {
+        self.add_event::<E>();
+        self.register_handler(name, move |world, _| {
+            world.resource_mut::<Events<E>>().send(event.clone());
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Started(usize);
+
+    #[derive(Clone)]
+    struct StartGame;
+
+    fn start(mut started: ResMut<Started>) {
+        started.0 += 1;
+    }
+
+    #[test]
+    fn register_handlers() {
+        let mut app = App::new();
+        app.init_resource::<Started>();
+        app.register_handler_system("menu:start", start);
+        app.register_handler_event("menu:start_event", StartGame);
+        let handlers = app.world.resource::<EventHandlers>().clone();
+        let source = app.world.spawn_empty().id();
+        handlers.get("menu:start").unwrap()(&mut app.world, source);
+        handlers.get("menu:start").unwrap()(&mut app.world, source);
+        handlers.get("menu:start_event").unwrap()(&mut app.world, source);
+        assert_eq!(app.world.resource::<Started>().0, 2);
+        assert_eq!(app.world.resource::<Events<StartGame>>().len(), 1);
+        assert!(!handlers.contains("menu:quit"));
+    }
+}
@@ -1,12 +1,18 @@
 use crate::{ElementBuilderRegistry, PropertyExtractor, PropertyTransformer};
 use bevy::prelude::*;
 
-use asset::{update_eml_scene, EmlAsset, EmlLoader};
+use asset::{update_eml_scene, EmlAsset, EmlLoader, EmlWhitespace};
+use handlers::EventHandlers;
 pub mod asset;
 pub mod build;
 pub mod content;
+pub mod export;
+pub mod handlers;
 pub mod params;
 pub mod parse;
+pub mod repeat;
+pub mod style;
+pub mod toggle;
 pub mod variant;
 
 #[derive(Default)]
@@ -30,10 +36,20 @@ impl Plugin for EmlPlugin {
             .world
             .get_resource_or_insert_with(ElementBuilderRegistry::default)
             .clone();
+        let handlers = app
+            .world
+            .get_resource_or_insert_with(EventHandlers::default)
+            .clone();
+        let whitespace = app
+            .world
+            .get_resource_or_insert_with(EmlWhitespace::default)
+            .clone();
         app.add_asset_loader(EmlLoader {
             transformer: validator,
             extractor,
             registry,
+            handlers,
+            whitespace,
         });
         app.add_system(update_eml_scene);
     }
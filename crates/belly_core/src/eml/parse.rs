@@ -1,33 +1,80 @@
-use std::fmt::Display;
+use bevy::{
+    asset::AssetPath,
+    utils::{HashMap, HashSet},
+};
+use std::{fmt::Display, mem, path::Path};
 
-use roxmltree;
+use belly_syntax::{
+    eml::{self, EmlElementSyntax, EmlNodeSyntax, NS_STYLE},
+    LineIndex, Spanned,
+};
 use tagstr::{AsTag, Tag};
 
-use super::asset::{EmlElement, EmlLoader, EmlNode};
+use super::asset::{EmlElement, EmlLoader, EmlNode, Whitespace};
 use super::Variant;
 use crate::{ess::StyleProperty, ElementsError};
 
-const NS_STYLE: &str = "s";
+/// The root node of the document, the templates declared in it,
+/// the paths of the included documents and the problems replaced
+/// with the error placeholders.
+pub(crate) struct EmlDocument {
+    pub(crate) root: EmlNode,
+    pub(crate) templates: HashMap<Tag, EmlNode>,
+    pub(crate) includes: Vec<AssetPath<'static>>,
+    pub(crate) errors: Vec<ParseError>,
+}
 
-pub(crate) fn parse(source: &str, loader: &EmlLoader) -> Result<EmlNode, ParseError> {
-    let source = EmlSource::new(source);
-    parse_internal(&source, loader).map_err(|e| ParseError::new(e, &source))
+/// Parses the document, the `src` of the `<include>` tags is
+/// resolved relative to the `dir` of the document. The malformed or
+/// invalid elements are replaced with [`EmlNode::Error`] placeholders,
+/// the parsing fails only if the document has no usable root.
+pub(crate) fn parse(
+    source: &str,
+    dir: &Path,
+    loader: &EmlLoader,
+) -> Result<EmlDocument, ParseError> {
+    let syntax = eml::parse(source);
+    let mut errors = syntax
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| Error::Syntax(diagnostic.message, diagnostic.span.start));
+    if syntax.nodes.is_empty() {
+        if let Some(err) = errors.next() {
+            return Err(ParseError::new(err, source));
+        }
+    }
+    parse_root(&syntax.nodes, errors.collect(), source, dir, loader)
+        .map_err(|e| ParseError::new(e, source))
 }
 
 enum Error {
-    InvalidElement(String, roxmltree::TextPos),
-    InvalidStyleValue(String, roxmltree::TextPos),
-    InvalidDocumentStructure(String, roxmltree::TextPos),
-    Internal(roxmltree::Error),
+    InvalidElement(String, usize),
+    InvalidStyleValue(String, usize),
+    InvalidParam(String, usize),
+    InvalidDocumentStructure(String, usize),
+    Syntax(String, usize),
 }
 
 impl Error {
-    fn pos(&self) -> roxmltree::TextPos {
+    fn offset(&self) -> usize {
+        match self {
+            Error::InvalidElement(_, offset) => *offset,
+            Error::InvalidDocumentStructure(_, offset) => *offset,
+            Error::InvalidStyleValue(_, offset) => *offset,
+            Error::InvalidParam(_, offset) => *offset,
+            Error::Syntax(_, offset) => *offset,
+        }
+    }
+
+    fn message(&self) -> String {
         match self {
-            Error::InvalidElement(_, pos) => *pos,
-            Error::InvalidDocumentStructure(_, pos) => *pos,
-            Error::InvalidStyleValue(_, pos) => *pos,
-            Error::Internal(e) => e.pos(),
+            Error::Syntax(e, _) => e.clone(),
+            Error::InvalidElement(e, _) => format!("Invalid element: {}", e),
+            Error::InvalidDocumentStructure(msg, _) => {
+                format!("Invalid document structure: {}", msg)
+            }
+            Error::InvalidStyleValue(msg, _) => msg.clone(),
+            Error::InvalidParam(msg, _) => msg.clone(),
         }
     }
 }
@@ -43,29 +90,16 @@ impl Display for ParseError {
 impl std::error::Error for ParseError {}
 
 impl ParseError {
-    fn new(err: Error, source: &EmlSource) -> ParseError {
-        let msg = match &err {
-            Error::Internal(e) => format!("{}", e),
-            Error::InvalidElement(e, pos) => format!("Invalid element: {} at {}", e, pos),
-            Error::InvalidDocumentStructure(msg, pos) => {
-                format!("Invalid document structure: {} at {}", msg, pos)
-            }
-            Error::InvalidStyleValue(msg, pos) => format!("{} at {}", msg, pos),
-        };
-
-        let pos = err.pos();
-        let posmsg = format!(" at {}:{}", pos.row, pos.col);
-        let msg = format!(
-            "{} at {}:{}",
-            msg.replace(&posmsg, ""),
-            pos.row - source.line_offset,
-            pos.col
-        );
+    fn new(err: Error, source: &str) -> ParseError {
+        let pos = LineIndex::new(source).position(err.offset());
+        let msg = format!("{} at {}", err.message(), pos);
         let msglen = msg.chars().count();
-        let lineidx = if pos.row > 0 { pos.row - 1 } else { pos.row } as usize;
-        let line = source.data.lines().nth(lineidx).unwrap();
+        let line = source
+            .lines()
+            .nth(pos.line as usize - 1)
+            .unwrap_or_default();
         let linelen = line.chars().count();
-        let pos = pos.col as usize;
+        let pos = pos.column as usize;
         let suffix0len = if linelen > msglen {
             linelen - msglen + 1
         } else {
@@ -76,7 +110,7 @@ impl ParseError {
         } else {
             msglen - linelen + 2
         };
-        let suffix2len = linelen.max(msglen) - pos + 1;
+        let suffix2len = (linelen.max(msglen) + 1).saturating_sub(pos);
         let empty = "";
         let errmsg = format!(
             "{msg} {empty:-<s0$}.\n{line}{empty: <s1$}|\n{empty: <pos$}^{empty:-<s2$}`\n",
@@ -89,110 +123,496 @@ impl ParseError {
     }
 }
 
-struct EmlSource {
-    line_offset: u32,
-    data: String,
+fn parse_root(
+    nodes: &[EmlNodeSyntax],
+    errors: Vec<Error>,
+    source: &str,
+    dir: &Path,
+    loader: &EmlLoader,
+) -> Result<EmlDocument, Error> {
+    let children: Vec<_> = nodes
+        .iter()
+        .filter(|n| match n {
+            EmlNodeSyntax::Element(element) => !is_template(element),
+            EmlNodeSyntax::Error(_) => true,
+            EmlNodeSyntax::Text(_) => false,
+        })
+        .collect();
+    if children.is_empty() {
+        return Err(Error::InvalidDocumentStructure(
+            "Node should has at least one child".to_string(),
+            0,
+        ));
+    }
+    let mut parser = Parser {
+        loader,
+        dir,
+        lines: LineIndex::new(source),
+        declarations: Default::default(),
+        templates: Default::default(),
+        failed: Default::default(),
+        stack: vec![],
+        includes: vec![],
+        errors,
+        whitespace: loader.whitespace.get(),
+        default_whitespace: loader.whitespace.get(),
+    };
+    parser.collect_templates(nodes);
+    let mut roots: Vec<_> = children.into_iter().map(|n| parser.root(n)).collect();
+    // several roots of the document are the fragment
+    let root = match roots.len() {
+        1 => roots.remove(0),
+        _ => EmlNode::Fragment(roots),
+    };
+    for name in parser.declarations.keys().copied().collect::<Vec<_>>() {
+        if !parser.failed.contains(&name) {
+            let template = parser.template(name, 0);
+            parser.recover(template);
+        }
+    }
+    let mut errors = parser.errors;
+    errors.sort_by_key(Error::offset);
+    Ok(EmlDocument {
+        root,
+        templates: parser.templates,
+        includes: parser.includes,
+        errors: errors
+            .into_iter()
+            .map(|e| ParseError::new(e, source))
+            .collect(),
+    })
+}
+
+/// The line break right after the start tag is the part of the markup.
+fn raw_text(text: &str) -> String {
+    let text = text.strip_prefix('\r').unwrap_or(text);
+    text.strip_prefix('\n').unwrap_or(text).to_string()
 }
 
-impl EmlSource {
-    fn new(data: &str) -> EmlSource {
-        let prefix = format!("<skip:root xmlns:skip=\"skip\" xmlns:{NS_STYLE}=\"{NS_STYLE}\">\n");
-        let suffix = "\n</skip:root>";
-        let line_offset = 1;
-        let data = prefix + data + suffix;
-        EmlSource { line_offset, data }
-    }
+/// The text of the `<pre>` element or of the element with `raw="true"`
+/// attribute is kept as is.
+fn is_raw(node: &EmlElementSyntax) -> bool {
+    node.local_name() == "pre"
+        || node
+            .attribute("raw")
+            .map(|raw| raw.value.value == "true")
+            .unwrap_or(false)
 }
 
-fn parse_internal(source: &EmlSource, loader: &EmlLoader) -> Result<EmlNode, Error> {
-    let document = roxmltree::Document::parse(&source.data);
-    match document {
-        Err(e) => Err(Error::Internal(e)),
-        Ok(doc) => parse_root(doc.root(), loader),
+fn is_template(node: &EmlElementSyntax) -> bool {
+    node.local_name() == "template"
+}
+
+/// Replaces the slot definitions with the content of the `slots`
+/// or with the fallback content.
+fn fill_slots(nodes: Vec<EmlNode>, slots: &mut HashMap<Tag, Vec<EmlNode>>) -> Vec<EmlNode> {
+    let mut result = vec![];
+    for node in nodes {
+        match node {
+            EmlNode::Define(name, fallback) => match slots.remove(&name) {
+                Some(content) => result.extend(content),
+                None => result.extend(fill_slots(fallback, slots)),
+            },
+            EmlNode::Element(mut element) => {
+                element.children = fill_slots(element.children, slots);
+                result.push(EmlNode::Element(element));
+            }
+            EmlNode::Fragment(nodes) => result.push(EmlNode::Fragment(fill_slots(nodes, slots))),
+            EmlNode::Slot(name, content) => {
+                result.push(EmlNode::Slot(name, fill_slots(content, slots)))
+            }
+            node => result.push(node),
+        }
     }
+    result
+}
+
+struct Parser<'a> {
+    loader: &'a EmlLoader,
+    dir: &'a Path,
+    lines: LineIndex,
+    declarations: HashMap<Tag, &'a EmlElementSyntax>,
+    /// Parsed templates with the slot definitions kept.
+    templates: HashMap<Tag, EmlNode>,
+    /// Templates failed to parse, the problem is reported once.
+    failed: HashSet<Tag>,
+    /// Templates being parsed, used to report the recursive ones.
+    stack: Vec<Tag>,
+    includes: Vec<AssetPath<'static>>,
+    /// Problems replaced with the error placeholders.
+    errors: Vec<Error>,
+    /// The whitespace mode inherited from the parent elements.
+    whitespace: Whitespace,
+    /// The whitespace mode of the document, templates start with it.
+    default_whitespace: Whitespace,
 }
 
-fn parse_root(node: roxmltree::Node, loader: &EmlLoader) -> Result<EmlNode, Error> {
-    let ns = node.tag_name().namespace();
-    let doc = node.document();
-    let pos = doc.text_pos_at(node.position());
-    if node.is_root() || ns == Some("skip") {
-        let children: Vec<_> = node.children().filter(|n| n.is_element()).collect();
-        if children.len() != 1 {
+impl<'a> Parser<'a> {
+    /// Keeps parsing the rest of the document when the element fails:
+    /// the element is replaced with the placeholder.
+    fn recover(&mut self, result: Result<EmlNode, Error>) -> EmlNode {
+        result.unwrap_or_else(|err| {
+            let pos = self.lines.position(err.offset());
+            let node = EmlNode::Error(format!("{} at {}", err.message(), pos));
+            self.errors.push(err);
+            node
+        })
+    }
+
+    /// The placeholder of the malformed element, the problem is already
+    /// reported by the syntax parser.
+    fn error(&self, message: &Spanned<String>) -> EmlNode {
+        let pos = self.lines.position(message.span.start);
+        EmlNode::Error(format!("{} at {}", message.value, pos))
+    }
+
+    fn root(&mut self, node: &EmlNodeSyntax) -> EmlNode {
+        match node {
+            EmlNodeSyntax::Element(element) => {
+                let root = self.walk(element);
+                self.recover(root)
+            }
+            EmlNodeSyntax::Error(message) => self.error(message),
+            EmlNodeSyntax::Text(_) => unreachable!("text is not the root"),
+        }
+    }
+
+    /// Templates could be declared at any level of the document
+    /// and used anywhere in it. Invalid declarations are reported
+    /// and skipped.
+    fn collect_templates(&mut self, nodes: &'a [EmlNodeSyntax]) {
+        for node in nodes.iter() {
+            let EmlNodeSyntax::Element(element) = node else {
+                continue;
+            };
+            if is_template(element) {
+                if let Err(err) = self.declare(element) {
+                    self.errors.push(err);
+                }
+            }
+            self.collect_templates(&element.children);
+        }
+    }
+
+    fn declare(&mut self, element: &'a EmlElementSyntax) -> Result<(), Error> {
+        let pos = element.span.start;
+        let name = element.attribute("name").ok_or_else(|| {
+            Error::InvalidElement(
+                "<template> tag should have 'name' attribute.".to_string(),
+                pos,
+            )
+        })?;
+        let name = name.value.value.as_tag();
+        if self.loader.registry.has_builder(name) {
+            return Err(Error::InvalidElement(
+                format!("template {name} conflicts with the widget of the same name"),
+                pos,
+            ));
+        }
+        if self.declarations.contains_key(&name) {
             return Err(Error::InvalidDocumentStructure(
-                "Node should has exactly one child".to_string(),
+                format!("template {name} is already declared"),
                 pos,
             ));
         }
-        parse_root(children[0], loader)
-    } else {
-        walk(node, loader)
+        self.declarations.insert(name, element);
+        Ok(())
     }
-}
 
-fn walk(node: roxmltree::Node, loader: &EmlLoader) -> Result<EmlNode, Error> {
-    let doc = node.document();
-    let pos = doc.text_pos_at(node.position());
-    if node.is_text() {
-        let text = node.text().unwrap();
-        let text = text.trim();
-        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
-        Ok(EmlNode::Text(text))
-    } else if node.is_element() && node.tag_name().name() == "slot" {
-        let slot_name = node.attribute("replace").ok_or_else(|| {
-            Error::InvalidElement(format!("<slot> tag should have 'for' attribute."), pos)
-        })?;
-        let mut slot_elements: Vec<EmlNode> = vec![];
-        for ch in node.children() {
-            slot_elements.push(walk(ch, loader)?);
+    fn template(&mut self, name: Tag, pos: usize) -> Result<EmlNode, Error> {
+        if let Some(template) = self.templates.get(&name) {
+            return Ok(template.clone());
+        }
+        if self.failed.contains(&name) {
+            return Err(Error::InvalidElement(
+                format!("template {name} is invalid"),
+                pos,
+            ));
+        }
+        if self.stack.contains(&name) {
+            return Err(Error::InvalidDocumentStructure(
+                format!("template {name} uses itself"),
+                pos,
+            ));
+        }
+        let inherited = mem::replace(&mut self.whitespace, self.default_whitespace);
+        let template = self.parse_template(name);
+        self.whitespace = inherited;
+        match &template {
+            Ok(template) => {
+                self.templates.insert(name, template.clone());
+            }
+            Err(_) => {
+                self.failed.insert(name);
+            }
+        }
+        template
+    }
+
+    fn parse_template(&mut self, name: Tag) -> Result<EmlNode, Error> {
+        let declaration = self.declarations[&name];
+        let children: Vec<_> = declaration
+            .children
+            .iter()
+            .filter(|n| match n {
+                EmlNodeSyntax::Element(element) => !is_template(element),
+                EmlNodeSyntax::Error(_) => true,
+                EmlNodeSyntax::Text(_) => false,
+            })
+            .collect();
+        self.stack.push(name);
+        let template = match children.as_slice() {
+            [] => Err(Error::InvalidDocumentStructure(
+                format!("template {name} should has at least one child element"),
+                declaration.span.start,
+            )),
+            // the params of the root are completed by the instances
+            [EmlNodeSyntax::Element(root)] => self.node(root, false),
+            roots => Ok(EmlNode::Fragment(
+                roots.iter().map(|n| self.root(n)).collect(),
+            )),
+        };
+        self.stack.pop();
+        let template = template?;
+        if !matches!(template, EmlNode::Element(_) | EmlNode::Fragment(_)) {
+            return Err(Error::InvalidDocumentStructure(
+                format!("template {name} should has the element root"),
+                declaration.span.start,
+            ));
+        }
+        Ok(template)
+    }
+
+    fn children(&mut self, node: &EmlElementSyntax) -> Vec<EmlNode> {
+        let raw = is_raw(node);
+        let whitespace = node
+            .attribute("whitespace")
+            .and_then(|w| w.value.value.parse().ok())
+            .unwrap_or(self.whitespace);
+        let inherited = mem::replace(&mut self.whitespace, whitespace);
+        let mut children = vec![];
+        for ch in node.children.iter() {
+            match ch {
+                EmlNodeSyntax::Text(value) => {
+                    let text = if raw {
+                        raw_text(&value.value)
+                    } else {
+                        whitespace.apply(&value.value)
+                    };
+                    if !text.is_empty() {
+                        children.push(EmlNode::Text(text));
+                    }
+                }
+                EmlNodeSyntax::Element(element) if is_template(element) => continue,
+                EmlNodeSyntax::Element(element) => {
+                    let child = self.walk(element);
+                    children.push(self.recover(child));
+                }
+                EmlNodeSyntax::Error(message) => children.push(self.error(message)),
+            }
+        }
+        self.whitespace = inherited;
+        children
+    }
+
+    fn params(&self, node: &EmlElementSyntax) -> Result<HashMap<String, String>, Error> {
+        let mut params = HashMap::default();
+        for attr in node.attributes.iter() {
+            let pos = attr.span.start;
+            if attr.namespace.as_deref() == Some(NS_STYLE) {
+                let value = &attr.value.value;
+                validate_style(attr.local_name().as_tag(), value, self.loader).map_err(|e| {
+                    Error::InvalidStyleValue(
+                        format!(
+                            "Invalid value for {NS_STYLE}:{} attribute: {}",
+                            attr.local_name(),
+                            e
+                        ),
+                        pos,
+                    )
+                })?;
+            }
+            if attr.name.value == "whitespace" {
+                let value = &attr.value.value;
+                value
+                    .parse::<Whitespace>()
+                    .map_err(|e| Error::InvalidParam(e, pos))?;
+            }
+            if let Some(signal) = attr.name.value.strip_prefix("on:") {
+                let handler = &attr.value.value;
+                if !self.loader.handlers.contains(handler) {
+                    return Err(Error::InvalidParam(
+                        format!("Unknown handler '{handler}' for {signal} signal"),
+                        pos,
+                    ));
+                }
+            }
+            params.insert(attr.name.value.clone(), attr.value.value.clone());
         }
-        Ok(EmlNode::Slot(slot_name.as_tag(), slot_elements))
-    } else if node.is_element() {
-        let node_name = node.tag_name().name().as_tag();
-        if !loader.registry.has_builder(node_name) {
-            return Err(Error::InvalidElement(node_name.to_string(), pos));
+        Ok(params)
+    }
+
+    fn walk(&mut self, node: &EmlElementSyntax) -> Result<EmlNode, Error> {
+        self.node(node, true)
+    }
+
+    /// Checks the `params` of the element against the params schema
+    /// of the widget.
+    fn validate(&self, elem: &EmlElement, pos: usize) -> Result<(), Error> {
+        let Some(builder) = self.loader.registry.get_builder(elem.name) else {
+            return Ok(());
+        };
+        let tag = elem.name;
+        for schema in builder.params() {
+            match elem.params.get(schema.name) {
+                Some(value) => (schema.validate)(value).map_err(|e| {
+                    Error::InvalidParam(
+                        format!(
+                            "Invalid value for '{}' param of <{tag}>, {} expected: {e}",
+                            schema.name, schema.type_name
+                        ),
+                        pos,
+                    )
+                })?,
+                None if schema.required => {
+                    return Err(Error::InvalidParam(
+                        format!("<{tag}> requires '{}' param", schema.name),
+                        pos,
+                    ))
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn node(&mut self, node: &EmlElementSyntax, validate: bool) -> Result<EmlNode, Error> {
+        let pos = node.span.start;
+        if node.local_name() == "slot" {
+            if let Some(slot_name) = node.attribute("define") {
+                let fallback = self.children(node);
+                return Ok(EmlNode::Define(slot_name.value.value.as_tag(), fallback));
+            }
+            let slot_name = node.attribute("replace").ok_or_else(|| {
+                Error::InvalidElement(format!("<slot> tag should have 'for' attribute."), pos)
+            })?;
+            let slot_elements = self.children(node);
+            return Ok(EmlNode::Slot(slot_name.value.value.as_tag(), slot_elements));
+        }
+        if node.local_name() == "fragment" {
+            if let Some(attr) = node.attributes.first() {
+                return Err(Error::InvalidElement(
+                    "<fragment> tag takes no params".to_string(),
+                    attr.span.start,
+                ));
+            }
+            return Ok(EmlNode::Fragment(self.children(node)));
+        }
+        if node.local_name() == "include" {
+            let src = node.attribute("src").ok_or_else(|| {
+                Error::InvalidElement(
+                    "<include> tag should have 'src' attribute.".to_string(),
+                    pos,
+                )
+            })?;
+            let path = AssetPath::new(self.dir.join(&src.value.value), None);
+            if !self.includes.contains(&path) {
+                self.includes.push(path.clone());
+            }
+            return Ok(EmlNode::Include(path));
+        }
+        let node_name = node.local_name().as_tag();
+        if self.declarations.contains_key(&node_name) {
+            return self.instantiate(node_name, node, validate);
+        }
+        if !self.loader.registry.has_builder(node_name) {
+            return Err(Error::InvalidElement(
+                node_name.to_string(),
+                node.name.span.start,
+            ));
         }
 
         let mut elem = EmlElement::new(node_name);
-        for attr in node.attributes() {
-            let pos = doc.text_pos_at(attr.position());
-            let name = if let Some(ns) = attr.namespace() {
-                if ns == NS_STYLE {
-                    validate_style(attr.name().as_tag(), attr.value(), loader).map_err(|e| {
-                        Error::InvalidStyleValue(
-                            format!(
-                                "Invalid value for {NS_STYLE}:{} attribute: {}",
-                                attr.name(),
-                                e
-                            ),
-                            pos,
-                        )
-                    })?;
+        elem.params = self.params(node)?;
+        elem.children = self.children(node);
+        if validate {
+            self.validate(&elem, pos)?;
+        }
+        Ok(EmlNode::Element(elem))
+    }
+
+    /// Reports the `slots` left unfilled by the template `name`.
+    fn check_slots(
+        &self,
+        name: Tag,
+        slots: &HashMap<Tag, Vec<EmlNode>>,
+        pos: usize,
+    ) -> Result<(), Error> {
+        match slots.keys().next() {
+            Some(slot) => Err(Error::InvalidElement(
+                format!("template {name} has no slot {slot}"),
+                pos,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Expands the template: `<slot replace="name">` children of the `node`
+    /// fill the slots of the template, the rest of the children are appended
+    /// to the root of the template, params override the params of the root.
+    fn instantiate(
+        &mut self,
+        name: Tag,
+        node: &EmlElementSyntax,
+        validate: bool,
+    ) -> Result<EmlNode, Error> {
+        let pos = node.span.start;
+        let template = self.template(name, pos)?;
+        let mut slots = HashMap::default();
+        let mut children = vec![];
+        for child in self.children(node) {
+            match child {
+                EmlNode::Slot(slot, content) => {
+                    slots.insert(slot, content);
                 }
-                format!("{}:{}", ns, attr.name())
-            } else {
-                attr.name().to_string()
-            };
-            elem.params.insert(name, attr.value().to_string());
-        }
-        for ch in node.children() {
-            if ch.is_text() {
-                let text = ch.text().unwrap();
-                let text = text.trim();
-                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
-                if text.len() > 0 {
-                    elem.children.push(EmlNode::Text(text));
+                child => children.push(child),
+            }
+        }
+        let mut elem = match template {
+            EmlNode::Element(elem) => elem,
+            EmlNode::Fragment(roots) => {
+                // the rest of the children follow the roots of the fragment
+                let mut roots = fill_slots(roots, &mut slots);
+                self.check_slots(name, &slots, pos)?;
+                if !node.attributes.is_empty() {
+                    return Err(Error::InvalidElement(
+                        format!("template {name} has several roots and takes no params"),
+                        pos,
+                    ));
                 }
-            } else if ch.is_element() {
-                elem.children.push(walk(ch, loader)?);
+                roots.extend(children);
+                return Ok(EmlNode::Fragment(roots));
             }
+            _ => unreachable!("templates are checked to have the element root"),
+        };
+        elem.children = fill_slots(elem.children, &mut slots);
+        self.check_slots(name, &slots, pos)?;
+        elem.children.extend(children);
+        for (param, value) in self.params(node)? {
+            match elem.params.get_mut(&param) {
+                Some(classes) if param == "class" => {
+                    classes.push(' ');
+                    classes.push_str(&value);
+                }
+                _ => {
+                    elem.params.insert(param, value);
+                }
+            }
+        }
+        if validate {
+            self.validate(&elem, pos)?;
         }
         Ok(EmlNode::Element(elem))
-    } else {
-        Err(Error::InvalidDocumentStructure(
-            format!("Invalid element: {node:?}"),
-            pos,
-        ))
     }
 }
 
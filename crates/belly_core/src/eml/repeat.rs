@@ -0,0 +1,178 @@
+use std::{any::TypeId, mem, sync::Arc};
+
+use bevy::{ecs::system::Command, prelude::*};
+
+use crate::{
+    relations::{
+        bind::{bind_id, FromComponent, FromResource, ToComponentWithoutTransformer},
+        RelationsSystems,
+    },
+    ElementBundle,
+};
+
+/// The item of the collection the `<for>` loop is bound to. Items are
+/// compared to find out which of them are already rendered.
+pub trait RepeatItem: Clone + PartialEq + Send + Sync + 'static {}
+impl<T: Clone + PartialEq + Send + Sync + 'static> RepeatItem for T {}
+
+type Render<T> = Arc<dyn Fn(&mut World, &T) -> Vec<Entity> + Send + Sync>;
+
+/// Renders the children per item of the bound collection. This is what
+/// the `<for>` loop bound with `from!` expands to:
+/// ```rust,ignore
+/// eml! {
+///     <div c:inventory>
+///         <for item in=from!(Inventory:items)>
+///             <span c:slot>{item.name.clone()}</span>
+///         </for>
+///     </div>
+/// }
+/// ```
+/// When the collection changes, the children of the items still present
+/// in it are kept (and reordered if required), the children of the removed
+/// items are despawned and the children of the new items are rendered. The
+/// items are identified by the value, so the changed item is rendered again.
+#[derive(Component)]
+pub struct Repeat<T: RepeatItem> {
+    pub items: Vec<T>,
+    render: Render<T>,
+    /// The items the children are rendered for.
+    rendered: Vec<(T, Vec<Entity>)>,
+}
+
+impl<T: RepeatItem> Repeat<T> {
+    /// Makes the `entity` render the children with `render` per item of
+    /// the collection bound with the `source`.
+    pub fn spawn<S, F>(world: &mut World, entity: Entity, source: S, render: F)
+    where
+        S: RepeatSource<Item = T>,
+        F: Fn(&mut World, &T) -> Vec<Entity> + Send + Sync + 'static,
+    {
+        {
+            let systems = world.get_resource_or_insert_with(RelationsSystems::default);
+            let mut systems = systems.0.write().unwrap();
+            systems.add_custom_system(TypeId::of::<Repeat<T>>(), update_repeat::<T>);
+        }
+        world.entity_mut(entity).insert((
+            ElementBundle::default(),
+            Repeat {
+                items: vec![],
+                render: Arc::new(render),
+                rendered: vec![],
+            },
+        ));
+        source.bind(world, entity);
+    }
+
+    /// The target to bind the collection to.
+    pub fn target(entity: Entity) -> ToComponentWithoutTransformer<Repeat<T>, Vec<T>> {
+        ToComponentWithoutTransformer {
+            id: bind_id::<Repeat<T>>("items"),
+            target: entity,
+            reader: |c: &Mut<Repeat<T>>| &c.items,
+            writer: |c: &mut Mut<Repeat<T>>| &mut c.items,
+        }
+    }
+}
+
+/// The source of the collection the `<for>` loop could be bound to:
+/// `from!(entity, Component:items)` or `from!(Resource:items)`
+/// reading the `Vec` of items.
+pub trait RepeatSource {
+    type Item: RepeatItem;
+    fn bind(self, world: &mut World, target: Entity);
+}
+
+impl<R: Component, T: RepeatItem> RepeatSource for FromComponent<R, Vec<T>> {
+    type Item = T;
+    fn bind(self, world: &mut World, target: Entity) {
+        (self >> Repeat::<T>::target(target)).write(world);
+    }
+}
+
+impl<R: Resource, T: RepeatItem> RepeatSource for FromResource<R, Vec<T>> {
+    type Item = T;
+    fn bind(self, world: &mut World, target: Entity) {
+        (self >> Repeat::<T>::target(target)).write(world);
+    }
+}
+
+fn update_repeat<T: RepeatItem>(world: &mut World) {
+    let changed: Vec<Entity> = world
+        .query_filtered::<Entity, Changed<Repeat<T>>>()
+        .iter(world)
+        .collect();
+    for entity in changed {
+        let Some(mut repeat) = world.get_mut::<Repeat<T>>(entity) else {
+            continue;
+        };
+        let repeat = repeat.bypass_change_detection();
+        if repeat.items.len() == repeat.rendered.len()
+            && repeat
+                .items
+                .iter()
+                .zip(repeat.rendered.iter())
+                .all(|(a, (b, _))| a == b)
+        {
+            continue;
+        }
+        let items = repeat.items.clone();
+        let render = repeat.render.clone();
+        let mut unused = mem::take(&mut repeat.rendered);
+        let mut rendered = Vec::with_capacity(items.len());
+        for item in items {
+            match unused.iter().position(|(rendered, _)| *rendered == item) {
+                Some(index) => rendered.push(unused.remove(index)),
+                None => {
+                    let children = render(world, &item);
+                    rendered.push((item, children));
+                }
+            }
+        }
+        for (_, children) in unused {
+            for child in children {
+                DespawnRecursive { entity: child }.write(world);
+            }
+        }
+        // pushing the children already attached moves them to the end,
+        // so the children follow the order of the items
+        let children: Vec<Entity> = rendered.iter().flat_map(|(_, c)| c).copied().collect();
+        world.entity_mut(entity).push_children(&children);
+        if let Some(mut repeat) = world.get_mut::<Repeat<T>>(entity) {
+            repeat.bypass_change_detection().rendered = rendered;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn repeat_keeps_rendered_items() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        world.entity_mut(entity).insert(Repeat::<u32> {
+            items: vec![1, 2, 3],
+            render: Arc::new(|world: &mut World, _: &u32| vec![world.spawn_empty().id()]),
+            rendered: vec![],
+        });
+        update_repeat::<u32>(&mut world);
+        let children = |world: &World| world.get::<Children>(entity).unwrap().to_vec();
+        let first = children(&world);
+        assert_eq!(first.len(), 3);
+
+        world.get_mut::<Repeat<u32>>(entity).unwrap().items = vec![3, 4, 1];
+        world.clear_trackers();
+        world.get_mut::<Repeat<u32>>(entity).unwrap().set_changed();
+        update_repeat::<u32>(&mut world);
+        let second = children(&world);
+        assert_eq!(second.len(), 3);
+        assert_eq!(second[0], first[2]);
+        assert_eq!(second[2], first[0]);
+        assert!(
+            world.get_entity(first[1]).is_none(),
+            "Should despawn removed"
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use std::any::TypeId;
+
+use bevy::{ecs::entity::Entities, ecs::query::ReadOnlyWorldQuery, prelude::*};
+use tagstr::Tag;
+
+use crate::{
+    ess::transform_inline_style,
+    relations::{
+        bind::{
+            bind_id, BindableSource, BindableTarget, FromComponent, FromComponentWithTransformer,
+            FromComputed, FromResource, FromResourceWithTransformer, ToComponentWithoutTransformer,
+        },
+        RelationsSystems,
+    },
+    Element, PropertyExtractor, PropertyTransformer, Variant,
+};
+
+/// The value the style property could be bound to: `String` is parsed
+/// the same way the `s:` param is, the other values (`Val`, `Color`,
+/// `f32`) are passed to the property as is.
+pub trait StyleValue: BindableSource + BindableTarget + Default + Into<Variant> {}
+impl<T: BindableSource + BindableTarget + Default + Into<Variant>> StyleValue for T {}
+
+/// Sets the inline style `name` of the `target` element to the bound
+/// value. This is what the bound `s:` params expand to:
+/// ```rust,ignore
+/// eml! {
+///     <div c:progress s:width=from!(player, Health:current | val.percent)/>
+/// }
+/// ```
+/// The value is transformed by the same property transformers the
+/// stylesheets use, inline styles win over any rule of the stylesheets.
+/// The binding is kept on its own entity, it is despawned with the `target`.
+#[derive(Component)]
+pub struct BoundStyle<T: StyleValue> {
+    pub target: Entity,
+    pub name: Tag,
+    pub value: T,
+}
+
+impl<T: StyleValue> BoundStyle<T> {
+    /// Spawns the binding of the style property `name` of the `target`
+    /// element and returns the property to bind the value to.
+    pub fn spawn(
+        world: &mut World,
+        target: Entity,
+        name: Tag,
+    ) -> ToComponentWithoutTransformer<BoundStyle<T>, T> {
+        {
+            let systems = world.get_resource_or_insert_with(RelationsSystems::default);
+            let mut systems = systems.0.write().unwrap();
+            systems.add_custom_system(TypeId::of::<BoundStyle<T>>(), update_bound_styles::<T>);
+        }
+        let bound = world
+            .spawn(BoundStyle {
+                target,
+                name,
+                value: T::default(),
+            })
+            .id();
+        ToComponentWithoutTransformer {
+            id: bind_id::<BoundStyle<T>>("value"),
+            target: bound,
+            reader: |c: &Mut<BoundStyle<T>>| &c.value,
+            writer: |c: &mut Mut<BoundStyle<T>>| &mut c.value,
+        }
+    }
+}
+
+/// The source the style property could be bound to:
+/// `from!(entity, Component:property)`, `from!(Resource:property)` or
+/// the computed value, with or without the transformer.
+pub trait StyleSource {
+    fn bind_style(self, world: &mut World, target: Entity, name: Tag);
+}
+
+impl<R: Component, S: StyleValue> StyleSource for FromComponent<R, S> {
+    fn bind_style(self, world: &mut World, target: Entity, name: Tag) {
+        (self >> BoundStyle::<S>::spawn(world, target, name)).write(world);
+    }
+}
+
+impl<R: Component, S: BindableSource, T: StyleValue> StyleSource
+    for FromComponentWithTransformer<R, S, T>
+{
+    fn bind_style(self, world: &mut World, target: Entity, name: Tag) {
+        (self >> BoundStyle::<T>::spawn(world, target, name)).write(world);
+    }
+}
+
+impl<R: Resource, S: StyleValue> StyleSource for FromResource<R, S> {
+    fn bind_style(self, world: &mut World, target: Entity, name: Tag) {
+        (self >> BoundStyle::<S>::spawn(world, target, name)).write(world);
+    }
+}
+
+impl<R: Resource, S: BindableSource, T: StyleValue> StyleSource
+    for FromResourceWithTransformer<R, S, T>
+{
+    fn bind_style(self, world: &mut World, target: Entity, name: Tag) {
+        (self >> BoundStyle::<T>::spawn(world, target, name)).write(world);
+    }
+}
+
+impl<Q, F, S> StyleSource for FromComputed<Q, F, S>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    S: StyleValue,
+{
+    fn bind_style(self, world: &mut World, target: Entity, name: Tag) {
+        (self >> BoundStyle::<S>::spawn(world, target, name)).write(world);
+    }
+}
+
+fn update_bound_styles<T: StyleValue>(
+    mut commands: Commands,
+    entities: &Entities,
+    transformer: Res<PropertyTransformer>,
+    extractor: Res<PropertyExtractor>,
+    binds: Query<(Entity, &BoundStyle<T>, ChangeTrackers<BoundStyle<T>>)>,
+    mut elements: Query<&mut Element>,
+) {
+    for (entity, bind, tracker) in binds.iter() {
+        if !entities.contains(bind.target) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if !tracker.is_changed() {
+            continue;
+        }
+        let Ok(mut element) = elements.get_mut(bind.target) else {
+            continue;
+        };
+        let value = bind.value.clone().into();
+        match transform_inline_style(&transformer, &extractor, bind.name, value) {
+            Ok(styles) => element.styles.extend(styles),
+            Err(e) => error!("Ignoring bound style {}: {}", bind.name, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{relations::RelationsPlugin, TransformProperty, *};
+    use bevy::utils::HashMap;
+    use tagstr::*;
+
+    #[derive(Component)]
+    struct Health {
+        current: f32,
+    }
+
+    fn transform(variant: Variant) -> Result<PropertyValue, ElementsError> {
+        match variant {
+            Variant::String(value) => Ok(PropertyValue::new(value)),
+            _ => Err(ElementsError::InvalidPropertyValue(
+                "Expected string".to_string(),
+            )),
+        }
+    }
+
+    #[test]
+    fn bind_style() {
+        let mut transformers: HashMap<Tag, TransformProperty> = Default::default();
+        transformers.insert(tag!("width"), transform);
+        let mut app = App::new();
+        app.add_plugin(RelationsPlugin);
+        app.insert_resource(PropertyTransformer::new(transformers));
+        app.insert_resource(PropertyExtractor::default());
+        let player = app.world.spawn(Health { current: 50. }).id();
+        let bar = app.world.spawn(Element::default()).id();
+        from!(player, Health: current | fmt("{:.0}%")).bind_style(
+            &mut app.world,
+            bar,
+            tag!("width"),
+        );
+        let width = |app: &App| {
+            let styles = &app.world.get::<Element>(bar).unwrap().styles;
+            styles
+                .get(&tag!("width"))
+                .and_then(|w| w.source().map(String::from))
+        };
+
+        app.update();
+        assert_eq!(width(&app), Some("50%".to_string()));
+
+        app.world.get_mut::<Health>(player).unwrap().current = 20.;
+        app.update();
+        assert_eq!(width(&app), Some("20%".to_string()));
+    }
+}
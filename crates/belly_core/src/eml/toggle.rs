@@ -0,0 +1,155 @@
+use std::any::TypeId;
+
+use bevy::{ecs::entity::Entities, prelude::*};
+use tagstr::Tag;
+
+use crate::{
+    relations::{
+        bind::{bind_id, ToComponentWithoutTransformer},
+        RelationsSystems,
+    },
+    Elements,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToggleKind {
+    Class,
+    State,
+}
+
+/// Adds or removes the class (or the state) of the `target` element
+/// following the bound `bool`. This is what the bound `c:` and `state:`
+/// params expand to:
+/// ```rust,ignore
+/// eml! {
+///     <span c:danger=from!(player, |h: &Health| h.current < 20.)
+///           state:disabled=from!(Settings:locked)>
+///         "Health"
+///     </span>
+/// }
+/// ```
+/// The toggle is kept on its own entity, it is despawned with the `target`.
+#[derive(Component)]
+pub struct ClassToggle {
+    pub target: Entity,
+    pub name: Tag,
+    pub kind: ToggleKind,
+    pub value: bool,
+}
+
+impl ClassToggle {
+    /// Spawns the toggle of the `class` of the `target` element and
+    /// returns the property to bind the value to.
+    pub fn class(
+        world: &mut World,
+        target: Entity,
+        class: Tag,
+    ) -> ToComponentWithoutTransformer<ClassToggle, bool> {
+        Self::spawn(world, target, class, ToggleKind::Class)
+    }
+
+    /// Spawns the toggle of the `state` of the `target` element and
+    /// returns the property to bind the value to.
+    pub fn state(
+        world: &mut World,
+        target: Entity,
+        state: Tag,
+    ) -> ToComponentWithoutTransformer<ClassToggle, bool> {
+        Self::spawn(world, target, state, ToggleKind::State)
+    }
+
+    fn spawn(
+        world: &mut World,
+        target: Entity,
+        name: Tag,
+        kind: ToggleKind,
+    ) -> ToComponentWithoutTransformer<ClassToggle, bool> {
+        {
+            let systems = world.get_resource_or_insert_with(RelationsSystems::default);
+            let mut systems = systems.0.write().unwrap();
+            systems.add_custom_system(TypeId::of::<ClassToggle>(), update_toggles);
+        }
+        let toggle = world
+            .spawn(ClassToggle {
+                target,
+                name,
+                kind,
+                value: false,
+            })
+            .id();
+        ToComponentWithoutTransformer {
+            id: bind_id::<ClassToggle>("value"),
+            target: toggle,
+            reader: |c: &Mut<ClassToggle>| &c.value,
+            writer: |c: &mut Mut<ClassToggle>| &mut c.value,
+        }
+    }
+}
+
+fn update_toggles(
+    mut commands: Commands,
+    mut elements: Elements,
+    entities: &Entities,
+    toggles: Query<(Entity, &ClassToggle, ChangeTrackers<ClassToggle>)>,
+) {
+    for (entity, toggle, tracker) in toggles.iter() {
+        if !entities.contains(toggle.target) {
+            commands.entity(entity).despawn();
+        } else if tracker.is_changed() {
+            match toggle.kind {
+                ToggleKind::Class => elements.set_class(toggle.target, toggle.name, toggle.value),
+                ToggleKind::State => elements.set_state(toggle.target, toggle.name, toggle.value),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{relations::RelationsPlugin, *};
+    use tagstr::*;
+
+    #[derive(Component)]
+    struct Health {
+        current: f32,
+    }
+
+    #[test]
+    fn bind_class() {
+        let mut app = App::new();
+        app.add_plugin(RelationsPlugin);
+        let player = app.world.spawn(Health { current: 50. }).id();
+        let label = app
+            .world
+            .spawn((Element::default(), Classes::default()))
+            .id();
+        let target = ClassToggle::class(&mut app.world, label, tag!("danger"));
+        (target << from!(player, |h: &Health| h.current < 20.)).write(&mut app.world);
+        let danger = |app: &App| {
+            app.world
+                .get::<Classes>(label)
+                .unwrap()
+                .contains(&tag!("danger"))
+        };
+
+        app.update();
+        assert!(!danger(&app));
+
+        app.world.get_mut::<Health>(player).unwrap().current = 10.;
+        app.update();
+        assert!(danger(&app), "Class should be added");
+
+        app.world.get_mut::<Health>(player).unwrap().current = 30.;
+        app.update();
+        assert!(!danger(&app), "Class should be removed");
+
+        app.world.despawn(label);
+        app.update();
+        app.update();
+        assert_eq!(
+            app.world.query::<&ClassToggle>().iter(&app.world).count(),
+            0
+        );
+    }
+}
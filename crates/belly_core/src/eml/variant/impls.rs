@@ -1,4 +1,4 @@
-use crate::{eml::Params, ess::ColorFromHexExtension};
+use crate::{eml::Params, ess::ColorFromHexExtension, ElementsBuilder};
 use bevy::prelude::*;
 
 use super::{ApplyCommands, Variant};
@@ -49,6 +49,24 @@ impl From<f32> for Variant {
     }
 }
 
+impl TryFrom<Variant> for i32 {
+    type Error = String;
+    fn try_from(variant: Variant) -> Result<Self, Self::Error> {
+        match variant {
+            Variant::String(s) => s.parse().map_err(|e| format!("Can't parse {e} as i32")),
+            variant => variant
+                .take::<i32>()
+                .ok_or_else(|| format!("Can't cast Variant to i32")),
+        }
+    }
+}
+
+impl From<i32> for Variant {
+    fn from(v: i32) -> Self {
+        Variant::boxed(v)
+    }
+}
+
 impl TryFrom<Variant> for bool {
     type Error = String;
     fn try_from(variant: Variant) -> Result<Self, Self::Error> {
@@ -126,6 +144,12 @@ impl From<Params> for Variant {
     }
 }
 
+impl From<ElementsBuilder> for Variant {
+    fn from(v: ElementsBuilder) -> Self {
+        Variant::Elements(v)
+    }
+}
+
 impl From<JustifyContent> for Variant {
     fn from(value: JustifyContent) -> Self {
         Variant::Boxed(Box::new(value))
@@ -0,0 +1,438 @@
+use std::str::FromStr;
+
+use crate::eml::Variant;
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{
+    ecs::query::QueryItem,
+    math::Rect,
+    prelude::*,
+    render::{Extract, RenderApp, RenderStage},
+    ui::{CalculatedClip, ExtractedUiNode, ExtractedUiNodes, RenderUiSystem, UiStack},
+    utils::HashMap,
+    window::WindowId,
+};
+use tagstr::*;
+
+pub(crate) struct BackgroundPlugin;
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_compound_property::<BackgroundProperty>();
+        app.register_property::<BackgroundImageProperty>();
+        app.register_property::<BackgroundSizeProperty>();
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.add_system_to_stage(
+                RenderStage::Extract,
+                extract_background_images.after(RenderUiSystem::ExtractNode),
+            );
+        }
+    }
+}
+
+/// The texture loaded by the `background-image` property. The texture
+/// is rendered through the [`UiImage`] of the node, the image is tinted
+/// with the [`BackgroundColor`] unless the color is fully transparent.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct BackgroundImage(pub Handle<Image>);
+
+/// Specifies how the [`BackgroundImage`] is fitted into the node.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq)]
+pub enum BackgroundSize {
+    /// The image is stretched to the node size.
+    #[default]
+    Stretch,
+    /// The image keeps its aspect ratio and covers the whole node,
+    /// the image is cropped around its center.
+    Cover,
+    /// The image keeps its aspect ratio and fits into the node,
+    /// the image is centered inside the node.
+    Contain,
+}
+
+impl FromStr for BackgroundSize {
+    type Err = ElementsError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stretch" => Ok(BackgroundSize::Stretch),
+            "cover" => Ok(BackgroundSize::Cover),
+            "contain" => Ok(BackgroundSize::Contain),
+            err => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected cover|contain|stretch, got `{err}`"
+            ))),
+        }
+    }
+}
+
+/// The source of the background image.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BackgroundSource {
+    /// The path of the texture loaded with the [`AssetServer`].
+    Path(String),
+    /// The generated texture of the [`Gradient`].
+    Gradient(Gradient),
+}
+
+impl BackgroundSource {
+    fn parse(tokens: &[StylePropertyToken]) -> Result<Option<BackgroundSource>, ElementsError> {
+        match tokens {
+            [StylePropertyToken::Identifier(ident)] if ident == "none" => Ok(None),
+            [StylePropertyToken::Function(name, args)] if Gradient::is_gradient_function(name) => {
+                Gradient::parse(name, args).map(|g| Some(BackgroundSource::Gradient(g)))
+            }
+            tokens => tokens.url().map(|path| Some(BackgroundSource::Path(path))),
+        }
+    }
+}
+
+/// Applies the `background-image` property:
+/// ```css
+/// background-image: url("ui/panel.png");
+/// background-image: url(ui/panel.png);
+/// background-image: "ui/panel.png";
+/// background-image: linear-gradient(to bottom, #222, #444);
+/// background-image: none;
+/// ```
+/// The texture is loaded with the [`AssetServer`] (or generated for the
+/// [`Gradient`]) and inserted as the [`UiImage`] of the node. Check
+/// [`BackgroundImage`] for details.
+#[derive(Default)]
+pub(crate) struct BackgroundImageProperty;
+
+impl Property for BackgroundImageProperty {
+    type Item = Option<BackgroundSource>;
+    type Components = (
+        Option<&'static mut UiImage>,
+        Option<&'static BackgroundImage>,
+        Option<&'static mut Gradient>,
+    );
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("background-image")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        BackgroundSource::parse(values.tokens())
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        (ui_image, background, gradient): QueryItem<Self::Components>,
+        asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match (cache, ui_image, background, gradient) {
+            (Some(BackgroundSource::Path(path)), ui_image, background, gradient) => {
+                if gradient.is_some() {
+                    commands.entity(entity).remove::<Gradient>();
+                }
+                let image = asset_server.load(path.as_str());
+                if background.map(|b| b.0 != image).unwrap_or(true) {
+                    commands
+                        .entity(entity)
+                        .insert(BackgroundImage(image.clone()));
+                }
+                match ui_image {
+                    Some(mut ui_image) if ui_image.0 != image => ui_image.0 = image,
+                    Some(_) => {}
+                    None => {
+                        commands.entity(entity).insert(UiImage(image));
+                    }
+                }
+            }
+            // the texture is generated by `update_gradient_images`
+            (Some(BackgroundSource::Gradient(gradient)), _, _, Some(mut current)) => {
+                if *current != *gradient {
+                    *current = gradient.clone();
+                }
+            }
+            (Some(BackgroundSource::Gradient(gradient)), _, _, None) => {
+                commands.entity(entity).insert(gradient.clone());
+            }
+            (None, ui_image, Some(background), gradient) => {
+                if gradient.is_some() {
+                    commands.entity(entity).remove::<Gradient>();
+                }
+                commands.entity(entity).remove::<BackgroundImage>();
+                if let Some(mut ui_image) = ui_image.filter(|i| i.0 == background.0) {
+                    *ui_image = UiImage::default();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Applies the `background-size` property:
+/// ```css
+/// background-size: cover;
+/// background-size: contain;
+/// background-size: stretch;
+/// ```
+/// Check [`BackgroundSize`] for details.
+#[derive(Default)]
+pub(crate) struct BackgroundSizeProperty;
+
+impl Property for BackgroundSizeProperty {
+    type Item = BackgroundSize;
+    type Components = Option<&'static mut BackgroundSize>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("background-size")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        match values.identifier() {
+            Some(ident) => ident.parse(),
+            None => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected cover|contain|stretch, got `{}`",
+                values.to_string()
+            ))),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match components {
+            Some(mut size) if *size != *cache => *size = *cache,
+            Some(_) => {}
+            None => {
+                commands.entity(entity).insert(*cache);
+            }
+        }
+    }
+}
+
+/// Extracts the `background` shorthand into the `background-color`,
+/// `background-image` and `background-size`:
+/// ```css
+/// background: linear-gradient(to bottom, #222, #444);
+/// background: #2f2f2f url("ui/panel.png") cover;
+/// background: none;
+/// ```
+/// Omitted parts are reset: the color to `transparent`, the image
+/// to `none` and the size to `stretch`.
+#[derive(Default)]
+pub(crate) struct BackgroundProperty;
+
+impl CompoundProperty for BackgroundProperty {
+    fn name() -> Tag {
+        tag!("background")
+    }
+
+    fn extract(value: Variant) -> Result<HashMap<Tag, PropertyValue>, ElementsError> {
+        let props = match value {
+            Variant::String(unparsed) => StyleProperty::try_from(unparsed)?,
+            Variant::Style(prop) => prop,
+            _ => return Self::error(format!("Can't extract background from variant")),
+        };
+        let mut color = None;
+        let mut source = None;
+        let mut size = None;
+        for token in props.iter() {
+            let token = std::slice::from_ref(token);
+            match token {
+                [StylePropertyToken::Identifier(ident)] if ident == "none" => source = Some(None),
+                [StylePropertyToken::Identifier(ident)]
+                    if ident.parse::<BackgroundSize>().is_ok() =>
+                {
+                    size = ident.parse().ok()
+                }
+                [StylePropertyToken::Identifier(_) | StylePropertyToken::Hash(_)] => {
+                    if color.is_some() {
+                        return Self::error(format!("Background color specified twice"));
+                    }
+                    color = Some(token.color()?);
+                }
+                token => {
+                    if source.is_some() {
+                        return Self::error(format!("Background image specified twice"));
+                    }
+                    source = Some(BackgroundSource::parse(token)?);
+                }
+            }
+        }
+        let mut result = HashMap::default();
+        result.insert(
+            tag!("background-color"),
+            PropertyValue::new(color.unwrap_or(Color::NONE)),
+        );
+        result.insert(
+            tag!("background-image"),
+            PropertyValue::new(source.unwrap_or_default()),
+        );
+        result.insert(
+            tag!("background-size"),
+            PropertyValue::new(size.unwrap_or_default()),
+        );
+        Ok(result)
+    }
+}
+
+/// Returns the region of the image with the `image_size` to draw and the
+/// size of the whole image, both scaled to fit the region into the
+/// `node_size`. The size is applied through the uvs rather than the node
+/// transform: bevy clips the node in the screen pixels and shifts the uvs
+/// by the clipped amount, so the rect has to be in the screen pixels too.
+fn fit_background(size: BackgroundSize, image_size: Vec2, node_size: Vec2) -> (Rect, Vec2) {
+    let full = Rect {
+        min: Vec2::ZERO,
+        max: image_size,
+    };
+    let scale = node_size / image_size;
+    let (region, scale) = match size {
+        BackgroundSize::Stretch => (full, scale),
+        BackgroundSize::Contain => (full, Vec2::splat(scale.min_element())),
+        BackgroundSize::Cover => {
+            let scale = scale.max_element();
+            let visible = node_size / scale;
+            let min = (image_size - visible) * 0.5;
+            let region = Rect {
+                min,
+                max: min + visible,
+            };
+            (region, Vec2::splat(scale))
+        }
+    };
+    let region = Rect {
+        min: region.min * scale,
+        max: region.max * scale,
+    };
+    (region, image_size * scale)
+}
+
+/// Bevy draws the [`UiImage`] stretched to the node and skips the nodes
+/// with transparent background, so the extracted node is fitted here
+/// according to the [`BackgroundSize`] or pushed if it was skipped.
+fn extract_background_images(
+    mut extracted_uinodes: ResMut<ExtractedUiNodes>,
+    images: Extract<Res<Assets<Image>>>,
+    ui_stack: Extract<Res<UiStack>>,
+    windows: Extract<Res<Windows>>,
+    uinode_query: Extract<
+        Query<(
+            &Node,
+            &GlobalTransform,
+            &UiImage,
+            &BackgroundImage,
+            Option<&BackgroundSize>,
+            &BackgroundColor,
+            &ComputedVisibility,
+            Option<&CalculatedClip>,
+        )>,
+    >,
+) {
+    let scale_factor = windows.scale_factor(WindowId::primary()) as f32;
+    for (stack_index, entity) in ui_stack.uinodes.iter().enumerate() {
+        let Ok((uinode, transform, ui_image, background, size, color, visibility, clip)) =
+            uinode_query.get(*entity)
+        else {
+            continue;
+        };
+        if !visibility.is_visible() || ui_image.0 != background.0 {
+            continue;
+        }
+        let Some(image) = images.get(&ui_image.0) else { continue };
+        let image_size = image.size();
+        let node_size = uinode.size();
+        if image_size.min_element() <= 0. || node_size.min_element() <= 0. {
+            continue;
+        }
+        let size = size.copied().unwrap_or_default();
+        let (rect, atlas_size) = fit_background(size, image_size, node_size);
+        if color.0.a() == 0. {
+            extracted_uinodes.uinodes.push(ExtractedUiNode {
+                transform: transform.compute_matrix(),
+                background_color: Color::WHITE,
+                rect,
+                image: ui_image.0.clone_weak(),
+                atlas_size: Some(atlas_size),
+                clip: clip.map(|clip| clip.clip),
+                scale_factor,
+                stack_index,
+            });
+        } else if size != BackgroundSize::Stretch {
+            let extracted = extracted_uinodes
+                .uinodes
+                .iter_mut()
+                .find(|n| n.stack_index == stack_index && n.image == ui_image.0);
+            if let Some(extracted) = extracted {
+                extracted.rect = rect;
+                extracted.atlas_size = Some(atlas_size);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_background_image() {
+        let parse = |value: &str| BackgroundImageProperty::parse(&value.try_into().unwrap());
+        let panel = Ok(Some(BackgroundSource::Path("ui/panel.png".to_string())));
+        assert_eq!(parse("url(\"ui/panel.png\")"), panel);
+        assert_eq!(parse("url(ui/panel.png)"), panel);
+        assert_eq!(parse("\"ui/panel.png\""), panel);
+        assert_eq!(parse("none"), Ok(None));
+        assert!(parse("conic(0.5)").is_err());
+        let gradient = parse("linear-gradient(to bottom, #222, #444)");
+        assert!(matches!(gradient, Ok(Some(BackgroundSource::Gradient(_)))));
+    }
+
+    #[test]
+    fn extract_background_shorthand() {
+        let extract =
+            |value: &str| BackgroundProperty::extract(Variant::String(value.to_string())).unwrap();
+        let props = extract("#2f2f2f url(\"ui/panel.png\") cover");
+        let color = props[&"background-color".as_tag()].downcast_ref::<Color>();
+        assert_eq!(color, Some(&Color::rgb_u8(0x2f, 0x2f, 0x2f)));
+        let size = props[&"background-size".as_tag()].downcast_ref::<BackgroundSize>();
+        assert_eq!(size, Some(&BackgroundSize::Cover));
+
+        let props = extract("linear-gradient(to bottom, #222, #444)");
+        let color = props[&"background-color".as_tag()].downcast_ref::<Color>();
+        assert_eq!(color, Some(&Color::NONE));
+        let image = &props[&"background-image".as_tag()];
+        let image = image.downcast_ref::<Option<BackgroundSource>>().unwrap();
+        assert!(matches!(image, Some(BackgroundSource::Gradient(_))));
+    }
+
+    #[test]
+    fn fit_background_size() {
+        let image = Vec2::new(200., 100.);
+        let node = Vec2::new(100., 100.);
+        let (rect, atlas) = fit_background(BackgroundSize::Cover, image, node);
+        assert_eq!(rect.min, Vec2::new(50., 0.));
+        assert_eq!(rect.max, Vec2::new(150., 100.));
+        assert_eq!(atlas, image);
+        let (rect, atlas) = fit_background(BackgroundSize::Contain, image, node);
+        assert_eq!(rect.min, Vec2::ZERO);
+        assert_eq!(rect.max, Vec2::new(100., 50.));
+        assert_eq!(atlas, Vec2::new(100., 50.));
+        let (rect, atlas) = fit_background(BackgroundSize::Stretch, image, node);
+        assert_eq!(rect.max, node);
+        assert_eq!(atlas, node);
+    }
+
+    #[test]
+    fn clip_scaled_background() {
+        // bevy moves the clipped edge of the rect by the clipped amount of
+        // the screen pixels and divides it by the atlas size to get the uv
+        let image = Vec2::new(200., 100.);
+        let node = Vec2::new(400., 400.);
+        let (rect, atlas) = fit_background(BackgroundSize::Cover, image, node);
+        assert_eq!(rect.size(), node);
+        // the left half of the node is clipped
+        let uv = (rect.min.x + node.x / 2.) / atlas.x;
+        assert_eq!(uv, 0.5);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::eml::Variant;
+use crate::ess::rounded::uniform_border;
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{
+    ecs::query::QueryItem,
+    math::Rect,
+    prelude::*,
+    render::{texture::DEFAULT_IMAGE_HANDLE, Extract, RenderApp, RenderStage},
+    ui::{CalculatedClip, ExtractedUiNode, ExtractedUiNodes, RenderUiSystem, UiStack},
+    utils::HashMap,
+    window::WindowId,
+};
+use tagstr::*;
+
+/// Width of the border when the `border` shorthand has the style
+/// but has no width, the css `medium` value.
+const DEFAULT_BORDER_WIDTH: f32 = 3.;
+
+pub(crate) struct BorderPlugin;
+impl Plugin for BorderPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_compound_property::<BorderProperty>();
+        app.register_property::<BorderColorProperty>();
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.add_system_to_stage(
+                RenderStage::Extract,
+                extract_borders.after(RenderUiSystem::ExtractNode),
+            );
+        }
+    }
+}
+
+/// The color of the node borders. Borders are drawn over the background
+/// of the node with the widths specified by the [`Style::border`].
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct BorderColor(pub Color);
+
+/// Extracts the `border` shorthand into the `border-left`, `border-right`,
+/// `border-top`, `border-bottom` widths and the `border-color`:
+/// ```css
+/// border: 2px solid red;
+/// border: 1px 2px;
+/// border: none;
+/// ```
+/// 1-4 widths are handled the same way as `padding` does. Only `solid`
+/// style is supported, `none` & `hidden` styles reset widths to zero and
+/// the rest of the styles (`dashed`, `dotted`, ...) are rejected.
+#[derive(Default)]
+pub(crate) struct BorderProperty;
+
+/// The css border styles which are not rendered, reported instead of
+/// being parsed as the color.
+const UNSUPPORTED_STYLES: &[&str] = &[
+    "dashed", "dotted", "double", "groove", "ridge", "inset", "outset",
+];
+
+impl CompoundProperty for BorderProperty {
+    fn name() -> Tag {
+        tag!("border")
+    }
+
+    fn extract(value: Variant) -> Result<HashMap<Tag, PropertyValue>, ElementsError> {
+        let props = match value {
+            Variant::String(unparsed) => StyleProperty::try_from(unparsed)?,
+            Variant::Style(prop) => prop,
+            variant => {
+                return match variant.take::<UiRect>() {
+                    Some(rect) => Ok(rect.to_rect_map("border")),
+                    None => Self::error(format!("Can't extract border from variant")),
+                }
+            }
+        };
+        let mut widths = vec![];
+        let mut visible = None;
+        let mut color = None;
+        for token in props.iter() {
+            match token {
+                StylePropertyToken::Identifier(ident) if ident == "solid" => visible = Some(true),
+                StylePropertyToken::Identifier(ident) if ident == "none" || ident == "hidden" => {
+                    visible = Some(false)
+                }
+                StylePropertyToken::Identifier(ident) if UNSUPPORTED_STYLES.contains(&&**ident) => {
+                    return Self::error(format!(
+                        "Unsupported border-style `{ident}`, only `solid` is supported"
+                    ));
+                }
+                StylePropertyToken::Identifier(_) | StylePropertyToken::Hash(_) => {
+                    if color.is_some() {
+                        return Self::error(format!("Border color specified twice"));
+                    }
+                    color = Some(std::slice::from_ref(token).color()?);
+                }
+                token => widths.push(token.clone()),
+            }
+        }
+        let rect = match (visible, widths.is_empty()) {
+            (Some(false), _) => UiRect::all(Val::Px(0.)),
+            (_, false) => widths.as_slice().rect()?,
+            (_, true) => UiRect::all(Val::Px(DEFAULT_BORDER_WIDTH)),
+        };
+        let mut result = rect.to_rect_map("border");
+        if let Some(color) = color {
+            result.insert(tag!("border-color"), PropertyValue::new(color));
+        }
+        Ok(result)
+    }
+}
+
+/// Applies the `border-color` property on [`BorderColor`] component of matched entities.
+#[derive(Default)]
+pub(crate) struct BorderColorProperty;
+
+impl Property for BorderColorProperty {
+    type Item = Color;
+    type Components = Option<&'static mut BorderColor>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("border-color")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        values.color()
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match components {
+            Some(mut border) if border.0 != *cache => border.0 = *cache,
+            Some(_) => {}
+            None => {
+                commands.entity(entity).insert(BorderColor(*cache));
+            }
+        }
+    }
+}
+
+fn extract_borders(
+    mut extracted_uinodes: ResMut<ExtractedUiNodes>,
+    ui_stack: Extract<Res<UiStack>>,
+    windows: Extract<Res<Windows>>,
+    uinode_query: Extract<
+        Query<(
+            &Node,
+            &Style,
+            &GlobalTransform,
+            &BorderColor,
+            Option<&BorderRadius>,
+            &ComputedVisibility,
+            Option<&CalculatedClip>,
+        )>,
+    >,
+) {
+    let scale_factor = windows.scale_factor(WindowId::primary()) as f32;
+    for (stack_index, entity) in ui_stack.uinodes.iter().enumerate() {
+        let Ok((uinode, style, transform, color, radius, visibility, clip)) =
+            uinode_query.get(*entity)
+        else {
+            continue;
+        };
+        if !visibility.is_visible() || color.0.a() == 0. {
+            continue;
+        }
+        // the rounded border is drawn along the corners by the rounded nodes
+        if radius.is_some() && uniform_border(style).is_some() {
+            continue;
+        }
+        let px = |val: Val| match val {
+            Val::Px(px) => px,
+            _ => 0.,
+        };
+        let edges = Edges {
+            left: px(style.border.left),
+            right: px(style.border.right),
+            top: px(style.border.top),
+            bottom: px(style.border.bottom),
+            color: color.0,
+        };
+        extract_edges(
+            &mut extracted_uinodes,
+            transform,
+            uinode.size(),
+            edges,
+            clip.map(|clip| clip.clip),
+            scale_factor,
+            stack_index,
+        );
+    }
+}
+
+/// Widths and color of the edges drawn along the node bounds.
+pub(crate) struct Edges {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+    pub color: Color,
+}
+
+/// Pushes the edges of the node with the `size` as the separate
+/// untextured ui nodes, the edges are drawn inside the node bounds.
+pub(crate) fn extract_edges(
+    extracted_uinodes: &mut ExtractedUiNodes,
+    transform: &GlobalTransform,
+    size: Vec2,
+    edges: Edges,
+    clip: Option<Rect>,
+    scale_factor: f32,
+    stack_index: usize,
+) {
+    let Edges {
+        left,
+        right,
+        top,
+        bottom,
+        color,
+    } = edges;
+    let inner = size.y - top - bottom;
+    // (center relative to the node center, size)
+    let edges = [
+        (Vec2::new(0., (top - size.y) * 0.5), Vec2::new(size.x, top)),
+        (
+            Vec2::new(0., (size.y - bottom) * 0.5),
+            Vec2::new(size.x, bottom),
+        ),
+        (
+            Vec2::new((left - size.x) * 0.5, (top - bottom) * 0.5),
+            Vec2::new(left, inner),
+        ),
+        (
+            Vec2::new((size.x - right) * 0.5, (top - bottom) * 0.5),
+            Vec2::new(right, inner),
+        ),
+    ];
+    let tr = transform.compute_matrix();
+    for (offset, edge) in edges {
+        if edge.x <= 0. || edge.y <= 0. {
+            continue;
+        }
+        extracted_uinodes.uinodes.push(ExtractedUiNode {
+            transform: tr * Mat4::from_translation(offset.extend(0.)),
+            background_color: color,
+            rect: Rect {
+                min: Vec2::ZERO,
+                max: edge,
+            },
+            image: DEFAULT_IMAGE_HANDLE.typed().clone_weak(),
+            atlas_size: None,
+            clip,
+            scale_factor,
+            stack_index,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn extract(value: &str) -> HashMap<Tag, PropertyValue> {
+        BorderProperty::extract(Variant::String(value.to_string())).unwrap()
+    }
+
+    fn get<T: 'static + Copy>(props: &HashMap<Tag, PropertyValue>, name: &str) -> T {
+        *props[&name.as_tag()].downcast_ref::<T>().unwrap()
+    }
+
+    #[test]
+    fn extract_border_shorthand() {
+        let props = extract("2px solid red");
+        assert_eq!(get::<Val>(&props, "border-left"), Val::Px(2.));
+        assert_eq!(get::<Val>(&props, "border-bottom"), Val::Px(2.));
+        assert_eq!(get::<Color>(&props, "border-color"), Color::RED);
+
+        let props = extract("1px 4px");
+        assert_eq!(get::<Val>(&props, "border-top"), Val::Px(1.));
+        assert_eq!(get::<Val>(&props, "border-right"), Val::Px(4.));
+        assert!(!props.contains_key(&"border-color".as_tag()));
+
+        let props = extract("none");
+        assert_eq!(get::<Val>(&props, "border-left"), Val::Px(0.));
+    }
+
+    #[test]
+    fn reject_unsupported_border_style() {
+        for value in ["1px dashed red", "2px dotted", "3px double #fff"] {
+            let result = BorderProperty::extract(Variant::String(value.to_string()));
+            let Err(ElementsError::InvalidPropertyValue(message)) = result else {
+                panic!("Expected unsupported border-style error for `{value}`");
+            };
+            assert!(message.contains("Unsupported border-style"), "{message}");
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use crate::{element::ElementRef, ess::*};
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use std::sync::Arc;
+use tagstr::Tag;
+
+/// Describes the property registered with
+/// [`register_property`](RegisterProperty::register_property).
+#[derive(Clone, Copy, Debug)]
+pub struct PropertyInfo {
+    pub inherited: bool,
+    pub affects_virtual_elements: bool,
+}
+
+/// All the properties registered with
+/// [`register_property`](RegisterProperty::register_property).
+#[derive(Resource, Default, Debug, Deref)]
+pub struct RegisteredProperties(HashMap<Tag, PropertyInfo>);
+
+impl RegisteredProperties {
+    pub(crate) fn insert<T: Property>(&mut self) {
+        self.0.insert(
+            T::name(),
+            PropertyInfo {
+                inherited: T::inherited(),
+                affects_virtual_elements: T::affects_virtual_elements(),
+            },
+        );
+    }
+}
+
+/// Where the computed value comes from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StyleSource {
+    /// The value is specified by the inline style of the element.
+    Inline,
+    /// The value is specified by the rule of the `stylesheet`, the `media`
+    /// is the query of the `@media` rule the rule is nested into.
+    Rule {
+        stylesheet: Handle<StyleSheet>,
+        selector: String,
+        media: Option<Arc<MediaQuery>>,
+    },
+    /// The property is reset to its [`initial`](Property::initial) value
+    /// by the css-wide keyword.
+    Initial,
+    /// The value is managed outside of styles.
+    Managed,
+}
+
+/// The final value of the property resolved for the element.
+#[derive(Debug)]
+pub struct ComputedValue<'a> {
+    /// The specified value, `None` for the [`Initial`](StyleSource::Initial)
+    /// and [`Managed`](StyleSource::Managed) values.
+    pub value: Option<&'a PropertyValue>,
+    pub source: StyleSource,
+    /// The element the value is specified for: the ancestor the value is
+    /// inherited from or the closest non-virtual ancestor of the virtual element.
+    pub owner: Entity,
+}
+
+impl<'a> ComputedValue<'a> {
+    /// Returns the value parsed by the property.
+    pub fn get<T: 'static>(&self) -> Option<&'a T> {
+        self.value.and_then(|v| v.downcast_ref::<T>())
+    }
+
+    /// Returns the text the value was parsed from, if known.
+    pub fn source_text(&self) -> Option<&'a str> {
+        self.value.and_then(|v| v.source())
+    }
+}
+
+/// The values of all the registered properties resolved for the element.
+/// Properties no rule, inline style or inheritance specifies for the element
+/// are not listed.
+#[derive(Debug, Deref)]
+pub struct ComputedStyle<'a>(HashMap<Tag, ComputedValue<'a>>);
+
+/// Resolves the [`ComputedStyle`] of the element the same way the styles
+/// are applied, for tooling and tests:
+/// ```rust,ignore
+/// fn inspect(computed: ComputedStyles, selected: Query<Entity, With<Selected>>) {
+///     for entity in selected.iter() {
+///         let Some(style) = computed.get(entity) else { continue };
+///         if let Some(color) = style.get(&tag!("color")) {
+///             info!("color: {:?} from {:?}", color.source_text(), color.source);
+///         }
+///     }
+/// }
+/// ```
+/// Use the [`SystemState`](bevy::ecs::system::SystemState) to inspect styles
+/// with the exclusive [`World`] access.
+#[derive(SystemParam)]
+pub struct ComputedStyles<'w, 's> {
+    styles: Res<'w, Styles>,
+    stylesheets: Res<'w, Assets<StyleSheet>>,
+    media: Res<'w, Media>,
+    properties: Res<'w, RegisteredProperties>,
+    elements: Query<'w, 's, ElementRef>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+
+impl<'w, 's> ComputedStyles<'w, 's> {
+    /// Returns the computed style of the `entity` or `None` if the
+    /// `entity` is not an element.
+    pub fn get(&self, entity: Entity) -> Option<ComputedStyle> {
+        let rules = active_rules(&self.styles, &self.stylesheets, &self.media, |_| true);
+        compute_style(
+            entity,
+            &rules,
+            &self.properties,
+            &self.elements,
+            &self.parents,
+        )
+    }
+}
+
+fn compute_style<'a>(
+    entity: Entity,
+    rules: &[ActiveRule<'a>],
+    properties: &RegisteredProperties,
+    elements: &'a Query<ElementRef>,
+    parents: &Query<&Parent>,
+) -> Option<ComputedStyle<'a>> {
+    let element = elements.get(entity).ok()?;
+    let mut values = HashMap::default();
+    for (name, info) in properties.iter() {
+        if element.is_virtual() && !info.affects_virtual_elements {
+            continue;
+        }
+        let resolved = resolve_value(*name, info.inherited, entity, rules, elements, parents);
+        let computed = match resolved {
+            Resolved::Value(specified) => ComputedValue {
+                value: Some(specified.value),
+                source: match specified.rule {
+                    Some(rule) => StyleSource::Rule {
+                        stylesheet: rule.stylesheet.clone_weak(),
+                        selector: rule.rule.selector.to_string(),
+                        media: rule.rule.media.clone(),
+                    },
+                    None => StyleSource::Inline,
+                },
+                owner: specified.owner,
+            },
+            Resolved::Initial => ComputedValue {
+                value: None,
+                source: StyleSource::Initial,
+                owner: entity,
+            },
+            Resolved::Managed => ComputedValue {
+                value: None,
+                source: StyleSource::Managed,
+                owner: entity,
+            },
+            Resolved::None => continue,
+        };
+        values.insert(*name, computed);
+    }
+    Some(ComputedStyle(values))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        element::{Classes, Element, ElementTag},
+        eml::Variant,
+        ElementsError, PropertyExtractor, PropertyTransformer, TransformProperty,
+    };
+    use bevy::{asset::HandleId, ecs::system::SystemState};
+    use tagstr::*;
+
+    fn transform(variant: Variant) -> Result<PropertyValue, ElementsError> {
+        match variant {
+            Variant::Style(style) => Ok(PropertyValue::new(style)),
+            _ => Err(ElementsError::InvalidPropertyValue(
+                "Expected style".to_string(),
+            )),
+        }
+    }
+
+    #[test]
+    fn compute_rule_and_inline_values() {
+        let mut transformers: HashMap<Tag, TransformProperty> = Default::default();
+        transformers.insert(tag!("color"), transform);
+        transformers.insert(tag!("width"), transform);
+        let parser = StyleSheetParser::new(
+            PropertyTransformer::new(transformers),
+            PropertyExtractor::default(),
+        );
+        let parsed = parser.parse("div { color: red; width: 10px }");
+        let stylesheet = Handle::<StyleSheet>::weak(HandleId::random::<StyleSheet>());
+        let rules: Vec<_> = parsed
+            .iter()
+            .map(|rule| ActiveRule {
+                rule,
+                stylesheet: &stylesheet,
+                scope: None,
+            })
+            .collect();
+
+        let mut properties = RegisteredProperties::default();
+        let info = |inherited| PropertyInfo {
+            inherited,
+            affects_virtual_elements: false,
+        };
+        properties.0.insert(tag!("color"), info(true));
+        properties.0.insert(tag!("width"), info(false));
+
+        let mut world = World::new();
+        let tag = |name| ElementTag {
+            names: smallvec::smallvec![name],
+            ..default()
+        };
+        let div = world.spawn((Element::default(), tag(tag!("div")))).id();
+        let mut inline = Element::default();
+        let width = StyleProperty::try_from("20px").unwrap();
+        inline
+            .styles
+            .insert(tag!("width"), PropertyValue::new(width).with_source("20px"));
+        let span = world.spawn((inline, tag(tag!("span")))).id();
+        world.entity_mut(div).push_children(&[span]);
+
+        let mut state: SystemState<(Query<ElementRef>, Query<&Parent>)> =
+            SystemState::new(&mut world);
+        let (elements, parents) = state.get(&world);
+        let style = compute_style(span, &rules, &properties, &elements, &parents).unwrap();
+
+        let color = style.get(&tag!("color")).unwrap();
+        assert_eq!(color.owner, div);
+        assert_eq!(color.source_text(), Some("red"));
+        assert_eq!(
+            color.source,
+            StyleSource::Rule {
+                stylesheet: stylesheet.clone(),
+                selector: "div".to_string(),
+                media: None,
+            }
+        );
+        let width = style.get(&tag!("width")).unwrap();
+        assert_eq!(width.owner, span);
+        assert_eq!(width.source, StyleSource::Inline);
+        assert_eq!(width.source_text(), Some("20px"));
+        assert!(width.get::<StyleProperty>().is_some());
+
+        let style = compute_style(div, &rules, &properties, &elements, &parents).unwrap();
+        assert_eq!(
+            style.get(&tag!("width")).unwrap().source_text(),
+            Some("10px")
+        );
+    }
+
+    #[test]
+    fn resolve_all_by_specificity() {
+        let mut transformers: HashMap<Tag, TransformProperty> = Default::default();
+        transformers.insert(tag!("color"), transform);
+        let parser = StyleSheetParser::new(
+            PropertyTransformer::new(transformers),
+            PropertyExtractor::default(),
+        );
+        let parsed = parser.parse(
+            "a { color: red } a.reset { all: initial } \
+             b.reset { color: blue } .reset { all: initial } \
+             c { all: initial; color: green }",
+        );
+        let stylesheet = Handle::<StyleSheet>::weak(HandleId::random::<StyleSheet>());
+        let mut rules: Vec<_> = parsed
+            .iter()
+            .map(|rule| ActiveRule {
+                rule,
+                stylesheet: &stylesheet,
+                scope: None,
+            })
+            .collect();
+        rules.sort_by_key(|r| -r.rule.selector.weight);
+
+        let mut properties = RegisteredProperties::default();
+        properties.0.insert(
+            tag!("color"),
+            PropertyInfo {
+                inherited: false,
+                affects_virtual_elements: false,
+            },
+        );
+
+        let mut world = World::new();
+        let mut spawn = |name| {
+            let tag = ElementTag {
+                names: smallvec::smallvec![name],
+                ..default()
+            };
+            let classes = Classes([tag!("reset")].into_iter().collect());
+            world.spawn((Element::default(), tag, classes)).id()
+        };
+        let a = spawn(tag!("a"));
+        let b = spawn(tag!("b"));
+        let c = spawn(tag!("c"));
+
+        let mut state: SystemState<(Query<ElementRef>, Query<&Parent>)> =
+            SystemState::new(&mut world);
+        let (elements, parents) = state.get(&world);
+        let color = |entity| {
+            let style = compute_style(entity, &rules, &properties, &elements, &parents).unwrap();
+            let color = style.get(&tag!("color")).unwrap();
+            (color.source.clone(), color.source_text())
+        };
+        assert_eq!(color(a), (StyleSource::Initial, None));
+        assert_eq!(color(b).1, Some("blue"));
+        assert_eq!(color(c).1, Some("green"));
+    }
+}
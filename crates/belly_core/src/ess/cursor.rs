@@ -0,0 +1,300 @@
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{
+    ecs::query::QueryItem,
+    prelude::*,
+    ui::{CalculatedClip, FocusPolicy, UiStack},
+    window::CursorIcon,
+};
+use tagstr::*;
+
+pub(crate) struct CursorPlugin;
+impl Plugin for CursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_property::<CursorProperty>();
+        app.add_system(update_cursor);
+    }
+}
+
+/// The cursor shown while the pointer is over the node or its descendants
+/// (unless they have their own cursor). The window cursor is restored
+/// when the pointer leaves the node.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub enum NodeCursor {
+    Icon(CursorIcon),
+    /// Hides the cursor.
+    Hidden,
+    /// Hides the cursor and draws the image instead, the `hotspot` is the
+    /// point of the image placed at the pointer position.
+    Image {
+        image: Handle<Image>,
+        hotspot: Vec2,
+    },
+}
+
+/// The parsed `cursor` value, the image is loaded when the property is applied.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum CursorValue {
+    Icon(CursorIcon),
+    Hidden,
+    Image(String, Vec2),
+}
+
+/// Returns the icon by its css name.
+fn cursor_icon(name: &str) -> Option<CursorIcon> {
+    use CursorIcon::*;
+    Some(match name {
+        "default" => Default,
+        "pointer" => Hand,
+        "text" => Text,
+        "vertical-text" => VerticalText,
+        "crosshair" => Crosshair,
+        "move" => Move,
+        "grab" => Grab,
+        "grabbing" => Grabbing,
+        "all-scroll" => AllScroll,
+        "wait" => Wait,
+        "progress" => Progress,
+        "help" => Help,
+        "not-allowed" => NotAllowed,
+        "no-drop" => NoDrop,
+        "context-menu" => ContextMenu,
+        "cell" => Cell,
+        "alias" => Alias,
+        "copy" => Copy,
+        "zoom-in" => ZoomIn,
+        "zoom-out" => ZoomOut,
+        "e-resize" => EResize,
+        "n-resize" => NResize,
+        "ne-resize" => NeResize,
+        "nw-resize" => NwResize,
+        "s-resize" => SResize,
+        "se-resize" => SeResize,
+        "sw-resize" => SwResize,
+        "w-resize" => WResize,
+        "ew-resize" => EwResize,
+        "ns-resize" => NsResize,
+        "nesw-resize" => NeswResize,
+        "nwse-resize" => NwseResize,
+        "col-resize" => ColResize,
+        "row-resize" => RowResize,
+        _ => return None,
+    })
+}
+
+/// Applies the `cursor` property:
+/// ```css
+/// cursor: pointer;
+/// cursor: none;
+/// cursor: url("cursor.png");
+/// cursor: url("crosshair.png") 16 16;
+/// cursor: auto;
+/// ```
+/// The numbers after the image are the hotspot coordinates in pixels,
+/// `auto` removes the cursor of the node. Check [`NodeCursor`] for details.
+#[derive(Default)]
+pub(crate) struct CursorProperty;
+
+impl Property for CursorProperty {
+    type Item = Option<CursorValue>;
+    type Components = Option<&'static mut NodeCursor>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("cursor")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        let error = || {
+            ElementsError::InvalidPropertyValue(format!(
+                "Expected cursor name, none, auto or url(), got `{}`",
+                values.to_string()
+            ))
+        };
+        let tokens = values.tokens();
+        match tokens {
+            [StylePropertyToken::Identifier(ident)] => match ident.as_str() {
+                "auto" => Ok(None),
+                "none" => Ok(Some(CursorValue::Hidden)),
+                name => cursor_icon(name)
+                    .map(|icon| Some(CursorValue::Icon(icon)))
+                    .ok_or_else(error),
+            },
+            [url, hotspot @ ..] if hotspot.is_empty() || hotspot.len() == 2 => {
+                let path = std::slice::from_ref(url).url()?;
+                let hotspot = match hotspot {
+                    [StylePropertyToken::Number(x), StylePropertyToken::Number(y)] => {
+                        Vec2::new(x.into(), y.into())
+                    }
+                    [] => Vec2::ZERO,
+                    _ => return Err(error()),
+                };
+                Ok(Some(CursorValue::Image(path, hotspot)))
+            }
+            _ => Err(error()),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        let cursor = match cache {
+            None => {
+                if components.is_some() {
+                    commands.entity(entity).remove::<NodeCursor>();
+                }
+                return;
+            }
+            Some(CursorValue::Icon(icon)) => NodeCursor::Icon(*icon),
+            Some(CursorValue::Hidden) => NodeCursor::Hidden,
+            Some(CursorValue::Image(path, hotspot)) => NodeCursor::Image {
+                image: asset_server.load(path),
+                hotspot: *hotspot,
+            },
+        };
+        match components {
+            Some(mut current) if *current != cursor => *current = cursor,
+            Some(_) => {}
+            None => {
+                commands.entity(entity).insert(cursor);
+            }
+        }
+    }
+}
+
+/// The node drawing the image cursor.
+#[derive(Component)]
+struct CursorImage;
+
+#[derive(Default)]
+struct CursorState {
+    current: Option<NodeCursor>,
+    image: Option<Entity>,
+}
+
+fn update_cursor(
+    mut commands: Commands,
+    mut state: Local<CursorState>,
+    mut windows: ResMut<Windows>,
+    ui_stack: Res<UiStack>,
+    nodes: Query<
+        (
+            &Node,
+            &GlobalTransform,
+            Option<&CalculatedClip>,
+            &ComputedVisibility,
+        ),
+        (Without<CursorImage>, Without<NoPointerEvents>),
+    >,
+    cursors: Query<(Option<&NodeCursor>, Option<&Parent>)>,
+    mut images: Query<&mut Style, With<CursorImage>>,
+) {
+    let Some(window) = windows.get_primary_mut() else { return };
+    let position = window.cursor_position().map(|mut pos| {
+        pos.y = window.height() - pos.y;
+        pos
+    });
+    // the topmost node under the pointer
+    let hovered = position.and_then(|pos| {
+        ui_stack.uinodes.iter().rev().copied().find(|entity| {
+            let Ok((node, transform, clip, visibility)) = nodes.get(*entity) else { return false };
+            let center = transform.translation().truncate();
+            let mut min = center - node.size() * 0.5;
+            let mut max = center + node.size() * 0.5;
+            if let Some(clip) = clip {
+                min = min.max(clip.clip.min);
+                max = max.min(clip.clip.max);
+            }
+            visibility.is_visible()
+                && (min.x..max.x).contains(&pos.x)
+                && (min.y..max.y).contains(&pos.y)
+        })
+    });
+    // the cursor is inherited from the ancestors
+    let mut cursor = None;
+    let mut next = hovered;
+    while let Some(entity) = next {
+        let Ok((node_cursor, parent)) = cursors.get(entity) else { break };
+        if let Some(node_cursor) = node_cursor {
+            cursor = Some(node_cursor.clone());
+            break;
+        }
+        next = parent.map(|p| p.get());
+    }
+    if cursor != state.current {
+        match &cursor {
+            None => {
+                window.set_cursor_icon(CursorIcon::Default);
+                window.set_cursor_visibility(true);
+            }
+            Some(NodeCursor::Icon(icon)) => {
+                window.set_cursor_icon(*icon);
+                window.set_cursor_visibility(true);
+            }
+            Some(NodeCursor::Hidden | NodeCursor::Image { .. }) => {
+                window.set_cursor_visibility(false);
+            }
+        }
+        if let Some(image) = state.image.take() {
+            commands.entity(image).despawn_recursive();
+        }
+        if let Some(NodeCursor::Image { image, .. }) = &cursor {
+            let entity = commands
+                .spawn(ImageBundle {
+                    image: UiImage(image.clone()),
+                    focus_policy: FocusPolicy::Pass,
+                    z_index: ZIndex::Global(i32::MAX),
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(CursorImage)
+                .id();
+            state.image = Some(entity);
+        }
+        state.current = cursor;
+    }
+    let (Some(NodeCursor::Image { hotspot, .. }), Some(image), Some(position)) =
+        (&state.current, state.image, position)
+    else {
+        return;
+    };
+    let Ok(mut style) = images.get_mut(image) else { return };
+    let left = Val::Px(position.x - hotspot.x);
+    let top = Val::Px(position.y - hotspot.y);
+    if style.position.left != left || style.position.top != top {
+        style.position.left = left;
+        style.position.top = top;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_cursor() {
+        let parse = |value: &str| CursorProperty::parse(&value.try_into().unwrap());
+        assert_eq!(
+            parse("pointer"),
+            Ok(Some(CursorValue::Icon(CursorIcon::Hand)))
+        );
+        assert_eq!(parse("none"), Ok(Some(CursorValue::Hidden)));
+        assert_eq!(parse("auto"), Ok(None));
+        assert_eq!(
+            parse("url(\"cursor.png\") 4 8"),
+            Ok(Some(CursorValue::Image(
+                "cursor.png".to_string(),
+                Vec2::new(4., 8.)
+            )))
+        );
+        assert!(parse("pointy").is_err());
+        assert!(parse("url(\"cursor.png\") 4").is_err());
+    }
+}
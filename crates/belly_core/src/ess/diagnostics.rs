@@ -0,0 +1,70 @@
+use crate::ess::StyleSheet;
+use bevy::{prelude::*, utils::HashMap};
+use std::fmt::Display;
+
+/// The problem found in the stylesheet: unknown property, invalid value,
+/// invalid selector or unsupported at-rule. The rule or the declaration
+/// with the problem is skipped, the rest of the stylesheet is applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StyleDiagnostic {
+    /// The path of the stylesheet asset, `None` for the stylesheets
+    /// parsed from the code.
+    pub path: Option<String>,
+    /// One-based line of the problem.
+    pub line: u32,
+    /// One-based column of the problem.
+    pub column: u32,
+    pub message: String,
+}
+
+impl Display for StyleDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{path}:")?;
+        }
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Diagnostics of the loaded stylesheets. Entries are replaced when
+/// the stylesheet is reloaded and dropped when it is removed:
+/// ```rust,ignore
+/// fn report(diagnostics: Res<StyleDiagnostics>) {
+///     if diagnostics.is_changed() {
+///         diagnostics.iter().for_each(|d| eprintln!("{d}"));
+///     }
+/// }
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct StyleDiagnostics(HashMap<Handle<StyleSheet>, Vec<StyleDiagnostic>>);
+
+impl StyleDiagnostics {
+    /// Returns the diagnostics of the `stylesheet`.
+    pub fn get(&self, stylesheet: &Handle<StyleSheet>) -> &[StyleDiagnostic] {
+        self.0
+            .get(stylesheet)
+            .map(|d| d.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the diagnostics of all the stylesheets.
+    pub fn iter(&self) -> impl Iterator<Item = &StyleDiagnostic> {
+        self.0.values().flatten()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.values().all(|d| d.is_empty())
+    }
+
+    pub(crate) fn set(&mut self, stylesheet: &Handle<StyleSheet>, diagnostics: &[StyleDiagnostic]) {
+        if diagnostics.is_empty() {
+            self.0.remove(stylesheet);
+        } else {
+            self.0.insert(stylesheet.clone_weak(), diagnostics.to_vec());
+        }
+    }
+
+    pub(crate) fn remove(&mut self, stylesheet: &Handle<StyleSheet>) {
+        self.0.remove(stylesheet);
+    }
+}
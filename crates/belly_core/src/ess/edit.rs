@@ -0,0 +1,103 @@
+use crate::ess::{MediaQuery, StyleSheet};
+use bevy::prelude::*;
+use std::sync::Arc;
+
+pub(crate) struct StyleEditPlugin;
+impl Plugin for StyleEditPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EditStyleRule>();
+        #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+        app.add_system(writeback::write_style_edits);
+    }
+}
+
+/// Sets the `property` of the rule with the `selector` in the `stylesheet`
+/// loaded from the `.ess` file. In dev builds the change is written back
+/// to the source file: the existing declaration is replaced in place, the
+/// new one is appended to the rule, the rest of the file is untouched.
+/// With the asset hot-reloading enabled the updated file is reloaded
+/// and applied right away. The event is ignored in release builds.
+///
+/// The value is validated by the property before the file is written.
+/// The `<inspector>` widget sends the event for the values edited in it.
+pub struct EditStyleRule {
+    pub stylesheet: Handle<StyleSheet>,
+    pub selector: String,
+    /// The query of the `@media` rule the edited rule is nested into,
+    /// `None` for the top-level rule.
+    pub media: Option<Arc<MediaQuery>>,
+    pub property: String,
+    pub value: String,
+}
+
+#[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+mod writeback {
+    use super::EditStyleRule;
+    use crate::{ess::MediaQuery, eml::Variant, PropertyExtractor, PropertyTransformer};
+    use bevy::{asset::FileAssetIo, prelude::*};
+    use cssparser::{Parser, ParserInput};
+    use tagstr::*;
+
+    pub(super) fn write_style_edits(
+        mut events: EventReader<EditStyleRule>,
+        asset_server: Res<AssetServer>,
+        transformer: Res<PropertyTransformer>,
+        extractor: Res<PropertyExtractor>,
+    ) {
+        for edit in events.iter() {
+            if let Err(e) = write_style_edit(edit, &asset_server, &transformer, &extractor) {
+                error!(
+                    "Unable to write `{}: {}` to the `{}` rule: {e}",
+                    edit.property, edit.value, edit.selector
+                );
+            }
+        }
+    }
+
+    fn write_style_edit(
+        edit: &EditStyleRule,
+        asset_server: &AssetServer,
+        transformer: &PropertyTransformer,
+        extractor: &PropertyExtractor,
+    ) -> Result<(), String> {
+        let name = edit.property.as_tag();
+        let value = Variant::String(edit.value.clone());
+        if extractor.is_compound_property(name) {
+            extractor.extract(name, value).map_err(|e| e.to_string())?;
+        } else {
+            transformer
+                .transform(name, value)
+                .map_err(|e| e.to_string())?;
+        }
+        let path = asset_server
+            .get_handle_path(&edit.stylesheet)
+            .ok_or("the stylesheet is not loaded from the file")?;
+        let io = asset_server
+            .asset_io()
+            .downcast_ref::<FileAssetIo>()
+            .ok_or("assets are not loaded from the file system")?;
+        let path = io.root_path().join(path.path());
+        let source = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let source = match &edit.media {
+            Some(media) => belly_syntax::ess::set_media_declaration(
+                &source,
+                |prelude| {
+                    let mut input = ParserInput::new(prelude);
+                    let mut parser = Parser::new(&mut input);
+                    MediaQuery::parse(&mut parser).ok().as_ref() == Some(&**media)
+                },
+                &edit.selector,
+                &edit.property,
+                &edit.value,
+            ),
+            None => belly_syntax::ess::set_declaration(
+                &source,
+                &edit.selector,
+                &edit.property,
+                &edit.value,
+            ),
+        }
+        .ok_or_else(|| format!("there is no such rule in {}", path.display()))?;
+        std::fs::write(&path, source).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,194 @@
+use std::f32::consts::TAU;
+
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{
+    ecs::query::QueryItem,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use tagstr::*;
+
+/// The size of the generated mask texture.
+const MASK_SIZE: u32 = 64;
+
+pub(crate) struct FillPlugin;
+impl Plugin for FillPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_property::<FillProperty>();
+        app.add_system_to_stage(CoreStage::PostUpdate, update_conic_masks);
+    }
+}
+
+/// Limits the visible part of the node background to the sector swept
+/// clockwise from the top of the node, from the `from` to the `to` turn.
+/// Both values are fractions of the full turn, when `from` is greater
+/// than `to` the sector wraps over the top.
+///
+/// The sector is rendered as the generated mask [`UiImage`] tinted
+/// with the [`BackgroundColor`], so the node image is replaced while
+/// the fill is active.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct ConicFill {
+    pub from: f32,
+    pub to: f32,
+}
+
+impl ConicFill {
+    pub fn new(from: f32, to: f32) -> ConicFill {
+        ConicFill { from, to }
+    }
+
+    /// Returns `true` if the `turn` in `0..1` range is inside the sector.
+    pub fn contains(&self, turn: f32) -> bool {
+        if self.from <= self.to {
+            turn >= self.from && turn <= self.to
+        } else {
+            turn >= self.from || turn <= self.to
+        }
+    }
+
+    fn parse(args: &str) -> Result<ConicFill, ElementsError> {
+        let error = || ElementsError::InvalidPropertyValue(format!("Can't parse conic({args})"));
+        let turn = |value: &str| value.trim().parse::<f32>().map_err(|_| error());
+        match args.split_once("..") {
+            Some((from, to)) => Ok(ConicFill::new(turn(from)?, turn(to)?)),
+            None => Ok(ConicFill::new(0., turn(args)?)),
+        }
+    }
+}
+
+/// The mask texture generated for the [`ConicFill`].
+#[derive(Component)]
+struct ConicMask(Handle<Image>);
+
+/// Applies the `fill` property:
+/// ```css
+/// fill: conic(0.0..0.75);
+/// fill: conic(0.5);
+/// fill: none;
+/// ```
+/// `conic(to)` is the same as `conic(0..to)`. Check [`ConicFill`] for details.
+#[derive(Default)]
+pub(crate) struct FillProperty;
+
+impl Property for FillProperty {
+    type Item = Option<ConicFill>;
+    type Components = Option<&'static mut ConicFill>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("fill")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        match values.tokens() {
+            [StylePropertyToken::Identifier(ident)] if ident == "none" => Ok(None),
+            [StylePropertyToken::Function(name, args)] if name == "conic" => {
+                ConicFill::parse(args).map(Some)
+            }
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected conic() or none, got `{}`",
+                values.to_string()
+            ))),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match (cache, components) {
+            (Some(fill), Some(mut current)) if *current != *fill => *current = *fill,
+            (Some(fill), None) => {
+                commands.entity(entity).insert(*fill);
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<ConicFill>();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn update_conic_masks(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    fills: Query<(Entity, &ConicFill, Option<&ConicMask>), Changed<ConicFill>>,
+    mut ui_images: Query<&mut UiImage>,
+    masks: Query<(), With<ConicMask>>,
+    removed: RemovedComponents<ConicFill>,
+) {
+    for entity in removed.iter() {
+        if !masks.contains(entity) {
+            continue;
+        }
+        commands.entity(entity).remove::<ConicMask>();
+        if let Ok(mut image) = ui_images.get_mut(entity) {
+            *image = UiImage::default();
+        }
+    }
+    for (entity, fill, mask) in fills.iter() {
+        if let Some(image) = mask.and_then(|m| images.get_mut(&m.0)) {
+            draw_mask(&mut image.data, fill);
+            continue;
+        }
+        let mut data = vec![0; (MASK_SIZE * MASK_SIZE * 4) as usize];
+        draw_mask(&mut data, fill);
+        let image = images.add(Image::new(
+            Extent3d {
+                width: MASK_SIZE,
+                height: MASK_SIZE,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        ));
+        if let Ok(mut ui_image) = ui_images.get_mut(entity) {
+            ui_image.0 = image.clone();
+        } else {
+            commands.entity(entity).insert(UiImage(image.clone()));
+        }
+        commands.entity(entity).insert(ConicMask(image));
+    }
+}
+
+fn draw_mask(data: &mut [u8], fill: &ConicFill) {
+    let half = MASK_SIZE as f32 * 0.5;
+    for y in 0..MASK_SIZE {
+        for x in 0..MASK_SIZE {
+            let dx = x as f32 + 0.5 - half;
+            let dy = y as f32 + 0.5 - half;
+            // clockwise from the top, y axis of the texture points down
+            let turn = (dx.atan2(-dy) / TAU).rem_euclid(1.);
+            let alpha = if fill.contains(turn) { 255 } else { 0 };
+            let idx = ((y * MASK_SIZE + x) * 4) as usize;
+            data[idx..idx + 4].copy_from_slice(&[255, 255, 255, alpha]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_conic_fill() {
+        let parse = |value: &str| FillProperty::parse(&value.try_into().unwrap());
+        assert_eq!(
+            parse("conic(0.0..0.75)"),
+            Ok(Some(ConicFill::new(0., 0.75)))
+        );
+        assert_eq!(parse("conic(0.5)"), Ok(Some(ConicFill::new(0., 0.5))));
+        assert_eq!(parse("none"), Ok(None));
+        assert!(parse("conic(a..b)").is_err());
+
+        let wrapped = ConicFill::new(0.75, 0.25);
+        assert!(wrapped.contains(0.9) && wrapped.contains(0.1));
+        assert!(!wrapped.contains(0.5));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{ecs::query::QueryItem, prelude::*, utils::HashMap};
+use tagstr::*;
+
+pub(crate) struct FontFamilyPlugin;
+impl Plugin for FontFamilyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FontFamilies>();
+        app.register_property::<FontFamilyProperty>();
+    }
+}
+
+/// The font declared by the `@font-face` rule of the stylesheet.
+#[derive(Clone, Debug)]
+pub struct FontFace {
+    pub family: String,
+    pub font: Handle<Font>,
+}
+
+/// Fonts available for the `font-family` property by their family names.
+/// Stylesheets register their `@font-face` rules here when they are loaded,
+/// families could be registered from the code as well. Names are compared
+/// case-insensitively.
+#[derive(Resource, Default, Debug)]
+pub struct FontFamilies(HashMap<String, Handle<Font>>);
+
+impl FontFamilies {
+    pub fn insert(&mut self, family: &str, font: Handle<Font>) {
+        self.0.insert(family.to_lowercase(), font);
+    }
+
+    pub fn get(&self, family: &str) -> Option<&Handle<Font>> {
+        self.0.get(&family.to_lowercase())
+    }
+
+    /// Returns the font of the first registered family from the `families` list.
+    pub fn resolve<S: AsRef<str>>(&self, families: &[S]) -> Option<&Handle<Font>> {
+        families.iter().find_map(|family| self.get(family.as_ref()))
+    }
+}
+
+/// Parses the comma separated list of family names. Each name is either
+/// the string literal or the sequence of identifiers: `"Title", Open Sans`.
+pub(crate) fn parse_families(values: &StyleProperty) -> Result<Vec<String>, ElementsError> {
+    let error = || {
+        ElementsError::InvalidPropertyValue(format!(
+            "Expected comma separated family names, got `{}`",
+            values.to_string()
+        ))
+    };
+    let mut families = vec![];
+    for name in values.tokens().split(|t| *t == StylePropertyToken::Comma) {
+        let family = match name {
+            [StylePropertyToken::String(family)] => family.clone(),
+            idents if !idents.is_empty() => idents
+                .iter()
+                .map(|token| match token {
+                    StylePropertyToken::Identifier(ident) => Ok(ident.as_str()),
+                    _ => Err(error()),
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" "),
+            _ => return Err(error()),
+        };
+        families.push(family);
+    }
+    if families.is_empty() {
+        return Err(error());
+    }
+    Ok(families)
+}
+
+/// Applies the `font-family` property on [`TextStyle::font`](`TextStyle`) property
+/// of all sections on matched [`Text`] components:
+/// ```css
+/// @font-face {
+///     font-family: "Title";
+///     src: url("fonts/title.ttf");
+/// }
+/// .header {
+///     font-family: "Title", "Fallback";
+/// }
+/// ```
+/// The first family registered in [`FontFamilies`] is used, the font is
+/// left untouched if none of them are registered. The property is inherited.
+#[derive(Default)]
+pub(crate) struct FontFamilyProperty;
+
+impl Property for FontFamilyProperty {
+    type Item = Vec<String>;
+    type Components = &'static Text;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("font-family")
+    }
+
+    fn affects_virtual_elements() -> bool {
+        true
+    }
+
+    fn inherited() -> bool {
+        true
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        parse_families(values)
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        _components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        let families = cache.clone();
+        commands.add(move |world: &mut World| {
+            let Some(font) = world.resource::<FontFamilies>().resolve(&families).cloned() else {
+                return;
+            };
+            let Some(mut text) = world.get_mut::<Text>(entity) else { return };
+            text.sections
+                .iter_mut()
+                .filter(|section| section.style.font != font)
+                .for_each(|section| section.style.font = font.clone());
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::asset::HandleId;
+
+    #[test]
+    fn parse_font_family() {
+        let parse = |value: &str| FontFamilyProperty::parse(&value.try_into().unwrap());
+        assert_eq!(parse("\"Title\""), Ok(vec!["Title".to_string()]));
+        assert_eq!(
+            parse("\"Title\", Open Sans"),
+            Ok(vec!["Title".to_string(), "Open Sans".to_string()])
+        );
+        assert!(parse("\"Title\",").is_err());
+        assert!(parse("12px").is_err());
+    }
+
+    #[test]
+    fn resolve_font_family() {
+        let mut families = FontFamilies::default();
+        let title = Handle::<Font>::weak(HandleId::random::<Font>());
+        families.insert("Title", title.clone());
+        assert_eq!(families.resolve(&["Missing", "title"]), Some(&title));
+        assert_eq!(families.resolve(&["Missing"]), None);
+    }
+}
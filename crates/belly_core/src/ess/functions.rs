@@ -0,0 +1,175 @@
+use crate::{ess::*, Elements, ElementsError, PropertyTransformer};
+use bevy::{ecs::system::SystemState, prelude::*, utils::HashMap};
+use std::sync::{Arc, RwLock};
+use tagstr::*;
+
+pub(crate) struct ValueFunctionsPlugin;
+impl Plugin for ValueFunctionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StyleEnv>();
+        app.register_value_function("env", env);
+        app.add_system(invalidate_env);
+    }
+}
+
+/// Resolves the call of the value function into the tokens replacing the call.
+/// Receives the arguments of the call and the [`World`] to read resources from.
+pub type ValueFunction = fn(&StyleProperty, &World) -> Result<StyleProperty, ElementsError>;
+
+/// Functions registered with
+/// [`register_value_function`](RegisterProperty::register_value_function).
+#[derive(Default, Clone)]
+pub struct ValueFunctions(Arc<RwLock<HashMap<Tag, ValueFunction>>>);
+
+impl ValueFunctions {
+    pub(crate) fn insert(&self, name: Tag, function: ValueFunction) -> Option<ValueFunction> {
+        self.0.write().unwrap().insert(name, function)
+    }
+
+    /// Returns `true` if the `value` calls any of the registered functions.
+    pub fn is_dynamic(&self, value: &StyleProperty) -> bool {
+        let functions = self.0.read().unwrap();
+        value.iter().any(|token| match token {
+            StylePropertyToken::Function(name, _) => functions.contains_key(&name.as_tag()),
+            _ => false,
+        })
+    }
+
+    /// Replaces the calls of the registered functions with their results.
+    pub fn resolve(
+        &self,
+        value: &StyleProperty,
+        world: &World,
+    ) -> Result<StyleProperty, ElementsError> {
+        let functions = self.0.read().unwrap();
+        let mut tokens = vec![];
+        for token in value.iter() {
+            let function = match token {
+                StylePropertyToken::Function(name, args) => {
+                    functions.get(&name.as_tag()).map(|f| (f, args))
+                }
+                _ => None,
+            };
+            match function {
+                Some((function, args)) => {
+                    let args = StyleProperty::try_from(args.as_str())?;
+                    tokens.extend(function(&args, world)?.0);
+                }
+                None => tokens.push(token.clone()),
+            }
+        }
+        Ok(tokens.into_iter().collect())
+    }
+}
+
+/// The value calling the registered value functions. The calls are resolved
+/// and the value is parsed by the property every time it is applied.
+#[derive(Clone, Debug)]
+pub struct DynamicValue(pub StyleProperty);
+
+pub(crate) fn apply_dynamic<T: Property>(
+    value: &DynamicValue,
+    entity: Entity,
+    commands: &mut Commands,
+) {
+    let value = value.0.clone();
+    commands.add(move |world: &mut World| {
+        let functions = world.resource::<PropertyTransformer>().functions().clone();
+        let item = match functions.resolve(&value, world).and_then(|v| T::parse(&v)) {
+            Ok(item) => item,
+            Err(e) => {
+                error!("Unable to apply {} property: {e}", T::name());
+                return;
+            }
+        };
+        let mut state: SystemState<(Query<T::Components, T::Filters>, Res<AssetServer>, Commands)> =
+            SystemState::new(world);
+        let (mut components, asset_server, mut commands) = state.get_mut(world);
+        if let Ok(components) = components.get_mut(entity) {
+            T::apply(&item, components, &asset_server, &mut commands, entity);
+        }
+        state.apply(world);
+    });
+}
+
+/// Values of the `env()` function. The function takes the name of the
+/// value and the optional fallback: `env(safe-area-inset-top, 0px)`.
+/// Elements are restyled when the values are changed:
+/// ```rust,ignore
+/// fn update_safe_area(mut env: ResMut<StyleEnv>) {
+///     env.set("safe-area-inset-top", "24px".try_into().unwrap());
+/// }
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct StyleEnv(HashMap<String, StyleProperty>);
+
+impl StyleEnv {
+    pub fn set(&mut self, name: impl Into<String>, value: StyleProperty) {
+        self.0.insert(name.into(), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&StyleProperty> {
+        self.0.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<StyleProperty> {
+        self.0.remove(name)
+    }
+}
+
+fn env(args: &StyleProperty, world: &World) -> Result<StyleProperty, ElementsError> {
+    let mut args = args.split(|t| *t == StylePropertyToken::Comma);
+    let Some(name) = args.next().and_then(|name| name.identifier()) else {
+        return Err(ElementsError::InvalidPropertyValue(
+            "env() expects the name of the value".to_string(),
+        ));
+    };
+    let value = world
+        .get_resource::<StyleEnv>()
+        .and_then(|env| env.get(name));
+    match (value, args.next()) {
+        (Some(value), _) => Ok(value.clone()),
+        (None, Some(fallback)) => Ok(fallback.iter().cloned().collect()),
+        (None, None) => Err(ElementsError::InvalidPropertyValue(format!(
+            "env() value `{name}` is not set"
+        ))),
+    }
+}
+
+fn invalidate_env(env: Res<StyleEnv>, mut elements: Elements) {
+    if env.is_changed() && !env.is_added() {
+        elements.invalidate_all();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn double(args: &StyleProperty, _: &World) -> Result<StyleProperty, ElementsError> {
+        let value = args.f32()? * 2.;
+        StyleProperty::try_from(format!("{value}px"))
+    }
+
+    #[test]
+    fn resolve_value_functions() {
+        let functions = ValueFunctions::default();
+        functions.insert(tag!("double"), double);
+        functions.insert(tag!("env"), env);
+        let mut world = World::new();
+        let mut style_env = StyleEnv::default();
+        style_env.set("inset", "4px".try_into().unwrap());
+        world.insert_resource(style_env);
+
+        let value = "double(5) env(inset) env(missing, 1px) url(a.png)"
+            .try_into()
+            .unwrap();
+        assert!(functions.is_dynamic(&value));
+        let expected: StyleProperty = "10px 4px 1px url(a.png)".try_into().unwrap();
+        assert_eq!(functions.resolve(&value, &world), Ok(expected));
+        assert!(!functions.is_dynamic(&"url(a.png)".try_into().unwrap()));
+        assert!(functions
+            .resolve(&"env(missing)".try_into().unwrap(), &world)
+            .is_err());
+    }
+}
@@ -0,0 +1,301 @@
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    utils::HashMap,
+};
+
+/// The size of the generated gradient texture.
+const GRADIENT_SIZE: u32 = 256;
+
+pub(crate) struct GradientPlugin;
+impl Plugin for GradientPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PostUpdate, update_gradient_images);
+    }
+}
+
+/// The color at the `position` along the gradient line, from `0.0` to `1.0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorStop {
+    pub color: Color,
+    pub position: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GradientShape {
+    /// The gradient line goes through the center of the node in the
+    /// direction of the angle: `0` points to the top, `90` to the right.
+    Linear(f32),
+    /// The ellipse centered in the node, the last stop reaches the corners.
+    Radial,
+}
+
+/// The gradient used as the background image of the node. The gradient
+/// is rendered into the texture stretched over the node, textures of the
+/// equal gradients are generated once and shared.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct Gradient {
+    pub shape: GradientShape,
+    pub stops: Vec<ColorStop>,
+}
+
+impl Gradient {
+    /// Returns `true` if the function with the `name` produces the gradient.
+    pub fn is_gradient_function(name: &str) -> bool {
+        name == "linear-gradient" || name == "radial-gradient"
+    }
+
+    /// Parses the arguments of the `linear-gradient()` or `radial-gradient()`:
+    /// ```css
+    /// linear-gradient(to bottom, #222, #444)
+    /// linear-gradient(45deg, red 0%, rgba(255, 0, 0, 0) 50%)
+    /// radial-gradient(white, black)
+    /// ```
+    /// Stops without the position are distributed evenly between the
+    /// neighbour stops. Only the default elliptical radial gradient
+    /// is supported.
+    pub fn parse(name: &str, args: &str) -> Result<Gradient, ElementsError> {
+        let error = |msg: &str| {
+            ElementsError::InvalidPropertyValue(format!("Can't parse {name}({args}): {msg}"))
+        };
+        let mut args = split_args(args);
+        let shape = match name {
+            "linear-gradient" => match args.first().and_then(|a| parse_direction(a)) {
+                Some(angle) => {
+                    args.remove(0);
+                    GradientShape::Linear(angle)
+                }
+                None => GradientShape::Linear(180.),
+            },
+            "radial-gradient" => GradientShape::Radial,
+            _ => return Err(error("unknown gradient")),
+        };
+        if args.len() < 2 {
+            return Err(error("expected at least two color stops"));
+        }
+        let mut stops = vec![];
+        let mut positions = vec![];
+        for arg in args {
+            let prop = StyleProperty::try_from(arg)?;
+            let (color, position) = match prop.tokens() {
+                [color] => (color, None),
+                [color, StylePropertyToken::Percentage(p)] => (color, Some(f32::from(p) / 100.)),
+                _ => return Err(error("expected `<color> [<percentage>]` stop")),
+            };
+            let color = std::slice::from_ref(color).color()?;
+            stops.push(ColorStop {
+                color,
+                position: 0.,
+            });
+            positions.push(position);
+        }
+        distribute_positions(&mut positions);
+        for (stop, position) in stops.iter_mut().zip(positions) {
+            stop.position = position.unwrap_or_default();
+        }
+        Ok(Gradient { shape, stops })
+    }
+
+    /// Returns the color at the position `t` along the gradient line.
+    pub fn color_at(&self, t: f32) -> Color {
+        let Some(first) = self.stops.first() else { return Color::NONE };
+        let mut prev = first;
+        if t <= prev.position {
+            return prev.color;
+        }
+        for stop in self.stops.iter().skip(1) {
+            if t <= stop.position {
+                let len = stop.position - prev.position;
+                let k = if len <= 0. {
+                    1.
+                } else {
+                    (t - prev.position) / len
+                };
+                let from = Vec4::from(prev.color.as_rgba_f32());
+                let to = Vec4::from(stop.color.as_rgba_f32());
+                return Color::from(from.lerp(to, k));
+            }
+            prev = stop;
+        }
+        prev.color
+    }
+
+    /// Returns the position along the gradient line of the texture point
+    /// `uv`, the origin is the top left corner.
+    fn project(&self, uv: Vec2) -> f32 {
+        let offset = uv - Vec2::splat(0.5);
+        match self.shape {
+            GradientShape::Linear(angle) => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                // y axis of the texture points down
+                let dir = Vec2::new(sin, -cos);
+                // the gradient line reaches the corners of the square
+                let half_len = (sin.abs() + cos.abs()) * 0.5;
+                offset.dot(dir) / half_len * 0.5 + 0.5
+            }
+            GradientShape::Radial => offset.length() / Vec2::splat(0.5).length(),
+        }
+    }
+
+    fn to_image(&self) -> Image {
+        let mut data = Vec::with_capacity((GRADIENT_SIZE * GRADIENT_SIZE * 4) as usize);
+        for y in 0..GRADIENT_SIZE {
+            for x in 0..GRADIENT_SIZE {
+                let uv = (Vec2::new(x as f32, y as f32) + 0.5) / GRADIENT_SIZE as f32;
+                let color = self.color_at(self.project(uv)).as_rgba_f32();
+                data.extend(color.map(|c| (c.clamp(0., 1.) * 255.).round() as u8));
+            }
+        }
+        Image::new(
+            Extent3d {
+                width: GRADIENT_SIZE,
+                height: GRADIENT_SIZE,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+}
+
+/// Splits the function arguments by commas outside of the nested parentheses.
+fn split_args(args: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, ch) in args.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(args[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    result.push(args[start..].trim());
+    result
+}
+
+/// Parses `to <side-or-corner>` or the angle into degrees.
+fn parse_direction(arg: &str) -> Option<f32> {
+    if let Some(sides) = arg.strip_prefix("to ") {
+        let (mut x, mut y) = (0., 0.);
+        for side in sides.split_whitespace() {
+            match side {
+                "top" => y = -1.,
+                "bottom" => y = 1.,
+                "left" => x = -1.,
+                "right" => x = 1.,
+                _ => return None,
+            }
+        }
+        if x == 0. && y == 0. {
+            return None;
+        }
+        let angle = f32::atan2(x, -y).to_degrees();
+        return Some(angle.rem_euclid(360.));
+    }
+    parse_angle(arg)
+}
+
+/// Parses the angle in `deg`, `grad`, `rad` or `turn` units into degrees.
+pub(crate) fn parse_angle(arg: &str) -> Option<f32> {
+    let units = [
+        ("deg", 1.),
+        ("grad", 0.9),
+        ("rad", 180. / std::f32::consts::PI),
+        ("turn", 360.),
+    ];
+    units.iter().find_map(|(unit, scale)| {
+        let value = arg.strip_suffix(unit)?.trim().parse::<f32>().ok()?;
+        Some(value * scale)
+    })
+}
+
+/// Fills the missing positions: the first and the last stops default to
+/// `0` and `1`, the rest are distributed evenly between the known ones.
+fn distribute_positions(positions: &mut [Option<f32>]) {
+    let last = positions.len() - 1;
+    positions[0].get_or_insert(0.);
+    positions[last].get_or_insert(1.);
+    let mut known = 0;
+    for idx in 1..=last {
+        let Some(position) = positions[idx] else { continue };
+        let from = positions[known].unwrap_or_default();
+        let count = (idx - known) as f32;
+        for missing in known + 1..idx {
+            let k = (missing - known) as f32 / count;
+            positions[missing] = Some(from + (position - from) * k);
+        }
+        known = idx;
+    }
+}
+
+fn update_gradient_images(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut cache: Local<HashMap<String, Handle<Image>>>,
+    mut gradients: Query<(Entity, &Gradient, Option<&mut UiImage>), Changed<Gradient>>,
+) {
+    for (entity, gradient, ui_image) in gradients.iter_mut() {
+        let image = cache
+            .entry(format!("{gradient:?}"))
+            .or_insert_with(|| images.add(gradient.to_image()))
+            .clone();
+        commands
+            .entity(entity)
+            .insert(BackgroundImage(image.clone()));
+        match ui_image {
+            Some(mut ui_image) if ui_image.0 != image => ui_image.0 = image,
+            Some(_) => {}
+            None => {
+                commands.entity(entity).insert(UiImage(image));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_gradients() {
+        let gradient = Gradient::parse("linear-gradient", "to bottom, #222, #444").unwrap();
+        assert_eq!(gradient.shape, GradientShape::Linear(180.));
+        assert_eq!(gradient.stops.len(), 2);
+        assert_eq!(gradient.stops[1].position, 1.);
+
+        let gradient = Gradient::parse("linear-gradient", "90deg, red, rgb(0, 255, 0) 75%, blue");
+        let gradient = gradient.unwrap();
+        assert_eq!(gradient.shape, GradientShape::Linear(90.));
+        assert_eq!(gradient.stops[1].color, Color::rgb(0., 1., 0.));
+        assert_eq!(gradient.stops[1].position, 0.75);
+
+        let gradient = Gradient::parse("linear-gradient", "to top right, red, green, blue");
+        let gradient = gradient.unwrap();
+        assert_eq!(gradient.shape, GradientShape::Linear(45.));
+        assert_eq!(gradient.stops[1].position, 0.5);
+
+        let gradient = Gradient::parse("radial-gradient", "white, black").unwrap();
+        assert_eq!(gradient.shape, GradientShape::Radial);
+        assert!(Gradient::parse("linear-gradient", "to bottom, red").is_err());
+        assert!(Gradient::parse("radial-gradient", "white, 10px").is_err());
+    }
+
+    #[test]
+    fn gradient_colors() {
+        let gradient = Gradient::parse("linear-gradient", "black, white").unwrap();
+        assert_eq!(gradient.color_at(-1.), Color::BLACK);
+        assert_eq!(gradient.color_at(0.5), Color::rgb(0.5, 0.5, 0.5));
+        assert_eq!(gradient.color_at(2.), Color::WHITE);
+        // to bottom: the top row is the first stop, the bottom row is the last one
+        assert!(gradient.project(Vec2::new(0.5, 0.)).abs() < 1e-5);
+        assert!((gradient.project(Vec2::new(0.5, 1.)) - 1.).abs() < 1e-5);
+    }
+}
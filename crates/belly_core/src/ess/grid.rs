@@ -0,0 +1,734 @@
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{ecs::query::QueryItem, prelude::*, ui::UiSystem};
+use tagstr::*;
+
+pub(crate) struct GridPlugin;
+impl Plugin for GridPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_property::<DisplayProperty>();
+        app.register_property::<GridTemplateColumnsProperty>();
+        app.register_property::<GridTemplateRowsProperty>();
+        app.register_property::<GapProperty>();
+        app.register_property::<GridColumnProperty>();
+        app.register_property::<GridRowProperty>();
+        app.add_system_to_stage(CoreStage::PostUpdate, layout_grids.before(UiSystem::Flex));
+    }
+}
+
+/// Marks the `display: grid` node. The flex layout has no grid support,
+/// so the children of the grid are positioned absolutely by the columns
+/// and the rows of the grid:
+/// - the columns share the width of the node by [`GridColumns`],
+/// - the rows are as high as their content unless [`GridRows`] says otherwise,
+/// - the items are placed by [`GridPlacement`] or flow row by row,
+/// - the [`Gap`] separates the tracks.
+///
+/// The row heights are measured after the layout, so the new content
+/// takes its place in the grid the next frame. The min height of the
+/// grid without the height is set to fit the rows.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Grid;
+
+/// The size of the grid column or row.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GridTrack {
+    Px(f32),
+    /// The percentage of the grid size.
+    Percent(f32),
+    /// The share of the space left by the other tracks.
+    Fr(f32),
+    /// The size of the content for rows, the same as `1fr` for columns.
+    Auto,
+}
+
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct GridColumns(pub Vec<GridTrack>);
+
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct GridRows(pub Vec<GridTrack>);
+
+/// The space between the rows (`y`) and the columns (`x`) of the grid.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Gap(pub Vec2);
+
+/// The line the item starts at (counting from `1`) and the number of
+/// the tracks it spans. Items without the start line are placed into
+/// the first free cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridLine {
+    pub start: Option<u16>,
+    pub span: u16,
+}
+
+impl Default for GridLine {
+    fn default() -> Self {
+        GridLine {
+            start: None,
+            span: 1,
+        }
+    }
+}
+
+impl GridLine {
+    /// Parses the `grid-column` or `grid-row` value:
+    /// ```css
+    /// auto
+    /// 2
+    /// span 2
+    /// 1 / 3
+    /// 2 / span 2
+    /// ```
+    fn parse(values: &StyleProperty) -> Result<GridLine, ElementsError> {
+        use StylePropertyToken::*;
+        let error = || {
+            ElementsError::InvalidPropertyValue(format!(
+                "Expected grid line, got `{}`",
+                values.to_string()
+            ))
+        };
+        let line = |line: f32| {
+            if line >= 1. && line.fract() == 0. {
+                Ok(line as u16)
+            } else {
+                Err(error())
+            }
+        };
+        match values.tokens() {
+            [Identifier(auto)] if auto == "auto" => Ok(GridLine::default()),
+            [Number(start)] => Ok(GridLine {
+                start: Some(line(start.into())?),
+                span: 1,
+            }),
+            [Identifier(span), Number(count)] if span == "span" => Ok(GridLine {
+                start: None,
+                span: line(count.into())?,
+            }),
+            [Number(start), Slash, Number(end)] => {
+                let (start, end) = (line(start.into())?, line(end.into())?);
+                if end <= start {
+                    return Err(error());
+                }
+                Ok(GridLine {
+                    start: Some(start),
+                    span: end - start,
+                })
+            }
+            [Number(start), Slash, Identifier(span), Number(count)] if span == "span" => {
+                Ok(GridLine {
+                    start: Some(line(start.into())?),
+                    span: line(count.into())?,
+                })
+            }
+            _ => Err(error()),
+        }
+    }
+}
+
+/// The position of the item in the grid.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct GridPlacement {
+    pub column: GridLine,
+    pub row: GridLine,
+}
+
+/// Marks the child positioned by the grid.
+#[derive(Component)]
+struct GridItem;
+
+/// Parses the track list, `repeat(count, tracks)` is expanded:
+/// ```css
+/// 100px 1fr 2fr
+/// repeat(3, 1fr)
+/// ```
+fn parse_tracks(values: &StyleProperty) -> Result<Vec<GridTrack>, ElementsError> {
+    let error = |value: &str| {
+        ElementsError::InvalidPropertyValue(format!("Expected grid track, got `{value}`"))
+    };
+    let mut tracks = vec![];
+    for token in values.iter() {
+        match token {
+            StylePropertyToken::Function(name, args) if name == "repeat" => {
+                let (count, repeated) = args.split_once(',').ok_or_else(|| error(args))?;
+                let count = count.trim().parse::<usize>().map_err(|_| error(args))?;
+                let repeated = parse_tracks(&StyleProperty::try_from(repeated.trim())?)?;
+                for _ in 0..count {
+                    tracks.extend(repeated.iter().copied());
+                }
+            }
+            StylePropertyToken::Identifier(ident) if ident == "auto" => {
+                tracks.push(GridTrack::Auto)
+            }
+            StylePropertyToken::Fraction(fr) => tracks.push(GridTrack::Fr(f32::from(fr))),
+            StylePropertyToken::Dimension(px) => tracks.push(GridTrack::Px(f32::from(px))),
+            StylePropertyToken::Percentage(percent) => {
+                tracks.push(GridTrack::Percent(f32::from(percent)))
+            }
+            token => return Err(error(&token.to_string())),
+        }
+    }
+    if tracks.is_empty() {
+        return Err(error("nothing"));
+    }
+    Ok(tracks)
+}
+
+/// Resolves the sizes of the `tracks` sharing the `space`. The `content`
+/// sizes are used by `auto` tracks when `auto_fits_content` is set,
+/// otherwise they are treated as `1fr`.
+fn resolve_tracks(
+    tracks: &[GridTrack],
+    content: &[f32],
+    space: Option<f32>,
+    gap: f32,
+    auto_fits_content: bool,
+) -> Vec<f32> {
+    let mut sizes = vec![0.; tracks.len()];
+    let mut fr_total = 0.;
+    let mut used = gap * tracks.len().saturating_sub(1) as f32;
+    for (idx, track) in tracks.iter().enumerate() {
+        let content = content.get(idx).copied().unwrap_or_default();
+        sizes[idx] = match (track, space) {
+            (GridTrack::Px(px), _) => *px,
+            (GridTrack::Percent(percent), Some(space)) => space * percent / 100.,
+            (GridTrack::Auto, _) if auto_fits_content => content,
+            (GridTrack::Fr(fr), Some(_)) => {
+                fr_total += fr;
+                continue;
+            }
+            (GridTrack::Auto, Some(_)) => {
+                fr_total += 1.;
+                continue;
+            }
+            // there is no space to share without the size of the grid
+            _ => content,
+        };
+        used += sizes[idx];
+    }
+    let Some(space) = space else { return sizes };
+    let free = (space - used).max(0.);
+    for (idx, track) in tracks.iter().enumerate() {
+        match track {
+            GridTrack::Fr(fr) if fr_total > 0. => sizes[idx] = free * fr / fr_total,
+            GridTrack::Auto if !auto_fits_content => sizes[idx] = free / fr_total,
+            _ => {}
+        }
+    }
+    sizes
+}
+
+/// Places the items with the `(column, row)` lines into the grid with the
+/// `columns` count. Returns the `(column, row)` of the top left cell
+/// of each item, the rows are added when needed.
+fn place_items(columns: usize, items: &[(GridLine, GridLine)]) -> Vec<(usize, usize)> {
+    let mut occupied: Vec<Vec<bool>> = vec![];
+    let mut result = vec![];
+    let mut cursor = (0, 0);
+    for (column, row) in items {
+        let span = (column.span as usize).clamp(1, columns);
+        let row_span = (row.span as usize).max(1);
+        let fixed_column = column.start.map(|c| (c as usize - 1).min(columns - span));
+        let fixed_row = row.start.map(|r| r as usize - 1);
+        let fits = |occupied: &Vec<Vec<bool>>, c: usize, r: usize| {
+            (r..r + row_span)
+                .all(|r| (c..c + span).all(|c| !occupied.get(r).map(|row| row[c]).unwrap_or(false)))
+        };
+        let cell = match (fixed_column, fixed_row) {
+            (Some(c), Some(r)) => (c, r),
+            (Some(c), None) => (c, (0..).find(|r| fits(&occupied, c, *r)).unwrap()),
+            (None, Some(r)) => (
+                (0..=columns - span)
+                    .find(|c| fits(&occupied, *c, r))
+                    .unwrap_or(0),
+                r,
+            ),
+            (None, None) => {
+                let (mut c, mut r) = cursor;
+                while c + span > columns || !fits(&occupied, c, r) {
+                    c += 1;
+                    if c + span > columns {
+                        c = 0;
+                        r += 1;
+                    }
+                }
+                cursor = (c + span, r);
+                (c, r)
+            }
+        };
+        let (c, r) = cell;
+        if occupied.len() < r + row_span {
+            occupied.resize(r + row_span, vec![false; columns]);
+        }
+        for row in occupied.iter_mut().skip(r).take(row_span) {
+            row[c..c + span].fill(true);
+        }
+        result.push(cell);
+    }
+    result
+}
+
+fn px(val: Val) -> f32 {
+    match val {
+        Val::Px(px) => px,
+        _ => 0.,
+    }
+}
+
+fn layout_grids(
+    mut commands: Commands,
+    grids: Query<
+        (
+            Entity,
+            &Children,
+            Option<&GridColumns>,
+            Option<&GridRows>,
+            Option<&Gap>,
+        ),
+        With<Grid>,
+    >,
+    mut nodes: Query<(&Node, &mut Style, Option<&GridPlacement>, Option<&GridItem>)>,
+    released: Query<(Entity, &Parent), With<GridItem>>,
+) {
+    // the items which are not in the grid anymore
+    for (entity, parent) in released.iter() {
+        if grids.contains(parent.get()) {
+            continue;
+        }
+        let Ok((_, mut style, _, _)) = nodes.get_mut(entity) else { continue };
+        style.position_type = PositionType::Relative;
+        style.position = UiRect::default();
+        style.size.width = Val::Auto;
+        commands.entity(entity).remove::<GridItem>();
+    }
+    for (grid, children, columns, rows, gap) in grids.iter() {
+        let Ok((node, style, _, _)) = nodes.get(grid) else { continue };
+        let size = node.size();
+        let fixed_height = style.size.height != Val::Auto;
+        let (padding, border) = (style.padding, style.border);
+        let gap = gap.map(|g| g.0).unwrap_or_default();
+        let columns = columns
+            .map(|c| c.0.as_slice())
+            .unwrap_or(&[GridTrack::Auto]);
+        let rows = rows.map(|r| r.0.as_slice()).unwrap_or(&[]);
+        let inset = |a: Val, b: Val| px(a) + px(b);
+        let origin = Vec2::new(
+            px(padding.left) + px(border.left),
+            px(padding.top) + px(border.top),
+        );
+        let inner = size
+            - Vec2::new(
+                inset(padding.left, padding.right) + inset(border.left, border.right),
+                inset(padding.top, padding.bottom) + inset(border.top, border.bottom),
+            );
+        let mut entities = vec![];
+        let mut lines = vec![];
+        let mut heights = vec![];
+        for child in children.iter() {
+            let Ok((item_node, item_style, placement, _)) = nodes.get(*child) else { continue };
+            if item_style.display == Display::None {
+                continue;
+            }
+            let placement = placement.copied().unwrap_or_default();
+            entities.push(*child);
+            lines.push((placement.column, placement.row));
+            heights.push(item_node.size().y);
+        }
+        let column_sizes = resolve_tracks(columns, &[], Some(inner.x.max(0.)), gap.x, false);
+        let cells = place_items(column_sizes.len(), &lines);
+        let row_count = cells
+            .iter()
+            .zip(lines.iter())
+            .map(|((_, r), (_, row))| r + row.span as usize)
+            .max()
+            .unwrap_or(0)
+            .max(rows.len());
+        // the implicit rows fit the content
+        let mut row_tracks = rows.to_vec();
+        row_tracks.resize(row_count, GridTrack::Auto);
+        let mut content = vec![0.; row_count];
+        for (idx, (_, r)) in cells.iter().enumerate() {
+            if lines[idx].1.span <= 1 {
+                content[*r] = f32::max(content[*r], heights[idx]);
+            }
+        }
+        let space = fixed_height.then_some(inner.y.max(0.));
+        let row_sizes = resolve_tracks(&row_tracks, &content, space, gap.y, true);
+        let column_offsets = track_offsets(&column_sizes, gap.x);
+        let row_offsets = track_offsets(&row_sizes, gap.y);
+        if !fixed_height {
+            let height = (row_offsets[row_count] - gap.y).max(0.);
+            let min_height = Val::Px(height + size.y - inner.y);
+            if let Ok((_, mut style, _, _)) = nodes.get_mut(grid) {
+                if style.min_size.height != min_height {
+                    style.min_size.height = min_height;
+                }
+            }
+        }
+        for (idx, entity) in entities.iter().enumerate() {
+            let Ok((_, mut style, _, item)) = nodes.get_mut(*entity) else { continue };
+            let (c, r) = cells[idx];
+            let span = (lines[idx].0.span as usize).clamp(1, column_sizes.len());
+            let width = column_offsets[c + span] - column_offsets[c] - gap.x;
+            let left = Val::Px(origin.x + column_offsets[c]);
+            let top = Val::Px(origin.y + row_offsets[r]);
+            if style.position_type != PositionType::Absolute {
+                style.position_type = PositionType::Absolute;
+            }
+            if style.position.left != left || style.position.top != top {
+                style.position.left = left;
+                style.position.top = top;
+            }
+            if style.size.width != Val::Px(width) {
+                style.size.width = Val::Px(width);
+            }
+            if item.is_none() {
+                commands.entity(*entity).insert(GridItem);
+            }
+        }
+    }
+}
+
+/// Returns the offsets of the track starts, each track is followed
+/// by the gap, the last offset is the end of the last gap.
+fn track_offsets(sizes: &[f32], gap: f32) -> Vec<f32> {
+    let mut offset = 0.;
+    let mut offsets = vec![];
+    for size in sizes {
+        offsets.push(offset);
+        offset += size + gap;
+    }
+    offsets.push(offset);
+    offsets
+}
+
+/// Applies the `display` property: `flex`, `grid` or `none`.
+/// The grid is the flex node with the [`Grid`] component.
+#[derive(Default)]
+pub(crate) struct DisplayProperty;
+
+impl Property for DisplayProperty {
+    type Item = Option<Display>;
+    type Components = (&'static mut Style, Option<&'static Grid>);
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("display")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        match values.identifier() {
+            Some("flex") => Ok(Some(Display::Flex)),
+            Some("none") => Ok(Some(Display::None)),
+            Some("grid") => Ok(None),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected flex, grid or none, got `{}`",
+                values.to_string()
+            ))),
+        }
+    }
+
+    fn initial() -> Self::Item {
+        Some(Display::Flex)
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        (mut style, grid): QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        let display = cache.unwrap_or(Display::Flex);
+        if style.display != display {
+            style.display = display;
+        }
+        match (cache, grid) {
+            (None, None) => {
+                commands.entity(entity).insert(Grid);
+            }
+            (Some(_), Some(_)) => {
+                commands.entity(entity).remove::<Grid>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Applies the `grid-template-columns` property:
+/// ```css
+/// grid-template-columns: 1fr 2fr;
+/// grid-template-columns: 64px repeat(3, 1fr);
+/// ```
+/// Check [`Grid`] for details.
+#[derive(Default)]
+pub(crate) struct GridTemplateColumnsProperty;
+
+impl Property for GridTemplateColumnsProperty {
+    type Item = GridColumns;
+    type Components = Option<&'static mut GridColumns>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("grid-template-columns")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        parse_tracks(values).map(GridColumns)
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match components {
+            Some(mut columns) if *columns != *cache => *columns = cache.clone(),
+            None => {
+                commands.entity(entity).insert(cache.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Applies the `grid-template-rows` property:
+/// ```css
+/// grid-template-rows: 48px auto;
+/// grid-template-rows: repeat(2, 1fr);
+/// ```
+/// `fr` rows share the height only when the grid has the height.
+/// Check [`Grid`] for details.
+#[derive(Default)]
+pub(crate) struct GridTemplateRowsProperty;
+
+impl Property for GridTemplateRowsProperty {
+    type Item = GridRows;
+    type Components = Option<&'static mut GridRows>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("grid-template-rows")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        parse_tracks(values).map(GridRows)
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match components {
+            Some(mut rows) if *rows != *cache => *rows = cache.clone(),
+            None => {
+                commands.entity(entity).insert(cache.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Applies the `gap` property:
+/// ```css
+/// gap: 8px;
+/// gap: 8px 16px;
+/// ```
+/// The first value is the gap between the rows, the second one is
+/// the gap between the columns. Check [`Grid`] for details.
+#[derive(Default)]
+pub(crate) struct GapProperty;
+
+impl Property for GapProperty {
+    type Item = Gap;
+    type Components = Option<&'static mut Gap>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("gap")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        let length = |token: &StylePropertyToken| match token {
+            StylePropertyToken::Dimension(v) | StylePropertyToken::Number(v) => Ok(f32::from(v)),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected gap in px, got `{}`",
+                values.to_string()
+            ))),
+        };
+        match values.tokens() {
+            [gap] => Ok(Gap(Vec2::splat(length(gap)?))),
+            [row, column] => Ok(Gap(Vec2::new(length(column)?, length(row)?))),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected 1-2 gaps, got `{}`",
+                values.to_string()
+            ))),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match components {
+            Some(mut gap) if *gap != *cache => *gap = *cache,
+            None => {
+                commands.entity(entity).insert(*cache);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Applies the `grid-column` property:
+/// ```css
+/// grid-column: 2;
+/// grid-column: span 2;
+/// grid-column: 1 / 3;
+/// grid-column: 2 / span 2;
+/// ```
+/// Check [`GridPlacement`] for details.
+#[derive(Default)]
+pub(crate) struct GridColumnProperty;
+
+impl Property for GridColumnProperty {
+    type Item = GridLine;
+    type Components = Option<&'static mut GridPlacement>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("grid-column")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        GridLine::parse(values)
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match components {
+            Some(mut placement) if placement.column != *cache => placement.column = *cache,
+            None => {
+                commands.entity(entity).insert(GridPlacement {
+                    column: *cache,
+                    ..default()
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Applies the `grid-row` property, the same way as the `grid-column`.
+/// Check [`GridPlacement`] for details.
+#[derive(Default)]
+pub(crate) struct GridRowProperty;
+
+impl Property for GridRowProperty {
+    type Item = GridLine;
+    type Components = Option<&'static mut GridPlacement>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("grid-row")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        GridLine::parse(values)
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match components {
+            Some(mut placement) if placement.row != *cache => placement.row = *cache,
+            None => {
+                commands.entity(entity).insert(GridPlacement {
+                    row: *cache,
+                    ..default()
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_grid_properties() {
+        let tracks = |value: &str| parse_tracks(&value.try_into().unwrap());
+        assert_eq!(
+            tracks("64px repeat(2, 1fr) auto"),
+            Ok(vec![
+                GridTrack::Px(64.),
+                GridTrack::Fr(1.),
+                GridTrack::Fr(1.),
+                GridTrack::Auto
+            ])
+        );
+        assert!(tracks("1fr big").is_err());
+
+        let line = |value: &str| GridLine::parse(&value.try_into().unwrap());
+        let expect = |start, span| Ok(GridLine { start, span });
+        assert_eq!(line("2"), expect(Some(2), 1));
+        assert_eq!(line("span 2"), expect(None, 2));
+        assert_eq!(line("1 / 3"), expect(Some(1), 2));
+        assert_eq!(line("2 / span 3"), expect(Some(2), 3));
+        assert!(line("3 / 1").is_err());
+
+        let gap = |value: &str| GapProperty::parse(&value.try_into().unwrap());
+        assert_eq!(gap("8px 4px"), Ok(Gap(Vec2::new(4., 8.))));
+    }
+
+    #[test]
+    fn layout_tracks() {
+        let columns = [GridTrack::Px(100.), GridTrack::Fr(1.), GridTrack::Fr(2.)];
+        let sizes = resolve_tracks(&columns, &[], Some(420.), 10., false);
+        assert_eq!(sizes, vec![100., 100., 200.]);
+        let rows = [GridTrack::Auto, GridTrack::Px(20.)];
+        let sizes = resolve_tracks(&rows, &[35., 0.], None, 0., true);
+        assert_eq!(sizes, vec![35., 20.]);
+    }
+
+    #[test]
+    fn place_grid_items() {
+        let auto = GridLine::default();
+        let span = |span| GridLine { start: None, span };
+        let at = |start| GridLine {
+            start: Some(start),
+            span: 1,
+        };
+        let items = [
+            (span(2), auto),
+            (auto, auto),
+            (auto, auto),
+            (at(3), at(1)),
+            (auto, auto),
+        ];
+        // the span fills the first row up to the third column
+        let cells = place_items(3, &items[..3]);
+        assert_eq!(cells, vec![(0, 0), (2, 0), (0, 1)]);
+        // the fixed item takes the cell first, the rest flows around it
+        let cells = place_items(3, &[items[3], items[0], items[1], items[4]]);
+        assert_eq!(cells, vec![(2, 0), (0, 0), (0, 1), (1, 1)]);
+    }
+}
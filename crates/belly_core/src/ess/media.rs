@@ -0,0 +1,245 @@
+use bevy::prelude::*;
+use cssparser::{ParseError, Parser, Token};
+
+use crate::{Elements, ElementsError};
+
+use super::{StyleSheet, Styles};
+
+/// The state of the primary window media queries are evaluated against.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct Media {
+    /// Logical width of the window.
+    pub width: f32,
+    /// Logical height of the window.
+    pub height: f32,
+    pub scale_factor: f32,
+}
+
+impl Default for Media {
+    fn default() -> Self {
+        Media {
+            width: 1280.0,
+            height: 720.0,
+            scale_factor: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// Single `(name: value)` condition of the media query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MediaFeature {
+    MinWidth(f32),
+    MaxWidth(f32),
+    MinHeight(f32),
+    MaxHeight(f32),
+    /// `min-resolution: 2dppx` compares with the window scale factor.
+    MinResolution(f32),
+    MaxResolution(f32),
+    Orientation(Orientation),
+}
+
+impl MediaFeature {
+    pub fn matches(&self, media: &Media) -> bool {
+        match *self {
+            MediaFeature::MinWidth(width) => media.width >= width,
+            MediaFeature::MaxWidth(width) => media.width <= width,
+            MediaFeature::MinHeight(height) => media.height >= height,
+            MediaFeature::MaxHeight(height) => media.height <= height,
+            MediaFeature::MinResolution(scale) => media.scale_factor >= scale,
+            MediaFeature::MaxResolution(scale) => media.scale_factor <= scale,
+            MediaFeature::Orientation(Orientation::Portrait) => media.height >= media.width,
+            MediaFeature::Orientation(Orientation::Landscape) => media.width > media.height,
+        }
+    }
+}
+
+/// Parsed `@media` prelude: the comma separated list of queries, each
+/// query is the list of features joined with `and`. The list matches
+/// when any of the queries matches, the query matches when all of its
+/// features match:
+/// ```css
+/// @media (max-width: 800px), (orientation: portrait) and (min-resolution: 2dppx) {
+///     .sidebar { display: none; }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaQuery(pub Vec<Vec<MediaFeature>>);
+
+impl MediaQuery {
+    pub fn matches(&self, media: &Media) -> bool {
+        self.0
+            .iter()
+            .any(|query| query.iter().all(|feature| feature.matches(media)))
+    }
+
+    pub(crate) fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<MediaQuery, ParseError<'i, ElementsError>> {
+        let queries = input.parse_comma_separated(parse_query)?;
+        Ok(MediaQuery(queries))
+    }
+}
+
+fn parse_query<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Vec<MediaFeature>, ParseError<'i, ElementsError>> {
+    let mut features = vec![];
+    if let Ok(media_type) = input.try_parse(|i| i.expect_ident_cloned()) {
+        // the window is the only supported media
+        if !media_type.eq_ignore_ascii_case("all") && !media_type.eq_ignore_ascii_case("screen") {
+            return Err(
+                input.new_custom_error(ElementsError::InvalidPropertyValue(format!(
+                    "Unsupported media type {media_type}"
+                ))),
+            );
+        }
+        if input.is_exhausted() {
+            return Ok(features);
+        }
+        input.expect_ident_matching("and")?;
+    }
+    loop {
+        input.expect_parenthesis_block()?;
+        features.push(input.parse_nested_block(parse_feature)?);
+        if input.is_exhausted() {
+            return Ok(features);
+        }
+        input.expect_ident_matching("and")?;
+    }
+}
+
+fn parse_feature<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<MediaFeature, ParseError<'i, ElementsError>> {
+    let name = input.expect_ident()?.to_ascii_lowercase();
+    input.expect_colon()?;
+    let feature = match name.as_str() {
+        "min-width" => MediaFeature::MinWidth(parse_dimension(input, &["px"])?),
+        "max-width" => MediaFeature::MaxWidth(parse_dimension(input, &["px"])?),
+        "min-height" => MediaFeature::MinHeight(parse_dimension(input, &["px"])?),
+        "max-height" => MediaFeature::MaxHeight(parse_dimension(input, &["px"])?),
+        "min-resolution" => MediaFeature::MinResolution(parse_dimension(input, &["dppx", "x"])?),
+        "max-resolution" => MediaFeature::MaxResolution(parse_dimension(input, &["dppx", "x"])?),
+        "orientation" => {
+            let location = input.current_source_location();
+            let orientation = input.expect_ident()?;
+            if orientation.eq_ignore_ascii_case("portrait") {
+                MediaFeature::Orientation(Orientation::Portrait)
+            } else if orientation.eq_ignore_ascii_case("landscape") {
+                MediaFeature::Orientation(Orientation::Landscape)
+            } else {
+                let token = Token::Ident(orientation.clone());
+                return Err(location.new_unexpected_token_error(token));
+            }
+        }
+        _ => {
+            return Err(
+                input.new_custom_error(ElementsError::InvalidPropertyValue(format!(
+                    "Unsupported media feature {name}"
+                ))),
+            )
+        }
+    };
+    input.expect_exhausted()?;
+    Ok(feature)
+}
+
+fn parse_dimension<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    units: &[&str],
+) -> Result<f32, ParseError<'i, ElementsError>> {
+    let location = input.current_source_location();
+    match input.next()? {
+        Token::Dimension { value, unit, .. }
+            if units.iter().any(|u| unit.eq_ignore_ascii_case(u)) =>
+        {
+            Ok(*value)
+        }
+        token => Err(location.new_unexpected_token_error(token.clone())),
+    }
+}
+
+/// Tracks the primary window and restyles all the elements when
+/// any of the loaded media queries changes its result.
+pub(crate) fn update_media_system(
+    windows: Res<Windows>,
+    mut media: ResMut<Media>,
+    styles: Res<Styles>,
+    stylesheets: Res<Assets<StyleSheet>>,
+    mut elements: Elements,
+) {
+    let Some(window) = windows.get_primary() else { return };
+    let current = Media {
+        width: window.width(),
+        height: window.height(),
+        scale_factor: window.scale_factor() as f32,
+    };
+    if *media == current {
+        return;
+    }
+    let previous = *media;
+    *media = current;
+    let changed = styles
+        .iter()
+        .filter_map(|h| stylesheets.get(h))
+        .flat_map(|s| s.iter())
+        .filter_map(|r| r.media.as_ref())
+        .any(|q| q.matches(&previous) != q.matches(&current));
+    if changed {
+        elements.invalidate_all();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cssparser::ParserInput;
+
+    fn parse(source: &str) -> Result<MediaQuery, String> {
+        let mut input = ParserInput::new(source);
+        let mut parser = Parser::new(&mut input);
+        MediaQuery::parse(&mut parser).map_err(|e| format!("{e:?}"))
+    }
+
+    #[test]
+    fn parse_media_queries() {
+        assert_eq!(
+            parse("screen and (max-width: 800px), (orientation: portrait) and (min-resolution: 2dppx)"),
+            Ok(MediaQuery(vec![
+                vec![MediaFeature::MaxWidth(800.0)],
+                vec![
+                    MediaFeature::Orientation(Orientation::Portrait),
+                    MediaFeature::MinResolution(2.0)
+                ]
+            ]))
+        );
+        assert!(parse("(max-width: 10%)").is_err());
+        assert!(parse("(max-width: 10px) and").is_err());
+        assert!(parse("print").is_err());
+    }
+
+    #[test]
+    fn match_media_queries() {
+        let query = parse("(max-width: 800px), (min-resolution: 2x)").unwrap();
+        let deck = Media {
+            width: 1280.0,
+            height: 800.0,
+            scale_factor: 1.0,
+        };
+        assert!(!query.matches(&deck));
+        assert!(query.matches(&Media {
+            width: 640.0,
+            ..deck
+        }));
+        assert!(query.matches(&Media {
+            scale_factor: 2.0,
+            ..deck
+        }));
+    }
+}
@@ -1,25 +1,78 @@
+mod background;
+mod border;
+mod computed;
+mod cursor;
+mod diagnostics;
+mod edit;
+mod fill;
+mod font;
+mod functions;
+mod gradient;
+mod grid;
+mod media;
+mod opacity;
+mod overflow;
 mod parser;
+mod pointer;
 mod property;
+mod rounded;
+mod runtime;
+mod scope;
 mod selector;
+mod strict;
 #[cfg(feature = "stylebox")]
 mod stylebox;
+mod text;
+mod transform;
+mod unmatched;
+mod visibility;
 
 use bevy::{
-    asset::{AssetLoader, LoadedAsset},
+    asset::{AssetLoader, AssetPath, LoadedAsset},
     ecs::system::Command,
     prelude::*,
     reflect::TypeUuid,
-    utils::{hashbrown::hash_map::Keys, HashMap},
+    ui::UiSystem,
+    utils::{hashbrown::hash_map::Keys, HashMap, HashSet},
 };
+pub use background::{BackgroundImage, BackgroundSize, BackgroundSource};
+pub use border::BorderColor;
+pub use computed::{
+    ComputedStyle, ComputedStyles, ComputedValue, PropertyInfo, RegisteredProperties, StyleSource,
+};
+pub use cursor::NodeCursor;
+pub use diagnostics::{StyleDiagnostic, StyleDiagnostics};
+pub use edit::EditStyleRule;
+pub use fill::ConicFill;
+pub use font::{FontFace, FontFamilies};
+pub use functions::{DynamicValue, StyleEnv, ValueFunction, ValueFunctions};
+pub use gradient::{ColorStop, Gradient, GradientShape};
+pub use grid::{Gap, Grid, GridColumns, GridLine, GridPlacement, GridRows, GridTrack};
+pub use media::{Media, MediaFeature, MediaQuery, Orientation};
+pub use opacity::Opacity;
+pub use overflow::{OverflowMode, Scroll, ScrollEvent, ScrollSettings};
+pub use pointer::NoPointerEvents;
 pub use property::*;
+pub use rounded::{BorderRadius, BoxShadow};
+pub(crate) use runtime::transform_inline_style;
+pub use runtime::{
+    AddRuleCommand, RemoveInlineStyleCommand, RemoveRulesCommand, ReplaceStyleSheetCommand,
+    SetInlineStyleCommand, StyleCommandsExtension,
+};
+pub use scope::ScopedStyleSheet;
 pub use selector::*;
 use smallvec::SmallVec;
-use tagstr::Tag;
+pub use strict::{StrictStylesPlugin, Unstyled};
+pub use text::{LetterSpacing, LineHeight, NoWrap};
+pub use transform::{NodeTransform, TransformOrigin};
+pub use unmatched::UnmatchedRulesPlugin;
+pub use visibility::StyleHidden;
+use tagstr::{AsTag, Tag};
 
 use crate::{Defaults, Elements, PropertyExtractor, PropertyTransformer};
 
-pub use self::parser::StyleSheetParser;
-use std::ops::Deref;
+pub use self::parser::{FontFaceRule, ParsedStyleSheet, StyleSheetParser};
+use std::{ops::Deref, path::Path, sync::Arc};
 
 #[derive(Default)]
 pub struct EssPlugin;
@@ -27,6 +80,8 @@ pub struct EssPlugin;
 impl Plugin for EssPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.init_resource::<Styles>();
+        app.init_resource::<Media>();
+        app.init_resource::<StyleDiagnostics>();
         app.add_asset::<StyleSheet>();
         let extractor = app
             .world
@@ -41,12 +96,17 @@ impl Plugin for EssPlugin {
             extractor,
         });
         app.add_system(process_styles_system);
+        app.add_plugin(edit::StyleEditPlugin);
+        app.add_system_to_stage(CoreStage::PreUpdate, media::update_media_system);
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            property::calc::resolve_calc_styles.before(UiSystem::Flex),
+        );
         #[cfg(feature = "stylebox")]
         app.add_plugin(bevy_stylebox::StyleboxPlugin);
         #[cfg(feature = "stylebox")]
         app.add_plugin(stylebox::StyleboxPropertyPlugin);
 
-        app.register_property::<impls::DisplayProperty>();
         app.register_property::<impls::PositionTypeProperty>();
         app.register_property::<impls::DirectionProperty>();
         app.register_property::<impls::FlexDirectionProperty>();
@@ -55,7 +115,6 @@ impl Plugin for EssPlugin {
         app.register_property::<impls::AlignSelfProperty>();
         app.register_property::<impls::AlignContentProperty>();
         app.register_property::<impls::JustifyContentProperty>();
-        app.register_property::<impls::OverflowProperty>();
 
         app.register_property::<impls::WidthProperty>();
         app.register_property::<impls::HeightProperty>();
@@ -66,6 +125,7 @@ impl Plugin for EssPlugin {
         app.register_property::<impls::FlexBasisProperty>();
         app.register_property::<impls::FlexGrowProperty>();
         app.register_property::<impls::FlexShrinkProperty>();
+        app.register_compound_property::<impls::FlexProperty>();
         app.register_property::<impls::AspectRatioProperty>();
 
         app.register_compound_property::<impls::PositionProperty>();
@@ -86,7 +146,22 @@ impl Plugin for EssPlugin {
         app.register_property::<impls::MarginTopProperty>();
         app.register_property::<impls::MarginBottomProperty>();
 
-        app.register_compound_property::<impls::BorderProperty>();
+        app.add_plugin(border::BorderPlugin);
+        app.add_plugin(fill::FillPlugin);
+        app.add_plugin(background::BackgroundPlugin);
+        app.add_plugin(gradient::GradientPlugin);
+        app.add_plugin(rounded::RoundedPlugin);
+        app.add_plugin(opacity::OpacityPlugin);
+        app.add_plugin(transform::NodeTransformPlugin);
+        app.add_plugin(overflow::OverflowPlugin);
+        app.add_plugin(visibility::NodeVisibilityPlugin);
+        app.add_plugin(cursor::CursorPlugin);
+        app.add_plugin(grid::GridPlugin);
+        app.add_plugin(pointer::PointerEventsPlugin);
+        app.add_plugin(font::FontFamilyPlugin);
+        app.add_plugin(text::TextStylePlugin);
+        app.add_plugin(scope::StyleScopePlugin);
+        app.add_plugin(functions::ValueFunctionsPlugin);
         app.register_property::<impls::BorderLeftProperty>();
         app.register_property::<impls::BorderRightProperty>();
         app.register_property::<impls::BorderTopProperty>();
@@ -101,12 +176,17 @@ impl Plugin for EssPlugin {
 
         app.register_property::<impls::BackgroundColorProperty>();
         app.register_property::<impls::ScaleProperty>();
+        app.register_property::<impls::ZIndexProperty>();
     }
 }
 
 pub trait RegisterProperty {
     fn register_property<T: Property + 'static>(&mut self) -> &mut Self;
     fn register_compound_property<T: CompoundProperty + 'static>(&mut self) -> &mut Self;
+    /// Registers the function which could be called in property values:
+    /// `width: safe-width(10px)`. Values calling the registered functions
+    /// are resolved and parsed by the property every time they are applied.
+    fn register_value_function(&mut self, name: &str, function: ValueFunction) -> &mut Self;
 }
 
 impl RegisterProperty for bevy::prelude::App {
@@ -119,6 +199,9 @@ impl RegisterProperty for bevy::prelude::App {
             .entry(T::name())
             .and_modify(|_| panic!("Property `{}` already registered.", T::name()))
             .or_insert(T::transform);
+        self.world
+            .get_resource_or_insert_with(RegisteredProperties::default)
+            .insert::<T>();
         self.add_system(T::apply_defaults /* .label(EcssSystem::Apply) */);
         self
     }
@@ -134,6 +217,18 @@ impl RegisterProperty for bevy::prelude::App {
             .insert(T::extract);
         self
     }
+
+    fn register_value_function(&mut self, name: &str, function: ValueFunction) -> &mut Self {
+        let previous = self
+            .world
+            .get_resource_or_insert_with(PropertyTransformer::default)
+            .functions()
+            .insert(name.as_tag(), function);
+        if previous.is_some() {
+            panic!("Value function `{name}` already registered");
+        }
+        self
+    }
 }
 
 #[derive(Default)]
@@ -155,12 +250,36 @@ impl AssetLoader for EssLoader {
         Box::pin(async move {
             let source = std::str::from_utf8(bytes)?;
             let parser = StyleSheetParser::new(self.validator.clone(), self.extractor.clone());
-            let rules = parser.parse(source);
+            let parsed = parser.parse_stylesheet(source);
             let mut stylesheet = StyleSheet::default();
-            for rule in rules {
+            for rule in parsed.rules {
                 stylesheet.add_rule(rule)
             }
-            load_context.set_default_asset(LoadedAsset::new(stylesheet));
+            let path = load_context.path().to_string_lossy();
+            for mut diagnostic in parsed.diagnostics {
+                diagnostic.path = Some(path.to_string());
+                warn!("Failed to parse stylesheet: {diagnostic}");
+                stylesheet.diagnostics.push(diagnostic);
+            }
+            let mut dependencies = vec![];
+            let base = load_context.path().parent().unwrap_or(Path::new(""));
+            for import in parsed.imports {
+                let path = AssetPath::new(base.join(import), None);
+                stylesheet
+                    .imports
+                    .push(load_context.get_handle(path.clone()));
+                dependencies.push(path);
+            }
+            for font_face in parsed.font_faces {
+                let path = AssetPath::new(base.join(font_face.src), None);
+                stylesheet.font_faces.push(FontFace {
+                    family: font_face.family,
+                    font: load_context.get_handle(path.clone()),
+                });
+                dependencies.push(path);
+            }
+            load_context
+                .set_default_asset(LoadedAsset::new(stylesheet).with_dependencies(dependencies));
             Ok(())
         })
     }
@@ -171,6 +290,9 @@ impl AssetLoader for EssLoader {
 pub struct StyleSheet {
     weight: usize,
     rules: Vec<StyleRule>,
+    imports: Vec<Handle<StyleSheet>>,
+    font_faces: Vec<FontFace>,
+    diagnostics: Vec<StyleDiagnostic>,
 }
 
 unsafe impl Send for StyleSheet {}
@@ -191,8 +313,22 @@ impl Command for ParseCommand {
         let extractor = world.resource::<PropertyExtractor>().clone();
         let validator = world.resource::<PropertyTransformer>().clone();
         let parser = StyleSheetParser::new(validator, extractor);
-        let rules = parser.parse(&self.source);
-        let stylesheet = StyleSheet::new(rules);
+        let parsed = parser.parse_stylesheet(&self.source);
+        let mut stylesheet = StyleSheet::new(parsed.rules);
+        for diagnostic in parsed.diagnostics {
+            warn!("Failed to parse stylesheet: {diagnostic}");
+            stylesheet.diagnostics.push(diagnostic);
+        }
+        let asset_server = world.resource::<AssetServer>();
+        for import in parsed.imports {
+            stylesheet.imports.push(asset_server.load(&import));
+        }
+        for font_face in parsed.font_faces {
+            stylesheet.font_faces.push(FontFace {
+                family: font_face.family,
+                font: asset_server.load(&font_face.src),
+            });
+        }
         let mut styles = world.resource_mut::<Styles>();
         let mut assets = world.resource_mut::<Assets<StyleSheet>>();
         let handle = assets.add(stylesheet);
@@ -273,6 +409,30 @@ impl StyleSheet {
         self.rules.push(rule);
     }
 
+    /// Keeps only the rules the `filter` returns `true` for.
+    pub fn retain_rules<F: FnMut(&StyleRule) -> bool>(&mut self, filter: F) {
+        self.rules.retain(filter);
+        self.rules
+            .iter_mut()
+            .enumerate()
+            .for_each(|(index, rule)| rule.selector.index = SelectorIndex::new(index));
+    }
+
+    /// Stylesheets requested by `@import` rules of this stylesheet.
+    pub fn imports(&self) -> &[Handle<StyleSheet>] {
+        &self.imports
+    }
+
+    /// Fonts declared by `@font-face` rules of this stylesheet.
+    pub fn font_faces(&self) -> &[FontFace] {
+        &self.font_faces
+    }
+
+    /// Problems found while parsing this stylesheet.
+    pub fn diagnostics(&self) -> &[StyleDiagnostic] {
+        &self.diagnostics
+    }
+
     pub(crate) fn extra_weight(&self) -> usize {
         self.weight
     }
@@ -298,16 +458,35 @@ pub struct StyleRule {
     pub selector: Selector,
     // pub properties: HashMap<Tag, StyleProperty>,
     pub properties: HashMap<Tag, PropertyValue>,
+    /// The rule is applied only when the query matches the current [`Media`].
+    pub media: Option<Arc<MediaQuery>>,
+}
+
+impl StyleRule {
+    pub fn is_active(&self, media: &Media) -> bool {
+        self.media
+            .as_ref()
+            .map(|q| q.matches(media))
+            .unwrap_or(true)
+    }
 }
 
 #[derive(Default, Resource)]
 pub struct Styles {
     last_id: usize,
     map: HashMap<Handle<StyleSheet>, usize>,
+    /// Stylesheets removed by [`Styles::replace`], they are not activated
+    /// again when reloaded.
+    removed: HashSet<Handle<StyleSheet>>,
+    /// The stylesheet with the rules added by [`StyleCommandsExtension::add_style_rule`].
+    runtime: Option<Handle<StyleSheet>>,
+    /// Roots of the subtrees the scoped stylesheets are attached to.
+    scopes: HashMap<Handle<StyleSheet>, SmallVec<[Entity; 2]>>,
 }
 
 impl Styles {
     pub fn insert(&mut self, handle: Handle<StyleSheet>) -> usize {
+        self.removed.remove(&handle);
         let default = self.last_id + 1;
         let id = *self.map.entry(handle).or_insert(default);
         if id > self.last_id {
@@ -316,6 +495,54 @@ impl Styles {
         id
     }
 
+    /// Inserts the `handle` right below the `importer`, so rules from
+    /// the importing stylesheet always win over the imported ones.
+    /// Returns `true` if the weights of already inserted stylesheets
+    /// were changed.
+    pub(crate) fn insert_import(
+        &mut self,
+        handle: Handle<StyleSheet>,
+        importer: &Handle<StyleSheet>,
+    ) -> bool {
+        if self.map.contains_key(&handle) {
+            return false;
+        }
+        let Some(&position) = self.map.get(importer) else {
+            self.insert(handle);
+            return false;
+        };
+        self.map
+            .values_mut()
+            .filter(|id| **id >= position)
+            .for_each(|id| *id += 1);
+        self.last_id += 1;
+        self.map.insert(handle, position);
+        true
+    }
+
+    /// Deactivates the `old` stylesheet and activates the `new` one
+    /// with the weight of the `old` one.
+    pub fn replace(&mut self, old: &Handle<StyleSheet>, new: Handle<StyleSheet>) {
+        let Some(id) = self.map.remove(old) else {
+            self.insert(new);
+            return;
+        };
+        self.removed.insert(old.clone());
+        self.removed.remove(&new);
+        self.map.insert(new, id);
+    }
+
+    /// Returns the roots of the subtrees the stylesheet is limited to by
+    /// [`ScopedStyleSheet`], `None` if the stylesheet is applied globally.
+    pub fn scope(&self, handle: &Handle<StyleSheet>) -> Option<&[Entity]> {
+        self.scopes.get(handle).map(|roots| roots.as_slice())
+    }
+
+    /// Returns `true` if the stylesheet was deactivated by [`Styles::replace`].
+    pub fn is_removed(&self, handle: &Handle<StyleSheet>) -> bool {
+        self.removed.contains(handle)
+    }
+
     pub fn iter(&self) -> Keys<Handle<StyleSheet>, usize> {
         self.map.keys()
     }
@@ -330,27 +557,55 @@ fn process_styles_system(
     mut assets: ResMut<Assets<StyleSheet>>,
     mut events: EventReader<AssetEvent<StyleSheet>>,
     mut elements: Elements,
+    mut font_families: ResMut<FontFamilies>,
+    mut diagnostics: ResMut<StyleDiagnostics>,
     defaults: Res<Defaults>,
 ) {
     let mut styles_changed = false;
+    let mut weights_changed = false;
     for event in events.iter() {
         styles_changed = true;
         match event {
-            AssetEvent::Removed { handle: _ } => styles_changed = true,
+            AssetEvent::Removed { handle } => diagnostics.remove(handle),
             AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
-                if handle == &defaults.style_sheet {
-                    if assets.get(handle).unwrap().extra_weight() != 0 {
-                        assets.get_mut(handle).unwrap().set_extra_weight(0);
-                    }
+                let Some(sheet) = assets.get(handle) else {
+                    continue;
+                };
+                diagnostics.set(handle, &sheet.diagnostics);
+                if styles.is_removed(handle) {
+                    continue;
+                }
+                for import in sheet.imports.iter() {
+                    weights_changed |= styles.insert_import(import.clone(), handle);
+                }
+                for font_face in sheet.font_faces.iter() {
+                    font_families.insert(&font_face.family, font_face.font.clone());
+                }
+                let weight = if handle == &defaults.style_sheet {
+                    0
                 } else {
-                    let weight = styles.insert(handle.clone());
-                    if assets.get(handle).unwrap().extra_weight() != weight {
-                        assets.get_mut(handle).unwrap().set_extra_weight(weight);
+                    styles.insert(handle.clone())
+                };
+                if sheet.extra_weight() != weight {
+                    if let Some(sheet) = assets.get_mut(handle) {
+                        sheet.set_extra_weight(weight);
                     }
                 }
             }
         }
     }
+    if weights_changed {
+        for (handle, weight) in styles.map.iter() {
+            if handle == &defaults.style_sheet {
+                continue;
+            }
+            if let Some(sheet) = assets.get(handle) {
+                if sheet.extra_weight() != *weight {
+                    assets.get_mut(handle).unwrap().set_extra_weight(*weight);
+                }
+            }
+        }
+    }
     if styles_changed {
         elements.invalidate_all();
     }
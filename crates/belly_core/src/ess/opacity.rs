@@ -0,0 +1,169 @@
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{Extract, RenderApp, RenderStage},
+    ui::{prepare_uinodes, ExtractedUiNodes, UiStack},
+    utils::HashMap,
+};
+use tagstr::*;
+
+pub(crate) struct OpacityPlugin;
+impl Plugin for OpacityPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_property::<OpacityProperty>();
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<ExtractedOpacity>();
+            render_app.add_system_to_stage(RenderStage::Extract, extract_opacity);
+            // text & custom nodes are extracted by the unlabeled systems,
+            // so the opacity is applied right before the nodes are prepared
+            render_app
+                .add_system_to_stage(RenderStage::Prepare, apply_opacity.before(prepare_uinodes));
+        }
+    }
+}
+
+/// The opacity of the node and all of its descendants, from `0.0` to
+/// `1.0`. Opacities of the nested nodes are multiplied. The opacity is
+/// applied when the node is rendered: the background, the image, the
+/// text and the borders are faded, the colors of the components stay
+/// untouched.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Opacity(pub f32);
+
+impl Default for Opacity {
+    fn default() -> Self {
+        Opacity(1.)
+    }
+}
+
+/// Applies the `opacity` property:
+/// ```css
+/// opacity: 0.5;
+/// opacity: 50%;
+/// ```
+/// Check [`Opacity`] for details.
+#[derive(Default)]
+pub(crate) struct OpacityProperty;
+
+impl Property for OpacityProperty {
+    type Item = f32;
+    type Components = Option<&'static mut Opacity>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("opacity")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        match values.tokens() {
+            [StylePropertyToken::Number(value)] => Ok(f32::from(value).clamp(0., 1.)),
+            [StylePropertyToken::Percentage(value)] => Ok((f32::from(value) / 100.).clamp(0., 1.)),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected number or percentage, got `{}`",
+                values.to_string()
+            ))),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match components {
+            Some(_) if *cache >= 1. => {
+                commands.entity(entity).remove::<Opacity>();
+            }
+            Some(mut opacity) if opacity.0 != *cache => opacity.0 = *cache,
+            None if *cache < 1. => {
+                commands.entity(entity).insert(Opacity(*cache));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The effective opacity of the extracted nodes by their stack index,
+/// empty when there are no translucent nodes.
+#[derive(Resource, Default)]
+struct ExtractedOpacity(Vec<f32>);
+
+/// Returns the product of the opacities of the `entity` and its ancestors.
+fn effective_opacity(
+    entity: Entity,
+    nodes: &Query<(Option<&Opacity>, Option<&Parent>)>,
+    cache: &mut HashMap<Entity, f32>,
+) -> f32 {
+    if let Some(opacity) = cache.get(&entity) {
+        return *opacity;
+    }
+    let Ok((opacity, parent)) = nodes.get(entity) else { return 1. };
+    let inherited = parent
+        .map(|p| effective_opacity(p.get(), nodes, cache))
+        .unwrap_or(1.);
+    let opacity = opacity.map(|o| o.0).unwrap_or(1.) * inherited;
+    cache.insert(entity, opacity);
+    opacity
+}
+
+fn extract_opacity(
+    mut extracted: ResMut<ExtractedOpacity>,
+    ui_stack: Extract<Res<UiStack>>,
+    translucent: Extract<Query<(), With<Opacity>>>,
+    nodes: Extract<Query<(Option<&Opacity>, Option<&Parent>)>>,
+) {
+    extracted.0.clear();
+    if translucent.is_empty() {
+        return;
+    }
+    let mut cache = HashMap::default();
+    for entity in ui_stack.uinodes.iter() {
+        let opacity = effective_opacity(*entity, &nodes, &mut cache);
+        extracted.0.push(opacity);
+    }
+}
+
+fn apply_opacity(opacity: Res<ExtractedOpacity>, mut extracted: ResMut<ExtractedUiNodes>) {
+    if opacity.0.is_empty() {
+        return;
+    }
+    for node in extracted.uinodes.iter_mut() {
+        let Some(opacity) = opacity.0.get(node.stack_index) else { continue };
+        if *opacity < 1. {
+            let alpha = node.background_color.a();
+            node.background_color.set_a(alpha * opacity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::ecs::system::SystemState;
+
+    #[test]
+    fn parse_opacity() {
+        let parse = |value: &str| OpacityProperty::parse(&value.try_into().unwrap());
+        assert_eq!(parse("0.5"), Ok(0.5));
+        assert_eq!(parse("25%"), Ok(0.25));
+        assert_eq!(parse("2"), Ok(1.));
+        assert!(parse("10px").is_err());
+    }
+
+    #[test]
+    fn multiply_opacity() {
+        let mut world = World::new();
+        let child = world.spawn(Opacity(0.5)).id();
+        let middle = world.spawn_empty().push_children(&[child]).id();
+        world.spawn(Opacity(0.5)).push_children(&[middle]);
+        let mut state = SystemState::<Query<(Option<&Opacity>, Option<&Parent>)>>::new(&mut world);
+        let nodes = state.get(&world);
+        let mut cache = HashMap::default();
+        assert_eq!(effective_opacity(child, &nodes, &mut cache), 0.25);
+        assert_eq!(effective_opacity(middle, &nodes, &mut cache), 0.5);
+    }
+}
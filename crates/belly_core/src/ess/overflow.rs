@@ -0,0 +1,337 @@
+use crate::ess::*;
+use crate::relations::Signal;
+use crate::touch::touch_position;
+use crate::ElementsError;
+use bevy::{
+    ecs::query::QueryItem,
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    transform::TransformSystem,
+    ui::{CalculatedClip, UiStack, UiSystem},
+};
+use tagstr::*;
+
+pub(crate) struct OverflowPlugin;
+impl Plugin for OverflowPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_property::<OverflowProperty>();
+        app.init_resource::<ScrollSettings>();
+        app.add_event::<ScrollEvent>();
+        app.add_system(scroll_wheel_system);
+        app.add_system(touch_scroll_system);
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            update_scroll
+                .after(UiSystem::Flex)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// The value of the `overflow` property.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowMode {
+    /// The content is drawn outside of the node.
+    #[default]
+    Visible,
+    /// The content is clipped by the node.
+    Hidden,
+    /// The content is clipped and could be scrolled.
+    Scroll,
+    /// The same as [`OverflowMode::Scroll`], scrollbars should be shown
+    /// only when the content doesn't fit.
+    Auto,
+}
+
+/// The scroll position of the node with the `overflow: scroll` or
+/// `overflow: auto`. The children of the node are moved by the `offset`
+/// after the layout. The `max` offset is the size of the content exceeding
+/// the node, the `offset` is clamped to it. Set the `offset` to scroll
+/// the node from the code or bind to it to show the scrollbar.
+///
+/// Children shrink to fit the node by default, use `flex-shrink: 0`
+/// to let the content overflow.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Scroll {
+    pub offset: Vec2,
+    pub max: Vec2,
+    /// `true` for the `overflow: auto` node.
+    pub auto: bool,
+}
+
+impl Scroll {
+    /// Returns `true` if the content doesn't fit the node.
+    pub fn scrollable(&self) -> bool {
+        self.max.x > 0. || self.max.y > 0.
+    }
+}
+
+/// The scaling of the mouse wheel and the trackpad input.
+#[derive(Resource, Debug, Clone)]
+pub struct ScrollSettings {
+    /// The distance in pixels scrolled by the single line of the mouse wheel.
+    pub line_height: f32,
+    /// The multiplier of the trackpad input measured in pixels.
+    pub pixel_scale: f32,
+}
+
+impl Default for ScrollSettings {
+    fn default() -> Self {
+        ScrollSettings {
+            line_height: 20.,
+            pixel_scale: 1.,
+        }
+    }
+}
+
+/// Sent when the node is scrolled by the mouse wheel or the trackpad.
+pub struct ScrollEvent {
+    pub entities: [Entity; 1],
+    /// The change of the [`Scroll::offset`].
+    pub delta: Vec2,
+    /// The new [`Scroll::offset`].
+    pub offset: Vec2,
+}
+
+impl Signal for ScrollEvent {
+    fn sources(&self) -> &[Entity] {
+        &self.entities
+    }
+}
+
+/// Applies the `overflow` property:
+/// ```css
+/// overflow: visible;
+/// overflow: hidden;
+/// overflow: scroll;
+/// overflow: auto;
+/// ```
+/// All values except `visible` clip the content, `scroll` and `auto`
+/// add the [`Scroll`] component, the content is scrolled by the mouse wheel
+/// and by dragging the touch.
+#[derive(Default)]
+pub(crate) struct OverflowProperty;
+
+impl Property for OverflowProperty {
+    type Item = OverflowMode;
+    type Components = (&'static mut Style, Option<&'static mut Scroll>);
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("overflow")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        match values.identifier() {
+            Some("visible") => Ok(OverflowMode::Visible),
+            Some("hidden") => Ok(OverflowMode::Hidden),
+            Some("scroll") => Ok(OverflowMode::Scroll),
+            Some("auto") => Ok(OverflowMode::Auto),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected visible, hidden, scroll or auto, got `{}`",
+                values.to_string()
+            ))),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        (mut style, scroll): QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        let overflow = match cache {
+            OverflowMode::Visible => Overflow::Visible,
+            _ => Overflow::Hidden,
+        };
+        if style.overflow != overflow {
+            style.overflow = overflow;
+        }
+        let auto = *cache == OverflowMode::Auto;
+        match (cache, scroll) {
+            (OverflowMode::Visible | OverflowMode::Hidden, Some(_)) => {
+                commands.entity(entity).remove::<Scroll>();
+            }
+            (OverflowMode::Scroll | OverflowMode::Auto, None) => {
+                commands.entity(entity).insert(Scroll { auto, ..default() });
+            }
+            (_, Some(mut scroll)) if scroll.auto != auto => scroll.auto = auto,
+            _ => {}
+        }
+    }
+}
+
+/// Returns the bottom right corner of the children of the node relative
+/// to the top left corner of the node.
+fn content_extent(node_size: Vec2, children: &[(Vec3, Vec2)]) -> Vec2 {
+    children
+        .iter()
+        .map(|(translation, size)| translation.truncate() + *size * 0.5 + node_size * 0.5)
+        .fold(Vec2::ZERO, Vec2::max)
+}
+
+fn update_scroll(
+    mut nodes: Query<(&Node, &Style, &mut Scroll, Option<&Children>)>,
+    mut children: Query<(&Node, &mut Transform)>,
+) {
+    for (node, style, mut scroll, node_children) in nodes.iter_mut() {
+        let Some(node_children) = node_children else { continue };
+        let extents: Vec<_> = node_children
+            .iter()
+            .filter_map(|child| children.get(*child).ok())
+            .map(|(child, transform)| (transform.translation, child.size()))
+            .collect();
+        let padding = |val: Val| match val {
+            Val::Px(px) => px,
+            _ => 0.,
+        };
+        let padding = Vec2::new(padding(style.padding.right), padding(style.padding.bottom));
+        let content = content_extent(node.size(), &extents) + padding;
+        let max = (content - node.size()).max(Vec2::ZERO);
+        let offset = scroll.offset.clamp(Vec2::ZERO, max);
+        if scroll.max != max || scroll.offset != offset {
+            scroll.max = max;
+            scroll.offset = offset;
+        }
+        // the flex layout writes the translation of the moved nodes
+        // each frame, so the offset is added after the layout every frame
+        if offset == Vec2::ZERO {
+            continue;
+        }
+        for child in node_children.iter() {
+            let Ok((_, mut transform)) = children.get_mut(*child) else { continue };
+            transform.translation -= offset.extend(0.);
+        }
+    }
+}
+
+type ScrollNodes<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Node,
+        &'static GlobalTransform,
+        &'static mut Scroll,
+        Option<&'static CalculatedClip>,
+        &'static ComputedVisibility,
+    ),
+    Without<NoPointerEvents>,
+>;
+
+/// Scrolls the topmost scrollable node under the cursor.
+fn scroll_wheel_system(
+    mut wheel: EventReader<MouseWheel>,
+    settings: Res<ScrollSettings>,
+    mut events: EventWriter<ScrollEvent>,
+    windows: Res<Windows>,
+    keys: Res<Input<KeyCode>>,
+    ui_stack: Res<UiStack>,
+    mut nodes: ScrollNodes,
+) {
+    let mut delta = Vec2::ZERO;
+    for event in wheel.iter() {
+        let scale = match event.unit {
+            MouseScrollUnit::Line => settings.line_height,
+            MouseScrollUnit::Pixel => settings.pixel_scale,
+        };
+        delta += Vec2::new(event.x, event.y) * scale;
+    }
+    if delta == Vec2::ZERO {
+        return;
+    }
+    // the wheel scrolls horizontally while shift is pressed
+    if delta.x == 0. && (keys.pressed(KeyCode::LShift) || keys.pressed(KeyCode::RShift)) {
+        delta = Vec2::new(delta.y, 0.);
+    }
+    let Some(window) = windows.get_primary() else { return };
+    let Some(mut cursor) = window.cursor_position() else { return };
+    cursor.y = window.height() - cursor.y;
+    scroll_at(cursor, delta, &ui_stack, &mut nodes, &mut events);
+}
+
+/// Scrolls the topmost scrollable node under the single touch dragged
+/// over the screen, the content follows the finger.
+fn touch_scroll_system(
+    touches: Res<Touches>,
+    windows: Res<Windows>,
+    ui_stack: Res<UiStack>,
+    mut nodes: ScrollNodes,
+    mut events: EventWriter<ScrollEvent>,
+) {
+    let mut pressed = touches.iter();
+    let (Some(touch), None) = (pressed.next(), pressed.next()) else {
+        return;
+    };
+    // the touch position grows upward, the UI coordinates grow downward
+    let delta = touch.delta() * Vec2::new(1., -1.);
+    if delta == Vec2::ZERO {
+        return;
+    }
+    let position = touch_position(&windows, touch.position());
+    scroll_at(position, delta, &ui_stack, &mut nodes, &mut events);
+}
+
+/// Scrolls the topmost node under the `point` which could be scrolled
+/// by the `delta`. The node scrolled to the edge passes the input to the
+/// scrollable ancestor.
+fn scroll_at(
+    point: Vec2,
+    delta: Vec2,
+    ui_stack: &UiStack,
+    nodes: &mut ScrollNodes,
+    events: &mut EventWriter<ScrollEvent>,
+) {
+    for entity in ui_stack.uinodes.iter().rev() {
+        let Ok((node, transform, mut scroll, clip, visibility)) = nodes.get_mut(*entity) else {
+            continue;
+        };
+        if !visibility.is_visible() {
+            continue;
+        }
+        let position = transform.translation().truncate();
+        let mut min = position - node.size() * 0.5;
+        let mut max = position + node.size() * 0.5;
+        if let Some(clip) = clip {
+            min = min.max(clip.clip.min);
+            max = max.min(clip.clip.max);
+        }
+        let hovered = (min.x..max.x).contains(&point.x) && (min.y..max.y).contains(&point.y);
+        // the wheel up scrolls the content up, so the offset decreases
+        let offset = (scroll.offset - delta).clamp(Vec2::ZERO, scroll.max);
+        if !hovered || offset == scroll.offset {
+            continue;
+        }
+        events.send(ScrollEvent {
+            entities: [*entity],
+            delta: offset - scroll.offset,
+            offset,
+        });
+        scroll.offset = offset;
+        return;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_overflow() {
+        let parse = |value: &str| OverflowProperty::parse(&value.try_into().unwrap());
+        assert_eq!(parse("hidden"), Ok(OverflowMode::Hidden));
+        assert_eq!(parse("auto"), Ok(OverflowMode::Auto));
+        assert!(parse("clip").is_err());
+    }
+
+    #[test]
+    fn measure_content() {
+        let node = Vec2::new(100., 50.);
+        // two children stacked in the column, both of them 40px height
+        let children = [
+            (Vec3::new(0., -5., 0.), Vec2::new(100., 40.)),
+            (Vec3::new(0., 35., 0.), Vec2::new(100., 40.)),
+        ];
+        assert_eq!(content_extent(node, &children), Vec2::new(100., 80.));
+    }
+}
@@ -1,11 +1,19 @@
-use bevy::prelude::{error, warn};
+use bevy::{prelude::warn, utils::HashMap};
 use smallvec::{smallvec, SmallVec};
+use std::sync::Arc;
 
 use cssparser::*;
 use tagstr::{AsTag, Tag};
 
+use belly_syntax::{
+    ess::{parse_declarations_in, parse_items_in, EssAtRule, EssDeclaration, EssItem, EssRule},
+    LineIndex, Span,
+};
+
 use crate::{
-    eml::Variant, ess::Selector, ess::SelectorElement, ess::StyleProperty, ess::StyleRule,
+    eml::Variant, ess::font::parse_families, ess::property::calc, ess::property::colors,
+    ess::MediaQuery, ess::PropertyValue, ess::Selector, ess::SelectorElement, ess::StyleDiagnostic,
+    ess::StyleProperty, ess::StylePropertyMethods, ess::StylePropertyToken, ess::StyleRule,
     ElementsError, PropertyExtractor, PropertyTransformer,
 };
 
@@ -22,27 +30,233 @@ impl StyleSheetParser {
         }
     }
     pub fn parse(&self, content: &str) -> SmallVec<[StyleRule; 8]> {
-        let mut input = ParserInput::new(content);
-        let mut parser = Parser::new(&mut input);
-        RuleListParser::new_for_stylesheet(&mut parser, self)
-            .into_iter()
-            .filter_map(|result| match result {
-                Ok(rule) => Some(rule),
-                Err((err, rule)) => {
-                    error!(
-                        "Failed to parse rule: {}. Error: {}",
-                        rule,
+        self.parse_with_imports(content).0
+    }
+
+    /// Parses the content like [`StyleSheetParser::parse`] does, but also
+    /// returns the list of paths requested by `@import "path";` rules
+    /// in order of appearance.
+    pub fn parse_with_imports(&self, content: &str) -> (SmallVec<[StyleRule; 8]>, Vec<String>) {
+        let parsed = self.parse_stylesheet(content);
+        for diagnostic in parsed.diagnostics.iter() {
+            warn!("Failed to parse stylesheet: {diagnostic}");
+        }
+        (parsed.rules, parsed.imports)
+    }
+
+    /// Parses the content into the rules, the `@import` paths and
+    /// the `@font-face` declarations. Problems found in the content
+    /// are not logged but returned as [`ParsedStyleSheet::diagnostics`].
+    pub fn parse_stylesheet(&self, content: &str) -> ParsedStyleSheet {
+        let syntax = belly_syntax::ess::parse(content);
+        let mut reporter = Reporter::new(content);
+        for diagnostic in syntax.diagnostics.iter() {
+            reporter.report(diagnostic.span.start, &diagnostic.message);
+        }
+        let mut parsed = ParsedStyleSheet::default();
+        for item in syntax.items.iter() {
+            let result = match item {
+                EssItem::Rule(rule) => self.parse_rule(rule, &mut reporter).map(ParsedRule::Style),
+                EssItem::AtRule(rule) if rule.name.value.eq_ignore_ascii_case("media") => {
+                    self.parse_media(content, rule, &mut reporter)
+                }
+                EssItem::AtRule(rule) if rule.name.value.eq_ignore_ascii_case("font-face") => {
+                    parse_font_face(content, rule)
+                }
+                EssItem::AtRule(rule) => parse_at_rule(rule),
+            };
+            match result {
+                Ok(ParsedRule::Style(rule)) => parsed.rules.push(rule),
+                Ok(ParsedRule::Media(media_rules)) => parsed.rules.extend(media_rules),
+                Ok(ParsedRule::Import(path)) => parsed.imports.push(path),
+                Ok(ParsedRule::FontFace(font_face)) => parsed.font_faces.push(font_face),
+                Err(err) => {
+                    let span = item.span();
+                    let rule = span.slice(content);
+                    reporter.report(span.start, format!("Failed to parse rule `{rule}`: {err}"));
+                }
+            }
+        }
+        parsed.diagnostics = reporter.diagnostics;
+        parsed
+    }
+
+    /// Parses the list of declarations without the selector and braces,
+    /// like the `style` attribute of the element: `"margin: 4px; color: red"`.
+    /// Problems are logged, invalid declarations are skipped.
+    pub fn parse_declarations(&self, content: &str) -> HashMap<Tag, PropertyValue> {
+        let (declarations, diagnostics) =
+            parse_declarations_in(content, Span::new(0, content.len()));
+        let mut reporter = Reporter::new(content);
+        for diagnostic in diagnostics.iter() {
+            reporter.report(diagnostic.span.start, &diagnostic.message);
+        }
+        let mut properties = HashMap::default();
+        self.parse_properties(&declarations, &mut properties, &mut reporter);
+        for diagnostic in reporter.diagnostics.iter() {
+            warn!("Failed to parse declarations `{content}`: {diagnostic}");
+        }
+        properties
+    }
+
+    /// Parses the rule, declarations with unknown properties or invalid
+    /// values are reported and skipped.
+    fn parse_rule(&self, syntax: &EssRule, reporter: &mut Reporter) -> Result<StyleRule, String> {
+        let mut input = ParserInput::new(syntax.selector.value);
+        let selector = parse_selector(&mut Parser::new(&mut input)).map_err(format_error)?;
+        let mut rule = StyleRule {
+            selector,
+            properties: Default::default(),
+            media: None,
+        };
+        self.parse_properties(&syntax.declarations, &mut rule.properties, reporter);
+        Ok(rule)
+    }
+
+    /// Parses the declarations into the `properties`, declarations with
+    /// unknown properties or invalid values are reported and skipped.
+    fn parse_properties(
+        &self,
+        declarations: &[EssDeclaration],
+        properties: &mut HashMap<Tag, PropertyValue>,
+        reporter: &mut Reporter,
+    ) {
+        for declaration in declarations.iter() {
+            let mut input = ParserInput::new(declaration.value.value);
+            let mut parser = Parser::new(&mut input);
+            let name = declaration.name.value.into();
+            let (name, property) = match PropertyParser.parse_value(name, &mut parser) {
+                Ok(property) => property,
+                Err(err) => {
+                    let message = format!(
+                        "Failed to parse property {}: {}",
+                        declaration.name.value,
                         format_error(err)
                     );
-                    None
+                    reporter.report(declaration.value.span.start, message);
+                    continue;
+                }
+            };
+            let result = if self.extractor.is_compound_property(name) {
+                self.extractor
+                    .extract(name, Variant::style(property))
+                    .map(|extracted| properties.extend(extracted))
+            } else {
+                self.transformer
+                    .transform(name, Variant::style(property))
+                    .map(|value| {
+                        let value = value.with_source(declaration.value.value.trim());
+                        properties.insert(name, value);
+                    })
+            };
+            match result {
+                Err(err @ ElementsError::UnsupportedProperty(_)) => {
+                    reporter.report(declaration.name.span.start, err.to_string())
+                }
+                Err(err) => reporter.report(declaration.value.span.start, err.to_string()),
+                Ok(()) => {}
+            }
+        }
+    }
+
+    /// Parses the `@media (query) { rules }` block. Nested at-rules
+    /// are not supported.
+    fn parse_media(
+        &self,
+        content: &str,
+        syntax: &EssAtRule,
+        reporter: &mut Reporter,
+    ) -> Result<ParsedRule, String> {
+        let Some(block) = syntax.block else {
+            return Err("@media rule should have a block".to_string());
+        };
+        let mut input = ParserInput::new(syntax.prelude.value);
+        let mut parser = Parser::new(&mut input);
+        let query = Arc::new(MediaQuery::parse(&mut parser).map_err(format_error)?);
+        let (items, diagnostics) = parse_items_in(content, block);
+        for diagnostic in diagnostics {
+            reporter.report(diagnostic.span.start, diagnostic.message);
+        }
+        let mut rules = vec![];
+        for item in items.iter() {
+            let result = match item {
+                EssItem::Rule(rule) => self.parse_rule(rule, reporter),
+                EssItem::AtRule(rule) => Err(format!(
+                    "At rule isn't supported inside @media: @{}",
+                    rule.name.value
+                )),
+            };
+            match result {
+                Ok(mut rule) => {
+                    rule.media = Some(query.clone());
+                    rules.push(rule);
+                }
+                Err(err) => {
+                    let span = item.span();
+                    let rule = span.slice(content);
+                    reporter.report(span.start, format!("Failed to parse rule `{rule}`: {err}"));
                 }
-            })
-            .collect()
+            }
+        }
+        Ok(ParsedRule::Media(rules))
     }
 }
 
+/// Collects the problems found while parsing the stylesheet.
+struct Reporter {
+    lines: LineIndex,
+    diagnostics: Vec<StyleDiagnostic>,
+}
+
+impl Reporter {
+    fn new(content: &str) -> Reporter {
+        Reporter {
+            lines: LineIndex::new(content),
+            diagnostics: vec![],
+        }
+    }
+
+    fn report(&mut self, offset: usize, message: impl Into<String>) {
+        let position = self.lines.position(offset);
+        self.diagnostics.push(StyleDiagnostic {
+            path: None,
+            line: position.line,
+            column: position.column,
+            message: message.into(),
+        });
+    }
+}
+
+pub enum ParsedRule {
+    Style(StyleRule),
+    /// Rules of the `@media` block with the query attached.
+    Media(Vec<StyleRule>),
+    Import(String),
+    FontFace(FontFaceRule),
+}
+
+/// The content of the stylesheet returned by [`StyleSheetParser::parse_stylesheet`].
+#[derive(Default)]
+pub struct ParsedStyleSheet {
+    pub rules: SmallVec<[StyleRule; 8]>,
+    /// Paths requested by `@import` rules in order of appearance.
+    pub imports: Vec<String>,
+    pub font_faces: Vec<FontFaceRule>,
+    /// Problems found in the content, the rules and declarations
+    /// with problems are skipped.
+    pub diagnostics: Vec<StyleDiagnostic>,
+}
+
+/// The `@font-face { font-family: "Title"; src: url("title.ttf"); }` rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontFaceRule {
+    pub family: String,
+    /// The path to the font, relative to the stylesheet.
+    pub src: String,
+}
+
 fn format_error(error: ParseError<ElementsError>) -> String {
-    let error_description = match error.kind {
+    match error.kind {
         cssparser::ParseErrorKind::Basic(b) => match b {
             cssparser::BasicParseErrorKind::UnexpectedToken(token) => {
                 format!("Unexpected token {}", token.to_css_string())
@@ -57,12 +271,7 @@ fn format_error(error: ParseError<ElementsError>) -> String {
             cssparser::BasicParseErrorKind::QualifiedRuleInvalid => "Invalid rule".to_string(),
         },
         cssparser::ParseErrorKind::Custom(c) => c.to_string(),
-    };
-
-    format!(
-        "{} at {}:{}",
-        error_description, error.location.line, error.location.column
-    )
+    }
 }
 
 #[derive(Default)]
@@ -73,117 +282,120 @@ enum NextElement {
     Attribute,
 }
 
-impl<'i> QualifiedRuleParser<'i> for &StyleSheetParser {
-    type Prelude = Selector;
-    type QualifiedRule = StyleRule;
-    type Error = ElementsError;
+fn parse_selector<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Selector, ParseError<'i, ElementsError>> {
+    let mut elements: SmallVec<[SelectorElement; 8]> = smallvec![];
 
-    fn parse_prelude<'t>(
-        &mut self,
-        input: &mut Parser<'i, 't>,
-    ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
-        let mut elements: SmallVec<[SelectorElement; 8]> = smallvec![];
+    let mut next = NextElement::Tag;
 
-        let mut next = NextElement::Tag;
-
-        while let Ok(token) = input.next_including_whitespace() {
-            use cssparser::Token::*;
-            match token {
-                Ident(v) => {
-                    match next {
-                        NextElement::Tag => {
-                            elements.insert(0, SelectorElement::Tag(v.to_string().as_tag()))
-                        }
-                        NextElement::Class => {
-                            elements.insert(0, SelectorElement::Class(v.to_string().as_tag()))
-                        }
-                        NextElement::Attribute => {
-                            elements.insert(0, SelectorElement::State(v.to_string().as_tag()))
-                        }
-                    };
-                    next = NextElement::Tag;
-                }
-                IDHash(v) => {
-                    if v.is_empty() {
-                        return Err(input.new_custom_error(ElementsError::InvalidSelector));
-                    } else {
-                        elements.insert(0, SelectorElement::Id(v.to_string().as_tag()));
+    while let Ok(token) = input.next_including_whitespace() {
+        use cssparser::Token::*;
+        match token {
+            Ident(v) => {
+                match next {
+                    NextElement::Tag => {
+                        elements.insert(0, SelectorElement::Tag(v.to_string().as_tag()))
                     }
-                }
-                WhiteSpace(_) => elements.insert(0, SelectorElement::AnyChild),
-                Delim(c) if *c == '.' => next = NextElement::Class,
-                Delim(c) if *c == '*' => elements.insert(0, SelectorElement::Any),
-                Colon => next = NextElement::Attribute,
-                _ => {
-                    warn!("Unexpected token: {:?}", token);
-                    let token = token.to_css_string();
-                    return Err(input.new_custom_error(ElementsError::UnexpectedToken(token)));
+                    NextElement::Class => {
+                        elements.insert(0, SelectorElement::Class(v.to_string().as_tag()))
+                    }
+                    NextElement::Attribute => {
+                        elements.insert(0, SelectorElement::State(v.to_string().as_tag()))
+                    }
+                };
+                next = NextElement::Tag;
+            }
+            IDHash(v) => {
+                if v.is_empty() {
+                    return Err(input.new_custom_error(ElementsError::InvalidSelector));
+                } else {
+                    elements.insert(0, SelectorElement::Id(v.to_string().as_tag()));
                 }
             }
-        }
-
-        if elements.is_empty() {
-            return Err(input.new_custom_error(ElementsError::InvalidSelector));
-        }
-
-        // Remove noise the trailing white spaces, if any
-        while !elements.is_empty() {
-            if elements.last().unwrap().is_any_child() {
-                elements.pop();
-            } else if elements.first().unwrap().is_any_child() {
-                elements.remove(0);
-            } else {
-                break;
+            WhiteSpace(_) => elements.insert(0, SelectorElement::AnyChild),
+            Delim(c) if *c == '.' => next = NextElement::Class,
+            Delim(c) if *c == '*' => elements.insert(0, SelectorElement::Any),
+            Colon => next = NextElement::Attribute,
+            _ => {
+                warn!("Unexpected token: {:?}", token);
+                let token = token.to_css_string();
+                return Err(input.new_custom_error(ElementsError::UnexpectedToken(token)));
             }
         }
-
-        Ok(Selector::new(elements))
     }
 
-    fn parse_block<'t>(
-        &mut self,
-        prelude: Self::Prelude,
-        _start: &cssparser::ParserState,
-        input: &mut Parser<'i, 't>,
-    ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
-        let mut rule = StyleRule {
-            selector: prelude,
-            properties: Default::default(),
-        };
+    if elements.is_empty() {
+        return Err(input.new_custom_error(ElementsError::InvalidSelector));
+    }
 
-        for property in DeclarationListParser::new(input, PropertyParser) {
-            match property {
-                Ok((name, property)) => {
-                    if self.extractor.is_compound_property(name) {
-                        let extracted = match self.extractor.extract(name, Variant::style(property))
-                        {
-                            Err(e) => return Err(input.new_custom_error(e)),
-                            Ok(extracted) => extracted,
-                        };
-                        for (name, property) in extracted {
-                            rule.properties.insert(name, property);
-                        }
-                    } else {
-                        match self.transformer.transform(name, Variant::style(property)) {
-                            Ok(variant) => {
-                                rule.properties.insert(name, variant);
-                            }
-                            Err(e) => return Err(input.new_custom_error(e)),
-                        }
-                    }
-                }
-                Err((err, a)) => println!("Failed: {:?} ({})", err, a),
-            }
+    // Remove noise the trailing white spaces, if any
+    while !elements.is_empty() {
+        if elements.last().unwrap().is_any_child() {
+            elements.pop();
+        } else if elements.first().unwrap().is_any_child() {
+            elements.remove(0);
+        } else {
+            break;
         }
+    }
 
-        Ok(rule)
+    Ok(Selector::new(elements))
+}
+
+fn parse_at_rule(syntax: &EssAtRule) -> Result<ParsedRule, String> {
+    if !syntax.name.value.eq_ignore_ascii_case("import") || syntax.block.is_some() {
+        return Err(format!("At rule isn't supported @{}", syntax.name.value));
     }
+    let mut input = ParserInput::new(syntax.prelude.value);
+    let mut parser = Parser::new(&mut input);
+    let path = parser
+        .expect_url_or_string()
+        .map(|path| path.to_string())
+        .map_err(|e| format_error(e.into()))?;
+    parser
+        .expect_exhausted()
+        .map_err(|e| format_error(e.into()))?;
+    Ok(ParsedRule::Import(path))
 }
 
-impl<'i> AtRuleParser<'i> for &StyleSheetParser {
-    type Prelude = ();
-    type AtRule = StyleRule;
-    type Error = ElementsError;
+/// Parses the `@font-face` block, both `font-family` and `src` are required.
+/// Only the first source of the `src` list is used.
+fn parse_font_face(content: &str, syntax: &EssAtRule) -> Result<ParsedRule, String> {
+    let Some(block) = syntax.block else {
+        return Err("@font-face rule should have a block".to_string());
+    };
+    let (declarations, diagnostics) = parse_declarations_in(content, block);
+    if let Some(diagnostic) = diagnostics.first() {
+        return Err(diagnostic.message.clone());
+    }
+    let mut family = None;
+    let mut src = None;
+    for declaration in declarations.iter() {
+        let mut input = ParserInput::new(declaration.value.value);
+        let mut parser = Parser::new(&mut input);
+        let name = declaration.name.value.into();
+        let (name, value) = PropertyParser
+            .parse_value(name, &mut parser)
+            .map_err(format_error)?;
+        if name == "font-family".as_tag() {
+            let families = parse_families(&value).map_err(|e| e.to_string())?;
+            match families.as_slice() {
+                [name] => family = Some(name.clone()),
+                _ => return Err("@font-face should declare a single font-family".to_string()),
+            }
+        } else if name == "src".as_tag() {
+            let mut sources = value.split(|t| *t == StylePropertyToken::Comma);
+            let first = sources.next().unwrap_or_default();
+            let path = first.get(..1).unwrap_or_default().url();
+            src = Some(path.map_err(|e| e.to_string())?);
+        }
+    }
+    match (family, src) {
+        (Some(family), Some(src)) => Ok(ParsedRule::FontFace(FontFaceRule { family, src })),
+        (None, _) => Err("@font-face should declare font-family".to_string()),
+        (_, None) => Err("@font-face should declare src".to_string()),
+    }
 }
 
 struct PropertyParser;
@@ -199,10 +411,28 @@ impl<'i> DeclarationParser<'i> for PropertyParser {
         parser: &mut Parser<'i, 't>,
     ) -> Result<Self::Declaration, ParseError<'i, ElementsError>> {
         let mut tokens = smallvec![];
-        for token in parse_values(parser)? {
-            match token.try_into() {
-                Ok(t) => tokens.push(t),
-                Err(_) => continue,
+        while let Ok(token) = parser.next_including_whitespace() {
+            match token {
+                Token::Function(function) if function.eq_ignore_ascii_case("calc") => {
+                    tokens.push(parser.parse_nested_block(calc::parse_calc)?)
+                }
+                Token::Function(function) if colors::is_color_function(function) => {
+                    let name = function.to_string();
+                    tokens.push(
+                        parser.parse_nested_block(|p| colors::parse_color_function(&name, p))?,
+                    )
+                }
+                Token::Function(function) => {
+                    let name = function.to_string();
+                    tokens.push(
+                        parser
+                            .parse_nested_block(|p| StylePropertyToken::parse_function(&name, p))?,
+                    )
+                }
+                token => match token.clone().try_into() {
+                    Ok(t) => tokens.push(t),
+                    Err(_) => continue,
+                },
             }
         }
 
@@ -216,22 +446,10 @@ impl<'i> AtRuleParser<'i> for PropertyParser {
     type Error = ElementsError;
 }
 
-fn parse_values<'i, 'tt>(
-    parser: &mut Parser<'i, 'tt>,
-) -> Result<SmallVec<[Token<'i>; 8]>, ParseError<'i, ElementsError>> {
-    let mut values = SmallVec::new();
-
-    while let Ok(token) = parser.next_including_whitespace() {
-        values.push(token.clone())
-    }
-
-    Ok(values)
-}
-
 #[cfg(test)]
 mod tests {
     use crate::{
-        ess::{PropertyValue, StylePropertyToken},
+        ess::{PropertyKeyword, PropertyValue, StylePropertyToken},
         ExtractProperty, TransformProperty,
     };
 
@@ -609,4 +827,97 @@ mod tests {
                 })
         });
     }
+
+    #[test]
+    fn parse_imports() {
+        let parser = TestParser::new();
+        let parser = StyleSheetParser::new(parser.transformer, parser.extractor);
+        let (rules, imports) = parser.parse_with_imports(
+            r#"
+            @import "common.ess";
+            @import url(theme/dark.ess);
+            @unknown "skipped.ess";
+            a { b: c }
+        "#,
+        );
+        assert_eq!(rules.len(), 1, "Should have a single rule");
+        assert_eq!(
+            imports,
+            vec!["common.ess".to_string(), "theme/dark.ess".to_string()],
+            "Should collect imports in order of appearance"
+        );
+    }
+
+    #[test]
+    fn parse_font_faces() {
+        let parser = TestParser::new();
+        let parser = StyleSheetParser::new(parser.transformer, parser.extractor);
+        let parsed = parser.parse_stylesheet(
+            r#"
+            @font-face { font-family: "Title"; src: url("fonts/title.ttf"); }
+            @font-face { font-family: Body Text; src: "body.otf", url(fallback.ttf); }
+            @font-face { font-family: "Missing" }
+            a { b: c }
+        "#,
+        );
+        assert_eq!(parsed.rules.len(), 1, "Should have a single rule");
+        assert_eq!(
+            parsed.font_faces,
+            vec![
+                FontFaceRule {
+                    family: "Title".to_string(),
+                    src: "fonts/title.ttf".to_string()
+                },
+                FontFaceRule {
+                    family: "Body Text".to_string(),
+                    src: "body.otf".to_string()
+                },
+            ],
+            "Should skip the font face without src"
+        );
+    }
+
+    #[test]
+    fn parse_diagnostics() {
+        let parser = TestParser::new();
+        let parser = StyleSheetParser::new(parser.transformer, parser.extractor);
+        let parsed =
+            parser.parse_stylesheet("a {\n  b: c;\n  unknown: c;\n}\n$ { a: b }\n@unknown;");
+        assert_eq!(parsed.rules.len(), 1, "Should keep the rule");
+        assert!(parsed.rules[0].properties.contains_key(&"b".as_tag()));
+        let positions: Vec<_> = parsed
+            .diagnostics
+            .iter()
+            .map(|d| (d.line, d.column))
+            .collect();
+        assert_eq!(positions, vec![(3, 3), (5, 1), (6, 1)]);
+        assert_eq!(
+            parsed.diagnostics[0].to_string(),
+            "3:3: Unsupported property: unknown"
+        );
+    }
+
+    #[test]
+    fn parse_all_property() {
+        let parser = TestParser::new();
+        let rules = parser.parse("a { all: unset; b: c }");
+        assert_eq!(rules.len(), 1, "Should have a single rule");
+        let all = rules[0].properties.get(&"all".as_tag()).unwrap();
+        assert_eq!(all.keyword(), Some(PropertyKeyword::Unset));
+        assert!(
+            parser.parse("a { all: red }").is_empty(),
+            "`all` should accept only css-wide keywords"
+        );
+    }
+
+    #[test]
+    fn parse_declarations() {
+        let parser = TestParser::new();
+        let parser = StyleSheetParser::new(parser.transformer, parser.extractor);
+        let properties = parser.parse_declarations("a: b; compound: c; unknown: d");
+        assert_eq!(properties.len(), 2, "Should skip invalid declarations");
+        assert_eq!(properties.get(&"b".as_tag()).unwrap().source(), None);
+        let a = properties.get(&"a".as_tag()).unwrap();
+        assert_eq!(a.downcast_ref::<StyleProperty>().unwrap().to_string(), "a");
+    }
 }
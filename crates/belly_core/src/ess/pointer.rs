@@ -0,0 +1,82 @@
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{ecs::query::QueryItem, prelude::*};
+use tagstr::*;
+
+pub(crate) struct PointerEventsPlugin;
+impl Plugin for PointerEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_property::<PointerEventsProperty>();
+    }
+}
+
+/// Makes the node transparent to the pointer: it is not hovered, pressed
+/// or scrolled, and doesn't block the pointer for the nodes below it.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct NoPointerEvents;
+
+/// Applies the `pointer-events` property:
+/// ```css
+/// pointer-events: none;
+/// pointer-events: auto;
+/// ```
+/// The property is inherited, so `none` affects the whole subtree,
+/// descendants with `auto` receive the pointer again.
+#[derive(Default)]
+pub(crate) struct PointerEventsProperty;
+
+impl Property for PointerEventsProperty {
+    type Item = bool;
+    type Components = Option<&'static NoPointerEvents>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("pointer-events")
+    }
+
+    fn inherited() -> bool {
+        true
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        match values.identifier() {
+            Some("auto") => Ok(false),
+            Some("none") => Ok(true),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected auto or none, got `{}`",
+                values.to_string()
+            ))),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match (cache, components) {
+            (true, None) => {
+                commands.entity(entity).insert(NoPointerEvents);
+            }
+            (false, Some(_)) => {
+                commands.entity(entity).remove::<NoPointerEvents>();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_pointer_events() {
+        let parse = |value: &str| PointerEventsProperty::parse(&value.try_into().unwrap());
+        assert_eq!(parse("none"), Ok(true));
+        assert_eq!(parse("auto"), Ok(false));
+        assert!(parse("visible-painted").is_err());
+    }
+}
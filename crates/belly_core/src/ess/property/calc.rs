@@ -0,0 +1,527 @@
+use bevy::{prelude::*, ui::Val, utils::HashMap};
+use cssparser::{ParseError, Parser, Token};
+use std::ops::{Add, Mul};
+use tagstr::Tag;
+
+use crate::ElementsError;
+
+use super::{style::Number, StylePropertyToken};
+
+/// The font size used when the element has no computed `font-size`.
+pub(crate) const DEFAULT_FONT_SIZE: f32 = 22.;
+
+/// The result of the `calc()` expression: the linear combination of
+/// the parent size percentage, the fixed amount of pixels and the
+/// lengths relative to the font size or to the window size.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Calc {
+    pub percent: f32,
+    pub px: f32,
+    /// Multiples of the element font size.
+    pub em: f32,
+    /// Multiples of the root element font size.
+    pub rem: f32,
+    /// Percents of the window width.
+    pub vw: f32,
+    /// Percents of the window height.
+    pub vh: f32,
+    /// Percents of the smaller window dimension.
+    pub vmin: f32,
+    /// Percents of the larger window dimension.
+    pub vmax: f32,
+}
+
+impl Calc {
+    /// Creates the single length `value` in the `unit`. Supported units are
+    /// `px`, `em`, `rem`, `vw`, `vh`, `vmin` and `vmax`.
+    pub fn length(value: f32, unit: &str) -> Result<Calc, ElementsError> {
+        let mut calc = Calc::default();
+        match unit.to_ascii_lowercase().as_str() {
+            "px" => calc.px = value,
+            "em" => calc.em = value,
+            "rem" => calc.rem = value,
+            "vw" => calc.vw = value,
+            "vh" => calc.vh = value,
+            "vmin" => calc.vmin = value,
+            "vmax" => calc.vmax = value,
+            unit => {
+                return Err(ElementsError::InvalidPropertyValue(format!(
+                    "Unsupported length unit `{unit}`"
+                )))
+            }
+        }
+        Ok(calc)
+    }
+
+    /// Returns `true` if the value depends on the font or the window size.
+    pub fn is_relative(&self) -> bool {
+        self.em != 0.0
+            || self.rem != 0.0
+            || self.vw != 0.0
+            || self.vh != 0.0
+            || self.vmin != 0.0
+            || self.vmax != 0.0
+    }
+
+    /// Returns the plain [`Val`] if the expression is not mixed.
+    pub fn as_val(&self) -> Option<Val> {
+        if self.is_relative() {
+            None
+        } else if self.percent == 0.0 {
+            Some(Val::Px(self.px))
+        } else if self.px == 0.0 {
+            Some(Val::Percent(self.percent))
+        } else {
+            None
+        }
+    }
+
+    /// Resolves the expression against the parent size along the property axis
+    /// and the font & window sizes of the `units`.
+    pub fn resolve(&self, parent: f32, units: &RelativeUnits) -> f32 {
+        let viewport = units.viewport / 100.0;
+        parent * self.percent / 100.0
+            + self.px
+            + self.em * units.font_size
+            + self.rem * units.root_font_size
+            + self.vw * viewport.x
+            + self.vh * viewport.y
+            + self.vmin * viewport.min_element()
+            + self.vmax * viewport.max_element()
+    }
+
+    pub(crate) fn to_token(&self) -> StylePropertyToken {
+        match self.as_val() {
+            Some(Val::Percent(percent)) => StylePropertyToken::Percentage(percent.into()),
+            Some(Val::Px(px)) => StylePropertyToken::Dimension(px.into()),
+            _ if self.is_relative() => StylePropertyToken::Relative(self.to_numbers()),
+            _ => StylePropertyToken::Calc(self.percent.into(), self.px.into()),
+        }
+    }
+
+    pub(crate) fn from_numbers(percent: &Number, px: &Number) -> Calc {
+        Calc {
+            percent: percent.into(),
+            px: px.into(),
+            ..default()
+        }
+    }
+
+    /// The hashable form of the value used by [`StylePropertyToken::Relative`].
+    pub(crate) fn to_numbers(&self) -> [Number; 8] {
+        [
+            self.percent,
+            self.px,
+            self.em,
+            self.rem,
+            self.vw,
+            self.vh,
+            self.vmin,
+            self.vmax,
+        ]
+        .map(Number::from)
+    }
+
+    pub(crate) fn from_relative_numbers(numbers: &[Number; 8]) -> Calc {
+        let [percent, px, em, rem, vw, vh, vmin, vmax] = numbers.map(f32::from);
+        Calc {
+            percent,
+            px,
+            em,
+            rem,
+            vw,
+            vh,
+            vmin,
+            vmax,
+        }
+    }
+}
+
+impl Add for Calc {
+    type Output = Calc;
+    fn add(self, rhs: Calc) -> Calc {
+        Calc {
+            percent: self.percent + rhs.percent,
+            px: self.px + rhs.px,
+            em: self.em + rhs.em,
+            rem: self.rem + rhs.rem,
+            vw: self.vw + rhs.vw,
+            vh: self.vh + rhs.vh,
+            vmin: self.vmin + rhs.vmin,
+            vmax: self.vmax + rhs.vmax,
+        }
+    }
+}
+
+impl Mul<f32> for Calc {
+    type Output = Calc;
+    fn mul(self, factor: f32) -> Calc {
+        Calc {
+            percent: self.percent * factor,
+            px: self.px * factor,
+            em: self.em * factor,
+            rem: self.rem * factor,
+            vw: self.vw * factor,
+            vh: self.vh * factor,
+            vmin: self.vmin * factor,
+            vmax: self.vmax * factor,
+        }
+    }
+}
+
+/// The sizes the relative lengths of the [`Calc`] are resolved against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelativeUnits {
+    /// The computed `font-size` of the element, the `em` unit.
+    pub font_size: f32,
+    /// The computed `font-size` of the root element, the `rem` unit.
+    pub root_font_size: f32,
+    /// The size of the window, the `vw`, `vh`, `vmin` & `vmax` units.
+    pub viewport: Vec2,
+}
+
+/// The computed `font-size` of the element, inherited by the elements
+/// without text as well. Used to resolve the `em` & `rem` units.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct ComputedFontSize(pub f32);
+
+/// The value of the size-like property: either plain [`Val`] or the
+/// mixed `calc()` expression which is resolved each time the parent
+/// node changes its size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalcVal {
+    Val(Val),
+    Calc(Calc),
+}
+
+impl Default for CalcVal {
+    fn default() -> Self {
+        CalcVal::Val(Val::default())
+    }
+}
+
+impl From<Val> for CalcVal {
+    fn from(val: Val) -> Self {
+        CalcVal::Val(val)
+    }
+}
+
+/// The [`UiRect`] counterpart with [`CalcVal`] sides, the value of
+/// the `margin`, `padding` and `position` shorthands.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CalcRect {
+    pub left: CalcVal,
+    pub right: CalcVal,
+    pub top: CalcVal,
+    pub bottom: CalcVal,
+}
+
+impl CalcRect {
+    pub fn new(left: CalcVal, right: CalcVal, top: CalcVal, bottom: CalcVal) -> CalcRect {
+        CalcRect {
+            left,
+            right,
+            top,
+            bottom,
+        }
+    }
+
+    pub fn all(value: CalcVal) -> CalcRect {
+        CalcRect::new(value, value, value, value)
+    }
+}
+
+impl From<UiRect> for CalcRect {
+    fn from(rect: UiRect) -> Self {
+        CalcRect::new(
+            rect.left.into(),
+            rect.right.into(),
+            rect.top.into(),
+            rect.bottom.into(),
+        )
+    }
+}
+
+/// The parent axis the percentage part of the `calc()` is relative to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalcAxis {
+    Horizontal,
+    Vertical,
+    /// The main axis of the parent, set by its `flex-direction`.
+    Main,
+}
+
+impl CalcAxis {
+    /// Picks the dimension of the parent `size` along the axis, the
+    /// `direction` is the `flex-direction` of the parent.
+    fn select(self, size: Vec2, direction: FlexDirection) -> f32 {
+        match (self, direction) {
+            (CalcAxis::Horizontal, _) => size.x,
+            (CalcAxis::Vertical, _) => size.y,
+            (CalcAxis::Main, FlexDirection::Column | FlexDirection::ColumnReverse) => size.y,
+            (CalcAxis::Main, _) => size.x,
+        }
+    }
+}
+
+/// Mixed `calc()` values applied to the entity, keyed by the property name.
+#[derive(Component, Default)]
+pub struct CalcStyle(pub(crate) HashMap<Tag, (Calc, CalcAxis, fn(&mut Style) -> &mut Val)>);
+
+pub(crate) fn resolve_calc_styles(
+    windows: Res<Windows>,
+    mut elements: ParamSet<(
+        Query<(Entity, &CalcStyle, Option<&Parent>, &mut Style)>,
+        Query<&Style>,
+    )>,
+    nodes: Query<&Node>,
+    parents: Query<&Parent>,
+    font_sizes: Query<&ComputedFontSize>,
+) {
+    let viewport = windows
+        .get_primary()
+        .map(|window| Vec2::new(window.width(), window.height()))
+        .unwrap_or_default();
+    let font_size = |entity| {
+        font_sizes
+            .get(entity)
+            .map(|size| size.0)
+            .unwrap_or(DEFAULT_FONT_SIZE)
+    };
+    let parents: Vec<_> = elements
+        .p0()
+        .iter()
+        .filter_map(|(_, _, parent, _)| parent.map(|parent| parent.get()))
+        .collect();
+    let directions: HashMap<_, _> = parents
+        .into_iter()
+        .filter_map(|parent| {
+            let direction = elements.p1().get(parent).ok()?.flex_direction;
+            Some((parent, direction))
+        })
+        .collect();
+    for (entity, calc, parent, mut style) in elements.p0().iter_mut() {
+        // root nodes are laid out relative to the window
+        let direction = parent
+            .and_then(|parent| directions.get(&parent.get()))
+            .copied()
+            .unwrap_or_default();
+        let size = match parent.map(|parent| nodes.get(parent.get())) {
+            Some(Ok(parent)) => parent.size(),
+            Some(Err(_)) => continue,
+            None => viewport,
+        };
+        let mut root = entity;
+        while let Ok(parent) = parents.get(root) {
+            root = parent.get();
+        }
+        let units = RelativeUnits {
+            font_size: font_size(entity),
+            root_font_size: font_size(root),
+            viewport,
+        };
+        for (value, axis, field) in calc.0.values() {
+            let parent = axis.select(size, direction);
+            let resolved = Val::Px(value.resolve(parent, &units));
+            if *field(style.bypass_change_detection()) != resolved {
+                *field(&mut *style) = resolved;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Operand {
+    calc: Calc,
+    number: bool,
+}
+
+impl Operand {
+    fn number(value: f32) -> Operand {
+        Operand {
+            calc: Calc {
+                px: value,
+                ..default()
+            },
+            number: true,
+        }
+    }
+    fn scale(self, factor: f32) -> Operand {
+        Operand {
+            calc: self.calc * factor,
+            number: self.number,
+        }
+    }
+}
+
+/// Parses the content of the `calc()` function into the single token.
+pub(crate) fn parse_calc<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<StylePropertyToken, ParseError<'i, ElementsError>> {
+    let result = parse_sum(input)?;
+    input.expect_exhausted()?;
+    if result.number {
+        return Err(input.new_custom_error(ElementsError::InvalidPropertyValue(
+            "calc() should result in length or percentage, got number".to_string(),
+        )));
+    }
+    Ok(result.calc.to_token())
+}
+
+fn parse_sum<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Operand, ParseError<'i, ElementsError>> {
+    let mut result = parse_product(input)?;
+    loop {
+        let state = input.state();
+        let sign = match input.next() {
+            Ok(Token::Delim('+')) => 1.0,
+            Ok(Token::Delim('-')) => -1.0,
+            _ => {
+                input.reset(&state);
+                return Ok(result);
+            }
+        };
+        let rhs = parse_product(input)?.scale(sign);
+        if rhs.number != result.number {
+            return Err(input.new_custom_error(ElementsError::InvalidPropertyValue(
+                "Can't mix numbers and lengths in calc() sum".to_string(),
+            )));
+        }
+        result.calc = result.calc + rhs.calc;
+    }
+}
+
+fn parse_product<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Operand, ParseError<'i, ElementsError>> {
+    let mut result = parse_operand(input)?;
+    loop {
+        let state = input.state();
+        let divide = match input.next() {
+            Ok(Token::Delim('*')) => false,
+            Ok(Token::Delim('/')) => true,
+            _ => {
+                input.reset(&state);
+                return Ok(result);
+            }
+        };
+        let rhs = parse_operand(input)?;
+        result = match (divide, result.number, rhs.number) {
+            (false, true, _) => rhs.scale(result.calc.px),
+            (false, false, true) => result.scale(rhs.calc.px),
+            (true, _, true) if rhs.calc.px != 0.0 => result.scale(1.0 / rhs.calc.px),
+            (true, _, true) => {
+                return Err(input.new_custom_error(ElementsError::InvalidPropertyValue(
+                    "Division by zero in calc()".to_string(),
+                )))
+            }
+            _ => {
+                return Err(input.new_custom_error(ElementsError::InvalidPropertyValue(
+                    "calc() supports multiplication and division by numbers only".to_string(),
+                )))
+            }
+        };
+    }
+}
+
+fn parse_operand<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Operand, ParseError<'i, ElementsError>> {
+    let token = input.next()?.clone();
+    match token {
+        Token::Number { value, .. } => Ok(Operand::number(value)),
+        Token::Percentage { unit_value, .. } => Ok(Operand {
+            calc: Calc {
+                percent: unit_value * 100.0,
+                ..default()
+            },
+            number: false,
+        }),
+        Token::Dimension { value, unit, .. } => match Calc::length(value, &unit) {
+            Ok(calc) => Ok(Operand {
+                calc,
+                number: false,
+            }),
+            Err(err) => Err(input.new_custom_error(err)),
+        },
+        Token::ParenthesisBlock => input.parse_nested_block(parse_sum),
+        Token::Function(name) if name.eq_ignore_ascii_case("calc") => {
+            input.parse_nested_block(parse_sum)
+        }
+        token => Err(input.new_unexpected_token_error(token)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ess::{StyleProperty, StylePropertyMethods};
+    use smallvec::SmallVec;
+
+    #[test]
+    fn parse_calc_values() {
+        let expected = StyleProperty(SmallVec::from_vec(vec![
+            StylePropertyToken::Calc(100f32.into(), (-20f32).into()),
+            StylePropertyToken::Percentage(25f32.into()),
+            StylePropertyToken::Dimension(30f32.into()),
+        ]));
+        let value = "calc(100% - 20px) calc((100% - 50%) / 2) calc(2 * (10px + 5px))";
+        assert_eq!(Ok(expected), value.try_into());
+    }
+
+    #[test]
+    fn resolve_relative_units() {
+        let units = RelativeUnits {
+            font_size: 10.,
+            root_font_size: 20.,
+            viewport: Vec2::new(800., 600.),
+        };
+        let resolve = |value: &str| match StyleProperty::try_from(value).unwrap().calc_val() {
+            Ok(CalcVal::Calc(calc)) => calc.resolve(200., &units),
+            other => panic!("Expected relative value, got {other:?}"),
+        };
+        assert_eq!(resolve("2em"), 20.);
+        assert_eq!(resolve("1.5rem"), 30.);
+        assert_eq!(resolve("10vw"), 80.);
+        assert_eq!(resolve("10vh"), 60.);
+        assert_eq!(resolve("50vmin"), 300.);
+        assert_eq!(resolve("50vmax"), 400.);
+        assert_eq!(resolve("calc(50% - 1em + 2px)"), 92.);
+        assert_eq!(
+            StyleProperty::try_from("0em").unwrap().calc_val(),
+            Ok(CalcVal::Val(Val::Px(0.)))
+        );
+    }
+
+    #[test]
+    fn select_main_axis() {
+        let size = Vec2::new(200., 100.);
+        assert_eq!(CalcAxis::Main.select(size, FlexDirection::Row), 200.);
+        assert_eq!(CalcAxis::Main.select(size, FlexDirection::RowReverse), 200.);
+        assert_eq!(CalcAxis::Main.select(size, FlexDirection::Column), 100.);
+        assert_eq!(
+            CalcAxis::Main.select(size, FlexDirection::ColumnReverse),
+            100.
+        );
+        assert_eq!(
+            CalcAxis::Horizontal.select(size, FlexDirection::Column),
+            200.
+        );
+        assert_eq!(CalcAxis::Vertical.select(size, FlexDirection::Row), 100.);
+    }
+
+    #[test]
+    fn reject_invalid_calc() {
+        assert!(StyleProperty::try_from("calc(10px * 10px)").is_err());
+        assert!(StyleProperty::try_from("calc(10px / 0)").is_err());
+        assert!(StyleProperty::try_from("calc(10px + 2)").is_err());
+        assert!(StyleProperty::try_from("calc(10px + 2pt)").is_err());
+        assert!(StyleProperty::try_from("10furlongs").is_err());
+        assert_eq!(
+            Calc::length(2., "EM"),
+            Ok(Calc {
+                em: 2.,
+                ..default()
+            })
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use bevy::prelude::Color;
+use cssparser::{ParseError, Parser, Token};
 
 use crate::ElementsError;
 
+use super::StylePropertyToken;
+
 pub trait ColorFromHexExtension {
     fn from_hex<T: AsRef<str>>(color: T) -> Color {
         let color = color.as_ref().trim().trim_start_matches('#');
@@ -33,6 +36,113 @@ pub(super) fn parse_hex_color(hex: &str) -> Result<Color, ElementsError> {
     }
 }
 
+/// Returns `true` if `name` is one of the functional color notations.
+pub(crate) fn is_color_function(name: &str) -> bool {
+    ["rgb", "rgba", "hsl", "hsla"]
+        .iter()
+        .any(|f| name.eq_ignore_ascii_case(f))
+}
+
+enum Component {
+    Number(f32),
+    /// Percentage in `0..1` range.
+    Percentage(f32),
+    /// Angle in degrees.
+    Angle(f32),
+}
+
+/// Parses the arguments of the `rgb()`, `rgba()`, `hsl()` or `hsla()` function
+/// into the single [`Hash`](StylePropertyToken::Hash) token. Both the legacy
+/// comma separated and the modern space separated syntaxes are supported:
+/// ```css
+/// color: rgb(255, 0, 0);
+/// color: rgba(100%, 0%, 0%, 0.5);
+/// color: rgb(255 0 0 / 50%);
+/// color: hsl(120deg, 100%, 50%);
+/// color: hsl(0.5turn 60% 40% / 0.8);
+/// ```
+/// The channels are treated as sRGB values, the same way the hex colors are.
+pub(crate) fn parse_color_function<'i, 't>(
+    name: &str,
+    input: &mut Parser<'i, 't>,
+) -> Result<StylePropertyToken, ParseError<'i, ElementsError>> {
+    let hsl = name.to_ascii_lowercase().starts_with("hsl");
+    let first = parse_component(input)?;
+    let commas = input.try_parse(|i| i.expect_comma()).is_ok();
+    let second = parse_component(input)?;
+    if commas {
+        input.expect_comma()?;
+    }
+    let third = parse_component(input)?;
+    let alpha = if input.is_exhausted() {
+        1.0
+    } else {
+        if commas {
+            input.expect_comma()?;
+        } else {
+            input.expect_delim('/')?;
+        }
+        match parse_component(input)? {
+            Component::Number(alpha) | Component::Percentage(alpha) => alpha,
+            Component::Angle(_) => return Err(color_error(input, "Alpha can't be an angle")),
+        }
+    };
+    input.expect_exhausted()?;
+    let alpha = alpha.clamp(0.0, 1.0);
+    let color = if hsl {
+        let hue = match first {
+            Component::Number(deg) | Component::Angle(deg) => deg.rem_euclid(360.0),
+            Component::Percentage(_) => {
+                return Err(color_error(input, "Hue can't be a percentage"));
+            }
+        };
+        let percent = |component| match component {
+            Component::Percentage(value) => Ok(value.clamp(0.0, 1.0)),
+            _ => Err(color_error(
+                input,
+                "Saturation & lightness should be percentages",
+            )),
+        };
+        Color::hsla(hue, percent(second)?, percent(third)?, alpha)
+    } else {
+        let channel = |component| match component {
+            Component::Number(value) => Ok((value / 255.0).clamp(0.0, 1.0)),
+            Component::Percentage(value) => Ok(value.clamp(0.0, 1.0)),
+            Component::Angle(_) => Err(color_error(input, "Color channel can't be an angle")),
+        };
+        Color::rgba(channel(first)?, channel(second)?, channel(third)?, alpha)
+    };
+    let [r, g, b, a] = color.as_rgba_f32().map(|c| (c * 255.0).round() as u8);
+    Ok(StylePropertyToken::Hash(format!(
+        "{r:02x}{g:02x}{b:02x}{a:02x}"
+    )))
+}
+
+fn parse_component<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Component, ParseError<'i, ElementsError>> {
+    let location = input.current_source_location();
+    match input.next()? {
+        Token::Number { value, .. } => Ok(Component::Number(*value)),
+        Token::Percentage { unit_value, .. } => Ok(Component::Percentage(*unit_value)),
+        Token::Dimension { value, unit, .. } => {
+            let degrees = match unit.to_ascii_lowercase().as_str() {
+                "deg" => *value,
+                "rad" => value.to_degrees(),
+                "grad" => *value * 0.9,
+                "turn" => *value * 360.0,
+                _ => return Err(location.new_unexpected_token_error(Token::Ident(unit.clone()))),
+            };
+            Ok(Component::Angle(degrees))
+        }
+        token => Err(location.new_unexpected_token_error(token.clone())),
+    }
+}
+
+fn color_error<'i>(input: &Parser<'i, '_>, message: &str) -> ParseError<'i, ElementsError> {
+    input.new_custom_error(ElementsError::InvalidPropertyValue(message.to_string()))
+}
+
 // Source: https://developer.mozilla.org/en-US/docs/Web/CSS/named-color
 
 /// Parses a named color, like "silver" or "azure" into a [`Color`]
@@ -199,3 +309,33 @@ pub(super) fn parse_named_color(name: &str) -> Option<Color> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::ess::{StyleProperty, StylePropertyMethods};
+    use bevy::prelude::Color;
+
+    fn color(value: &str) -> Color {
+        StyleProperty::try_from(value).unwrap().color().unwrap()
+    }
+
+    fn assert_color(value: &str, expected: Color) {
+        let actual = color(value).as_rgba_f32();
+        let expected = expected.as_rgba_f32();
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a - e).abs() < 0.01, "{value}: {actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn parse_functional_colors() {
+        assert_color("rgb(255, 0, 0)", Color::RED);
+        assert_color("rgba(0%, 100%, 0%, 0.5)", Color::rgba(0., 1., 0., 0.5));
+        assert_color("rgb(0 0 255 / 25%)", Color::rgba(0., 0., 1., 0.25));
+        assert_color("hsl(120deg, 100%, 50%)", Color::rgb(0., 1., 0.));
+        assert_color("hsla(0.5turn 100% 50% / 1)", Color::CYAN);
+        assert_color("RebeccaPurple", Color::rgb(0.4, 0.2, 0.6));
+        assert!(StyleProperty::try_from("rgb(1, 2)").is_err());
+        assert!(StyleProperty::try_from("hsl(10%, 10%, 10%)").is_err());
+    }
+}
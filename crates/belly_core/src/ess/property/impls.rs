@@ -12,7 +12,7 @@ pub(crate) use transform::*;
 #[macro_export]
 macro_rules! style_property {
     ( $(#[doc = $s:literal])*
-      $typename:ident($prop_name:literal) {
+      $vis:vis $typename:ident($prop_name:literal) {
         Item = $item:ty;
         Components = $components:ty;
         Filters = $filters:ty;
@@ -22,14 +22,14 @@ macro_rules! style_property {
     }) => {
         #[derive(Default)]
         $(#[doc = $s])*
-        struct $typename;
+        $vis struct $typename;
         impl $crate::ess::Property for $typename {
             type Item = $item;
             type Components = $components;
             type Filters = $filters;
 
-            fn name() -> Tag {
-                tag!($prop_name)
+            fn name() -> $crate::Tag {
+                $crate::tag!($prop_name)
             }
 
             fn parse($tokens: &$crate::ess::StyleProperty) -> Result<Self::Item, $crate::ElementsError> {
@@ -62,10 +62,10 @@ macro_rules! compound_style_property {
         $(#[doc = $s])*
         struct $typename;
         impl $crate::ess::CompoundProperty for $typename {
-            fn name() -> Tag {
-                tag!($prop_name)
+            fn name() -> $crate::Tag {
+                $crate::tag!($prop_name)
             }
-            fn extract($value: Variant) -> Result<::bevy::utils::HashMap<$crate::Tag, $crate::ess::PropertyValue>, $crate::ElementsError> {
+            fn extract($value: $crate::Variant) -> Result<::bevy::utils::HashMap<$crate::Tag, $crate::ess::PropertyValue>, $crate::ElementsError> {
                 $body
             }
             fn docstring() -> &'static str {
@@ -80,7 +80,12 @@ mod style {
 
     use bevy::utils::HashMap;
 
-    use crate::{eml::Variant, ess::PropertyValue};
+    use crate::{
+        eml::Variant,
+        ess::{
+            CalcAxis, CalcRect, CalcStyle, CalcVal, PropertyValue, StylePropertyToken, ToRectMap,
+        },
+    };
 
     use super::*;
     // #[derive(Default)]
@@ -108,6 +113,8 @@ mod style {
     // }
 
     /// Implements a new property property extractor for [`Style`] component which expects a rect value.
+    /// The shorthand accepts 1-4 sides the same way css does (`top right bottom left`),
+    /// each side could be the mixed `calc()` expression.
     macro_rules! impl_style_rect {
         ($name:expr, $struct:ident) => {
             #[doc = "Applies the `"]
@@ -124,36 +131,21 @@ mod style {
                 fn extract(
                     value: crate::Variant,
                 ) -> Result<bevy::utils::HashMap<Tag, PropertyValue>, ElementsError> {
-                    let rect =
-                        match value {
-                            Variant::String(unparsed) => {
-                                StyleProperty::try_from(unparsed).and_then(|prop| prop.rect())?
-                            }
-                            Variant::Style(prop) => prop.rect()?,
-                            variant => variant.take::<UiRect>().ok_or(
+                    let rect = match value {
+                        Variant::String(unparsed) => {
+                            StyleProperty::try_from(unparsed).and_then(|prop| prop.calc_rect())?
+                        }
+                        Variant::Style(prop) => prop.calc_rect()?,
+                        variant => match variant.get::<UiRect>() {
+                            Some(rect) => CalcRect::from(*rect),
+                            None => variant.take::<CalcRect>().ok_or(
                                 ElementsError::InvalidPropertyValue(format!(
                                     "Can't extract rect from variant"
                                 )),
                             )?,
-                        };
-                    let mut props = HashMap::default();
-                    props.insert(
-                        concat!($name, "-left").as_tag(),
-                        PropertyValue::new(rect.left),
-                    );
-                    props.insert(
-                        concat!($name, "-right").as_tag(),
-                        PropertyValue::new(rect.right),
-                    );
-                    props.insert(
-                        concat!($name, "-top").as_tag(),
-                        PropertyValue::new(rect.top),
-                    );
-                    props.insert(
-                        concat!($name, "-bottom").as_tag(),
-                        PropertyValue::new(rect.bottom),
-                    );
-                    Ok(props)
+                        },
+                    };
+                    Ok(rect.to_rect_map($name))
                 }
             }
         };
@@ -197,41 +189,136 @@ mod style {
         };
     }
 
+    /// Implements a new property for [`Style`] component which expects a single size value.
+    /// Mixed `calc()` expressions are resolved against the parent node size along the `$axis`.
+    macro_rules! impl_style_calc_value {
+        ($name:expr, $struct:ident, $axis:ident, $style_prop:ident$(.$style_field:ident)*) => {
+            #[doc = "Applies the `"]
+            #[doc = $name]
+            #[doc = "` property on [Style::"]
+            #[doc = stringify!($style_prop)]
+            $(#[doc = concat!("::",stringify!($style_field))])*
+            #[doc = "](`Style`) field of all sections on matched [`Style`] components."]
+            #[derive(Default)]
+            pub(crate) struct $struct;
+
+            impl Property for $struct {
+                type Item = CalcVal;
+                type Components = &'static mut Style;
+                type Filters = With<Node>;
+
+                fn name() -> Tag {
+                    tag!($name)
+                }
+
+                fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+                    values.calc_val()
+                }
+
+                fn transform(variant: Variant) -> Result<PropertyValue, ElementsError> {
+                    if let Some(val) = variant.get::<Val>() {
+                        return Ok(PropertyValue::new(CalcVal::Val(*val)));
+                    }
+                    match variant {
+                        Variant::Style(p) => Self::parse(&p).map(PropertyValue::new),
+                        Variant::String(s) => StyleProperty::try_from(s)
+                            .and_then(|p| Self::parse(&p))
+                            .map(PropertyValue::new),
+                        Variant::Property(p) => match p.downcast_ref::<Val>() {
+                            Some(val) => Ok(PropertyValue::new(CalcVal::Val(*val))),
+                            None => Ok(p),
+                        },
+                        variant => variant.take::<CalcVal>().map(PropertyValue::new).ok_or(
+                            ElementsError::InvalidPropertyValue(format!(
+                                "Can't transform variant into {} value",
+                                $name
+                            )),
+                        ),
+                    }
+                }
+
+                fn apply<'w>(
+                    cache: &Self::Item,
+                    mut components: QueryItem<Self::Components>,
+                    _asset_server: &AssetServer,
+                    commands: &mut Commands,
+                    entity: Entity,
+                ) {
+                    fn field(style: &mut Style) -> &mut Val {
+                        &mut style.$style_prop$(.$style_field)*
+                    }
+                    let name = Self::name();
+                    match *cache {
+                        CalcVal::Val(val) => {
+                            *field(&mut components) = val;
+                            commands.add(move |world: &mut World| {
+                                let Some(mut entity) = world.get_entity_mut(entity) else { return };
+                                let Some(mut calc) = entity.get_mut::<CalcStyle>() else { return };
+                                if calc.0.contains_key(&name) {
+                                    calc.0.remove(&name);
+                                }
+                            });
+                        }
+                        CalcVal::Calc(value) => {
+                            let entry = (value, CalcAxis::$axis, field as fn(&mut Style) -> &mut Val);
+                            commands.add(move |world: &mut World| {
+                                let Some(mut entity) = world.get_entity_mut(entity) else { return };
+                                if let Some(mut calc) = entity.get_mut::<CalcStyle>() {
+                                    calc.0.insert(name, entry);
+                                } else {
+                                    let mut calc = CalcStyle::default();
+                                    calc.0.insert(name, entry);
+                                    entity.insert(calc);
+                                }
+                            });
+                        }
+                    }
+                }
+            }
+        };
+    }
+
     // Val properties
     impl_style_rect!("position", PositionProperty);
-    impl_style_single_value!("left", LeftProperty, Val, val, position.left);
-    impl_style_single_value!("right", RightProperty, Val, val, position.right);
-    impl_style_single_value!("top", TopProperty, Val, val, position.top);
-    impl_style_single_value!("bottom", BottomProperty, Val, val, position.bottom);
+    impl_style_calc_value!("left", LeftProperty, Horizontal, position.left);
+    impl_style_calc_value!("right", RightProperty, Horizontal, position.right);
+    impl_style_calc_value!("top", TopProperty, Vertical, position.top);
+    impl_style_calc_value!("bottom", BottomProperty, Vertical, position.bottom);
     impl_style_rect!("margin", MarginProperty);
-    impl_style_single_value!("margin-left", MarginLeftProperty, Val, val, margin.left);
-    impl_style_single_value!("margin-right", MarginRightProperty, Val, val, margin.right);
-    impl_style_single_value!("margin-top", MarginTopProperty, Val, val, margin.top);
-    impl_style_single_value!(
+    impl_style_calc_value!("margin-left", MarginLeftProperty, Horizontal, margin.left);
+    impl_style_calc_value!(
+        "margin-right",
+        MarginRightProperty,
+        Horizontal,
+        margin.right
+    );
+    impl_style_calc_value!("margin-top", MarginTopProperty, Horizontal, margin.top);
+    impl_style_calc_value!(
         "margin-bottom",
         MarginBottomProperty,
-        Val,
-        val,
+        Horizontal,
         margin.bottom
     );
     impl_style_rect!("padding", PaddingProperty);
-    impl_style_single_value!("padding-left", PaddingLeftProperty, Val, val, padding.left);
-    impl_style_single_value!(
+    impl_style_calc_value!(
+        "padding-left",
+        PaddingLeftProperty,
+        Horizontal,
+        padding.left
+    );
+    impl_style_calc_value!(
         "padding-right",
         PaddingRightProperty,
-        Val,
-        val,
+        Horizontal,
         padding.right
     );
-    impl_style_single_value!("padding-top", PaddingTopProperty, Val, val, padding.top);
-    impl_style_single_value!(
+    impl_style_calc_value!("padding-top", PaddingTopProperty, Horizontal, padding.top);
+    impl_style_calc_value!(
         "padding-bottom",
         PaddingBottomProperty,
-        Val,
-        val,
+        Horizontal,
         padding.bottom
     );
-    impl_style_rect!("border", BorderProperty);
     impl_style_single_value!("border-left", BorderLeftProperty, Val, val, border.left);
     impl_style_single_value!("border-right", BorderRightProperty, Val, val, border.right);
     impl_style_single_value!("border-top", BorderTopProperty, Val, val, border.top);
@@ -243,27 +330,127 @@ mod style {
         border.bottom
     );
 
-    impl_style_single_value!("width", WidthProperty, Val, val, size.width);
-    impl_style_single_value!("height", HeightProperty, Val, val, size.height);
+    impl_style_calc_value!("width", WidthProperty, Horizontal, size.width);
+    impl_style_calc_value!("height", HeightProperty, Vertical, size.height);
 
-    impl_style_single_value!("min-width", MinWidthProperty, Val, val, min_size.width);
-    impl_style_single_value!("min-height", MinHeightProperty, Val, val, min_size.height);
+    impl_style_calc_value!("min-width", MinWidthProperty, Horizontal, min_size.width);
+    impl_style_calc_value!("min-height", MinHeightProperty, Vertical, min_size.height);
 
-    impl_style_single_value!("max-width", MaxWidthProperty, Val, val, max_size.width);
-    impl_style_single_value!("max-height", MaxHeightProperty, Val, val, max_size.height);
+    impl_style_calc_value!("max-width", MaxWidthProperty, Horizontal, max_size.width);
+    impl_style_calc_value!("max-height", MaxHeightProperty, Vertical, max_size.height);
 
-    impl_style_single_value!("flex-basis", FlexBasisProperty, Val, val, flex_basis);
+    impl_style_calc_value!("flex-basis", FlexBasisProperty, Main, flex_basis);
 
     impl_style_single_value!("flex-grow", FlexGrowProperty, f32, f32, flex_grow);
     impl_style_single_value!("flex-shrink", FlexShrinkProperty, f32, f32, flex_shrink);
 
-    impl_style_single_value!(
-        "aspect-ratio",
-        AspectRatioProperty,
-        Option<f32>,
-        option_f32,
-        aspect_ratio
-    );
+    /// Extracts the `flex` shorthand into `flex-grow`, `flex-shrink` and `flex-basis`:
+    /// - `flex: none` is `0 0 auto`
+    /// - `flex: auto` is `1 1 auto`
+    /// - `flex: 2` is `2 1 0%`
+    /// - `flex: 2 3` is `2 3 0%`
+    /// - `flex: 100px` is `1 1 100px`
+    /// - `flex: 2 3 100px` & `flex: 2 100px` specify all (or grow & basis) values
+    #[derive(Default)]
+    pub(crate) struct FlexProperty;
+
+    impl CompoundProperty for FlexProperty {
+        fn name() -> Tag {
+            tag!("flex")
+        }
+
+        fn extract(value: Variant) -> Result<HashMap<Tag, PropertyValue>, ElementsError> {
+            let props = match value {
+                Variant::String(unparsed) => StyleProperty::try_from(unparsed)?,
+                Variant::Style(prop) => prop,
+                v => return Self::error(format!("Don't know how to extract flex from {v:?}")),
+            };
+            let (grow, shrink, basis) = match props.identifier() {
+                Some("none") if props.len() == 1 => (0., 0., CalcVal::Val(Val::Auto)),
+                Some("auto") if props.len() == 1 => (1., 1., CalcVal::Val(Val::Auto)),
+                _ => {
+                    let mut numbers: Vec<f32> = vec![];
+                    let mut basis = None;
+                    for token in props.iter() {
+                        match token {
+                            StylePropertyToken::Number(n)
+                                if basis.is_none() && numbers.len() < 2 =>
+                            {
+                                numbers.push(n.into())
+                            }
+                            token if basis.is_none() => basis = Some(token.calc_val()?),
+                            token => {
+                                return Self::error(format!(
+                                    "Unexpected flex value {}",
+                                    std::slice::from_ref(token).to_string()
+                                ))
+                            }
+                        }
+                    }
+                    let basis = basis.unwrap_or(if numbers.is_empty() {
+                        CalcVal::Val(Val::Auto)
+                    } else {
+                        CalcVal::Val(Val::Percent(0.))
+                    });
+                    let grow = numbers.first().copied().unwrap_or(1.);
+                    let shrink = numbers.get(1).copied().unwrap_or(1.);
+                    (grow, shrink, basis)
+                }
+            };
+            let mut result = HashMap::default();
+            result.insert(tag!("flex-grow"), PropertyValue::new(grow));
+            result.insert(tag!("flex-shrink"), PropertyValue::new(shrink));
+            result.insert(tag!("flex-basis"), PropertyValue::new(basis));
+            Ok(result)
+        }
+    }
+
+    /// Applies the `aspect-ratio` property on [`Style::aspect_ratio`]:
+    /// - `aspect-ratio: 16 / 9` is the width to height ratio
+    /// - `aspect-ratio: 1.5` is the same as `1.5 / 1`
+    /// - `aspect-ratio: auto` (or `none`) removes the constraint
+    #[derive(Default)]
+    pub(crate) struct AspectRatioProperty;
+
+    impl Property for AspectRatioProperty {
+        type Item = Option<f32>;
+        type Components = &'static mut Style;
+        type Filters = With<Node>;
+
+        fn name() -> Tag {
+            tag!("aspect-ratio")
+        }
+
+        fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+            use StylePropertyToken::*;
+            let ratio = match values.tokens() {
+                [Identifier(ident)] if ident == "auto" || ident == "none" => return Ok(None),
+                [Number(ratio)] => f32::from(ratio),
+                [Number(width), Slash, Number(height)] => f32::from(width) / f32::from(height),
+                _ => f32::NAN,
+            };
+            if ratio.is_finite() && ratio > 0. {
+                Ok(Some(ratio))
+            } else {
+                Err(ElementsError::InvalidPropertyValue(format!(
+                    "Expected positive ratio or auto, got `{}`",
+                    values.to_string()
+                )))
+            }
+        }
+
+        fn apply<'w>(
+            cache: &Self::Item,
+            mut components: QueryItem<Self::Components>,
+            _asset_server: &AssetServer,
+            _commands: &mut Commands,
+            _entity: Entity,
+        ) {
+            if components.aspect_ratio != *cache {
+                components.aspect_ratio = *cache;
+            }
+        }
+    }
 
     /// Implements a new property for [`Style`] component which expects an enum.
     macro_rules! impl_style_enum {
@@ -314,11 +501,6 @@ mod style {
         };
     }
 
-    impl_style_enum!(Display, "display", DisplayProperty, display,
-        "flex" => Flex,
-        "none" => None
-    );
-
     impl_style_enum!(PositionType, "position-type", PositionTypeProperty, position_type,
         "absolute" => Absolute,
         "relative" => Relative,
@@ -377,16 +559,12 @@ mod style {
         "space-around" => SpaceAround,
         "space-evenly" => SpaceEvenly,
     );
-
-    impl_style_enum!(Overflow, "overflow", OverflowProperty, overflow,
-        "visible" => Visible,
-        "hidden" => Hidden,
-    );
 }
 
 /// Impls for `bevy_text` [`Text`] component
 mod text {
     use super::*;
+    use crate::ess::property::calc::{ComputedFontSize, DEFAULT_FONT_SIZE};
     use crate::Defaults;
 
     #[derive(Default, Clone)]
@@ -416,6 +594,10 @@ mod text {
             true
         }
 
+        fn inherited() -> bool {
+            true
+        }
+
         fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
             values.color()
         }
@@ -451,6 +633,10 @@ mod text {
             true
         }
 
+        fn inherited() -> bool {
+            true
+        }
+
         fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
             if let Ok(path) = values.string() {
                 Ok(FontPath::Custom(path))
@@ -507,12 +693,16 @@ mod text {
     }
 
     /// Applies the `font-size` property on [`TextStyle::font_size`](`TextStyle`) property of all sections on matched [`Text`] components.
+    /// The value is also stored in the [`ComputedFontSize`] of every element to resolve the `em` & `rem` units.
     #[derive(Default)]
     pub(crate) struct FontSizeProperty;
 
     impl Property for FontSizeProperty {
         type Item = f32;
-        type Components = &'static mut Text;
+        type Components = (
+            Option<&'static mut Text>,
+            Option<&'static mut ComputedFontSize>,
+        );
         type Filters = With<Node>;
 
         fn name() -> Tag {
@@ -523,21 +713,38 @@ mod text {
             true
         }
 
+        fn inherited() -> bool {
+            true
+        }
+
+        /// The size of the text used by the default stylesheet.
+        fn initial() -> Self::Item {
+            DEFAULT_FONT_SIZE
+        }
+
         fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
             values.f32()
         }
 
         fn apply<'w>(
             cache: &Self::Item,
-            mut components: QueryItem<Self::Components>,
+            (text, computed): QueryItem<Self::Components>,
             _asset_server: &AssetServer,
-            _commands: &mut Commands,
-            _entity: Entity,
+            commands: &mut Commands,
+            entity: Entity,
         ) {
-            components
-                .sections
-                .iter_mut()
-                .for_each(|section| section.style.font_size = *cache);
+            if let Some(mut text) = text {
+                text.sections
+                    .iter_mut()
+                    .for_each(|section| section.style.font_size = *cache);
+            }
+            match computed {
+                Some(mut computed) if computed.0 != *cache => computed.0 = *cache,
+                Some(_) => {}
+                None => {
+                    commands.entity(entity).insert(ComputedFontSize(*cache));
+                }
+            }
         }
     }
 
@@ -711,3 +918,66 @@ impl Property for BackgroundColorProperty {
         commands.entity(components).insert(BackgroundColor(*cache));
     }
 }
+
+/// Applies the `z-index` property on [`ZIndex`] component of matched entities:
+/// ```css
+/// z-index: 10;
+/// z-index: global(100);
+/// z-index: auto;
+/// ```
+/// The plain number is [`ZIndex::Local`] and orders the node among its siblings,
+/// `global()` is [`ZIndex::Global`] and orders the node among all nodes.
+/// `auto` is the same as `0`.
+#[derive(Default)]
+pub(crate) struct ZIndexProperty;
+
+impl Property for ZIndexProperty {
+    type Item = ZIndex;
+    type Components = &'static mut ZIndex;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("z-index")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        use crate::ess::StylePropertyToken::*;
+        let error = || {
+            ElementsError::InvalidPropertyValue(format!(
+                "Expected integer, global(integer) or auto, got `{}`",
+                values.to_string()
+            ))
+        };
+        let integer = |value: f32| {
+            if value.fract() == 0. {
+                Ok(value as i32)
+            } else {
+                Err(error())
+            }
+        };
+        match values.tokens() {
+            [Identifier(ident)] if ident == "auto" => Ok(ZIndex::Local(0)),
+            [Number(value)] => integer(value.into()).map(ZIndex::Local),
+            [Function(name, args)] if name == "global" => {
+                let value = args.trim().parse::<f32>().map_err(|_| error())?;
+                integer(value).map(ZIndex::Global)
+            }
+            _ => Err(error()),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        mut components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        _commands: &mut Commands,
+        _entity: Entity,
+    ) {
+        // ZIndex has no PartialEq, so compare by hand to keep change detection quiet
+        match (*components, *cache) {
+            (ZIndex::Local(a), ZIndex::Local(b)) | (ZIndex::Global(a), ZIndex::Global(b))
+                if a == b => {}
+            _ => *components = *cache,
+        }
+    }
+}
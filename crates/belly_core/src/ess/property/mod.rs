@@ -1,8 +1,15 @@
+pub(crate) mod calc;
 pub mod colors;
 pub(crate) mod impls;
 mod style;
-use std::any::{type_name, Any};
+use std::{
+    any::{type_name, Any},
+    ops::Deref,
+};
 
+pub use self::calc::{
+    Calc, CalcAxis, CalcRect, CalcStyle, CalcVal, ComputedFontSize, RelativeUnits,
+};
 pub use self::colors::*;
 pub use self::style::StyleProperty;
 pub use self::style::StylePropertyMethods;
@@ -12,7 +19,10 @@ use crate::tags::*;
 use crate::{
     element::*,
     eml::Variant,
-    ess::{ElementsBranch, StyleSheet, Styles},
+    ess::{
+        functions::apply_dynamic, DynamicValue, ElementsBranch, Media, StyleRule, StyleSheet,
+        Styles,
+    },
     ElementsError,
 };
 use bevy::{
@@ -21,6 +31,7 @@ use bevy::{
     utils::HashMap,
 };
 use itertools::Itertools;
+use smallvec::{smallvec, SmallVec};
 
 pub struct ManagedPropertyValue(StyleProperty);
 
@@ -37,12 +48,36 @@ pub fn managed_default(default: &str) -> PropertyValue {
         }
     }
 }
-#[derive(Deref, Debug)]
-pub struct PropertyValue(Box<dyn Any + Send + Sync + 'static>);
+#[derive(Debug)]
+pub struct PropertyValue {
+    value: Box<dyn Any + Send + Sync + 'static>,
+    source: Option<String>,
+}
+
+impl Deref for PropertyValue {
+    type Target = Box<dyn Any + Send + Sync + 'static>;
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
 
 impl PropertyValue {
     pub fn new<T: Any + Send + Sync + 'static>(value: T) -> PropertyValue {
-        PropertyValue(Box::new(value))
+        PropertyValue {
+            value: Box::new(value),
+            source: None,
+        }
+    }
+
+    /// Attaches the text the value was parsed from.
+    pub fn with_source(mut self, source: impl Into<String>) -> PropertyValue {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Returns the text the value was parsed from, if known.
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
     }
 
     pub fn new_managed() -> PropertyValue {
@@ -53,17 +88,56 @@ impl PropertyValue {
     }
 
     pub fn is_managed(&self) -> bool {
-        self.0.is::<ManagedPropertyValue>()
+        self.value.is::<ManagedPropertyValue>()
+    }
+
+    /// Returns the css-wide keyword the value was specified with.
+    pub fn keyword(&self) -> Option<PropertyKeyword> {
+        self.value.downcast_ref::<PropertyKeyword>().copied()
     }
 
     pub fn managed_default(&self) -> Option<&StyleProperty> {
-        self.0.downcast_ref::<ManagedPropertyValue>().and_then(|s| {
-            if s.0.is_empty() {
-                None
-            } else {
-                Some(&s.0)
-            }
-        })
+        self.value
+            .downcast_ref::<ManagedPropertyValue>()
+            .and_then(|s| if s.0.is_empty() { None } else { Some(&s.0) })
+    }
+}
+
+/// The css-wide keywords accepted by any property:
+/// - `inherit` takes the value of the property from the parent element,
+/// - `initial` resets the property to its [`Property::initial`] value,
+/// - `unset` acts as `inherit` for [inherited](Property::inherited)
+/// properties and as `initial` for the rest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyKeyword {
+    Inherit,
+    Initial,
+    Unset,
+}
+
+impl PropertyKeyword {
+    pub fn parse(value: &StyleProperty) -> Option<PropertyKeyword> {
+        match value.tokens() {
+            [StylePropertyToken::Identifier(ident)] => Self::from_ident(ident),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn from_variant(variant: &Variant) -> Option<PropertyKeyword> {
+        match variant {
+            Variant::Style(value) => Self::parse(value),
+            Variant::String(value) => Self::from_ident(value.trim()),
+            _ => None,
+        }
+    }
+
+    fn from_ident(ident: &str) -> Option<PropertyKeyword> {
+        match ident {
+            "inherit" => Some(PropertyKeyword::Inherit),
+            "initial" => Some(PropertyKeyword::Initial),
+            "unset" => Some(PropertyKeyword::Unset),
+            _ => None,
+        }
     }
 }
 
@@ -123,6 +197,17 @@ pub trait Property: Default + Sized + Send + Sync + 'static {
         false
     }
 
+    /// Inherited properties take the value of the parent element
+    /// when no rule specifies the value for the element.
+    fn inherited() -> bool {
+        false
+    }
+
+    /// The value applied by the `initial` keyword.
+    fn initial() -> Self::Item {
+        Self::Item::default()
+    }
+
     fn docstring() -> &'static str {
         ""
     }
@@ -175,109 +260,245 @@ pub trait Property: Default + Sized + Send + Sync + 'static {
     /// The default implementation will cover most use cases, by just implementing [`apply`](Property::apply)
     fn apply_defaults(
         // mut cached_properties: Local<CachedProperties<Self>>,
-        mut components: Query<(Entity, Self::Components), (Changed<Element>, Self::Filters)>,
+        mut components: Query<(Entity, Self::Components), (ElementChanged, Self::Filters)>,
         mut commands: Commands,
         asset_server: Res<AssetServer>,
         styles: Res<Styles>,
         stylesheets: Res<Assets<StyleSheet>>,
         parents: Query<&Parent>,
-        elements: Query<&Element>,
+        elements: Query<ElementRef>,
+        frozen: Query<(), With<Frozen>>,
+        media: Res<Media>,
     ) {
         if components.is_empty() {
             return;
         }
         // info!("[prop] changed {}", components.iter().count());
+        // values of the `all` property apply to the unspecified properties
+        let all = tag!("all");
         // TODO: this should be cached
-        let mut rules: Vec<_> = styles
-            .iter()
-            .filter_map(|h| stylesheets.get(h))
-            .flat_map(|s| s.iter())
-            .filter(|r| r.properties.contains_key(&Self::name()))
-            .collect();
-        rules.sort_by_key(|r| -r.selector.weight);
-
-        for (entity, components) in components.iter_mut() {
+        let rules = active_rules(&styles, &stylesheets, &media, |r| {
+            let props = &r.properties;
+            props.contains_key(&Self::name()) || props.contains_key(&all)
+        });
+
+        'elements: for (entity, components) in components.iter_mut() {
             let Ok(element) = elements.get(entity) else { continue };
             if element.is_virtual() && !Self::affects_virtual_elements() {
                 continue;
             }
 
-            // extract default value
-            let mut element_with_default = element;
-            let mut entity_with_default = entity;
-            let mut default = None;
+            let mut tail = entity;
             loop {
-                if !element_with_default.is_virtual() {
-                    default = element_with_default.styles.get(&Self::name());
-                    break;
+                if frozen.contains(tail) {
+                    continue 'elements;
                 }
-                if let Ok(parent) = parents.get(entity_with_default) {
-                    entity_with_default = parent.get();
-                    if let Ok(element) = elements.get(entity_with_default) {
-                        element_with_default = element;
-                    } else {
-                        break;
-                    }
-                } else {
-                    break;
+                match parents.get(tail) {
+                    Ok(parent) => tail = parent.get(),
+                    Err(_) => break,
                 }
             }
-            if default.is_some() && default.unwrap().is_managed() {
-                continue;
-            }
 
-            // compute branch
-            let mut branch = ElementsBranch::new();
-            let mut tail = entity;
-            while let Ok(element) = elements.get(tail) {
-                if !element.is_virtual() {
-                    branch.insert(element);
-                }
-                if let Ok(parent) = parents.get(tail) {
-                    tail = parent.get();
-                } else {
-                    break;
+            let resolved = resolve_value(
+                Self::name(),
+                Self::inherited(),
+                entity,
+                &rules,
+                &elements,
+                &parents,
+            );
+            match resolved {
+                Resolved::Value(specified) => {
+                    if let Some(property) = specified.value.downcast_ref::<Self::Item>() {
+                        Self::apply(property, components, &asset_server, &mut commands, entity);
+                    } else if let Some(value) = specified.value.downcast_ref::<DynamicValue>() {
+                        apply_dynamic::<Self>(value, entity, &mut commands);
+                    } else {
+                        error!(
+                            "Unable to apply {} property: inconsistent Variant {:?}",
+                            Self::name(),
+                            specified.value
+                        );
+                    }
                 }
-            }
-            let property = default.or_else(|| {
-                rules
-                    .iter()
-                    .filter_map(|r| {
-                        if let Some(depth) = r.selector.match_depth(&branch) {
-                            Some((
-                                r.properties.get(&Self::name()).unwrap(),
-                                depth,
-                                r.selector.weight,
-                            ))
-                        } else {
-                            None
-                        }
-                    })
-                    .group_by(|(_prop, _depth, weight)| *weight)
-                    .into_iter()
-                    .map(|(_, group)| group)
-                    .next()
-                    .map(|properties| {
-                        let mut variants = properties.collect::<Vec<_>>();
-                        variants.sort_by_key(|(_prop, depth, _weight)| -(*depth as i16));
-                        let (value, _depth, _weight) = variants.pop().unwrap();
-                        value
-                    })
-            });
-
-            if let Some(property) = property {
-                if let Some(property) = property.downcast_ref::<Self::Item>() {
-                    Self::apply(property, components, &asset_server, &mut commands, entity);
-                } else {
-                    error!(
-                        "Unable to apply {} property: inconsistent Variant {:?}",
-                        Self::name(),
-                        property
+                Resolved::Initial => {
+                    Self::apply(
+                        &Self::initial(),
+                        components,
+                        &asset_server,
+                        &mut commands,
+                        entity,
                     );
                 }
+                Resolved::Managed | Resolved::None => {}
+            }
+        }
+    }
+}
+
+/// The rule of the active stylesheet.
+#[derive(Clone, Copy)]
+pub(crate) struct ActiveRule<'a> {
+    pub rule: &'a StyleRule,
+    pub stylesheet: &'a Handle<StyleSheet>,
+    /// The roots of the subtrees the rule is limited to,
+    /// `None` for the rules of the global stylesheets.
+    pub scope: Option<&'a [Entity]>,
+}
+
+/// Returns the rules of the active stylesheets matching the current media
+/// and the `filter`, the most specific rules go first.
+pub(crate) fn active_rules<'a, F: Fn(&StyleRule) -> bool>(
+    styles: &'a Styles,
+    stylesheets: &'a Assets<StyleSheet>,
+    media: &Media,
+    filter: F,
+) -> Vec<ActiveRule<'a>> {
+    let mut rules: Vec<_> = styles
+        .iter()
+        .filter_map(|h| stylesheets.get(h).map(|s| (h, s, styles.scope(h))))
+        .flat_map(|(stylesheet, s, scope)| {
+            s.iter().map(move |rule| ActiveRule {
+                rule,
+                stylesheet,
+                scope,
+            })
+        })
+        .filter(|r| r.rule.is_active(media) && filter(r.rule))
+        .collect();
+    rules.sort_by_key(|r| -r.rule.selector.weight);
+    rules
+}
+
+/// The value specified for the element by the inline style or the rule.
+pub(crate) struct Specified<'a> {
+    pub value: &'a PropertyValue,
+    /// The element the value is specified for, differs from the resolved
+    /// element for the inherited values and for the virtual elements.
+    pub owner: Entity,
+    /// The rule specified the value, `None` for the inline style.
+    pub rule: Option<ActiveRule<'a>>,
+}
+
+pub(crate) enum Resolved<'a> {
+    Value(Specified<'a>),
+    Initial,
+    /// The value of the element is managed outside of styles.
+    Managed,
+    None,
+}
+
+/// Resolves the value of the `name` property for the `entity`:
+/// follows the inheritance and the css-wide keywords.
+pub(crate) fn resolve_value<'a>(
+    name: Tag,
+    inherited: bool,
+    entity: Entity,
+    rules: &[ActiveRule<'a>],
+    elements: &'a Query<ElementRef>,
+    parents: &Query<&Parent>,
+) -> Resolved<'a> {
+    let mut target = entity;
+    loop {
+        let (owner, specified) = specified_value(name, target, rules, elements, parents);
+        let value = specified.as_ref().map(|s| s.value);
+        if value.map(|v| v.is_managed()).unwrap_or(false) {
+            if target == entity {
+                return Resolved::Managed;
             }
+            // the parent value is managed outside of styles
+            return Resolved::None;
+        }
+        let keyword = value.and_then(|v| v.keyword());
+        let inherit = match (specified, keyword) {
+            (_, Some(PropertyKeyword::Inherit)) => true,
+            (_, Some(PropertyKeyword::Unset)) => inherited,
+            (_, Some(PropertyKeyword::Initial)) => false,
+            (Some(specified), None) => return Resolved::Value(specified),
+            (None, None) => inherited,
+        };
+        let parent = parents.get(owner).map(|p| p.get());
+        match parent {
+            Ok(parent) if inherit && elements.contains(parent) => target = parent,
+            _ if keyword.is_some() => return Resolved::Initial,
+            _ => return Resolved::None,
+        }
+    }
+}
+
+/// Returns the value of the `name` property specified for the `entity`:
+/// the inline style or the value of the most specific matching rule.
+/// The `all` property competes with the `name` property by specificity
+/// and order, the `name` property wins when both are in the same rule.
+/// Rules without both properties and scoped rules applied outside
+/// of their scopes are skipped.
+/// Virtual elements use the values specified for the closest non-virtual
+/// ancestor, the entity of this ancestor is returned as the owner of the value.
+fn specified_value<'a>(
+    name: Tag,
+    entity: Entity,
+    rules: &[ActiveRule<'a>],
+    elements: &'a Query<ElementRef>,
+    parents: &Query<&Parent>,
+) -> (Entity, Option<Specified<'a>>) {
+    let mut owner = entity;
+    let element = loop {
+        match elements.get(owner) {
+            Ok(element) if !element.is_virtual() => break element,
+            Ok(_) => match parents.get(owner) {
+                Ok(parent) => owner = parent.get(),
+                Err(_) => return (owner, None),
+            },
+            Err(_) => return (owner, None),
+        }
+    };
+    let all = tag!("all");
+    let styles = &element.element.styles;
+    if let Some(value) = styles.get(&name).or_else(|| styles.get(&all)) {
+        let rule = None;
+        return (owner, Some(Specified { value, owner, rule }));
+    }
+
+    // compute branch
+    let mut branch = ElementsBranch::new();
+    let mut ancestors: SmallVec<[Entity; 16]> = smallvec![];
+    let mut tail = owner;
+    while let Ok(element) = elements.get(tail) {
+        ancestors.push(tail);
+        if !element.is_virtual() {
+            branch.insert(element);
+        }
+        if let Ok(parent) = parents.get(tail) {
+            tail = parent.get();
+        } else {
+            break;
         }
     }
+    let specified = rules
+        .iter()
+        .filter(|r| {
+            r.scope
+                .map(|roots| roots.iter().any(|root| ancestors.contains(root)))
+                .unwrap_or(true)
+        })
+        .filter_map(|r| {
+            let properties = &r.rule.properties;
+            let value = properties.get(&name).or_else(|| properties.get(&all))?;
+            let depth = r.rule.selector.match_depth(&branch)?;
+            Some((value, *r, depth, r.rule.selector.weight))
+        })
+        .group_by(|(_prop, _rule, _depth, weight)| *weight)
+        .into_iter()
+        .map(|(_, group)| group)
+        .next()
+        .map(|properties| {
+            let mut variants = properties.collect::<Vec<_>>();
+            variants.sort_by_key(|(_prop, _rule, depth, _weight)| -(*depth as i16));
+            let (value, rule, _depth, _weight) = variants.pop().unwrap();
+            let rule = Some(rule);
+            Specified { value, owner, rule }
+        });
+    (owner, specified)
 }
 
 pub trait CompoundProperty: Default + Sized + Send + Sync + 'static {
@@ -306,4 +527,49 @@ mod test {
         let value = "21% 22px";
         assert_eq!(Ok(expected), value.try_into());
     }
+
+    #[test]
+    fn parse_keywords() {
+        let keyword = |value: &str| PropertyKeyword::parse(&value.try_into().unwrap());
+        assert_eq!(keyword("inherit"), Some(PropertyKeyword::Inherit));
+        assert_eq!(keyword("initial"), Some(PropertyKeyword::Initial));
+        assert_eq!(keyword("unset"), Some(PropertyKeyword::Unset));
+        assert_eq!(keyword("inherit inherit"), None);
+        assert_eq!(keyword("red"), None);
+    }
+
+    crate::style_property! {
+        #[doc = " The glow of the item rarity."]
+        pub RarityGlowProperty("rarity-glow") {
+            Item = f32;
+            Components = &'static mut BackgroundColor;
+            Filters = With<Node>;
+            Parse = |v| v.f32();
+            Apply = |value, color, _assets, _commands, _entity| {
+                color.0 = Color::rgba(1., 0.8, 0., *value);
+            };
+        }
+    }
+
+    #[test]
+    fn declare_custom_property() {
+        assert_eq!(RarityGlowProperty::name(), tag!("rarity-glow"));
+        assert_eq!(
+            RarityGlowProperty::docstring(),
+            " The glow of the item rarity.\n"
+        );
+        let parse = |value: &str| RarityGlowProperty::parse(&value.try_into().unwrap());
+        assert_eq!(parse("0.5"), Ok(0.5));
+        assert!(parse("bright").is_err());
+    }
+
+    #[test]
+    fn parse_aspect_ratio() {
+        let ratio = |value: &str| impls::AspectRatioProperty::parse(&value.try_into().unwrap());
+        assert_eq!(ratio("16 / 9"), Ok(Some(16. / 9.)));
+        assert_eq!(ratio("1.5"), Ok(Some(1.5)));
+        assert_eq!(ratio("auto"), Ok(None));
+        assert!(ratio("1 / 0").is_err());
+        assert!(ratio("-1").is_err());
+    }
 }
@@ -3,13 +3,16 @@ use bevy::{
     ui::{UiRect, Val},
     utils::HashMap,
 };
-use cssparser::{BasicParseErrorKind, Token};
+use cssparser::{BasicParseErrorKind, ParseError, Parser, Token};
 use smallvec::SmallVec;
 use tagstr::Tag;
 
 use crate::ElementsError;
 
-use super::{colors, PropertyValue};
+use super::{
+    calc::{self, Calc, CalcRect, CalcVal},
+    colors, PropertyValue,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Hash)]
 pub struct Number([u8; 4]);
@@ -51,9 +54,10 @@ impl From<&Number> for f32 {
 pub enum StylePropertyToken {
     /// A value which was parsed percent value, like `100%` or `73.23%`.
     Percentage(Number),
-    /// A value which was parsed dimension value, like `10px` or `35em.
+    /// A value which was parsed dimension value, like `10px`.
     ///
-    /// Currently there is no distinction between [`length-values`](https://developer.mozilla.org/en-US/docs/Web/CSS/length).
+    /// All the absolute [`length-values`](https://developer.mozilla.org/en-US/docs/Web/CSS/length)
+    /// are treated as pixels, relative ones are parsed as [`Relative`](StylePropertyToken::Relative).
     Dimension(Number),
     /// A numeric float value, like `31.1` or `43`.
     Number(Number),
@@ -66,6 +70,19 @@ pub enum StylePropertyToken {
     /// Property delimiter (comma or slash)
     Slash,
     Comma,
+    /// A mixed `calc()` expression, like `calc(100% - 20px)`: percentage and pixels parts.
+    ///
+    /// Not mixed expressions are reduced to [`Percentage`](StylePropertyToken::Percentage)
+    /// or [`Dimension`](StylePropertyToken::Dimension) while parsing.
+    Calc(Number, Number),
+    /// A length relative to the font or the window size, like `2em` or `50vw`,
+    /// or the `calc()` expression containing such lengths: the [`Calc`] parts.
+    Relative([Number; 8]),
+    /// A flexible length in the `fr` units used by the grid tracks, like `1fr`.
+    Fraction(Number),
+    /// A function resolved by the property itself, like `conic(0..0.5)`:
+    /// the lowercase name and the raw source of the arguments.
+    Function(String, String),
 }
 
 impl StylePropertyToken {
@@ -79,6 +96,33 @@ impl StylePropertyToken {
             StylePropertyToken::String(v) => format!("\"{}\"", v),
             StylePropertyToken::Slash => format!("/"),
             StylePropertyToken::Comma => format!(","),
+            StylePropertyToken::Calc(percent, px) => {
+                format!("calc({}% + {}px)", percent.to_float(), px.to_float())
+            }
+            StylePropertyToken::Relative(numbers) => {
+                let calc = Calc::from_relative_numbers(numbers);
+                let parts = [
+                    (calc.percent, "%"),
+                    (calc.px, "px"),
+                    (calc.em, "em"),
+                    (calc.rem, "rem"),
+                    (calc.vw, "vw"),
+                    (calc.vh, "vh"),
+                    (calc.vmin, "vmin"),
+                    (calc.vmax, "vmax"),
+                ];
+                let parts: Vec<_> = parts
+                    .iter()
+                    .filter(|(value, _)| *value != 0.0)
+                    .map(|(value, unit)| format!("{value}{unit}"))
+                    .collect();
+                match parts.len() {
+                    1 => parts[0].clone(),
+                    _ => format!("calc({})", parts.join(" + ")),
+                }
+            }
+            StylePropertyToken::Fraction(v) => format!("{}fr", v.to_float()),
+            StylePropertyToken::Function(name, args) => format!("{name}({args})"),
         }
     }
 
@@ -95,6 +139,31 @@ impl StylePropertyToken {
         }
     }
 
+    pub(crate) fn calc_val(&self) -> Result<CalcVal, ElementsError> {
+        match self {
+            StylePropertyToken::Calc(percent, px) => {
+                Ok(CalcVal::Calc(Calc::from_numbers(percent, px)))
+            }
+            StylePropertyToken::Relative(numbers) => {
+                Ok(CalcVal::Calc(Calc::from_relative_numbers(numbers)))
+            }
+            token => token.val().map(CalcVal::Val),
+        }
+    }
+
+    /// Consumes the arguments block of the `name` function into the
+    /// [`Function`](StylePropertyToken::Function) token.
+    pub(crate) fn parse_function<'i, 't>(
+        name: &str,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<StylePropertyToken, ParseError<'i, ElementsError>> {
+        let start = input.position();
+        while input.next_including_whitespace_and_comments().is_ok() {}
+        let args = input.slice_from(start).trim().to_string();
+        let name = name.to_ascii_lowercase();
+        Ok(StylePropertyToken::Function(name, args))
+    }
+
     fn is_delimiter(&self) -> bool {
         match self {
             Self::Slash | Self::Comma => true,
@@ -112,11 +181,17 @@ impl<'i> TryFrom<Token<'i>> for StylePropertyToken {
             Token::Hash(val) => Ok(Self::Hash(val.to_string())),
             Token::IDHash(val) => Ok(Self::Hash(val.to_string())),
             Token::QuotedString(val) => Ok(Self::String(val.to_string())),
+            Token::UnquotedUrl(val) => Ok(Self::Function("url".to_string(), val.to_string())),
             Token::Number { value, .. } => Ok(Self::Number(value.into())),
             Token::Percentage { unit_value, .. } => {
                 Ok(Self::Percentage((unit_value * 100.0).into()))
             }
-            Token::Dimension { value, .. } => Ok(Self::Dimension(value.into())),
+            Token::Dimension { value, unit, .. } if unit.eq_ignore_ascii_case("fr") => {
+                Ok(Self::Fraction(value.into()))
+            }
+            Token::Dimension { value, unit, .. } => Calc::length(value, &unit)
+                .map(|calc| calc.to_token())
+                .map_err(|err| err.to_string()),
             Token::Comma => Ok(Self::Comma),
             Token::Delim(d) if d == '/' => Ok(Self::Slash),
             token => Err(format!("Invalid token: {:?}", token)),
@@ -180,6 +255,12 @@ impl From<&StyleProperty> for StyleProperty {
     }
 }
 
+impl FromIterator<StylePropertyToken> for StyleProperty {
+    fn from_iter<T: IntoIterator<Item = StylePropertyToken>>(iter: T) -> Self {
+        StyleProperty(iter.into_iter().collect())
+    }
+}
+
 pub trait StylePropertyMethods {
     fn tokens(&self) -> &[StylePropertyToken];
     fn hello(&self) {}
@@ -204,6 +285,21 @@ pub trait StylePropertyMethods {
         }
     }
 
+    /// Tries to parses the current values as a single path: the string literal
+    /// or the `url()` function, quoted or not.
+    fn url(&self) -> Result<String, ElementsError> {
+        match self.tokens() {
+            [StylePropertyToken::String(path)] => Ok(path.clone()),
+            [StylePropertyToken::Function(name, args)] if name == "url" => {
+                Ok(args.trim_matches(|c| c == '"' || c == '\'').to_string())
+            }
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected url() or string literal, got `{}`",
+                self.to_string()
+            ))),
+        }
+    }
+
     /// Tries to parses the current values as a single [`Option<UiRect>`].
     ///
     /// Optional values are handled by this function, so if only one value is present it is used as `top`, `right`, `bottom` and `left`,
@@ -239,6 +335,41 @@ pub trait StylePropertyMethods {
         }
     }
 
+    /// Tries to parses the current values as a single [`CalcRect`].
+    ///
+    /// Works the same way as [`rect`](StylePropertyMethods::rect) does, but also accepts
+    /// mixed `calc()` expressions for each side.
+    fn calc_rect(&self) -> Result<CalcRect, ElementsError> {
+        let props = self.tokens();
+        match props.len() {
+            1 => props[0].calc_val().map(CalcRect::all),
+            2 => {
+                let top_bottom = props[0].calc_val()?;
+                let left_right = props[1].calc_val()?;
+                Ok(CalcRect::new(
+                    left_right, left_right, top_bottom, top_bottom,
+                ))
+            }
+            3 => {
+                let top = props[0].calc_val()?;
+                let left_right = props[1].calc_val()?;
+                let bottom = props[2].calc_val()?;
+                Ok(CalcRect::new(left_right, left_right, top, bottom))
+            }
+            4 => {
+                let top = props[0].calc_val()?;
+                let right = props[1].calc_val()?;
+                let bottom = props[2].calc_val()?;
+                let left = props[3].calc_val()?;
+                Ok(CalcRect::new(left, right, top, bottom))
+            }
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Can't extract rect from `{}`",
+                props.to_string()
+            ))),
+        }
+    }
+
     fn rect_map(&self, prefix: &str) -> Result<HashMap<Tag, PropertyValue>, ElementsError> {
         let rect = self.tokens().rect()?;
         Ok(rect.to_rect_map(prefix))
@@ -256,10 +387,11 @@ pub trait StylePropertyMethods {
             )));
         }
         match &props[0] {
-            StylePropertyToken::Identifier(name) => colors::parse_named_color(name.as_str())
-                .ok_or_else(|| {
+            StylePropertyToken::Identifier(name) => {
+                colors::parse_named_color(name.to_ascii_lowercase().as_str()).ok_or_else(|| {
                     ElementsError::InvalidPropertyValue(format!("Unknown color name '{name}'"))
-                }),
+                })
+            }
             StylePropertyToken::Hash(hash) => colors::parse_hex_color(hash.as_str()),
             prop => {
                 return Err(ElementsError::InvalidPropertyValue(format!(
@@ -306,6 +438,19 @@ pub trait StylePropertyMethods {
         }
     }
 
+    /// Tries to parses the current values as a single [`CalcVal`].
+    ///
+    /// Works the same way as [`val`](StylePropertyMethods::val) does, but also accepts
+    /// mixed [`Calc`](PropertyToken::Calc) expressions.
+    fn calc_val(&self) -> Result<CalcVal, ElementsError> {
+        match self.tokens().iter().next() {
+            Some(token @ (StylePropertyToken::Calc(..) | StylePropertyToken::Relative(..))) => {
+                token.calc_val()
+            }
+            _ => self.val().map(CalcVal::Val),
+        }
+    }
+
     /// Tries to parses the current values as a single [`f32`].
     ///
     /// Only [`Percentage`](PropertyToken::Percentage), [`Dimension`](PropertyToken::Dimension`) and [`Number`](PropertyToken::Number`)
@@ -395,6 +540,30 @@ impl ToRectMap for UiRect {
     }
 }
 
+impl ToRectMap for CalcRect {
+    fn to_rect_map(&self, prefix: &str) -> HashMap<Tag, PropertyValue> {
+        let mut props = HashMap::default();
+        let prefix = prefix.to_string();
+        props.insert(
+            Tag::new(prefix.clone() + "-left"),
+            PropertyValue::new(self.left),
+        );
+        props.insert(
+            Tag::new(prefix.clone() + "-right"),
+            PropertyValue::new(self.right),
+        );
+        props.insert(
+            Tag::new(prefix.clone() + "-top"),
+            PropertyValue::new(self.top),
+        );
+        props.insert(
+            Tag::new(prefix.clone() + "-bottom"),
+            PropertyValue::new(self.bottom),
+        );
+        props
+    }
+}
+
 fn parse_style_propery_value(value: &str) -> Result<StyleProperty, ElementsError> {
     let mut input = cssparser::ParserInput::new(value);
     let mut parser = cssparser::Parser::new(&mut input);
@@ -402,6 +571,37 @@ fn parse_style_propery_value(value: &str) -> Result<StyleProperty, ElementsError
     loop {
         let next = parser.next();
         match next {
+            Ok(Token::Function(name)) if name.eq_ignore_ascii_case("calc") => {
+                values.push(parser.parse_nested_block(calc::parse_calc).map_err(|e| {
+                    ElementsError::InvalidPropertyValue(format!("Can't parse `{}`: {:?}", value, e))
+                })?)
+            }
+            Ok(Token::Function(name)) if colors::is_color_function(name) => {
+                let name = name.to_string();
+                values.push(
+                    parser
+                        .parse_nested_block(|p| colors::parse_color_function(&name, p))
+                        .map_err(|e| {
+                            ElementsError::InvalidPropertyValue(format!(
+                                "Can't parse `{}`: {:?}",
+                                value, e
+                            ))
+                        })?,
+                )
+            }
+            Ok(Token::Function(name)) => {
+                let name = name.to_string();
+                values.push(
+                    parser
+                        .parse_nested_block(|p| StylePropertyToken::parse_function(&name, p))
+                        .map_err(|e| {
+                            ElementsError::InvalidPropertyValue(format!(
+                                "Can't parse `{}`: {:?}",
+                                value, e
+                            ))
+                        })?,
+                )
+            }
             Ok(token) => values.push(token.clone().try_into().map_err(|e| {
                 ElementsError::InvalidPropertyValue(format!(
                     "Can't parse `{}` (invalid token `{:?}`: {:?}",
@@ -0,0 +1,700 @@
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{
+    asset::HandleId,
+    ecs::query::QueryItem,
+    math::Rect,
+    prelude::*,
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::DEFAULT_IMAGE_HANDLE,
+        Extract, RenderApp, RenderStage,
+    },
+    ui::{CalculatedClip, ExtractedUiNode, ExtractedUiNodes, RenderUiSystem, UiStack, UiSystem},
+    utils::{HashMap, HashSet},
+    window::WindowId,
+};
+use tagstr::*;
+
+/// The largest radius & blur of the generated corner textures.
+const MAX_CORNER_SIZE: f32 = 128.;
+
+pub(crate) struct RoundedPlugin;
+impl Plugin for RoundedPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_property::<BorderRadiusProperty>();
+        app.register_property::<BoxShadowProperty>();
+        app.init_resource::<RoundedTextures>();
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            prepare_rounded_textures.after(UiSystem::Flex),
+        );
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.add_system_to_stage(
+                RenderStage::Extract,
+                extract_rounded_nodes.after(RenderUiSystem::ExtractNode),
+            );
+        }
+    }
+}
+
+/// The radius of the node corners in pixels. The radius is clamped so the
+/// corners fit into the node. The [`BackgroundColor`], the [`UiImage`] with
+/// the 8-bit `rgba` texture and the border of the same width on all the
+/// sides are rounded, other images and borders keep square corners.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct BorderRadius(pub f32);
+
+/// The shadow drawn under the node background. The shadow is the copy of
+/// the (rounded) background moved by the `offset`, grown by the `spread`
+/// and softened by the `blur`, all values are in pixels.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct BoxShadow {
+    pub offset: Vec2,
+    pub blur: f32,
+    pub spread: f32,
+    pub color: Color,
+}
+
+/// Applies the `border-radius` property:
+/// ```css
+/// border-radius: 8px;
+/// border-radius: 0;
+/// ```
+/// Check [`BorderRadius`] for details.
+#[derive(Default)]
+pub(crate) struct BorderRadiusProperty;
+
+impl Property for BorderRadiusProperty {
+    type Item = f32;
+    type Components = Option<&'static mut BorderRadius>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("border-radius")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        match values.tokens() {
+            [StylePropertyToken::Dimension(radius) | StylePropertyToken::Number(radius)] => {
+                Ok(f32::from(radius).max(0.))
+            }
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected radius in px, got `{}`",
+                values.to_string()
+            ))),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match components {
+            Some(_) if *cache <= 0. => {
+                commands.entity(entity).remove::<BorderRadius>();
+            }
+            Some(mut radius) if radius.0 != *cache => radius.0 = *cache,
+            None if *cache > 0. => {
+                commands.entity(entity).insert(BorderRadius(*cache));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Applies the `box-shadow` property:
+/// ```css
+/// box-shadow: 2px 4px;
+/// box-shadow: 0px 4px 12px #00000080;
+/// box-shadow: 0px 0px 8px 2px rgba(0, 0, 0, 0.5);
+/// box-shadow: none;
+/// ```
+/// The lengths are `offset-x offset-y [blur [spread]]`, the color is
+/// `black` by default. Check [`BoxShadow`] for details.
+#[derive(Default)]
+pub(crate) struct BoxShadowProperty;
+
+impl Property for BoxShadowProperty {
+    type Item = Option<BoxShadow>;
+    type Components = Option<&'static mut BoxShadow>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("box-shadow")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        if let [StylePropertyToken::Identifier(ident)] = values.tokens() {
+            if ident == "none" {
+                return Ok(None);
+            }
+        }
+        let mut lengths = vec![];
+        let mut color = None;
+        for token in values.iter() {
+            let token = std::slice::from_ref(token);
+            match token {
+                [StylePropertyToken::Dimension(v) | StylePropertyToken::Number(v)] => {
+                    lengths.push(f32::from(v))
+                }
+                [StylePropertyToken::Identifier(_) | StylePropertyToken::Hash(_)]
+                    if color.is_none() =>
+                {
+                    color = Some(token.color()?)
+                }
+                _ => {
+                    return Err(ElementsError::InvalidPropertyValue(format!(
+                        "Unexpected `{}` in box-shadow",
+                        token.to_string()
+                    )))
+                }
+            }
+        }
+        if lengths.len() < 2 || lengths.len() > 4 {
+            return Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected 2-4 lengths in box-shadow, got `{}`",
+                values.to_string()
+            )));
+        }
+        Ok(Some(BoxShadow {
+            offset: Vec2::new(lengths[0], lengths[1]),
+            blur: lengths.get(2).copied().unwrap_or_default().max(0.),
+            spread: lengths.get(3).copied().unwrap_or_default(),
+            color: color.unwrap_or(Color::BLACK),
+        }))
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match (cache, components) {
+            (Some(shadow), Some(mut current)) if *current != *shadow => *current = *shadow,
+            (Some(shadow), None) => {
+                commands.entity(entity).insert(*shadow);
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<BoxShadow>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Identifies the generated texture of the rounded rect: the rect with
+/// `2 * (max(radius, border) + blur)` size, so it could be sliced by 9
+/// parts. The rect with the `border` is the ring of the border width.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct RoundedKey {
+    radius: u32,
+    blur: u32,
+    border: u32,
+}
+
+impl RoundedKey {
+    /// Returns the key of the rect with the `size`. The blur is at least
+    /// one pixel to antialias the edges, the radius is clamped so the
+    /// corners fit into the rect.
+    fn new(size: Vec2, radius: f32, blur: f32) -> RoundedKey {
+        let blur = blur.max(1.).min(MAX_CORNER_SIZE).round();
+        let fit = ((size.min_element() - blur) * 0.5).max(0.);
+        let radius = radius.min(fit).min(MAX_CORNER_SIZE).round();
+        RoundedKey {
+            radius: radius as u32,
+            blur: blur as u32,
+            border: 0,
+        }
+    }
+
+    /// Returns the key of the border ring of the rect with the `size`.
+    fn border(size: Vec2, radius: f32, border: f32) -> RoundedKey {
+        let fit = (size.min_element() * 0.5).max(0.);
+        RoundedKey {
+            border: border.min(fit).min(MAX_CORNER_SIZE).round() as u32,
+            ..RoundedKey::new(size, radius, 0.)
+        }
+    }
+
+    /// The size of the corner slice of the texture.
+    fn corner(&self) -> f32 {
+        (self.radius.max(self.border) + self.blur) as f32
+    }
+
+    /// Returns the opacity of the texture point `pos`.
+    fn alpha(&self, pos: Vec2) -> f32 {
+        let corner = self.corner();
+        let (radius, blur, border) = (self.radius as f32, self.blur as f32, self.border as f32);
+        // the edges of the rect are half of the blur away from the texture edges
+        let pos = pos - Vec2::splat(corner);
+        let half = Vec2::splat(corner - blur * 0.5);
+        let outer = coverage(pos, half, radius, blur);
+        if self.border == 0 {
+            return outer;
+        }
+        let inner = coverage(pos, half - border, (radius - border).max(0.), blur);
+        outer * (1. - inner)
+    }
+
+    fn to_image(&self) -> Image {
+        let size = (self.corner() * 2.) as u32;
+        let mut data = Vec::with_capacity((size * size * 4) as usize);
+        for y in 0..size {
+            for x in 0..size {
+                let alpha = self.alpha(Vec2::new(x as f32 + 0.5, y as f32 + 0.5));
+                data.extend([255, 255, 255, (alpha * 255.).round() as u8]);
+            }
+        }
+        Image::new(
+            Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+}
+
+/// Returns the coverage of the point `pos` by the rect with the `half`
+/// extents and the `radius` corners centered at the origin. The edges
+/// of the rect are smoothed over the `blur`.
+fn coverage(pos: Vec2, half: Vec2, radius: f32, blur: f32) -> f32 {
+    // the signed distance to the rounded rect
+    let p = pos.abs() - (half - radius);
+    let distance = p.max(Vec2::ZERO).length() + p.max_element().min(0.) - radius;
+    let t = ((distance + blur * 0.5) / blur).clamp(0., 1.);
+    1. - t * t * (3. - 2. * t)
+}
+
+/// Identifies the copy of the image with the rounded corners: the image
+/// stretched over the node with the `size` and the corners of the `radius`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct ImageKey {
+    image: HandleId,
+    size: UVec2,
+    radius: u32,
+}
+
+/// Returns the copy of the `image` stretched over the rect with the `size`
+/// with the corners of the `radius` cut off. Only the 8-bit `rgba` images
+/// could be rounded.
+fn round_image(image: &Image, size: Vec2, radius: f32) -> Option<Image> {
+    if !matches!(
+        image.texture_descriptor.format,
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+    ) {
+        return None;
+    }
+    let extent = image.texture_descriptor.size;
+    let pixels = Vec2::new(extent.width as f32, extent.height as f32);
+    if pixels.min_element() < 1. || size.min_element() <= 0. {
+        return None;
+    }
+    // the pixel of the image stretched over the rect
+    let scale = size / pixels;
+    let blur = scale.max_element().max(1.);
+    // the straight edges of the image stay opaque
+    let half = (size + blur) * 0.5;
+    let radius = radius + blur * 0.5;
+    let mut rounded = image.clone();
+    for (idx, pixel) in rounded.data.chunks_exact_mut(4).enumerate() {
+        let (x, y) = (idx as u32 % extent.width, idx as u32 / extent.width);
+        let pos = (Vec2::new(x as f32, y as f32) + 0.5) * scale - size * 0.5;
+        let alpha = coverage(pos, half, radius, blur);
+        pixel[3] = (pixel[3] as f32 * alpha).round() as u8;
+    }
+    Some(rounded)
+}
+
+/// The generated textures of the rounded rects and the rounded copies of
+/// the images. The textures unused by the nodes are dropped every frame.
+#[derive(Resource, Default)]
+struct RoundedTextures {
+    corners: HashMap<RoundedKey, Handle<Image>>,
+    images: HashMap<ImageKey, Handle<Image>>,
+}
+
+fn background_key(size: Vec2, radius: &BorderRadius) -> RoundedKey {
+    RoundedKey::new(size, radius.0, 0.)
+}
+
+fn image_key(size: Vec2, radius: &BorderRadius, image: &UiImage) -> ImageKey {
+    ImageKey {
+        image: image.0.id(),
+        size: size.round().as_uvec2(),
+        radius: background_key(size, radius).radius,
+    }
+}
+
+/// Returns the width of the border drawn along the rounded corners: the
+/// border should have the same width in pixels on all the sides.
+pub(crate) fn uniform_border(style: &Style) -> Option<f32> {
+    let UiRect {
+        left,
+        right,
+        top,
+        bottom,
+    } = style.border;
+    match left {
+        Val::Px(width) if width > 0. && [right, top, bottom].iter().all(|v| *v == left) => {
+            Some(width)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the key of the border ring of the rounded node.
+fn border_key(size: Vec2, radius: &BorderRadius, style: &Style) -> Option<RoundedKey> {
+    uniform_border(style).map(|border| RoundedKey::border(size, radius.0, border))
+}
+
+/// Returns the size of the shadow rect and the key of its texture.
+fn shadow_key(size: Vec2, radius: Option<&BorderRadius>, shadow: &BoxShadow) -> (Vec2, RoundedKey) {
+    let size = (size + Vec2::splat(shadow.spread * 2.)).max(Vec2::ZERO);
+    let radius = radius.map(|r| r.0).unwrap_or_default();
+    (size, RoundedKey::new(size, radius, shadow.blur))
+}
+
+fn prepare_rounded_textures(
+    mut textures: ResMut<RoundedTextures>,
+    mut images: ResMut<Assets<Image>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    nodes: Query<
+        (
+            &Node,
+            &Style,
+            Option<&BorderRadius>,
+            Option<&BoxShadow>,
+            Option<&BorderColor>,
+            Option<&UiImage>,
+        ),
+        Or<(With<BorderRadius>, With<BoxShadow>)>,
+    >,
+) {
+    // the rounded copies of the changed images are generated again
+    for event in image_events.iter() {
+        if let AssetEvent::Modified { handle } | AssetEvent::Removed { handle } = event {
+            textures.images.retain(|key, _| key.image != handle.id());
+        }
+    }
+    let mut corners = HashSet::new();
+    let mut rounded_images = HashSet::new();
+    for (node, style, radius, shadow, border_color, image) in nodes.iter() {
+        let size = node.size();
+        let background = radius.map(|r| background_key(size, r));
+        let border = radius
+            .filter(|_| border_color.is_some())
+            .and_then(|r| border_key(size, r, style));
+        let shadow = shadow.map(|s| shadow_key(size, radius, s).1);
+        for key in background.into_iter().chain(border).chain(shadow) {
+            corners.insert(key);
+            textures
+                .corners
+                .entry(key)
+                .or_insert_with(|| images.add(key.to_image()));
+        }
+        let (Some(radius), Some(image)) = (radius, image) else {
+            continue;
+        };
+        if image.0.id() == DEFAULT_IMAGE_HANDLE.id() {
+            continue;
+        }
+        let key = image_key(size, radius, image);
+        rounded_images.insert(key);
+        if textures.images.contains_key(&key) {
+            continue;
+        }
+        // the image is not loaded yet or can't be rounded
+        let Some(rounded) = images
+            .get(&image.0)
+            .and_then(|source| round_image(source, size, key.radius as f32))
+        else {
+            continue;
+        };
+        textures.images.insert(key, images.add(rounded));
+    }
+    textures.corners.retain(|key, _| corners.contains(key));
+    textures
+        .images
+        .retain(|key, _| rounded_images.contains(key));
+}
+
+fn extract_rounded_nodes(
+    mut extracted_uinodes: ResMut<ExtractedUiNodes>,
+    textures: Extract<Res<RoundedTextures>>,
+    ui_stack: Extract<Res<UiStack>>,
+    windows: Extract<Res<Windows>>,
+    uinode_query: Extract<
+        Query<(
+            &Node,
+            &GlobalTransform,
+            &Style,
+            Option<&UiImage>,
+            Option<&BorderRadius>,
+            Option<&BoxShadow>,
+            Option<&BorderColor>,
+            &ComputedVisibility,
+            Option<&CalculatedClip>,
+        )>,
+    >,
+) {
+    let scale_factor = windows.scale_factor(WindowId::primary()) as f32;
+    for (stack_index, entity) in ui_stack.uinodes.iter().enumerate() {
+        let Ok((uinode, style, transform, image, radius, shadow, border_color, visibility, clip)) =
+            uinode_query.get(*entity)
+        else {
+            continue;
+        };
+        if !visibility.is_visible() || (radius.is_none() && shadow.is_none()) {
+            continue;
+        }
+        let clip = clip.map(|clip| clip.clip);
+        let transform = transform.compute_matrix();
+        let nodes = &mut extracted_uinodes.uinodes;
+        let textured = image
+            .map(|i| i.0.id() != DEFAULT_IMAGE_HANDLE.id())
+            .unwrap_or(false);
+        let to_nodes = |slices: Vec<Slice>, transform: Mat4, key: RoundedKey| {
+            let atlas_size = Vec2::splat(key.corner() * 2.);
+            slices
+                .into_iter()
+                .map(move |(offset, scale, rect, image, color)| ExtractedUiNode {
+                    transform: transform
+                        * Mat4::from_translation(offset.extend(0.))
+                        * Mat4::from_scale(scale.extend(1.)),
+                    background_color: color,
+                    atlas_size: Some(atlas_size)
+                        .filter(|_| image.id() != DEFAULT_IMAGE_HANDLE.id()),
+                    rect,
+                    image,
+                    clip,
+                    scale_factor,
+                    stack_index,
+                })
+        };
+        if let Some(radius) = radius.filter(|_| !textured) {
+            let key = background_key(uinode.size(), radius);
+            let background = nodes.iter().position(|n| {
+                n.stack_index == stack_index && n.image.id() == DEFAULT_IMAGE_HANDLE.id()
+            });
+            if let (Some(idx), Some(texture)) = (background, textures.corners.get(&key)) {
+                let node = nodes.remove(idx);
+                let slices = slice_rounded_rect(key, texture, uinode.size(), node.background_color);
+                nodes.splice(idx..idx, to_nodes(slices, transform, key));
+            }
+        }
+        if let (Some(radius), Some(image)) = (radius.filter(|_| textured), image) {
+            let key = image_key(uinode.size(), radius, image);
+            let rounded = textures.images.get(&key);
+            let node = nodes
+                .iter_mut()
+                .find(|n| n.stack_index == stack_index && n.image.id() == image.0.id());
+            if let (Some(node), Some(rounded)) = (node, rounded) {
+                node.image = rounded.clone_weak();
+            }
+        }
+        // the square edges of the rounded node are not extracted
+        let border = radius
+            .zip(border_color)
+            .filter(|(_, color)| color.0.a() > 0.)
+            .and_then(|(r, color)| Some((border_key(uinode.size(), r, style)?, color.0)));
+        if let Some((key, color)) = border {
+            if let Some(texture) = textures.corners.get(&key) {
+                let slices = slice_rounded_rect(key, texture, uinode.size(), color);
+                nodes.extend(to_nodes(slices, transform, key));
+            }
+        }
+        if let Some(shadow) = shadow.filter(|s| s.color.a() > 0.) {
+            let (size, key) = shadow_key(uinode.size(), radius, shadow);
+            let Some(texture) = textures.corners.get(&key) else {
+                continue;
+            };
+            // the shadow goes under everything drawn for the node
+            let idx = nodes
+                .iter()
+                .position(|n| n.stack_index == stack_index)
+                .unwrap_or(nodes.len());
+            let slices = slice_rounded_rect(key, texture, size, shadow.color);
+            let transform = transform * Mat4::from_translation(shadow.offset.extend(0.));
+            nodes.splice(idx..idx, to_nodes(slices, transform, key));
+        }
+    }
+}
+
+/// The part of the sliced rect: the offset from the rect center, the scale,
+/// the region of the texture, the texture and the color.
+type Slice = (Vec2, Vec2, Rect, Handle<Image>, Color);
+
+/// Slices the rounded rect with the `size` by 9 parts: the corners and the
+/// edges are drawn with the texture of the `key`, the middle is the plain
+/// color. The middle of the border ring is empty.
+/// The rect is grown by the half of the blur on each side, so the edges of
+/// the shape stay where the rect edges are.
+fn slice_rounded_rect(
+    key: RoundedKey,
+    texture: &Handle<Image>,
+    size: Vec2,
+    color: Color,
+) -> Vec<Slice> {
+    let corner = key.corner();
+    let size = size + Vec2::splat(key.blur as f32);
+    // shrink the corners of the rect smaller than the corner slices
+    let k = (size.min_element() / (corner * 2.)).min(1.);
+    let drawn = corner * k;
+    let inner = size - Vec2::splat(drawn * 2.);
+    let half = (size - Vec2::splat(drawn)) * 0.5;
+    let texture = || texture.clone_weak();
+    let rect = |min: Vec2, max: Vec2| Rect { min, max };
+    let mut slices = vec![];
+    for (sx, sy) in [(-1., -1.), (1., -1.), (-1., 1.), (1., 1.)] {
+        let min = Vec2::new(sx + 1., sy + 1.) * 0.5 * corner;
+        slices.push((
+            half * Vec2::new(sx, sy),
+            Vec2::splat(k),
+            rect(min, min + corner),
+            texture(),
+            color,
+        ));
+    }
+    if inner.x > 0. {
+        for sy in [-1., 1.] {
+            let min = Vec2::new(corner - 0.5, (sy + 1.) * 0.5 * corner);
+            slices.push((
+                Vec2::new(0., half.y * sy),
+                Vec2::new(inner.x, k),
+                rect(min, min + Vec2::new(1., corner)),
+                texture(),
+                color,
+            ));
+        }
+    }
+    if inner.y > 0. {
+        for sx in [-1., 1.] {
+            let min = Vec2::new((sx + 1.) * 0.5 * corner, corner - 0.5);
+            slices.push((
+                Vec2::new(half.x * sx, 0.),
+                Vec2::new(k, inner.y),
+                rect(min, min + Vec2::new(corner, 1.)),
+                texture(),
+                color,
+            ));
+        }
+    }
+    if inner.x > 0. && inner.y > 0. && key.border == 0 {
+        slices.push((
+            Vec2::ZERO,
+            Vec2::ONE,
+            rect(Vec2::ZERO, inner),
+            DEFAULT_IMAGE_HANDLE.typed().clone_weak(),
+            color,
+        ));
+    }
+    slices
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_rounded_properties() {
+        let radius = |value: &str| BorderRadiusProperty::parse(&value.try_into().unwrap());
+        assert_eq!(radius("8px"), Ok(8.));
+        assert_eq!(radius("0"), Ok(0.));
+        assert!(radius("50%").is_err());
+
+        let shadow = |value: &str| BoxShadowProperty::parse(&value.try_into().unwrap());
+        assert_eq!(
+            shadow("2px 4px"),
+            Ok(Some(BoxShadow {
+                offset: Vec2::new(2., 4.),
+                blur: 0.,
+                spread: 0.,
+                color: Color::BLACK,
+            }))
+        );
+        let blurred = shadow("0px 4px 12px 2px red").unwrap().unwrap();
+        assert_eq!((blurred.blur, blurred.spread), (12., 2.));
+        assert_eq!(blurred.color, Color::RED);
+        assert_eq!(shadow("none"), Ok(None));
+        assert!(shadow("2px").is_err());
+        assert!(shadow("1px 2px red blue").is_err());
+    }
+
+    #[test]
+    fn rounded_texture() {
+        let key = RoundedKey::new(Vec2::new(100., 20.), 16., 0.);
+        // the radius is clamped to fit the rect
+        assert_eq!(
+            key,
+            RoundedKey {
+                radius: 10,
+                blur: 1,
+                border: 0,
+            }
+        );
+        // the outer corner is transparent, the middle is opaque
+        assert!(key.alpha(Vec2::new(0.5, 0.5)) < 0.01);
+        assert!(key.alpha(Vec2::splat(key.corner())) > 0.99);
+        // the straight edge is half transparent at the rect edge
+        let edge = key.alpha(Vec2::new(key.corner(), 0.5));
+        assert!((edge - 0.5).abs() < 0.01, "{edge}");
+    }
+
+    #[test]
+    fn rounded_border_texture() {
+        let key = RoundedKey::border(Vec2::new(100., 100.), 4., 8.);
+        assert_eq!((key.radius, key.border), (4, 8));
+        assert_eq!(key.corner(), 9.);
+        // the ring is opaque between the edges and transparent inside
+        assert!(key.alpha(Vec2::new(key.corner(), 4.5)) > 0.99);
+        assert!(key.alpha(Vec2::splat(key.corner())) < 0.01);
+    }
+
+    #[test]
+    fn rounded_image() {
+        let image = Image::new_fill(
+            Extent3d {
+                width: 10,
+                height: 10,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[255, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        let rounded = round_image(&image, Vec2::new(100., 100.), 40.).unwrap();
+        let alpha = |x: usize, y: usize| rounded.data[(y * 10 + x) * 4 + 3];
+        assert!(alpha(0, 0) < 8, "The corner should be cut off");
+        assert_eq!(alpha(5, 0), 255, "The edge should stay opaque");
+        assert_eq!(alpha(5, 5), 255);
+    }
+
+    #[test]
+    fn uniform_borders() {
+        let style = |border| Style {
+            border,
+            ..default()
+        };
+        assert_eq!(uniform_border(&style(UiRect::all(Val::Px(2.)))), Some(2.));
+        assert_eq!(uniform_border(&style(UiRect::all(Val::Px(0.)))), None);
+        assert_eq!(
+            uniform_border(&style(UiRect::new(
+                Val::Px(1.),
+                Val::Px(2.),
+                Val::Px(1.),
+                Val::Px(1.)
+            ))),
+            None
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use crate::{eml::Variant, ess::*, Element, ElementsError, PropertyExtractor, PropertyTransformer};
+use bevy::{ecs::system::Command, prelude::*};
+use tagstr::*;
+
+/// Commands to change the styles at runtime. Elements affected by the
+/// change are restyled automatically:
+/// ```rust,ignore
+/// fn enable_colorblind_mode(mut commands: Commands) {
+///     commands.add_style_rule(".hint", "color: #0077bb; font: bold");
+///     commands.remove_rules_matching(".hint.red");
+/// }
+/// ```
+/// Inline styles of the single element are changed the same way the
+/// `s:`-prefixed params specify them in eml:
+/// ```rust,ignore
+/// fn highlight(mut commands: Commands, selected: Query<Entity, Added<Selected>>) {
+///     for entity in selected.iter() {
+///         commands.set_inline_style(entity, "background-color", "#202020");
+///     }
+/// }
+/// ```
+pub trait StyleCommandsExtension {
+    /// Adds the rule with the `selector` and the `properties` declarations
+    /// (`"color: red; font-size: 20px"`). Rules added at runtime are kept
+    /// in the separate stylesheet and win over the rules of stylesheets
+    /// loaded before the first rule was added.
+    fn add_style_rule(&mut self, selector: &str, properties: &str);
+    /// Removes the rules with the `selector` from all active stylesheets.
+    /// Stylesheets loaded from files get the rules back when reloaded.
+    fn remove_rules_matching(&mut self, selector: &str);
+    /// Deactivates the `old` stylesheet and activates the `new` one with
+    /// the weight of the `old` one. Rules of the `old` stylesheet stay applied
+    /// until the `new` one is loaded.
+    fn replace_stylesheet(&mut self, old: Handle<StyleSheet>, new: Handle<StyleSheet>);
+    /// Sets the inline style of the `entity` element: the `value` of
+    /// the property `name` wins over any rule of the stylesheets.
+    /// Compound properties (`padding`, `border`) set all their parts.
+    fn set_inline_style(&mut self, entity: Entity, name: &str, value: &str);
+    /// Removes the inline style of the `entity` element, the value of
+    /// the property is specified by the stylesheets again. Compound
+    /// properties are removed by their parts (`padding-left`, ...).
+    fn remove_inline_style(&mut self, entity: Entity, name: &str);
+}
+
+impl<'w, 's> StyleCommandsExtension for Commands<'w, 's> {
+    fn add_style_rule(&mut self, selector: &str, properties: &str) {
+        self.add(AddRuleCommand {
+            selector: selector.to_string(),
+            properties: properties.to_string(),
+        });
+    }
+
+    fn remove_rules_matching(&mut self, selector: &str) {
+        self.add(RemoveRulesCommand {
+            selector: selector.to_string(),
+        });
+    }
+
+    fn replace_stylesheet(&mut self, old: Handle<StyleSheet>, new: Handle<StyleSheet>) {
+        self.add(ReplaceStyleSheetCommand { old, new });
+    }
+
+    fn set_inline_style(&mut self, entity: Entity, name: &str, value: &str) {
+        self.add(SetInlineStyleCommand {
+            entity,
+            name: name.as_tag(),
+            value: value.to_string(),
+        });
+    }
+
+    fn remove_inline_style(&mut self, entity: Entity, name: &str) {
+        self.add(RemoveInlineStyleCommand {
+            entity,
+            name: name.as_tag(),
+        });
+    }
+}
+
+fn parser(world: &World) -> StyleSheetParser {
+    let extractor = world.resource::<PropertyExtractor>().clone();
+    let transformer = world.resource::<PropertyTransformer>().clone();
+    StyleSheetParser::new(transformer, extractor)
+}
+
+/// Transforms the inline style the same way the declaration of the rule
+/// is transformed: compound properties are extracted into their parts.
+pub(crate) fn transform_inline_style(
+    transformer: &PropertyTransformer,
+    extractor: &PropertyExtractor,
+    name: Tag,
+    value: Variant,
+) -> Result<Vec<(Tag, PropertyValue)>, ElementsError> {
+    if extractor.is_compound_property(name) {
+        return Ok(extractor.extract(name, value)?.drain().collect());
+    }
+    let source = match &value {
+        Variant::String(source) => Some(source.trim().to_string()),
+        _ => None,
+    };
+    let value = transformer.transform(name, value)?;
+    Ok(vec![match source {
+        Some(source) => (name, value.with_source(source)),
+        None => (name, value),
+    }])
+}
+
+/// Returns the normalized form of the `selector` to compare it with
+/// the selectors of the parsed rules.
+fn normalize_selector(parser: &StyleSheetParser, selector: &str) -> Option<String> {
+    let rules = parser.parse(&format!("{selector} {{}}"));
+    rules.first().map(|rule| rule.selector.to_string())
+}
+
+pub struct AddRuleCommand {
+    selector: String,
+    properties: String,
+}
+
+impl Command for AddRuleCommand {
+    fn write(self, world: &mut World) {
+        let source = format!("{} {{ {} }}", self.selector, self.properties);
+        let rules = parser(world).parse(&source);
+        if rules.is_empty() {
+            error!("Unable to add the style rule `{source}`");
+            return;
+        }
+        let world = world.cell();
+        let mut styles = world.resource_mut::<Styles>();
+        let mut assets = world.resource_mut::<Assets<StyleSheet>>();
+        match styles.runtime.as_ref().and_then(|h| assets.get_mut(h)) {
+            Some(stylesheet) => {
+                for rule in rules {
+                    stylesheet.add_rule(rule);
+                }
+            }
+            None => {
+                let handle = assets.add(StyleSheet::new(rules));
+                styles.insert(handle.clone());
+                styles.runtime = Some(handle);
+            }
+        }
+    }
+}
+
+pub struct RemoveRulesCommand {
+    selector: String,
+}
+
+impl Command for RemoveRulesCommand {
+    fn write(self, world: &mut World) {
+        let Some(selector) = normalize_selector(&parser(world), &self.selector) else {
+            error!(
+                "Unable to remove rules, invalid selector `{}`",
+                self.selector
+            );
+            return;
+        };
+        let world = world.cell();
+        let styles = world.resource::<Styles>();
+        let mut assets = world.resource_mut::<Assets<StyleSheet>>();
+        for handle in styles.iter() {
+            let matches = |rule: &StyleRule| rule.selector.to_string() == selector;
+            // get_mut marks the stylesheet as modified, so it is called
+            // only for the stylesheets with the matching rules
+            if assets
+                .get(handle)
+                .map(|s| s.iter().any(matches))
+                .unwrap_or(false)
+            {
+                assets
+                    .get_mut(handle)
+                    .unwrap()
+                    .retain_rules(|rule| !matches(rule));
+            }
+        }
+    }
+}
+
+pub struct ReplaceStyleSheetCommand {
+    old: Handle<StyleSheet>,
+    new: Handle<StyleSheet>,
+}
+
+impl Command for ReplaceStyleSheetCommand {
+    fn write(self, world: &mut World) {
+        let world = world.cell();
+        let mut styles = world.resource_mut::<Styles>();
+        styles.replace(&self.old, self.new.clone());
+        // the loaded stylesheet is marked as modified to restyle
+        // the elements, otherwise it is done when the loading completes
+        let _ = world
+            .resource_mut::<Assets<StyleSheet>>()
+            .get_mut(&self.new);
+    }
+}
+
+pub struct SetInlineStyleCommand {
+    entity: Entity,
+    name: Tag,
+    value: String,
+}
+
+impl Command for SetInlineStyleCommand {
+    fn write(self, world: &mut World) {
+        let transformer = world.resource::<PropertyTransformer>().clone();
+        let extractor = world.resource::<PropertyExtractor>().clone();
+        let value = Variant::String(self.value);
+        let styles = match transform_inline_style(&transformer, &extractor, self.name, value) {
+            Ok(styles) => styles,
+            Err(e) => {
+                error!("Ignoring inline style {}: {}", self.name, e);
+                return;
+            }
+        };
+        match world.get_mut::<Element>(self.entity) {
+            Some(mut element) => element.styles.extend(styles),
+            None => error!(
+                "Unable to set inline style {}, {:?} is not an element",
+                self.name, self.entity
+            ),
+        }
+    }
+}
+
+pub struct RemoveInlineStyleCommand {
+    entity: Entity,
+    name: Tag,
+}
+
+impl Command for RemoveInlineStyleCommand {
+    fn write(self, world: &mut World) {
+        let Some(element) = world.get::<Element>(self.entity) else { return };
+        // Element is accessed mutably only when the style is
+        // actually removed, so nothing is restyled otherwise
+        if element.styles.contains_key(&self.name) {
+            world
+                .get_mut::<Element>(self.entity)
+                .unwrap()
+                .styles
+                .remove(&self.name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TransformProperty;
+    use bevy::{asset::HandleId, utils::HashMap};
+
+    #[test]
+    fn replace_keeps_weight() {
+        let handle = || Handle::<StyleSheet>::weak(HandleId::random::<StyleSheet>());
+        let mut styles = Styles::default();
+        let (first, second, third) = (handle(), handle(), handle());
+        styles.insert(first.clone());
+        styles.insert(second.clone());
+        styles.replace(&first, third.clone());
+        assert_eq!(styles.weight(&third), 1);
+        assert_eq!(styles.weight(&second), 2);
+        assert_eq!(styles.weight(&first), 0);
+        assert!(styles.is_removed(&first));
+        styles.insert(first.clone());
+        assert!(!styles.is_removed(&first));
+    }
+
+    fn transform(variant: Variant) -> Result<PropertyValue, ElementsError> {
+        match variant {
+            Variant::String(value) => Ok(PropertyValue::new(value)),
+            _ => Err(ElementsError::InvalidPropertyValue(
+                "Expected string".to_string(),
+            )),
+        }
+    }
+
+    #[test]
+    fn set_and_remove_inline_style() {
+        let mut transformers: HashMap<Tag, TransformProperty> = Default::default();
+        transformers.insert(tag!("width"), transform);
+        let mut world = World::new();
+        world.insert_resource(PropertyTransformer::new(transformers));
+        world.insert_resource(PropertyExtractor::default());
+        let entity = world.spawn(Element::default()).id();
+
+        let set = |name: &str, value: &str| SetInlineStyleCommand {
+            entity,
+            name: name.as_tag(),
+            value: value.to_string(),
+        };
+        set("width", " 20px ").write(&mut world);
+        set("height", "10px").write(&mut world);
+        let styles = &world.get::<Element>(entity).unwrap().styles;
+        assert_eq!(styles.len(), 1);
+        assert_eq!(styles.get(&tag!("width")).unwrap().source(), Some("20px"));
+
+        RemoveInlineStyleCommand {
+            entity,
+            name: tag!("width"),
+        }
+        .write(&mut world);
+        assert!(world.get::<Element>(entity).unwrap().styles.is_empty());
+    }
+}
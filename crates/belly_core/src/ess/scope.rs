@@ -0,0 +1,77 @@
+use crate::{ess::*, Elements};
+use bevy::prelude::*;
+
+pub(crate) struct StyleScopePlugin;
+impl Plugin for StyleScopePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(update_style_scopes);
+    }
+}
+
+/// Attaches the stylesheet to the entity: the rules of the stylesheet
+/// are applied only to the entity and its descendants, so the styles
+/// of the widget library don't leak into the rest of the UI:
+/// ```rust,ignore
+/// commands
+///     .entity(inventory)
+///     .insert(ScopedStyleSheet(asset_server.load("inventory.ess")));
+/// ```
+/// The same stylesheet could be attached to several entities. Once
+/// attached, the stylesheet is never applied globally, even when the
+/// component is removed. Scoped rules keep their weights, so the rules
+/// of the stylesheets loaded later still win over them.
+#[derive(Component, Clone, Debug)]
+pub struct ScopedStyleSheet(pub Handle<StyleSheet>);
+
+fn update_style_scopes(
+    mut styles: ResMut<Styles>,
+    mut elements: Elements,
+    scoped: Query<(Entity, &ScopedStyleSheet), Changed<ScopedStyleSheet>>,
+    removed: RemovedComponents<ScopedStyleSheet>,
+) {
+    let mut changed = false;
+    for root in removed.iter().chain(scoped.iter().map(|(root, _)| root)) {
+        styles
+            .scopes
+            .values_mut()
+            .for_each(|roots| roots.retain(|r| *r != root));
+        changed = true;
+    }
+    for (root, stylesheet) in scoped.iter() {
+        styles
+            .scopes
+            .entry(stylesheet.0.clone())
+            .or_default()
+            .push(root);
+        styles.insert(stylesheet.0.clone());
+    }
+    // the stylesheet could have been applied globally before
+    // it was attached, so all the elements are restyled
+    if changed {
+        elements.invalidate_all();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::asset::HandleId;
+
+    #[test]
+    fn attach_scoped_stylesheet() {
+        let mut world = World::new();
+        world.init_resource::<Styles>();
+        let stylesheet = Handle::<StyleSheet>::weak(HandleId::random::<StyleSheet>());
+        let root = world.spawn(ScopedStyleSheet(stylesheet.clone())).id();
+        let mut stage = SystemStage::single(update_style_scopes);
+        stage.run(&mut world);
+        let styles = world.resource::<Styles>();
+        assert_eq!(styles.scope(&stylesheet), Some(&[root][..]));
+        assert_eq!(styles.weight(&stylesheet), 1);
+
+        world.entity_mut(root).remove::<ScopedStyleSheet>();
+        stage.run(&mut world);
+        let styles = world.resource::<Styles>();
+        assert_eq!(styles.scope(&stylesheet), Some(&[][..]));
+    }
+}
@@ -4,7 +4,7 @@ use bevy::prelude::{default, Changed, Entity, Parent, Query};
 use smallvec::{smallvec, SmallVec};
 use tagstr::Tag;
 
-use crate::Element;
+use crate::element::{Element, ElementRef, ElementRefItem};
 
 #[derive(Copy, Clone, PartialEq, Eq, Ord, Default, Debug)]
 pub struct SelectorWeight(pub(crate) i32, pub(crate) i32);
@@ -318,14 +318,14 @@ pub trait EmlNode: Sized {
 }
 
 #[derive(Default)]
-pub struct ElementsBranch<'e>(SmallVec<[&'e Element; 12]>);
+pub struct ElementsBranch<'e>(SmallVec<[ElementRefItem<'e>; 12]>);
 
 impl<'e> ElementsBranch<'e> {
     pub fn new() -> ElementsBranch<'e> {
         ElementsBranch::default()
     }
 
-    pub fn insert(&mut self, element: &'e Element) {
+    pub fn insert(&mut self, element: ElementRefItem<'e>) {
         self.0.push(element);
     }
 
@@ -335,16 +335,16 @@ impl<'e> ElementsBranch<'e> {
             if node.is_virtual() {
                 continue;
             }
-            for name in node.names.iter() {
+            for name in node.names() {
                 result.push_str(&format!("{name}"));
             }
-            if let Some(id) = node.id {
+            if let Some(id) = node.element_id() {
                 result.push_str(&format!("#{}", id));
             }
-            for class in node.classes.iter() {
+            for class in node.classes.iter().flat_map(|c| c.iter()) {
                 result.push_str(&format!(".{}", class));
             }
-            for state in node.state.iter() {
+            for state in node.states.iter().flat_map(|s| s.iter()) {
                 result.push_str(&format!(":{}", state));
             }
             if idx != 0 {
@@ -361,24 +361,18 @@ pub struct ElementNode<'b, 'e> {
 
 impl<'b, 'e> EmlNode for ElementNode<'b, 'e> {
     fn id(&self) -> Option<Tag> {
-        self.branch.0[self.idx].id
+        self.branch.0[self.idx].element_id()
     }
     fn has_tag(&self, tag: &Tag) -> bool {
-        self.branch.0[self.idx]
-            .names
-            .iter()
-            .chain(self.branch.0[self.idx].aliases.iter())
-            .filter(|t| *t == tag)
-            .next()
-            .is_some()
+        self.branch.0[self.idx].has_tag(tag)
     }
 
     fn has_class(&self, class: &Tag) -> bool {
-        self.branch.0[self.idx].classes.contains(class)
+        self.branch.0[self.idx].has_class(class)
     }
 
     fn has_state(&self, tag: &Tag) -> bool {
-        self.branch.0[self.idx].state.contains(tag)
+        self.branch.0[self.idx].has_state(tag)
     }
 
     fn next(&self) -> Option<Self> {
@@ -406,7 +400,7 @@ impl<'b, 'e> EmlBranch for &'b ElementsBranch<'e> {
 fn _example(
     entities: Query<Entity, Changed<Element>>,
     parents: Query<&Parent>,
-    elements: Query<&Element>,
+    elements: Query<ElementRef>,
 ) {
     for entity in entities.iter() {
         // build branch for each entity
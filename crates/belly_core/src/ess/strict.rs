@@ -0,0 +1,187 @@
+use bevy::{
+    prelude::*,
+    render::{Extract, RenderApp, RenderStage},
+    ui::{CalculatedClip, ExtractedUiNodes, RenderUiSystem, UiStack, UiSystem},
+    utils::HashSet,
+    window::WindowId,
+};
+
+use super::border::{extract_edges, Edges};
+use super::{ElementsBranch, Media, StyleSheet, Styles};
+use crate::element::{ElementChanged, ElementRef, Frozen};
+
+const OUTLINE_COLOR: Color = Color::FUCHSIA;
+const OUTLINE_WIDTH: f32 = 1.;
+
+/// Development helper which flags the elements nobody cared to style:
+/// ```rust
+/// use bevy::prelude::*;
+/// use belly_core::ess::StrictStylesPlugin;
+///
+/// fn main() {
+///    let mut app = App::new();
+///    app.add_plugins(DefaultPlugins);
+///    app.add_plugin(belly_core::ElementsCorePlugin);
+///    app.add_plugin(StrictStylesPlugin);
+/// }
+/// ```
+/// The element is flagged with the [`Unstyled`] component and the magenta
+/// outline when it matches no style rules or when it has zero size after
+/// the layout. Universal rules (`*`) are not taken into account, so the
+/// element with a typo in its tag or class is flagged. Every problem is
+/// logged once per element.
+pub struct StrictStylesPlugin;
+
+impl Plugin for StrictStylesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            check_unstyled_elements.after(UiSystem::Flex),
+        );
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.add_system_to_stage(
+                RenderStage::Extract,
+                extract_unstyled_outlines.after(RenderUiSystem::ExtractNode),
+            );
+        }
+    }
+}
+
+/// The reason the element was flagged by the [`StrictStylesPlugin`].
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Unstyled {
+    /// The element matches no style rules and has no inline styles.
+    NoMatchingRules,
+    /// The element is displayed but has zero width or height.
+    ZeroSize,
+}
+
+fn check_unstyled_elements(
+    mut commands: Commands,
+    mut reported: Local<HashSet<(Entity, Unstyled)>>,
+    styles: Res<Styles>,
+    stylesheets: Res<Assets<StyleSheet>>,
+    media: Res<Media>,
+    changed: Query<Entity, Or<(ElementChanged, Changed<Node>)>>,
+    elements: Query<ElementRef>,
+    nodes: Query<(&Node, &Style)>,
+    parents: Query<&Parent>,
+    frozen: Query<(), With<Frozen>>,
+    unstyled: Query<&Unstyled>,
+) {
+    if changed.is_empty() {
+        return;
+    }
+    let rules: Vec<_> = styles
+        .iter()
+        .filter_map(|h| stylesheets.get(h))
+        .flat_map(|s| s.iter())
+        .filter(|r| r.selector.weight.0 > 0 && r.is_active(&media))
+        .collect();
+
+    'elements: for entity in changed.iter() {
+        let Ok(element) = elements.get(entity) else { continue };
+        if element.is_virtual() {
+            continue;
+        }
+        let inline = !element.styles.is_empty();
+
+        let mut branch = ElementsBranch::new();
+        let mut displayed = true;
+        let mut tail = entity;
+        while let Ok(element) = elements.get(tail) {
+            if frozen.contains(tail) {
+                continue 'elements;
+            }
+            if let Ok((_, style)) = nodes.get(tail) {
+                displayed &= style.display != Display::None;
+            }
+            if !element.is_virtual() {
+                branch.insert(element);
+            }
+            if let Ok(parent) = parents.get(tail) {
+                tail = parent.get();
+            } else {
+                break;
+            }
+        }
+
+        let size = nodes.get(entity).map(|(n, _)| n.size()).unwrap_or_default();
+        let problem = if !inline && !rules.iter().any(|r| r.selector.matches(&branch)) {
+            Some(Unstyled::NoMatchingRules)
+        } else if displayed && (size.x == 0. || size.y == 0.) {
+            Some(Unstyled::ZeroSize)
+        } else {
+            None
+        };
+
+        match problem {
+            Some(problem) => {
+                if unstyled.get(entity).ok() != Some(&problem) {
+                    commands.entity(entity).insert(problem);
+                }
+                if reported.insert((entity, problem)) {
+                    let path = branch.to_string();
+                    match problem {
+                        Unstyled::NoMatchingRules => {
+                            warn!("Element {entity:?} `{path}` matches no style rules")
+                        }
+                        Unstyled::ZeroSize => {
+                            warn!("Element {entity:?} `{path}` has zero size")
+                        }
+                    }
+                }
+            }
+            None if unstyled.contains(entity) => {
+                commands.entity(entity).remove::<Unstyled>();
+            }
+            None => {}
+        }
+    }
+}
+
+fn extract_unstyled_outlines(
+    mut extracted_uinodes: ResMut<ExtractedUiNodes>,
+    ui_stack: Extract<Res<UiStack>>,
+    windows: Extract<Res<Windows>>,
+    uinode_query: Extract<
+        Query<
+            (
+                &Node,
+                &GlobalTransform,
+                &ComputedVisibility,
+                Option<&CalculatedClip>,
+            ),
+            With<Unstyled>,
+        >,
+    >,
+) {
+    let scale_factor = windows.scale_factor(WindowId::primary()) as f32;
+    for (stack_index, entity) in ui_stack.uinodes.iter().enumerate() {
+        let Ok((uinode, transform, visibility, clip)) = uinode_query.get(*entity)
+        else {
+            continue;
+        };
+        if !visibility.is_visible() {
+            continue;
+        }
+        // make zero sized nodes visible too
+        let size = uinode.size().max(Vec2::splat(OUTLINE_WIDTH * 2.));
+        let edges = Edges {
+            left: OUTLINE_WIDTH,
+            right: OUTLINE_WIDTH,
+            top: OUTLINE_WIDTH,
+            bottom: OUTLINE_WIDTH,
+            color: OUTLINE_COLOR,
+        };
+        extract_edges(
+            &mut extracted_uinodes,
+            transform,
+            size,
+            edges,
+            clip.map(|clip| clip.clip),
+            scale_factor,
+            stack_index,
+        );
+    }
+}
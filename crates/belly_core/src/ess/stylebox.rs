@@ -8,6 +8,7 @@ pub struct StyleboxPropertyPlugin;
 impl Plugin for StyleboxPropertyPlugin {
     fn build(&self, app: &mut App) {
         app.register_compound_property::<StyleboxProperty>();
+        app.register_compound_property::<BorderImageProperty>();
         app.register_property::<StyleboxSourceProperty>();
         app.register_property::<StyleboxModulateProperty>();
         app.register_property::<StyleboxRegionProperty>();
@@ -42,7 +43,7 @@ compound_style_property! {
         let mut stream = props.as_stream();
         let mut result = HashMap::default();
         if let Some(path) = stream.single() {
-            result.insert(tag!("stylebox-source"), PropertyValue::new(path.string()?));
+            result.insert(tag!("stylebox-source"), PropertyValue::new(Some(path.url()?)));
         }
         if let Some(slice) = stream.compound() {
             result.insert(tag!("stylebox-slice"), PropertyValue::new(slice.rect()?));
@@ -60,16 +61,70 @@ compound_style_property! {
     }
 }
 
+compound_style_property! {
+    #[doc = " Specify the image drawn over the element sliced by 9 parts, so the"]
+    #[doc = " corners keep their size while the edges and the middle are stretched."]
+    #[doc = " The `border-image` property is the css-like shorthand for `stylebox`"]
+    #[doc = " properties:"]
+    #[doc = " "]
+    #[doc = " 'source slice [/ width]'"]
+    #[doc = " "]
+    #[doc = " - `source` is the `url()` or the path to the image (`stylebox-source`)"]
+    #[doc = " - `slice` is `rect-shorthand` in `px` or `%` (`stylebox-slice`)"]
+    #[doc = " - `width` is `rect-shorthand` in `px` or `%` (`stylebox-width`), `100%` by default"]
+    #[doc = " "]
+    #[doc = " Example:"]
+    #[doc = " ```css"]
+    #[doc = "   border-image: url(\"frame.png\") 8px;"]
+    #[doc = "   border-image: url(\"button.png\") 12px 8px / 6px 4px;"]
+    #[doc = "   border-image: none;"]
+    #[doc = " ```"]
+    BorderImageProperty("border-image", value) {
+        let props = match value {
+            Variant::String(unparsed) => StyleProperty::try_from(unparsed)?,
+            Variant::Style(prop) => prop,
+            v => return Self::error(format!("Don't know how to extract border-image from {v:?}"))
+        };
+        let mut result = HashMap::default();
+        if let Some("none") = props.identifier() {
+            result.insert(tag!("stylebox-source"), PropertyValue::new(None::<String>));
+            return Ok(result);
+        }
+        if props.is_empty() {
+            return Self::error("Expected border-image source, got nothing".to_string());
+        }
+        let (source, rest) = props.split_at(1);
+        result.insert(tag!("stylebox-source"), PropertyValue::new(Some(source.url()?)));
+        let (slice, width) = match rest.iter().position(|t| *t == StylePropertyToken::Slash) {
+            Some(idx) => (&rest[..idx], Some(&rest[idx + 1..])),
+            None => (rest, None),
+        };
+        result.insert(tag!("stylebox-slice"), PropertyValue::new(slice.rect()?));
+        let width = match width {
+            Some(width) => width.rect()?,
+            None => UiRect::all(Val::Percent(100.)),
+        };
+        result.insert(tag!("stylebox-width"), PropertyValue::new(width));
+        Ok(result)
+    }
+}
+
 style_property! {
     #[doc = " The `stylebox-source` property specifies the path to the image to be used"]
-    #[doc = " as a stylebox. The property accepts `String` values."]
+    #[doc = " as a stylebox. The property accepts `String` values, `url()` or `none`."]
     StyleboxSourceProperty("stylebox-source") {
-        Item = String;
+        Item = Option<String>;
         Components = &'static mut Stylebox;
         Filters = With<Node>;
-        Parse = |v| v.string();
+        Parse = |v| match v.identifier() {
+            Some("none") => Ok(None),
+            _ => v.url().map(Some),
+        };
         Apply = |value, stylebox, assets, _commands, _entity| {
-            let image = assets.load(value);
+            let image = match value {
+                Some(path) => assets.load(path.as_str()),
+                None => Handle::default(),
+            };
             if stylebox.texture != image {
                 stylebox.texture = image;
             }
@@ -163,3 +218,45 @@ style_property! {
         };
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn extract(value: &str) -> HashMap<Tag, PropertyValue> {
+        BorderImageProperty::extract(Variant::String(value.to_string())).unwrap()
+    }
+
+    fn get<T: 'static + Clone>(props: &HashMap<Tag, PropertyValue>, name: &str) -> T {
+        props[&name.as_tag()].downcast_ref::<T>().unwrap().clone()
+    }
+
+    #[test]
+    fn extract_border_image_shorthand() {
+        let props = extract("url(\"frame.png\") 8px");
+        let source = get::<Option<String>>(&props, "stylebox-source");
+        assert_eq!(source, Some("frame.png".to_string()));
+        assert_eq!(
+            get::<UiRect>(&props, "stylebox-slice"),
+            UiRect::all(Val::Px(8.))
+        );
+        let width = get::<UiRect>(&props, "stylebox-width");
+        assert_eq!(width, UiRect::all(Val::Percent(100.)));
+
+        let props = extract("url(button.png) 12px 8px / 6px 4px");
+        let slice = get::<UiRect>(&props, "stylebox-slice");
+        assert_eq!(
+            slice,
+            UiRect::new(Val::Px(8.), Val::Px(8.), Val::Px(12.), Val::Px(12.))
+        );
+        let width = get::<UiRect>(&props, "stylebox-width");
+        assert_eq!(
+            width,
+            UiRect::new(Val::Px(4.), Val::Px(4.), Val::Px(6.), Val::Px(6.))
+        );
+
+        let props = extract("none");
+        assert_eq!(get::<Option<String>>(&props, "stylebox-source"), None);
+        assert!(BorderImageProperty::extract(Variant::String("8px".into())).is_err());
+    }
+}
@@ -0,0 +1,434 @@
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{
+    ecs::query::QueryItem,
+    prelude::*,
+    text::TextLayoutInfo,
+    ui::{widget::text_system, CalculatedSize, UiScale, UiSystem},
+    window::WindowId,
+};
+use tagstr::*;
+
+/// Spaces are replaced with this one by `text-wrap: nowrap`,
+/// so the text layout doesn't break the lines.
+const NO_BREAK_SPACE: char = '\u{a0}';
+
+pub(crate) struct TextStylePlugin;
+impl Plugin for TextStylePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_property::<LetterSpacingProperty>();
+        app.register_property::<LineHeightProperty>();
+        app.register_property::<TextWrapProperty>();
+        app.add_system(update_text_wrap);
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            apply_text_spacing.after(text_system).before(UiSystem::Flex),
+        );
+    }
+}
+
+/// The extra space in pixels between the glyphs of the text.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct LetterSpacing(pub f32);
+
+/// The height of the line box of the text. The glyphs are centered
+/// inside the line box, consecutive lines are moved apart by the
+/// difference between the line height and the font size.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub enum LineHeight {
+    /// Multiple of the font size.
+    Factor(f32),
+    Px(f32),
+}
+
+impl LineHeight {
+    pub fn resolve(&self, font_size: f32) -> f32 {
+        match self {
+            LineHeight::Factor(factor) => factor * font_size,
+            LineHeight::Px(px) => *px,
+        }
+    }
+}
+
+/// Keeps the text on a single line by the `text-wrap: nowrap`.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct NoWrap;
+
+/// Marks the text with spaces replaced by [`NoWrap`], so they are
+/// restored when the text could wrap again.
+#[derive(Component)]
+struct UnbreakableSpaces;
+
+/// Glyph positions written by [`apply_text_spacing`], used to skip
+/// the layout that is already spaced.
+#[derive(Component)]
+struct SpacedGlyphs(Vec<Vec2>);
+
+/// Applies the `letter-spacing` property:
+/// ```css
+/// letter-spacing: 2px;
+/// letter-spacing: normal;
+/// ```
+/// The property is inherited.
+#[derive(Default)]
+pub(crate) struct LetterSpacingProperty;
+
+impl Property for LetterSpacingProperty {
+    type Item = f32;
+    type Components = (
+        Option<&'static mut LetterSpacing>,
+        Option<&'static mut Text>,
+    );
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("letter-spacing")
+    }
+
+    fn affects_virtual_elements() -> bool {
+        true
+    }
+
+    fn inherited() -> bool {
+        true
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        match values.tokens() {
+            [StylePropertyToken::Identifier(ident)] if ident == "normal" => Ok(0.),
+            [StylePropertyToken::Dimension(px)] => Ok(px.into()),
+            [StylePropertyToken::Number(px)] if f32::from(px) == 0. => Ok(0.),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected normal or length in px, got `{}`",
+                values.to_string()
+            ))),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        (spacing, text): QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        let changed = match (*cache, spacing) {
+            (value, Some(_)) if value == 0. => {
+                commands.entity(entity).remove::<LetterSpacing>();
+                true
+            }
+            (value, Some(mut spacing)) if spacing.0 != value => {
+                spacing.0 = value;
+                true
+            }
+            (value, None) if value != 0. => {
+                commands.entity(entity).insert(LetterSpacing(value));
+                true
+            }
+            _ => false,
+        };
+        // the text is laid out again to drop the previous spacing
+        if let (true, Some(mut text)) = (changed, text) {
+            text.set_changed();
+        }
+    }
+}
+
+/// Applies the `line-height` property:
+/// ```css
+/// line-height: 1.5;
+/// line-height: 150%;
+/// line-height: 32px;
+/// line-height: normal;
+/// ```
+/// Numbers and percents are relative to the font size of the text.
+/// The property is inherited.
+#[derive(Default)]
+pub(crate) struct LineHeightProperty;
+
+impl Property for LineHeightProperty {
+    type Item = Option<LineHeight>;
+    type Components = (Option<&'static mut LineHeight>, Option<&'static mut Text>);
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("line-height")
+    }
+
+    fn affects_virtual_elements() -> bool {
+        true
+    }
+
+    fn inherited() -> bool {
+        true
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        let line_height = match values.tokens() {
+            [StylePropertyToken::Identifier(ident)] if ident == "normal" => return Ok(None),
+            [StylePropertyToken::Number(factor)] => LineHeight::Factor(factor.into()),
+            [StylePropertyToken::Percentage(percent)] => {
+                LineHeight::Factor(f32::from(percent) / 100.)
+            }
+            [StylePropertyToken::Dimension(px)] => LineHeight::Px(px.into()),
+            _ => {
+                return Err(ElementsError::InvalidPropertyValue(format!(
+                    "Expected normal, number, percent or length in px, got `{}`",
+                    values.to_string()
+                )))
+            }
+        };
+        match line_height {
+            LineHeight::Factor(value) | LineHeight::Px(value) if value < 0. => {
+                Err(ElementsError::InvalidPropertyValue(format!(
+                    "line-height can't be negative, got `{}`",
+                    values.to_string()
+                )))
+            }
+            line_height => Ok(Some(line_height)),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        (line_height, text): QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        let changed = match (cache, line_height) {
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<LineHeight>();
+                true
+            }
+            (Some(value), Some(mut line_height)) if *line_height != *value => {
+                *line_height = *value;
+                true
+            }
+            (Some(value), None) => {
+                commands.entity(entity).insert(*value);
+                true
+            }
+            _ => false,
+        };
+        // the text is laid out again to drop the previous spacing
+        if let (true, Some(mut text)) = (changed, text) {
+            text.set_changed();
+        }
+    }
+}
+
+/// Applies the `text-wrap` property:
+/// ```css
+/// text-wrap: wrap;
+/// text-wrap: nowrap;
+/// ```
+/// The text wraps only when the width of the node is limited, `nowrap`
+/// keeps it on a single line. The property is inherited.
+#[derive(Default)]
+pub(crate) struct TextWrapProperty;
+
+impl Property for TextWrapProperty {
+    type Item = bool;
+    type Components = Option<&'static NoWrap>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("text-wrap")
+    }
+
+    fn affects_virtual_elements() -> bool {
+        true
+    }
+
+    fn inherited() -> bool {
+        true
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        match values.identifier() {
+            Some("wrap") => Ok(false),
+            Some("nowrap") => Ok(true),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected wrap or nowrap, got `{}`",
+                values.to_string()
+            ))),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match (cache, components) {
+            (true, None) => {
+                commands.entity(entity).insert(NoWrap);
+            }
+            (false, Some(_)) => {
+                commands.entity(entity).remove::<NoWrap>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The text layout has no option to disable wrapping, so the spaces
+/// of the [`NoWrap`] text are replaced with the no-break ones.
+fn update_text_wrap(
+    mut commands: Commands,
+    mut nowrap: Query<(Entity, &mut Text), (With<NoWrap>, Changed<Text>)>,
+    removed: RemovedComponents<NoWrap>,
+    mut texts: Query<&mut Text, (With<UnbreakableSpaces>, Without<NoWrap>)>,
+) {
+    for (entity, mut text) in nowrap.iter_mut() {
+        if text.sections.iter().any(|s| s.value.contains(' ')) {
+            for section in text.sections.iter_mut() {
+                section.value = section.value.replace(' ', &NO_BREAK_SPACE.to_string());
+            }
+            commands.entity(entity).insert(UnbreakableSpaces);
+        }
+    }
+    for entity in removed.iter() {
+        let Ok(mut text) = texts.get_mut(entity) else { continue };
+        for section in text.sections.iter_mut() {
+            section.value = section.value.replace(NO_BREAK_SPACE, " ");
+        }
+        commands.entity(entity).remove::<UnbreakableSpaces>();
+    }
+}
+
+/// Moves the glyphs at `positions` by the `letter` spacing and the `leading`
+/// (the difference between the line height and the font size). Lines start
+/// where the glyph goes back to the left, `align` is the horizontal alignment
+/// of the text: `0.` for the left, `0.5` for the center and `1.` for the right.
+/// Returns the new positions and the growth of the text size.
+fn space_glyphs(positions: &[Vec2], letter: f32, leading: f32, align: f32) -> (Vec<Vec2>, Vec2) {
+    let mut lines: Vec<&[Vec2]> = vec![];
+    let mut start = 0;
+    for index in 1..positions.len() {
+        if positions[index].x < positions[index - 1].x {
+            lines.push(&positions[start..index]);
+            start = index;
+        }
+    }
+    if start < positions.len() {
+        lines.push(&positions[start..]);
+    }
+    let mut spaced = Vec::with_capacity(positions.len());
+    let mut width = 0f32;
+    for (line_index, line) in lines.iter().enumerate() {
+        let extra = (line.len() as f32 - 1.) * letter;
+        width = width.max(extra);
+        let y = leading * (line_index as f32 + 0.5);
+        for (index, position) in line.iter().enumerate() {
+            let x = index as f32 * letter - extra * align;
+            spaced.push(*position + Vec2::new(x, y));
+        }
+    }
+    (spaced, Vec2::new(width, leading * lines.len() as f32))
+}
+
+fn apply_text_spacing(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    ui_scale: Res<UiScale>,
+    mut texts: Query<
+        (
+            Entity,
+            &Text,
+            &mut TextLayoutInfo,
+            &mut CalculatedSize,
+            Option<&LetterSpacing>,
+            Option<&LineHeight>,
+            Option<&SpacedGlyphs>,
+        ),
+        (
+            Changed<TextLayoutInfo>,
+            Or<(With<LetterSpacing>, With<LineHeight>)>,
+        ),
+    >,
+) {
+    let scale = (windows.scale_factor(WindowId::primary()) * ui_scale.scale) as f32;
+    for (entity, text, mut layout, mut size, letter, line_height, spaced) in texts.iter_mut() {
+        let positions: Vec<_> = layout.glyphs.iter().map(|g| g.position).collect();
+        if spaced.map(|s| s.0 == positions).unwrap_or(false) {
+            continue;
+        }
+        let Some(font_size) = text.sections.first().map(|s| s.style.font_size) else { continue };
+        let letter = letter.map(|l| l.0).unwrap_or(0.) * scale;
+        let leading = line_height
+            .map(|l| l.resolve(font_size) - font_size)
+            .unwrap_or(0.)
+            * scale;
+        let align = match text.alignment.horizontal {
+            HorizontalAlign::Left => 0.,
+            HorizontalAlign::Center => 0.5,
+            HorizontalAlign::Right => 1.,
+        };
+        let (positions, growth) = space_glyphs(&positions, letter, leading, align);
+        for (glyph, position) in layout.glyphs.iter_mut().zip(positions.iter()) {
+            glyph.position = *position;
+        }
+        layout.size += growth;
+        if let Val::Px(width) = size.size.width {
+            size.size.width = Val::Px(width + growth.x / scale);
+        }
+        commands.entity(entity).insert(SpacedGlyphs(positions));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_text_properties() {
+        let letter_spacing = |v: &str| LetterSpacingProperty::parse(&v.try_into().unwrap());
+        assert_eq!(letter_spacing("2px"), Ok(2.));
+        assert_eq!(letter_spacing("normal"), Ok(0.));
+        assert!(letter_spacing("wide").is_err());
+
+        let line_height = |v: &str| LineHeightProperty::parse(&v.try_into().unwrap());
+        assert_eq!(line_height("1.5"), Ok(Some(LineHeight::Factor(1.5))));
+        assert_eq!(line_height("150%"), Ok(Some(LineHeight::Factor(1.5))));
+        assert_eq!(line_height("32px"), Ok(Some(LineHeight::Px(32.))));
+        assert_eq!(line_height("normal"), Ok(None));
+        assert!(line_height("-1").is_err());
+
+        let text_wrap = |v: &str| TextWrapProperty::parse(&v.try_into().unwrap());
+        assert_eq!(text_wrap("nowrap"), Ok(true));
+        assert_eq!(text_wrap("wrap"), Ok(false));
+        assert!(text_wrap("balance").is_err());
+    }
+
+    #[test]
+    fn space_glyph_lines() {
+        // two lines: three glyphs and two glyphs
+        let positions = [
+            Vec2::new(5., 10.),
+            Vec2::new(15., 10.),
+            Vec2::new(25., 10.),
+            Vec2::new(5., 30.),
+            Vec2::new(15., 30.),
+        ];
+        let (spaced, growth) = space_glyphs(&positions, 2., 4., 0.);
+        assert_eq!(
+            spaced,
+            vec![
+                Vec2::new(5., 12.),
+                Vec2::new(17., 12.),
+                Vec2::new(29., 12.),
+                Vec2::new(5., 36.),
+                Vec2::new(17., 36.),
+            ]
+        );
+        assert_eq!(growth, Vec2::new(4., 8.));
+
+        let (spaced, _) = space_glyphs(&positions[..3], 2., 0., 0.5);
+        assert_eq!(spaced[0], Vec2::new(3., 10.));
+        assert_eq!(spaced[2], Vec2::new(27., 10.));
+    }
+}
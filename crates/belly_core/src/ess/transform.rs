@@ -0,0 +1,329 @@
+use crate::ess::gradient::parse_angle;
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{ecs::query::QueryItem, prelude::*, transform::TransformSystem, ui::UiSystem};
+use tagstr::*;
+
+pub(crate) struct NodeTransformPlugin;
+impl Plugin for NodeTransformPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_property::<TransformProperty>();
+        app.register_property::<TransformOriginProperty>();
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            apply_node_transforms
+                .after(UiSystem::Flex)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// The visual transformation of the node applied on top of the layout:
+/// the node is scaled, rotated around the [`TransformOrigin`] and moved
+/// by the `translate` offset in pixels. The layout of the siblings is not
+/// affected, the interaction area of the node is not transformed.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct NodeTransform {
+    pub translate: Vec2,
+    /// Clockwise rotation in degrees.
+    pub rotate: f32,
+    pub scale: Vec2,
+}
+
+impl Default for NodeTransform {
+    fn default() -> Self {
+        NodeTransform {
+            translate: Vec2::ZERO,
+            rotate: 0.,
+            scale: Vec2::ONE,
+        }
+    }
+}
+
+impl NodeTransform {
+    /// Parses the list of the transform functions:
+    /// ```css
+    /// translate(4px, 2px) translateX(4px) translateY(2px)
+    /// scale(1.1) scale(1.1, 0.9) scaleX(1.1) scaleY(0.9)
+    /// rotate(3deg)
+    /// ```
+    /// Translations and rotations are summed up, scales are multiplied.
+    pub fn parse(values: &StyleProperty) -> Result<NodeTransform, ElementsError> {
+        let mut transform = NodeTransform::default();
+        for token in values.iter() {
+            let StylePropertyToken::Function(name, args) = token else {
+                return Err(ElementsError::InvalidPropertyValue(format!(
+                    "Expected transform function, got `{}`",
+                    token.to_string()
+                )));
+            };
+            let error =
+                || ElementsError::InvalidPropertyValue(format!("Can't parse {name}({args})"));
+            let numbers = args
+                .split(',')
+                .map(|arg| {
+                    let arg = arg.trim();
+                    arg.strip_suffix("px")
+                        .unwrap_or(arg)
+                        .trim()
+                        .parse::<f32>()
+                        .map_err(|_| error())
+                })
+                .collect::<Result<Vec<_>, _>>();
+            match (name.as_str(), numbers.as_deref()) {
+                ("translate", Ok([x, y])) => transform.translate += Vec2::new(*x, *y),
+                ("translate", Ok([x])) | ("translateX", Ok([x])) => transform.translate.x += x,
+                ("translateY", Ok([y])) => transform.translate.y += y,
+                ("scale", Ok([x, y])) => transform.scale *= Vec2::new(*x, *y),
+                ("scale", Ok([s])) => transform.scale *= *s,
+                ("scaleX", Ok([x])) => transform.scale.x *= x,
+                ("scaleY", Ok([y])) => transform.scale.y *= y,
+                ("rotate", Ok([zero])) if *zero == 0. => {}
+                ("rotate", _) => transform.rotate += parse_angle(args.trim()).ok_or_else(error)?,
+                _ => return Err(error()),
+            }
+        }
+        Ok(transform)
+    }
+}
+
+/// The point the [`NodeTransform`] rotates and scales the node around,
+/// relative to the top left corner of the node. Percentages are relative
+/// to the node size, the default is the center of the node.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct TransformOrigin {
+    pub x: Val,
+    pub y: Val,
+}
+
+impl Default for TransformOrigin {
+    fn default() -> Self {
+        TransformOrigin {
+            x: Val::Percent(50.),
+            y: Val::Percent(50.),
+        }
+    }
+}
+
+impl TransformOrigin {
+    /// Returns the offset of the origin from the center of the node.
+    fn offset(&self, size: Vec2) -> Vec2 {
+        let resolve = |val: Val, size: f32| match val {
+            Val::Px(px) => px,
+            Val::Percent(percent) => size * percent / 100.,
+            _ => size * 0.5,
+        };
+        Vec2::new(resolve(self.x, size.x), resolve(self.y, size.y)) - size * 0.5
+    }
+}
+
+/// Applies the `transform` property:
+/// ```css
+/// transform: scale(1.1) rotate(3deg);
+/// transform: translate(0px, -4px);
+/// transform: none;
+/// ```
+/// Check [`NodeTransform`] for details.
+#[derive(Default)]
+pub(crate) struct TransformProperty;
+
+impl Property for TransformProperty {
+    type Item = Option<NodeTransform>;
+    type Components = Option<&'static mut NodeTransform>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("transform")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        match values.tokens() {
+            [StylePropertyToken::Identifier(ident)] if ident == "none" => Ok(None),
+            _ => NodeTransform::parse(values).map(Some),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match (cache, components) {
+            (Some(transform), Some(mut current)) if *current != *transform => *current = *transform,
+            (Some(transform), None) => {
+                commands.entity(entity).insert(*transform);
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<NodeTransform>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Applies the `transform-origin` property:
+/// ```css
+/// transform-origin: center;
+/// transform-origin: left top;
+/// transform-origin: 0px 100%;
+/// ```
+/// Check [`TransformOrigin`] for details.
+#[derive(Default)]
+pub(crate) struct TransformOriginProperty;
+
+impl Property for TransformOriginProperty {
+    type Item = TransformOrigin;
+    type Components = Option<&'static mut TransformOrigin>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("transform-origin")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        let error = || {
+            ElementsError::InvalidPropertyValue(format!(
+                "Expected 1-2 keywords or lengths, got `{}`",
+                values.to_string()
+            ))
+        };
+        let mut x = None;
+        let mut y = None;
+        for token in values.iter() {
+            let val = match token {
+                StylePropertyToken::Identifier(ident) => match ident.as_str() {
+                    "left" | "top" => Val::Percent(0.),
+                    "center" => Val::Percent(50.),
+                    "right" | "bottom" => Val::Percent(100.),
+                    _ => return Err(error()),
+                },
+                _ => std::slice::from_ref(token).val()?,
+            };
+            let keyword = match token {
+                StylePropertyToken::Identifier(ident) => ident.as_str(),
+                _ => "",
+            };
+            if matches!(keyword, "left" | "right") && x.is_some() {
+                // `center left` is the same as `left center`
+                if y.is_some() || x != Some(Val::Percent(50.)) {
+                    return Err(error());
+                }
+                y = x.replace(val);
+            } else if matches!(keyword, "top" | "bottom") || x.is_some() {
+                if y.replace(val).is_some() {
+                    return Err(error());
+                }
+            } else {
+                x = Some(val);
+            }
+        }
+        if x.is_none() && y.is_none() {
+            return Err(error());
+        }
+        Ok(TransformOrigin {
+            x: x.unwrap_or(Val::Percent(50.)),
+            y: y.unwrap_or(Val::Percent(50.)),
+        })
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match components {
+            Some(mut origin) if *origin != *cache => *origin = *cache,
+            None => {
+                commands.entity(entity).insert(*cache);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn apply_node_transforms(
+    mut nodes: Query<(
+        &Node,
+        &NodeTransform,
+        Option<&TransformOrigin>,
+        &mut Transform,
+    )>,
+    mut transforms: Query<&mut Transform, Without<NodeTransform>>,
+    removed: RemovedComponents<NodeTransform>,
+) {
+    // the flex layout resets the translation of the moved node,
+    // the rotation & the scale are reset here
+    for entity in removed.iter() {
+        let Ok(mut transform) = transforms.get_mut(entity) else { continue };
+        transform.rotation = Quat::IDENTITY;
+        transform.scale = Vec3::ONE;
+    }
+    for (node, node_transform, origin, mut transform) in nodes.iter_mut() {
+        let origin = origin.copied().unwrap_or_default().offset(node.size());
+        let rotation = Quat::from_rotation_z(node_transform.rotate.to_radians());
+        let scale = node_transform.scale.extend(1.);
+        // rotate & scale around the origin instead of the node center
+        let moved = rotation * (scale * origin.extend(0.));
+        let offset = origin - moved.truncate() + node_transform.translate;
+        // the flex layout writes the translation of the moved nodes each
+        // frame, so the offset is added after the layout every frame
+        if offset != Vec2::ZERO {
+            transform.translation += offset.extend(0.);
+        }
+        if transform.rotation != rotation {
+            transform.rotation = rotation;
+        }
+        if transform.scale != scale {
+            transform.scale = scale;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_transform() {
+        let parse = |value: &str| TransformProperty::parse(&value.try_into().unwrap());
+        let transform = parse("scale(1.1) rotate(3deg)").unwrap().unwrap();
+        assert_eq!(transform.scale, Vec2::splat(1.1));
+        assert_eq!(transform.rotate, 3.);
+        let transform = parse("translate(4px, -2px) translateX(1px) scaleY(2)").unwrap();
+        let transform = transform.unwrap();
+        assert_eq!(transform.translate, Vec2::new(5., -2.));
+        assert_eq!(transform.scale, Vec2::new(1., 2.));
+        assert_eq!(parse("rotate(0.5turn)").unwrap().unwrap().rotate, 180.);
+        assert_eq!(parse("none"), Ok(None));
+        assert!(parse("skew(10deg)").is_err());
+        assert!(parse("rotate(3px)").is_err());
+    }
+
+    #[test]
+    fn parse_transform_origin() {
+        let parse = |value: &str| TransformOriginProperty::parse(&value.try_into().unwrap());
+        let origin = |x, y| Ok(TransformOrigin { x, y });
+        assert_eq!(
+            parse("center"),
+            origin(Val::Percent(50.), Val::Percent(50.))
+        );
+        assert_eq!(
+            parse("top left"),
+            origin(Val::Percent(0.), Val::Percent(0.))
+        );
+        assert_eq!(parse("10px 100%"), origin(Val::Px(10.), Val::Percent(100.)));
+        assert_eq!(
+            TransformOrigin::default().offset(Vec2::new(20., 10.)),
+            Vec2::ZERO
+        );
+        assert_eq!(
+            parse("center left"),
+            origin(Val::Percent(0.), Val::Percent(50.))
+        );
+        assert!(parse("top bottom").is_err());
+    }
+}
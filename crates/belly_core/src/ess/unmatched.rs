@@ -0,0 +1,138 @@
+use bevy::{
+    prelude::*,
+    utils::{Duration, HashSet},
+};
+
+use super::{ElementsBranch, StyleSheet, Styles};
+use crate::element::{ElementChanged, ElementRef};
+
+/// Development helper which reports the rules nobody needs anymore:
+/// ```rust
+/// use bevy::prelude::*;
+/// use belly_core::ess::UnmatchedRulesPlugin;
+///
+/// fn main() {
+///    let mut app = App::new();
+///    app.add_plugins(DefaultPlugins);
+///    app.add_plugin(belly_core::ElementsCorePlugin);
+///    app.add_plugin(UnmatchedRulesPlugin::default());
+/// }
+/// ```
+/// Every time the elements and the stylesheets stay unchanged for the
+/// `idle` period, the rules of the active stylesheets whose selectors
+/// have never matched any live element are logged. Every rule is logged
+/// once, the rules of the modified stylesheet are checked from scratch.
+/// Selectors with states (`:hover`, `:focus`, ...) match only when some
+/// element is in this state, so the idle period should be long enough
+/// to visit the UI.
+pub struct UnmatchedRulesPlugin {
+    pub idle: Duration,
+}
+
+impl Default for UnmatchedRulesPlugin {
+    fn default() -> Self {
+        UnmatchedRulesPlugin {
+            idle: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Plugin for UnmatchedRulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RuleMatches {
+            idle: self.idle,
+            ..default()
+        });
+        app.add_system_to_stage(CoreStage::PostUpdate, check_unmatched_rules);
+    }
+}
+
+/// The rules are identified by the stylesheet and the index of the rule.
+#[derive(Resource, Default)]
+struct RuleMatches {
+    idle: Duration,
+    last_change: Duration,
+    /// The rules are already checked since the last change.
+    idle_checked: bool,
+    matched: HashSet<(Handle<StyleSheet>, usize)>,
+    reported: HashSet<(Handle<StyleSheet>, usize)>,
+}
+
+fn check_unmatched_rules(
+    mut state: ResMut<RuleMatches>,
+    mut events: EventReader<AssetEvent<StyleSheet>>,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    styles: Res<Styles>,
+    stylesheets: Res<Assets<StyleSheet>>,
+    changed: Query<Entity, ElementChanged>,
+    elements: Query<ElementRef>,
+    parents: Query<&Parent>,
+) {
+    let now = time.elapsed();
+    for event in events.iter() {
+        let handle = match event {
+            AssetEvent::Created { handle }
+            | AssetEvent::Modified { handle }
+            | AssetEvent::Removed { handle } => handle,
+        };
+        // indices of the rules are changed, the modified stylesheet
+        // restyles all the elements, so they are matched again
+        state.matched.retain(|(h, _)| h != handle);
+        state.reported.retain(|(h, _)| h != handle);
+        state.last_change = now;
+        state.idle_checked = false;
+    }
+
+    for entity in changed.iter() {
+        let Ok(element) = elements.get(entity) else { continue };
+        if element.is_virtual() {
+            continue;
+        }
+        state.last_change = now;
+        state.idle_checked = false;
+        let mut branch = ElementsBranch::new();
+        let mut tail = entity;
+        while let Ok(element) = elements.get(tail) {
+            if !element.is_virtual() {
+                branch.insert(element);
+            }
+            if let Ok(parent) = parents.get(tail) {
+                tail = parent.get();
+            } else {
+                break;
+            }
+        }
+        for handle in styles.iter() {
+            let Some(stylesheet) = stylesheets.get(handle) else { continue };
+            for (index, rule) in stylesheet.iter().enumerate() {
+                let key = (handle.clone_weak(), index);
+                if !state.matched.contains(&key) && rule.selector.matches(&branch) {
+                    state.matched.insert(key);
+                }
+            }
+        }
+    }
+
+    if state.idle_checked || now - state.last_change < state.idle {
+        return;
+    }
+    state.idle_checked = true;
+    for handle in styles.iter() {
+        let Some(stylesheet) = stylesheets.get(handle) else { continue };
+        for (index, rule) in stylesheet.iter().enumerate() {
+            let key = (handle.clone_weak(), index);
+            if state.matched.contains(&key) || !state.reported.insert(key) {
+                continue;
+            }
+            let selector = rule.selector.to_string();
+            match asset_server.get_handle_path(handle) {
+                Some(path) => warn!(
+                    "Rule `{selector}` of {} never matched any element",
+                    path.path().display()
+                ),
+                None => warn!("Rule `{selector}` never matched any element"),
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::ess::*;
+use crate::ElementsError;
+use bevy::{ecs::query::QueryItem, prelude::*, render::view::VisibilitySystems};
+use tagstr::*;
+
+pub(crate) struct NodeVisibilityPlugin;
+impl Plugin for NodeVisibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_property::<VisibilityProperty>();
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            update_node_visibility.before(VisibilitySystems::VisibilityPropagate),
+        );
+    }
+}
+
+/// Hides the node by the `visibility: hidden` while it keeps its place
+/// in the layout.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct StyleHidden;
+
+/// Marks the node hidden by the styles, so the [`Visibility`] is restored
+/// when the node is shown again. The visibility set from the code is
+/// not touched otherwise.
+#[derive(Component)]
+struct HiddenByStyle;
+
+/// Applies the `visibility` property:
+/// ```css
+/// visibility: visible;
+/// visibility: hidden;
+/// ```
+/// Hidden nodes keep their place in the layout, use `display: none`
+/// to remove the node from the layout. Both hide the node with the
+/// whole subtree: the descendants can't be made visible.
+#[derive(Default)]
+pub(crate) struct VisibilityProperty;
+
+impl Property for VisibilityProperty {
+    type Item = bool;
+    type Components = Option<&'static StyleHidden>;
+    type Filters = With<Node>;
+
+    fn name() -> Tag {
+        tag!("visibility")
+    }
+
+    fn parse<'a>(values: &StyleProperty) -> Result<Self::Item, ElementsError> {
+        match values.identifier() {
+            Some("visible") => Ok(false),
+            Some("hidden") => Ok(true),
+            _ => Err(ElementsError::InvalidPropertyValue(format!(
+                "Expected visible or hidden, got `{}`",
+                values.to_string()
+            ))),
+        }
+    }
+
+    fn apply<'w>(
+        cache: &Self::Item,
+        components: QueryItem<Self::Components>,
+        _asset_server: &AssetServer,
+        commands: &mut Commands,
+        entity: Entity,
+    ) {
+        match (cache, components) {
+            (true, None) => {
+                commands.entity(entity).insert(StyleHidden);
+            }
+            (false, Some(_)) => {
+                commands.entity(entity).remove::<StyleHidden>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Hides the nodes with the `display: none` or the `visibility: hidden`.
+/// The flex layout collapses the `display: none` subtree, but the text
+/// is still drawn, so the node is hidden as well.
+fn update_node_visibility(
+    mut commands: Commands,
+    changed: Query<Entity, Or<(Changed<Style>, Changed<StyleHidden>)>>,
+    removed: RemovedComponents<StyleHidden>,
+    mut nodes: Query<(
+        &Style,
+        Option<&StyleHidden>,
+        Option<&HiddenByStyle>,
+        &mut Visibility,
+    )>,
+) {
+    for entity in changed.iter().chain(removed.iter()) {
+        let Ok((style, hidden, hidden_by_style, mut visibility)) = nodes.get_mut(entity) else {
+            continue;
+        };
+        if style.display == Display::None || hidden.is_some() {
+            if visibility.is_visible {
+                visibility.is_visible = false;
+                commands.entity(entity).insert(HiddenByStyle);
+            }
+        } else if hidden_by_style.is_some() {
+            visibility.is_visible = true;
+            commands.entity(entity).remove::<HiddenByStyle>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_visibility() {
+        let parse = |value: &str| VisibilityProperty::parse(&value.try_into().unwrap());
+        assert_eq!(parse("hidden"), Ok(true));
+        assert_eq!(parse("visible"), Ok(false));
+        assert!(parse("collapse").is_err());
+    }
+}
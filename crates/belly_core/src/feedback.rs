@@ -0,0 +1,164 @@
+use bevy::{prelude::*, utils::HashMap};
+use tagstr::Tag;
+
+pub(crate) struct FeedbackPlugin;
+impl Plugin for FeedbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FeedbackMap>()
+            .add_event::<FeedbackRequest>()
+            .add_event::<HapticFeedback>()
+            .add_system_to_stage(CoreStage::PostUpdate, process_feedback_system);
+    }
+}
+
+/// The input action widgets provide the feedback for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeedbackAction {
+    /// The button was pressed or the value was accepted.
+    Confirm,
+    /// The toggle was released.
+    Release,
+    /// The press was aborted (the pointer left the button before it
+    /// was lifted) or the action was dismissed.
+    Cancel,
+    /// The focus was moved by the keyboard or controller.
+    MoveFocus,
+    /// The value was stepped by the keyboard or controller, like the
+    /// arrows nudging the slider.
+    Adjust,
+    /// Any app-specific action.
+    Custom(Tag),
+}
+
+/// The haptic impulse description. `bevy` has no rumble api yet, so
+/// haptics are emitted as [`HapticFeedback`] events for the app to
+/// route them to the gamepad.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Haptic {
+    pub intensity: f32,
+    pub duration: f32,
+}
+
+/// The sound and/or haptic response to the [`FeedbackAction`].
+#[derive(Debug, Clone, Default)]
+pub struct Feedback {
+    pub sound: Option<Handle<AudioSource>>,
+    pub haptic: Option<Haptic>,
+}
+
+impl Feedback {
+    pub fn sound(sound: Handle<AudioSource>) -> Feedback {
+        Feedback {
+            sound: Some(sound),
+            haptic: None,
+        }
+    }
+
+    pub fn haptic(intensity: f32, duration: f32) -> Feedback {
+        Feedback {
+            sound: None,
+            haptic: Some(Haptic {
+                intensity,
+                duration,
+            }),
+        }
+    }
+
+    pub fn with_sound(mut self, sound: Handle<AudioSource>) -> Feedback {
+        self.sound = Some(sound);
+        self
+    }
+
+    pub fn with_haptic(mut self, intensity: f32, duration: f32) -> Feedback {
+        self.haptic = Some(Haptic {
+            intensity,
+            duration,
+        });
+        self
+    }
+}
+
+/// The global action -> feedback map consumed by the widgets:
+/// ```ignore
+/// fn setup(mut feedback: ResMut<FeedbackMap>, asset_server: Res<AssetServer>) {
+///     feedback.insert(
+///         FeedbackAction::Confirm,
+///         Feedback::sound(asset_server.load("click.ogg")).with_haptic(0.3, 0.1),
+///     );
+/// }
+/// ```
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct FeedbackMap(HashMap<FeedbackAction, Feedback>);
+
+/// Overrides the [`FeedbackMap`] for the entity and all its descendants.
+/// The nearest override containing the action wins, `None` mutes the action:
+/// ```ignore
+/// eml! {
+///     <div with=FeedbackOverride::default().mute(FeedbackAction::MoveFocus)>
+///         ...
+///     </div>
+/// }
+/// ```
+#[derive(Component, Clone, Default, Deref, DerefMut)]
+pub struct FeedbackOverride(HashMap<FeedbackAction, Option<Feedback>>);
+
+impl FeedbackOverride {
+    pub fn set(mut self, action: FeedbackAction, feedback: Feedback) -> Self {
+        self.0.insert(action, Some(feedback));
+        self
+    }
+
+    pub fn mute(mut self, action: FeedbackAction) -> Self {
+        self.0.insert(action, None);
+        self
+    }
+}
+
+/// Sent by widgets when they handle the [`FeedbackAction`].
+pub struct FeedbackRequest {
+    pub action: FeedbackAction,
+    pub source: Entity,
+}
+
+impl FeedbackRequest {
+    pub fn new(action: FeedbackAction, source: Entity) -> FeedbackRequest {
+        FeedbackRequest { action, source }
+    }
+}
+
+/// Emitted for each requested feedback with the haptic part.
+pub struct HapticFeedback {
+    pub action: FeedbackAction,
+    pub source: Entity,
+    pub haptic: Haptic,
+}
+
+fn process_feedback_system(
+    mut requests: EventReader<FeedbackRequest>,
+    mut haptics: EventWriter<HapticFeedback>,
+    map: Res<FeedbackMap>,
+    overrides: Query<&FeedbackOverride>,
+    parents: Query<&Parent>,
+    audio: Option<Res<Audio>>,
+) {
+    for request in requests.iter() {
+        let overridden = std::iter::once(request.source)
+            .chain(parents.iter_ancestors(request.source))
+            .find_map(|e| overrides.get(e).ok().and_then(|o| o.get(&request.action)));
+        let feedback = match overridden {
+            Some(feedback) => feedback.as_ref(),
+            None => map.get(&request.action),
+        };
+        let Some(feedback) = feedback else { continue };
+        if let (Some(sound), Some(audio)) = (&feedback.sound, &audio) {
+            audio.play(sound.clone());
+        }
+        if let Some(haptic) = feedback.haptic {
+            haptics.send(HapticFeedback {
+                action: request.action,
+                source: request.source,
+                haptic,
+            });
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use bevy::{prelude::*, ui::UiSystem};
+use tagstr::*;
+
+use crate::{eml::Variant, Elements};
+
+pub(crate) struct FollowPlugin;
+impl Plugin for FollowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PostUpdate, follow_targets.before(UiSystem::Flex));
+    }
+}
+
+/// Keeps the element over the world-space entity, set by the `follow`
+/// param (`follow=unit`) or inserted from the code. The element is
+/// centered at the projection of the `target` by the active camera and
+/// shifted by the `offset` (`follow-offset="0px -40px"`). The element gets
+/// the `:offscreen` state while the target is out of the view. The element
+/// with the `follow-clamp` param stays at the edge of its parent instead
+/// of leaving it, which makes the off-screen indicators:
+/// ```rust,ignore
+/// commands.add(eml! {
+///     <body>
+///         <progressbar c:health follow=unit follow-offset="0px -48px"
+///             bind:value=from!(unit, Health:ratio)/>
+///         <img c:quest-marker follow=npc follow-clamp src="marker.png"/>
+///     </body>
+/// });
+/// ```
+/// The element is positioned absolutely relative to its parent,
+/// so the parent is expected to cover the viewport.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Follow {
+    pub target: Entity,
+    /// The shift of the element center from the projected target, in pixels.
+    pub offset: Vec2,
+    /// Keeps the element within its parent when the target is out of the view.
+    pub clamp: bool,
+}
+
+impl Follow {
+    pub fn new(target: Entity) -> Follow {
+        Follow {
+            target,
+            offset: Vec2::ZERO,
+            clamp: false,
+        }
+    }
+
+    pub fn offset(mut self, offset: Vec2) -> Follow {
+        self.offset = offset;
+        self
+    }
+
+    pub fn clamp(mut self) -> Follow {
+        self.clamp = true;
+        self
+    }
+
+    /// Parses the value of the `follow-offset` param: the horizontal and
+    /// the vertical shifts in pixels (`"8px -40px"`), or the single shift
+    /// for both of them.
+    pub fn parse_offset(value: &str) -> Result<Vec2, String> {
+        let parts: Result<Vec<f32>, _> = value
+            .split_whitespace()
+            .map(|part| part.trim_end_matches("px").parse::<f32>())
+            .collect();
+        match parts.map_err(|e| format!("Can't parse `{value}` as offset: {e}"))?[..] {
+            [shift] => Ok(Vec2::splat(shift)),
+            [x, y] => Ok(Vec2::new(x, y)),
+            _ => Err(format!("Can't parse `{value}` as offset")),
+        }
+    }
+
+    /// Reads the `follow`, the `follow-offset` and the `follow-clamp` params.
+    pub(crate) fn from_params(
+        target: Option<Variant>,
+        offset: Option<Variant>,
+        clamp: Option<Variant>,
+    ) -> Option<Follow> {
+        let target = match target? {
+            Variant::Entity(target) => target,
+            variant => {
+                error!("Ignoring follow param, expected entity, got {variant:?}");
+                return None;
+            }
+        };
+        let mut follow = Follow::new(target);
+        match offset {
+            Some(Variant::String(offset)) => match Follow::parse_offset(&offset) {
+                Ok(offset) => follow.offset = offset,
+                Err(e) => error!("Ignoring follow-offset param: {e}"),
+            },
+            Some(offset) => match offset.take::<Vec2>() {
+                Some(offset) => follow.offset = offset,
+                None => error!("Ignoring follow-offset param, expected Vec2 or string"),
+            },
+            None => {}
+        }
+        follow.clamp = matches!(clamp, Some(Variant::Bool(true)));
+        Some(follow)
+    }
+}
+
+/// Projects the world `point` into the `viewport` by the `view_projection`
+/// matrix of the camera. The projection origin is at the top left corner,
+/// the second value is `true` when the point is out of the view. The point
+/// behind the camera is pushed out of the viewport on the side it is found at.
+pub fn project(view_projection: Mat4, point: Vec3, viewport: Vec2) -> (Vec2, bool) {
+    let clip = view_projection * point.extend(1.);
+    let behind = clip.w <= 0.;
+    let mut ndc = clip.truncate().truncate() / clip.w.abs().max(f32::EPSILON);
+    if behind {
+        // the direction is all that is known about the point behind
+        let direction = ndc.try_normalize().unwrap_or(Vec2::NEG_Y);
+        ndc = direction / direction.abs().max_element() * 2.;
+    }
+    let offscreen = behind || ndc.abs().max_element() > 1.;
+    let position = Vec2::new(ndc.x + 1., 1. - ndc.y) * 0.5 * viewport;
+    (position, offscreen)
+}
+
+fn follow_targets(
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    transforms: Query<&GlobalTransform>,
+    parents: Query<&Parent>,
+    nodes: Query<&Node>,
+    mut followers: Query<(Entity, &Follow, &mut Style)>,
+    mut elements: Elements,
+) {
+    let Some((camera, camera_transform)) = cameras
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .max_by_key(|(camera, _)| camera.priority)
+    else {
+        return;
+    };
+    let Some(viewport) = camera.logical_viewport_size() else {
+        return;
+    };
+    let view_projection = camera.projection_matrix() * camera_transform.compute_matrix().inverse();
+    for (entity, follow, mut style) in followers.iter_mut() {
+        let Ok(target) = transforms.get(follow.target) else {
+            elements.set_state(entity, offscreen(), true);
+            continue;
+        };
+        let (point, offscreen_now) = project(view_projection, target.translation(), viewport);
+        elements.set_state(entity, offscreen(), offscreen_now);
+        let size = nodes.get(entity).map(|n| n.size()).unwrap_or_default();
+        // the node is positioned by its center
+        let (origin, space) = parents
+            .get(entity)
+            .ok()
+            .and_then(|p| Some((transforms.get(p.get()).ok()?, nodes.get(p.get()).ok()?)))
+            .map(|(t, n)| (t.translation().truncate() - n.size() / 2., n.size()))
+            .unwrap_or((Vec2::ZERO, viewport));
+        let mut position = point + follow.offset - size / 2. - origin;
+        if follow.clamp {
+            position = position.clamp(Vec2::ZERO, (space - size).max(Vec2::ZERO));
+        }
+        let (left, top) = (Val::Px(position.x), Val::Px(position.y));
+        if style.position_type != PositionType::Absolute {
+            style.position_type = PositionType::Absolute;
+        }
+        if style.position.left != left || style.position.top != top {
+            style.position.left = left;
+            style.position.top = top;
+        }
+    }
+}
+
+fn offscreen() -> Tag {
+    "offscreen".as_tag()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_follow_offset() {
+        assert_eq!(Follow::parse_offset("8px -40px"), Ok(Vec2::new(8., -40.)));
+        assert_eq!(Follow::parse_offset("12"), Ok(Vec2::splat(12.)));
+        assert!(Follow::parse_offset("1px 2px 3px").is_err());
+        assert!(Follow::parse_offset("top").is_err());
+    }
+
+    #[test]
+    fn project_points() {
+        let view = Mat4::from_translation(Vec3::new(0., 0., 500.)).inverse();
+        let viewport = Vec2::new(200., 100.);
+        let orthographic = Mat4::orthographic_rh(-100., 100., -50., 50., 0., 1000.) * view;
+        let project_orthographic = |point| project(orthographic, point, viewport);
+        assert_eq!(
+            project_orthographic(Vec3::ZERO),
+            (Vec2::new(100., 50.), false)
+        );
+        assert_eq!(
+            project_orthographic(Vec3::new(-50., 25., 0.)),
+            (Vec2::new(50., 25.), false)
+        );
+        assert!(project_orthographic(Vec3::new(150., 0., 0.)).1);
+
+        let perspective = Mat4::perspective_rh(1., 2., 0.1, 1000.) * view;
+        let (position, offscreen) = project(perspective, Vec3::new(10., 0., 600.), viewport);
+        assert!(offscreen);
+        // the point behind the camera on the right stays on the right
+        assert!(position.x > viewport.x);
+    }
+}
@@ -0,0 +1,453 @@
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig,
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::{RenderLayers, VisibilitySystems},
+    },
+    transform::TransformSystem,
+    ui::{UiStack, UiSystem},
+    utils::HashSet,
+};
+
+use crate::element::Frozen;
+
+/// The snapshot camera gets its viewport the frame after it is spawned,
+/// the image is rendered by the end of the next one.
+const SNAPSHOT_FRAMES: u32 = 2;
+
+pub(crate) struct FreezePlugin;
+impl Plugin for FreezePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PostUpdate, snapshot_frozen_subtrees);
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            hide_snapshot_overlays
+                .after(UiSystem::Stack)
+                .after(TransformSystem::TransformPropagate)
+                .before(VisibilitySystems::VisibilityPropagate),
+        );
+        app.add_system_to_stage(CoreStage::PostUpdate, show_frozen_snapshots);
+        app.add_system_to_stage(CoreStage::PostUpdate, restore_unfrozen_subtrees);
+    }
+}
+
+/// The snapshot of the subtree frozen by [`Elements::freeze`](crate::Elements::freeze).
+/// The subtree is rendered into the `image` once, then the image node is
+/// shown in place of the live children until the subtree is unfrozen. The
+/// image could be used for the blurred backgrounds and the transitions:
+/// ```rust,ignore
+/// fn blur_background(snapshots: Query<&FrozenSnapshot, Added<FrozenSnapshot>>) {
+///     for snapshot in snapshots.iter() {
+///         blur.texture = snapshot.image().clone();
+///     }
+/// }
+/// ```
+#[derive(Component)]
+pub struct FrozenSnapshot {
+    image: Handle<Image>,
+    /// The logical size of the image, the size of the primary window.
+    size: Vec2,
+    /// The camera rendering the image, dropped once the image is shown.
+    camera: Option<Entity>,
+    /// The frames the camera exists for.
+    frames: u32,
+    /// The node showing the image.
+    node: Option<Entity>,
+    /// The visibility of the children hidden behind the image.
+    hidden: Vec<(Entity, Visibility)>,
+    /// The visibility of the nodes drawn over the subtree, hidden while
+    /// the image is rendered.
+    overlays: Vec<(Entity, Visibility)>,
+    /// The overflow of the frozen root, the image is clipped by it.
+    overflow: Overflow,
+}
+
+impl FrozenSnapshot {
+    /// The image the subtree is rendered into.
+    pub fn image(&self) -> &Handle<Image> {
+        &self.image
+    }
+
+    /// Tells if the image is rendered and shown instead of the subtree.
+    pub fn is_shown(&self) -> bool {
+        self.node.is_some()
+    }
+}
+
+/// Marks the camera rendering the snapshot of the frozen `root`.
+#[derive(Component)]
+struct SnapshotCamera(Entity);
+
+fn snapshot_image(size: Extent3d) -> Image {
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("belly_frozen_snapshot"),
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+        },
+        ..default()
+    };
+    image.resize(size);
+    image
+}
+
+/// Spawns the camera rendering the ui of the primary window into the
+/// image for each newly frozen subtree.
+fn snapshot_frozen_subtrees(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    mut images: ResMut<Assets<Image>>,
+    frozen: Query<Entity, (With<Frozen>, Without<FrozenSnapshot>)>,
+) {
+    let Some(window) = windows.get_primary() else {
+        return;
+    };
+    let size = Vec2::new(window.width(), window.height()).max(Vec2::ONE);
+    let extent = Extent3d {
+        width: size.x as u32,
+        height: size.y as u32,
+        depth_or_array_layers: 1,
+    };
+    for root in frozen.iter() {
+        let image = images.add(snapshot_image(extent));
+        let camera = commands
+            .spawn((
+                Camera2dBundle {
+                    camera: Camera {
+                        target: RenderTarget::Image(image.clone()),
+                        ..default()
+                    },
+                    camera_2d: Camera2d {
+                        clear_color: ClearColorConfig::Custom(Color::NONE),
+                    },
+                    ..default()
+                },
+                // the world isn't drawn into the snapshot, only the ui is
+                RenderLayers::layer(RenderLayers::TOTAL_LAYERS as u8 - 1),
+                SnapshotCamera(root),
+            ))
+            .id();
+        commands.entity(root).insert(FrozenSnapshot {
+            image,
+            size,
+            camera: Some(camera),
+            frames: 0,
+            node: None,
+            hidden: vec![],
+            overlays: vec![],
+            overflow: Overflow::Visible,
+        });
+    }
+}
+
+/// Hides the nodes drawn over the frozen subtree until its image is
+/// rendered. The camera renders the ui of the whole window, so the
+/// overlays spawned along with the freeze (like the pause menu) would be
+/// baked into the image otherwise. They are shown again with the image.
+fn hide_snapshot_overlays(
+    stack: Res<UiStack>,
+    mut snapshots: Query<(Entity, &mut FrozenSnapshot, &Node, &GlobalTransform), With<Frozen>>,
+    nodes: Query<(&Node, &GlobalTransform)>,
+    children: Query<&Children>,
+    mut visibilities: Query<&mut Visibility>,
+) {
+    for (root, mut snapshot, node, transform) in snapshots.iter_mut() {
+        if snapshot.camera.is_none() {
+            continue;
+        }
+        let mut subtree = HashSet::new();
+        let mut pending = vec![root];
+        while let Some(entity) = pending.pop() {
+            subtree.insert(entity);
+            pending.extend(children.get(entity).into_iter().flat_map(|c| c.iter()));
+        }
+        let Some(top) = stack.uinodes.iter().rposition(|e| subtree.contains(e)) else {
+            continue;
+        };
+        for entity in stack.uinodes[top + 1..].iter() {
+            let Ok((overlay, overlay_transform)) = nodes.get(*entity) else {
+                continue;
+            };
+            if !overlaps(node, transform, overlay, overlay_transform) {
+                continue;
+            }
+            let Ok(mut visibility) = visibilities.get_mut(*entity) else {
+                continue;
+            };
+            if !visibility.is_visible {
+                continue;
+            }
+            snapshot.overlays.push((*entity, visibility.clone()));
+            visibility.is_visible = false;
+        }
+    }
+}
+
+fn overlaps(
+    a: &Node,
+    a_transform: &GlobalTransform,
+    b: &Node,
+    b_transform: &GlobalTransform,
+) -> bool {
+    let distance = (a_transform.translation() - b_transform.translation())
+        .truncate()
+        .abs();
+    distance.cmple((a.size() + b.size()) / 2.).all()
+}
+
+fn restore_visibility(
+    nodes: &mut Vec<(Entity, Visibility)>,
+    visibilities: &mut Query<&mut Visibility>,
+) {
+    for (entity, visible) in nodes.drain(..) {
+        if let Ok(mut visibility) = visibilities.get_mut(entity) {
+            *visibility = visible;
+        }
+    }
+}
+
+/// Replaces the live children of the frozen subtree with the rendered
+/// image. The image covers the whole window, so it is shifted to the
+/// root position and clipped by the root.
+fn show_frozen_snapshots(
+    mut commands: Commands,
+    mut snapshots: Query<
+        (
+            Entity,
+            &mut FrozenSnapshot,
+            &Node,
+            &GlobalTransform,
+            &mut Style,
+            Option<&Children>,
+        ),
+        With<Frozen>,
+    >,
+    mut visibilities: Query<&mut Visibility>,
+) {
+    for (root, mut snapshot, node, transform, mut style, children) in snapshots.iter_mut() {
+        let Some(camera) = snapshot.camera else {
+            continue;
+        };
+        snapshot.frames += 1;
+        if snapshot.frames <= SNAPSHOT_FRAMES {
+            continue;
+        }
+        commands.entity(camera).despawn();
+        snapshot.camera = None;
+        restore_visibility(&mut snapshot.overlays, &mut visibilities);
+        for child in children.into_iter().flat_map(|c| c.iter()) {
+            let Ok(mut visibility) = visibilities.get_mut(*child) else {
+                continue;
+            };
+            snapshot.hidden.push((*child, visibility.clone()));
+            visibility.is_visible = false;
+        }
+        snapshot.overflow = style.overflow;
+        style.overflow = Overflow::Hidden;
+        let corner = transform.translation().truncate() - node.size() / 2.;
+        let image = commands
+            .spawn(ImageBundle {
+                image: UiImage(snapshot.image.clone()),
+                background_color: BackgroundColor(Color::WHITE),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(-corner.x),
+                        top: Val::Px(-corner.y),
+                        ..default()
+                    },
+                    size: Size::new(Val::Px(snapshot.size.x), Val::Px(snapshot.size.y)),
+                    ..default()
+                },
+                ..default()
+            })
+            .id();
+        commands.entity(root).add_child(image);
+        snapshot.node = Some(image);
+    }
+}
+
+/// Shows the live children of the unfrozen subtree again and drops the
+/// snapshot along with the camera of the despawned roots.
+fn restore_unfrozen_subtrees(
+    mut commands: Commands,
+    mut snapshots: Query<(Entity, &mut FrozenSnapshot, &mut Style), Without<Frozen>>,
+    mut visibilities: Query<&mut Visibility>,
+    cameras: Query<(Entity, &SnapshotCamera)>,
+    roots: Query<(), With<FrozenSnapshot>>,
+) {
+    for (root, mut snapshot, mut style) in snapshots.iter_mut() {
+        if let Some(camera) = snapshot.camera {
+            commands.entity(camera).despawn();
+        }
+        if let Some(node) = snapshot.node {
+            commands.entity(node).despawn_recursive();
+            if style.overflow != snapshot.overflow {
+                style.overflow = snapshot.overflow;
+            }
+        }
+        restore_visibility(&mut snapshot.hidden, &mut visibilities);
+        restore_visibility(&mut snapshot.overlays, &mut visibilities);
+        commands.entity(root).remove::<FrozenSnapshot>();
+    }
+    for (camera, SnapshotCamera(root)) in cameras.iter() {
+        if !roots.contains(*root) {
+            commands.entity(camera).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Element, Elements};
+
+    #[test]
+    fn freeze_and_unfreeze_in_one_frame() {
+        let mut app = App::new();
+        let root = app.world.spawn(Element::default()).id();
+        app.add_system(move |mut elements: Elements| {
+            elements.freeze(root);
+            elements.unfreeze(root);
+        });
+        app.update();
+        assert!(app.world.get::<Frozen>(root).is_none());
+    }
+
+    #[test]
+    fn swap_snapshot() {
+        let mut app = App::new();
+        app.add_system(show_frozen_snapshots)
+            .add_system(restore_unfrozen_subtrees.after(show_frozen_snapshots));
+        let child = app.world.spawn(Visibility::default()).id();
+        let camera = app.world.spawn_empty().id();
+        let root = app
+            .world
+            .spawn((
+                Frozen,
+                Node::default(),
+                GlobalTransform::from_xyz(50., 20., 0.),
+                Style::default(),
+            ))
+            .push_children(&[child])
+            .id();
+        app.world.entity_mut(camera).insert(SnapshotCamera(root));
+        app.world.entity_mut(root).insert(FrozenSnapshot {
+            image: Handle::default(),
+            size: Vec2::new(200., 100.),
+            camera: Some(camera),
+            frames: 0,
+            node: None,
+            hidden: vec![],
+            overlays: vec![],
+            overflow: Overflow::Visible,
+        });
+        for _ in 0..SNAPSHOT_FRAMES {
+            app.update();
+        }
+        assert!(!app.world.get::<FrozenSnapshot>(root).unwrap().is_shown());
+        app.update();
+        let snapshot = app.world.get::<FrozenSnapshot>(root).unwrap();
+        assert!(snapshot.is_shown());
+        assert!(app.world.get_entity(camera).is_none());
+        assert!(!app.world.get::<Visibility>(child).unwrap().is_visible);
+        let node = snapshot.node.unwrap();
+        let style = app.world.get::<Style>(node).unwrap();
+        assert_eq!(style.position.left, Val::Px(-50.));
+        assert_eq!(style.position.top, Val::Px(-20.));
+        assert_eq!(
+            app.world.get::<Style>(root).unwrap().overflow,
+            Overflow::Hidden
+        );
+
+        app.world.entity_mut(root).remove::<Frozen>();
+        app.update();
+        assert!(app.world.get::<FrozenSnapshot>(root).is_none());
+        assert!(app.world.get_entity(node).is_none());
+        assert!(app.world.get::<Visibility>(child).unwrap().is_visible);
+        assert_eq!(
+            app.world.get::<Style>(root).unwrap().overflow,
+            Overflow::Visible
+        );
+    }
+
+    #[test]
+    fn hide_overlays_while_capturing() {
+        let mut app = App::new();
+        app.add_system(hide_snapshot_overlays)
+            .add_system(show_frozen_snapshots.after(hide_snapshot_overlays));
+        let camera = app.world.spawn_empty().id();
+        let child = app
+            .world
+            .spawn((Node::default(), GlobalTransform::default()))
+            .id();
+        let root = app
+            .world
+            .spawn((
+                Frozen,
+                Node::default(),
+                GlobalTransform::from_xyz(50., 20., 0.),
+                Style::default(),
+            ))
+            .push_children(&[child])
+            .id();
+        let overlay = app
+            .world
+            .spawn((
+                Node::default(),
+                GlobalTransform::from_xyz(50., 20., 0.),
+                Visibility::default(),
+            ))
+            .id();
+        let aside = app
+            .world
+            .spawn((
+                Node::default(),
+                GlobalTransform::from_xyz(500., 20., 0.),
+                Visibility::default(),
+            ))
+            .id();
+        let below = app
+            .world
+            .spawn((
+                Node::default(),
+                GlobalTransform::from_xyz(50., 20., 0.),
+                Visibility::default(),
+            ))
+            .id();
+        app.insert_resource(UiStack {
+            uinodes: vec![below, root, child, overlay, aside],
+        });
+        app.world.entity_mut(root).insert(FrozenSnapshot {
+            image: Handle::default(),
+            size: Vec2::new(200., 100.),
+            camera: Some(camera),
+            frames: 0,
+            node: None,
+            hidden: vec![],
+            overlays: vec![],
+            overflow: Overflow::Visible,
+        });
+        let visible = |app: &App, entity| app.world.get::<Visibility>(entity).unwrap().is_visible;
+
+        for _ in 0..SNAPSHOT_FRAMES {
+            app.update();
+            assert!(!visible(&app, overlay));
+            assert!(visible(&app, aside));
+            assert!(visible(&app, below));
+        }
+        app.update();
+        assert!(app.world.get::<FrozenSnapshot>(root).unwrap().is_shown());
+        assert!(visible(&app, overlay));
+    }
+}
@@ -1,51 +1,99 @@
-use crate::{tags, Element, Elements};
+use crate::{
+    eml::Variant,
+    ess::NoPointerEvents,
+    propagation::{propagate_system, Propagate, Propagating},
+    relations::Signal,
+    tags,
+    touch::touch_position,
+    Element, ElementId, Elements, FeedbackAction, FeedbackRequest, States,
+};
 use bevy::{
-    ecs::query::WorldQuery,
-    input::InputSystem,
+    ecs::{entity::Entities, query::WorldQuery, system::SystemParam},
+    input::{keyboard::KeyboardInput, InputSystem},
     prelude::*,
     render::camera::RenderTarget,
     ui::{FocusPolicy, UiStack},
     utils::HashSet,
 };
+use tagstr::*;
 
 pub(crate) struct ElementsInputPlugin;
 impl Plugin for ElementsInputPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PointerInput>()
+            .add_event::<Propagating<PointerInput>>()
+            .add_event::<KeyInput>()
+            .add_event::<Propagating<KeyInput>>()
             .add_event::<RequestFocus>()
+            .add_event::<FocusEvent>()
             .init_resource::<Focused>()
+            .init_resource::<PointerSettings>()
             .add_system_to_stage(
                 CoreStage::PreUpdate,
                 pointer_input_system
-                    .label(Label::Signals)
+                    .label(Label::PointerInput)
                     .after(InputSystem),
             )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                propagate_system::<PointerInput>
+                    .label(Label::Signals)
+                    .after(Label::PointerInput),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                key_input_system.label(Label::KeyInput).after(Label::Focus),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                propagate_system::<KeyInput>
+                    .label(Label::KeySignals)
+                    .after(Label::KeyInput),
+            )
             .add_system_to_stage(
                 CoreStage::PreUpdate,
                 tab_focus_system
                     .label(Label::TabFocus)
                     .after(Label::Signals),
             )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                directional_focus_system
+                    .label(Label::DirectionalFocus)
+                    .after(Label::KeySignals),
+            )
             .add_system_to_stage(
                 CoreStage::PreUpdate,
                 focus_system.label(Label::Focus).after(Label::TabFocus),
             )
             .add_system_to_stage(
                 CoreStage::PreUpdate,
-                hover_system.label(Label::Hover).after(Label::Signals),
+                hover_system
+                    .label(Label::Hover)
+                    .after(Label::PointerInput)
+                    .before(Label::Signals),
             )
             .add_system_to_stage(
                 CoreStage::PreUpdate,
-                active_system.label(Label::Active).after(Label::Signals),
+                active_system
+                    .label(Label::Active)
+                    .after(Label::PointerInput)
+                    .before(Label::Signals),
             );
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
 pub enum Label {
+    PointerInput,
     Signals,
+    KeyInput,
+    KeySignals,
     TabFocus,
+    DirectionalFocus,
     Focus,
+    Shortcuts,
+    DragAndDrop,
     Hover,
     Active,
 }
@@ -59,6 +107,28 @@ pub enum PointerInputData {
     Drag { from: Vec<Entity> },
     DragStop,
     Motion,
+    DoubleClick,
+    LongPress,
+    SecondaryPress,
+}
+
+/// The timings of the pointer gestures.
+#[derive(Resource, Debug, Clone)]
+pub struct PointerSettings {
+    /// The longest delay between the presses of the double click, in seconds.
+    pub double_click_interval: f32,
+    /// How long the pointer should be held down to make the long press,
+    /// in seconds.
+    pub long_press_duration: f32,
+}
+
+impl Default for PointerSettings {
+    fn default() -> Self {
+        PointerSettings {
+            double_click_interval: 0.3,
+            long_press_duration: 0.5,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -163,6 +233,73 @@ impl PointerInput {
     pub fn motion(&self) -> bool {
         self.data == PointerInputData::Motion
     }
+
+    /// The second press in a row within the
+    /// [`PointerSettings::double_click_interval`].
+    pub fn double_click(&self) -> bool {
+        self.data == PointerInputData::DoubleClick
+    }
+
+    /// The pointer is held down without dragging for the
+    /// [`PointerSettings::long_press_duration`].
+    pub fn long_press(&self) -> bool {
+        self.data == PointerInputData::LongPress
+    }
+
+    /// The right mouse button is pressed or the touch is held for the
+    /// long press.
+    pub fn secondary_press(&self) -> bool {
+        self.data == PointerInputData::SecondaryPress
+    }
+}
+
+impl Propagate for PointerInput {
+    fn target(&self) -> Option<Entity> {
+        self.entities.first().copied()
+    }
+
+    fn retain(&mut self, reached: impl Fn(Entity) -> bool) -> bool {
+        self.entities.retain(|e| reached(*e));
+        !self.entities.is_empty()
+    }
+}
+
+/// The key pressed or released while the element is focused.
+#[derive(Debug)]
+pub struct KeyInput {
+    pub entities: [Entity; 1],
+    pub key: KeyCode,
+    pub pressed: bool,
+    /// The propagation is stopped by the listener: the arrow keys handled
+    /// by the widget don't move the focus.
+    pub stopped: bool,
+}
+
+impl KeyInput {
+    /// The direction of the arrow key.
+    pub fn direction(&self) -> Option<NavDirection> {
+        NavDirection::from_key(self.key)
+    }
+}
+
+impl Signal for KeyInput {
+    fn sources(&self) -> &[Entity] {
+        &self.entities
+    }
+}
+
+impl Propagate for KeyInput {
+    fn target(&self) -> Option<Entity> {
+        Some(self.entities[0])
+    }
+
+    fn retain(&mut self, reached: impl Fn(Entity) -> bool) -> bool {
+        reached(self.entities[0])
+    }
+
+    fn stopped(&mut self) {
+        self.stopped = true;
+    }
 }
 
 /// Contains entities whose Interaction should be set to None
@@ -177,6 +314,7 @@ pub struct State {
     last_cursor_position: Option<Vec2>,
     drag_accumulator: Vec2,
     dragging: bool,
+    long_pressed: bool,
 }
 
 /// Main query for [`ui_focus_system`]
@@ -190,11 +328,14 @@ pub struct NodeQuery {
     focus_policy: Option<&'static FocusPolicy>,
     calculated_clip: Option<&'static CalculatedClip>,
     computed_visibility: Option<&'static ComputedVisibility>,
+    no_pointer_events: Option<&'static NoPointerEvents>,
 }
 
 /// The system that sets Interaction for all UI elements based on the mouse cursor activity
 ///
 /// Entities with a hidden [`ComputedVisibility`] are always treated as released.
+/// The release after the long press doesn't press the entities. The right
+/// mouse button and the touch long press send the secondary press.
 pub fn pointer_input_system(
     mut state: Local<State>,
     camera: Query<(&Camera, Option<&UiCameraConfig>)>,
@@ -203,13 +344,17 @@ pub fn pointer_input_system(
     touches_input: Res<Touches>,
     ui_stack: Res<UiStack>,
     time: Res<Time>,
+    settings: Res<PointerSettings>,
     mut node_query: Query<NodeQuery>,
-    mut events: EventWriter<PointerInput>,
+    mut events: EventWriter<Propagating<PointerInput>>,
 ) {
     let up =
         mouse_button_input.just_released(MouseButton::Left) || touches_input.any_just_released();
     let down =
         mouse_button_input.just_pressed(MouseButton::Left) || touches_input.any_just_pressed();
+    let touch_down = touches_input.any_just_pressed();
+    let touch_up = touches_input.any_just_released();
+    let secondary = mouse_button_input.just_pressed(MouseButton::Right);
 
     let is_ui_disabled =
         |camera_ui| matches!(camera_ui, Some(&UiCameraConfig { show_ui: false, .. }));
@@ -232,8 +377,24 @@ pub fn pointer_input_system(
                 cursor_pos
             })
         })
-        .or_else(|| touches_input.first_pressed_position());
+        .or_else(|| {
+            // the released touch isn't pressed anymore, but it is still
+            // needed to send the release
+            touches_input
+                .first_pressed_position()
+                .or_else(|| {
+                    touches_input
+                        .iter_just_released()
+                        .next()
+                        .map(|t| t.position())
+                })
+                .map(|position| touch_position(&windows, position))
+        });
 
+    if touch_down {
+        // the new touch doesn't move the pointer from the last one
+        state.last_cursor_position = None;
+    }
     if down {
         state.press_position = cursor_position;
         state.drag_accumulator = Vec2::ZERO;
@@ -267,6 +428,10 @@ pub fn pointer_input_system(
                         return None;
                     }
                 }
+                // Nodes with `pointer-events: none` let the pointer through
+                if node.no_pointer_events.is_some() {
+                    return None;
+                }
 
                 let position = node.global_transform.translation();
                 let ui_position = position.truncate();
@@ -304,6 +469,7 @@ pub fn pointer_input_system(
     let mut drag_entities = vec![];
     let mut motion_entities = vec![];
     let mut drag_start_entities = vec![];
+    let mut secondary_entities = vec![];
     if delta.length_squared() > 0.0 && !state.dragging && !state.pressed_entities.is_empty() {
         state.dragging = true;
         drag_start_entities = state.pressed_entities.clone();
@@ -330,6 +496,9 @@ pub fn pointer_input_system(
             state.pressed_entities.push(entity);
             down_entities.push(entity);
         }
+        if secondary {
+            secondary_entities.push(entity);
+        }
         if up {
             up_entities.push(entity);
             let pressed_entity_idx = state.pressed_entities.iter().position(|e| *e == entity);
@@ -341,7 +510,8 @@ pub fn pointer_input_system(
         // if send_drag_start {
         //     drag_start_entities.push(entity);
         // }
-        if delta != Vec2::ZERO {
+        // the touch hovers the element it is put on
+        if delta != Vec2::ZERO || touch_down {
             if state.dragging {
                 drag_entities.push(entity);
             } else {
@@ -362,7 +532,8 @@ pub fn pointer_input_system(
 
     let Some(pos) = cursor_position else { return };
     if down_entities.len() > 0 {
-        if time.elapsed_seconds() - state.was_down_at < 0.3 && down_entities == state.was_down {
+        let interval = time.elapsed_seconds() - state.was_down_at;
+        if interval < settings.double_click_interval && down_entities == state.was_down {
             state.presses += 1;
         } else {
             state.presses = 0;
@@ -370,65 +541,113 @@ pub fn pointer_input_system(
         let presses = state.presses + 1;
         state.was_down = down_entities.clone();
         state.was_down_at = time.elapsed_seconds();
-        events.send(PointerInput {
+        state.long_pressed = false;
+        events.send(Propagating(PointerInput {
             pos,
             delta,
             entities: down_entities,
             data: PointerInputData::Down { presses },
-        });
+        }));
     }
-    if pressed_entities.len() > 0 {
+    // the touch dragged over the element scrolls it rather than presses
+    let scrolled = touch_up && state.dragging;
+    if pressed_entities.len() > 0 && !state.long_pressed && !scrolled {
         let presses = state.presses;
-        events.send(PointerInput {
+        events.send(Propagating(PointerInput {
             pos,
             delta,
             entities: pressed_entities.clone(),
             data: PointerInputData::Pressed { presses },
-        });
+        }));
+        if presses == 1 {
+            events.send(Propagating(PointerInput {
+                pos,
+                delta,
+                entities: pressed_entities.clone(),
+                data: PointerInputData::DoubleClick,
+            }));
+        }
+    }
+    let held = time.elapsed_seconds() - state.was_down_at;
+    if !up
+        && !state.long_pressed
+        && !state.dragging
+        && !state.pressed_entities.is_empty()
+        && held >= settings.long_press_duration
+    {
+        state.long_pressed = true;
+        events.send(Propagating(PointerInput {
+            pos,
+            delta,
+            entities: state.pressed_entities.clone(),
+            data: PointerInputData::LongPress,
+        }));
+        // the touch has no other way to make the secondary press
+        if touches_input.iter().next().is_some() {
+            secondary_entities = state.pressed_entities.clone();
+        }
+    }
+    if secondary_entities.len() > 0 {
+        events.send(Propagating(PointerInput {
+            pos,
+            delta,
+            entities: secondary_entities,
+            data: PointerInputData::SecondaryPress,
+        }));
     }
     if motion_entities.len() > 0 {
-        events.send(PointerInput {
+        events.send(Propagating(PointerInput {
             pos,
             delta,
             entities: motion_entities,
             data: PointerInputData::Motion,
-        });
+        }));
     }
     if drag_start_entities.len() > 0 {
         state.dragging_from = drag_start_entities.clone();
-        events.send(PointerInput {
+        events.send(Propagating(PointerInput {
             pos,
             delta,
             entities: drag_start_entities,
             data: PointerInputData::DragStart,
-        });
+        }));
     }
-    if drag_entities.len() > 0 && drag_stop_entities.is_empty() {
-        events.send(PointerInput {
+    // sent over nothing as well, so the drop targets are left
+    if state.dragging && delta != Vec2::ZERO && drag_stop_entities.is_empty() {
+        events.send(Propagating(PointerInput {
             pos,
             delta,
             entities: drag_entities,
             data: PointerInputData::Drag {
                 from: state.dragging_from.clone(),
             },
-        });
+        }));
     }
     if drag_stop_entities.len() > 0 {
-        events.send(PointerInput {
+        events.send(Propagating(PointerInput {
             pos,
             delta,
             entities: drag_stop_entities,
             data: PointerInputData::DragStop,
-        });
+        }));
     }
     if up_entities.len() > 0 {
         let presses = state.presses;
-        events.send(PointerInput {
+        events.send(Propagating(PointerInput {
             pos,
             delta,
             entities: up_entities,
             data: PointerInputData::Up { presses },
-        });
+        }));
+    }
+    if touch_up {
+        // the lifted touch doesn't hover anything
+        events.send(Propagating(PointerInput {
+            pos,
+            delta,
+            entities: vec![],
+            data: PointerInputData::Motion,
+        }));
     }
 
     if up {
@@ -442,23 +661,160 @@ pub fn pointer_input_system(
 #[derive(Component)]
 pub struct Focus(bool);
 
+/// The element receiving the key input.
 #[derive(Resource, Default)]
 pub struct Focused(Option<Entity>);
 
-pub struct RequestFocus(Entity);
+impl Focused {
+    pub fn get(&self) -> Option<Entity> {
+        self.0
+    }
+}
+
+/// Moves the focus to the element.
+pub struct RequestFocus(pub Entity);
+
+/// The order of the element in the Tab navigation, set by the `tab-index`
+/// param. The elements with the positive index go first in the ascending
+/// order, the ones with zero (and the interactable ones without the index)
+/// follow in the document order. The elements with the negative index are
+/// focused by the pointer only.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TabIndex(pub i32);
+
+/// Keeps the keyboard navigation inside the element, set by the
+/// `focus-trap` param: Tab and the arrows move the focus between its
+/// descendants only. The topmost visible trap wins, so the dialog opened
+/// over another one takes the navigation over.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct FocusTrap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl NavDirection {
+    pub fn from_key(key: KeyCode) -> Option<NavDirection> {
+        match key {
+            KeyCode::Up => Some(NavDirection::Up),
+            KeyCode::Down => Some(NavDirection::Down),
+            KeyCode::Left => Some(NavDirection::Left),
+            KeyCode::Right => Some(NavDirection::Right),
+            _ => None,
+        }
+    }
+
+    pub fn from_button(button: GamepadButtonType) -> Option<NavDirection> {
+        match button {
+            GamepadButtonType::DPadUp => Some(NavDirection::Up),
+            GamepadButtonType::DPadDown => Some(NavDirection::Down),
+            GamepadButtonType::DPadLeft => Some(NavDirection::Left),
+            GamepadButtonType::DPadRight => Some(NavDirection::Right),
+            _ => None,
+        }
+    }
+
+    /// The unit vector of the direction in the UI coordinates
+    /// (`y` grows downward).
+    pub fn vector(&self) -> Vec2 {
+        match self {
+            NavDirection::Up => Vec2::new(0., -1.),
+            NavDirection::Down => Vec2::new(0., 1.),
+            NavDirection::Left => Vec2::new(-1., 0.),
+            NavDirection::Right => Vec2::new(1., 0.),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavTarget {
+    Entity(Entity),
+    /// The id of the element, set as `nav-down="#submit"`.
+    Id(Tag),
+}
+
+impl NavTarget {
+    /// Parses the value of the `nav-*` param.
+    pub fn from_variant(value: Variant) -> Option<NavTarget> {
+        match value {
+            Variant::Entity(entity) => Some(NavTarget::Entity(entity)),
+            Variant::String(id) => Some(NavTarget::Id(id.trim_start_matches('#').as_tag())),
+            _ => None,
+        }
+    }
+}
+
+/// The explicit targets of the directional navigation, set by the
+/// `nav-up`, `nav-down`, `nav-left` and `nav-right` params. The nearest
+/// focusable element in the pressed direction is focused when the target
+/// for the direction isn't set.
+#[derive(Component, Debug, Clone, Default)]
+pub struct Navigation {
+    pub up: Option<NavTarget>,
+    pub down: Option<NavTarget>,
+    pub left: Option<NavTarget>,
+    pub right: Option<NavTarget>,
+}
+
+impl Navigation {
+    pub fn get(&self, direction: NavDirection) -> Option<&NavTarget> {
+        match direction {
+            NavDirection::Up => self.up.as_ref(),
+            NavDirection::Down => self.down.as_ref(),
+            NavDirection::Left => self.left.as_ref(),
+            NavDirection::Right => self.right.as_ref(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.up.is_none() && self.down.is_none() && self.left.is_none() && self.right.is_none()
+    }
+}
+
+pub enum FocusEvent {
+    Focus([Entity; 1]),
+    Blur([Entity; 1]),
+}
+
+impl FocusEvent {
+    pub fn focused(&self) -> bool {
+        matches!(self, FocusEvent::Focus(_))
+    }
+    pub fn blurred(&self) -> bool {
+        matches!(self, FocusEvent::Blur(_))
+    }
+}
+
+impl Signal for FocusEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            FocusEvent::Focus(source) => source,
+            FocusEvent::Blur(source) => source,
+        }
+    }
+}
 
 pub fn focus_system(
     mut focused: ResMut<Focused>,
     // mut elements: Query<(Entity, &mut Element)>,
     mut elements: Elements,
+    entities: &Entities,
     interactable: Query<Entity, (With<Interaction>, With<Element>)>,
     mut signals: EventReader<PointerInput>,
     mut requests: EventReader<RequestFocus>,
-    mut dirty: Local<Vec<Entity>>,
+    mut feedback: EventWriter<FeedbackRequest>,
+    mut focus_events: EventWriter<FocusEvent>,
 ) {
-    dirty.clear();
+    if matches!(focused.0, Some(entity) if !entities.contains(entity)) {
+        focused.0 = None;
+    }
     let mut target_focus = None;
     let mut update_required = false;
+    let mut requested = false;
     for signal in signals.iter().filter(|s| s.down()) {
         for entity in interactable.iter_many(&signal.entities) {
             update_required = true;
@@ -469,37 +825,40 @@ pub fn focus_system(
     }
     for RequestFocus(entity) in requests.iter() {
         update_required = true;
+        requested = true;
         target_focus = Some(*entity);
     }
 
     if update_required && target_focus != focused.0 {
         if let Some(was_focused) = focused.0 {
-            if let Ok(mut element) = elements.get_mut(was_focused) {
-                element.state.remove(&tags::focus());
-                dirty.push(was_focused);
-            }
+            elements.set_state(was_focused, tags::focus(), false);
+            focus_events.send(FocusEvent::Blur([was_focused]));
         }
         if let Some(target_focus) = target_focus {
-            if let Ok(mut element) = elements.get_mut(target_focus) {
-                element.state.insert(tags::focus());
-                dirty.push(target_focus);
+            elements.set_state(target_focus, tags::focus(), true);
+            focus_events.send(FocusEvent::Focus([target_focus]));
+            if requested {
+                feedback.send(FeedbackRequest::new(
+                    FeedbackAction::MoveFocus,
+                    target_focus,
+                ));
             }
         }
         focused.0 = target_focus;
     }
-    dirty.iter().for_each(|e| elements.invalidate(*e));
 }
 
+/// Reads the raw hits before the propagation, so the listeners stopping
+/// the propagation or preventing the default don't affect the `:hover` state.
 pub fn hover_system(
-    mut events: EventReader<PointerInput>,
+    mut events: EventReader<Propagating<PointerInput>>,
     mut elements: Elements,
     mut hovered_entities: Local<HashSet<Entity>>,
-    mut dirty: Local<Vec<Entity>>,
 ) {
-    dirty.clear();
     let mut any_motion = false;
     let new_hovered_entities: HashSet<_> = events
         .iter()
+        .map(|Propagating(e)| e)
         .filter(|e| e.motion() || e.dragging())
         .map(|e| {
             any_motion = true;
@@ -514,34 +873,26 @@ pub fn hover_system(
 
     // remove hovered state
     for entity in hovered_entities.difference(&new_hovered_entities) {
-        if let Ok(mut element) = elements.get_mut(*entity) {
-            element.state.remove(&tags::hover());
-            dirty.push(*entity);
-        }
+        elements.set_state(*entity, tags::hover(), false);
     }
     // add hovered state to newely hovered entityes
     for entity in new_hovered_entities.difference(&hovered_entities) {
-        if let Ok(mut element) = elements.get_mut(*entity) {
-            element.state.insert(tags::hover());
-            dirty.push(*entity);
-        }
+        elements.set_state(*entity, tags::hover(), true);
     }
     *hovered_entities = new_hovered_entities;
-    for entity in dirty.iter() {
-        elements.invalidate(*entity);
-    }
 }
 
+/// Like the [`hover_system`], reads the raw hits before the propagation.
 pub fn active_system(
     mut elements: Elements,
-    mut events: EventReader<PointerInput>,
+    mut events: EventReader<Propagating<PointerInput>>,
     mut active_elements: Local<HashSet<Entity>>,
     mut add_active: Local<HashSet<Entity>>,
     mut remove_active: Local<HashSet<Entity>>,
 ) {
     add_active.clear();
     remove_active.clear();
-    for event in events.iter() {
+    for Propagating(event) in events.iter() {
         match &event.data {
             PointerInputData::Drag { from } => {
                 if event.dragging_over_self() {
@@ -565,33 +916,423 @@ pub fn active_system(
             continue;
         }
         active_elements.insert(*entity);
-        elements.invalidate(*entity);
-        if let Ok(mut element) = elements.get_mut(*entity) {
-            element.state.insert(tags::active());
-        }
+        elements.set_state(*entity, tags::active(), true);
     }
     for entity in remove_active.iter() {
         if !active_elements.contains(entity) {
             continue;
         }
         active_elements.remove(entity);
-        elements.invalidate(*entity);
-        if let Ok(mut element) = elements.get_mut(*entity) {
-            element.state.remove(&tags::active());
+        elements.set_state(*entity, tags::active(), false);
+    }
+}
+
+pub fn key_input_system(
+    focused: Res<Focused>,
+    mut keys: EventReader<KeyboardInput>,
+    mut events: EventWriter<Propagating<KeyInput>>,
+) {
+    let Some(target) = focused.0 else {
+        keys.clear();
+        return;
+    };
+    for input in keys.iter() {
+        if let Some(key) = input.key_code {
+            events.send(Propagating(KeyInput {
+                entities: [target],
+                key,
+                pressed: input.state.is_pressed(),
+                stopped: false,
+            }));
         }
     }
 }
 
+/// The elements the keyboard navigation could move the focus to.
+#[derive(SystemParam)]
+pub struct Focusable<'w, 's> {
+    elements: Query<
+        'w,
+        's,
+        (
+            Option<&'static TabIndex>,
+            Option<&'static States>,
+            Option<&'static ComputedVisibility>,
+        ),
+        (With<Element>, Or<(With<Interaction>, With<TabIndex>)>),
+    >,
+    traps: Query<'w, 's, (Entity, Option<&'static ComputedVisibility>), With<FocusTrap>>,
+    parents: Query<'w, 's, &'static Parent>,
+    ui_stack: Res<'w, UiStack>,
+}
+
+impl<'w, 's> Focusable<'w, 's> {
+    /// The tab index of the element if the element is focusable by
+    /// the keyboard: it isn't disabled, hidden or removed from the
+    /// navigation by the negative index.
+    pub fn tab_index(&self, entity: Entity) -> Option<i32> {
+        let (index, states, visibility) = self.elements.get(entity).ok()?;
+        let index = index.map(|i| i.0).unwrap_or_default();
+        let disabled = states.map_or(false, |s| s.contains(&tags::disabled()));
+        let hidden = visibility.map_or(false, |v| !v.is_visible());
+        (index >= 0 && !disabled && !hidden).then_some(index)
+    }
+
+    /// The topmost visible [`FocusTrap`], if any.
+    pub fn trap(&self) -> Option<Entity> {
+        self.traps
+            .iter()
+            .filter(|(_, visibility)| visibility.map_or(true, |v| v.is_visible()))
+            .max_by_key(|(entity, _)| self.ui_stack.uinodes.iter().position(|e| e == entity))
+            .map(|(entity, _)| entity)
+    }
+
+    /// Returns `true` if the element is inside of the `trap`.
+    pub fn trapped(&self, entity: Entity, trap: Option<Entity>) -> bool {
+        let Some(trap) = trap else {
+            return true;
+        };
+        entity == trap || self.parents.iter_ancestors(entity).any(|e| e == trap)
+    }
+}
+
+/// Moves the focus to the next element on Tab and to the previous one
+/// on Shift-Tab, see [`TabIndex`] for the order.
 pub fn tab_focus_system(
     keyboard: Res<Input<KeyCode>>,
-    elements: Query<(Entity, &Element), With<Interaction>>,
+    focused: Res<Focused>,
+    roots: Query<Entity, (With<Element>, Without<Parent>)>,
+    children: Query<&Children>,
+    focusable: Focusable,
     mut requests: EventWriter<RequestFocus>,
 ) {
     if !keyboard.just_pressed(KeyCode::Tab) {
         return;
     }
-    for (entity, _) in elements.iter() {
+    let backward = keyboard.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let mut roots: Vec<_> = match focusable.trap() {
+        Some(trap) => vec![trap],
+        None => roots.iter().collect(),
+    };
+    roots.sort();
+    let mut order = vec![];
+    let mut stack: Vec<_> = roots.into_iter().rev().collect();
+    while let Some(entity) = stack.pop() {
+        if let Some(index) = focusable.tab_index(entity) {
+            order.push((index, entity));
+        }
+        if let Ok(children) = children.get(entity) {
+            stack.extend(children.iter().rev());
+        }
+    }
+    if order.is_empty() {
+        return;
+    }
+    // the sort is stable, so the document order is kept for the same index
+    order.sort_by_key(|(index, _)| if *index > 0 { *index } else { i32::MAX });
+    let current = focused
+        .0
+        .and_then(|focused| order.iter().position(|(_, e)| *e == focused));
+    let next = match (current, backward) {
+        (Some(idx), false) => (idx + 1) % order.len(),
+        (Some(idx), true) => (idx + order.len() - 1) % order.len(),
+        (None, false) => 0,
+        (None, true) => order.len() - 1,
+    };
+    requests.send(RequestFocus(order[next].1));
+}
+
+/// Moves the focus with the arrow keys and the gamepad D-pad to the
+/// [`Navigation`] target of the focused element or to the nearest
+/// focusable element in the pressed direction. The arrow keys are read
+/// from the delivered [`KeyInput`], so the widget could keep them by
+/// preventing the default.
+pub fn directional_focus_system(
+    focused: Res<Focused>,
+    keyboard: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut keys: EventReader<KeyInput>,
+    focusable: Focusable,
+    navigation: Query<&Navigation>,
+    ids: Query<(Entity, &ElementId)>,
+    nodes: Query<(Entity, &Node, &GlobalTransform)>,
+    mut requests: EventWriter<RequestFocus>,
+) {
+    let mut direction = keys
+        .iter()
+        .filter(|k| k.pressed && !k.stopped)
+        .filter_map(|k| k.direction())
+        .last();
+    if focused.0.is_none() {
+        direction = direction.or_else(|| {
+            keyboard
+                .get_just_pressed()
+                .filter_map(|k| NavDirection::from_key(*k))
+                .last()
+        });
+    }
+    direction = direction.or_else(|| {
+        gamepads
+            .iter()
+            .flat_map(|gamepad| {
+                buttons
+                    .get_just_pressed()
+                    .filter(move |b| b.gamepad == gamepad)
+            })
+            .filter_map(|b| NavDirection::from_button(b.button_type))
+            .last()
+    });
+    let Some(direction) = direction else {
+        return;
+    };
+    let trap = focusable.trap();
+    let explicit = focused
+        .0
+        .and_then(|e| navigation.get(e).ok())
+        .and_then(|nav| nav.get(direction))
+        .and_then(|target| match target {
+            NavTarget::Entity(entity) => Some(*entity),
+            NavTarget::Id(id) => ids.iter().find(|(_, e)| e.0 == *id).map(|(e, _)| e),
+        })
+        .filter(|target| focusable.trapped(*target, trap));
+    if let Some(target) = explicit {
+        requests.send(RequestFocus(target));
+        return;
+    }
+    let axis = direction.vector();
+    let candidates = nodes.iter().filter(|(e, _, _)| {
+        Some(*e) != focused.0 && focusable.tab_index(*e).is_some() && focusable.trapped(*e, trap)
+    });
+    let Some((_, from_node, from)) = focused.0.and_then(|e| nodes.get(e).ok()) else {
+        // nothing is focused yet: start from the outermost element
+        // on the side opposite to the direction
+        let first = candidates
+            .map(|(e, _, t)| (e, t.translation().truncate().dot(axis)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(e, _)| e);
+        if let Some(entity) = first {
+            requests.send(RequestFocus(entity));
+        }
+        return;
+    };
+    let from = from.translation().truncate();
+    let nearest = candidates
+        .filter_map(|(entity, node, transform)| {
+            let offset = transform.translation().truncate() - from;
+            let distance = offset.dot(axis);
+            if distance <= 0. {
+                return None;
+            }
+            // the gap between the edges along the direction
+            let extents = (from_node.size() + node.size()) / 2. * axis.abs();
+            let gap = (distance - extents.x - extents.y).max(0.);
+            let orthogonal = offset.perp_dot(axis).abs();
+            Some((entity, gap + 2. * orthogonal))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(e, _)| e);
+    if let Some(entity) = nearest {
         requests.send(RequestFocus(entity));
-        break;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hover_and_active_ignore_propagation() {
+        use crate::propagation::{Listen, PropagationContext};
+        let mut app = App::new();
+        app.add_event::<PointerInput>()
+            .add_event::<Propagating<PointerInput>>()
+            .add_system(hover_system.before(propagate_system::<PointerInput>))
+            .add_system(active_system.before(propagate_system::<PointerInput>))
+            .add_system(propagate_system::<PointerInput>);
+        let root = app.world.spawn(Element::default()).id();
+        let button = app.world.spawn(Element::default()).id();
+        app.world.entity_mut(root).push_children(&[button]);
+        Listen::bubble(button, |ctx: &mut PropagationContext<PointerInput>| {
+            ctx.stop_propagation();
+            ctx.prevent_default();
+        })
+        .write(&mut app.world);
+        let send = |app: &mut App, data: PointerInputData| {
+            app.world
+                .resource_mut::<Events<Propagating<PointerInput>>>()
+                .send(Propagating(PointerInput {
+                    entities: vec![button, root],
+                    pos: Vec2::ZERO,
+                    delta: Vec2::ZERO,
+                    data,
+                }));
+            app.update();
+        };
+        let has_state = |app: &App, entity: Entity, state: Tag| {
+            app.world
+                .get::<States>(entity)
+                .map_or(false, |s| s.contains(&state))
+        };
+
+        send(&mut app, PointerInputData::Motion);
+        assert!(has_state(&app, button, tags::hover()));
+        assert!(
+            has_state(&app, root, tags::hover()),
+            "Stopped propagation should not drop the hover of the ancestors"
+        );
+        send(&mut app, PointerInputData::Down { presses: 1 });
+        assert!(has_state(&app, root, tags::active()));
+        send(&mut app, PointerInputData::Up { presses: 1 });
+        assert!(
+            !has_state(&app, button, tags::active()),
+            "Prevented release should not leave the element active"
+        );
+        assert!(!has_state(&app, root, tags::active()));
+    }
+
+    #[test]
+    fn tab_navigation() {
+        let mut app = App::new();
+        app.add_event::<PointerInput>()
+            .add_event::<RequestFocus>()
+            .add_event::<FocusEvent>()
+            .add_event::<FeedbackRequest>()
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<Focused>()
+            .init_resource::<UiStack>()
+            .add_system(tab_focus_system)
+            .add_system(focus_system.after(tab_focus_system));
+        let interactable = || (Element::default(), Interaction::default());
+        let a = app.world.spawn(interactable()).id();
+        let b = app.world.spawn((interactable(), TabIndex(-1))).id();
+        let c = app.world.spawn(interactable()).id();
+        let d = app.world.spawn((Element::default(), TabIndex(1))).id();
+        app.world
+            .spawn(Element::default())
+            .push_children(&[a, b, c, d]);
+        let tab = |app: &mut App, shift: bool| {
+            {
+                let mut keyboard = app.world.resource_mut::<Input<KeyCode>>();
+                keyboard.release_all();
+                keyboard.clear();
+                if shift {
+                    keyboard.press(KeyCode::LShift);
+                }
+                keyboard.press(KeyCode::Tab);
+            }
+            app.update();
+            app.world.resource::<Focused>().get()
+        };
+
+        assert_eq!(tab(&mut app, false), Some(d), "Positive index goes first");
+        assert_eq!(tab(&mut app, false), Some(a));
+        assert_eq!(tab(&mut app, false), Some(c), "Negative index is skipped");
+        assert_eq!(tab(&mut app, false), Some(d));
+        assert_eq!(tab(&mut app, true), Some(c));
+        let states = app.world.get::<States>(c).unwrap();
+        assert!(states.contains(&tags::focus()));
+        let states = app.world.get::<States>(d).unwrap();
+        assert!(!states.contains(&tags::focus()));
+
+        let e = app.world.spawn(interactable()).id();
+        let f = app.world.spawn(interactable()).id();
+        app.world
+            .spawn((Element::default(), FocusTrap))
+            .push_children(&[e, f]);
+        assert_eq!(tab(&mut app, false), Some(e), "Should enter the trap");
+        assert_eq!(tab(&mut app, false), Some(f));
+        assert_eq!(tab(&mut app, false), Some(e), "Should stay in the trap");
+    }
+
+    #[test]
+    fn directional_navigation() {
+        let mut app = App::new();
+        app.add_event::<PointerInput>()
+            .add_event::<KeyInput>()
+            .add_event::<RequestFocus>()
+            .add_event::<FocusEvent>()
+            .add_event::<FeedbackRequest>()
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<Input<GamepadButton>>()
+            .init_resource::<Gamepads>()
+            .init_resource::<Focused>()
+            .init_resource::<UiStack>()
+            .add_system(directional_focus_system)
+            .add_system(focus_system.after(directional_focus_system));
+        let mut button = |x: f32, y: f32| {
+            app.world
+                .spawn((
+                    Element::default(),
+                    Interaction::default(),
+                    Node::default(),
+                    GlobalTransform::from_xyz(x, y, 0.),
+                ))
+                .id()
+        };
+        // a b
+        // c   d
+        let a = button(0., 0.);
+        let b = button(100., 0.);
+        let c = button(0., 100.);
+        let d = button(200., 110.);
+        app.world.entity_mut(c).insert(ElementId("c".as_tag()));
+        app.world.entity_mut(a).insert(Navigation {
+            left: Some(NavTarget::Id("c".as_tag())),
+            ..default()
+        });
+        let press = |app: &mut App, key: KeyCode| {
+            if let Some(focused) = app.world.resource::<Focused>().get() {
+                app.world.resource_mut::<Events<KeyInput>>().send(KeyInput {
+                    entities: [focused],
+                    key,
+                    pressed: true,
+                    stopped: false,
+                });
+            } else {
+                let mut keyboard = app.world.resource_mut::<Input<KeyCode>>();
+                keyboard.clear();
+                keyboard.press(key);
+            }
+            app.update();
+            app.world.resource::<Focused>().get()
+        };
+
+        assert_eq!(
+            press(&mut app, KeyCode::Up),
+            Some(d),
+            "Should start from the opposite side"
+        );
+        assert_eq!(press(&mut app, KeyCode::Left), Some(c));
+        assert_eq!(press(&mut app, KeyCode::Up), Some(a));
+        assert_eq!(press(&mut app, KeyCode::Right), Some(b));
+        assert_eq!(
+            press(&mut app, KeyCode::Down),
+            Some(c),
+            "Should prefer the aligned element"
+        );
+        assert_eq!(press(&mut app, KeyCode::Up), Some(a));
+        assert_eq!(
+            press(&mut app, KeyCode::Up),
+            Some(a),
+            "Should keep the focus at the edge"
+        );
+        assert_eq!(
+            press(&mut app, KeyCode::Left),
+            Some(c),
+            "Should follow the nav target"
+        );
+        app.world.resource_mut::<Events<KeyInput>>().send(KeyInput {
+            entities: [c],
+            key: KeyCode::Right,
+            pressed: true,
+            stopped: true,
+        });
+        app.update();
+        assert_eq!(
+            app.world.resource::<Focused>().get(),
+            Some(c),
+            "Should not move the focus with the key handled by the widget"
+        );
     }
 }
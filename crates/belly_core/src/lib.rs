@@ -4,21 +4,46 @@ use bevy::utils::HashMap;
 use bevy::{ecs::system::EntityCommands, prelude::*};
 use eml::build::BuildPligin;
 use eml::EmlPlugin;
-use ess::{EssPlugin, StyleSheet, StyleSheetParser};
+use ess::{
+    DynamicValue, EssPlugin, LineHeight, StyleProperty, StyleSheet, StyleSheetParser,
+    ValueFunctions,
+};
+use context_menu::ContextMenuPlugin;
+use dnd::DragAndDropPlugin;
+use feedback::FeedbackPlugin;
+use follow::FollowPlugin;
+use freeze::FreezePlugin;
 use input::ElementsInputPlugin;
+use shortcut::ShortcutPlugin;
+use touch::TouchPlugin;
 use std::error::Error;
 use std::fmt::Display;
 use std::sync::{Arc, RwLock};
 
+pub mod context_menu;
+pub mod dnd;
 pub mod element;
 pub mod eml;
 pub mod ess;
+pub mod feedback;
+pub mod follow;
+pub mod freeze;
 pub mod input;
+pub mod propagation;
 pub mod relations;
+pub mod shortcut;
 pub mod tags;
+pub mod touch;
 
 pub struct ElementsCorePlugin;
 
+pub use crate::context_menu::ContextMenu;
+pub use crate::context_menu::ContextMenuElement;
+pub use crate::context_menu::ContextMenuPopup;
+pub use crate::context_menu::KeepContextMenuOpen;
+pub use crate::context_menu::OpenContextMenu;
+pub use crate::dnd::DragEvent;
+pub use crate::dnd::DragPayload;
 pub use crate::eml::build::ElementBuilder;
 pub use crate::eml::build::ElementBuilderRegistry;
 pub use crate::eml::build::ElementContext;
@@ -29,19 +54,51 @@ pub use crate::eml::build::WidgetBuilder;
 pub use crate::eml::content::ExpandElements;
 pub use crate::eml::content::ExpandElementsExt;
 pub use crate::eml::content::IntoContent;
+pub use crate::eml::export::ToEmlExtension;
+pub use crate::eml::handlers::EventHandlers;
+pub use crate::eml::handlers::RegisterHandlerExtension;
 pub use crate::eml::Param;
 pub use crate::eml::Params;
 pub use crate::eml::Variant;
 pub use crate::ess::managed;
+pub use crate::ess::ScrollEvent;
 pub use crate::ess::CompoundProperty;
+pub use crate::ess::EditStyleRule;
+pub use crate::ess::PropertyKeyword;
 pub use crate::ess::PropertyValue;
 pub use crate::ess::StylePropertyMethods;
 pub use crate::ess::ToRectMap;
+pub use crate::feedback::Feedback;
+pub use crate::feedback::FeedbackAction;
+pub use crate::feedback::FeedbackMap;
+pub use crate::feedback::FeedbackOverride;
+pub use crate::feedback::FeedbackRequest;
+pub use crate::follow::Follow;
 pub use crate::input::PointerInput;
 pub use crate::input::PointerInputData;
+pub use crate::input::PointerSettings;
+pub use crate::input::FocusEvent;
+pub use crate::input::FocusTrap;
+pub use crate::input::Focused;
+pub use crate::input::KeyInput;
+pub use crate::input::NavDirection;
+pub use crate::input::NavTarget;
+pub use crate::input::Navigation;
+pub use crate::input::RequestFocus;
+pub use crate::input::TabIndex;
+pub use crate::propagation::Listen;
+pub use crate::propagation::Phase;
+pub use crate::propagation::PropagationContext;
 pub use crate::relations::Connect;
+pub use crate::relations::ConnectionId;
+pub use crate::relations::Disconnect;
 pub use crate::relations::ConnectionTo;
 pub use crate::relations::Signal;
+pub use crate::shortcut::Shortcut;
+pub use crate::shortcut::ShortcutEvent;
+pub use crate::shortcut::ShortcutScope;
+pub use crate::touch::PinchEvent;
+pub use crate::touch::Zoomable;
 pub use crate::element::ElementBundle;
 pub use crate::element::TextElementBundle;
 pub use crate::element::ImageElementBundle;
@@ -51,12 +108,28 @@ pub use crate::relations::bind::Prop;
 pub use crate::relations::transform::TransformableTo;
 
 // new bound system
+pub use crate::relations::bind::BindId;
+pub use crate::relations::bind::BindingStats;
+pub use crate::relations::bind::RateLimit;
+pub use crate::relations::bind::RateLimitExtension;
+pub use crate::relations::bind::Unbind;
+pub use crate::relations::reflect::ReflectBindExtension;
 pub use crate::relations::bind::TransformationError;
 pub use crate::relations::bind::TransformationResult;
 pub use crate::relations::transform::ColorTransformerExtension;
+pub use crate::relations::transform::ValTransformerExtension;
 
+pub use element::Classes;
 pub use element::Element;
+pub use element::ElementChanged;
+pub use element::ElementId;
+pub use element::ElementRef;
+pub use element::ElementTag;
 pub use element::Elements;
+pub use element::Frozen;
+pub use element::States;
+pub use element::Tooltip;
+pub use freeze::FrozenSnapshot;
 pub use ess::Property;
 pub use tagstr;
 pub use tagstr::*;
@@ -73,12 +146,14 @@ pub mod build {
     pub use crate::tag;
 
     // traits
+    pub use crate::ess::Property;
     pub use crate::ess::RegisterProperty;
     pub use crate::ess::StylePropertyMethods;
 
     // structs
     pub use crate::ess::PropertyValue;
     pub use crate::ess::StyleProperty;
+    pub use crate::ElementsError;
     pub use crate::Tag;
     pub use crate::Variant;
 }
@@ -90,6 +165,13 @@ impl Plugin for ElementsCorePlugin {
             // .init_resource::<input::Focused>()
             .insert_resource(Defaults::default())
             .add_plugin(ElementsInputPlugin)
+            .add_plugin(ShortcutPlugin)
+            .add_plugin(DragAndDropPlugin)
+            .add_plugin(TouchPlugin)
+            .add_plugin(ContextMenuPlugin)
+            .add_plugin(FeedbackPlugin)
+            .add_plugin(FollowPlugin)
+            .add_plugin(FreezePlugin)
             .add_plugin(RelationsPlugin)
             .add_plugin(BuildPligin)
             .add_plugin(EssPlugin)
@@ -148,25 +230,54 @@ impl<'w, 's, 'a> WithElements for EntityCommands<'w, 's, 'a> {
 // pub(crate) type TransformProperty = Box<dyn Fn(&StyleProperty) -> Result<(), ElementsError>>;
 pub(crate) type TransformProperty = fn(Variant) -> Result<PropertyValue, ElementsError>;
 #[derive(Default, Clone, Resource)]
-pub struct PropertyTransformer(Arc<RwLock<HashMap<Tag, TransformProperty>>>);
+pub struct PropertyTransformer(Arc<RwLock<HashMap<Tag, TransformProperty>>>, ValueFunctions);
 unsafe impl Send for PropertyTransformer {}
 unsafe impl Sync for PropertyTransformer {}
 impl PropertyTransformer {
     #[cfg(test)]
     pub(crate) fn new(rules: HashMap<Tag, TransformProperty>) -> PropertyTransformer {
-        PropertyTransformer(Arc::new(RwLock::new(rules)))
+        PropertyTransformer(Arc::new(RwLock::new(rules)), Default::default())
     }
+
+    pub(crate) fn functions(&self) -> &ValueFunctions {
+        &self.1
+    }
+
     pub(crate) fn transform(
         &self,
         name: Tag,
         value: Variant,
     ) -> Result<PropertyValue, ElementsError> {
-        self.0
-            .read()
-            .unwrap()
+        // `all` is not a real property and accepts only css-wide keywords
+        if name == tag!("all") {
+            return PropertyKeyword::from_variant(&value)
+                .map(PropertyValue::new)
+                .ok_or_else(|| {
+                    ElementsError::InvalidPropertyValue(format!(
+                        "`all` accepts only initial, inherit or unset, got {value:?}"
+                    ))
+                });
+        }
+        let transformers = self.0.read().unwrap();
+        let transform = transformers
             .get(&name)
-            .ok_or(ElementsError::UnsupportedProperty(name.to_string()))
-            .and_then(|transform| transform(value))
+            .ok_or(ElementsError::UnsupportedProperty(name.to_string()))?;
+        // css-wide keywords are accepted by any property
+        if let Some(keyword) = PropertyKeyword::from_variant(&value) {
+            return Ok(PropertyValue::new(keyword));
+        }
+        // values calling the value functions are parsed when applied
+        let dynamic = match &value {
+            Variant::Style(style) if self.1.is_dynamic(style) => Some(style.clone()),
+            Variant::String(source) => StyleProperty::try_from(source.as_str())
+                .ok()
+                .filter(|style| self.1.is_dynamic(style)),
+            _ => None,
+        };
+        if let Some(style) = dynamic {
+            return Ok(PropertyValue::new(DynamicValue(style)));
+        }
+        transform(value)
     }
 }
 
@@ -249,11 +360,20 @@ pub fn setup_defaults(
 }
 
 pub fn fix_text_height(
-    mut texts: Query<(&Text, &mut Style), Or<(Changed<Text>, Changed<TextLayoutInfo>)>>,
+    mut texts: Query<
+        (&Text, &mut Style, Option<&LineHeight>),
+        Or<(Changed<Text>, Changed<TextLayoutInfo>)>,
+    >,
 ) {
-    for (text, mut style) in texts.iter_mut() {
+    for (text, mut style, line_height) in texts.iter_mut() {
         if text.sections.len() > 0 {
-            style.size.height = Val::Px(text.sections[0].style.font_size);
+            let font_size = text.sections[0].style.font_size;
+            let height = line_height
+                .map(|l| l.resolve(font_size))
+                .unwrap_or(font_size);
+            if style.size.height != Val::Px(height) {
+                style.size.height = Val::Px(height);
+            }
         }
     }
 }
@@ -0,0 +1,327 @@
+use bevy::{ecs::system::Command, prelude::*};
+
+use crate::relations::Signal;
+
+/// The event propagated through the element hierarchy: the listeners of
+/// the ancestors are called from the root down to the `target` (capture),
+/// after that from the `target` up to the root (bubble). The widgets handle
+/// the event when the propagation is over.
+pub trait Propagate: Signal {
+    /// The deepest element the event is dispatched to.
+    fn target(&self) -> Option<Entity>;
+
+    /// Keeps the elements the widgets should handle the event for, the
+    /// elements the propagation didn't reach are removed. Returns `false`
+    /// if no element is left.
+    fn retain(&mut self, reached: impl Fn(Entity) -> bool) -> bool;
+
+    /// Called when a listener stops the propagation, so the systems
+    /// handling the event could tell it was handled on the way.
+    fn stopped(&mut self) {}
+}
+
+/// The event waiting for the propagation. The input systems send it
+/// instead of the event itself, the event is sent after the listeners
+/// are called unless the default is prevented.
+pub struct Propagating<E: Propagate>(pub E);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Capture,
+    Target,
+    Bubble,
+}
+
+pub struct PropagationContext<'a, 'w, 's, E: Propagate> {
+    event: &'a E,
+    target: Entity,
+    current: Entity,
+    phase: Phase,
+    stopped: bool,
+    prevented: bool,
+    commands: &'a mut Commands<'w, 's>,
+}
+
+impl<'a, 'w, 's, E: Propagate> PropagationContext<'a, 'w, 's, E> {
+    pub fn event(&self) -> &E {
+        self.event
+    }
+    /// The deepest element the event is dispatched to.
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+    /// The element the listener is called for.
+    pub fn current(&self) -> Entity {
+        self.current
+    }
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+    /// Stops the propagation once the listeners of the current element are
+    /// called. The widgets of the elements the event didn't reach don't
+    /// handle it.
+    pub fn stop_propagation(&mut self) {
+        self.stopped = true;
+    }
+    /// Keeps the widgets from handling the event at all.
+    pub fn prevent_default(&mut self) {
+        self.prevented = true;
+    }
+    pub fn default_prevented(&self) -> bool {
+        self.prevented
+    }
+    pub fn commands(&mut self) -> &mut Commands<'w, 's> {
+        self.commands
+    }
+}
+
+pub type Listener<E> = Box<dyn Fn(&mut PropagationContext<E>) + Send + Sync>;
+
+/// The listeners of the propagated events attached to the element,
+/// added with the [`Listen`] command.
+#[derive(Component)]
+pub struct Listeners<E: Propagate> {
+    capture: Vec<Listener<E>>,
+    bubble: Vec<Listener<E>>,
+}
+
+impl<E: Propagate> Default for Listeners<E> {
+    fn default() -> Self {
+        Listeners {
+            capture: vec![],
+            bubble: vec![],
+        }
+    }
+}
+
+/// Adds the listener of the propagated event to the element:
+/// ```rust,ignore
+/// commands.add(Listen::bubble(card, |ctx: &mut PropagationContext<PointerInput>| {
+///     if ctx.event().pressed() {
+///         ctx.stop_propagation();
+///     }
+/// }));
+/// ```
+/// The listener is removed with the element.
+pub struct Listen<E: Propagate> {
+    entity: Entity,
+    capture: bool,
+    listener: Listener<E>,
+}
+
+impl<E: Propagate> Listen<E> {
+    /// Listens the event on the way down to the target.
+    pub fn capture<F>(entity: Entity, listener: F) -> Listen<E>
+    where
+        F: Fn(&mut PropagationContext<E>) + Send + Sync + 'static,
+    {
+        Listen {
+            entity,
+            capture: true,
+            listener: Box::new(listener),
+        }
+    }
+
+    /// Listens the event on the way up from the target.
+    pub fn bubble<F>(entity: Entity, listener: F) -> Listen<E>
+    where
+        F: Fn(&mut PropagationContext<E>) + Send + Sync + 'static,
+    {
+        Listen {
+            entity,
+            capture: false,
+            listener: Box::new(listener),
+        }
+    }
+}
+
+impl<E: Propagate> Command for Listen<E> {
+    fn write(self, world: &mut World) {
+        let Some(mut entity) = world.get_entity_mut(self.entity) else {
+            return;
+        };
+        if !entity.contains::<Listeners<E>>() {
+            entity.insert(Listeners::<E>::default());
+        }
+        let mut listeners = entity.get_mut::<Listeners<E>>().unwrap();
+        if self.capture {
+            listeners.capture.push(self.listener);
+        } else {
+            listeners.bubble.push(self.listener);
+        }
+    }
+}
+
+pub fn propagate_system<E: Propagate>(
+    mut commands: Commands,
+    mut pending: ResMut<Events<Propagating<E>>>,
+    mut events: EventWriter<E>,
+    listeners: Query<&Listeners<E>>,
+    parents: Query<&Parent>,
+) {
+    for Propagating(mut event) in pending.drain() {
+        if dispatch(&mut event, &listeners, &parents, &mut commands) {
+            events.send(event);
+        }
+    }
+}
+
+/// Calls the listeners along the path of the `event`, returns `false`
+/// if the default is prevented or the event reached no element.
+fn dispatch<E: Propagate>(
+    event: &mut E,
+    listeners: &Query<&Listeners<E>>,
+    parents: &Query<&Parent>,
+    commands: &mut Commands,
+) -> bool {
+    let Some(target) = event.target() else {
+        return true;
+    };
+    // from the target up to the root
+    let mut path = vec![target];
+    while let Ok(parent) = parents.get(*path.last().unwrap()) {
+        path.push(parent.get());
+    }
+    let mut ctx = PropagationContext {
+        event: &*event,
+        target,
+        current: target,
+        phase: Phase::Capture,
+        stopped: false,
+        prevented: false,
+        commands,
+    };
+    let call = |ctx: &mut PropagationContext<E>, entity: Entity, phase: Phase, capture: bool| {
+        let Ok(listeners) = listeners.get(entity) else {
+            return;
+        };
+        ctx.current = entity;
+        ctx.phase = phase;
+        let listeners = if capture {
+            &listeners.capture
+        } else {
+            &listeners.bubble
+        };
+        for listener in listeners.iter() {
+            listener(ctx);
+        }
+    };
+    for entity in path.iter().skip(1).rev() {
+        call(&mut ctx, *entity, Phase::Capture, true);
+        if ctx.stopped {
+            break;
+        }
+    }
+    let mut reached = vec![];
+    if !ctx.stopped {
+        call(&mut ctx, target, Phase::Target, true);
+        call(&mut ctx, target, Phase::Target, false);
+        reached.push(target);
+        for entity in path.iter().skip(1) {
+            if ctx.stopped {
+                break;
+            }
+            call(&mut ctx, *entity, Phase::Bubble, false);
+            reached.push(*entity);
+        }
+    }
+    let (stopped, prevented) = (ctx.stopped, ctx.prevented);
+    if stopped {
+        event.stopped();
+    }
+    event.retain(|e| !path.contains(&e) || reached.contains(&e)) && !prevented
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::{PointerInput, PointerInputData};
+    use std::sync::{Arc, Mutex};
+
+    type Log = Arc<Mutex<Vec<(Entity, Phase)>>>;
+
+    fn pressed(entities: Vec<Entity>) -> Propagating<PointerInput> {
+        Propagating(PointerInput {
+            entities,
+            pos: Vec2::ZERO,
+            delta: Vec2::ZERO,
+            data: PointerInputData::Pressed { presses: 0 },
+        })
+    }
+
+    fn listen(app: &mut App, log: &Log, entity: Entity, stop: bool) {
+        let capture_log = log.clone();
+        let bubble_log = log.clone();
+        Listen::capture(entity, move |ctx: &mut PropagationContext<PointerInput>| {
+            capture_log
+                .lock()
+                .unwrap()
+                .push((ctx.current(), ctx.phase()));
+        })
+        .write(&mut app.world);
+        Listen::bubble(entity, move |ctx: &mut PropagationContext<PointerInput>| {
+            bubble_log
+                .lock()
+                .unwrap()
+                .push((ctx.current(), ctx.phase()));
+            if stop {
+                ctx.stop_propagation();
+            }
+        })
+        .write(&mut app.world);
+    }
+
+    #[test]
+    fn propagate_pointer_input() {
+        let mut app = App::new();
+        app.add_event::<Propagating<PointerInput>>();
+        app.add_event::<PointerInput>();
+        app.add_system(propagate_system::<PointerInput>);
+        let root = app.world.spawn_empty().id();
+        let card = app.world.spawn_empty().id();
+        let button = app.world.spawn_empty().id();
+        app.world.entity_mut(root).push_children(&[card]);
+        app.world.entity_mut(card).push_children(&[button]);
+        let log = Log::default();
+        listen(&mut app, &log, root, false);
+        listen(&mut app, &log, card, true);
+        listen(&mut app, &log, button, false);
+
+        app.world
+            .resource_mut::<Events<Propagating<PointerInput>>>()
+            .send(pressed(vec![button, card, root]));
+        app.update();
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                (root, Phase::Capture),
+                (card, Phase::Capture),
+                (button, Phase::Target),
+                (button, Phase::Target),
+                (card, Phase::Bubble),
+            ]
+        );
+        let events = app.world.resource::<Events<PointerInput>>();
+        let delivered = events.iter_current_update_events().next().unwrap();
+        assert_eq!(
+            delivered.entities,
+            vec![button, card],
+            "Should not deliver the event above the stopped element"
+        );
+
+        Listen::capture(root, |ctx: &mut PropagationContext<PointerInput>| {
+            ctx.prevent_default();
+        })
+        .write(&mut app.world);
+        app.world
+            .resource_mut::<Events<Propagating<PointerInput>>>()
+            .send(pressed(vec![button]));
+        app.update();
+        let events = app.world.resource::<Events<PointerInput>>();
+        assert_eq!(
+            events.iter_current_update_events().count(),
+            0,
+            "Should not deliver the prevented event"
+        );
+    }
+}
@@ -7,14 +7,21 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
-use bevy::{ecs::system::Command, prelude::*, utils::HashMap};
+use bevy::{
+    asset::Asset,
+    ecs::{query::ReadOnlyWorldQuery, system::Command},
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use itertools::Itertools;
 use smallvec::SmallVec;
 use tagstr::Tag;
 
-use super::RelationsSystems;
+use super::{BindingStage, RelationsSystems};
 
 pub type SourceReader<R, S> = fn(&R) -> S;
+pub type QueryReader<Q, F, S> = for<'w, 's> fn(&Query<'w, 's, Q, F>) -> S;
+pub type ComputedReader<Q, S> = for<'w, 's> fn(&Query<'w, 's, Q>, Entity) -> Option<S>;
 pub type Transformer<S, T> = fn(&S, Prop<T>) -> TransformationResult;
 pub type RefReader<W, T> = for<'b> fn(&'b Mut<W>) -> &'b T;
 pub type MutReader<W, T> = for<'b> fn(&'b mut Mut<W>) -> &'b mut T;
@@ -27,22 +34,37 @@ impl<T: PartialEq + Send + Sync + 'static> BindableTarget for T {}
 
 fn write_component_changes<W: Component, S: BindableSource, T: BindableTarget>(
     changes: &ActiveChanges<S>,
-    writes: &mut Query<(&WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
+    writes: &mut Query<(&mut WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
+    clock: &BindingClock,
 ) {
     for (target, sources) in changes.iter() {
-        let Ok((writers, mut component, mut component_change)) = writes.get_mut(*target) else {
+        let Ok((mut writers, mut component, mut component_change)) = writes.get_mut(*target) else {
             continue
         };
         for (id, source) in sources {
-            for write_descriptor in writers.iter().filter(|w| &w.id == id) {
-                let mut prop_descriptor = write_descriptor.prop_descripror(&mut component);
-                if let Err(e) = write_descriptor.transform(source, prop_descriptor.prop()) {
-                    error!("Error transforming {:?}: {}", id, e.0);
-                } else if prop_descriptor.changed {
-                    // TODO: protect infinity circular loops by tracking property changes
-                    // info!("[bind] just writed {:?}", id);
-                    component_change.set_changed();
+            for write_descriptor in writers.iter_mut().filter(|w| &w.id == id) {
+                // the rate limited bindings may postpone the value
+                if let Some(limiter) = write_descriptor.limiter.as_mut() {
+                    if !limiter.pass(source, clock) {
+                        continue;
+                    }
                 }
+                write_descriptor.write_component(source, &mut component, &mut component_change);
+            }
+        }
+    }
+}
+
+/// Writes the values postponed by the rate limited bindings once they are due.
+pub fn flush_limited_writes<W: Component, S: BindableSource, T: BindableTarget>(
+    clock: Res<BindingClock>,
+    mut writes: Query<(&mut WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
+) {
+    for (mut writers, mut component, mut component_change) in writes.iter_mut() {
+        for write_descriptor in writers.iter_mut() {
+            let limiter = write_descriptor.limiter.as_mut();
+            if let Some(value) = limiter.and_then(|l| l.flush(&clock)) {
+                write_descriptor.write_component(&value, &mut component, &mut component_change);
             }
         }
     }
@@ -56,9 +78,10 @@ pub fn component_to_component_system<
 >(
     mut binds: ParamSet<(
         Query<(&ReadComponent<R, S>, &R), Changed<R>>,
-        Query<(&WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
+        Query<(&mut WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
     )>,
     mut changes: Local<ActiveChanges<S>>,
+    clock: Res<BindingClock>,
 ) {
     changes.clear();
     for (readers, component) in binds.p0().iter() {
@@ -68,7 +91,7 @@ pub fn component_to_component_system<
         }
     }
     let mut writes = binds.p1();
-    write_component_changes(&mut changes, &mut writes);
+    write_component_changes(&mut changes, &mut writes, &clock);
 }
 
 pub fn resource_to_component_system<
@@ -79,10 +102,12 @@ pub fn resource_to_component_system<
 >(
     res: Res<R>,
     read: Res<ReadResource<R, S>>,
-    mut writes: Query<(&WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
+    mut writes: Query<(&mut WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
     mut changes: Local<ActiveChanges<S>>,
+    clock: Res<BindingClock>,
 ) {
-    if !res.is_changed() {
+    // the new binds should receive the value as well
+    if !res.is_changed() && !read.is_changed() {
         return;
     }
     changes.clear();
@@ -91,7 +116,123 @@ pub fn resource_to_component_system<
         let value = (descriptor.reader)(&res);
         changes.add_change(descriptor.id, value);
     }
-    write_component_changes(&mut changes, &mut writes);
+    write_component_changes(&mut changes, &mut writes, &clock);
+}
+
+pub fn asset_to_component_system<A: Asset, W: Component, S: BindableSource, T: BindableTarget>(
+    assets: Res<Assets<A>>,
+    read: Res<ReadAsset<A, S>>,
+    mut writes: Query<(&mut WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
+    mut changes: Local<ActiveChanges<S>>,
+    clock: Res<BindingClock>,
+) {
+    if !assets.is_changed() && !read.is_changed() {
+        return;
+    }
+    changes.clear();
+
+    for (handle, descriptor) in read.iter() {
+        if let Some(asset) = assets.get(handle) {
+            let value = (descriptor.reader)(asset);
+            changes.add_change(descriptor.id, value);
+        }
+    }
+    write_component_changes(&mut changes, &mut writes, &clock);
+}
+
+pub fn computed_to_component_system<
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+>(
+    mut binds: ParamSet<(
+        Query<(), F>,
+        Query<Q>,
+        Query<(&mut WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
+    )>,
+    read: Res<ReadComputed<Q, F, S>>,
+    mut changes: Local<ActiveChanges<S>>,
+    clock: Res<BindingClock>,
+) {
+    changes.clear();
+    // F matches the sources with any of the inputs changed,
+    // the new binds are computed anyway
+    let changed = binds.p0();
+    let sources: Vec<_> = read
+        .binds
+        .iter()
+        .filter(|(id, _)| {
+            let source = id.source.unwrap();
+            read.is_changed() || changed.contains(source)
+        })
+        .collect();
+    let inputs = binds.p1();
+    for (id, reader) in sources {
+        if let Some(value) = reader(&inputs, id.source.unwrap()) {
+            changes.add_change(*id, value);
+        }
+    }
+    let mut writes = binds.p2();
+    write_component_changes(&mut changes, &mut writes, &clock);
+}
+
+pub fn query_to_component_system<
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+>(
+    mut binds: ParamSet<(
+        Query<Q, F>,
+        Query<(&mut WriteComponent<W, S, T>, &mut W, &mut Change<W>)>,
+    )>,
+    read: Res<ReadQuery<Q, F, S>>,
+    mut changes: Local<ActiveChanges<S>>,
+    clock: Res<BindingClock>,
+) {
+    // there is no way to detect the changes of the query result,
+    // so it is read every time, only the changed values are written
+    changes.clear();
+    let query = binds.p0();
+    for (id, reader) in read.iter() {
+        let value = reader(&query);
+        changes.add_change(*id, value);
+    }
+    let mut writes = binds.p1();
+    write_component_changes(&mut changes, &mut writes, &clock);
+}
+
+pub fn component_to_resource_system<
+    R: Component,
+    W: Resource,
+    S: BindableSource,
+    T: BindableTarget,
+>(
+    reads: Query<(&ReadComponent<R, S>, &R), Changed<R>>,
+    writes: Res<WriteResource<W, S, T>>,
+    res: Option<ResMut<W>>,
+    mut state: ResMut<ChangesState>,
+) {
+    let Some(res) = res else {
+        return;
+    };
+    let mut res: Mut<W> = res.into();
+    for (readers, component) in reads.iter() {
+        for descriptor in readers.iter() {
+            for write_descriptor in writes.iter().filter(|w| w.id == descriptor.id) {
+                let source = (descriptor.reader)(component);
+                let mut prop_descriptor = write_descriptor.prop_descripror(&mut res);
+                if let Err(e) = write_descriptor.transform(&source, prop_descriptor.prop()) {
+                    error!("Error transforming {:?}: {}", descriptor.id, e.0);
+                } else if prop_descriptor.changed {
+                    state.report_changed();
+                }
+            }
+        }
+    }
 }
 
 pub(crate) fn watch_changes<W: Component>(
@@ -103,29 +244,105 @@ pub(crate) fn watch_changes<W: Component>(
     }
 }
 
+pub(crate) fn cleanup_component_reads<R: Component, S: BindableSource>(
+    bindings: Res<Bindings>,
+    mut reads: Query<&mut ReadComponent<R, S>>,
+) {
+    for id in bindings.disconnected.iter() {
+        if let Some(Ok(mut reads)) = id.source.map(|e| reads.get_mut(e)) {
+            reads.retain(|r| r.id != *id);
+        }
+    }
+}
+
+pub(crate) fn cleanup_component_writes<W: Component, S: BindableSource, T: BindableTarget>(
+    bindings: Res<Bindings>,
+    mut writes: Query<&mut WriteComponent<W, S, T>>,
+) {
+    for id in bindings.disconnected.iter() {
+        if let Some(Ok(mut writes)) = id.target.map(|e| writes.get_mut(e)) {
+            writes.retain(|w| w.id != *id);
+        }
+    }
+}
+
+pub(crate) fn cleanup_resource_reads<R: Resource, S: BindableSource>(
+    bindings: Res<Bindings>,
+    mut reads: ResMut<ReadResource<R, S>>,
+) {
+    reads
+        .bypass_change_detection()
+        .retain(|r| !bindings.disconnected.contains(&r.id));
+}
+
+pub(crate) fn cleanup_resource_writes<W: Resource, S: BindableSource, T: BindableTarget>(
+    bindings: Res<Bindings>,
+    mut writes: ResMut<WriteResource<W, S, T>>,
+) {
+    writes
+        .bypass_change_detection()
+        .retain(|w| !bindings.disconnected.contains(&w.id));
+}
+
+pub(crate) fn cleanup_asset_reads<A: Asset, S: BindableSource>(
+    bindings: Res<Bindings>,
+    mut reads: ResMut<ReadAsset<A, S>>,
+) {
+    reads
+        .bypass_change_detection()
+        .retain(|(_, r)| !bindings.disconnected.contains(&r.id));
+}
+
+pub(crate) fn cleanup_query_reads<
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    S: BindableSource,
+>(
+    bindings: Res<Bindings>,
+    mut reads: ResMut<ReadQuery<Q, F, S>>,
+) {
+    reads
+        .bypass_change_detection()
+        .retain(|(id, _)| !bindings.disconnected.contains(id));
+}
+
+pub(crate) fn cleanup_computed_reads<
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    S: BindableSource,
+>(
+    bindings: Res<Bindings>,
+    mut reads: ResMut<ReadComputed<Q, F, S>>,
+) {
+    reads
+        .bypass_change_detection()
+        .binds
+        .retain(|(id, _)| !bindings.disconnected.contains(id));
+}
+
 #[derive(Deref, DerefMut)]
 pub struct ActiveChanges<S: BindableSource>(HashMap<Entity, SmallVec<[(BindId, S); 16]>>);
 
-pub struct PropertyDescriptor<'a, 'c, C: Component, T> {
+pub struct PropertyDescriptor<'a, 'c, C, T> {
     changed: bool,
     component: &'a mut Mut<'c, C>,
     ref_getter: for<'b> fn(&'b Mut<C>) -> &'b T,
     mut_getter: for<'b> fn(&'b mut Mut<C>) -> &'b mut T,
 }
 
-impl<'a, 'c, C: Component, T> PropertyDescriptor<'a, 'c, C, T> {
+impl<'a, 'c, C, T> PropertyDescriptor<'a, 'c, C, T> {
     fn prop(&mut self) -> Prop<T> {
         Prop(self)
     }
 }
 
-impl<'a, 'c, C: Component, T> AsRef<T> for PropertyDescriptor<'a, 'c, C, T> {
+impl<'a, 'c, C, T> AsRef<T> for PropertyDescriptor<'a, 'c, C, T> {
     fn as_ref(&self) -> &T {
         (self.ref_getter)(&self.component)
     }
 }
 
-impl<'a, 'c, C: Component, T> AsMut<T> for PropertyDescriptor<'a, 'c, C, T> {
+impl<'a, 'c, C, T> AsMut<T> for PropertyDescriptor<'a, 'c, C, T> {
     fn as_mut(&mut self) -> &mut T {
         self.changed = true;
         (self.mut_getter)(&mut self.component)
@@ -150,9 +367,40 @@ impl<'a, T> DerefMut for Prop<'a, T> {
     }
 }
 
+struct ValueProperty<T>(T);
+
+impl<T> AsRef<T> for ValueProperty<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> AsMut<T> for ValueProperty<T> {
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// Passes the `source` through the `first` transformer and the value it
+/// produces through the `second` one. This is how the chains like
+/// `from!(player, Health:current | val.percent | ...)` are transformed,
+/// the `first` transformer starts from the default value.
+pub fn chain<S, M: Default, T>(
+    source: &S,
+    target: Prop<T>,
+    first: Transformer<S, M>,
+    second: Transformer<M, T>,
+) -> TransformationResult {
+    let mut value = ValueProperty(M::default());
+    first(source, Prop(&mut value))?;
+    second(&value.0, target)
+}
+
 impl<S: BindableSource> ActiveChanges<S> {
     fn add_change(&mut self, id: BindId, value: S) {
-        self.entry(id.target).or_default().push((id, value));
+        if let Some(target) = id.target {
+            self.entry(target).or_default().push((id, value));
+        }
     }
 }
 impl<S: BindableSource> Default for ActiveChanges<S> {
@@ -165,12 +413,12 @@ impl<S: BindableSource> Default for ActiveChanges<S> {
 pub struct BindId {
     source: Option<Entity>,
     from: Tag,
-    target: Entity,
+    target: Option<Entity>,
     to: Tag,
 }
 
 impl BindId {
-    fn new(source: Option<Entity>, from: Tag, target: Entity, to: Tag) -> BindId {
+    fn new(source: Option<Entity>, from: Tag, target: Option<Entity>, to: Tag) -> BindId {
         BindId {
             source,
             from,
@@ -178,6 +426,90 @@ impl BindId {
             to,
         }
     }
+
+    /// Disconnects the binding returned by `write()`. The binding is
+    /// removed before the bindings are processed next time.
+    pub fn unbind(self, world: &mut World) {
+        world
+            .get_resource_or_insert_with(Bindings::default)
+            .unbound
+            .insert(self);
+    }
+}
+
+/// Disconnects the binding, see [`BindId::unbind`].
+pub struct Unbind(pub BindId);
+
+impl Command for Unbind {
+    fn write(self, world: &mut World) {
+        self.0.unbind(world);
+    }
+}
+
+/// The connected bindings. The bindings with any of the endpoints
+/// despawned (or unbound) are collected into `disconnected` and removed
+/// from the storages by the cleanup systems.
+#[derive(Resource, Default)]
+pub(crate) struct Bindings {
+    live: HashSet<BindId>,
+    unbound: HashSet<BindId>,
+    disconnected: HashSet<BindId>,
+}
+
+/// The number of the bindings. The `live` number growing all the time
+/// means the bindings are written over and over (every frame, for each
+/// spawned entity) and never disconnected.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BindingStats {
+    /// The bindings currently connected.
+    pub live: usize,
+    /// The bindings disconnected so far: unbound or with any of
+    /// the endpoints despawned.
+    pub dead: usize,
+}
+
+fn register_binding(world: &mut World, id: BindId) -> BindId {
+    let mut bindings = world.get_resource_or_insert_with(Bindings::default);
+    bindings.live.insert(id);
+    let live = bindings.live.len();
+    world
+        .get_resource_or_insert_with(BindingStats::default)
+        .live = live;
+    id
+}
+
+/// Moves the unbound bindings and the bindings with the despawned
+/// endpoints to the `disconnected` ones. Returns `true` if there are
+/// bindings to be removed from the storages.
+pub(crate) fn collect_disconnected(world: &mut World) -> bool {
+    if !world.contains_resource::<Bindings>() {
+        return false;
+    }
+    let disconnected = world.resource_scope(|world, mut bindings: Mut<Bindings>| {
+        let entities = world.entities();
+        let alive = |entity: Option<Entity>| entity.map_or(true, |e| entities.contains(e));
+        let bindings = bindings.bypass_change_detection();
+        let unbound = std::mem::take(&mut bindings.unbound);
+        let mut disconnected = std::mem::take(&mut bindings.disconnected);
+        disconnected.clear();
+        bindings.live.retain(|id| {
+            let connected = !unbound.contains(id) && alive(id.source) && alive(id.target);
+            if !connected {
+                disconnected.insert(*id);
+            }
+            connected
+        });
+        bindings.disconnected = disconnected;
+        bindings.disconnected.len()
+    });
+    if disconnected == 0 {
+        return false;
+    }
+    let live = world.resource::<Bindings>().live.len();
+    let mut stats = world.get_resource_or_insert_with(BindingStats::default);
+    stats.live = live;
+    stats.dead += disconnected;
+    true
 }
 
 #[derive(Resource, Default)]
@@ -226,14 +558,54 @@ impl<R: Resource, S: BindableSource> Default for ReadResource<R, S> {
     }
 }
 
+#[derive(Resource, Deref, DerefMut)]
+pub struct ReadAsset<A: Asset, S: BindableSource>(Vec<(Handle<A>, ReadDescriptor<A, S>)>);
+
+impl<A: Asset, S: BindableSource> Default for ReadAsset<A, S> {
+    fn default() -> Self {
+        ReadAsset(vec![])
+    }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct ReadQuery<Q: ReadOnlyWorldQuery + 'static, F: ReadOnlyWorldQuery + 'static, S>(
+    Vec<(BindId, QueryReader<Q, F, S>)>,
+);
+
+impl<Q: ReadOnlyWorldQuery + 'static, F: ReadOnlyWorldQuery + 'static, S> Default
+    for ReadQuery<Q, F, S>
+{
+    fn default() -> Self {
+        ReadQuery(vec![])
+    }
+}
+
+#[derive(Resource)]
+pub struct ReadComputed<Q: ReadOnlyWorldQuery + 'static, F: ReadOnlyWorldQuery + 'static, S> {
+    binds: Vec<(BindId, ComputedReader<Q, S>)>,
+    _filter: PhantomData<F>,
+}
+
+impl<Q: ReadOnlyWorldQuery + 'static, F: ReadOnlyWorldQuery + 'static, S> Default
+    for ReadComputed<Q, F, S>
+{
+    fn default() -> Self {
+        ReadComputed {
+            binds: vec![],
+            _filter: PhantomData,
+        }
+    }
+}
+
 pub struct WriteDescriptor<W, S: BindableSource, T: BindableTarget> {
     id: BindId,
     transformer: Transformer<S, T>,
     ref_getter: RefReader<W, T>,
     mut_getter: MutReader<W, T>,
+    limiter: Option<Limiter<S>>,
 }
 
-impl<W: Component, S: BindableSource, T: BindableTarget> WriteDescriptor<W, S, T> {
+impl<W, S: BindableSource, T: BindableTarget> WriteDescriptor<W, S, T> {
     fn prop_descripror<'a, 'c>(
         &self,
         component: &'a mut Mut<'c, W>,
@@ -250,11 +622,133 @@ impl<W: Component, S: BindableSource, T: BindableTarget> WriteDescriptor<W, S, T
         (self.transformer)(source, prop)
     }
 }
+
+impl<W: Component, S: BindableSource, T: BindableTarget> WriteDescriptor<W, S, T> {
+    fn write_component(&self, source: &S, component: &mut Mut<W>, change: &mut Mut<Change<W>>) {
+        let mut prop_descriptor = self.prop_descripror(component);
+        if let Err(e) = self.transform(source, prop_descriptor.prop()) {
+            error!("Error transforming {:?}: {}", self.id, e.0);
+        } else if prop_descriptor.changed {
+            change.set_changed();
+        }
+    }
+}
+
+/// Limits how often the binding writes the target, see [`RateLimitExtension`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimit {
+    /// The value is written once the source stays unchanged for the seconds.
+    Debounce(f32),
+    /// The value is written at most once per the number of frames.
+    ThrottleFrames(u32),
+}
+
+/// Limits how often the binding writes the target, so the targets which
+/// are expensive to update (text layout, generated textures) are not
+/// updated every frame by the rapidly changing sources:
+/// ```rust,ignore
+/// (from!(player, Transform:translation | fmt("{:?}")) >> to!(label, Label:value))
+///     .throttle_frames(5)
+///     .write(world);
+/// ```
+/// The latest value is always written in the end.
+pub trait RateLimitExtension: Sized {
+    fn rate_limit(self, rate: RateLimit) -> Self;
+
+    /// Writes the value once the source stays unchanged for the `seconds`.
+    fn debounce(self, seconds: f32) -> Self {
+        self.rate_limit(RateLimit::Debounce(seconds))
+    }
+
+    /// Writes the value at most once per the `frames`.
+    fn throttle_frames(self, frames: u32) -> Self {
+        self.rate_limit(RateLimit::ThrottleFrames(frames))
+    }
+}
+
+/// The frame and the time the rate limited bindings are measured by.
+#[derive(Resource, Default, Debug)]
+pub struct BindingClock {
+    pub frame: u64,
+    pub time: f64,
+}
+
+pub(crate) fn tick_binding_clock(mut clock: ResMut<BindingClock>, time: Option<Res<Time>>) {
+    clock.frame += 1;
+    if let Some(time) = time {
+        clock.time = time.elapsed_seconds_f64();
+    }
+}
+
+/// Keeps the latest value postponed by the [`RateLimit`].
+struct Limiter<S> {
+    rate: RateLimit,
+    pending: Option<S>,
+    changed_at: f64,
+    written_at: Option<u64>,
+}
+
+impl<S: BindableSource> Limiter<S> {
+    fn new(rate: RateLimit) -> Limiter<S> {
+        Limiter {
+            rate,
+            pending: None,
+            changed_at: 0.,
+            written_at: None,
+        }
+    }
+
+    fn due(&self, clock: &BindingClock) -> bool {
+        match self.rate {
+            RateLimit::Debounce(seconds) => clock.time - self.changed_at >= seconds as f64,
+            RateLimit::ThrottleFrames(frames) => self
+                .written_at
+                .map_or(true, |frame| clock.frame >= frame + frames as u64),
+        }
+    }
+
+    /// Returns `true` if the changed `value` should be written right now,
+    /// otherwise the value is kept until it is due.
+    fn pass(&mut self, value: &S, clock: &BindingClock) -> bool {
+        if let RateLimit::Debounce(_) = self.rate {
+            self.changed_at = clock.time;
+        }
+        if self.due(clock) {
+            self.pending = None;
+            self.written_at = Some(clock.frame);
+            true
+        } else {
+            self.pending = Some(value.clone());
+            false
+        }
+    }
+
+    /// Takes the postponed value once it is due.
+    fn flush(&mut self, clock: &BindingClock) -> Option<S> {
+        if self.pending.is_some() && self.due(clock) {
+            self.written_at = Some(clock.frame);
+            self.pending.take()
+        } else {
+            None
+        }
+    }
+}
 #[derive(Component, Deref, DerefMut, Default)]
 pub struct WriteComponent<W: Component, S: BindableSource, T: BindableTarget>(
     Vec<WriteDescriptor<W, S, T>>,
 );
 
+#[derive(Resource, Deref, DerefMut)]
+pub struct WriteResource<W: Resource, S: BindableSource, T: BindableTarget>(
+    Vec<WriteDescriptor<W, S, T>>,
+);
+
+impl<W: Resource, S: BindableSource, T: BindableTarget> Default for WriteResource<W, S, T> {
+    fn default() -> Self {
+        WriteResource(vec![])
+    }
+}
+
 pub struct FromComponent<R: Component, S: BindableSource> {
     pub id: Tag,
     pub source: Entity,
@@ -266,7 +760,17 @@ impl<R: Component, S: BindableSource> FromComponent<R, S> {
         self,
         to: ToComponent<W, S, T>,
     ) -> ComponentToComponent<R, W, S, T> {
-        ComponentToComponent { from: self, to }
+        ComponentToComponent {
+            from: self,
+            to,
+            rate: None,
+        }
+    }
+    pub fn bind_resource<W: Resource, T: BindableTarget>(
+        self,
+        to: ToResource<W, S, T>,
+    ) -> ComponentToResource<R, W, S, T> {
+        ComponentToResource { from: self, to }
     }
 }
 
@@ -289,6 +793,18 @@ impl<R: Component, S: BindableSource, T: BindableTarget> FromComponentWithTransf
             transformer,
         })
     }
+    pub fn bind_resource<W: Resource>(
+        self,
+        to: ToResourceWithoutTransformer<W, T>,
+    ) -> ComponentToResource<R, W, S, T> {
+        let transformer = self.transformer;
+        self.from.bind_resource(ToResource {
+            id: to.id,
+            writer: to.writer,
+            reader: to.reader,
+            transformer,
+        })
+    }
 }
 
 pub struct FromResource<R: Resource, S: BindableSource> {
@@ -301,7 +817,11 @@ impl<R: Resource, S: BindableSource> FromResource<R, S> {
         self,
         to: ToComponent<W, S, T>,
     ) -> ResourceToComponent<R, W, S, T> {
-        ResourceToComponent { from: self, to }
+        ResourceToComponent {
+            from: self,
+            to,
+            rate: None,
+        }
     }
 }
 
@@ -327,6 +847,141 @@ impl<R: Resource, S: BindableSource, T: BindableTarget> FromResourceWithTransfor
     }
 }
 
+pub struct FromAsset<A: Asset, S: BindableSource> {
+    pub id: Tag,
+    pub handle: Handle<A>,
+    pub reader: SourceReader<A, S>,
+}
+
+impl<A: Asset, S: BindableSource> FromAsset<A, S> {
+    pub fn bind_component<W: Component, T: BindableTarget>(
+        self,
+        to: ToComponent<W, S, T>,
+    ) -> AssetToComponent<A, W, S, T> {
+        AssetToComponent {
+            from: self,
+            to,
+            rate: None,
+        }
+    }
+}
+
+pub struct FromAssetWithTransformer<A: Asset, S: BindableSource, T: BindableTarget> {
+    pub from: FromAsset<A, S>,
+    pub transformer: Transformer<S, T>,
+}
+
+impl<A: Asset, S: BindableSource, T: BindableTarget> FromAssetWithTransformer<A, S, T> {
+    pub fn bind_component<W: Component>(
+        self,
+        to: ToComponentWithoutTransformer<W, T>,
+    ) -> AssetToComponent<A, W, S, T> {
+        self.from.bind_component(ToComponent {
+            id: to.id,
+            target: to.target,
+            writer: to.writer,
+            reader: to.reader,
+            transformer: self.transformer,
+        })
+    }
+}
+
+pub struct FromQuery<Q, F, S>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    S: BindableSource,
+{
+    pub id: Tag,
+    pub reader: QueryReader<Q, F, S>,
+}
+
+impl<Q, F, S> FromQuery<Q, F, S>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    S: BindableSource,
+{
+    pub fn bind_component<W: Component, T: BindableTarget>(
+        self,
+        to: ToComponent<W, S, T>,
+    ) -> QueryToComponent<Q, F, W, S, T> {
+        QueryToComponent {
+            from: self,
+            to,
+            rate: None,
+        }
+    }
+}
+
+pub struct FromQueryWithTransformer<Q, F, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    pub from: FromQuery<Q, F, S>,
+    pub transformer: Transformer<S, T>,
+}
+
+impl<Q, F, S, T> FromQueryWithTransformer<Q, F, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    pub fn bind_component<W: Component>(
+        self,
+        to: ToComponentWithoutTransformer<W, T>,
+    ) -> QueryToComponent<Q, F, W, S, T> {
+        self.from.bind_component(ToComponent {
+            id: to.id,
+            target: to.target,
+            writer: to.writer,
+            reader: to.reader,
+            transformer: self.transformer,
+        })
+    }
+}
+
+/// The value computed from several components of the `source` entity:
+/// ```rust,ignore
+/// from!(player, |hp: &Health, max: &MaxHealth| hp.0 / max.0)
+/// ```
+/// `Q` is the query of the inputs, `F` matches the entities with any of
+/// the inputs changed: the value is computed again only then.
+pub struct FromComputed<Q, F, S>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    S: BindableSource,
+{
+    pub id: Tag,
+    pub source: Entity,
+    pub reader: ComputedReader<Q, S>,
+    pub filter: PhantomData<F>,
+}
+
+impl<Q, F, S> FromComputed<Q, F, S>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    S: BindableSource,
+{
+    pub fn bind_component<W: Component, T: BindableTarget>(
+        self,
+        to: ToComponent<W, S, T>,
+    ) -> ComputedToComponent<Q, F, W, S, T> {
+        ComputedToComponent {
+            from: self,
+            to,
+            rate: None,
+        }
+    }
+}
+
 // pub struct ToCmp<W, S, T>
 // where
 //     W: Component,
@@ -353,13 +1008,21 @@ impl<W: Component, S: BindableSource, T: BindableTarget> ToComponent<W, S, T> {
         self,
         from: FromComponent<R, S>,
     ) -> ComponentToComponent<R, W, S, T> {
-        ComponentToComponent { from, to: self }
+        ComponentToComponent {
+            from,
+            to: self,
+            rate: None,
+        }
     }
     pub fn bind_resource<R: Resource>(
         self,
         from: FromResource<R, S>,
     ) -> ResourceToComponent<R, W, S, T> {
-        ResourceToComponent { from, to: self }
+        ResourceToComponent {
+            from,
+            to: self,
+            rate: None,
+        }
     }
 }
 
@@ -398,6 +1061,50 @@ impl<W: Component, T: BindableTarget> ToComponentWithoutTransformer<W, T> {
     // pub fn with_transormer<S: BindableSource>(self, transformator: fn())
 }
 
+pub struct ToResource<W: Resource, S: BindableSource, T: BindableTarget> {
+    pub id: Tag,
+    pub transformer: Transformer<S, T>,
+    pub reader: RefReader<W, T>,
+    pub writer: MutReader<W, T>,
+}
+
+impl<W: Resource, S: BindableSource, T: BindableTarget> ToResource<W, S, T> {
+    pub fn bind_component<R: Component>(
+        self,
+        from: FromComponent<R, S>,
+    ) -> ComponentToResource<R, W, S, T> {
+        ComponentToResource { from, to: self }
+    }
+}
+
+pub struct ToResourceWithoutTransformer<W: Resource, T: BindableTarget> {
+    pub id: Tag,
+    pub reader: RefReader<W, T>,
+    pub writer: MutReader<W, T>,
+}
+
+impl<W: Resource, T: BindableTarget> ToResourceWithoutTransformer<W, T> {
+    pub fn bind_component<R: Component, S: BindableSource>(
+        self,
+        from: FromComponentWithTransformer<R, S, T>,
+    ) -> ComponentToResource<R, W, S, T> {
+        from.bind_resource(self)
+    }
+}
+
+/// Both directions of the two-way binding produced by the `bind!` macro:
+/// ```rust,ignore
+/// let volume = bind!(Settings:volume);
+/// (to!(slider, Slider:value) << volume.from).write(world);
+/// (from!(slider, Slider:value) >> volume.to).write_back(world);
+/// ```
+/// The `eml!` macro does this for the `bind!` params:
+/// `<slider value=bind!(Settings:volume)/>`.
+pub struct TwoWayBind<F, T> {
+    pub from: F,
+    pub to: T,
+}
+
 pub trait AsTransformer {
     type Transformer;
     fn as_transformer() -> Self::Transformer;
@@ -425,113 +1132,438 @@ impl<W: Component, T: BindableTarget + AsTransformer> ToComponentTransformable<W
     }
 }
 
-fn register_component_writer<W: Component, S: BindableSource, T: BindableTarget>(
-    world: &mut World,
-    id: BindId,
+fn register_component_reader<R: Component, S: BindableSource>(
+    world: &mut World,
+    id: BindId,
+    from: FromComponent<R, S>,
+) {
+    let mut source_entity = world.entity_mut(from.source);
+    let read_descriptor = ReadDescriptor {
+        id,
+        reader: from.reader,
+    };
+    if let Some(mut source_component) = source_entity.get_mut::<ReadComponent<R, S>>() {
+        source_component.push(read_descriptor);
+    } else {
+        source_entity.insert(ReadComponent(vec![read_descriptor]));
+    }
+}
+
+fn register_component_writer<W: Component, S: BindableSource, T: BindableTarget>(
+    world: &mut World,
+    id: BindId,
+    to: ToComponent<W, S, T>,
+    rate: Option<RateLimit>,
+) {
+    if rate.is_some() {
+        let systems_ref = world.get_resource_or_insert_with(RelationsSystems::default);
+        let mut systems = systems_ref.0.write().unwrap();
+        systems.add_limited_writes::<W, S, T>();
+    }
+    let mut target_entity = world.entity_mut(to.target);
+    let write_descriptor = WriteDescriptor {
+        id,
+        ref_getter: to.reader,
+        mut_getter: to.writer,
+        transformer: to.transformer,
+        limiter: rate.map(Limiter::new),
+    };
+    if !target_entity.contains::<Change<W>>() {
+        target_entity.insert(Change::<W>::new());
+    }
+    if let Some(mut writer_component) = target_entity.get_mut::<WriteComponent<W, S, T>>() {
+        writer_component.push(write_descriptor);
+    } else {
+        target_entity.insert(WriteComponent(vec![write_descriptor]));
+    }
+}
+
+pub struct ComponentToComponent<R: Component, W: Component, S: BindableSource, T: BindableTarget> {
+    from: FromComponent<R, S>,
+    to: ToComponent<W, S, T>,
+    rate: Option<RateLimit>,
+}
+
+impl<R: Component, W: Component, S: BindableSource, T: BindableTarget> std::fmt::Display
+    for ComponentToComponent<R, W, S, T>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source_str = self.from.id;
+        let target_str = self.to.id;
+        write!(f, "ComponentToComponent( {source_str} >> {target_str} )")
+    }
+}
+
+impl<R: Component, W: Component, S: BindableSource, T: BindableTarget> Command
+    for ComponentToComponent<R, W, S, T>
+{
+    fn write(self, world: &mut World) {
+        self.write(world);
+    }
+}
+
+impl<R: Component, W: Component, S: BindableSource, T: BindableTarget>
+    ComponentToComponent<R, W, S, T>
+{
+    pub fn write(self, world: &mut World) -> BindId {
+        self.register(world, BindingStage::Bind)
+    }
+
+    /// Writes the binding applied after the regular ones. This is the way
+    /// back of the two-way binding: when both sides are changed at once
+    /// (like when the binding is just written), the other way wins.
+    pub fn write_back(self, world: &mut World) -> BindId {
+        self.register(world, BindingStage::BindBack)
+    }
+
+    fn register(self, world: &mut World, stage: BindingStage) -> BindId {
+        {
+            let systems_ref = world.get_resource_or_insert_with(RelationsSystems::default);
+            let mut systems = systems_ref.0.write().unwrap();
+            systems.add_component_to_component::<R, W, S, T>(stage);
+        }
+        let id = BindId::new(
+            Some(self.from.source),
+            self.from.id,
+            Some(self.to.target),
+            self.to.id,
+        );
+        register_component_reader(world, id, self.from);
+        register_component_writer(world, id, self.to, self.rate);
+        register_binding(world, id)
+    }
+}
+
+pub struct ResourceToComponent<R: Resource, W: Component, S: BindableSource, T: BindableTarget> {
+    from: FromResource<R, S>,
+    to: ToComponent<W, S, T>,
+    rate: Option<RateLimit>,
+}
+
+impl<R: Resource, W: Component, S: BindableSource, T: BindableTarget> std::fmt::Display
+    for ResourceToComponent<R, W, S, T>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source_str = self.from.id;
+        let target_str = self.to.id;
+        write!(f, "ResourceToComponent( {source_str} >> {target_str} )")
+    }
+}
+
+impl<R: Resource, W: Component, S: BindableSource, T: BindableTarget>
+    ResourceToComponent<R, W, S, T>
+{
+    pub fn write(self, world: &mut World) -> BindId {
+        {
+            let systems_ref = world.get_resource_or_insert_with(RelationsSystems::default);
+            let mut systems = systems_ref.0.write().unwrap();
+            systems.add_resource_to_component::<R, W, S, T>();
+        }
+        let id = BindId::new(None, self.from.id, Some(self.to.target), self.to.id);
+        let read_descriptor = ReadDescriptor {
+            id,
+            reader: self.from.reader,
+        };
+        world
+            .get_resource_or_insert_with(ReadResource::<R, S>::default)
+            .push(read_descriptor);
+        register_component_writer(world, id, self.to, self.rate);
+        register_binding(world, id)
+    }
+}
+
+pub struct AssetToComponent<A: Asset, W: Component, S: BindableSource, T: BindableTarget> {
+    from: FromAsset<A, S>,
+    to: ToComponent<W, S, T>,
+    rate: Option<RateLimit>,
+}
+
+impl<A: Asset, W: Component, S: BindableSource, T: BindableTarget> std::fmt::Display
+    for AssetToComponent<A, W, S, T>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source_str = self.from.id;
+        let target_str = self.to.id;
+        write!(f, "AssetToComponent( {source_str} >> {target_str} )")
+    }
+}
+
+impl<A: Asset, W: Component, S: BindableSource, T: BindableTarget> Command
+    for AssetToComponent<A, W, S, T>
+{
+    fn write(self, world: &mut World) {
+        self.write(world);
+    }
+}
+
+impl<A: Asset, W: Component, S: BindableSource, T: BindableTarget> AssetToComponent<A, W, S, T> {
+    /// Writes the binding reading the asset every time it is loaded or
+    /// modified. The binding doesn't keep the asset loaded.
+    pub fn write(self, world: &mut World) -> BindId {
+        {
+            let systems_ref = world.get_resource_or_insert_with(RelationsSystems::default);
+            let mut systems = systems_ref.0.write().unwrap();
+            systems.add_asset_to_component::<A, W, S, T>();
+        }
+        let id = BindId::new(None, self.from.id, Some(self.to.target), self.to.id);
+        let read_descriptor = ReadDescriptor {
+            id,
+            reader: self.from.reader,
+        };
+        world
+            .get_resource_or_insert_with(ReadAsset::<A, S>::default)
+            .push((self.from.handle.clone_weak(), read_descriptor));
+        register_component_writer(world, id, self.to, self.rate);
+        register_binding(world, id)
+    }
+}
+
+pub struct QueryToComponent<Q, F, W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    from: FromQuery<Q, F, S>,
     to: ToComponent<W, S, T>,
-) {
-    let mut target_entity = world.entity_mut(to.target);
-    let write_descriptor = WriteDescriptor {
-        id,
-        ref_getter: to.reader,
-        mut_getter: to.writer,
-        transformer: to.transformer,
-    };
-    if !target_entity.contains::<Change<W>>() {
-        target_entity.insert(Change::<W>::new());
+    rate: Option<RateLimit>,
+}
+
+impl<Q, F, W, S, T> std::fmt::Display for QueryToComponent<Q, F, W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source_str = self.from.id;
+        let target_str = self.to.id;
+        write!(f, "QueryToComponent( {source_str} >> {target_str} )")
     }
-    if let Some(mut writer_component) = target_entity.get_mut::<WriteComponent<W, S, T>>() {
-        writer_component.push(write_descriptor);
-    } else {
-        target_entity.insert(WriteComponent(vec![write_descriptor]));
+}
+
+impl<Q, F, W, S, T> Command for QueryToComponent<Q, F, W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    fn write(self, world: &mut World) {
+        self.write(world);
     }
 }
 
-pub struct ComponentToComponent<R: Component, W: Component, S: BindableSource, T: BindableTarget> {
-    from: FromComponent<R, S>,
+impl<Q, F, W, S, T> QueryToComponent<Q, F, W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    /// Writes the binding reading the query every time the bindings are
+    /// processed, the target is changed only when the value differs.
+    pub fn write(self, world: &mut World) -> BindId {
+        {
+            let systems_ref = world.get_resource_or_insert_with(RelationsSystems::default);
+            let mut systems = systems_ref.0.write().unwrap();
+            systems.add_query_to_component::<Q, F, W, S, T>();
+        }
+        let id = BindId::new(None, self.from.id, Some(self.to.target), self.to.id);
+        world
+            .get_resource_or_insert_with(ReadQuery::<Q, F, S>::default)
+            .push((id, self.from.reader));
+        register_component_writer(world, id, self.to, self.rate);
+        register_binding(world, id)
+    }
+}
+
+pub struct ComputedToComponent<Q, F, W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    from: FromComputed<Q, F, S>,
     to: ToComponent<W, S, T>,
+    rate: Option<RateLimit>,
 }
 
-impl<R: Component, W: Component, S: BindableSource, T: BindableTarget> std::fmt::Display
-    for ComponentToComponent<R, W, S, T>
+impl<Q, F, W, S, T> std::fmt::Display for ComputedToComponent<Q, F, W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let source_str = self.from.id;
         let target_str = self.to.id;
-        write!(f, "ComponentToComponent( {source_str} >> {target_str} )")
+        write!(f, "ComputedToComponent( {source_str} >> {target_str} )")
     }
 }
 
-impl<R: Component, W: Component, S: BindableSource, T: BindableTarget> Command
-    for ComponentToComponent<R, W, S, T>
+impl<Q, F, W, S, T> Command for ComputedToComponent<Q, F, W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
 {
     fn write(self, world: &mut World) {
         self.write(world);
     }
 }
 
-impl<R: Component, W: Component, S: BindableSource, T: BindableTarget>
-    ComponentToComponent<R, W, S, T>
+impl<Q, F, W, S, T> ComputedToComponent<Q, F, W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
 {
-    pub fn write(self, world: &mut World) {
+    pub fn write(self, world: &mut World) -> BindId {
         {
             let systems_ref = world.get_resource_or_insert_with(RelationsSystems::default);
             let mut systems = systems_ref.0.write().unwrap();
-            systems.add_component_to_component::<R, W, S, T>();
+            systems.add_computed_to_component::<Q, F, W, S, T>();
         }
         let id = BindId::new(
             Some(self.from.source),
             self.from.id,
-            self.to.target,
+            Some(self.to.target),
             self.to.id,
         );
-        let mut source_entity = world.entity_mut(self.from.source);
-        let read_descriptor = ReadDescriptor {
-            id,
-            reader: self.from.reader,
-        };
-        if let Some(mut source_component) = source_entity.get_mut::<ReadComponent<R, S>>() {
-            source_component.push(read_descriptor);
-        } else {
-            source_entity.insert(ReadComponent(vec![read_descriptor]));
-        }
-        register_component_writer(world, id, self.to);
+        world
+            .get_resource_or_insert_with(ReadComputed::<Q, F, S>::default)
+            .binds
+            .push((id, self.from.reader));
+        register_component_writer(world, id, self.to, self.rate);
+        register_binding(world, id)
     }
 }
 
-pub struct ResourceToComponent<R: Resource, W: Component, S: BindableSource, T: BindableTarget> {
-    from: FromResource<R, S>,
-    to: ToComponent<W, S, T>,
+impl<R: Component, W: Component, S: BindableSource, T: BindableTarget> RateLimitExtension
+    for ComponentToComponent<R, W, S, T>
+{
+    fn rate_limit(mut self, rate: RateLimit) -> Self {
+        self.rate = Some(rate);
+        self
+    }
 }
 
-impl<R: Resource, W: Component, S: BindableSource, T: BindableTarget> std::fmt::Display
+impl<R: Resource, W: Component, S: BindableSource, T: BindableTarget> RateLimitExtension
     for ResourceToComponent<R, W, S, T>
+{
+    fn rate_limit(mut self, rate: RateLimit) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+}
+
+impl<A: Asset, W: Component, S: BindableSource, T: BindableTarget> RateLimitExtension
+    for AssetToComponent<A, W, S, T>
+{
+    fn rate_limit(mut self, rate: RateLimit) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+}
+
+impl<Q, F, W, S, T> RateLimitExtension for QueryToComponent<Q, F, W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    fn rate_limit(mut self, rate: RateLimit) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+}
+
+impl<Q, F, W, S, T> RateLimitExtension for ComputedToComponent<Q, F, W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    fn rate_limit(mut self, rate: RateLimit) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+}
+
+pub struct ComponentToResource<R: Component, W: Resource, S: BindableSource, T: BindableTarget> {
+    from: FromComponent<R, S>,
+    to: ToResource<W, S, T>,
+}
+
+impl<R: Component, W: Resource, S: BindableSource, T: BindableTarget> std::fmt::Display
+    for ComponentToResource<R, W, S, T>
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let source_str = self.from.id;
         let target_str = self.to.id;
-        write!(f, "ResourceToComponent( {source_str} >> {target_str} )")
+        write!(f, "ComponentToResource( {source_str} >> {target_str} )")
     }
 }
 
-impl<R: Resource, W: Component, S: BindableSource, T: BindableTarget>
-    ResourceToComponent<R, W, S, T>
+impl<R: Component, W: Resource, S: BindableSource, T: BindableTarget> Command
+    for ComponentToResource<R, W, S, T>
+{
+    fn write(self, world: &mut World) {
+        self.write(world);
+    }
+}
+
+impl<R: Component, W: Resource, S: BindableSource, T: BindableTarget>
+    ComponentToResource<R, W, S, T>
 {
-    pub fn write(self, world: &mut World) {
+    pub fn write(self, world: &mut World) -> BindId {
+        self.register(world, BindingStage::Bind)
+    }
+
+    /// Writes the binding applied after the regular ones, see
+    /// [`ComponentToComponent::write_back`].
+    pub fn write_back(self, world: &mut World) -> BindId {
+        self.register(world, BindingStage::BindBack)
+    }
+
+    fn register(self, world: &mut World, stage: BindingStage) -> BindId {
         {
             let systems_ref = world.get_resource_or_insert_with(RelationsSystems::default);
             let mut systems = systems_ref.0.write().unwrap();
-            systems.add_resource_to_component::<R, W, S, T>();
+            systems.add_component_to_resource::<R, W, S, T>(stage);
         }
-        let id = BindId::new(None, self.from.id, self.to.target, self.to.id);
-        let read_descriptor = ReadDescriptor {
+        let id = BindId::new(Some(self.from.source), self.from.id, None, self.to.id);
+        register_component_reader(world, id, self.from);
+        let write_descriptor = WriteDescriptor {
             id,
-            reader: self.from.reader,
+            ref_getter: self.to.reader,
+            mut_getter: self.to.writer,
+            transformer: self.to.transformer,
+            limiter: None,
         };
         world
-            .get_resource_or_insert_with(ReadResource::<R, S>::default)
-            .push(read_descriptor);
-        register_component_writer(world, id, self.to);
+            .get_resource_or_insert_with(WriteResource::<W, S, T>::default)
+            .push(write_descriptor);
+        register_binding(world, id)
     }
 }
 
@@ -637,6 +1669,42 @@ macro_rules! bind {
             }
         }
     };
+    // from!(asset handle, Asset:some.property)
+    (@bind from asset $handle:expr, $cls:ty, { $($prop:tt)+ }, default) => {
+        $crate::relations::bind::FromAsset {
+            id: $crate::relations::bind::bind_id::<$cls>(stringify!($($prop)+)),
+            handle: $handle,
+            reader: |c: &$cls| c.$($prop)+.clone()
+        }
+    };
+    // from!(asset handle, Asset:some.property | some:transformer)
+    (@bind from asset $handle:expr, $cls:ty, { $($prop:tt)+ }, $transformer:expr) => {
+        $crate::relations::bind::FromAssetWithTransformer {
+            transformer: $transformer,
+            from: $crate::relations::bind::FromAsset {
+                id: $crate::relations::bind::bind_id::<$cls>(stringify!($($prop)+)),
+                handle: $handle,
+                reader: |c: &$cls| c.$($prop)+.clone()
+            }
+        }
+    };
+    // from!(query Query, Filter: some.method())
+    (@bind from query [$q:ty, $f:ty], { $($prop:tt)+ }, default) => {
+        $crate::relations::bind::FromQuery::<$q, $f, _> {
+            id: $crate::relations::bind::bind_id::<($q, $f)>(stringify!($($prop)+)),
+            reader: |c| c.$($prop)+.clone()
+        }
+    };
+    // from!(query Query, Filter: some.method() | some:transformer)
+    (@bind from query [$q:ty, $f:ty], { $($prop:tt)+ }, $transformer:expr) => {
+        $crate::relations::bind::FromQueryWithTransformer {
+            transformer: $transformer,
+            from: $crate::relations::bind::FromQuery::<$q, $f, _> {
+                id: $crate::relations::bind::bind_id::<($q, $f)>(stringify!($($prop)+)),
+                reader: |c| c.$($prop)+.clone()
+            }
+        }
+    };
     // to!(entity, Component:some.property)
     (@bind to component $entity:expr, $cls:ty, { $($prop:tt)+ }, default) => {
         $crate::relations::bind::ToComponentWithoutTransformer {
@@ -665,8 +1733,50 @@ macro_rules! bind {
             transformer: $transformer,
         }
     };
+    // to!(Resource:some.property)
+    (@bind to resource $cls:ty, { $($prop:tt)+ }, default) => {
+        $crate::relations::bind::ToResourceWithoutTransformer {
+            id: $crate::relations::bind::bind_id::<$cls>(stringify!($($prop)+)),
+            reader: |c: &::bevy::prelude::Mut<$cls>| &c.$($prop)+,
+            writer: |c: &mut ::bevy::prelude::Mut<$cls>| &mut c.$($prop)+,
+        }
+    };
+    // to!(Resource:some.property | some:transformer)
+    (@bind to resource $cls:ty, { $($prop:tt)+ }, $transformer:expr) => {
+        $crate::relations::bind::ToResource {
+            id: $crate::relations::bind::bind_id::<$cls>(stringify!($($prop)+)),
+            reader: |c: &::bevy::prelude::Mut<$cls>| &c.$($prop)+,
+            writer: |c: &mut ::bevy::prelude::Mut<$cls>| &mut c.$($prop)+,
+            transformer: $transformer,
+        }
+    };
+
 
+    // transformers are chained with pipes: a | b | c
+    (@pipe [ $($first:tt)+ ] | $($rest:tt)+ ) => {
+        |s, t| $crate::relations::bind::chain(
+            s,
+            t,
+            $crate::bind!(@transform $($first)+),
+            $crate::bind!(@pipe [] $($rest)+),
+        )
+    };
+    (@pipe [ $($first:tt)* ] $next:tt $($rest:tt)* ) => {
+        $crate::bind!(@pipe [ $($first)* $next ] $($rest)*)
+    };
+    (@pipe [ $($first:tt)+ ] ) => {
+        $crate::bind!(@transform $($first)+)
+    };
 
+    (@transform fmt( $($fmt:tt)* ) ) => {
+        |s, mut t| {
+            let val = format!($($fmt)*, s);
+            if val != *t {
+                *t = val;
+            }
+            Ok(())
+        }
+    };
     (@transform fmt:$val:ident( $($fmt:tt)* ) ) => {
         |s, mut t| {
             $crate::relations::bind::deprecated_transformer();
@@ -714,6 +1824,12 @@ macro_rules! bind {
     // };
 
     // only transformers here, can bind actually
+    (@args {[$q:ty, $f:ty] query $direction:ident}, $prop:tt) => {
+        $crate::bind!(@bind $direction query [$q, $f], $prop, default)
+    };
+    (@args {[$q:ty, $f:ty] query $direction:ident}, $prop:tt | $($transformer:tt)+ ) => {
+        $crate::bind!(@bind $direction query [$q, $f], $prop, $crate::bind!(@pipe [] $($transformer)+))
+    };
     (@args {$mode:ident from $entity:expr, $cls:ty}, $prop:tt) => {
         $crate::bind!(@bind from $mode $entity, $cls, $prop, default)
     };
@@ -724,17 +1840,20 @@ macro_rules! bind {
     (@args {$mode:ident to $entity:expr, $cls:ty}, $prop:tt) => {
         $crate::bind!(@bind to $mode $entity, $cls, $prop, default)
     };
+    (@args {$mode:ident to $cls:ty}, $prop:tt) => {
+        $crate::bind!(@bind to $mode $cls, $prop, default)
+    };
 
 
     (@args {$mode:ident $direction:ident $cls:ty}, $prop:tt | $($transformer:tt)+ ) => {
-        $crate::bind!(@bind $direction $mode $cls, $prop, $crate::bind!(@transform $($transformer)+))
+        $crate::bind!(@bind $direction $mode $cls, $prop, $crate::bind!(@pipe [] $($transformer)+))
     };
 
     (@args {$mode:ident $direction:ident $entity:expr, $cls:ty}, $prop:tt | $transformer:ident ) => {
         $crate::bind!(@bind $direction $mode $entity, $cls, $prop, transformable $transformer)
     };
     (@args {$mode:ident $direction:ident $entity:expr, $cls:ty}, $prop:tt | $($transformer:tt)+ ) => {
-        $crate::bind!(@bind $direction $mode $entity, $cls, $prop, $crate::bind!(@transform $($transformer)+))
+        $crate::bind!(@bind $direction $mode $entity, $cls, $prop, $crate::bind!(@pipe [] $($transformer)+))
     };
 
     // adding the rest of props, everyting before |
@@ -764,6 +1883,15 @@ macro_rules! bind {
     };
 
     // start here and move up
+    ( $direction:ident asset $handle:expr, $cls:ty: $($args:tt)+ ) => {
+        $crate::bind!(@args {asset $direction $handle, $cls}: $($args)+ )
+    };
+    ( $direction:ident query $q:ty, $f:ty: $($args:tt)+ ) => {
+        $crate::bind!(@args {[$q, $f] query $direction}: $($args)+ )
+    };
+    ( $direction:ident query $q:ty: $($args:tt)+ ) => {
+        $crate::bind!(@args {[$q, ()] query $direction}: $($args)+ )
+    };
     ( $direction:ident $cls:ty: $($args:tt)+ ) => {
         $crate::bind!(@args {resource $direction $cls}: $($args)+ )
     };
@@ -776,6 +1904,37 @@ macro_rules! bind {
     ( <= $entity:expr, $cls:ty: $($args:tt)+ ) => {
         $crate::bind!(@args {component from $entity, $cls}: $($args)+ )
     };
+
+    // two-way binds, no direction provided
+    // bind!(Resource:some.property)
+    ( $cls:ty: $($args:tt)+ ) => {
+        $crate::relations::bind::TwoWayBind {
+            from: $crate::bind!(from $cls: $($args)+),
+            to: $crate::bind!(to $cls: $($args)+),
+        }
+    };
+    // bind!(entity, Component:some.property)
+    ( $entity:expr, $cls:ty: $($args:tt)+ ) => {
+        $crate::relations::bind::TwoWayBind {
+            from: $crate::bind!(from $entity, $cls: $($args)+),
+            to: $crate::bind!(to $entity, $cls: $($args)+),
+        }
+    };
+
+    // computed binds, the value is combined from several components
+    // from!(entity, |a: &A, b: &B| expression)
+    ( from $entity:expr, | $($arg:ident: & $cls:ty),+ | $body:expr ) => {
+        $crate::relations::bind::FromComputed::<
+            ($(&$cls,)+),
+            ::bevy::prelude::Or<($(::bevy::prelude::Changed<$cls>,)+)>,
+            _
+        > {
+            id: $crate::relations::bind::bind_id::<($($cls,)+)>(stringify!($body)),
+            source: $entity,
+            reader: |q, e| q.get(e).ok().map(|($($arg,)+)| $body),
+            filter: ::std::marker::PhantomData,
+        }
+    };
 }
 
 #[macro_export]
@@ -814,6 +1973,22 @@ mod test {
         _max: f32,
     }
 
+    #[derive(Component, Default)]
+    struct Shield {
+        value: f32,
+    }
+
+    #[derive(Resource, Default)]
+    struct Settings {
+        volume: f32,
+    }
+
+    #[derive(bevy::reflect::TypeUuid)]
+    #[uuid = "1b9a5a47-7f0e-4d6c-9b53-3c1d2a6f0e84"]
+    struct Level {
+        name: String,
+    }
+
     #[derive(Default, Clone, PartialEq)]
     enum BtnMode {
         #[default]
@@ -878,6 +2053,11 @@ mod test {
         let _bind = to!(e, HealthBar: color | color.r) << from!(e, Health: percent());
         let _bind = from!(e, Health: percent() | color.r) >> to!(e, HealthBar: color);
 
+        let _bind = from!(e, Health: current | fmt("{:.0} HP")) >> to!(e, HealthBar: output);
+        let _bind = from!(e, Health: current) >> to!(e, Style: size.width | percent);
+        let _bind = from!(e, Health: current | val.px) >> to!(e, Style: size.width);
+        let _bind = from!(e, Health: current | val.px | fmt("{:?}")) >> to!(e, HealthBar: output);
+
         let _bind = from!(e, HealthBar: output) >> to!(e, Btn: mode);
         let _bind = to!(e, Btn: mode) << from!(e, HealthBar: output);
 
@@ -890,6 +2070,52 @@ mod test {
         let _bind = from!(Time: elapsed_seconds() | fmt.val("{val}")) >> to!(e, HealthBar: output);
         let _bind =
             to!(e, HealthBar: output) << from!(Time: elapsed_seconds() | fmt.val("{val:0.3}"));
+        let _bind = from!(e, Health: current) >> to!(Settings: volume);
+        let _bind = to!(Settings: volume) << from!(e, Health: current);
+
+        // assets & queries
+        let level: Handle<Level> = Handle::default();
+        let _bind = from!(asset level.clone(), Level: name) >> to!(e, HealthBar: output);
+        let _bind = to!(e, HealthBar: output) << from!(asset level, Level: name | fmt("{:?}"));
+        let _bind = from!(query &Health: iter().map(|h| h.current).sum::<f32>())
+            >> to!(e, HealthBar: value);
+        let _bind =
+            from!(query (), With<Health>: iter().count() | fmt("{}")) >> to!(e, HealthBar: output);
+
+        // computed
+        let _bind =
+            from!(e, |h: &Health, s: &Shield| h.current + s.value) >> to!(e, HealthBar: value);
+        let _bind =
+            to!(e, HealthBar: output | fmt("{:.0}")) << from!(e, |h: &Health| h.current / h.max);
+
+        // two-way
+        let _bind = bind!(Settings: volume);
+        let _bind = bind!(e, Health: current);
+    }
+
+    #[test]
+    fn two_way_bind() {
+        let mut app = App::new();
+        app.add_plugin(RelationsPlugin);
+        app.insert_resource(Settings { volume: 0.5 });
+        let bar = app.world.spawn(HealthBar::default()).id();
+        let volume = bind!(Settings: volume);
+        (to!(bar, HealthBar: value) << volume.from).write(&mut app.world);
+        (from!(bar, HealthBar: value) >> volume.to).write_back(&mut app.world);
+        let value = |app: &App| app.world.get::<HealthBar>(bar).unwrap().value;
+        let volume = |app: &App| app.world.resource::<Settings>().volume;
+
+        app.update();
+        assert_eq!(value(&app), 0.5, "Initial value should be reflected");
+        assert_eq!(volume(&app), 0.5, "Initial value shouldn't be written back");
+
+        app.world.get_mut::<HealthBar>(bar).unwrap().value = 0.8;
+        app.update();
+        assert_eq!(volume(&app), 0.8, "Changes should be written back");
+
+        app.world.resource_mut::<Settings>().volume = 0.2;
+        app.update();
+        assert_eq!(value(&app), 0.2, "Changes should be reflected");
     }
 
     #[test]
@@ -959,6 +2185,125 @@ mod test {
         );
     }
 
+    #[test]
+    fn transformers_chain() {
+        let mut app = App::new();
+        app.add_plugin(RelationsPlugin);
+        let player = app.world.spawn(Health::default()).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        let label = app.world.spawn(HealthBar::default()).id();
+        let bind = from!(player, Health: current | fmt("{:.0} HP")) >> to!(bar, HealthBar: output);
+        bind.write(&mut app.world);
+        let bind = from!(player, Health: current | val.percent | fmt("{:?}"))
+            >> to!(label, HealthBar: output);
+        bind.write(&mut app.world);
+
+        app.world.get_mut::<Health>(player).unwrap().current = 20.4;
+        app.update();
+        let output = |app: &App, e: Entity| app.world.get::<HealthBar>(e).unwrap().output.clone();
+        assert_eq!(output(&app, bar), "20 HP");
+        assert_eq!(output(&app, label), "Percent(20.4)");
+    }
+
+    #[test]
+    fn query_source() {
+        let mut app = App::new();
+        app.add_plugin(RelationsPlugin);
+        let label = app.world.spawn(HealthBar::default()).id();
+        let bind = from!(query (), With<Health>: iter().count() | fmt("Enemies: {}"))
+            >> to!(label, HealthBar: output);
+        bind.write(&mut app.world);
+        let output = |app: &App| app.world.get::<HealthBar>(label).unwrap().output.clone();
+
+        app.update();
+        assert_eq!(output(&app), "Enemies: 0");
+
+        app.world.spawn(Health::default());
+        app.world.spawn(Health::default());
+        app.update();
+        assert_eq!(output(&app), "Enemies: 2");
+    }
+
+    #[test]
+    fn computed_bind() {
+        let mut app = App::new();
+        app.add_plugin(RelationsPlugin);
+        let player = app.world.spawn((Health::default(), Shield::default())).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        let bind = from!(player, |h: &Health, s: &Shield| h.current + s.value)
+            >> to!(bar, HealthBar: value);
+        bind.write(&mut app.world);
+        let value = |app: &App| app.world.get::<HealthBar>(bar).unwrap().value;
+
+        app.world.get_mut::<Health>(player).unwrap().current = 20.;
+        app.update();
+        assert_eq!(value(&app), 20.);
+
+        app.world.get_mut::<Shield>(player).unwrap().value = 5.;
+        app.update();
+        assert_eq!(value(&app), 25., "Should recompute when any input changes");
+    }
+
+    #[test]
+    fn disconnect_bindings() {
+        let mut app = App::new();
+        app.add_plugin(RelationsPlugin);
+        let player = app.world.spawn(Health::default()).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        let label = app.world.spawn(HealthBar::default()).id();
+        (from!(player, Health: current) >> to!(bar, HealthBar: value)).write(&mut app.world);
+        let bind = from!(player, Health: current) >> to!(label, HealthBar: value);
+        let id = bind.write(&mut app.world);
+        let stats = |app: &App| *app.world.resource::<BindingStats>();
+        let value = |app: &App, e: Entity| app.world.get::<HealthBar>(e).unwrap().value;
+        app.update();
+        assert_eq!(stats(&app), BindingStats { live: 2, dead: 0 });
+
+        id.unbind(&mut app.world);
+        app.world.get_mut::<Health>(player).unwrap().current = 10.;
+        app.update();
+        assert_eq!(value(&app, bar), 10.);
+        assert_eq!(value(&app, label), 0., "Unbound target shouldn't change");
+        assert_eq!(stats(&app), BindingStats { live: 1, dead: 1 });
+
+        app.world.despawn(bar);
+        app.update();
+        assert_eq!(stats(&app), BindingStats { live: 0, dead: 2 });
+        let reads = app.world.get::<ReadComponent<Health, f32>>(player).unwrap();
+        assert!(reads.is_empty(), "Should remove disconnected bindings");
+    }
+
+    #[test]
+    fn rate_limited_bind() {
+        let mut app = App::new();
+        app.add_plugin(RelationsPlugin);
+        let player = app.world.spawn(Health::default()).id();
+        let bar = app.world.spawn(HealthBar::default()).id();
+        let label = app.world.spawn(HealthBar::default()).id();
+        (from!(player, Health: current) >> to!(bar, HealthBar: value))
+            .throttle_frames(2)
+            .write(&mut app.world);
+        (from!(player, Health: current) >> to!(label, HealthBar: value))
+            .debounce(0.5)
+            .write(&mut app.world);
+        let value = |app: &App, e: Entity| app.world.get::<HealthBar>(e).unwrap().value;
+        let set = |app: &mut App, v: f32| app.world.get_mut::<Health>(player).unwrap().current = v;
+
+        set(&mut app, 1.);
+        app.update();
+        assert_eq!(value(&app, bar), 1.);
+        set(&mut app, 2.);
+        app.update();
+        assert_eq!(value(&app, bar), 1., "Should postpone the value");
+        app.update();
+        assert_eq!(value(&app, bar), 2., "Should write the latest value");
+
+        assert_eq!(value(&app, label), 0., "Should wait for the source");
+        app.world.resource_mut::<BindingClock>().time = 1.;
+        app.update();
+        assert_eq!(value(&app, label), 2.);
+    }
+
     #[test]
     fn chain_bind() {
         let mut app = App::new();
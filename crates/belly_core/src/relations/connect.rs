@@ -5,12 +5,21 @@ use bevy::{
         system::{Command, EntityCommands},
     },
     prelude::*,
-    utils::HashMap,
+    utils::{HashMap, HashSet},
 };
 use itertools::Itertools;
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
-use crate::{ElementsBuilder, PointerInput, WithElements};
+use crate::{
+    eml::{build::ElementBuilderRegistry, handlers::EventHandler},
+    ElementTag, ElementsBuilder, PointerInput, WithElements,
+};
 
 use super::RelationsSystems;
 pub trait Signal: Event {
@@ -120,6 +129,7 @@ impl<C: Component, S: Signal> ConnectionTo<C, S> {
 
     pub fn all(self) -> Connection<C, S> {
         Connection {
+            id: ConnectionId::next(),
             target: self,
             filter: |_| true,
         }
@@ -127,6 +137,7 @@ impl<C: Component, S: Signal> ConnectionTo<C, S> {
 
     pub fn filter(self, filter: fn(&S) -> bool) -> Connection<C, S> {
         Connection {
+            id: ConnectionId::next(),
             target: self,
             filter,
         }
@@ -162,6 +173,7 @@ impl<S: Signal> ConnectionTo<WithoutComponent, S> {
 }
 
 pub struct Connection<C: Component, S: Signal> {
+    pub id: ConnectionId,
     pub target: ConnectionTo<C, S>,
     filter: fn(&S) -> bool,
 }
@@ -185,15 +197,128 @@ pub struct Connect<C: Component, S: Signal> {
 }
 
 impl<C: Component, S: Signal> Connect<C, S> {
-    pub fn write(self, world: &mut World) {
+    pub fn write(self, world: &mut World) -> ConnectionId {
+        let id = self.target.id;
         {
             let systems = world.get_resource_or_insert_with(RelationsSystems::default);
             systems.0.write().unwrap().add_signals_processor::<C, S>();
         }
+        world.init_resource::<DisconnectedSignals>();
         {
             let mut connections = world.get_resource_or_insert_with(Connections::<C, S>::default);
             connections.add(self);
         }
+        id
+    }
+}
+
+/// The handle of the connection returned by `write()`. The connections
+/// are removed with their source or target entity, the handle disconnects
+/// the connection earlier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConnectionId(u64);
+
+impl ConnectionId {
+    fn next() -> ConnectionId {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        ConnectionId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Disconnects the connection, the handler is not called anymore.
+    pub fn disconnect(self, world: &mut World) {
+        world
+            .get_resource_or_insert_with(DisconnectedSignals::default)
+            .insert(self);
+    }
+}
+
+/// Disconnects the connection, see [`ConnectionId::disconnect`].
+pub struct Disconnect(pub ConnectionId);
+
+impl Command for Disconnect {
+    fn write(self, world: &mut World) {
+        self.0.disconnect(world);
+    }
+}
+
+/// The ids disconnected since the last relations pass, cleared
+/// once every `Connections<C, S>` drops them.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(crate) struct DisconnectedSignals(HashSet<ConnectionId>);
+
+/// The context of the handler connected with
+/// `connect!(source.signal => |ctx| ...)`.
+pub struct SignalContext<'w> {
+    world: &'w mut World,
+    source: Entity,
+}
+
+impl<'w> SignalContext<'w> {
+    pub fn new(world: &'w mut World, source: Entity) -> SignalContext<'w> {
+        SignalContext { world, source }
+    }
+    /// The entity emitted the signal.
+    pub fn source(&self) -> Entity {
+        self.source
+    }
+    pub fn world(&mut self) -> &mut World {
+        self.world
+    }
+}
+
+/// Connects the handler to the signal of the widget by the name of the
+/// signal, so the widgets are wired from the code the same way the `on:`
+/// params wire them:
+/// ```rust,ignore
+/// let id = connect!(btn.press => |ctx| {
+///     ctx.world().resource_mut::<Score>().0 += 1;
+/// })
+/// .write(world);
+/// // or with the world-mutating handler
+/// commands.add(connect!(btn.press => |world: &mut World, btn: Entity| start(world, btn)));
+/// ```
+/// The widget is found by the tag of the `source`, so the `source` should
+/// be built before the connection is written.
+pub struct ConnectSignal {
+    pub source: Entity,
+    pub signal: &'static str,
+    pub handler: EventHandler,
+}
+
+impl ConnectSignal {
+    pub fn new<F>(source: Entity, signal: &'static str, handler: F) -> ConnectSignal
+    where
+        F: Fn(&mut World, Entity) + Send + Sync + 'static,
+    {
+        ConnectSignal {
+            source,
+            signal,
+            handler: Arc::new(handler),
+        }
+    }
+
+    /// Connects the handler, returns `None` if the widget of the `source`
+    /// has no such signal.
+    pub fn write(self, world: &mut World) -> Option<ConnectionId> {
+        let names = world
+            .get::<ElementTag>(self.source)
+            .map(|tag| tag.names.clone())
+            .unwrap_or_default();
+        let registry = world.resource::<ElementBuilderRegistry>().clone();
+        for builder in names.iter().filter_map(|name| registry.get_builder(*name)) {
+            let handler = self.handler.clone();
+            if let Some(id) = builder.connect_handler(world, self.source, self.signal, handler) {
+                return Some(id);
+            }
+        }
+        error!("{:?} has no {} signal", self.source, self.signal);
+        None
+    }
+}
+
+impl Command for ConnectSignal {
+    fn write(self, world: &mut World) {
+        ConnectSignal::write(self, world);
     }
 }
 
@@ -244,6 +369,12 @@ impl<C: Component, S: Signal> Connections<C, S> {
             .or_default()
             .push(connection.target);
     }
+    pub fn retain(&mut self, filter: impl Fn(ConnectionId) -> bool) {
+        self.map
+            .values_mut()
+            .for_each(|c| c.retain(|c| filter(c.id)));
+        self.map.retain(|_, c| !c.is_empty());
+    }
     pub fn remove(&mut self, source: &Entity) {
         if let Some(connections_to) = self.index.remove(source) {
             for connection_to in connections_to.iter() {
@@ -258,6 +389,19 @@ impl<C: Component, S: Signal> Connections<C, S> {
 
 #[macro_export]
 macro_rules! connect {
+    ($source:ident . $signal:ident => |$ctx:ident| $cb:expr) => {
+        $crate::relations::ConnectSignal::new(
+            $source,
+            stringify!($signal),
+            move |world: &mut ::bevy::prelude::World, source: ::bevy::prelude::Entity| {
+                let $ctx = &mut $crate::relations::SignalContext::new(world, source);
+                $cb;
+            },
+        )
+    };
+    ($source:ident . $signal:ident => $handler:expr) => {
+        $crate::relations::ConnectSignal::new($source, stringify!($signal), $handler)
+    };
     ($entity:expr, |$ctx:ident, $arg:ident: $typ:ty| $cb:expr) => {
         $crate::relations::ConnectionTo::component(
             $entity,
@@ -286,3 +430,54 @@ macro_rules! connect {
         $crate::relations::ConnectionTo::general(move |$ctx| $cb)
     };
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::relations::RelationsPlugin;
+    use bevy::asset::FileAssetIo;
+
+    struct Pressed([Entity; 1]);
+    impl Signal for Pressed {
+        fn sources(&self) -> &[Entity] {
+            &self.0
+        }
+    }
+
+    #[derive(Component, Default)]
+    struct Counter(usize);
+
+    #[test]
+    fn disconnect_signal() {
+        let mut app = App::new();
+        app.add_plugin(RelationsPlugin);
+        app.add_event::<Pressed>();
+        app.init_resource::<Time>();
+        app.insert_resource(AssetServer::new(FileAssetIo::new("assets", false)));
+        let counter = app.world.spawn(Counter::default()).id();
+        let button = app.world.spawn_empty().id();
+        let id = connect!(counter, |c: Counter| c.0 += 1)
+            .all()
+            .from(button)
+            .write(&mut app.world);
+        let press = |app: &mut App| {
+            app.world
+                .resource_mut::<Events<Pressed>>()
+                .send(Pressed([button]));
+            app.update();
+            app.world.get::<Counter>(counter).unwrap().0
+        };
+
+        assert_eq!(press(&mut app), 1);
+        id.disconnect(&mut app.world);
+        assert_eq!(press(&mut app), 1, "Should not call disconnected handler");
+        assert!(app
+            .world
+            .resource::<Connections<Counter, Pressed>>()
+            .is_empty());
+        assert!(
+            app.world.resource::<DisconnectedSignals>().is_empty(),
+            "Should forget the ids of the dropped connections"
+        );
+    }
+}
@@ -2,6 +2,7 @@ pub mod bind;
 mod connect;
 pub mod convert;
 pub mod ops;
+pub mod reflect;
 pub mod transform;
 
 use std::{
@@ -9,11 +10,13 @@ use std::{
     sync::{Arc, RwLock},
 };
 
-use bevy::{prelude::*, utils::HashSet};
+use bevy::{asset::Asset, ecs::query::ReadOnlyWorldQuery, prelude::*, utils::HashSet};
 
-use self::bind::{BindableSource, BindableTarget, ChangesState};
+use self::bind::{BindableSource, BindableTarget, BindingClock, BindingStats, ChangesState};
+use self::connect::DisconnectedSignals;
 pub use self::connect::{
-    Connect, ConnectionEntityContext, ConnectionGeneralContext, ConnectionTo, Connections, Signal,
+    Connect, ConnectSignal, ConnectionEntityContext, ConnectionGeneralContext, ConnectionId,
+    ConnectionTo, Connections, Disconnect, Signal, SignalContext,
 };
 
 pub struct RelationsPlugin;
@@ -28,6 +31,9 @@ pub enum RelationsStage {
 impl Plugin for RelationsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ChangesState>();
+        app.init_resource::<BindingStats>();
+        app.init_resource::<BindingClock>();
+        app.add_system_to_stage(CoreStage::First, bind::tick_binding_clock);
         app.add_stage_after(
             CoreStage::PreUpdate,
             RelationsStage::PreUpdate,
@@ -60,9 +66,19 @@ pub enum BindingStage {
 
     // new `bound` system states
     Bind,
+    /// The way back of the two-way bindings, applied after the regular ones.
+    BindBack,
     Watch,
+    /// Removes the disconnected bindings, runs before the bindings
+    /// are processed.
+    Cleanup,
 }
 
+/// The bindings are applied until nothing changes. The binding loop (like
+/// `a >> b` with `b >> a` transforming the value differently) never
+/// settles, so it is cut after this number of passes.
+const MAX_BINDING_PASSES: usize = 32;
+
 pub fn process_relations_system(world: &mut World) {
     let systems_ref = world
         .get_resource_or_insert_with(RelationsSystems::default)
@@ -74,6 +90,7 @@ pub fn process_relations_system(world: &mut World) {
 pub fn process_signals_system<C: Component, S: Signal>(
     asset_server: Res<AssetServer>,
     connections: Res<Connections<C, S>>,
+    disconnected: Res<DisconnectedSignals>,
     time: Res<Time>,
     mut commands: Commands,
     mut events: EventReader<S>,
@@ -89,7 +106,10 @@ pub fn process_signals_system<C: Component, S: Signal>(
                     asset_server: asset_server.clone(),
                     commands: &mut commands,
                 };
-                for connection in connections.iter().filter(|c| c.handles(signal)) {
+                for connection in connections
+                    .iter()
+                    .filter(|c| c.handles(signal) && !disconnected.contains(&c.id))
+                {
                     match &connection.target {
                         ConnectionTo::General { handler } => {
                             handler(&mut context);
@@ -117,6 +137,14 @@ pub fn process_signals_system<C: Component, S: Signal>(
     }
 }
 
+/// Removes the connections disconnected by their [`ConnectionId`].
+pub fn drop_disconnected_signals_system<C: Component, S: Signal>(
+    mut connections: ResMut<Connections<C, S>>,
+    disconnected: Res<DisconnectedSignals>,
+) {
+    connections.retain(|id| !disconnected.contains(&id));
+}
+
 pub fn cleanup_signals_system<C: Component, S: Signal>(
     mut connections: ResMut<Connections<C, S>>,
     mut commands: Commands,
@@ -136,8 +164,12 @@ pub(crate) struct BindingSystemsInternal {
     custom: HashSet<TypeId>,
 
     // new `bound` added system hashes
-    systems: HashSet<(TypeId, TypeId, TypeId, TypeId)>,
+    systems: HashSet<(BindingStage, TypeId, TypeId, TypeId, TypeId)>,
     watchers: HashSet<TypeId>,
+    cleanup: Schedule,
+    cleanups: HashSet<TypeId>,
+    /// Drops the disconnected signals from every `Connections<C, S>`.
+    disconnect: Schedule,
 }
 
 #[derive(Default, Clone, Resource)]
@@ -154,6 +186,10 @@ impl BindingSystemsInternal {
             .add_system_to_stage(BindingStage::Process, process_signals_system::<C, S>);
         self.schedule
             .add_system_to_stage(BindingStage::Process, cleanup_signals_system::<C, S>);
+        self.disconnect.add_system_to_stage(
+            BindingStage::Cleanup,
+            drop_disconnected_signals_system::<C, S>,
+        );
     }
     pub fn add_custom_system<Params, S: IntoSystemDescriptor<Params>>(
         &mut self,
@@ -168,16 +204,47 @@ impl BindingSystemsInternal {
             .add_system_to_stage(BindingStage::Custom, system);
     }
     pub fn run(&mut self, world: &mut World) {
+        if bind::collect_disconnected(world) {
+            self.cleanup.run(world);
+        }
+        // every storage drops the disconnected signals at once,
+        // so the ids are not kept after that
+        let disconnected = world.get_resource::<DisconnectedSignals>();
+        if disconnected.map_or(false, |ids| !ids.is_empty()) {
+            self.disconnect.run(world);
+            world.resource_mut::<DisconnectedSignals>().clear();
+        }
         let mut last_state = world.resource::<ChangesState>().get();
-        loop {
+        for _ in 0..MAX_BINDING_PASSES {
             self.schedule.run(world);
             let current_state = world.resource::<ChangesState>().get();
             if last_state == current_state {
-                break;
+                return;
             } else {
                 last_state = current_state;
             }
         }
+        warn!("Bindings are still changing after {MAX_BINDING_PASSES} passes, check for loops");
+    }
+
+    /// Adds the system removing the disconnected bindings from the
+    /// storage `C`.
+    fn add_cleanup<C: 'static, Params>(&mut self, system: impl IntoSystemDescriptor<Params>) {
+        let storage = TypeId::of::<C>();
+        if !self.cleanups.contains(&storage) {
+            self.cleanups.insert(storage);
+            self.cleanup
+                .add_system_to_stage(BindingStage::Cleanup, system);
+        }
+    }
+
+    fn add_watcher<R: Component>(&mut self) {
+        let watcher = TypeId::of::<R>();
+        if !self.watchers.contains(&watcher) {
+            self.watchers.insert(watcher);
+            self.schedule
+                .add_system_to_stage(BindingStage::Watch, bind::watch_changes::<R>);
+        }
     }
 
     fn add_component_to_component<
@@ -187,30 +254,79 @@ impl BindingSystemsInternal {
         T: BindableTarget,
     >(
         &mut self,
+        stage: BindingStage,
     ) {
-        let watcher = TypeId::of::<R>();
+        self.add_watcher::<R>();
         let entry = (
-            TypeId::of::<W>(),
+            stage.clone(),
+            TypeId::of::<R>(),
             TypeId::of::<W>(),
             TypeId::of::<S>(),
             TypeId::of::<T>(),
         );
-        if !self.watchers.contains(&watcher) {
-            self.watchers.insert(watcher);
+        if !self.systems.contains(&entry) {
+            self.systems.insert(entry);
             self.schedule
-                .add_system_to_stage(BindingStage::Watch, bind::watch_changes::<R>);
+                .add_system_to_stage(stage, bind::component_to_component_system::<R, W, S, T>);
         }
+        self.add_cleanup::<bind::ReadComponent<R, S>, _>(bind::cleanup_component_reads::<R, S>);
+        self.add_cleanup::<bind::WriteComponent<W, S, T>, _>(
+            bind::cleanup_component_writes::<W, S, T>,
+        );
+    }
+    fn add_resource_to_component<
+        R: Resource,
+        W: Component,
+        S: BindableSource,
+        T: BindableTarget,
+    >(
+        &mut self,
+    ) {
+        let entry = (
+            BindingStage::Bind,
+            TypeId::of::<R>(),
+            TypeId::of::<W>(),
+            TypeId::of::<S>(),
+            TypeId::of::<T>(),
+        );
 
         if !self.systems.contains(&entry) {
             self.systems.insert(entry);
             self.schedule.add_system_to_stage(
                 BindingStage::Bind,
-                bind::component_to_component_system::<R, W, S, T>,
+                bind::resource_to_component_system::<R, W, S, T>,
             );
         }
+        self.add_cleanup::<bind::ReadResource<R, S>, _>(bind::cleanup_resource_reads::<R, S>);
+        self.add_cleanup::<bind::WriteComponent<W, S, T>, _>(
+            bind::cleanup_component_writes::<W, S, T>,
+        );
     }
-    fn add_resource_to_component<
-        R: Resource,
+    fn add_asset_to_component<A: Asset, W: Component, S: BindableSource, T: BindableTarget>(
+        &mut self,
+    ) {
+        let entry = (
+            BindingStage::Bind,
+            TypeId::of::<Assets<A>>(),
+            TypeId::of::<W>(),
+            TypeId::of::<S>(),
+            TypeId::of::<T>(),
+        );
+        if !self.systems.contains(&entry) {
+            self.systems.insert(entry);
+            self.schedule.add_system_to_stage(
+                BindingStage::Bind,
+                bind::asset_to_component_system::<A, W, S, T>,
+            );
+        }
+        self.add_cleanup::<bind::ReadAsset<A, S>, _>(bind::cleanup_asset_reads::<A, S>);
+        self.add_cleanup::<bind::WriteComponent<W, S, T>, _>(
+            bind::cleanup_component_writes::<W, S, T>,
+        );
+    }
+    fn add_computed_to_component<
+        Q: ReadOnlyWorldQuery + 'static,
+        F: ReadOnlyWorldQuery + 'static,
         W: Component,
         S: BindableSource,
         T: BindableTarget,
@@ -218,19 +334,92 @@ impl BindingSystemsInternal {
         &mut self,
     ) {
         let entry = (
+            BindingStage::Bind,
+            TypeId::of::<bind::ReadComputed<Q, F, S>>(),
             TypeId::of::<W>(),
+            TypeId::of::<S>(),
+            TypeId::of::<T>(),
+        );
+        if !self.systems.contains(&entry) {
+            self.systems.insert(entry);
+            self.schedule.add_system_to_stage(
+                BindingStage::Bind,
+                bind::computed_to_component_system::<Q, F, W, S, T>,
+            );
+        }
+        self.add_cleanup::<bind::ReadComputed<Q, F, S>, _>(bind::cleanup_computed_reads::<Q, F, S>);
+        self.add_cleanup::<bind::WriteComponent<W, S, T>, _>(
+            bind::cleanup_component_writes::<W, S, T>,
+        );
+    }
+    fn add_query_to_component<
+        Q: ReadOnlyWorldQuery + 'static,
+        F: ReadOnlyWorldQuery + 'static,
+        W: Component,
+        S: BindableSource,
+        T: BindableTarget,
+    >(
+        &mut self,
+    ) {
+        let entry = (
+            BindingStage::Bind,
+            TypeId::of::<(Q, F)>(),
             TypeId::of::<W>(),
             TypeId::of::<S>(),
             TypeId::of::<T>(),
         );
-
         if !self.systems.contains(&entry) {
             self.systems.insert(entry);
             self.schedule.add_system_to_stage(
                 BindingStage::Bind,
-                bind::resource_to_component_system::<R, W, S, T>,
+                bind::query_to_component_system::<Q, F, W, S, T>,
             );
         }
+        self.add_cleanup::<bind::ReadQuery<Q, F, S>, _>(bind::cleanup_query_reads::<Q, F, S>);
+        self.add_cleanup::<bind::WriteComponent<W, S, T>, _>(
+            bind::cleanup_component_writes::<W, S, T>,
+        );
+    }
+    fn add_limited_writes<W: Component, S: BindableSource, T: BindableTarget>(&mut self) {
+        let entry = (
+            BindingStage::Bind,
+            TypeId::of::<bind::RateLimit>(),
+            TypeId::of::<W>(),
+            TypeId::of::<S>(),
+            TypeId::of::<T>(),
+        );
+        if !self.systems.contains(&entry) {
+            self.systems.insert(entry);
+            self.schedule
+                .add_system_to_stage(BindingStage::Bind, bind::flush_limited_writes::<W, S, T>);
+        }
+    }
+    fn add_component_to_resource<
+        R: Component,
+        W: Resource,
+        S: BindableSource,
+        T: BindableTarget,
+    >(
+        &mut self,
+        stage: BindingStage,
+    ) {
+        self.add_watcher::<R>();
+        let entry = (
+            stage.clone(),
+            TypeId::of::<R>(),
+            TypeId::of::<W>(),
+            TypeId::of::<S>(),
+            TypeId::of::<T>(),
+        );
+        if !self.systems.contains(&entry) {
+            self.systems.insert(entry);
+            self.schedule
+                .add_system_to_stage(stage, bind::component_to_resource_system::<R, W, S, T>);
+        }
+        self.add_cleanup::<bind::ReadComponent<R, S>, _>(bind::cleanup_component_reads::<R, S>);
+        self.add_cleanup::<bind::WriteResource<W, S, T>, _>(
+            bind::cleanup_resource_writes::<W, S, T>,
+        );
     }
 }
 
@@ -242,6 +431,11 @@ impl Default for BindingSystemsInternal {
         // new `bound` hashes
         let systems = HashSet::default();
         let watchers = HashSet::default();
+        let mut cleanup = Schedule::default();
+        cleanup.add_stage(BindingStage::Cleanup, SystemStage::parallel());
+        let cleanups = HashSet::default();
+        let mut disconnect = Schedule::default();
+        disconnect.add_stage(BindingStage::Cleanup, SystemStage::parallel());
 
         let mut schedule = Schedule::default();
         schedule
@@ -252,6 +446,7 @@ impl Default for BindingSystemsInternal {
             .add_stage(BindingStage::Report, SystemStage::parallel())
             // new `bound` stages
             .add_stage(BindingStage::Bind, SystemStage::parallel())
+            .add_stage(BindingStage::BindBack, SystemStage::parallel())
             .add_stage(BindingStage::Watch, SystemStage::parallel());
         Self {
             schedule,
@@ -261,6 +456,9 @@ impl Default for BindingSystemsInternal {
             // new `bound` hashes
             systems,
             watchers,
+            cleanup,
+            cleanups,
+            disconnect,
         }
     }
 }
@@ -1,5 +1,5 @@
 use super::bind::*;
-use bevy::prelude::*;
+use bevy::{asset::Asset, ecs::query::ReadOnlyWorldQuery, prelude::*};
 
 fn transform<
     S: BindableSource,
@@ -191,3 +191,325 @@ where
         .bind_resource(from)
     }
 }
+// from!(entity, Component:property) >> to!(Resource:property | filter)
+impl<R: Component, W: Resource, S: BindableSource, T: BindableTarget>
+    std::ops::Shr<ToResource<W, S, T>> for FromComponent<R, S>
+{
+    type Output = ComponentToResource<R, W, S, T>;
+    fn shr(self, rhs: ToResource<W, S, T>) -> Self::Output {
+        self.bind_resource(rhs)
+    }
+}
+// to!(Resource:property | filter) << from!(entity, Component:property)
+impl<R: Component, W: Resource, S: BindableSource, T: BindableTarget>
+    std::ops::Shl<FromComponent<R, S>> for ToResource<W, S, T>
+{
+    type Output = ComponentToResource<R, W, S, T>;
+    fn shl(self, from: FromComponent<R, S>) -> Self::Output {
+        self.bind_component(from)
+    }
+}
+// from!(entity, Component:property | filter) >> to!(Resource:property)
+impl<R: Component, W: Resource, S: BindableSource, T: BindableTarget>
+    std::ops::Shr<ToResourceWithoutTransformer<W, T>> for FromComponentWithTransformer<R, S, T>
+{
+    type Output = ComponentToResource<R, W, S, T>;
+    fn shr(self, rhs: ToResourceWithoutTransformer<W, T>) -> Self::Output {
+        self.bind_resource(rhs)
+    }
+}
+// to!(Resource:property) << from!(entity, Component:property | filter)
+impl<R: Component, W: Resource, S: BindableSource, T: BindableTarget>
+    std::ops::Shl<FromComponentWithTransformer<R, S, T>> for ToResourceWithoutTransformer<W, T>
+{
+    type Output = ComponentToResource<R, W, S, T>;
+    fn shl(self, from: FromComponentWithTransformer<R, S, T>) -> Self::Output {
+        self.bind_component(from)
+    }
+}
+// from!(entity, Component:property) >> to!(Resource:property)
+impl<R, W, S, T, E> std::ops::Shr<ToResourceWithoutTransformer<W, T>> for FromComponent<R, S>
+where
+    E: Into<TransformationError>,
+    R: Component,
+    W: Resource,
+    S: BindableSource,
+    T: BindableTarget + TryFrom<S, Error = E>,
+{
+    type Output = ComponentToResource<R, W, S, T>;
+    fn shr(self, to: ToResourceWithoutTransformer<W, T>) -> Self::Output {
+        ToResource {
+            id: to.id,
+            reader: to.reader,
+            writer: to.writer,
+            transformer: transform::<S, T, E>,
+        }
+        .bind_component(self)
+    }
+}
+// to!(Resource:property) << from!(entity, Component:property)
+impl<R, W, S, T, E> std::ops::Shl<FromComponent<R, S>> for ToResourceWithoutTransformer<W, T>
+where
+    E: Into<TransformationError>,
+    R: Component,
+    W: Resource,
+    S: BindableSource,
+    T: BindableTarget + TryFrom<S, Error = E>,
+{
+    type Output = ComponentToResource<R, W, S, T>;
+    fn shl(self, from: FromComponent<R, S>) -> Self::Output {
+        ToResource {
+            id: self.id,
+            reader: self.reader,
+            writer: self.writer,
+            transformer: transform::<S, T, E>,
+        }
+        .bind_component(from)
+    }
+}
+// from!(asset handle, Asset:property) >> to!(entity, Component:property | filter)
+impl<A: Asset, W: Component, S: BindableSource, T: BindableTarget>
+    std::ops::Shr<ToComponent<W, S, T>> for FromAsset<A, S>
+{
+    type Output = AssetToComponent<A, W, S, T>;
+    fn shr(self, rhs: ToComponent<W, S, T>) -> Self::Output {
+        self.bind_component(rhs)
+    }
+}
+// to!(entity, Component:property | filter) << from!(asset handle, Asset:property)
+impl<A: Asset, W: Component, S: BindableSource, T: BindableTarget> std::ops::Shl<FromAsset<A, S>>
+    for ToComponent<W, S, T>
+{
+    type Output = AssetToComponent<A, W, S, T>;
+    fn shl(self, from: FromAsset<A, S>) -> Self::Output {
+        from.bind_component(self)
+    }
+}
+// from!(asset handle, Asset:property | filter) >> to!(entity, Component:property)
+impl<A: Asset, W: Component, S: BindableSource, T: BindableTarget>
+    std::ops::Shr<ToComponentWithoutTransformer<W, T>> for FromAssetWithTransformer<A, S, T>
+{
+    type Output = AssetToComponent<A, W, S, T>;
+    fn shr(self, rhs: ToComponentWithoutTransformer<W, T>) -> Self::Output {
+        self.bind_component(rhs)
+    }
+}
+// to!(entity, Component:property) << from!(asset handle, Asset:property | filter)
+impl<A: Asset, W: Component, S: BindableSource, T: BindableTarget>
+    std::ops::Shl<FromAssetWithTransformer<A, S, T>> for ToComponentWithoutTransformer<W, T>
+{
+    type Output = AssetToComponent<A, W, S, T>;
+    fn shl(self, from: FromAssetWithTransformer<A, S, T>) -> Self::Output {
+        from.bind_component(self)
+    }
+}
+// from!(asset handle, Asset:property) >> to!(entity, Component:property)
+impl<A, W, S, T, E> std::ops::Shr<ToComponentWithoutTransformer<W, T>> for FromAsset<A, S>
+where
+    E: Into<TransformationError>,
+    A: Asset,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget + TryFrom<S, Error = E>,
+{
+    type Output = AssetToComponent<A, W, S, T>;
+    fn shr(self, to: ToComponentWithoutTransformer<W, T>) -> Self::Output {
+        self.bind_component(ToComponent {
+            id: to.id,
+            target: to.target,
+            reader: to.reader,
+            writer: to.writer,
+            transformer: transform::<S, T, E>,
+        })
+    }
+}
+// to!(entity, Component:property) << from!(asset handle, Asset:property)
+impl<A, W, S, T, E> std::ops::Shl<FromAsset<A, S>> for ToComponentWithoutTransformer<W, T>
+where
+    E: Into<TransformationError>,
+    A: Asset,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget + TryFrom<S, Error = E>,
+{
+    type Output = AssetToComponent<A, W, S, T>;
+    fn shl(self, from: FromAsset<A, S>) -> Self::Output {
+        from.bind_component(ToComponent {
+            id: self.id,
+            target: self.target,
+            reader: self.reader,
+            writer: self.writer,
+            transformer: transform::<S, T, E>,
+        })
+    }
+}
+// from!(query Query, Filter: method()) >> to!(entity, Component:property | filter)
+impl<Q, F, W, S, T> std::ops::Shr<ToComponent<W, S, T>> for FromQuery<Q, F, S>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    type Output = QueryToComponent<Q, F, W, S, T>;
+    fn shr(self, rhs: ToComponent<W, S, T>) -> Self::Output {
+        self.bind_component(rhs)
+    }
+}
+// to!(entity, Component:property | filter) << from!(query Query, Filter: method())
+impl<Q, F, W, S, T> std::ops::Shl<FromQuery<Q, F, S>> for ToComponent<W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    type Output = QueryToComponent<Q, F, W, S, T>;
+    fn shl(self, from: FromQuery<Q, F, S>) -> Self::Output {
+        from.bind_component(self)
+    }
+}
+// from!(query Query, Filter: method() | filter) >> to!(entity, Component:property)
+impl<Q, F, W, S, T> std::ops::Shr<ToComponentWithoutTransformer<W, T>>
+    for FromQueryWithTransformer<Q, F, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    type Output = QueryToComponent<Q, F, W, S, T>;
+    fn shr(self, rhs: ToComponentWithoutTransformer<W, T>) -> Self::Output {
+        self.bind_component(rhs)
+    }
+}
+// to!(entity, Component:property) << from!(query Query, Filter: method() | filter)
+impl<Q, F, W, S, T> std::ops::Shl<FromQueryWithTransformer<Q, F, S, T>>
+    for ToComponentWithoutTransformer<W, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    type Output = QueryToComponent<Q, F, W, S, T>;
+    fn shl(self, from: FromQueryWithTransformer<Q, F, S, T>) -> Self::Output {
+        from.bind_component(self)
+    }
+}
+// from!(query Query, Filter: method()) >> to!(entity, Component:property)
+impl<Q, F, W, S, T, E> std::ops::Shr<ToComponentWithoutTransformer<W, T>> for FromQuery<Q, F, S>
+where
+    E: Into<TransformationError>,
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget + TryFrom<S, Error = E>,
+{
+    type Output = QueryToComponent<Q, F, W, S, T>;
+    fn shr(self, to: ToComponentWithoutTransformer<W, T>) -> Self::Output {
+        self.bind_component(ToComponent {
+            id: to.id,
+            target: to.target,
+            reader: to.reader,
+            writer: to.writer,
+            transformer: transform::<S, T, E>,
+        })
+    }
+}
+// to!(entity, Component:property) << from!(query Query, Filter: method())
+impl<Q, F, W, S, T, E> std::ops::Shl<FromQuery<Q, F, S>> for ToComponentWithoutTransformer<W, T>
+where
+    E: Into<TransformationError>,
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget + TryFrom<S, Error = E>,
+{
+    type Output = QueryToComponent<Q, F, W, S, T>;
+    fn shl(self, from: FromQuery<Q, F, S>) -> Self::Output {
+        from.bind_component(ToComponent {
+            id: self.id,
+            target: self.target,
+            reader: self.reader,
+            writer: self.writer,
+            transformer: transform::<S, T, E>,
+        })
+    }
+}
+// from!(entity, |a: &A, b: &B| expr) >> to!(entity, Component:property | filter)
+impl<Q, F, W, S, T> std::ops::Shr<ToComponent<W, S, T>> for FromComputed<Q, F, S>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    type Output = ComputedToComponent<Q, F, W, S, T>;
+    fn shr(self, rhs: ToComponent<W, S, T>) -> Self::Output {
+        self.bind_component(rhs)
+    }
+}
+// to!(entity, Component:property | filter) << from!(entity, |a: &A, b: &B| expr)
+impl<Q, F, W, S, T> std::ops::Shl<FromComputed<Q, F, S>> for ToComponent<W, S, T>
+where
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget,
+{
+    type Output = ComputedToComponent<Q, F, W, S, T>;
+    fn shl(self, from: FromComputed<Q, F, S>) -> Self::Output {
+        from.bind_component(self)
+    }
+}
+// from!(entity, |a: &A, b: &B| expr) >> to!(entity, Component:property)
+impl<Q, F, W, S, T, E> std::ops::Shr<ToComponentWithoutTransformer<W, T>> for FromComputed<Q, F, S>
+where
+    E: Into<TransformationError>,
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget + TryFrom<S, Error = E>,
+{
+    type Output = ComputedToComponent<Q, F, W, S, T>;
+    fn shr(self, to: ToComponentWithoutTransformer<W, T>) -> Self::Output {
+        self.bind_component(ToComponent {
+            id: to.id,
+            target: to.target,
+            reader: to.reader,
+            writer: to.writer,
+            transformer: transform::<S, T, E>,
+        })
+    }
+}
+// to!(entity, Component:property) << from!(entity, |a: &A, b: &B| expr)
+impl<Q, F, W, S, T, E> std::ops::Shl<FromComputed<Q, F, S>> for ToComponentWithoutTransformer<W, T>
+where
+    E: Into<TransformationError>,
+    Q: ReadOnlyWorldQuery + 'static,
+    F: ReadOnlyWorldQuery + 'static,
+    W: Component,
+    S: BindableSource,
+    T: BindableTarget + TryFrom<S, Error = E>,
+{
+    type Output = ComputedToComponent<Q, F, W, S, T>;
+    fn shl(self, from: FromComputed<Q, F, S>) -> Self::Output {
+        from.bind_component(ToComponent {
+            id: self.id,
+            target: self.target,
+            reader: self.reader,
+            writer: self.writer,
+            transformer: transform::<S, T, E>,
+        })
+    }
+}
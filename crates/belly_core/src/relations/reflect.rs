@@ -0,0 +1,213 @@
+use std::{
+    any::{type_name, TypeId},
+    marker::PhantomData,
+};
+
+use bevy::{ecs::entity::Entities, prelude::*, reflect::GetPath};
+use tagstr::Tag;
+
+use super::{
+    bind::{bind_id, BindableSource, BindableTarget, FromComponent, ToComponentWithoutTransformer},
+    RelationsSystems,
+};
+
+/// The value the reflected field could be bound to.
+pub trait ReflectedValue: Reflect + BindableSource + BindableTarget + Default {}
+impl<T: Reflect + BindableSource + BindableTarget + Default> ReflectedValue for T {}
+
+/// Keeps the value of the field at `path` of the component `C` of the
+/// `target` entity. The changes of the field are copied to the `value`,
+/// the bound changes of the `value` are copied to the field. When both
+/// are changed at once the bound value wins.
+/// The field is kept on its own entity, it is despawned with the `target`.
+#[derive(Component)]
+pub struct ReflectPath<C: Component + Reflect, T: ReflectedValue> {
+    pub target: Entity,
+    pub path: String,
+    pub value: T,
+    synced: T,
+    marker: PhantomData<C>,
+}
+
+/// The field of the component bound by the reflect path, see
+/// [`ReflectBindExtension::bind_component`].
+pub struct ReflectedField<C: Component + Reflect, T: ReflectedValue> {
+    id: Tag,
+    entity: Entity,
+    marker: PhantomData<(C, T)>,
+}
+
+impl<C: Component + Reflect, T: ReflectedValue> ReflectedField<C, T> {
+    /// The field as the source of the binding.
+    pub fn from(&self) -> FromComponent<ReflectPath<C, T>, T> {
+        FromComponent {
+            id: self.id,
+            source: self.entity,
+            reader: |c: &ReflectPath<C, T>| c.value.clone(),
+        }
+    }
+
+    /// The field as the target of the binding.
+    pub fn to(&self) -> ToComponentWithoutTransformer<ReflectPath<C, T>, T> {
+        ToComponentWithoutTransformer {
+            id: self.id,
+            target: self.entity,
+            reader: |c: &Mut<ReflectPath<C, T>>| &c.value,
+            writer: |c: &mut Mut<ReflectPath<C, T>>| &mut c.value,
+        }
+    }
+}
+
+/// Binds the nested fields of the components by the reflect path, so the
+/// components which can't be changed (like the ones of the third-party
+/// crates) are bound without writing the accessors:
+/// ```rust,ignore
+/// let armor = world.bind_component::<Stats, f32>(player, "defense.armor");
+/// (armor.from() >> to!(label, Label:value | fmt("{:.0}"))).write(world);
+/// (from!(slider, Slider:value) >> armor.to()).write(world);
+/// ```
+/// The component should derive `Reflect`. The invalid path is reported
+/// each time the field or the value is changed.
+pub trait ReflectBindExtension {
+    fn bind_component<C: Component + Reflect, T: ReflectedValue>(
+        &mut self,
+        entity: Entity,
+        path: &str,
+    ) -> ReflectedField<C, T>;
+}
+
+impl ReflectBindExtension for World {
+    fn bind_component<C: Component + Reflect, T: ReflectedValue>(
+        &mut self,
+        entity: Entity,
+        path: &str,
+    ) -> ReflectedField<C, T> {
+        {
+            let systems = self.get_resource_or_insert_with(RelationsSystems::default);
+            let mut systems = systems.0.write().unwrap();
+            systems.add_custom_system(
+                TypeId::of::<ReflectPath<C, T>>(),
+                update_reflected_fields::<C, T>,
+            );
+        }
+        let value = self
+            .get::<C>(entity)
+            .and_then(|c| c.path::<T>(path).ok())
+            .cloned()
+            .unwrap_or_default();
+        let field = self
+            .spawn(ReflectPath::<C, T> {
+                target: entity,
+                path: path.to_string(),
+                synced: value.clone(),
+                value,
+                marker: PhantomData,
+            })
+            .id();
+        ReflectedField {
+            id: bind_id::<C>(path),
+            entity: field,
+            marker: PhantomData,
+        }
+    }
+}
+
+fn update_reflected_fields<C: Component + Reflect, T: ReflectedValue>(
+    mut commands: Commands,
+    entities: &Entities,
+    mut fields: Query<(
+        Entity,
+        &mut ReflectPath<C, T>,
+        ChangeTrackers<ReflectPath<C, T>>,
+    )>,
+    mut components: Query<(&mut C, ChangeTrackers<C>)>,
+) {
+    for (entity, mut field, tracker) in fields.iter_mut() {
+        if !entities.contains(field.target) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let Ok((mut component, component_tracker)) = components.get_mut(field.target) else {
+            continue;
+        };
+        if !tracker.is_changed() && !component_tracker.is_changed() {
+            continue;
+        }
+        let current = match component.path::<T>(&field.path) {
+            Ok(value) => value.clone(),
+            Err(e) => {
+                error!(
+                    "Invalid path {} of {}: {:?}",
+                    field.path,
+                    type_name::<C>(),
+                    e
+                );
+                continue;
+            }
+        };
+        if field.value != field.synced {
+            let field = field.bypass_change_detection();
+            if current != field.value {
+                if let Ok(value) = component.path_mut::<T>(&field.path) {
+                    *value = field.value.clone();
+                }
+            }
+            field.synced = field.value.clone();
+        } else if current != field.synced {
+            field.value = current.clone();
+            field.synced = current;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{relations::RelationsPlugin, *};
+
+    #[derive(Reflect, Default)]
+    struct Defense {
+        armor: f32,
+    }
+
+    #[derive(Component, Reflect, Default)]
+    struct Stats {
+        defense: Defense,
+    }
+
+    #[derive(Component, Default)]
+    struct Label {
+        value: f32,
+    }
+
+    #[test]
+    fn bind_reflected_field() {
+        let mut app = App::new();
+        app.add_plugin(RelationsPlugin);
+        let player = app.world.spawn(Stats::default()).id();
+        let label = app.world.spawn(Label::default()).id();
+        let slider = app.world.spawn(Label { value: 5. }).id();
+        let armor = app
+            .world
+            .bind_component::<Stats, f32>(player, "defense.armor");
+        (armor.from() >> to!(label, Label: value)).write(&mut app.world);
+        let stats = |app: &App| app.world.get::<Stats>(player).unwrap().defense.armor;
+
+        app.world.get_mut::<Stats>(player).unwrap().defense.armor = 10.;
+        app.update();
+        assert_eq!(app.world.get::<Label>(label).unwrap().value, 10.);
+
+        let field = app
+            .world
+            .bind_component::<Stats, f32>(player, "defense.armor");
+        (from!(slider, Label: value) >> field.to()).write(&mut app.world);
+        app.update();
+        assert_eq!(stats(&app), 5., "Should write the field");
+        assert_eq!(app.world.get::<Label>(label).unwrap().value, 5.);
+
+        app.world.get_mut::<Label>(slider).unwrap().value = 7.;
+        app.update();
+        assert_eq!(stats(&app), 7.);
+        assert_eq!(app.world.get::<Label>(label).unwrap().value, 7.);
+    }
+}
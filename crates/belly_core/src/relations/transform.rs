@@ -84,3 +84,41 @@ pub trait ColorTransformerExtension {
     }
 }
 impl ColorTransformerExtension for Transformers {}
+
+macro_rules! impl_val_transformer {
+    ($func:ident, $unit:ident) => {
+        pub fn $func<T: TransformableTo<f32>>(&self) -> fn(&T, Prop<Val>) -> TransformationResult {
+            |source, mut val| {
+                let value = Val::$unit(T::transform(source)?);
+                if *val != value {
+                    *val = value;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Turns numbers into the `Val` of the style properties:
+/// ```rust,ignore
+/// from!(player, Health:current) >> to!(bar, Style:size.width | percent)
+/// ```
+pub struct ValTransformer;
+impl ValTransformer {
+    impl_val_transformer! { px, Px }
+    impl_val_transformer! { percent, Percent }
+}
+
+impl AsTransformer for Val {
+    type Transformer = ValTransformer;
+    fn as_transformer() -> Self::Transformer {
+        ValTransformer
+    }
+}
+
+pub trait ValTransformerExtension {
+    fn val() -> ValTransformer {
+        ValTransformer
+    }
+}
+impl ValTransformerExtension for Transformers {}
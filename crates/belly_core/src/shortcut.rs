@@ -0,0 +1,356 @@
+use std::fmt::Display;
+
+use bevy::{input::InputSystem, prelude::*, ui::UiStack};
+
+use crate::{eml::Variant, input, relations::Signal, tags, States};
+
+pub(crate) struct ShortcutPlugin;
+impl Plugin for ShortcutPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShortcutEvent>().add_system_to_stage(
+            CoreStage::PreUpdate,
+            shortcut_system
+                .label(input::Label::Shortcuts)
+                .after(InputSystem),
+        );
+    }
+}
+
+/// The key chord triggering the element, set by the `shortcut` param
+/// (`shortcut="Ctrl+S"`) or inserted from the code:
+/// ```rust,ignore
+/// commands.entity(save).insert(Shortcut::new(KeyCode::S).ctrl());
+/// ```
+/// The modifiers should match exactly, so `Ctrl+S` isn't triggered by
+/// `Ctrl+Shift+S`. `Cmd`, `Super` and `Meta` stand for the logo key.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub key: KeyCode,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub logo: bool,
+}
+
+impl Shortcut {
+    pub fn new(key: KeyCode) -> Shortcut {
+        Shortcut {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+            logo: false,
+        }
+    }
+    pub fn ctrl(mut self) -> Shortcut {
+        self.ctrl = true;
+        self
+    }
+    pub fn shift(mut self) -> Shortcut {
+        self.shift = true;
+        self
+    }
+    pub fn alt(mut self) -> Shortcut {
+        self.alt = true;
+        self
+    }
+    pub fn logo(mut self) -> Shortcut {
+        self.logo = true;
+        self
+    }
+
+    /// Returns `true` if the chord is just pressed.
+    pub fn just_pressed(&self, keyboard: &Input<KeyCode>) -> bool {
+        keyboard.just_pressed(self.key)
+            && self.ctrl == keyboard.any_pressed([KeyCode::LControl, KeyCode::RControl])
+            && self.shift == keyboard.any_pressed([KeyCode::LShift, KeyCode::RShift])
+            && self.alt == keyboard.any_pressed([KeyCode::LAlt, KeyCode::RAlt])
+            && self.logo == keyboard.any_pressed([KeyCode::LWin, KeyCode::RWin])
+    }
+}
+
+impl TryFrom<&str> for Shortcut {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut parts: Vec<_> = value.split('+').map(|p| p.trim()).collect();
+        // `Ctrl++` is the plus key
+        if value.trim().ends_with("++") {
+            parts.truncate(parts.len() - 2);
+            parts.push("+");
+        }
+        let Some(key) = parts.pop() else {
+            return Err(format!("Can't parse `{}` as Shortcut", value));
+        };
+        let key = parse_key(key).ok_or_else(|| format!("Unknown key `{}` in `{}`", key, value))?;
+        let mut shortcut = Shortcut::new(key);
+        for modifier in parts {
+            shortcut = match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => shortcut.ctrl(),
+                "shift" => shortcut.shift(),
+                "alt" | "option" => shortcut.alt(),
+                "cmd" | "super" | "meta" | "win" => shortcut.logo(),
+                _ => return Err(format!("Unknown modifier `{}` in `{}`", modifier, value)),
+            };
+        }
+        Ok(shortcut)
+    }
+}
+
+impl TryFrom<Variant> for Shortcut {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        match value {
+            Variant::String(s) => Shortcut::try_from(s.as_str()),
+            variant => variant
+                .take::<Shortcut>()
+                .ok_or_else(|| "Invalid value for Shortcut".to_string()),
+        }
+    }
+}
+
+impl From<Shortcut> for Variant {
+    fn from(shortcut: Shortcut) -> Self {
+        Variant::boxed(shortcut)
+    }
+}
+
+impl Display for Shortcut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (pressed, modifier) in [
+            (self.ctrl, "Ctrl+"),
+            (self.shift, "Shift+"),
+            (self.alt, "Alt+"),
+            (self.logo, "Cmd+"),
+        ] {
+            if pressed {
+                f.write_str(modifier)?;
+            }
+        }
+        match self.key {
+            KeyCode::Key0 => f.write_str("0"),
+            KeyCode::Key1 => f.write_str("1"),
+            KeyCode::Key2 => f.write_str("2"),
+            KeyCode::Key3 => f.write_str("3"),
+            KeyCode::Key4 => f.write_str("4"),
+            KeyCode::Key5 => f.write_str("5"),
+            KeyCode::Key6 => f.write_str("6"),
+            KeyCode::Key7 => f.write_str("7"),
+            KeyCode::Key8 => f.write_str("8"),
+            KeyCode::Key9 => f.write_str("9"),
+            KeyCode::Plus => f.write_str("+"),
+            KeyCode::Minus => f.write_str("-"),
+            key => write!(f, "{:?}", key),
+        }
+    }
+}
+
+fn parse_key(key: &str) -> Option<KeyCode> {
+    use KeyCode::*;
+    let code = match key.to_lowercase().as_str() {
+        "a" => A,
+        "b" => B,
+        "c" => C,
+        "d" => D,
+        "e" => E,
+        "f" => F,
+        "g" => G,
+        "h" => H,
+        "i" => I,
+        "j" => J,
+        "k" => K,
+        "l" => L,
+        "m" => M,
+        "n" => N,
+        "o" => O,
+        "p" => P,
+        "q" => Q,
+        "r" => R,
+        "s" => S,
+        "t" => T,
+        "u" => U,
+        "v" => V,
+        "w" => W,
+        "x" => X,
+        "y" => Y,
+        "z" => Z,
+        "0" => Key0,
+        "1" => Key1,
+        "2" => Key2,
+        "3" => Key3,
+        "4" => Key4,
+        "5" => Key5,
+        "6" => Key6,
+        "7" => Key7,
+        "8" => Key8,
+        "9" => Key9,
+        "f1" => F1,
+        "f2" => F2,
+        "f3" => F3,
+        "f4" => F4,
+        "f5" => F5,
+        "f6" => F6,
+        "f7" => F7,
+        "f8" => F8,
+        "f9" => F9,
+        "f10" => F10,
+        "f11" => F11,
+        "f12" => F12,
+        "esc" | "escape" => Escape,
+        "enter" | "return" => Return,
+        "space" => Space,
+        "tab" => Tab,
+        "backspace" | "back" => Back,
+        "del" | "delete" => Delete,
+        "ins" | "insert" => Insert,
+        "home" => Home,
+        "end" => End,
+        "pageup" => PageUp,
+        "pagedown" => PageDown,
+        "up" => Up,
+        "down" => Down,
+        "left" => Left,
+        "right" => Right,
+        "+" | "plus" => Plus,
+        "-" | "minus" => Minus,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Marks the element capturing the shortcuts: while the scope is
+/// visible only the shortcuts of its descendants are triggered. The
+/// topmost scope wins, so the modal dialog opened over another one
+/// keeps the shortcuts of the dialog below from firing.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct ShortcutScope;
+
+/// Sent when the [`Shortcut`] of the element is pressed. The widgets
+/// handle it as the activation, the `<button>` is pressed by it.
+pub struct ShortcutEvent {
+    pub entities: [Entity; 1],
+    pub shortcut: Shortcut,
+}
+
+impl Signal for ShortcutEvent {
+    fn sources(&self) -> &[Entity] {
+        &self.entities
+    }
+}
+
+/// Returns `true` if the element and its ancestors are visible.
+fn visible(visibility: Option<&ComputedVisibility>) -> bool {
+    visibility.map_or(true, |v| v.is_visible())
+}
+
+pub fn shortcut_system(
+    keyboard: Res<Input<KeyCode>>,
+    ui_stack: Res<UiStack>,
+    shortcuts: Query<(
+        Entity,
+        &Shortcut,
+        Option<&States>,
+        Option<&ComputedVisibility>,
+    )>,
+    scopes: Query<(Entity, Option<&ComputedVisibility>), With<ShortcutScope>>,
+    parents: Query<&Parent>,
+    mut events: EventWriter<ShortcutEvent>,
+) {
+    if keyboard.get_just_pressed().next().is_none() {
+        return;
+    }
+    let scope = scopes
+        .iter()
+        .filter(|(_, visibility)| visible(*visibility))
+        .max_by_key(|(entity, _)| ui_stack.uinodes.iter().position(|e| e == entity))
+        .map(|(entity, _)| entity);
+    for (entity, shortcut, states, visibility) in shortcuts.iter() {
+        if !shortcut.just_pressed(&keyboard) || !visible(visibility) {
+            continue;
+        }
+        if states.map_or(false, |s| s.contains(&tags::disabled())) {
+            continue;
+        }
+        if let Some(scope) = scope {
+            let in_scope = entity == scope || parents.iter_ancestors(entity).any(|e| e == scope);
+            if !in_scope {
+                continue;
+            }
+        }
+        events.send(ShortcutEvent {
+            entities: [entity],
+            shortcut: *shortcut,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_shortcut() {
+        assert_eq!(
+            Shortcut::try_from("Ctrl+S"),
+            Ok(Shortcut::new(KeyCode::S).ctrl())
+        );
+        assert_eq!(
+            Shortcut::try_from("ctrl + shift + 1"),
+            Ok(Shortcut::new(KeyCode::Key1).ctrl().shift())
+        );
+        assert_eq!(
+            Shortcut::try_from("Cmd++"),
+            Ok(Shortcut::new(KeyCode::Plus).logo())
+        );
+        assert_eq!(
+            Shortcut::new(KeyCode::Key1).ctrl().shift().to_string(),
+            "Ctrl+Shift+1"
+        );
+        assert!(Shortcut::try_from("Hyper+S").is_err());
+    }
+
+    #[test]
+    fn modal_scope_captures_shortcuts() {
+        let mut app = App::new();
+        app.add_event::<ShortcutEvent>()
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<UiStack>()
+            .add_system(shortcut_system);
+        let save = app.world.spawn(Shortcut::new(KeyCode::S).ctrl()).id();
+        let close = app.world.spawn(Shortcut::new(KeyCode::Escape)).id();
+        let press = |app: &mut App, keys: &[KeyCode]| {
+            {
+                let mut keyboard = app.world.resource_mut::<Input<KeyCode>>();
+                keyboard.release_all();
+                keyboard.clear();
+                for key in keys {
+                    keyboard.press(*key);
+                }
+            }
+            app.update();
+            let events = app.world.resource::<Events<ShortcutEvent>>();
+            events
+                .iter_current_update_events()
+                .map(|e| e.entities[0])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            press(&mut app, &[KeyCode::LControl, KeyCode::S]),
+            vec![save]
+        );
+        assert_eq!(
+            press(&mut app, &[KeyCode::LShift, KeyCode::LControl, KeyCode::S]),
+            vec![],
+            "Should match the modifiers exactly"
+        );
+
+        let modal = app.world.spawn(ShortcutScope).id();
+        app.world.entity_mut(modal).push_children(&[close]);
+        assert_eq!(
+            press(&mut app, &[KeyCode::LControl, KeyCode::S]),
+            vec![],
+            "Should capture the shortcuts outside the modal"
+        );
+        assert_eq!(press(&mut app, &[KeyCode::Escape]), vec![close]);
+    }
+}
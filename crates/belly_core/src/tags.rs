@@ -11,6 +11,10 @@ pub fn styles() -> Tag {
     tag!("styles")
 }
 
+pub fn style() -> Tag {
+    tag!("style")
+}
+
 pub fn id() -> Tag {
     tag!("id")
 }
@@ -34,3 +38,19 @@ pub fn active() -> Tag {
 pub fn pressed() -> Tag {
     tag!("pressed")
 }
+
+pub fn disabled() -> Tag {
+    tag!("disabled")
+}
+
+pub fn truncated() -> Tag {
+    tag!("truncated")
+}
+
+pub fn dragging() -> Tag {
+    tag!("dragging")
+}
+
+pub fn dragover() -> Tag {
+    tag!("dragover")
+}
@@ -0,0 +1,94 @@
+use bevy::{
+    prelude::*,
+    ui::{CalculatedClip, UiStack},
+};
+
+use crate::relations::Signal;
+
+pub(crate) struct TouchPlugin;
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PinchEvent>().add_system(pinch_system);
+    }
+}
+
+/// Converts the touch position (the origin is at the bottom left corner
+/// of the window, as for the cursor) to the UI coordinates.
+pub fn touch_position(windows: &Windows, position: Vec2) -> Vec2 {
+    match windows.get_primary() {
+        Some(window) => Vec2::new(position.x, window.height() - position.y),
+        None => position,
+    }
+}
+
+/// Allows to pinch the element with two fingers, set by the `zoomable`
+/// param. The element receives the [`PinchEvent`]s and scales its content
+/// itself.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Zoomable;
+
+/// Sent to the topmost [`Zoomable`] element under the center of the pinch.
+pub struct PinchEvent {
+    pub entities: [Entity; 1],
+    /// The ratio of the distance between the fingers to the distance
+    /// at the previous frame: greater than `1` when the fingers spread.
+    pub scale: f32,
+    /// The point between the fingers in the UI coordinates.
+    pub center: Vec2,
+}
+
+impl Signal for PinchEvent {
+    fn sources(&self) -> &[Entity] {
+        &self.entities
+    }
+}
+
+pub fn pinch_system(
+    touches: Res<Touches>,
+    windows: Res<Windows>,
+    ui_stack: Res<UiStack>,
+    nodes: Query<
+        (
+            &Node,
+            &GlobalTransform,
+            Option<&CalculatedClip>,
+            &ComputedVisibility,
+        ),
+        With<Zoomable>,
+    >,
+    mut events: EventWriter<PinchEvent>,
+) {
+    let mut pressed = touches.iter();
+    let (Some(a), Some(b), None) = (pressed.next(), pressed.next(), pressed.next()) else {
+        return;
+    };
+    let distance = a.position().distance(b.position());
+    let previous = a.previous_position().distance(b.previous_position());
+    if previous <= 0. || distance == previous {
+        return;
+    }
+    let center = touch_position(&windows, (a.position() + b.position()) * 0.5);
+    for entity in ui_stack.uinodes.iter().rev() {
+        let Ok((node, transform, clip, visibility)) = nodes.get(*entity) else {
+            continue;
+        };
+        if !visibility.is_visible() {
+            continue;
+        }
+        let position = transform.translation().truncate();
+        let mut min = position - node.size() * 0.5;
+        let mut max = position + node.size() * 0.5;
+        if let Some(clip) = clip {
+            min = min.max(clip.clip.min);
+            max = max.min(clip.clip.max);
+        }
+        if (min.x..max.x).contains(&center.x) && (min.y..max.y).contains(&center.y) {
+            events.send(PinchEvent {
+                entities: [*entity],
+                scale: distance / previous,
+                center,
+            });
+            return;
+        }
+    }
+}
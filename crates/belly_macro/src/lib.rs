@@ -1,7 +1,9 @@
 use proc_macro2::{Span, TokenStream};
 use quote::*;
 extern crate proc_macro;
-use syn::{parse_macro_input, spanned::Spanned, DeriveInput, Error, Expr, ExprPath};
+use syn::{
+    ext::IdentExt, parse_macro_input, spanned::Spanned, DeriveInput, Error, Expr, ExprPath,
+};
 use syn_rsx::{parse, Node, NodeAttribute, NodeElement};
 use toml;
 
@@ -259,6 +261,42 @@ fn create_attr_stmt(attr: &NodeAttribute) -> TokenStream {
     }
 }
 
+/// Returns the `bind!` value of the `prop=bind!(..)` or the
+/// `bind:prop=bind!(..)` param: the param is bound both ways.
+fn two_way_bind(attr: &NodeAttribute) -> Option<&Expr> {
+    match attr.value.as_ref()?.as_ref() {
+        bind @ Expr::Macro(expr) if expr.mac.path.is_ident("bind") => Some(bind),
+        _ => None,
+    }
+}
+
+/// Returns the kind of the toggle (`class` or `state`) and its name
+/// for the `c:name=from!(..)` and `state:name=from!(..)` params.
+fn bound_toggle(attr: &NodeAttribute) -> Option<(&'static str, String)> {
+    let Expr::Macro(_) = attr.value.as_ref()?.as_ref() else {
+        return None;
+    };
+    let name = attr.key.to_string();
+    if let Some(class) = name.strip_prefix("c:") {
+        Some(("class", class.to_string()))
+    } else {
+        name.strip_prefix("state:")
+            .map(|state| ("state", state.to_string()))
+    }
+}
+
+/// Returns the property name and the bind of the `s:name=from!(..)` param.
+fn bound_style(attr: &NodeAttribute) -> Option<(String, &Expr)> {
+    let name = attr.key.to_string();
+    let name = name.strip_prefix("s:")?;
+    match attr.value.as_ref()?.as_ref() {
+        bind @ Expr::Macro(expr) if expr.mac.path.is_ident("from") => {
+            Some((name.to_string(), bind))
+        }
+        _ => None,
+    }
+}
+
 fn process_for_loop(node: &NodeElement) -> TokenStream {
     let span = node.span();
     if node.attributes.len() != 2 {
@@ -287,29 +325,35 @@ fn process_for_loop(node: &NodeElement) -> TokenStream {
         );
     }
     let iter_value = iter_attr.value.as_ref().unwrap().as_ref();
-
-    let mut loop_content = quote! {};
-    for ch in node.children.iter() {
-        if let Node::Element(elem) = ch {
-            if &elem.name.to_string() == "for" {
-                let expr = process_for_loop(elem);
-                loop_content = quote! {
+    let bound = iter_value
+        .to_token_stream()
+        .to_string()
+        .trim()
+        .starts_with("from!");
+
+    let loop_content = walk_children(&node.children);
+    if bound {
+        // the loop is rendered again every time the bound collection changes
+        let core = core_path();
+        quote! {
+            let __repeat = __world.spawn_empty().id();
+            #core::eml::repeat::Repeat::spawn(
+                __world,
+                __repeat,
+                #iter_value,
+                move |__world: &mut ::bevy::prelude::World, #item_ident| {
+                    let mut __ctx = #core::eml::build::ElementContextData::new(__repeat);
                     #loop_content
-                    #expr
-                };
-                continue;
-            }
-        }
-
-        let expr = walk_nodes(ch, true);
-        loop_content = quote! {
-            #loop_content
-            __ctx.children.push( #expr );
+                    __ctx.children
+                },
+            );
+            __ctx.children.push(__repeat);
         }
-    }
-    quote! {
-        for #item_ident in #iter_value {
-            #loop_content
+    } else {
+        quote! {
+            for #item_ident in #iter_value {
+                #loop_content
+            }
         }
     }
 }
@@ -366,6 +410,74 @@ fn process_slots(node: &NodeElement) -> TokenStream {
     }
 }
 
+/// Statements pushing the elements built from the `nodes`
+/// into the `__ctx.children`.
+fn walk_children(nodes: &[Node]) -> TokenStream {
+    let core = core_path();
+    let mut children = quote! {};
+    for child in nodes.iter() {
+        match child {
+            Node::Element(element) => {
+                let element_name = element.name.to_string();
+                let expr = match element_name.as_str() {
+                    "for" => process_for_loop(element),
+                    "slot" => process_slots(element),
+                    "fragment" => process_fragment(element),
+                    _ => {
+                        let expr = walk_nodes(child, true);
+                        quote! {
+                            __ctx.children.push( #expr );
+                        }
+                    }
+                };
+                children = quote! {
+                    #children
+                    #expr
+                }
+            }
+            Node::Text(text) => {
+                let text = text.value.as_ref();
+                children = quote! {
+                    #children
+                    __ctx.children.push(
+                        __world.spawn(::bevy::prelude::TextBundle {
+                            text: ::bevy::prelude::Text::from_section(
+                                #text,
+                                ::std::default::Default::default()
+                            ),
+                            ..default()
+                        })
+                        .insert(#core::Element::inline())
+                        .id()
+                    );
+                };
+            }
+            Node::Block(block) => {
+                let block = block.value.as_ref();
+                let block_span = block.span();
+                children = quote_spanned! { block_span=>
+                    #children
+                    let __node = __world.spawn_empty().id();
+                    for __child in #block.into_content(__node, __world).iter() {
+                        __ctx.children.push( __child.clone() );
+                    }
+                }
+            }
+            _ => (),
+        };
+    }
+    children
+}
+
+/// The children of the `<fragment>` are pushed into the content
+/// of the parent directly.
+fn process_fragment(node: &NodeElement) -> TokenStream {
+    if !node.attributes.is_empty() {
+        return err2(node.span(), "<fragment> tag takes no attributes");
+    }
+    walk_children(&node.children)
+}
+
 fn walk_nodes<'a>(element: &'a Node, create_entity: bool) -> TokenStream {
     let core = core_path();
     let mut children = quote! {};
@@ -403,6 +515,46 @@ fn walk_nodes<'a>(element: &'a Node, create_entity: bool) -> TokenStream {
                         #connections
                         __builder.#signal_ident(__world, __parent, #connection);
                     }
+                } else if let Some((kind, name)) = bound_toggle(attr) {
+                    let bind = attr.value.as_ref().unwrap().as_ref();
+                    let stream = bind.to_token_stream().to_string();
+                    if !stream.trim().starts_with("from!") {
+                        return Error::new(
+                            bind.span(),
+                            format!("{attr_name} param should be bound with from! macro"),
+                        )
+                        .into_compile_error();
+                    }
+                    let kind = syn::Ident::new(kind, attr_span);
+                    connections = quote_spanned! {attr_span=>
+                        #connections
+                        let __toggle = #core::eml::toggle::ClassToggle::#kind(
+                            __world,
+                            __parent,
+                            #core::tagstr::Tag::new(#name),
+                        );
+                        (__toggle << #bind).write(__world);
+                    };
+                } else if let Some((name, bind)) = bound_style(attr) {
+                    connections = quote_spanned! {attr_span=>
+                        #connections
+                        #core::eml::style::StyleSource::bind_style(
+                            #bind,
+                            __world,
+                            __parent,
+                            #core::tagstr::Tag::new(#name),
+                        );
+                    };
+                } else if let Some(bind) = two_way_bind(attr) {
+                    let prop = attr_name.strip_prefix("bind:").unwrap_or(&attr_name);
+                    let bind_to = format_ident!("bind_to_{prop}", span = bind.span());
+                    let bind_from = format_ident!("bind_from_{prop}", span = bind.span());
+                    connections = quote_spanned! {attr_span=>
+                        #connections
+                        let __bind = #bind;
+                        (__builder.#bind_to(__parent) << __bind.from).write(__world);
+                        (__builder.#bind_from(__parent) >> __bind.to).write_back(__world);
+                    };
                 } else if let Some(prop) = attr_name.strip_prefix("bind:") {
                     let Some(bind) = attr.value.as_ref() else {
                         return Error::new(attr_span, format!("bind:{prop} param should provide connection"))
@@ -424,6 +576,12 @@ fn walk_nodes<'a>(element: &'a Node, create_entity: bool) -> TokenStream {
                             #connections
                             (__builder.#bind_to(__parent) << #bind).write(__world);
                         };
+                    } else {
+                        return Error::new(
+                            bind.span(),
+                            format!("bind:{prop} param should be bound with to! or from! macro"),
+                        )
+                        .into_compile_error();
                     }
                     // panic!("bind def: {}", bind_def.to_token_stream());
                     // let signal_ident = syn::Ident::new(signal, connection.span());
@@ -455,58 +613,14 @@ fn walk_nodes<'a>(element: &'a Node, create_entity: bool) -> TokenStream {
                 }
             }
         }
-        for child in element.children.iter() {
-            match child {
-                Node::Element(element) => {
-                    let element_name = element.name.to_string();
-                    let expr = match element_name.as_str() {
-                        "for" => process_for_loop(element),
-                        "slot" => process_slots(element),
-                        _ => {
-                            let expr = walk_nodes(child, true);
-                            quote! {
-                                __ctx.children.push( #expr );
-                            }
-                        }
-                    };
-                    children = quote! {
-                        #children
-                        #expr
-                    }
-                }
-                Node::Text(text) => {
-                    let text = text.value.as_ref();
-                    children = quote! {
-                        #children
-                        __ctx.children.push(
-                            __world.spawn(::bevy::prelude::TextBundle {
-                                text: ::bevy::prelude::Text::from_section(
-                                    #text,
-                                    ::std::default::Default::default()
-                                ),
-                                ..default()
-                            })
-                            .insert(#core::Element::inline())
-                            .id()
-                        );
-                    };
-                }
-                Node::Block(block) => {
-                    let block = block.value.as_ref();
-                    let block_span = block.span();
-                    children = quote_spanned! { block_span=>
-                        #children
-                        let __node = __world.spawn_empty().id();
-                        for __child in #block.into_content(__node, __world).iter() {
-                            __ctx.children.push( __child.clone() );
-                        }
-                    }
-                }
-                _ => (),
-            };
-        }
+        let content = walk_children(&element.children);
+        children = quote! {
+            #children
+            #content
+        };
 
-        let tag = syn::Ident::new(&element.name.to_string(), element.span());
+        let tag = element.name.to_string().replace(' ', "").replace('-', "_");
+        let tag = widget_ident(&tag, element.span());
         quote! {
             {
                 #parent
@@ -524,39 +638,97 @@ fn walk_nodes<'a>(element: &'a Node, create_entity: bool) -> TokenStream {
     }
 }
 
+/// Builds the tree of elements from the markup. Tag names resolve to the
+/// registered widgets, `on:` params to their signals and `bind:` params
+/// (and the params bound both ways with `bind!`) to their bindings, so
+/// the typos are reported at compile time:
+/// ```rust,ignore
+/// commands.add(eml! {
+///     <body s:padding="50px">
+///         <div c:menu>
+///             <button on:press=connect!(|ctx| ctx.source().despawn_recursive())>"Hide"</button>
+///         </div>
+///     </body>
+/// });
+/// ```
+/// The single root element is built over the entity the elements are
+/// added to. The several roots (or the `<fragment>` root) are built as
+/// the siblings: they are spliced into the content when the elements are
+/// used as the children and become the children of the entity otherwise.
 #[proc_macro]
 pub fn eml(tree: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let core = core_path();
-    match parse(tree.into()) {
-        Err(err) => err.to_compile_error().into(),
-        Ok(nodes) => {
-            let body = walk_nodes(&nodes[0], false);
-            // nodes[0]
-            let wraped = quote! {
+    let nodes = match parse(tree.into()) {
+        Err(err) => return err.to_compile_error().into(),
+        Ok(nodes) => nodes,
+    };
+    let roots = match nodes.as_slice() {
+        [] => return err(Span::call_site(), "eml! expects the root element"),
+        [Node::Element(root)] if root.name.to_string() == "fragment" => {
+            if !root.attributes.is_empty() {
+                return err(root.span(), "<fragment> tag takes no attributes");
+            }
+            &root.children
+        }
+        [root @ Node::Element(_)] => {
+            let body = walk_nodes(root, false);
+            let slots = process_unused_slots(quote! { #body; });
+            return proc_macro::TokenStream::from(quote! {
                 #core::ElementsBuilder::new(
                     move |
                         __world: &mut ::bevy::prelude::World,
                         __parent: ::bevy::prelude::Entity
                     | {
-                        let mut __slots_resource = __world.resource::<#core::eml::build::Slots>().clone();
-                        let __defined_slots = __slots_resource.keys();
-                        #body;
-                        for __slot in __slots_resource.keys() {
-                            if !__defined_slots.contains(&__slot) {
-                                warn!("Detected unused slot '{}', despawning it contnent.", __slot);
-                                use ::bevy::ecs::system::Command;
-                                for __entity in __slots_resource.remove(__slot).unwrap() {
-                                    let __despawn =  ::bevy::prelude::DespawnRecursive {
-                                        entity: __entity
-                                    };
-                                    __despawn.write(__world);
-                                }
-                            }
-                        }
+                        #slots
                     }
-            )};
-            proc_macro::TokenStream::from(wraped)
+                )
+            });
         }
+        nodes => nodes,
+    };
+    let body = walk_children(roots);
+    let slots = process_unused_slots(body);
+    proc_macro::TokenStream::from(quote! {
+        #core::ElementsBuilder::fragment(move |__world: &mut ::bevy::prelude::World| {
+            struct __Fragment {
+                children: Vec<::bevy::prelude::Entity>,
+            }
+            let mut __ctx = __Fragment { children: vec![] };
+            #slots
+            __ctx.children
+        })
+    })
+}
+
+/// Despawns the content of the slots left unused by the `body`.
+fn process_unused_slots(body: TokenStream) -> TokenStream {
+    let core = core_path();
+    quote! {
+        let mut __slots_resource = __world.resource::<#core::eml::build::Slots>().clone();
+        let __defined_slots = __slots_resource.keys();
+        #body
+        for __slot in __slots_resource.keys() {
+            if !__defined_slots.contains(&__slot) {
+                warn!("Detected unused slot '{}', despawning it contnent.", __slot);
+                use ::bevy::ecs::system::Command;
+                for __entity in __slots_resource.remove(__slot).unwrap() {
+                    let __despawn =  ::bevy::prelude::DespawnRecursive {
+                        entity: __entity
+                    };
+                    __despawn.write(__world);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the identifier of the widget function, keywords (`<if>`)
+/// are turned into the raw identifiers.
+fn widget_ident(name: &str, span: Span) -> syn::Ident {
+    if syn::parse_str::<syn::Ident>(name).is_ok() {
+        syn::Ident::new(name, span)
+    } else {
+        syn::Ident::new_raw(name, span)
     }
 }
 
@@ -651,11 +823,12 @@ pub fn widget_macro_derive(input: proc_macro::TokenStream) -> proc_macro::TokenS
 
     for attr in ast.attrs.iter() {
         if attr.path.is_ident("alias") {
-            let Ok(alias) = attr.parse_args::<syn::Ident>() else {
+            let Ok(alias) = attr.parse_args_with(syn::Ident::parse_any) else {
                 return err(attr.span(), "Alias should be defined using tokens: `#[alias(alias_name)]");
             };
             let alias_str = format!("{alias}");
             names_expr = quote! { #names_expr, #alias_str };
+            let alias = widget_ident(&alias_str, alias.span());
             extension_body = quote! {
                 #extension_body
                 #docs
@@ -666,7 +839,7 @@ pub fn widget_macro_derive(input: proc_macro::TokenStream) -> proc_macro::TokenS
         }
     }
 
-    let connect_signals = match parse_signals(&ast.attrs) {
+    let (connect_signals, connect_handlers) = match parse_signals(&ast.attrs) {
         Ok(tokens) => tokens,
         Err(e) => return proc_macro::TokenStream::from(e.to_compile_error()),
     };
@@ -680,6 +853,10 @@ pub fn widget_macro_derive(input: proc_macro::TokenStream) -> proc_macro::TokenS
         Ok(tokens) => tokens,
         Err(e) => return proc_macro::TokenStream::from(e.to_compile_error()),
     };
+    let params_schema = match prepare_params_schema(&ast) {
+        Ok(tokens) => tokens,
+        Err(e) => return proc_macro::TokenStream::from(e.to_compile_error()),
+    };
     let aliases_decl = match prepare_extends_aliases(&ast.attrs) {
         Ok(tokens) => tokens,
         Err(e) => return proc_macro::TokenStream::from(e.to_compile_error()),
@@ -717,6 +894,10 @@ pub fn widget_macro_derive(input: proc_macro::TokenStream) -> proc_macro::TokenS
 
             #aliases_decl
 
+            #params_schema
+
+            #connect_handlers
+
             fn construct_component(world: &mut ::bevy::prelude::World, params: &mut #core::eml::Params) -> ::std::option::Option<Self> {
                 ::std::option::Option::Some(#component {
                     #construct_body
@@ -751,6 +932,50 @@ fn capitalize(s: &str) -> String {
     }
 }
 
+/// The schema of the `#[param]` fields. The fields are optional
+/// params, the default value of the field is used when the param
+/// is not set.
+fn prepare_params_schema(ast: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let core = core_path();
+    let syn::Data::Struct(data) = &ast.data else {
+        return Err(syn::Error::new(
+            ast.span(),
+            "Widget could be derived only for structs",
+        ));
+    };
+    if !ast.generics.params.is_empty() {
+        // the schema is the constant, it can't refer the generic params
+        return Ok(quote! {});
+    }
+    let mut schema = quote! {};
+    for field in data.fields.iter() {
+        for param in Param::from_field(field)? {
+            let Param::Direct(ident) = param else {
+                continue;
+            };
+            let name = format!("{ident}");
+            let ty = &field.ty;
+            let type_name = quote!(#ty).to_string().replace(' ', "");
+            schema = quote! {
+                #schema
+                #core::eml::build::ParamSchema {
+                    name: #name,
+                    type_name: #type_name,
+                    default: ::std::option::Option::None,
+                    required: false,
+                    validate: <#ty as #core::eml::build::FromWorldAndParam>::validate_param,
+                },
+            };
+        }
+    }
+    Ok(quote! {
+        fn params() -> &'static [#core::eml::build::ParamSchema] {
+            const PARAMS: &[#core::eml::build::ParamSchema] = &[#schema];
+            PARAMS
+        }
+    })
+}
+
 fn prepare_construct_instance(ast: &syn::DeriveInput) -> syn::Result<TokenStream> {
     let core = core_path();
     let mut construct_body = quote! {};
@@ -913,9 +1138,12 @@ fn parse_docs(attrs: &Vec<syn::Attribute>) -> (Vec<String>, TokenStream) {
     (doclines, docs)
 }
 
-fn parse_signals(attrs: &Vec<syn::Attribute>) -> syn::Result<TokenStream> {
+/// Returns the descriptor methods connecting the signals and the
+/// `Widget::connect_handler` connecting the handlers by the signal name.
+fn parse_signals(attrs: &Vec<syn::Attribute>) -> syn::Result<(TokenStream, TokenStream)> {
     let core = core_path();
     let mut connect_body = quote! {};
+    let mut handlers_body = quote! {};
     for attr in attrs.iter() {
         if attr.path.is_ident("signal") {
             let span = attr.span();
@@ -961,16 +1189,44 @@ fn parse_signals(attrs: &Vec<syn::Attribute>) -> syn::Result<TokenStream> {
                     world: &mut ::bevy::prelude::World,
                     source: ::bevy::prelude::Entity,
                     target: #core::ConnectionTo<C, #event>
-                ) {
+                ) -> #core::relations::ConnectionId {
                     target
                         .filter(|e| e.#filter())
                         .from(source)
                         .write(world)
                 }
-            }
+            };
+            let name = name.to_string();
+            handlers_body = quote! {
+                #handlers_body
+                #name => {
+                    let target = #core::ConnectionTo::<_, #event>::general(move |ctx| {
+                        let source = ctx.source().id();
+                        let handler = handler.clone();
+                        ctx.add(move |world: &mut ::bevy::prelude::World| {
+                            handler(world, source)
+                        });
+                    });
+                    Some(target.filter(|e| e.#filter()).from(source).write(world))
+                }
+            };
         }
     }
-    Ok(connect_body)
+    let handlers_body = quote! {
+        #[allow(unused_variables)]
+        fn connect_handler(
+            world: &mut ::bevy::prelude::World,
+            source: ::bevy::prelude::Entity,
+            signal: &str,
+            handler: #core::eml::handlers::EventHandler,
+        ) -> Option<#core::relations::ConnectionId> {
+            match signal {
+                #handlers_body
+                _ => None,
+            }
+        }
+    };
+    Ok((connect_body, handlers_body))
 }
 
 fn prepare_extends_descriptor(
@@ -1040,7 +1296,7 @@ fn prepare_extends_aliases(attrs: &Vec<syn::Attribute>) -> syn::Result<TokenStre
 
 fn parse_styles(ident: &syn::Ident, attrs: &Vec<syn::Attribute>) -> syn::Result<TokenStream> {
     let mut styles = "".to_string();
-    let element = ident.to_string();
+    let element = ident.to_string().replace('_', "-");
     for attr in attrs.iter().filter(|a| a.path.is_ident("style")) {
         let span = attr.span();
         let Ok(meta) = attr.parse_meta() else {
@@ -1108,6 +1364,178 @@ fn parse_styles(ident: &syn::Ident, attrs: &Vec<syn::Attribute>) -> syn::Result<
     }
 }
 
+/// Returns the inner type of the `Option<T>`.
+fn option_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(syn::GenericArgument::Type(ty)) => Some(ty),
+        _ => None,
+    }
+}
+
+/// Parses the `#[param(default = "value")]` attribute of the argument.
+fn param_default(attrs: &[syn::Attribute]) -> syn::Result<Option<syn::LitStr>> {
+    let Some(attr) = attrs.iter().find(|a| a.path.is_ident("param")) else {
+        return Ok(None);
+    };
+    let error = || {
+        syn::Error::new(
+            attr.span(),
+            "Invalid syntax of #[param(default = \"value\")] attribute.",
+        )
+    };
+    let Ok(syn::Meta::List(list)) = attr.parse_meta() else {
+        return Err(error());
+    };
+    match list.nested.first() {
+        Some(syn::NestedMeta::Meta(syn::Meta::NameValue(value)))
+            if value.path.is_ident("default") && list.nested.len() == 1 =>
+        {
+            match &value.lit {
+                syn::Lit::Str(default) => Ok(Some(default.clone())),
+                _ => Err(error()),
+            }
+        }
+        _ => Err(error()),
+    }
+}
+
+/// The params of the `#[widget]` function: the context argument,
+/// the statements extracting the params, the docs and the schema.
+struct WidgetParams {
+    ctx_arg: TokenStream,
+    extract: TokenStream,
+    docs: TokenStream,
+    schema: TokenStream,
+}
+
+/// Extracts the params of the `#[widget]` function: every argument
+/// after the context is the param of the same name. `Option<T>`
+/// arguments and arguments with `#[param(default = "value")]` are
+/// optional, the rest of them are required.
+fn prepare_widget_params(
+    tag: &str,
+    inputs: &syn::punctuated::Punctuated<syn::FnArg, syn::Token![,]>,
+) -> syn::Result<WidgetParams> {
+    let core = core_path();
+    let mut args = inputs.iter();
+    let Some(syn::FnArg::Typed(ctx_arg)) = args.next() else {
+        return Err(syn::Error::new(
+            inputs.span(),
+            "#[widget] function should take the `&mut ElementContext` as the first argument",
+        ));
+    };
+    let syn::Pat::Ident(ctx) = ctx_arg.pat.as_ref() else {
+        return Err(syn::Error::new(
+            ctx_arg.span(),
+            "Expected the identifier of the context",
+        ));
+    };
+    let ctx = &ctx.ident;
+    let mut extract = quote! {};
+    let mut docs = quote! {};
+    let mut schema = quote! {};
+    for arg in args {
+        let syn::FnArg::Typed(arg) = arg else {
+            return Err(syn::Error::new(arg.span(), "Unexpected argument"));
+        };
+        let syn::Pat::Ident(ident) = arg.pat.as_ref() else {
+            return Err(syn::Error::new(
+                arg.span(),
+                "Params should be plain identifiers",
+            ));
+        };
+        let ident = &ident.ident;
+        let name = ident.unraw().to_string();
+        let ty = &arg.ty;
+        let default = param_default(&arg.attrs)?;
+        let (value_ty, required, doc) = match (option_type(ty), &default) {
+            (Some(_), Some(default)) => {
+                return Err(syn::Error::new(
+                    default.span(),
+                    "Optional params can't have the default value",
+                ));
+            }
+            (Some(inner), None) => {
+                extract = quote! {
+                    #extract
+                    let #ident: #ty = #ctx.try_param::<#inner>(#name);
+                };
+                (inner, false, "optional".to_string())
+            }
+            (None, Some(default)) => {
+                extract = quote! {
+                    #extract
+                    let ::std::option::Option::Some(#ident) = #ctx
+                        .try_param::<#ty>(#name)
+                        .or_else(|| #core::Variant::String(#default.to_string()).try_get())
+                    else {
+                        ::bevy::log::error!("Invalid default value of '{}' param", #name);
+                        return;
+                    };
+                };
+                (
+                    ty.as_ref(),
+                    false,
+                    format!("`{}` by default", default.value()),
+                )
+            }
+            (None, None) => {
+                extract = quote! {
+                    #extract
+                    let ::std::option::Option::Some(#ident) = #ctx.try_param::<#ty>(#name) else {
+                        ::bevy::log::error!("<{}> requires '{}' param", #tag, #name);
+                        return;
+                    };
+                };
+                (ty.as_ref(), true, "required".to_string())
+            }
+        };
+        let type_name = quote!(#value_ty).to_string().replace(' ', "");
+        let doc = format!(" - `{name}`: `{type_name}`, {doc}");
+        docs = quote! {
+            #docs
+            #[doc = #doc]
+        };
+        let default = match default {
+            Some(default) => quote! { ::std::option::Option::Some(#default) },
+            None => quote! { ::std::option::Option::None },
+        };
+        schema = quote! {
+            #schema
+            #core::eml::build::ParamSchema {
+                name: #name,
+                type_name: #type_name,
+                default: #default,
+                required: #required,
+                validate: #core::eml::build::validate_param::<#value_ty>,
+            },
+        };
+    }
+    if !docs.is_empty() {
+        docs = quote! {
+            #[doc = ""]
+            #[doc = " Params:"]
+            #docs
+        };
+    }
+    Ok(WidgetParams {
+        ctx_arg: quote! { #ctx_arg },
+        extract,
+        docs,
+        schema,
+    })
+}
+
 #[proc_macro_attribute]
 pub fn widget(
     _args: proc_macro::TokenStream,
@@ -1116,14 +1544,30 @@ pub fn widget(
     let ast = parse_macro_input!(input as syn::ItemFn);
     let core = core_path();
     let fn_ident = ast.sig.ident;
-    let fn_args = ast.sig.inputs;
     let fn_body = ast.block;
     let alias = fn_ident.to_string();
+    // `fn my_widget` is available as the `<my-widget>` tag
+    let tag = alias.replace('_', "-");
+    let names = if tag == alias {
+        quote! { #alias }
+    } else {
+        quote! { #tag, #alias }
+    };
     let mod_descriptor = format_ident!("{}_widget_descriptor", &alias);
     let extension = format_ident!("{}WidgetExtension", capitalize(&alias));
     let (_doclines, docs) = parse_docs(&ast.attrs);
-
-    let connect_signals = match parse_signals(&ast.attrs) {
+    let params = match prepare_widget_params(&tag, &ast.sig.inputs) {
+        Ok(params) => params,
+        Err(e) => return proc_macro::TokenStream::from(e.to_compile_error()),
+    };
+    let WidgetParams {
+        ctx_arg,
+        extract: extract_params,
+        docs: params_docs,
+        schema: params_schema,
+    } = params;
+
+    let (connect_signals, connect_handlers) = match parse_signals(&ast.attrs) {
         Ok(tokens) => tokens,
         Err(e) => return proc_macro::TokenStream::from(e.to_compile_error()),
     };
@@ -1164,14 +1608,20 @@ pub fn widget(
 
         impl #core::Widget for #fn_ident {
             fn names() -> &'static [&'static str] {
-                &[#alias]
+                &[#names]
+            }
+            fn params() -> &'static [#core::eml::build::ParamSchema] {
+                const PARAMS: &[#core::eml::build::ParamSchema] = &[#params_schema];
+                PARAMS
             }
+            #connect_handlers
             #aliases_decl
         }
 
         impl #core::WidgetBuilder for #fn_ident {
             #styles_decl
-            fn construct(#fn_args) {
+            fn construct(#ctx_arg) {
+                #extract_params
                 #fn_body
             }
         }
@@ -1179,6 +1629,7 @@ pub fn widget(
         pub trait #extension {
             type Descriptor;
             #docs
+            #params_docs
             fn #fn_ident() -> Descriptor {
                 Descriptor
             }
@@ -0,0 +1,466 @@
+//! Lossless `eml` syntax tree.
+//!
+//! `eml` is parsed as the `xml` fragment: the source may contain any number
+//! of top-level nodes, the `s:` prefix is predefined for style attributes.
+//! The tree keeps the tag & attribute names as written, the validation of
+//! tags, params and style values is up to the consumer.
+//!
+//! `xml` has no error recovery, so the malformed source is repaired and
+//! parsed again: the malformed tag is replaced with the
+//! [`EmlNodeSyntax::Error`] placeholder, the unclosed elements are closed
+//! by the closing tag of the parent and the unexpected closing tags are
+//! dropped. Every repaired problem is reported as the [`Diagnostic`]. The
+//! tree is empty if the source can't be repaired.
+use crate::{Diagnostic, LineIndex, Position, Span, Spanned};
+
+/// The namespace (and prefix) of the style attributes.
+pub const NS_STYLE: &str = "s";
+const NS_SKIP: &str = "skip";
+/// The number of problems repaired before giving up.
+const MAX_REPAIRS: usize = 32;
+
+/// The result of parsing the `eml` source.
+#[derive(Debug)]
+pub struct EmlSyntax {
+    pub nodes: Vec<EmlNodeSyntax>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug)]
+pub enum EmlNodeSyntax {
+    Element(EmlElementSyntax),
+    /// Raw text between the tags with entities resolved.
+    Text(Spanned<String>),
+    /// The placeholder of the malformed element with the message
+    /// of the problem.
+    Error(Spanned<String>),
+}
+
+impl EmlNodeSyntax {
+    pub fn span(&self) -> Span {
+        match self {
+            EmlNodeSyntax::Element(element) => element.span,
+            EmlNodeSyntax::Text(text) => text.span,
+            EmlNodeSyntax::Error(error) => error.span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EmlElementSyntax {
+    pub span: Span,
+    /// The tag name as written, `<s:div>` has the `s:div` name.
+    pub name: Spanned<String>,
+    pub attributes: Vec<EmlAttributeSyntax>,
+    pub children: Vec<EmlNodeSyntax>,
+}
+
+impl EmlElementSyntax {
+    /// Returns the unprefixed tag name.
+    pub fn local_name(&self) -> &str {
+        local_name(&self.name.value)
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&EmlAttributeSyntax> {
+        self.attributes.iter().find(|a| a.name.value == name)
+    }
+}
+
+#[derive(Debug)]
+pub struct EmlAttributeSyntax {
+    pub span: Span,
+    /// The attribute name as written, `s:width="1px"` has the `s:width` name.
+    pub name: Spanned<String>,
+    /// The namespace the attribute prefix is bound to.
+    pub namespace: Option<String>,
+    /// Unescaped value, the span covers the raw value between quotes.
+    pub value: Spanned<String>,
+}
+
+impl EmlAttributeSyntax {
+    /// Returns the unprefixed attribute name.
+    pub fn local_name(&self) -> &str {
+        local_name(&self.name.value)
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit_once(':').map(|(_, name)| name).unwrap_or(name)
+}
+
+/// Checks if the content of the element with the `name` and the start
+/// `tag` is the raw text: the `<pre>` element or the element with the
+/// `raw="true"` attribute.
+fn is_raw(name: &str, tag: &str) -> bool {
+    local_name(name) == "pre" || tag.contains("raw=\"true\"") || tag.contains("raw='true'")
+}
+
+/// Parses the `eml` source.
+pub fn parse(source: &str) -> EmlSyntax {
+    let prefix =
+        format!("<{NS_SKIP}:root xmlns:{NS_SKIP}=\"{NS_SKIP}\" xmlns:{NS_STYLE}=\"{NS_STYLE}\">\n");
+    let mut ctx = Context {
+        data: format!("{prefix}{source}\n</{NS_SKIP}:root>"),
+        offset: prefix.len(),
+        len: source.len(),
+        edits: vec![],
+        errors: vec![],
+    };
+    ctx.escape_raw();
+    let mut diagnostics = vec![];
+    let mut repairs = 0;
+    loop {
+        let err = match roxmltree::Document::parse(&ctx.data) {
+            Ok(doc) => {
+                let nodes = doc
+                    .root_element()
+                    .children()
+                    .filter_map(|node| ctx.node(node))
+                    .collect();
+                return EmlSyntax { nodes, diagnostics };
+            }
+            Err(err) => err,
+        };
+        let pos = err.pos();
+        let offset = LineIndex::new(&ctx.data).char_offset(
+            &ctx.data,
+            Position {
+                line: pos.row,
+                column: pos.col,
+            },
+        );
+        let start = ctx.offset(offset);
+        let end = source[start..]
+            .find('\n')
+            .map(|len| start + len)
+            .unwrap_or(source.len());
+        let message = format!("{err}").replace(&format!(" at {pos}"), "");
+        // the closing tag of the replaced element is not a problem
+        let cascaded = match &err {
+            roxmltree::Error::UnexpectedCloseTag { expected, .. } => {
+                *expected == format!("{NS_SKIP}:error")
+            }
+            _ => false,
+        };
+        if !cascaded {
+            diagnostics.push(Diagnostic::new(message.clone(), Span::new(start, end)));
+        }
+        repairs += 1;
+        if repairs > MAX_REPAIRS || !ctx.repair(&err, offset, message) {
+            return EmlSyntax {
+                nodes: vec![],
+                diagnostics,
+            };
+        }
+    }
+}
+
+/// The replacement of the `removed` bytes at the offset `at` of the data
+/// with the `inserted` bytes.
+struct Edit {
+    at: usize,
+    removed: usize,
+    inserted: usize,
+}
+
+struct Context {
+    data: String,
+    offset: usize,
+    len: usize,
+    edits: Vec<Edit>,
+    /// Messages of the problems replaced with the placeholders.
+    errors: Vec<String>,
+}
+
+impl Context {
+    fn replace(&mut self, start: usize, end: usize, text: &str) {
+        self.data.replace_range(start..end, text);
+        self.edits.push(Edit {
+            at: start,
+            removed: end - start,
+            inserted: text.len(),
+        });
+    }
+
+    /// Wraps the content of the raw elements into the `CDATA` sections, so
+    /// the markup-looking characters of the content are kept as the text.
+    fn escape_raw(&mut self) {
+        let mut pos = self.offset;
+        while let Some(len) = self.data[pos..].find('<') {
+            let start = pos + len;
+            pos = start + 1;
+            let Some(len) = self.data[start..].find('>') else {
+                break;
+            };
+            let end = start + len + 1;
+            let tag = &self.data[start + 1..end - 1];
+            if tag.starts_with(['/', '!', '?']) || tag.ends_with('/') {
+                continue;
+            }
+            let name = tag.split(char::is_whitespace).next().unwrap_or_default();
+            if !is_raw(name, tag) {
+                continue;
+            }
+            let close = format!("</{name}");
+            let Some(len) = self.data[end..].find(&close) else {
+                continue;
+            };
+            let mut content_end = end + len;
+            self.replace(end, end, "<![CDATA[");
+            content_end += "<![CDATA[".len();
+            // `]]>` would close the section
+            let mut at = end;
+            while let Some(len) = self.data[at..content_end].find("]]>") {
+                let split = at + len + 2;
+                self.replace(split, split, "]]><![CDATA[");
+                at = split + "]]><![CDATA[".len();
+                content_end += "]]><![CDATA[".len();
+            }
+            self.replace(content_end, content_end, "]]>");
+            pos = content_end + "]]>".len();
+        }
+    }
+
+    /// Repairs the problem at the `offset` of the data.
+    /// Returns `false` if the problem can't be repaired.
+    fn repair(&mut self, err: &roxmltree::Error, offset: usize, message: String) -> bool {
+        let root = format!("{NS_SKIP}:root");
+        let placeholder = format!("{NS_SKIP}:error");
+        // the closing tag of the root
+        let source_end = self.data.len() - root.len() - 3;
+        let mut offset = offset.min(source_end);
+        while !self.data.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        if let roxmltree::Error::UnexpectedCloseTag { expected, .. } = err {
+            let Some(start) = self.data[..offset + 1].rfind("</") else {
+                return false;
+            };
+            if start < self.offset {
+                return false;
+            }
+            let end = self.data[start..]
+                .find('>')
+                .map(|len| start + len + 1)
+                .unwrap_or(self.data.len());
+            if *expected == placeholder {
+                // the closing tag of the replaced element
+                self.replace(start, end, &format!("</{placeholder}>"));
+            } else if *expected == root {
+                self.replace(start, end, "");
+            } else {
+                // the unclosed element is closed by the parent
+                self.replace(start, start, &format!("</{expected}>"));
+            }
+            return true;
+        }
+        if offset < self.offset || offset >= source_end {
+            return false;
+        }
+        match self.data[..offset + 1].rfind('<') {
+            Some(start) if start >= self.offset && !self.data[start..offset].contains('>') => {
+                // the problem is inside of the tag
+                let end = match self.data[start + 1..source_end].find(['>', '<']) {
+                    Some(len) if self.data[start + 1 + len..].starts_with('>') => start + len + 2,
+                    Some(len) => start + len + 1,
+                    None => source_end,
+                };
+                let tag = &self.data[start..end];
+                let index = self.errors.len();
+                let replacement = if tag.starts_with("</") {
+                    String::new()
+                } else if tag.ends_with("/>") || !tag.ends_with('>') {
+                    format!("<{placeholder} {NS_SKIP}:index=\"{index}\"/>")
+                } else {
+                    format!("<{placeholder} {NS_SKIP}:index=\"{index}\">")
+                };
+                self.errors.push(message);
+                self.replace(start, end, &replacement);
+            }
+            _ => {
+                // the problem is inside of the text
+                let char = self.data[offset..].chars().next();
+                let replacement = match char {
+                    Some('&') => "&amp;",
+                    Some('<') => "&lt;",
+                    _ => "",
+                };
+                let len = char.map_or(0, char::len_utf8);
+                self.replace(offset, offset + len, replacement);
+            }
+        }
+        true
+    }
+
+    /// Converts the offset of the data into the offset of the source.
+    fn offset(&self, offset: usize) -> usize {
+        let mut offset = offset;
+        for edit in self.edits.iter().rev() {
+            if offset >= edit.at + edit.inserted {
+                offset = offset - edit.inserted + edit.removed;
+            } else if offset > edit.at {
+                offset = edit.at;
+            }
+        }
+        offset.saturating_sub(self.offset).min(self.len)
+    }
+
+    fn span(&self, start: usize, end: usize) -> Span {
+        Span::new(self.offset(start), self.offset(end))
+    }
+
+    fn node(&self, node: roxmltree::Node) -> Option<EmlNodeSyntax> {
+        let range = node.range();
+        if node.is_text() {
+            let text = node.text().unwrap_or_default().to_string();
+            Some(EmlNodeSyntax::Text(Spanned::new(
+                text,
+                self.span(range.start, range.end),
+            )))
+        } else if node.is_element() && node.tag_name().namespace() == Some(NS_SKIP) {
+            let index: usize = node.attribute((NS_SKIP, "index"))?.parse().ok()?;
+            Some(EmlNodeSyntax::Error(Spanned::new(
+                self.errors.get(index)?.clone(),
+                self.span(range.start, range.end),
+            )))
+        } else if node.is_element() {
+            // skip `<`
+            let name_start = range.start + 1;
+            let name_end = self.data[name_start..]
+                .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .map(|len| name_start + len)
+                .unwrap_or(range.end);
+            Some(EmlNodeSyntax::Element(EmlElementSyntax {
+                span: self.span(range.start, range.end),
+                name: Spanned::new(
+                    self.data[name_start..name_end].to_string(),
+                    self.span(name_start, name_end),
+                ),
+                attributes: node.attributes().map(|a| self.attribute(a)).collect(),
+                children: node.children().filter_map(|ch| self.node(ch)).collect(),
+            }))
+        } else {
+            None
+        }
+    }
+
+    fn attribute(&self, attr: roxmltree::Attribute) -> EmlAttributeSyntax {
+        let start = attr.position();
+        let rest = &self.data[start..];
+        let eq = rest.find('=').unwrap_or(0);
+        let name_end = start + rest[..eq].trim_end().len();
+        let after_eq = &rest[eq + 1..];
+        let quote_at = after_eq.len() - after_eq.trim_start().len();
+        let quote = after_eq[quote_at..].chars().next().unwrap_or('"');
+        let value_start = start + eq + 1 + quote_at + 1;
+        let value_end = self.data[value_start..]
+            .find(quote)
+            .map(|len| value_start + len)
+            .unwrap_or(value_start);
+        EmlAttributeSyntax {
+            span: self.span(start, value_end + 1),
+            name: Spanned::new(
+                self.data[start..name_end].to_string(),
+                self.span(start, name_end),
+            ),
+            namespace: attr.namespace().map(|ns| ns.to_string()),
+            value: Spanned::new(attr.value().to_string(), self.span(value_start, value_end)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_with_spans() {
+        let source = "<div s:width=\"10px\">\n  <span>Hi</span>\n</div>";
+        let syntax = parse(source);
+        assert!(syntax.diagnostics.is_empty(), "{:?}", syntax.diagnostics);
+        assert_eq!(syntax.nodes.len(), 1);
+        let EmlNodeSyntax::Element(div) = &syntax.nodes[0] else { panic!("Expected element") };
+        assert_eq!(div.span.slice(source), source);
+        assert_eq!(div.name.span.slice(source), "div");
+        let width = div.attribute("s:width").expect("Should have s:width");
+        assert_eq!(width.local_name(), "width");
+        assert_eq!(width.namespace.as_deref(), Some(NS_STYLE));
+        assert_eq!(width.span.slice(source), "s:width=\"10px\"");
+        assert_eq!(width.value.span.slice(source), "10px");
+        let span = div
+            .children
+            .iter()
+            .find_map(|ch| match ch {
+                EmlNodeSyntax::Element(e) => Some(e),
+                _ => None,
+            })
+            .expect("Should have span child");
+        assert_eq!(span.span.slice(source), "<span>Hi</span>");
+    }
+
+    #[test]
+    fn report_errors() {
+        let source = "<div>\n  <span>\n</div>";
+        let syntax = parse(source);
+        assert_eq!(syntax.nodes.len(), 1, "Should close the span");
+        assert_eq!(syntax.diagnostics.len(), 1);
+        let start = syntax.diagnostics[0].span.start;
+        assert_eq!(LineIndex::new(source).position(start).line, 3);
+    }
+
+    #[test]
+    fn report_errors_after_non_ascii_text() {
+        let source = "<div>\n  <label value=\"Привет, 世界\"/>Текст 文字 <span =/>\n</div>";
+        let syntax = parse(source);
+        assert!(!syntax.diagnostics.is_empty());
+        let span = syntax.diagnostics[0].span;
+        let tag = source.find("<span").unwrap();
+        assert!(
+            (tag..tag + "<span =/>".len()).contains(&span.start),
+            "Should point at the malformed tag, not {:?}",
+            span.slice(source)
+        );
+    }
+
+    #[test]
+    fn keep_raw_content() {
+        let source = "<div>\n  <pre>\n  a < b && c ]]> d\n</pre>\n</div>";
+        let syntax = parse(source);
+        assert!(syntax.diagnostics.is_empty(), "{:?}", syntax.diagnostics);
+        let EmlNodeSyntax::Element(div) = &syntax.nodes[0] else {
+            panic!("Expected element")
+        };
+        let Some(EmlNodeSyntax::Element(pre)) = div.children.get(1) else {
+            panic!("Expected pre element")
+        };
+        assert_eq!(pre.span.slice(source), "<pre>\n  a < b && c ]]> d\n</pre>");
+        let [EmlNodeSyntax::Text(text)] = pre.children.as_slice() else {
+            panic!("Expected single text")
+        };
+        assert_eq!(text.value, "\n  a < b && c ]]> d\n");
+    }
+
+    #[test]
+    fn replace_malformed_tags() {
+        let source = "<div>\n  <span a=b>x</span>\n  <br/>\n</div>\n</p>";
+        let syntax = parse(source);
+        assert_eq!(syntax.diagnostics.len(), 2, "{:?}", syntax.diagnostics);
+        assert_eq!(syntax.nodes.len(), 1);
+        let EmlNodeSyntax::Element(div) = &syntax.nodes[0] else {
+            panic!("Expected element")
+        };
+        let mut children = div
+            .children
+            .iter()
+            .filter(|ch| !matches!(ch, EmlNodeSyntax::Text(_)));
+        let Some(EmlNodeSyntax::Error(error)) = children.next() else {
+            panic!("Expected error placeholder")
+        };
+        assert_eq!(error.span.slice(source), "<span a=b>x</span>");
+        assert_eq!(error.value, syntax.diagnostics[0].message);
+        let Some(EmlNodeSyntax::Element(br)) = children.next() else {
+            panic!("Expected element")
+        };
+        assert_eq!(br.span.slice(source), "<br/>");
+    }
+}
@@ -0,0 +1,500 @@
+//! Lossless `ess` syntax tree.
+//!
+//! The tree describes the structure of the stylesheet only: selectors and
+//! property values are kept as source slices and are not validated, so the
+//! tree could be built for any stylesheet, even for the one with unknown
+//! properties. Invalid rules and declarations are skipped up to the next
+//! `;` or `}` and reported as [`Diagnostic`]s.
+use std::cell::RefCell;
+
+use cssparser::*;
+
+use crate::{Diagnostic, LineIndex, Position, Span, Spanned};
+
+/// The result of parsing the `ess` source.
+#[derive(Debug)]
+pub struct EssSyntax<'a> {
+    pub source: &'a str,
+    pub items: Vec<EssItem<'a>>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug)]
+pub enum EssItem<'a> {
+    Rule(EssRule<'a>),
+    AtRule(EssAtRule<'a>),
+}
+
+impl<'a> EssItem<'a> {
+    pub fn span(&self) -> Span {
+        match self {
+            EssItem::Rule(rule) => rule.span,
+            EssItem::AtRule(rule) => rule.span,
+        }
+    }
+}
+
+/// Qualified rule: `selector { declarations }`
+#[derive(Debug)]
+pub struct EssRule<'a> {
+    pub span: Span,
+    pub selector: Spanned<&'a str>,
+    pub block: Span,
+    pub declarations: Vec<EssDeclaration<'a>>,
+}
+
+/// Single `name: value` pair.
+#[derive(Debug)]
+pub struct EssDeclaration<'a> {
+    pub span: Span,
+    pub name: Spanned<&'a str>,
+    pub value: Spanned<&'a str>,
+}
+
+/// At-rule: `@name prelude;` or `@name prelude { block }`. The content of
+/// the block depends on the at-rule and could be parsed with [`parse_items_in`]
+/// or [`parse_declarations_in`].
+#[derive(Debug)]
+pub struct EssAtRule<'a> {
+    pub span: Span,
+    pub name: Spanned<&'a str>,
+    pub prelude: Spanned<&'a str>,
+    pub block: Option<Span>,
+}
+
+/// Parses the whole `ess` source.
+pub fn parse(source: &str) -> EssSyntax {
+    let (items, diagnostics) = parse_items_in(source, Span::new(0, source.len()));
+    EssSyntax {
+        source,
+        items,
+        diagnostics,
+    }
+}
+
+/// Parses the list of rules inside the `span` of the `source`, like the
+/// block of the `@media` rule.
+pub fn parse_items_in(source: &str, span: Span) -> (Vec<EssItem>, Vec<Diagnostic>) {
+    let parser = EssParser::new(source, span);
+    let mut input = ParserInput::new(span.slice(source));
+    let mut input = Parser::new(&mut input);
+    let mut items = vec![];
+    for result in RuleListParser::new_for_stylesheet(&mut input, &parser) {
+        match result {
+            Ok(item) => items.push(item),
+            Err((err, _)) => parser.report(err),
+        }
+    }
+    (items, parser.diagnostics.into_inner())
+}
+
+/// Parses the list of declarations inside the `span` of the `source`, like
+/// the block of the `@font-face` rule.
+pub fn parse_declarations_in(source: &str, span: Span) -> (Vec<EssDeclaration>, Vec<Diagnostic>) {
+    let parser = EssParser::new(source, span);
+    let mut input = ParserInput::new(span.slice(source));
+    let mut input = Parser::new(&mut input);
+    let declarations = parser.parse_declarations(&mut input);
+    (declarations, parser.diagnostics.into_inner())
+}
+
+/// Sets the value of the `name` declaration of the first rule with the
+/// `selector` and returns the updated source. The existing declaration
+/// is replaced in place, the new one is appended to the end of the rule
+/// using the indentation of the last declaration. The rest of the source
+/// is kept untouched. Returns `None` if there is no rule with the `selector`.
+///
+/// Selectors are compared ignoring the whitespace differences. The rules
+/// nested into the `@media` rules are looked up when there is no top-level
+/// rule with the `selector`, use [`set_media_declaration`] to pick the
+/// `@media` rule.
+pub fn set_declaration(source: &str, selector: &str, name: &str, value: &str) -> Option<String> {
+    let syntax = parse(source);
+    if let Some(rule) = find_rule(&syntax.items, selector) {
+        return Some(write_declaration(source, rule, name, value));
+    }
+    let nested = media_rules(source, &syntax.items, |_| true);
+    let rule = find_rule(&nested, selector)?;
+    Some(write_declaration(source, rule, name, value))
+}
+
+/// Like [`set_declaration`], but sets the declaration of the rule nested
+/// into the `@media` rule with the prelude (the query) accepted by the `media`.
+pub fn set_media_declaration(
+    source: &str,
+    media: impl Fn(&str) -> bool,
+    selector: &str,
+    name: &str,
+    value: &str,
+) -> Option<String> {
+    let syntax = parse(source);
+    let nested = media_rules(source, &syntax.items, media);
+    let rule = find_rule(&nested, selector)?;
+    Some(write_declaration(source, rule, name, value))
+}
+
+/// Returns the rules nested into the `@media` rules of the `items` with
+/// the prelude accepted by the `media`.
+fn media_rules<'a>(
+    source: &'a str,
+    items: &[EssItem<'a>],
+    media: impl Fn(&str) -> bool,
+) -> Vec<EssItem<'a>> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            EssItem::AtRule(rule) if rule.name.value == "media" && media(rule.prelude.value) => {
+                rule.block
+            }
+            _ => None,
+        })
+        .flat_map(|block| parse_items_in(source, block).0)
+        .collect()
+}
+
+fn find_rule<'i, 'a>(items: &'i [EssItem<'a>], selector: &str) -> Option<&'i EssRule<'a>> {
+    let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ");
+    let selector = normalize(selector);
+    items.iter().find_map(|item| match item {
+        EssItem::Rule(rule) if normalize(rule.selector.value) == selector => Some(rule),
+        _ => None,
+    })
+}
+
+fn write_declaration(source: &str, rule: &EssRule, name: &str, value: &str) -> String {
+    let mut result = source.to_string();
+    if let Some(decl) = rule.declarations.iter().find(|d| d.name.value == name) {
+        result.replace_range(decl.value.span.start..decl.value.span.end, value);
+        return result;
+    }
+    let block_end = if source[..rule.block.end].ends_with('}') {
+        rule.block.end - 1
+    } else {
+        rule.block.end
+    };
+    let Some(last) = rule.declarations.last() else {
+        let insert = format!(" {name}: {value}; ");
+        let start = source[..block_end].trim_end().len();
+        result.replace_range(start..block_end, &insert);
+        return result;
+    };
+    let mut offset = last.span.end;
+    let tail = &source[offset..block_end];
+    let separator = match tail.trim_start().starts_with(';') {
+        true => {
+            offset += tail.find(';').unwrap() + 1;
+            ""
+        }
+        false => ";",
+    };
+    let line_start = source[..last.span.start].rfind('\n').map(|i| i + 1);
+    let insert = match line_start {
+        // the rule spans several lines, keep the declaration on its own line
+        Some(line_start) if line_start > rule.selector.span.end => {
+            let indent = &source[line_start..last.span.start];
+            format!("{separator}\n{indent}{name}: {value};")
+        }
+        _ => format!("{separator} {name}: {value};"),
+    };
+    result.insert_str(offset, &insert);
+    result
+}
+
+struct EssParser<'a> {
+    source: &'a str,
+    offset: usize,
+    lines: LineIndex,
+    diagnostics: RefCell<Vec<Diagnostic>>,
+}
+
+impl<'a> EssParser<'a> {
+    fn new(source: &'a str, span: Span) -> EssParser<'a> {
+        EssParser {
+            source,
+            offset: span.start,
+            lines: LineIndex::new(span.slice(source)),
+            diagnostics: Default::default(),
+        }
+    }
+
+    fn offset(&self, position: SourcePosition) -> usize {
+        self.offset + position.byte_index()
+    }
+
+    fn location_offset(&self, location: SourceLocation) -> usize {
+        let source = &self.source[self.offset..];
+        self.offset
+            + self.lines.utf16_offset(
+                source,
+                Position {
+                    line: location.line + 1,
+                    column: location.column,
+                },
+            )
+    }
+
+    fn spanned(&self, start: usize, end: usize) -> Spanned<&'a str> {
+        let text = &self.source[start..end];
+        let trimmed = text.trim_start();
+        let start = start + text.len() - trimmed.len();
+        let end = start + trimmed.trim_end().len();
+        Spanned::new(&self.source[start..end], Span::new(start, end))
+    }
+
+    fn block_end(&self, end: usize) -> usize {
+        if self.source[end..].starts_with('}') {
+            end + 1
+        } else {
+            end
+        }
+    }
+
+    fn report<E: std::fmt::Debug>(&self, error: ParseError<E>) {
+        let start = self.location_offset(error.location).min(self.source.len());
+        let message = match error.kind {
+            ParseErrorKind::Basic(BasicParseErrorKind::UnexpectedToken(token)) => {
+                format!("Unexpected token {}", token.to_css_string())
+            }
+            ParseErrorKind::Basic(BasicParseErrorKind::EndOfInput) => {
+                "Unexpected end of input".to_string()
+            }
+            ParseErrorKind::Basic(BasicParseErrorKind::AtRuleInvalid(name)) => {
+                format!("Invalid at-rule @{name}")
+            }
+            ParseErrorKind::Basic(BasicParseErrorKind::AtRuleBodyInvalid) => {
+                "Invalid at-rule body".to_string()
+            }
+            ParseErrorKind::Basic(BasicParseErrorKind::QualifiedRuleInvalid) => {
+                "Invalid rule".to_string()
+            }
+            ParseErrorKind::Custom(custom) => format!("{custom:?}"),
+        };
+        let end = self.source[start..]
+            .find(|c: char| c == ';' || c == '}' || c == '\n')
+            .map(|len| start + len)
+            .unwrap_or(self.source.len());
+        self.diagnostics
+            .borrow_mut()
+            .push(Diagnostic::new(message, Span::new(start, end)));
+    }
+
+    fn consume_all<'i, 't>(&self, input: &mut Parser<'i, 't>) -> Spanned<&'a str> {
+        let start = self.offset(input.position());
+        while input.next_including_whitespace_and_comments().is_ok() {}
+        let end = self.offset(input.position());
+        self.spanned(start, end)
+    }
+
+    fn parse_declarations<'i, 't>(&self, input: &mut Parser<'i, 't>) -> Vec<EssDeclaration<'a>> {
+        let mut declarations = vec![];
+        let mut list = DeclarationListParser::new(input, DeclarationsParser(self));
+        loop {
+            list.input.skip_whitespace();
+            let start = self.offset(list.input.position());
+            let Some(result) = list.next() else { break };
+            match result {
+                Ok((name, value)) => {
+                    let name_end = (start + name.len()).min(self.source.len());
+                    declarations.push(EssDeclaration {
+                        span: Span::new(start, value.span.end),
+                        name: Spanned::new(
+                            &self.source[start..name_end],
+                            Span::new(start, name_end),
+                        ),
+                        value,
+                    })
+                }
+                Err((err, _)) => self.report(err),
+            }
+        }
+        declarations
+    }
+}
+
+impl<'i, 'a> QualifiedRuleParser<'i> for &EssParser<'a> {
+    type Prelude = Spanned<&'a str>;
+    type QualifiedRule = EssItem<'a>;
+    type Error = ();
+
+    fn parse_prelude<'t>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
+        Ok(self.consume_all(input))
+    }
+
+    fn parse_block<'t>(
+        &mut self,
+        prelude: Self::Prelude,
+        start: &ParserState,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
+        let block_start = self.offset(input.position());
+        let declarations = self.parse_declarations(input);
+        let block_end = self.block_end(self.offset(input.position()));
+        Ok(EssItem::Rule(EssRule {
+            span: Span::new(self.offset(start.position()), block_end),
+            selector: prelude,
+            block: Span::new(block_start, block_end),
+            declarations,
+        }))
+    }
+}
+
+impl<'i, 'a> AtRuleParser<'i> for &EssParser<'a> {
+    type Prelude = (Spanned<&'a str>, Spanned<&'a str>);
+    type AtRule = EssItem<'a>;
+    type Error = ();
+
+    fn parse_prelude<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
+        // input is positioned right after the `@name` token
+        let name_end = self.offset(input.position());
+        let name_start = name_end.saturating_sub(name.len());
+        let name = Spanned::new(
+            &self.source[name_start..name_end],
+            Span::new(name_start, name_end),
+        );
+        Ok((name, self.consume_all(input)))
+    }
+
+    fn rule_without_block(
+        &mut self,
+        prelude: Self::Prelude,
+        start: &ParserState,
+    ) -> Result<Self::AtRule, ()> {
+        let (name, prelude) = prelude;
+        let start = self.offset(start.position());
+        let end = prelude.span.end.max(name.span.end);
+        let end = if self.source[end..].starts_with(';') {
+            end + 1
+        } else {
+            end
+        };
+        Ok(EssItem::AtRule(EssAtRule {
+            span: Span::new(start, end),
+            name,
+            prelude,
+            block: None,
+        }))
+    }
+
+    fn parse_block<'t>(
+        &mut self,
+        prelude: Self::Prelude,
+        start: &ParserState,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::AtRule, ParseError<'i, Self::Error>> {
+        let (name, prelude) = prelude;
+        let block_start = self.offset(input.position());
+        while input.next_including_whitespace_and_comments().is_ok() {}
+        let block_end = self.offset(input.position());
+        Ok(EssItem::AtRule(EssAtRule {
+            span: Span::new(self.offset(start.position()), self.block_end(block_end)),
+            name,
+            prelude,
+            block: Some(Span::new(block_start, block_end)),
+        }))
+    }
+}
+
+struct DeclarationsParser<'p, 'a>(&'p EssParser<'a>);
+
+impl<'i, 'p, 'a> DeclarationParser<'i> for DeclarationsParser<'p, 'a> {
+    type Declaration = (CowRcStr<'i>, Spanned<&'a str>);
+    type Error = ();
+
+    fn parse_value<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Declaration, ParseError<'i, Self::Error>> {
+        Ok((name, self.0.consume_all(input)))
+    }
+}
+
+impl<'i, 'p, 'a> AtRuleParser<'i> for DeclarationsParser<'p, 'a> {
+    type Prelude = ();
+    type AtRule = (CowRcStr<'i>, Spanned<&'a str>);
+    type Error = ();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rules_with_spans() {
+        let source = "a .b { c: d; e: 1px 2px }\n@import \"f.ess\";";
+        let syntax = parse(source);
+        assert!(syntax.diagnostics.is_empty(), "{:?}", syntax.diagnostics);
+        assert_eq!(syntax.items.len(), 2);
+        let EssItem::Rule(rule) = &syntax.items[0] else { panic!("Expected rule, got {:?}", syntax.items[0]) };
+        assert_eq!(rule.selector.value, "a .b");
+        assert_eq!(rule.span.slice(source), "a .b { c: d; e: 1px 2px }");
+        assert_eq!(rule.declarations.len(), 2);
+        assert_eq!(rule.declarations[1].name.value, "e");
+        assert_eq!(rule.declarations[1].value.value, "1px 2px");
+        assert_eq!(rule.declarations[1].value.span.slice(source), "1px 2px");
+        let EssItem::AtRule(import) = &syntax.items[1] else { panic!("Expected at-rule, got {:?}", syntax.items[1]) };
+        assert_eq!(import.name.value, "import");
+        assert_eq!(import.prelude.value, "\"f.ess\"");
+        assert_eq!(import.span.slice(source), "@import \"f.ess\";");
+    }
+
+    #[test]
+    fn recover_after_errors() {
+        let source = "a { b; c: d }\ne { f: g }";
+        let syntax = parse(source);
+        assert_eq!(syntax.items.len(), 2, "Should keep both rules");
+        assert_eq!(
+            syntax.diagnostics.len(),
+            1,
+            "Should report invalid declaration"
+        );
+        let EssItem::Rule(rule) = &syntax.items[0] else { panic!() };
+        assert_eq!(rule.declarations.len(), 1);
+        assert_eq!(rule.declarations[0].name.value, "c");
+    }
+
+    #[test]
+    fn set_declarations() {
+        let source = "a { b: c }\n.panel {\n    color: red;\n    width: 10px\n}\n";
+        assert_eq!(
+            set_declaration(source, "a", "b", "d").unwrap(),
+            "a { b: d }\n.panel {\n    color: red;\n    width: 10px\n}\n"
+        );
+        assert_eq!(
+            set_declaration(source, "a", "e", "f").unwrap(),
+            "a { b: c; e: f; }\n.panel {\n    color: red;\n    width: 10px\n}\n"
+        );
+        assert_eq!(
+            set_declaration(source, ".panel", "height", "5px").unwrap(),
+            "a { b: c }\n.panel {\n    color: red;\n    width: 10px;\n    height: 5px;\n}\n"
+        );
+        assert_eq!(
+            set_declaration("x  y {}", "x y", "z", "1").unwrap(),
+            "x  y { z: 1; }"
+        );
+        assert!(set_declaration(source, ".missing", "b", "c").is_none());
+    }
+
+    #[test]
+    fn set_media_declarations() {
+        let source = "a { b: c }\n@media (max-width: 800px) {\n    .panel { width: 10px }\n}\n@media (orientation: portrait) {\n    a { b: e }\n}\n";
+        assert_eq!(
+            set_declaration(source, ".panel", "width", "5px").unwrap(),
+            source.replace("10px", "5px"),
+            "Should find the rule nested into @media"
+        );
+        assert_eq!(
+            set_media_declaration(source, |q| q.contains("portrait"), "a", "b", "f").unwrap(),
+            source.replace("b: e", "b: f"),
+            "Should pick the rule of the @media"
+        );
+        assert!(set_media_declaration(source, |q| q.contains("800px"), "a", "b", "f").is_none());
+    }
+}
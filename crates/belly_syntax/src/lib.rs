@@ -0,0 +1,168 @@
+//! Tooling-friendly parsers for `eml` & `ess` sources.
+//!
+//! Every node of the produced syntax trees keeps the [`Span`] of the
+//! source it was parsed from, so the exact source text of any node
+//! could be restored with [`Span::slice`]. Parsers never fail: the
+//! malformed parts of the source are skipped or replaced with the error
+//! nodes, all the problems are collected as [`Diagnostic`]s next to the
+//! tree.
+//!
+//! The `belly` loaders use the same parsers, so language servers and
+//! editor plugins see the sources exactly the way the game does.
+use std::fmt::Display;
+
+pub mod eml;
+pub mod ess;
+
+/// Byte range of the source text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, offset: usize) -> bool {
+        self.start <= offset && offset < self.end
+    }
+
+    pub fn slice<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+}
+
+/// The value and the [`Span`] it was parsed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(value: T, span: Span) -> Spanned<T> {
+        Spanned { value, span }
+    }
+}
+
+/// One-based line & column of the source text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Converts byte offsets to [`Position`]s and back.
+pub struct LineIndex {
+    lines: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> LineIndex {
+        let mut lines = vec![0];
+        lines.extend(source.match_indices('\n').map(|(idx, _)| idx + 1));
+        LineIndex { lines }
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let line = match self.lines.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+        Position {
+            line: line as u32 + 1,
+            column: (offset - self.lines[line]) as u32 + 1,
+        }
+    }
+
+    pub fn offset(&self, position: Position) -> usize {
+        let line = (position.line.max(1) as usize - 1).min(self.lines.len() - 1);
+        self.lines[line] + position.column.max(1) as usize - 1
+    }
+
+    /// Converts the position with the column counted in chars (the way
+    /// `roxmltree` reports it) to the byte offset of the `source`.
+    pub fn char_offset(&self, source: &str, position: Position) -> usize {
+        self.offset_by(source, position, |_| 1)
+    }
+
+    /// Converts the position with the column counted in `UTF-16` code units
+    /// (the way `cssparser` reports it) to the byte offset of the `source`.
+    pub fn utf16_offset(&self, source: &str, position: Position) -> usize {
+        self.offset_by(source, position, char::len_utf16)
+    }
+
+    fn offset_by(&self, source: &str, position: Position, width: fn(char) -> usize) -> usize {
+        let start = self.offset(Position {
+            column: 1,
+            ..position
+        });
+        let mut column = position.column.max(1) as usize - 1;
+        let mut offset = start.min(source.len());
+        for ch in source[offset..].chars() {
+            if column == 0 || ch == '\n' {
+                break;
+            }
+            column = column.saturating_sub(width(ch));
+            offset += ch.len_utf8();
+        }
+        offset
+    }
+}
+
+/// The problem found while parsing the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>, span: Span) -> Diagnostic {
+        Diagnostic {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_index() {
+        let index = LineIndex::new("a\nbc\n\nd");
+        assert_eq!(index.position(0), Position { line: 1, column: 1 });
+        assert_eq!(index.position(3), Position { line: 2, column: 2 });
+        assert_eq!(index.position(6), Position { line: 4, column: 1 });
+        assert_eq!(index.offset(Position { line: 2, column: 2 }), 3);
+    }
+
+    #[test]
+    fn non_ascii_columns() {
+        let source = "ok\nпривет 世界 🙂<";
+        let index = LineIndex::new(source);
+        let position = |column| Position { line: 2, column };
+        assert_eq!(index.char_offset(source, position(11)), source.find('🙂').unwrap());
+        assert_eq!(index.char_offset(source, position(12)), source.find('<').unwrap());
+        assert_eq!(index.utf16_offset(source, position(13)), source.find('<').unwrap());
+    }
+}
@@ -0,0 +1,162 @@
+use super::img::*;
+use belly_core::*;
+use belly_macro::*;
+use bevy::{prelude::*, utils::HashMap};
+
+/// The frame rate of the animation without the `fps` param.
+const DEFAULT_FPS: f32 = 12.;
+
+pub(crate) struct AnimationPlugin;
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AnimationEvent>();
+        app.register_widget::<Animation>();
+        app.add_system(play_animations);
+    }
+}
+
+pub enum AnimationEvent {
+    /// The animation played with the `once` param has shown the last frame.
+    Completed([Entity; 1]),
+    /// The looped animation has shown the last frame and starts over.
+    Cycled([Entity; 1]),
+}
+
+impl AnimationEvent {
+    pub fn completed(&self) -> bool {
+        match self {
+            AnimationEvent::Completed(_) => true,
+            _ => false,
+        }
+    }
+    pub fn cycled(&self) -> bool {
+        match self {
+            AnimationEvent::Cycled(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Signal for AnimationEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            AnimationEvent::Completed(source) => source,
+            AnimationEvent::Cycled(source) => source,
+        }
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(animation)]
+#[signal(complete, AnimationEvent, completed)]
+#[signal(cycle, AnimationEvent, cycled)]
+/// The `<animation>` tag cycles the regions of the texture atlas registered
+/// in the [`ImgAtlases`] by the `src` name, `fps` times per second (12 by
+/// default). The animation is looped unless the `once` param is set, the
+/// `paused` animation keeps its `frame`. The rest of the params (`mode`,
+/// `modulate`, `flip`) are passed to the underlying `<img>`:
+/// ```rust,ignore
+/// eml! {
+///     <animation src="spinner" fps=24./>
+///     <animation {cast} src="fireball" once paused
+///         on:complete=connect!(hud, |h: Hud| h.cast_ready())/>
+/// }
+/// ```
+pub struct Animation {
+    #[param]
+    pub src: String,
+    #[param]
+    pub fps: f32,
+    #[param]
+    pub paused: bool,
+    #[param]
+    pub once: bool,
+    /// The index of the shown region of the atlas.
+    pub frame: usize,
+}
+
+impl Animation {
+    pub fn play(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Plays the animation from the first frame.
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.paused = false;
+    }
+
+    fn frame_duration(&self) -> f32 {
+        if self.fps > 0. {
+            1. / self.fps
+        } else {
+            1. / DEFAULT_FPS
+        }
+    }
+}
+
+impl WidgetBuilder for Animation {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let params = ctx.params();
+        let atlas = self.src.clone();
+        ctx.render(eml! {
+            <img c:animation atlas=atlas region="0" params=params/>
+        })
+    }
+}
+
+fn play_animations(
+    time: Res<Time>,
+    atlases: Res<ImgAtlases>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    mut animations: Query<(Entity, &mut Animation, &mut Img)>,
+    mut clocks: Local<HashMap<Entity, f32>>,
+    mut events: EventWriter<AnimationEvent>,
+) {
+    clocks.retain(|entity, _| animations.contains(*entity));
+    for (entity, mut animation, mut img) in animations.iter_mut() {
+        if img.atlas != animation.src {
+            img.atlas = animation.src.clone();
+        }
+        let frames = atlases
+            .get(&animation.src)
+            .and_then(|atlas| texture_atlases.get(atlas))
+            .map_or(0, |atlas| atlas.textures.len());
+        if frames == 0 {
+            continue;
+        }
+        let clock = clocks.entry(entity).or_default();
+        if animation.paused {
+            *clock = 0.;
+        } else {
+            *clock += time.delta_seconds();
+            let duration = animation.frame_duration();
+            let mut frame = animation.frame.min(frames - 1);
+            while *clock >= duration {
+                *clock -= duration;
+                if frame + 1 < frames {
+                    frame += 1;
+                } else if animation.once {
+                    animation.paused = true;
+                    *clock = 0.;
+                    events.send(AnimationEvent::Completed([entity]));
+                    break;
+                } else {
+                    frame = 0;
+                    events.send(AnimationEvent::Cycled([entity]));
+                }
+            }
+            if animation.frame != frame {
+                animation.frame = frame;
+            }
+        }
+        let region = animation.frame.min(frames - 1).to_string();
+        if img.region != region {
+            img.region = region;
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use belly_core::*;
 use belly_macro::*;
 use bevy::prelude::*;
@@ -14,9 +16,12 @@ impl Plugin for CommonsPlugin {
         app.register_widget::<brl>();
         app.register_widget::<div>();
         app.register_widget::<Label>();
+        app.register_widget::<pre>();
         app.register_widget::<progressbar>();
         app.register_widget::<span>();
         app.register_widget::<strong>();
+        app.add_system(update_progress_bars.after(configure_range_layout));
+        app.add_system(animate_indeterminate_progress);
     }
 }
 
@@ -88,16 +93,232 @@ impl WidgetBuilder for Label {
     }
 }
 
+/// The side of the `<progressbar>` the fill grows from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum FillDirection {
+    /// From the left of the horizontal bar, from the bottom of the vertical one.
+    #[default]
+    Forward,
+    /// From the right of the horizontal bar, from the top of the vertical one.
+    Reverse,
+}
+
+impl From<FillDirection> for Variant {
+    fn from(d: FillDirection) -> Self {
+        Variant::boxed(d)
+    }
+}
+
+impl FromStr for FillDirection {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "forward" => Ok(FillDirection::Forward),
+            "reverse" => Ok(FillDirection::Reverse),
+            s => Err(format!("Don't know how to parse '{s}' as FillDirection")),
+        }
+    }
+}
+
+impl TryFrom<Variant> for FillDirection {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value.get_or_parse()
+    }
+}
+
+/// Inserted into the `<progressbar>`, bind to its fields to switch
+/// the mode or the text of the bar.
+#[derive(Component)]
+pub struct ProgressBar {
+    /// Shows the running chunk instead of the value.
+    pub indeterminate: bool,
+    /// The text over the bar, the `{value}`, `{minimum}`, `{maximum}`
+    /// and `{percent}` are replaced with the numbers.
+    pub format: Option<String>,
+    pub direction: FillDirection,
+    chunk: Entity,
+    text: Entity,
+}
+
+impl ProgressBar {
+    fn text(&self, value: &LimitedValue) -> String {
+        let Some(format) = &self.format else {
+            return String::new();
+        };
+        let number = |value: f32| {
+            let value = format!("{value:.2}");
+            value
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string()
+        };
+        format
+            .replace("{value}", &number(value.value()))
+            .replace("{minimum}", &number(value.minimum()))
+            .replace("{maximum}", &number(value.maximum()))
+            .replace("{percent}", &format!("{:.0}", value.relative() * 100.))
+    }
+}
+
 #[widget]
 #[extends(styles=Range)]
 #[extends(descriptor=Range)]
 #[style("min-width: 26px")]
 #[style("min-height: 26px")]
+#[style(
+    "progressbar .progress-bar-chunks",
+    "  display: none",
+    "  position-type: absolute",
+    "  left: 1px",
+    "  right: 1px",
+    "  top: 4px",
+    "  bottom: 3px"
+)]
+#[style(
+    "progressbar:vertical .progress-bar-chunks",
+    "  left: 4px",
+    "  right: 3px",
+    "  top: 1px",
+    "  bottom: 1px"
+)]
+#[style("progressbar:indeterminate .progress-bar-chunks", "  display: flex")]
+#[style("progressbar:indeterminate .range-low", "  display: none")]
+#[style(
+    "progressbar .progress-bar-chunk",
+    "  position-type: absolute",
+    "  background-color: #4f4f4fdf"
+)]
+#[style(
+    "progressbar .progress-bar-text",
+    "  position-type: absolute",
+    "  left: 0px",
+    "  right: 0px",
+    "  top: 0px",
+    "  bottom: 0px",
+    "  justify-content: center",
+    "  align-items: center",
+    "  color: #2f2f2f",
+    "  font-size: 14px"
+)]
+/// The `<progressbar>` tag is the `<range>` showing the bindable `value`
+/// between the `minimum` and the `maximum`. The `direction="reverse"`
+/// param fills the bar from the right (from the top for `mode="vertical"`),
+/// the `indeterminate` param shows the running chunk instead of the value
+/// and adds the `:indeterminate` state. The `format` param is the text
+/// shown over the bar (`.progress-bar-text`):
+/// ```rust,ignore
+/// eml! {
+///     <progressbar maximum=200. format="{value}/{maximum} HP"
+///         bind:value=from!(player, Health:current)/>
+/// }
+/// ```
 fn progressbar(ctx: &mut ElementContext) {
+    let indeterminate = ctx.try_param::<bool>("indeterminate").unwrap_or(false);
+    let format = ctx.try_param::<String>("format");
+    let direction = ctx.try_param("direction").unwrap_or_default();
+    let chunk = ctx.commands().spawn_empty().id();
+    let text = ctx.commands().spawn_empty().id();
     let params = ctx.params();
+    ctx.insert(ProgressBar {
+        indeterminate,
+        format,
+        direction,
+        chunk,
+        text,
+    });
     ctx.render(eml! {
         <range c:progress-bar params=params/>
-    })
+    });
+    // the overlays are added over the rendered range
+    let overlays = eml! {
+        <fragment>
+            <span c:progress-bar-chunks>
+                <span {chunk} c:progress-bar-chunk
+                    s:left=managed()
+                    s:top=managed()
+                    s:width=managed()
+                    s:height=managed()
+                />
+            </span>
+            <span c:progress-bar-text>
+                <label {text}/>
+            </span>
+        </fragment>
+    };
+    let entity = ctx.entity();
+    ctx.commands().add(overlays.with_entity(entity));
+}
+
+fn update_progress_bars(
+    mut elements: Elements,
+    bars: Query<(Entity, &ProgressBar, &Range), Or<(Changed<ProgressBar>, Changed<Range>)>>,
+    mut styles: Query<&mut Style>,
+    mut labels: Query<&mut Label>,
+) {
+    for (entity, bar, range) in bars.iter() {
+        elements.set_state(entity, "indeterminate".as_tag(), bar.indeterminate);
+        if let Ok(mut label) = labels.get_mut(bar.text) {
+            let text = bar.text(&range.value);
+            if label.value != text {
+                label.value = text;
+            }
+        }
+        let Ok(mut holder) = styles.get_mut(range.holder) else {
+            continue;
+        };
+        let direction = match (range.mode, bar.direction) {
+            (LayoutMode::Horizontal, FillDirection::Forward) => FlexDirection::Row,
+            (LayoutMode::Horizontal, FillDirection::Reverse) => FlexDirection::RowReverse,
+            (LayoutMode::Vertical, FillDirection::Forward) => FlexDirection::ColumnReverse,
+            (LayoutMode::Vertical, FillDirection::Reverse) => FlexDirection::Column,
+        };
+        if holder.flex_direction != direction {
+            holder.flex_direction = direction;
+        }
+    }
+}
+
+/// Runs the chunk of the indeterminate bars back and forth.
+fn animate_indeterminate_progress(
+    time: Res<Time>,
+    bars: Query<(&ProgressBar, &Range)>,
+    mut styles: Query<&mut Style>,
+) {
+    const CHUNK: f32 = 30.;
+    const PERIOD: f32 = 2.;
+    let phase = (time.elapsed_seconds() / PERIOD).fract() * 2.;
+    let offset = (100. - CHUNK) * if phase > 1. { 2. - phase } else { phase };
+    for (bar, range) in bars.iter().filter(|(bar, _)| bar.indeterminate) {
+        let Ok(mut style) = styles.get_mut(bar.chunk) else {
+            continue;
+        };
+        let (left, top, width, height) = match range.mode {
+            LayoutMode::Horizontal => (offset, 0., CHUNK, 100.),
+            LayoutMode::Vertical => (0., offset, 100., CHUNK),
+        };
+        style.position.left = Val::Percent(left);
+        style.position.top = Val::Percent(top);
+        style.size.width = Val::Percent(width);
+        style.size.height = Val::Percent(height);
+    }
+}
+
+#[widget]
+#[style("flex-basis: 100%")]
+/// The `<pre>` tag displays the preformatted text: spaces, line breaks
+/// and markup-looking characters of the content are kept as written, so
+/// the `<` and `&` don't have to be escaped within `eml` assets:
+/// ```xml
+/// <pre class="log">
+/// [12:01] hp < 10 && enemies > 3
+/// </pre>
+/// ```
+/// Other elements keep the text the same way with `raw="true"` param.
+/// Pair it with the monospace `font-family` to display the code or ASCII art.
+fn pre(ctx: &mut ElementContext) {
+    let content = ctx.content();
+    ctx.insert(ElementBundle::default()).push_children(&content);
 }
 
 #[widget]
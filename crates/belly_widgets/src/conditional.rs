@@ -0,0 +1,80 @@
+use belly_core::*;
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub(crate) struct ConditionalPlugin;
+impl Plugin for ConditionalPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<If>();
+        app.add_system(update_conditions);
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(if)]
+/// The `<if>` tag renders its content only when the bindable `value`
+/// param is `true`:
+/// ```rust,ignore
+/// eml! {
+///     <if bind:value=from!(GameState:paused)>
+///         <div c:pause-menu>"Paused"</div>
+///     </if>
+/// }
+/// ```
+/// While the `value` is `false`, the content is detached from the `<if>`
+/// element: it takes no space, isn't rendered and doesn't receive the
+/// input. The content is attached back with its state kept when the
+/// `value` becomes `true`.
+pub struct If {
+    #[param]
+    pub value: bool,
+    /// Holds the content while the `value` is `false`.
+    holder: Entity,
+}
+
+impl WidgetBuilder for If {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let content = ctx.content();
+        let holder = self.holder;
+        ctx.commands().entity(holder).insert(NodeBundle {
+            style: Style {
+                display: Display::None,
+                ..default()
+            },
+            visibility: Visibility::INVISIBLE,
+            ..default()
+        });
+        if self.value {
+            ctx.insert(ElementBundle::default())
+                .push_children(&content)
+                .add_child(holder);
+        } else {
+            ctx.commands().entity(holder).push_children(&content);
+            ctx.insert(ElementBundle::default()).add_child(holder);
+        }
+    }
+}
+
+fn update_conditions(
+    mut commands: Commands,
+    conditions: Query<(Entity, &If, &Children), Changed<If>>,
+    children: Query<&Children>,
+) {
+    for (entity, condition, attached) in conditions.iter() {
+        if condition.value {
+            if let Ok(detached) = children.get(condition.holder) {
+                let detached: Vec<_> = detached.iter().copied().collect();
+                commands.entity(entity).push_children(&detached);
+            }
+        } else {
+            let attached: Vec<_> = attached
+                .iter()
+                .copied()
+                .filter(|child| *child != condition.holder)
+                .collect();
+            if !attached.is_empty() {
+                commands.entity(condition.holder).push_children(&attached);
+            }
+        }
+    }
+}
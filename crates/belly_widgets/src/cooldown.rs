@@ -0,0 +1,120 @@
+use std::str::FromStr;
+
+use super::common::*;
+use belly_core::{ess::ConicFill, *};
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub(crate) struct CooldownPlugin;
+impl Plugin for CooldownPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<Cooldown>();
+        app.add_system(update_cooldown_fill);
+    }
+}
+
+/// The direction the darkened sector of the [`Cooldown`] shrinks in.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum Sweep {
+    #[default]
+    Clockwise,
+    CounterClockwise,
+}
+
+impl FromStr for Sweep {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Ok(Sweep::Clockwise),
+            "clockwise" => Ok(Sweep::Clockwise),
+            "counter-clockwise" => Ok(Sweep::CounterClockwise),
+            err => Err(format!("Can't parse `{}` as Sweep", err)),
+        }
+    }
+}
+
+impl TryFrom<String> for Sweep {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<Variant> for Sweep {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value.get_or_parse()
+    }
+}
+
+impl From<Sweep> for Variant {
+    fn from(sweep: Sweep) -> Self {
+        Variant::Boxed(Box::new(sweep))
+    }
+}
+
+#[derive(Component, Widget)]
+/// The `<cooldown>` tag darkens its content (usually the ability icon)
+/// with the pie-shaped overlay. The `<cooldown>` tag has two properties:
+/// - `value`: the darkened part of the full turn, from `0.0` to `1.0`
+/// - `sweep`: the direction the darkened sector shrinks in:
+///   - `clockwise`: the content is revealed clockwise from the top
+///   - `counter-clockwise`: the content is revealed counter-clockwise
+///
+/// The overlay has the `cooldown-overlay` class and can be restyled
+/// with the `background-color` property.
+#[alias(cooldown)]
+pub struct Cooldown {
+    #[param]
+    pub value: f32,
+    #[param]
+    pub sweep: Sweep,
+    overlay: Entity,
+}
+
+impl WidgetBuilder for Cooldown {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let content = ctx.content();
+        let overlay = self.overlay;
+        ctx.render(eml! {
+            <span c:cooldown>
+                {content}
+                <span {overlay} c:cooldown-overlay s:fill=managed()/>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            cooldown .cooldown-overlay {
+                position-type: absolute;
+                left: 0px;
+                right: 0px;
+                top: 0px;
+                bottom: 0px;
+                background-color: #000000a0;
+            }
+        "#
+    }
+}
+
+fn update_cooldown_fill(
+    mut commands: Commands,
+    cooldowns: Query<&Cooldown, Changed<Cooldown>>,
+    mut fills: Query<&mut ConicFill>,
+) {
+    for cooldown in cooldowns.iter() {
+        let value = cooldown.value.max(0.).min(1.);
+        let fill = match cooldown.sweep {
+            Sweep::Clockwise => ConicFill::new(1. - value, 1.),
+            Sweep::CounterClockwise => ConicFill::new(0., value),
+        };
+        match fills.get_mut(cooldown.overlay) {
+            Ok(mut current) if *current != fill => *current = fill,
+            Ok(_) => {}
+            Err(_) => {
+                commands.entity(cooldown.overlay).insert(fill);
+            }
+        }
+    }
+}
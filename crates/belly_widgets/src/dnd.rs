@@ -0,0 +1,83 @@
+use crate::common::*;
+use belly_core::{dnd::DragAndDrop, *};
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub(crate) struct DragGhostPlugin;
+impl Plugin for DragGhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<DragGhost>();
+        app.add_system(show_drag_ghost);
+    }
+}
+
+#[derive(Component, Widget)]
+/// The `<dragghost>` tag is the box following the pointer while the
+/// `draggable` element is dragged, it is not meant to be used in the
+/// markup directly. The ghost takes the size of the dragged element
+/// and keeps the point it was grabbed at under the pointer.
+#[alias(dragghost)]
+pub struct DragGhost;
+
+impl WidgetBuilder for DragGhost {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        ctx.render(eml! {
+            <span c:drag-ghost
+                s:left=managed()
+                s:top=managed()
+                s:width=managed()
+                s:height=managed()
+                s:display=managed()
+            />
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            dragghost {
+                position-type: absolute;
+                background-color: #dfdfdf80;
+            }
+        "#
+    }
+}
+
+fn show_drag_ghost(
+    mut commands: Commands,
+    mut ghost: Local<Option<Entity>>,
+    drag: Res<DragAndDrop>,
+    nodes: Query<&Node>,
+    mut styles: Query<&mut Style, With<DragGhost>>,
+) {
+    let ghost = *ghost.get_or_insert_with(|| {
+        let ghost = commands.spawn_empty().id();
+        commands.add(eml! { <dragghost/> }.with_entity(ghost));
+        commands.entity(ghost).insert(ZIndex::Global(i32::MAX));
+        ghost
+    });
+    let Ok(mut style) = styles.get_mut(ghost) else {
+        return;
+    };
+    let Some(dragging) = drag.get() else {
+        if style.display != Display::None {
+            style.display = Display::None;
+        }
+        return;
+    };
+    let size = nodes.get(dragging.source).map_or(Vec2::ZERO, |n| n.size());
+    let position = dragging.pos - dragging.offset - size / 2.;
+    let (left, top) = (Val::Px(position.x), Val::Px(position.y));
+    let (width, height) = (Val::Px(size.x), Val::Px(size.y));
+    if style.display != Display::Flex
+        || style.position.left != left
+        || style.position.top != top
+        || style.size.width != width
+        || style.size.height != height
+    {
+        style.display = Display::Flex;
+        style.position.left = left;
+        style.position.top = top;
+        style.size.width = width;
+        style.size.height = height;
+    }
+}
@@ -0,0 +1,878 @@
+use crate::window::{FloatingWindow, FloatingWindowEvent};
+use belly_core::*;
+use belly_macro::*;
+use bevy::{prelude::*, utils::HashMap};
+use std::{fmt::Display, str::FromStr};
+
+/// The share of the dock size at its edges docking the dragged window
+/// to the side areas.
+const EDGE_ZONE: f32 = 0.15;
+/// The share of the dock size around its center docking the dragged
+/// window to the center area.
+const CENTER_ZONE: f32 = 0.2;
+
+pub(crate) struct DockPlugin;
+impl Plugin for DockPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DockEvent>();
+        app.register_widget::<Dock>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_dock_input.after(input::Label::Signals),
+        );
+        app.add_system(sync_dock_layouts);
+        app.add_system(update_dock_panels.after(sync_dock_layouts));
+    }
+}
+
+pub enum DockEvent {
+    /// The panels are rearranged by the user.
+    Changed([Entity; 1]),
+}
+
+impl DockEvent {
+    pub fn changed(&self) -> bool {
+        match self {
+            DockEvent::Changed(_) => true,
+        }
+    }
+}
+
+impl Signal for DockEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            DockEvent::Changed(source) => source,
+        }
+    }
+}
+
+/// The area of the `<dock>` the window is docked into.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DockArea {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// The area in the middle, its panels are usually shown as tabs.
+    Center,
+}
+
+impl DockArea {
+    pub const ALL: [DockArea; 5] = [
+        DockArea::Left,
+        DockArea::Right,
+        DockArea::Top,
+        DockArea::Bottom,
+        DockArea::Center,
+    ];
+
+    /// The rect the docked window takes, in the shares of the dock size.
+    fn preview(&self) -> Rect {
+        match self {
+            DockArea::Left => Rect::new(0., 0., 0.25, 1.),
+            DockArea::Right => Rect::new(0.75, 0., 1., 1.),
+            DockArea::Top => Rect::new(0., 0., 1., 0.25),
+            DockArea::Bottom => Rect::new(0., 0.75, 1., 1.),
+            DockArea::Center => Rect::new(0.25, 0.25, 0.75, 0.75),
+        }
+    }
+
+    /// The area docking the window dropped at the `point`, in the shares
+    /// of the dock size.
+    fn at(point: Vec2) -> Option<DockArea> {
+        if point.x < 0. || point.y < 0. || point.x > 1. || point.y > 1. {
+            None
+        } else if point.x < EDGE_ZONE {
+            Some(DockArea::Left)
+        } else if point.x > 1. - EDGE_ZONE {
+            Some(DockArea::Right)
+        } else if point.y < EDGE_ZONE {
+            Some(DockArea::Top)
+        } else if point.y > 1. - EDGE_ZONE {
+            Some(DockArea::Bottom)
+        } else if (point - Vec2::splat(0.5)).abs().max_element() < CENTER_ZONE {
+            Some(DockArea::Center)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for DockArea {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left" => Ok(DockArea::Left),
+            "right" => Ok(DockArea::Right),
+            "top" => Ok(DockArea::Top),
+            "bottom" => Ok(DockArea::Bottom),
+            "center" => Ok(DockArea::Center),
+            s => Err(format!("Don't know how to parse '{s}' as DockArea")),
+        }
+    }
+}
+
+impl Display for DockArea {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DockArea::Left => "left",
+            DockArea::Right => "right",
+            DockArea::Top => "top",
+            DockArea::Bottom => "bottom",
+            DockArea::Center => "center",
+        })
+    }
+}
+
+/// Where the window of the `<dock>` is placed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PanelPlace {
+    /// The window is docked into the `area`, the `active` window
+    /// is the one shown when the area holds several windows.
+    Docked { area: DockArea, active: bool },
+    /// The window floats over the dock at the position of the size.
+    Floating {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+}
+
+/// The place of the window identified by its `id`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PanelLayout {
+    pub id: String,
+    pub place: PanelPlace,
+}
+
+/// The arrangement of the `<dock>` windows. It is stored as text,
+/// one window per line (or separated by `;`): the `id` of the window
+/// followed either by its area and the `active` flag or by the `float`
+/// keyword and its geometry:
+/// ```text
+/// scene center active; assets center; inspector right active;
+/// console float 40 380 480 160
+/// ```
+/// The docked windows of the same area are listed in their tabs order.
+/// The whitespaces, `;` and `%` of the ids are written as `%XX` escapes.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct DockLayout(pub Vec<PanelLayout>);
+
+/// Escapes the characters of the window `id` separating the layout words.
+fn escape_id(id: &str) -> String {
+    let mut escaped = String::with_capacity(id.len());
+    for c in id.chars() {
+        if c.is_whitespace() || c == ';' || c == '%' {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                escaped.push_str(&format!("%{byte:02X}"));
+            }
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Restores the window id escaped by the [`escape_id`].
+fn unescape_id(id: &str) -> Result<String, String> {
+    let mut bytes = Vec::with_capacity(id.len());
+    let mut rest = id.as_bytes();
+    while let Some((byte, tail)) = rest.split_first() {
+        if *byte == b'%' {
+            let hex = tail
+                .get(..2)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| format!("Invalid escape in the window id `{id}`"))?;
+            bytes.push(hex);
+            rest = &tail[2..];
+        } else {
+            bytes.push(*byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| format!("Invalid escape in the window id `{id}`"))
+}
+
+impl DockLayout {
+    /// The layout listing the docked windows first, by their areas,
+    /// and the floating windows last. The order of the windows of the
+    /// same area is kept.
+    fn normalized(&self) -> DockLayout {
+        let mut panels = self.0.clone();
+        panels.sort_by_key(|panel| match panel.place {
+            PanelPlace::Docked { area, .. } => DockArea::ALL.iter().position(|a| *a == area),
+            PanelPlace::Floating { .. } => Some(DockArea::ALL.len()),
+        });
+        DockLayout(panels)
+    }
+}
+
+impl Display for DockLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, panel) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            match panel.place {
+                PanelPlace::Docked { area, active } => {
+                    write!(f, "{} {area}", escape_id(&panel.id))?;
+                    if active {
+                        f.write_str(" active")?;
+                    }
+                }
+                PanelPlace::Floating {
+                    x,
+                    y,
+                    width,
+                    height,
+                } => write!(f, "{} float {x} {y} {width} {height}", escape_id(&panel.id))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DockLayout {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut panels = vec![];
+        for line in s.split(|c| c == ';' || c == '\n') {
+            let words: Vec<_> = line.split_whitespace().collect();
+            let place = match words[..] {
+                [] => continue,
+                [_, "float", x, y, width, height] => {
+                    let parse = |value: &str| {
+                        value
+                            .trim_end_matches("px")
+                            .parse::<f32>()
+                            .map_err(|e| format!("Can't parse `{line}` as panel layout: {e}"))
+                    };
+                    PanelPlace::Floating {
+                        x: parse(x)?,
+                        y: parse(y)?,
+                        width: parse(width)?,
+                        height: parse(height)?,
+                    }
+                }
+                [_, area] => PanelPlace::Docked {
+                    area: area.parse()?,
+                    active: false,
+                },
+                [_, area, "active"] => PanelPlace::Docked {
+                    area: area.parse()?,
+                    active: true,
+                },
+                _ => return Err(format!("Can't parse `{}` as panel layout", line.trim())),
+            };
+            panels.push(PanelLayout {
+                id: unescape_id(words[0])?,
+                place,
+            });
+        }
+        Ok(DockLayout(panels))
+    }
+}
+
+impl From<DockLayout> for Variant {
+    fn from(layout: DockLayout) -> Self {
+        Variant::boxed(layout)
+    }
+}
+
+impl TryFrom<Variant> for DockLayout {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value.get_or_parse()
+    }
+}
+
+/// The tab of the docked window, the pressed tab activates the window.
+#[derive(Component)]
+struct DockTab {
+    dock: Entity,
+    window: Entity,
+}
+
+#[derive(Component, Widget)]
+#[alias(dock)]
+#[signal(change, DockEvent, changed)]
+/// The `<dock>` tag arranges its `<window>` children the way the editors
+/// do. The window dragged by its title bar is undocked and the preview
+/// shows where it goes when dropped at the edge of the dock (`left`,
+/// `right`, `top`, `bottom`) or at its center (`center`). The windows
+/// docked into the same area are switched by the tabs (`.dock-tab`, the
+/// tab of the shown window gets the `:selected` state), the windows
+/// dropped elsewhere keep floating over the dock. The windows are known
+/// by their `id`s, the bindable `layout` param is the [`DockLayout`] of
+/// the windows, so the arrangement could be saved and restored. The
+/// `change` signal is emitted when the user rearranges the windows:
+/// ```rust,ignore
+/// eml! {
+///     <dock layout="scene center active; inspector right; console bottom"
+///         bind:layout=to!(settings, EditorSettings:layout)
+///         on:change=connect!(settings, |s: EditorSettings| s.save())>
+///         <window id="scene" title="Scene">...</window>
+///         <window id="inspector" title="Inspector">...</window>
+///         <window id="console" title="Console">...</window>
+///     </dock>
+/// }
+/// ```
+pub struct Dock {
+    #[param]
+    pub layout: DockLayout,
+    /// The layout the panels are arranged by, in the normalized order.
+    applied: Option<DockLayout>,
+    /// The layout is applied and the panels aren't captured yet.
+    settling: bool,
+    /// The docked windows in their tabs order.
+    panels: Vec<(DockArea, Entity)>,
+    /// The windows shown by the areas.
+    active: HashMap<DockArea, Entity>,
+    /// The panels the tabs are built for.
+    tabbed: Vec<(DockArea, Entity)>,
+    left: Entity,
+    right: Entity,
+    top: Entity,
+    bottom: Entity,
+    center: Entity,
+    left_tabs: Entity,
+    right_tabs: Entity,
+    top_tabs: Entity,
+    bottom_tabs: Entity,
+    center_tabs: Entity,
+    floating: Entity,
+    preview: Entity,
+}
+
+impl Dock {
+    /// The windows docked into the `area` in their tabs order.
+    pub fn panels(&self, area: DockArea) -> impl Iterator<Item = Entity> + '_ {
+        self.panels
+            .iter()
+            .filter(move |(a, _)| *a == area)
+            .map(|(_, window)| *window)
+    }
+
+    /// The window shown by the `area`.
+    pub fn active(&self, area: DockArea) -> Option<Entity> {
+        self.active.get(&area).copied()
+    }
+
+    fn area(&self, area: DockArea) -> Entity {
+        match area {
+            DockArea::Left => self.left,
+            DockArea::Right => self.right,
+            DockArea::Top => self.top,
+            DockArea::Bottom => self.bottom,
+            DockArea::Center => self.center,
+        }
+    }
+
+    fn tabs(&self, area: DockArea) -> Entity {
+        match area {
+            DockArea::Left => self.left_tabs,
+            DockArea::Right => self.right_tabs,
+            DockArea::Top => self.top_tabs,
+            DockArea::Bottom => self.bottom_tabs,
+            DockArea::Center => self.center_tabs,
+        }
+    }
+
+    fn area_of(&self, window: Entity) -> Option<DockArea> {
+        self.panels
+            .iter()
+            .find(|(_, w)| *w == window)
+            .map(|(area, _)| *area)
+    }
+
+    fn dock(&mut self, commands: &mut Commands, window: Entity, area: DockArea) {
+        self.undock(commands, window);
+        self.panels.push((area, window));
+        self.active.insert(area, window);
+        commands.entity(self.area(area)).add_child(window);
+    }
+
+    fn undock(&mut self, commands: &mut Commands, window: Entity) {
+        let Some(area) = self.area_of(window) else {
+            return;
+        };
+        self.panels.retain(|(_, w)| *w != window);
+        if self.active(area) == Some(window) {
+            match self.panels(area).next() {
+                Some(next) => self.active.insert(area, next),
+                None => self.active.remove(&area),
+            };
+        }
+        commands.entity(self.floating).add_child(window);
+    }
+}
+
+impl WidgetBuilder for Dock {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let content = ctx.content();
+        let (left, right, top, bottom, center) =
+            (self.left, self.right, self.top, self.bottom, self.center);
+        let (left_tabs, right_tabs, top_tabs, bottom_tabs, center_tabs) = (
+            self.left_tabs,
+            self.right_tabs,
+            self.top_tabs,
+            self.bottom_tabs,
+            self.center_tabs,
+        );
+        let floating = self.floating;
+        let preview = self.preview;
+        ctx.render(eml! {
+            <span c:dock>
+                <span {top} c:dock-area c:dock-top>
+                    <span {top_tabs} c:dock-tabs/>
+                </span>
+                <span c:dock-middle>
+                    <span {left} c:dock-area c:dock-left>
+                        <span {left_tabs} c:dock-tabs/>
+                    </span>
+                    <span {center} c:dock-area c:dock-center>
+                        <span {center_tabs} c:dock-tabs/>
+                    </span>
+                    <span {right} c:dock-area c:dock-right>
+                        <span {right_tabs} c:dock-tabs/>
+                    </span>
+                </span>
+                <span {bottom} c:dock-area c:dock-bottom>
+                    <span {bottom_tabs} c:dock-tabs/>
+                </span>
+                <span {floating} c:dock-floating>{content}</span>
+                <span {preview} c:dock-preview
+                    s:left=managed() s:top=managed()
+                    s:width=managed() s:height=managed()
+                />
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            dock {
+                flex-direction: column;
+                width: 100%;
+                height: 100%;
+            }
+            dock .dock-middle {
+                flex-grow: 1;
+                flex-shrink: 1;
+            }
+            dock .dock-area {
+                display: none;
+                flex-direction: column;
+                flex-shrink: 0;
+            }
+            dock .dock-area:occupied {
+                display: flex;
+            }
+            dock .dock-left,
+            dock .dock-right {
+                width: 25%;
+            }
+            dock .dock-top,
+            dock .dock-bottom {
+                height: 25%;
+            }
+            dock .dock-center {
+                display: flex;
+                flex-grow: 1;
+                flex-shrink: 1;
+            }
+            dock .dock-tabs {
+                display: none;
+                flex-shrink: 0;
+                background-color: #7f7f7f;
+            }
+            dock .dock-area:tabbed .dock-tabs {
+                display: flex;
+            }
+            dock .dock-tab {
+                padding: 3px 10px;
+                margin-right: 1px;
+                color: #efefef;
+            }
+            dock .dock-tab:hover {
+                background-color: #ffffff3f;
+            }
+            dock .dock-tab:selected {
+                background-color: #3f6f9f;
+            }
+            dock .dock-floating {
+                position-type: absolute;
+                left: 0px;
+                top: 0px;
+                right: 0px;
+                bottom: 0px;
+            }
+            dock .dock-preview {
+                display: none;
+                position-type: absolute;
+                background-color: #3f6f9f5f;
+                border: 2px solid #3f6f9f;
+            }
+            dock:docking .dock-preview {
+                display: flex;
+            }
+            dock window:docked {
+                position-type: relative;
+                flex-grow: 1;
+                flex-shrink: 1;
+                min-width: 0px;
+            }
+            dock window:docked .window-grip,
+            dock window:inactive {
+                display: none;
+            }
+        "#
+    }
+}
+
+/// Undocks the window dragged by its title bar, previews and docks
+/// it where it is dropped, and activates the windows by the tabs.
+fn handle_dock_input(
+    mut commands: Commands,
+    mut elements: Elements,
+    mut pointer: EventReader<PointerInput>,
+    mut docks: Query<&mut Dock>,
+    mut windows: Query<&mut FloatingWindow>,
+    mut styles: Query<&mut Style>,
+    tabs: Query<&DockTab>,
+    nodes: Query<(&GlobalTransform, &Node)>,
+    parents: Query<&Parent>,
+    mut dragging: Local<Option<(Entity, Entity, Option<DockArea>)>>,
+) {
+    for ev in pointer.iter() {
+        if ev.down() && dragging.is_none() {
+            let Some(&target) = ev.entities.first() else {
+                continue;
+            };
+            if let Ok(tab) = tabs.get(target) {
+                let Ok(mut dock) = docks.get_mut(tab.dock) else {
+                    continue;
+                };
+                let Some(area) = dock.area_of(tab.window) else {
+                    continue;
+                };
+                if dock.active(area) != Some(tab.window) {
+                    dock.active.insert(area, tab.window);
+                }
+                continue;
+            }
+            let Some(window) = parents
+                .iter_ancestors(target)
+                .find(|e| windows.get(*e).map_or(false, |w| w.titlebar() == target))
+            else {
+                continue;
+            };
+            let Some(dock) = parents.iter_ancestors(window).find(|e| docks.contains(*e)) else {
+                continue;
+            };
+            *dragging = Some((dock, window, None));
+            continue;
+        }
+        let Some((entity, window, zone)) = *dragging else {
+            continue;
+        };
+        let Ok(mut dock) = docks.get_mut(entity) else {
+            *dragging = None;
+            continue;
+        };
+        if ev.dragging() {
+            if let Ok(mut floating) = windows.get_mut(window) {
+                if floating.docked {
+                    dock.undock(&mut commands, window);
+                    floating.docked = false;
+                }
+            }
+            let Ok((transform, node)) = nodes.get(entity) else {
+                continue;
+            };
+            let size = node.size();
+            let corner = transform.translation().truncate() - size / 2.;
+            let area = DockArea::at((ev.pos - corner) / size.max(Vec2::ONE));
+            if area != zone {
+                *dragging = Some((entity, window, area));
+                elements.set_state(entity, "docking".as_tag(), area.is_some());
+            }
+            let Some(area) = area else {
+                continue;
+            };
+            let Ok(mut style) = styles.get_mut(dock.preview) else {
+                continue;
+            };
+            let rect = area.preview();
+            let (left, top) = (Val::Px(rect.min.x * size.x), Val::Px(rect.min.y * size.y));
+            let (width, height) = (
+                Val::Px(rect.width() * size.x),
+                Val::Px(rect.height() * size.y),
+            );
+            if style.position.left != left || style.position.top != top {
+                style.position.left = left;
+                style.position.top = top;
+            }
+            if style.size.width != width || style.size.height != height {
+                style.size.width = width;
+                style.size.height = height;
+            }
+        }
+        if ev.up() || ev.drag_stop() {
+            *dragging = None;
+            elements.set_state(entity, "docking".as_tag(), false);
+            let Some(area) = zone else {
+                continue;
+            };
+            let Ok(mut floating) = windows.get_mut(window) else {
+                continue;
+            };
+            dock.dock(&mut commands, window, area);
+            floating.docked = true;
+        }
+    }
+}
+
+/// The windows of the dock: the docked ones first, the floating ones last.
+fn dock_windows(
+    dock: &Dock,
+    children: &Query<&Children>,
+    windows: &Query<(&ElementId, &mut FloatingWindow)>,
+) -> Vec<Entity> {
+    let floating = children
+        .get(dock.floating)
+        .into_iter()
+        .flat_map(|c| c.iter());
+    dock.panels
+        .iter()
+        .map(|(_, window)| *window)
+        .chain(floating.copied().filter(|e| windows.contains(*e)))
+        .collect()
+}
+
+/// Arranges the windows when the `layout` is changed and captures the
+/// layout when the windows are rearranged.
+fn sync_dock_layouts(
+    mut commands: Commands,
+    mut docks: Query<(Entity, &mut Dock)>,
+    mut windows: Query<(&ElementId, &mut FloatingWindow)>,
+    mut window_events: EventReader<FloatingWindowEvent>,
+    children: Query<&Children>,
+    mut events: EventWriter<DockEvent>,
+) {
+    let touched: Vec<_> = window_events
+        .iter()
+        .filter(|e| e.moved() || e.resized())
+        .flat_map(|e| e.sources().iter().copied())
+        .collect();
+    for (entity, mut dock) in docks.iter_mut() {
+        let known = dock_windows(&dock, &children, &windows);
+        if dock.applied.as_ref() != Some(&dock.layout.normalized()) {
+            let layout = dock.layout.clone();
+            for panel in layout.0.iter() {
+                let Some(window) = known.iter().copied().find(|w| {
+                    windows
+                        .get(*w)
+                        .map_or(false, |(id, _)| id.0.as_str() == panel.id)
+                }) else {
+                    warn!("Ignoring layout of missing dock window '{}'", panel.id);
+                    continue;
+                };
+                let Ok((_, mut floating)) = windows.get_mut(window) else {
+                    continue;
+                };
+                match panel.place {
+                    PanelPlace::Docked { area, active } => {
+                        let previous = dock.active(area).filter(|p| *p != window);
+                        dock.dock(&mut commands, window, area);
+                        // the area shows its first window if none is active
+                        if let Some(previous) = previous.filter(|_| !active) {
+                            dock.active.insert(area, previous);
+                        }
+                        floating.docked = true;
+                    }
+                    PanelPlace::Floating {
+                        x,
+                        y,
+                        width,
+                        height,
+                    } => {
+                        dock.undock(&mut commands, window);
+                        floating.docked = false;
+                        floating.x = x;
+                        floating.y = y;
+                        floating.width = width;
+                        floating.height = height;
+                    }
+                }
+            }
+            dock.applied = Some(dock.layout.normalized());
+            dock.settling = true;
+            continue;
+        }
+        if !dock.is_changed() && !known.iter().any(|w| touched.contains(w)) {
+            continue;
+        }
+        let panels = known
+            .iter()
+            .filter_map(|window| {
+                let (id, floating) = windows.get(*window).ok()?;
+                let place = match dock.area_of(*window) {
+                    Some(area) => PanelPlace::Docked {
+                        area,
+                        active: dock.active(area) == Some(*window),
+                    },
+                    None => PanelPlace::Floating {
+                        x: floating.x,
+                        y: floating.y,
+                        width: floating.width,
+                        height: floating.height,
+                    },
+                };
+                Some(PanelLayout {
+                    id: id.0.to_string(),
+                    place,
+                })
+            })
+            .collect();
+        let layout = DockLayout(panels).normalized();
+        if dock.applied.as_ref() == Some(&layout) {
+            dock.bypass_change_detection().settling = false;
+            continue;
+        }
+        let settling = dock.settling;
+        dock.settling = false;
+        dock.applied = Some(layout.clone());
+        dock.layout = layout;
+        // the layout is completed with the windows missed by it silently
+        if !settling {
+            events.send(DockEvent::Changed([entity]));
+        }
+    }
+}
+
+/// Sets the states of the areas and the windows and rebuilds the tabs
+/// when the windows are docked or undocked.
+fn update_dock_panels(
+    mut commands: Commands,
+    mut elements: Elements,
+    mut docks: Query<(Entity, &mut Dock)>,
+    tabs: Query<(Entity, &DockTab)>,
+    new_tabs: Query<(Entity, &DockTab), Added<DockTab>>,
+) {
+    for (tab_entity, tab) in new_tabs.iter() {
+        let selected = docks.get(tab.dock).map_or(false, |(_, dock)| {
+            dock.area_of(tab.window)
+                .map_or(false, |area| dock.active(area) == Some(tab.window))
+        });
+        elements.set_state(tab_entity, "selected".as_tag(), selected);
+    }
+    for (entity, mut dock) in docks.iter_mut() {
+        if !dock.is_changed() {
+            continue;
+        }
+        for area in DockArea::ALL {
+            let count = dock.panels(area).count();
+            elements.set_state(dock.area(area), "occupied".as_tag(), count > 0);
+            elements.set_state(dock.area(area), "tabbed".as_tag(), count > 1);
+        }
+        let panels = dock.panels.clone();
+        for (area, window) in panels.iter() {
+            let inactive = dock.active(*area) != Some(*window);
+            elements.set_state(*window, "inactive".as_tag(), inactive);
+        }
+        for (tab_entity, tab) in tabs.iter().filter(|(_, t)| t.dock == entity) {
+            let selected = dock
+                .area_of(tab.window)
+                .map_or(false, |area| dock.active(area) == Some(tab.window));
+            elements.set_state(tab_entity, "selected".as_tag(), selected);
+        }
+        if dock.tabbed == panels {
+            continue;
+        }
+        // the undocked windows are shown again
+        for (_, window) in dock.tabbed.iter() {
+            if !panels.iter().any(|(_, w)| w == window) {
+                elements.set_state(*window, "inactive".as_tag(), false);
+            }
+        }
+        dock.bypass_change_detection().tabbed = panels;
+        for area in DockArea::ALL {
+            let strip = dock.tabs(area);
+            let items: Vec<_> = dock.panels(area).collect();
+            commands.entity(strip).despawn_descendants();
+            commands.add(move |world: &mut World| {
+                let items: Vec<_> = items
+                    .into_iter()
+                    .map(|window| {
+                        let tab = world
+                            .spawn(DockTab {
+                                dock: entity,
+                                window,
+                            })
+                            .id();
+                        eml! {
+                            <span {tab} c:dock-tab interactable>
+                                <label bind:value=from!(window, FloatingWindow:title)/>
+                            </span>
+                        }
+                    })
+                    .collect();
+                let roots = eml! { <span>{items}</span> }.build(world);
+                world.entity_mut(strip).push_children(&roots);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn layout_round_trip() {
+        let layout = DockLayout(vec![
+            PanelLayout {
+                id: "scene".to_string(),
+                place: PanelPlace::Docked {
+                    area: DockArea::Center,
+                    active: true,
+                },
+            },
+            PanelLayout {
+                id: "asset browser; 100%".to_string(),
+                place: PanelPlace::Docked {
+                    area: DockArea::Left,
+                    active: false,
+                },
+            },
+            PanelLayout {
+                id: "console\tlog".to_string(),
+                place: PanelPlace::Floating {
+                    x: 40.,
+                    y: 380.5,
+                    width: 480.,
+                    height: 160.25,
+                },
+            },
+        ]);
+        let text = layout.to_string();
+        assert_eq!(
+            text,
+            "scene center active; asset%20browser%3B%20100%25 left; \
+             console%09log float 40 380.5 480 160.25"
+        );
+        assert_eq!(text.parse::<DockLayout>(), Ok(layout));
+    }
+
+    #[test]
+    fn reject_invalid_layout() {
+        assert!("scene center active extra".parse::<DockLayout>().is_err());
+        assert!("sc%2 center".parse::<DockLayout>().is_err());
+        assert!("sc%zz center".parse::<DockLayout>().is_err());
+        assert!("scene middle".parse::<DockLayout>().is_err());
+    }
+}
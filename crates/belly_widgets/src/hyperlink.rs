@@ -0,0 +1,178 @@
+use belly_core::*;
+use belly_macro::*;
+use bevy::{prelude::*, utils::HashSet};
+
+pub(crate) struct HyperlinkPlugin;
+impl Plugin for HyperlinkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LinkActivated>();
+        app.init_resource::<VisitedLinks>();
+        app.register_widget::<Hyperlink>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            activate_links.after(input::Label::Signals),
+        );
+        app.add_system(update_visited_links);
+    }
+}
+
+/// The link is activated by the press.
+pub struct LinkActivated {
+    pub entities: [Entity; 1],
+    /// The `href` of the activated link.
+    pub href: String,
+}
+
+impl LinkActivated {
+    pub fn activated(&self) -> bool {
+        true
+    }
+}
+
+impl Signal for LinkActivated {
+    fn sources(&self) -> &[Entity] {
+        &self.entities
+    }
+}
+
+/// The `href`s of the activated links. The links to the visited
+/// targets get the `:visited` state, remove the target to reset it.
+#[derive(Resource, Default)]
+pub struct VisitedLinks(HashSet<String>);
+
+impl VisitedLinks {
+    pub fn contains(&self, href: &str) -> bool {
+        self.0.contains(href)
+    }
+
+    pub fn visit(&mut self, href: &str) {
+        self.0.insert(href.to_string());
+    }
+
+    pub fn remove(&mut self, href: &str) {
+        self.0.remove(href);
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Opens the `url` with the browser: the new tab on the web,
+/// the default application of the OS on the native platforms.
+pub fn open_url(url: &str) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        if let Err(err) = window.open_with_url_and_target(url, "_blank") {
+            error!("Unable to open {url}: {err:?}");
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::process::Command;
+        #[cfg(target_os = "windows")]
+        let result = Command::new("cmd").args(["/C", "start", "", url]).spawn();
+        #[cfg(target_os = "macos")]
+        let result = Command::new("open").arg(url).spawn();
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let result = Command::new("xdg-open").arg(url).spawn();
+        if let Err(err) = result {
+            error!("Unable to open {url}: {err}");
+        }
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(a)]
+#[signal(activate, LinkActivated, activated)]
+/// The `<a>` tag is the pressable text leading to the `href`. The pressed
+/// link emits the `activate` signal (and the [`LinkActivated`] event) and
+/// gets the `:visited` state along with the other links to the same `href`.
+/// The link with the `open` param also opens the `href` with the browser:
+/// ```rust,ignore
+/// eml! {
+///     <span c:credits>
+///         "Made with "<a href="https://bevyengine.org" open>"Bevy"</a>
+///         <a href="licenses" on:activate=connect!(menu, |m: Menu| m.show_licenses())>
+///             "Licenses"
+///         </a>
+///     </span>
+/// }
+/// ```
+pub struct Hyperlink {
+    #[param]
+    pub href: String,
+    #[param]
+    pub open: bool,
+}
+
+impl WidgetBuilder for Hyperlink {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let content = ctx.content();
+        ctx.render(eml! {
+            <span c:link interactable>{content}</span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            a {
+                color: #4f9fdf;
+            }
+            a:visited {
+                color: #9f7fdf;
+            }
+            a:hover {
+                color: #7fbfff;
+            }
+        "#
+    }
+}
+
+fn activate_links(
+    mut pointer: EventReader<PointerInput>,
+    links: Query<&Hyperlink>,
+    parents: Query<&Parent>,
+    mut visited: ResMut<VisitedLinks>,
+    mut events: EventWriter<LinkActivated>,
+) {
+    for ev in pointer.iter().filter(|e| e.pressed()) {
+        let Some(&target) = ev.entities.first() else {
+            continue;
+        };
+        let Some(entity) = std::iter::once(target)
+            .chain(parents.iter_ancestors(target))
+            .find(|e| links.contains(*e))
+        else {
+            continue;
+        };
+        let Ok(link) = links.get(entity) else {
+            continue;
+        };
+        if !visited.contains(&link.href) {
+            visited.visit(&link.href);
+        }
+        if link.open && !link.href.is_empty() {
+            open_url(&link.href);
+        }
+        events.send(LinkActivated {
+            entities: [entity],
+            href: link.href.clone(),
+        });
+    }
+}
+
+fn update_visited_links(
+    mut elements: Elements,
+    visited: Res<VisitedLinks>,
+    links: Query<(Entity, &Hyperlink, ChangeTrackers<Hyperlink>)>,
+) {
+    for (entity, link, tracker) in links.iter() {
+        if visited.is_changed() || tracker.is_changed() {
+            elements.set_state(entity, "visited".as_tag(), visited.contains(&link.href));
+        }
+    }
+}
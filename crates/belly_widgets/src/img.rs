@@ -3,6 +3,7 @@ use std::str::FromStr;
 use belly_core::*;
 use belly_macro::*;
 use bevy::{
+    math::Rect,
     prelude::*,
     utils::{HashMap, HashSet},
 };
@@ -13,6 +14,8 @@ impl Plugin for ImgPlugin {
         app.register_widget::<Img>();
 
         app.init_resource::<ImageRegistry>();
+        app.init_resource::<ImgAtlases>();
+        app.add_system(reload_atlas_imgs.before(load_img));
         app.add_system(load_img);
         app.add_system(update_img_size);
         app.add_system(update_img_layout);
@@ -23,6 +26,76 @@ impl Plugin for ImgPlugin {
 #[derive(Resource, Deref, DerefMut, Default)]
 struct ImageRegistry(HashMap<Handle<Image>, HashSet<Entity>>);
 
+/// The texture atlases the `<img>` tags refer by the `atlas` param. The
+/// `region` param of the tag is the index of the atlas region, the name
+/// given to the region with [`ImgAtlases::name_region`], or the path of the
+/// image the region is built from by the `TextureAtlasBuilder`:
+/// ```rust,ignore
+/// fn setup(mut atlases: ResMut<ImgAtlases>, mut assets: ResMut<Assets<TextureAtlas>>) {
+///     let icons = TextureAtlas::from_grid(texture, Vec2::splat(32.), 8, 8, None, None);
+///     atlases.insert("icons", assets.add(icons));
+///     atlases.name_region("icons", "sword", 12);
+/// }
+/// // <img atlas="icons" region="sword"/>
+/// ```
+#[derive(Resource, Default)]
+pub struct ImgAtlases {
+    atlases: HashMap<String, Handle<TextureAtlas>>,
+    regions: HashMap<(String, String), usize>,
+}
+
+impl ImgAtlases {
+    pub fn insert(&mut self, name: impl Into<String>, atlas: Handle<TextureAtlas>) {
+        self.atlases.insert(name.into(), atlas);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Handle<TextureAtlas>> {
+        self.regions.retain(|(atlas, _), _| atlas != name);
+        self.atlases.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Handle<TextureAtlas>> {
+        self.atlases.get(name)
+    }
+
+    /// Names the region of the atlas, so the `<img>` could refer it by the name.
+    pub fn name_region(
+        &mut self,
+        atlas: impl Into<String>,
+        region: impl Into<String>,
+        index: usize,
+    ) {
+        self.regions.insert((atlas.into(), region.into()), index);
+    }
+
+    /// Returns the texture of the atlas and the rect of the region in it,
+    /// the empty region stands for the whole texture.
+    fn resolve(
+        &self,
+        atlas: &str,
+        region: &str,
+        atlases: &Assets<TextureAtlas>,
+        asset_server: &AssetServer,
+    ) -> Option<(Handle<Image>, Option<Rect>)> {
+        let texture_atlas = atlases.get(self.atlases.get(atlas)?)?;
+        let texture = texture_atlas.texture.clone();
+        if region.is_empty() {
+            return Some((texture, None));
+        }
+        let index = region
+            .parse::<usize>()
+            .ok()
+            .or_else(|| {
+                self.regions
+                    .get(&(atlas.to_string(), region.to_string()))
+                    .copied()
+            })
+            .or_else(|| texture_atlas.get_texture_index(&asset_server.get_handle(region)))?;
+        let rect = texture_atlas.textures.get(index)?;
+        Some((texture, Some(*rect)))
+    }
+}
+
 // #[derive(Even)]
 pub enum ImgEvent {
     Loaded(Vec<Entity>),
@@ -66,12 +139,13 @@ pub enum ImgMode {
 impl FromStr for ImgMode {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // the css `object-fit` names are accepted as well
         match s {
             "" => Ok(ImgMode::Fit),
-            "fit" => Ok(ImgMode::Fit),
+            "fit" | "contain" => Ok(ImgMode::Fit),
             "cover" => Ok(ImgMode::Cover),
-            "stretch" => Ok(ImgMode::Stretch),
-            "source" => Ok(ImgMode::Source),
+            "stretch" | "fill" => Ok(ImgMode::Stretch),
+            "source" | "none" => Ok(ImgMode::Source),
             err => Err(format!("Can't parse `{}` as ImgMode", err)),
         }
     }
@@ -97,15 +171,69 @@ impl From<ImgMode> for Variant {
     }
 }
 
+/// Mirrors the image shown by the `<img>`, the content of the tag is
+/// not mirrored.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum ImgFlip {
+    #[default]
+    None,
+    /// Flips the image horizontally.
+    X,
+    /// Flips the image vertically.
+    Y,
+    Both,
+}
+
+impl ImgFlip {
+    fn x(&self) -> bool {
+        matches!(self, ImgFlip::X | ImgFlip::Both)
+    }
+
+    fn y(&self) -> bool {
+        matches!(self, ImgFlip::Y | ImgFlip::Both)
+    }
+}
+
+impl FromStr for ImgFlip {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" | "none" => Ok(ImgFlip::None),
+            "x" => Ok(ImgFlip::X),
+            "y" => Ok(ImgFlip::Y),
+            "both" => Ok(ImgFlip::Both),
+            err => Err(format!("Can't parse `{}` as ImgFlip", err)),
+        }
+    }
+}
+
+impl TryFrom<Variant> for ImgFlip {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value.get_or_parse()
+    }
+}
+
+impl From<ImgFlip> for Variant {
+    fn from(flip: ImgFlip) -> Self {
+        Variant::Boxed(Box::new(flip))
+    }
+}
+
 #[derive(Component, Widget)]
 /// The `<img>` tag is used to load image and show it content on the UI screen.
-/// The `<img>` tag has two properties:
+/// The `<img>` tag has the following properties:
 /// - `src`: Specifies the path to the image
-/// - `mode`: Specifies how an image should fits the space:
-///   - `fit`: resize the image to fit the box keeping it aspect ratio
+/// - `mode`: Specifies how an image should fits the space (the css
+///   `object-fit` names are given in the brackets):
+///   - `fit` (`contain`): resize the image to fit the box keeping it aspect ratio
 ///   - `cover`: resize the image to cover the box keeping it aspect ratio
-///   - `stretch`: resize image to take all the space ignoring the aspect ratio
-///   - `source`: do not resize the imagezzz
+///   - `stretch` (`fill`): resize image to take all the space ignoring the aspect ratio
+///   - `source` (`none`): do not resize the image
+/// - `modulate`: the color the image is tinted with
+/// - `flip`: mirrors the image by the `x`, the `y` or `both` axes
+/// - `atlas` and `region`: show the region of the texture atlas registered
+///   in the [`ImgAtlases`] instead of the `src` image
 #[alias(img)]
 #[signal(load, ImgEvent, loaded)]
 #[signal(unload, ImgEvent, unloaded)]
@@ -116,44 +244,93 @@ pub struct Img {
     pub mode: ImgMode,
     #[param]
     //#[bind(to entity@BacktroundColor:0)]
-    #[bindto(entity, BackgroundColor:0)]
+    #[bindto(picture, BackgroundColor:0)]
     pub modulate: Color,
+    #[param]
+    pub flip: ImgFlip,
+    #[param]
+    pub atlas: String,
+    #[param]
+    pub region: String,
     handle: Handle<Image>,
     entity: Entity,
+    /// The leaf node drawing the texture, so it could be mirrored
+    /// and shifted to the region without the content.
+    picture: Entity,
     size: Vec2,
+    /// The region of the texture shown by the image.
+    rect: Option<Rect>,
+    texture_size: Vec2,
 }
 
 impl WidgetBuilder for Img {
     fn setup(&mut self, ctx: &mut ElementContext) {
         let content = ctx.content();
-        ctx.commands().entity(self.entity).insert(ImageBundle {
+        ctx.commands().entity(self.entity).insert(NodeBundle {
             style: Style {
                 display: Display::None,
+                overflow: Overflow::Hidden,
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            ..default()
+        });
+        ctx.commands().entity(self.picture).insert(ImageBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
                 ..default()
             },
             ..default()
         });
         ctx.insert(ElementBundle::default())
             .push_children(&[self.entity]);
-        ctx.commands().entity(self.entity).push_children(&content);
+        ctx.commands()
+            .entity(self.entity)
+            .push_children(&[self.picture])
+            .push_children(&content);
+    }
+}
+
+/// Reloads the images showing the atlases when the atlases are changed.
+fn reload_atlas_imgs(
+    atlases: Res<ImgAtlases>,
+    mut atlas_events: EventReader<AssetEvent<TextureAtlas>>,
+    mut elements: Query<&mut Img>,
+) {
+    let changed = atlas_events.iter().count() > 0;
+    if !changed && !atlases.is_changed() {
+        return;
+    }
+    for mut img in elements.iter_mut().filter(|img| !img.atlas.is_empty()) {
+        img.set_changed();
     }
 }
 
 fn load_img(
     asset_server: Res<AssetServer>,
+    atlases: Res<ImgAtlases>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
     mut elements: Query<(Entity, &mut Img), Changed<Img>>,
-    mut images: Query<(&mut UiImage, &mut Style)>,
+    mut images: Query<&mut UiImage>,
+    mut styles: Query<&mut Style>,
     mut registry: ResMut<ImageRegistry>,
     assets: Res<Assets<Image>>,
     mut events: EventWriter<AssetEvent<Image>>,
     mut signals: EventWriter<ImgEvent>,
 ) {
     for (entity, mut img) in elements.iter_mut() {
-        let handle = if img.src.is_empty() {
-            Handle::default()
+        let (handle, rect) = if !img.atlas.is_empty() {
+            atlases
+                .resolve(&img.atlas, &img.region, &texture_atlases, &asset_server)
+                .unwrap_or_default()
+        } else if img.src.is_empty() {
+            (Handle::default(), None)
         } else {
-            asset_server.load(&img.src)
+            (asset_server.load(&img.src), None)
         };
+        if img.rect != rect {
+            img.rect = rect;
+        }
         if handle != img.handle {
             if assets.contains(&img.handle) {
                 signals.send(ImgEvent::Unloaded(vec![entity]));
@@ -168,8 +345,8 @@ fn load_img(
                 .insert(entity);
             img.handle = handle.clone();
         }
-        let (mut image, mut style) = images.get_mut(img.entity).unwrap();
-        image.0 = handle.clone();
+        images.get_mut(img.picture).unwrap().0 = handle.clone();
+        let mut style = styles.get_mut(img.entity).unwrap();
 
         // force inner image size recalculation if Image asset already loaded
         if assets.contains(&handle) {
@@ -208,8 +385,12 @@ fn update_img_size(
                 for entity in entities.iter() {
                     let Ok(mut element) = elements.get_mut(*entity) else { continue };
                     let Some(asset) = assets.get(handle) else { continue };
-                    if element.size != asset.size() {
-                        element.size = asset.size();
+                    let size = element.rect.map_or(asset.size(), |rect| rect.size());
+                    if element.size != size {
+                        element.size = size;
+                    }
+                    if element.texture_size != asset.size() {
+                        element.texture_size = asset.size();
                     }
                 }
             }
@@ -220,6 +401,7 @@ fn update_img_size(
 fn update_img_layout(
     elements: Query<(&Img, &Node), Or<(Changed<Img>, Changed<Node>)>>,
     mut styles: Query<&mut Style>,
+    mut transforms: Query<&mut Transform>,
 ) {
     for (element, node) in elements.iter() {
         let Ok(mut style) = styles.get_mut(element.entity) else { continue };
@@ -234,7 +416,7 @@ fn update_img_layout(
             style.display = Display::Flex;
         }
         let aspect = element.size.y / element.size.x;
-        match element.mode {
+        let size = match element.mode {
             ImgMode::Fit => {
                 let (width, height) = if aspect > 1.0 {
                     let width = node.size().x;
@@ -267,6 +449,7 @@ fn update_img_layout(
                 style.margin.bottom = Val::Px(vmargin.max(0.));
                 style.margin.left = Val::Px(hmargin.max(0.));
                 style.margin.right = Val::Px(hmargin.max(0.));
+                Vec2::new(width, height)
             }
             ImgMode::Cover => {
                 let (width, height) = if aspect > 1.0 {
@@ -301,11 +484,13 @@ fn update_img_layout(
                 style.margin.bottom = Val::Px(vmargin.min(0.));
                 style.margin.left = Val::Px(hmargin.min(0.));
                 style.margin.right = Val::Px(hmargin.min(0.));
+                Vec2::new(width, height)
             }
             ImgMode::Stretch => {
                 style.min_size = Size::new(Val::Undefined, Val::Undefined);
                 style.size = Size::new(Val::Percent(100.), Val::Percent(100.));
                 style.margin = UiRect::all(Val::Px(0.));
+                node.size()
             }
             ImgMode::Source => {
                 style.size = Size::new(Val::Px(element.size.x), Val::Px(element.size.y));
@@ -316,7 +501,45 @@ fn update_img_layout(
                 style.margin.right = Val::Px(hmargin);
                 style.margin.top = Val::Px(vmargin);
                 style.margin.bottom = Val::Px(vmargin);
+                element.size
             }
+        };
+        layout_picture(element, size, &mut styles, &mut transforms);
+    }
+}
+
+/// Scales the texture of the `<img>`, so its region takes the `size`,
+/// and moves the region into the view.
+fn layout_picture(
+    element: &Img,
+    size: Vec2,
+    styles: &mut Query<&mut Style>,
+    transforms: &mut Query<&mut Transform>,
+) {
+    let texture = element.texture_size.max(element.size);
+    let region = element.rect.unwrap_or(Rect {
+        min: Vec2::ZERO,
+        max: element.size,
+    });
+    let scale = size / region.size();
+    // the mirrored texture shows the region from the other side
+    let mut offset = region.min;
+    if element.flip.x() {
+        offset.x = texture.x - region.max.x;
+    }
+    if element.flip.y() {
+        offset.y = texture.y - region.max.y;
+    }
+    if let Ok(mut style) = styles.get_mut(element.picture) {
+        style.size = Size::new(Val::Px(texture.x * scale.x), Val::Px(texture.y * scale.y));
+        style.position.left = Val::Px(-offset.x * scale.x);
+        style.position.top = Val::Px(-offset.y * scale.y);
+    }
+    if let Ok(mut transform) = transforms.get_mut(element.picture) {
+        let flip = |flipped: bool| if flipped { -1. } else { 1. };
+        let mirror = Vec3::new(flip(element.flip.x()), flip(element.flip.y()), 1.);
+        if transform.scale != mirror {
+            transform.scale = mirror;
         }
     }
 }
@@ -14,12 +14,15 @@ impl Plugin for ButtonPlugin {
         app.add_event::<BtnEvent>();
         app.init_resource::<BtnGroups>();
         app.register_widget::<Btn>();
+        app.register_widget::<toolbar>();
         app.add_system(process_btngroups_system);
+        app.add_system(sync_btngroups_system.after(process_btngroups_system));
         app.add_system(force_btngroups_reconfiguration_system);
         app.add_system_to_stage(
             CoreStage::PreUpdate,
             handle_input_system
                 .after(input::Label::Signals)
+                .after(input::Label::Shortcuts)
                 .label(Label::HandleInput),
         );
         app.add_system_to_stage(
@@ -28,6 +31,10 @@ impl Plugin for ButtonPlugin {
                 .after(Label::HandleInput)
                 .label(Label::HadnleStates),
         );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            request_feedback_system.after(Label::HandleInput),
+        );
     }
 }
 
@@ -298,11 +305,44 @@ impl WidgetBuilder for Btn {
 
 #[derive(Component, Widget)]
 #[alias(buttongroup)]
+/// The `<buttongroup>` tag makes its `<button>` children select one of
+/// them: the pressed button stays pressed and the bindable `value` is
+/// the `value` of the pressed button. The group with the `multiple` param
+/// turns its buttons into toggles, its `value` is the space separated
+/// values of the pressed buttons. The pressed buttons of the group get
+/// the `:selected` state:
+/// ```rust,ignore
+/// eml! {
+///     <buttongroup multiple bind:value=to!(editor, Editor:overlays)>
+///         <button value="grid">"Grid"</button>
+///         <button value="gizmos">"Gizmos"</button>
+///     </buttongroup>
+/// }
+/// ```
 pub struct BtnGroup {
     #[param]
     pub value: String,
+    #[param]
+    pub multiple: bool,
 
     configurated: bool,
+    /// The buttons found by the configuration.
+    buttons: Vec<Entity>,
+    /// The value the buttons are pressed by.
+    shown: Option<String>,
+}
+
+impl BtnGroup {
+    /// Tells if the button with the `value` is selected by the group.
+    pub fn is_selected(&self, value: &str) -> bool {
+        if value.is_empty() {
+            false
+        } else if self.multiple {
+            self.value.split_whitespace().any(|v| v == value)
+        } else {
+            self.value == value
+        }
+    }
 }
 
 impl WidgetBuilder for BtnGroup {
@@ -314,6 +354,48 @@ impl WidgetBuilder for BtnGroup {
     }
 }
 
+#[widget]
+#[extends(descriptor=BtnGroup)]
+#[style("flex-wrap: nowrap")]
+#[style("flex-basis: auto")]
+#[style("align-items: center")]
+#[style("padding: 2px")]
+#[style("background-color: #bfbfbf")]
+#[style(
+    "toolbar button",
+    "  min-width: 32px",
+    "  min-height: 32px",
+    "  margin: 1px"
+)]
+#[style(
+    "toolbar .toolbar-separator",
+    "  width: 1px",
+    "  align-self: stretch",
+    "  margin: 2px 4px",
+    "  background-color: #7f7f7f"
+)]
+/// The `<toolbar>` tag is the `<buttongroup>` laid out as the bar of
+/// the tool buttons. It selects one tool at a time or, with the `multiple`
+/// param, toggles any number of them, the selected tools get the
+/// `:selected` state. The `.toolbar-separator` span divides the tools:
+/// ```rust,ignore
+/// eml! {
+///     <toolbar bind:value=to!(editor, Editor:tool)>
+///         <button value="select">"Select"</button>
+///         <button value="move">"Move"</button>
+///         <span c:toolbar-separator/>
+///         <button value="paint">"Paint"</button>
+///     </toolbar>
+/// }
+/// ```
+fn toolbar(ctx: &mut ElementContext) {
+    let content = ctx.content();
+    let params = ctx.params();
+    ctx.render(eml! {
+        <buttongroup c:toolbar params=params>{content}</buttongroup>
+    });
+}
+
 struct BtnGroupState {
     selected: Entity,
     buttons: HashSet<Entity>,
@@ -392,6 +474,7 @@ impl RepeatState {
 
 fn handle_input_system(
     mut pointer_events: EventReader<PointerInput>,
+    mut shortcuts: EventReader<ShortcutEvent>,
     mut button_events: EventWriter<BtnEvent>,
     mut buttons: Query<&mut Btn>,
     mut groups: ResMut<BtnGroups>,
@@ -472,6 +555,29 @@ fn handle_input_system(
             }
         }
     }
+    // the shortcut presses the button at once, whatever the mode is
+    for entity in shortcuts.iter().flat_map(|e| e.sources()) {
+        let Ok(mut button) = buttons.get_mut(*entity) else {
+            continue;
+        };
+        match &button.mode {
+            BtnMode::Toggle if button.pressed => {
+                button.pressed = false;
+                button_events.send(BtnEvent::Released([*entity]));
+            }
+            BtnMode::Toggle => {
+                button.pressed = true;
+                button_events.send(BtnEvent::Pressed([*entity]));
+            }
+            BtnMode::Group(group) => {
+                if !button.pressed {
+                    state_changes.insert(group.clone(), *entity);
+                    button_events.send(BtnEvent::Pressed([*entity]));
+                }
+            }
+            _ => button_events.send(BtnEvent::Pressed([*entity])),
+        }
+    }
     for (group, pressed_entity) in state_changes.drain() {
         if let BtnModeGroup::Entity(btn_group_id) = &group {
             if let Ok(mut btn_group) = btn_groups.get_mut(*btn_group_id) {
@@ -497,6 +603,46 @@ fn handle_input_system(
     }
 }
 
+/// Confirms the presses and the releases of the buttons. The buttons
+/// the pointer went down on but never pressed (the pointer was lifted
+/// outside of them) are cancelled. The instant and repeat buttons are
+/// pressed on down, so they are never cancelled.
+fn request_feedback_system(
+    mut pointer_events: EventReader<PointerInput>,
+    mut button_events: EventReader<BtnEvent>,
+    mut feedback: EventWriter<FeedbackRequest>,
+    buttons: Query<(), With<Btn>>,
+    mut pending: Local<HashSet<Entity>>,
+) {
+    let mut lifted = false;
+    for event in pointer_events.iter() {
+        let sources = event.sources().iter().filter(|e| buttons.contains(**e));
+        match event.data {
+            PointerInputData::Down { .. } => pending.extend(sources),
+            PointerInputData::Pressed { .. } => sources.for_each(|e| {
+                pending.remove(e);
+            }),
+            PointerInputData::Up { .. } => lifted = true,
+            _ => (),
+        }
+    }
+    for event in button_events.iter() {
+        let action = match event {
+            BtnEvent::Pressed(_) => FeedbackAction::Confirm,
+            BtnEvent::Released(_) => FeedbackAction::Release,
+        };
+        for source in event.sources() {
+            pending.remove(source);
+            feedback.send(FeedbackRequest::new(action, *source));
+        }
+    }
+    if lifted {
+        for source in pending.drain() {
+            feedback.send(FeedbackRequest::new(FeedbackAction::Cancel, source));
+        }
+    }
+}
+
 fn handle_states_system(
     mut groups: ResMut<BtnGroups>,
     mut elements: Elements,
@@ -548,13 +694,35 @@ fn process_btngroups_system(
             continue;
         }
         group.configurated = true;
+        group.buttons = find_buttons(entity, &buttons, &children);
+        group.shown = None;
+
+        if group.multiple {
+            let mut pressed_values = vec![];
+            for btnid in group.buttons.iter() {
+                let Ok(mut btn) = buttons.get_mut(*btnid) else {
+                    continue;
+                };
+                if btn.mode != BtnMode::Toggle {
+                    btn.mode = BtnMode::Toggle;
+                }
+                if btn.pressed && !btn.value.is_empty() {
+                    pressed_values.push(btn.value.clone());
+                }
+            }
+            // the buttons pressed in the markup are kept until the value is set
+            if group.value.is_empty() && !pressed_values.is_empty() {
+                group.value = pressed_values.join(" ");
+            }
+            continue;
+        }
 
         let mode_group = BtnModeGroup::Entity(entity);
         let mode = BtnMode::Group(mode_group.clone());
         let mut default_pressed = None;
         let mut found_state = None;
         let mut pressed_value = None;
-        for btnid in find_buttons(entity, &buttons, &children) {
+        for btnid in group.buttons.clone() {
             let state = groups
                 .entry(mode_group.clone())
                 .or_insert_with(|| BtnGroupState::single(btnid));
@@ -598,6 +766,59 @@ fn process_btngroups_system(
     }
 }
 
+/// Presses the buttons of the groups by the changed `value`, updates the
+/// `value` of the `multiple` groups by the toggled buttons and sets the
+/// `:selected` state of the buttons.
+fn sync_btngroups_system(
+    mut elements: Elements,
+    mut btn_groups: Query<&mut BtnGroup>,
+    mut buttons: Query<&mut Btn>,
+) {
+    for mut group in btn_groups.iter_mut() {
+        if !group.configurated {
+            continue;
+        }
+        if group.shown.as_ref() != Some(&group.value) {
+            // the value is set from the code or by the binding, the single
+            // selection group keeps its button if no button has the value
+            let known = group.buttons.iter().any(|btnid| {
+                buttons
+                    .get(*btnid)
+                    .map_or(false, |btn| group.is_selected(&btn.value))
+            });
+            if group.multiple || known {
+                for btnid in group.buttons.iter() {
+                    let Ok(mut btn) = buttons.get_mut(*btnid) else {
+                        continue;
+                    };
+                    let pressed = group.is_selected(&btn.value);
+                    if btn.pressed != pressed {
+                        btn.pressed = pressed;
+                    }
+                }
+            }
+            group.shown = Some(group.value.clone());
+        } else if group.multiple {
+            let value = group
+                .buttons
+                .iter()
+                .filter_map(|btnid| buttons.get(*btnid).ok())
+                .filter(|btn| btn.pressed && !btn.value.is_empty())
+                .map(|btn| btn.value.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            if group.value != value {
+                group.value = value.clone();
+                group.shown = Some(value);
+            }
+        }
+        for btnid in group.buttons.iter() {
+            let pressed = buttons.get(*btnid).map_or(false, |btn| btn.pressed);
+            elements.set_state(*btnid, "selected".as_tag(), pressed);
+        }
+    }
+}
+
 fn force_btngroups_reconfiguration_system(
     mut btn_gropus: Query<&mut BtnGroup>,
     new_buttons: Query<Entity, Added<Btn>>,
@@ -0,0 +1,339 @@
+use crate::input::text::{TextInput, TextInputEvent};
+use belly_core::{ess::ColorFromHexExtension, *};
+use belly_macro::*;
+use bevy::prelude::*;
+
+/// The number of the hue columns of the hue/saturation area.
+const AREA_COLUMNS: usize = 12;
+/// The number of the saturation rows of the hue/saturation area.
+const AREA_ROWS: usize = 6;
+
+pub(crate) struct ColorPickerPlugin;
+impl Plugin for ColorPickerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ColorPickerEvent>();
+        app.register_widget::<ColorPicker>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_area_input.after(input::Label::Signals),
+        );
+        app.add_system(apply_typed_colors);
+        app.add_system(update_color_pickers.after(apply_typed_colors));
+        app.add_system(update_color_areas.after(update_color_pickers));
+    }
+}
+
+pub enum ColorPickerEvent {
+    /// The color is changed by the user.
+    Changed([Entity; 1]),
+}
+
+impl ColorPickerEvent {
+    pub fn changed(&self) -> bool {
+        match self {
+            ColorPickerEvent::Changed(_) => true,
+        }
+    }
+}
+
+impl Signal for ColorPickerEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            ColorPickerEvent::Changed(source) => source,
+        }
+    }
+}
+
+/// Formats the color as `#rrggbb`, or as `#rrggbbaa` when it is translucent.
+fn hex(color: Color) -> String {
+    let [r, g, b, a] = color.as_rgba_f32().map(|c| (c * 255.).round() as u8);
+    if a == 255 {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    } else {
+        format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(colorpicker)]
+#[signal(change, ColorPickerEvent, changed)]
+/// The `<colorpicker>` tag edits the bindable `value` color. The hue and
+/// the saturation are picked in the `.colorpicker-area` (the hue grows to
+/// the right, the saturation grows to the top), the lightness and the alpha
+/// are set by the sliders, the hex input accepts `#rgb`, `#rgba`, `#rrggbb`
+/// and `#rrggbbaa` colors. The `change` signal is emitted when the color is
+/// changed by the user:
+/// ```rust,ignore
+/// eml! {
+///     <colorpicker value=bind!(player, Skin:color)/>
+/// }
+/// ```
+pub struct ColorPicker {
+    #[param]
+    pub value: Color,
+    // the picker keeps the components, so the hue of the gray color
+    // isn't lost when the saturation is zero
+    hue: f32,
+    saturation: f32,
+    lightness: f32,
+    alpha: f32,
+    /// The color the components are taken from.
+    shown: Option<Color>,
+    area: Entity,
+    thumb: Entity,
+    preview: Entity,
+    hex: Entity,
+}
+
+impl ColorPicker {
+    /// The color made of the components.
+    fn compose(&self) -> Color {
+        Color::hsla(self.hue, self.saturation, self.lightness, self.alpha).as_rgba()
+    }
+
+    /// Takes the components from the color.
+    fn decompose(&mut self, color: Color) {
+        let Color::Hsla {
+            hue,
+            saturation,
+            lightness,
+            alpha,
+        } = color.as_hsla()
+        else {
+            return;
+        };
+        if saturation > 0. && lightness > 0. && lightness < 1. {
+            self.hue = hue;
+        }
+        self.saturation = saturation;
+        self.lightness = lightness;
+        self.alpha = alpha;
+    }
+}
+
+impl WidgetBuilder for ColorPicker {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let this = ctx.entity();
+        let area = self.area;
+        let thumb = self.thumb;
+        let preview = self.preview;
+        let hex = self.hex;
+        let rows: Vec<_> = (0..AREA_ROWS)
+            .map(|_| {
+                let cells: Vec<_> = (0..AREA_COLUMNS)
+                    .map(|_| {
+                        eml! {
+                            <span c:colorpicker-cell s:background-color=managed()/>
+                        }
+                    })
+                    .collect();
+                eml! {
+                    <span c:colorpicker-area-row>{cells}</span>
+                }
+            })
+            .collect();
+        ctx.render(eml! {
+            <span c:colorpicker>
+                <span {area} c:colorpicker-area interactable>
+                    {rows}
+                    <span {thumb} c:colorpicker-thumb s:left=managed() s:top=managed()/>
+                </span>
+                <slider c:colorpicker-lightness minimum=0. maximum=1.
+                    value=bind!(this, ColorPicker:lightness)/>
+                <slider c:colorpicker-alpha minimum=0. maximum=1.
+                    value=bind!(this, ColorPicker:alpha)/>
+                <span c:colorpicker-footer>
+                    <span {preview} c:colorpicker-preview s:background-color=managed()/>
+                    <textinput {hex} c:colorpicker-hex maxlength=9/>
+                </span>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            colorpicker {
+                flex-direction: column;
+                width: 240px;
+                padding: 4px;
+                background-color: #dfdfdf;
+            }
+            colorpicker .colorpicker-area {
+                flex-direction: column;
+                height: 120px;
+                border: 1px solid #2f2f2f;
+            }
+            colorpicker .colorpicker-area-row {
+                flex-grow: 1;
+            }
+            colorpicker .colorpicker-cell {
+                flex-grow: 1;
+            }
+            colorpicker .colorpicker-thumb {
+                position-type: absolute;
+                width: 10px;
+                height: 10px;
+                margin: -5px 0px 0px -5px;
+                border: 1px solid white;
+            }
+            colorpicker .colorpicker-footer {
+                align-items: center;
+            }
+            colorpicker .colorpicker-preview {
+                width: 32px;
+                height: 32px;
+                margin: 5px;
+                border: 1px solid #2f2f2f;
+            }
+            colorpicker .colorpicker-hex {
+                flex-grow: 1;
+            }
+        "#
+    }
+}
+
+/// Syncs the components and the `value` of the pickers: the changed value
+/// is split into the components, the changed components make the value.
+fn update_color_pickers(
+    mut pickers: Query<(Entity, &mut ColorPicker), Changed<ColorPicker>>,
+    mut inputs: Query<&mut TextInput>,
+    mut colors: Query<&mut BackgroundColor>,
+    mut styles: Query<&mut Style>,
+    mut events: EventWriter<ColorPickerEvent>,
+) {
+    for (entity, mut picker) in pickers.iter_mut() {
+        let value = picker.value;
+        if picker.shown != Some(value) {
+            picker.decompose(value);
+        } else {
+            let composed = picker.compose();
+            if composed != value {
+                picker.value = composed;
+                events.send(ColorPickerEvent::Changed([entity]));
+            }
+        }
+        let value = picker.value;
+        picker.shown = Some(value);
+        if let Ok(mut preview) = colors.get_mut(picker.preview) {
+            preview.0 = value;
+        }
+        if let Ok(mut input) = inputs.get_mut(picker.hex) {
+            let text = hex(value);
+            let typed = Color::try_from_hex(&input.value).ok();
+            if typed.map(|c| c.as_rgba()) != Some(value) {
+                input.value = text;
+            }
+        }
+        if let Ok(mut style) = styles.get_mut(picker.thumb) {
+            let left = Val::Percent(picker.hue / 360. * 100.);
+            let top = Val::Percent((1. - picker.saturation) * 100.);
+            if style.position.left != left || style.position.top != top {
+                style.position.left = left;
+                style.position.top = top;
+            }
+        }
+    }
+}
+
+/// Paints the cells of the hue/saturation area with the lightness of the picker.
+fn update_color_areas(
+    pickers: Query<&ColorPicker, Changed<ColorPicker>>,
+    children: Query<&Children>,
+    mut colors: Query<&mut BackgroundColor>,
+) {
+    for picker in pickers.iter() {
+        let Ok(rows) = children.get(picker.area) else {
+            continue;
+        };
+        for (row, cells) in rows
+            .iter()
+            .filter_map(|e| children.get(*e).ok())
+            .enumerate()
+        {
+            let saturation = 1. - (row as f32 + 0.5) / AREA_ROWS as f32;
+            for (column, cell) in cells.iter().enumerate() {
+                let hue = (column as f32 + 0.5) / AREA_COLUMNS as f32 * 360.;
+                let color = Color::hsl(hue, saturation, picker.lightness);
+                let Ok(mut background) = colors.get_mut(*cell) else {
+                    continue;
+                };
+                if background.0 != color {
+                    background.0 = color;
+                }
+            }
+        }
+    }
+}
+
+/// Applies the valid colors typed into the hex inputs.
+fn apply_typed_colors(
+    mut text_events: EventReader<TextInputEvent>,
+    mut pickers: Query<(Entity, &mut ColorPicker)>,
+    inputs: Query<&TextInput>,
+    mut events: EventWriter<ColorPickerEvent>,
+) {
+    for ev in text_events.iter() {
+        let Some((entity, mut picker)) = pickers
+            .iter_mut()
+            .find(|(_, p)| ev.sources().contains(&p.hex))
+        else {
+            continue;
+        };
+        let Ok(input) = inputs.get(picker.hex) else {
+            continue;
+        };
+        let Ok(color) = Color::try_from_hex(&input.value) else {
+            continue;
+        };
+        let color = color.as_rgba();
+        if picker.value != color {
+            picker.value = color;
+            events.send(ColorPickerEvent::Changed([entity]));
+        }
+    }
+}
+
+fn handle_area_input(
+    mut events: EventReader<PointerInput>,
+    mut pickers: Query<(Entity, &mut ColorPicker)>,
+    nodes: Query<(&GlobalTransform, &Node)>,
+    mut active: Local<Option<Entity>>,
+) {
+    for ev in events.iter() {
+        if ev.down() && active.is_none() {
+            let Some(&target) = ev.entities.first() else {
+                continue;
+            };
+            *active = pickers
+                .iter()
+                .find(|(_, picker)| picker.area == target)
+                .map(|(entity, _)| entity);
+        }
+        let Some(entity) = *active else {
+            continue;
+        };
+        if ev.down() || ev.dragging() {
+            let Ok((_, mut picker)) = pickers.get_mut(entity) else {
+                continue;
+            };
+            let Ok((transform, node)) = nodes.get(picker.area) else {
+                continue;
+            };
+            let size = node.size();
+            if size.x <= 0. || size.y <= 0. {
+                continue;
+            }
+            // the node is positioned by its center
+            let start = transform.translation().truncate() - size / 2.;
+            let relative = ((ev.pos - start) / size).clamp(Vec2::ZERO, Vec2::ONE);
+            let (hue, saturation) = (relative.x * 360., 1. - relative.y);
+            if picker.hue != hue || picker.saturation != saturation {
+                picker.hue = hue;
+                picker.saturation = saturation;
+            }
+        }
+        if ev.up() || ev.drag_stop() {
+            *active = None;
+        }
+    }
+}
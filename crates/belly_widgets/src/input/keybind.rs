@@ -0,0 +1,352 @@
+use crate::common::*;
+use belly_core::*;
+use belly_macro::*;
+use bevy::{prelude::*, utils::HashMap};
+use std::fmt::Display;
+
+/// The gamepad axis should be deflected further than this to be captured.
+const AXIS_CAPTURE_THRESHOLD: f32 = 0.5;
+
+const CAPTURE_AXES: [GamepadAxisType; 6] = [
+    GamepadAxisType::LeftStickX,
+    GamepadAxisType::LeftStickY,
+    GamepadAxisType::LeftZ,
+    GamepadAxisType::RightStickX,
+    GamepadAxisType::RightStickY,
+    GamepadAxisType::RightZ,
+];
+
+pub(crate) struct KeybindPlugin;
+impl Plugin for KeybindPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<KeybindEvent>();
+        app.init_resource::<InputMap>();
+        app.init_resource::<InputGlyphs>();
+        app.register_widget::<Keybind>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_keybind_clicks.after(input::Label::Signals),
+        );
+        app.add_system(capture_keybind_input);
+        app.add_system(update_keybind_text.after(capture_keybind_input));
+    }
+}
+
+/// The direction of the gamepad axis bound to the action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+/// The single physical input the action is bound to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButtonType),
+    GamepadAxis(GamepadAxisType, AxisDirection),
+}
+
+impl Display for InputBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputBinding::Key(key) => write!(f, "{key:?}"),
+            InputBinding::Mouse(button) => write!(f, "Mouse {button:?}"),
+            InputBinding::GamepadButton(button) => write!(f, "{button:?}"),
+            InputBinding::GamepadAxis(axis, AxisDirection::Positive) => write!(f, "{axis:?}+"),
+            InputBinding::GamepadAxis(axis, AxisDirection::Negative) => write!(f, "{axis:?}-"),
+        }
+    }
+}
+
+/// The storage of the action bindings the `<keybind>` widgets read & write.
+/// Implement it over the input manager used by the game and replace the
+/// default [`SimpleInputMap`] with [`InputMap::new`].
+pub trait InputMapBackend: Send + Sync + 'static {
+    /// Returns the binding of the `action`.
+    fn binding(&self, action: &str) -> Option<InputBinding>;
+    /// Binds the `action` to the `binding`.
+    fn bind(&mut self, action: &str, binding: InputBinding);
+    /// Removes the binding of the `action`.
+    fn unbind(&mut self, action: &str);
+    /// Returns the action the `binding` is used by, if any.
+    fn action(&self, binding: InputBinding) -> Option<String>;
+}
+
+/// The default [`InputMapBackend`]: each action is bound to the single input.
+#[derive(Default, Debug, Clone)]
+pub struct SimpleInputMap(pub HashMap<String, InputBinding>);
+
+impl InputMapBackend for SimpleInputMap {
+    fn binding(&self, action: &str) -> Option<InputBinding> {
+        self.0.get(action).copied()
+    }
+
+    fn bind(&mut self, action: &str, binding: InputBinding) {
+        self.0.insert(action.to_string(), binding);
+    }
+
+    fn unbind(&mut self, action: &str) {
+        self.0.remove(action);
+    }
+
+    fn action(&self, binding: InputBinding) -> Option<String> {
+        self.0
+            .iter()
+            .find(|(_, b)| **b == binding)
+            .map(|(action, _)| action.clone())
+    }
+}
+
+/// The input map backend used by the `<keybind>` widgets.
+#[derive(Resource, Deref, DerefMut)]
+pub struct InputMap(Box<dyn InputMapBackend>);
+
+impl InputMap {
+    pub fn new(backend: impl InputMapBackend) -> InputMap {
+        InputMap(Box::new(backend))
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        InputMap::new(SimpleInputMap::default())
+    }
+}
+
+/// Text (or icon font characters) shown for the bindings. Bindings
+/// without the glyph are shown using their [`Display`] implementation.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct InputGlyphs(pub HashMap<InputBinding, String>);
+
+impl InputGlyphs {
+    pub fn glyph(&self, binding: InputBinding) -> String {
+        self.get(&binding)
+            .cloned()
+            .unwrap_or_else(|| binding.to_string())
+    }
+}
+
+pub enum KeybindEvent {
+    Changed([Entity; 1]),
+    Cancelled([Entity; 1]),
+}
+
+impl KeybindEvent {
+    pub fn changed(&self) -> bool {
+        matches!(self, KeybindEvent::Changed(_))
+    }
+
+    pub fn cancelled(&self) -> bool {
+        matches!(self, KeybindEvent::Cancelled(_))
+    }
+}
+
+impl Signal for KeybindEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            KeybindEvent::Changed(source) => source,
+            KeybindEvent::Cancelled(source) => source,
+        }
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Debug)]
+pub enum KeybindState {
+    #[default]
+    Idle,
+    /// Waiting for the input since the moment in seconds.
+    Capturing(f32),
+    /// The captured binding is used by the other action.
+    Conflict(InputBinding, String),
+}
+
+#[derive(Component, Widget)]
+#[alias(keybind)]
+#[signal(change, KeybindEvent, changed)]
+#[signal(cancel, KeybindEvent, cancelled)]
+/// The `<keybind>` tag shows the binding of the `action` from the [`InputMap`]
+/// using the [`InputGlyphs`]. Click the widget to capture the next pressed key,
+/// mouse button, gamepad button or deflected gamepad axis. If the captured
+/// input is used by another action the widget asks to click once more to
+/// replace the binding. `Escape` cancels the capture.
+///
+/// The widget has the `:capturing` state while waiting for the input and
+/// the `:conflict` state while waiting for the confirmation.
+pub struct Keybind {
+    #[param]
+    pub action: String,
+    pub state: KeybindState,
+    label: Entity,
+}
+
+impl WidgetBuilder for Keybind {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let label = self.label;
+        ctx.render(eml! {
+            <span c:keybind interactable>
+                <label {label} c:keybind-text/>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r##"
+            keybind {
+                min-width: 80px;
+                min-height: 32px;
+                margin: 5px;
+                padding: 4px 8px;
+                justify-content: center;
+                align-items: center;
+                background-color: #dfdfdf;
+                border: 1px solid #2f2f2f;
+            }
+            keybind:hover {
+                background-color: white;
+            }
+            keybind:capturing {
+                background-color: #bfbfbf;
+            }
+            keybind:conflict {
+                border-color: #bf2f2f;
+            }
+            keybind .keybind-text {
+                color: #2f2f2f;
+            }
+        "##
+    }
+}
+
+fn handle_keybind_clicks(
+    mut pointer_events: EventReader<PointerInput>,
+    mut keybinds: Query<&mut Keybind>,
+    mut input_map: ResMut<InputMap>,
+    mut events: EventWriter<KeybindEvent>,
+    time: Res<Time>,
+) {
+    for event in pointer_events.iter().filter(|e| e.pressed()) {
+        for entity in event.entities.iter() {
+            let Ok(mut keybind) = keybinds.get_mut(*entity) else { continue };
+            match keybind.state.clone() {
+                KeybindState::Idle => {
+                    keybind.state = KeybindState::Capturing(time.elapsed_seconds());
+                }
+                KeybindState::Conflict(binding, other) => {
+                    input_map.unbind(&other);
+                    input_map.bind(&keybind.action, binding);
+                    keybind.state = KeybindState::Idle;
+                    events.send(KeybindEvent::Changed([*entity]));
+                }
+                // the mouse buttons are captured by `capture_keybind_input`
+                KeybindState::Capturing(_) => {}
+            }
+        }
+    }
+}
+
+fn capture_keybind_input(
+    mut keybinds: Query<(Entity, &mut Keybind)>,
+    mut input_map: ResMut<InputMap>,
+    mut events: EventWriter<KeybindEvent>,
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<Gamepads>,
+    mut feedback: EventWriter<FeedbackRequest>,
+) {
+    for (entity, mut keybind) in keybinds.iter_mut() {
+        let capturing = match keybind.state {
+            KeybindState::Idle => continue,
+            KeybindState::Capturing(since) => since < time.elapsed_seconds(),
+            KeybindState::Conflict(..) => false,
+        };
+        if keys.just_pressed(KeyCode::Escape) {
+            keybind.state = KeybindState::Idle;
+            events.send(KeybindEvent::Cancelled([entity]));
+            feedback.send(FeedbackRequest::new(FeedbackAction::Cancel, entity));
+            continue;
+        }
+        if !capturing {
+            continue;
+        }
+        let axis = || {
+            gamepads.iter().find_map(|gamepad| {
+                CAPTURE_AXES.iter().find_map(|axis| {
+                    let value = gamepad_axes.get(GamepadAxis::new(gamepad, *axis))?;
+                    match value {
+                        v if v > AXIS_CAPTURE_THRESHOLD => Some((*axis, AxisDirection::Positive)),
+                        v if v < -AXIS_CAPTURE_THRESHOLD => Some((*axis, AxisDirection::Negative)),
+                        _ => None,
+                    }
+                })
+            })
+        };
+        // mouse buttons are captured on release, so the click which
+        // confirms the conflict is not treated as the new binding
+        let captured = keys
+            .get_just_pressed()
+            .next()
+            .map(|key| InputBinding::Key(*key))
+            .or_else(|| {
+                mouse
+                    .get_just_released()
+                    .next()
+                    .map(|button| InputBinding::Mouse(*button))
+            })
+            .or_else(|| {
+                gamepad_buttons
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| InputBinding::GamepadButton(button.button_type))
+            })
+            .or_else(|| axis().map(|(axis, dir)| InputBinding::GamepadAxis(axis, dir)));
+        let Some(binding) = captured else { continue };
+        match input_map.action(binding) {
+            Some(other) if other != keybind.action => {
+                keybind.state = KeybindState::Conflict(binding, other);
+            }
+            _ => {
+                input_map.bind(&keybind.action, binding);
+                keybind.state = KeybindState::Idle;
+                events.send(KeybindEvent::Changed([entity]));
+            }
+        }
+    }
+}
+
+fn update_keybind_text(
+    mut elements: Elements,
+    keybinds: Query<(Entity, &Keybind, ChangeTrackers<Keybind>)>,
+    mut labels: Query<&mut Label>,
+    input_map: Res<InputMap>,
+    glyphs: Res<InputGlyphs>,
+) {
+    let refresh = input_map.is_changed() || glyphs.is_changed();
+    for (entity, keybind, tracker) in keybinds.iter() {
+        if !refresh && !tracker.is_changed() {
+            continue;
+        }
+        let text = match &keybind.state {
+            KeybindState::Idle => match input_map.binding(&keybind.action) {
+                Some(binding) => glyphs.glyph(binding),
+                None => "-".to_string(),
+            },
+            KeybindState::Capturing(_) => "Press any key".to_string(),
+            KeybindState::Conflict(binding, other) => {
+                format!("{} is used by {other}", glyphs.glyph(*binding))
+            }
+        };
+        let capturing = matches!(keybind.state, KeybindState::Capturing(_));
+        let conflict = matches!(keybind.state, KeybindState::Conflict(..));
+        elements.set_state(entity, "capturing".as_tag(), capturing);
+        elements.set_state(entity, "conflict".as_tag(), conflict);
+        let Ok(mut label) = labels.get_mut(keybind.label) else { continue };
+        if label.value != text {
+            label.value = text;
+        }
+    }
+}
@@ -1,11 +1,20 @@
 pub mod button;
+pub mod colorpicker;
+pub mod keybind;
+pub mod range_slider;
 pub mod slider;
 pub mod text;
 
 use bevy::prelude::Plugin;
 pub use button::*;
+pub use colorpicker::*;
+pub use keybind::*;
+pub use range_slider::*;
 pub use slider::*;
+pub use text::Clipboard;
+pub use text::InputFilter;
 pub use text::TextInput;
+pub use text::TextInputEvent;
 pub use text::TextInputWidgetExtension;
 
 pub struct InputPlugins;
@@ -14,5 +23,8 @@ impl Plugin for InputPlugins {
         app.add_plugin(text::TextInputPlugin);
         app.add_plugin(button::ButtonPlugin);
         app.add_plugin(slider::SliderPlugin);
+        app.add_plugin(range_slider::RangeSliderPlugin);
+        app.add_plugin(keybind::KeybindPlugin);
+        app.add_plugin(colorpicker::ColorPickerPlugin);
     }
 }
@@ -0,0 +1,675 @@
+use std::str::FromStr;
+
+use crate::common::*;
+use crate::input::button::*;
+use crate::range::LayoutMode;
+use belly_core::{eml::build::FromWorldAndParam, relations::bind::AsTransformer, *};
+use belly_macro::*;
+use bevy::{prelude::*, utils::HashMap};
+
+pub(crate) struct RangeSliderPlugin;
+impl Plugin for RangeSliderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RangeSliderEvent>();
+        app.register_widget::<RangeSlider>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_pointer_input.after(input::Label::Signals),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_keyboard_input.after(input::Label::Focus),
+        );
+        app.add_system(configure_range_slider_layout);
+        app.add_system(update_range_slider_representation);
+    }
+}
+
+pub enum RangeSliderEvent {
+    /// The `low` or the `high` value is changed by the user.
+    Changed([Entity; 1]),
+    /// The user released the thumb.
+    Released([Entity; 1]),
+}
+
+impl RangeSliderEvent {
+    pub fn changed(&self) -> bool {
+        match self {
+            RangeSliderEvent::Changed(_) => true,
+            _ => false,
+        }
+    }
+    pub fn released(&self) -> bool {
+        match self {
+            RangeSliderEvent::Released(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Signal for RangeSliderEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            RangeSliderEvent::Changed(source) => source,
+            RangeSliderEvent::Released(source) => source,
+        }
+    }
+}
+
+/// What happens when the thumb dragged by the user meets the other one.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ThumbCollision {
+    /// The thumb stops at the other one.
+    #[default]
+    Block,
+    /// The thumb pushes the other one up to the limit.
+    Push,
+}
+
+impl From<ThumbCollision> for Variant {
+    fn from(c: ThumbCollision) -> Self {
+        Variant::boxed(c)
+    }
+}
+
+impl FromStr for ThumbCollision {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block" => Ok(ThumbCollision::Block),
+            "push" => Ok(ThumbCollision::Push),
+            s => Err(format!("Don't know how to parse '{s}' as ThumbCollision")),
+        }
+    }
+}
+
+impl TryFrom<Variant> for ThumbCollision {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value.get_or_parse()
+    }
+}
+
+/// The `low..=high` interval limited by the `minimum` and the `maximum`.
+/// The `low` never exceeds the `high - gap`.
+#[derive(Clone, Copy, PartialEq)]
+pub struct LimitedRange {
+    low: f32,
+    high: f32,
+    minimum: f32,
+    maximum: f32,
+    step: f32,
+    gap: f32,
+}
+
+impl FromWorldAndParam for LimitedRange {
+    fn from_world_and_param(_world: &mut World, param: Variant) -> Self {
+        let mut range = LimitedRange {
+            low: 0.0,
+            high: 1.0,
+            minimum: 0.0,
+            maximum: 1.0,
+            step: 0.0,
+            gap: 0.0,
+        };
+        let Variant::Params(mut params) = param else {
+            return range;
+        };
+        let minimum = params.try_get::<f32>("minimum").unwrap_or(0.0);
+        let maximum = params
+            .try_get::<f32>("maximum")
+            .unwrap_or(1.0f32.max(minimum));
+        range.minimum = minimum.min(maximum);
+        range.maximum = maximum.max(minimum);
+        range.step = params.try_get::<f32>("step").unwrap_or(0.0).max(0.0);
+        range.gap = params
+            .try_get::<f32>("gap")
+            .unwrap_or(0.0)
+            .max(0.0)
+            .min(range.maximum - range.minimum);
+        range.high = range.maximum;
+        range.low = range.minimum;
+        if let Some(high) = params.try_get::<f32>("high") {
+            range.set_high(high);
+        }
+        if let Some(low) = params.try_get::<f32>("low") {
+            range.set_low(low);
+        }
+        range
+    }
+}
+
+impl LimitedRange {
+    pub fn low(&self) -> f32 {
+        self.low
+    }
+
+    pub fn high(&self) -> f32 {
+        self.high
+    }
+
+    pub fn minimum(&self) -> f32 {
+        self.minimum
+    }
+
+    pub fn maximum(&self) -> f32 {
+        self.maximum
+    }
+
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    pub fn gap(&self) -> f32 {
+        self.gap
+    }
+
+    pub fn relative_low(&self) -> f32 {
+        (self.low - self.minimum) / (self.maximum - self.minimum)
+    }
+
+    pub fn relative_high(&self) -> f32 {
+        (self.high - self.minimum) / (self.maximum - self.minimum)
+    }
+
+    fn snap(&self, value: f32) -> f32 {
+        let value = value.min(self.maximum).max(self.minimum);
+        if self.step <= 0.0 {
+            return value;
+        }
+        let steps = ((value - self.minimum) / self.step).round();
+        (self.minimum + steps * self.step).min(self.maximum)
+    }
+
+    /// Sets the `low`, it stops at the `high - gap`.
+    pub fn set_low(&mut self, low: f32) {
+        self.low = self.snap(low.min(self.high - self.gap)).min(self.high);
+    }
+
+    /// Sets the `high`, it stops at the `low + gap`.
+    pub fn set_high(&mut self, high: f32) {
+        self.high = self.snap(high.max(self.low + self.gap)).max(self.low);
+    }
+
+    /// Sets the `low` moving the `high` out of the way.
+    pub fn push_low(&mut self, low: f32) {
+        let low = self.snap(low.min(self.maximum - self.gap));
+        if self.high < low + self.gap {
+            self.high = self.snap(low + self.gap).max(low);
+        }
+        self.low = low;
+    }
+
+    /// Sets the `high` moving the `low` out of the way.
+    pub fn push_high(&mut self, high: f32) {
+        let high = self.snap(high.max(self.minimum + self.gap));
+        if self.low > high - self.gap {
+            self.low = self.snap(high - self.gap).min(high);
+        }
+        self.high = high;
+    }
+
+    pub fn set_minimum(&mut self, minimum: f32) {
+        self.minimum = minimum.min(self.maximum);
+        self.low = self.low.max(self.minimum);
+        self.high = self.high.max(self.low);
+    }
+
+    pub fn set_maximum(&mut self, maximum: f32) {
+        self.maximum = maximum.max(self.minimum);
+        self.high = self.high.min(self.maximum);
+        self.low = self.low.min(self.high);
+    }
+
+    pub fn set_step(&mut self, step: f32) {
+        self.step = step.max(0.0);
+        self.low = self.snap(self.low);
+        self.high = self.snap(self.high).max(self.low);
+    }
+
+    pub fn set_gap(&mut self, gap: f32) {
+        self.gap = gap.max(0.0).min(self.maximum - self.minimum);
+        self.push_low(self.low);
+    }
+}
+
+impl AsTransformer for LimitedRange {
+    type Transformer = LimitedRangeTransformer;
+    fn as_transformer() -> Self::Transformer {
+        LimitedRangeTransformer
+    }
+}
+
+pub struct LimitedRangeTransformer;
+
+macro_rules! impl_transform {
+    ($method:ident, $setter:ident) => {
+        pub fn $method<T: TransformableTo<f32>>(
+            &self,
+        ) -> fn(&T, Prop<LimitedRange>) -> TransformationResult {
+            |source, mut range| {
+                let val = T::transform(source)?;
+                if val != range.$method() {
+                    range.$setter(val);
+                }
+                return Ok(());
+            }
+        }
+    };
+}
+impl LimitedRangeTransformer {
+    impl_transform! { low, set_low }
+    impl_transform! { high, set_high }
+    impl_transform! { minimum, set_minimum }
+    impl_transform! { maximum, set_maximum }
+    impl_transform! { step, set_step }
+    impl_transform! { gap, set_gap }
+}
+
+#[derive(Component, Widget)]
+#[signal(change, RangeSliderEvent, changed)]
+#[signal(release, RangeSliderEvent, released)]
+#[alias(rangeslider)]
+/// The `<rangeslider>` tag is the slider with two thumbs selecting the
+/// interval between the `low` and the `high` values, both could be bound
+/// independently. The `gap` param keeps the thumbs apart, the `collision`
+/// param sets what happens when the dragged thumb meets the other one:
+/// it stops (`block`, the default) or pushes the other thumb (`push`).
+pub struct RangeSlider {
+    #[param(low: f32)]
+    #[param(high: f32)]
+    #[param(minimum: f32)]
+    #[param(maximum: f32)]
+    #[param(step: f32)]
+    #[param(gap: f32)]
+    pub value: LimitedRange,
+
+    #[param]
+    pub mode: LayoutMode,
+
+    #[param]
+    pub collision: ThumbCollision,
+
+    holder: Entity,
+    before: Entity,
+    between: Entity,
+    low_thumb: Entity,
+    high_thumb: Entity,
+}
+
+impl RangeSlider {
+    /// Moves the thumb by the user, the other thumb is handled by
+    /// the `collision` rule.
+    fn move_thumb(&mut self, thumb: Thumb, value: f32) -> bool {
+        let mut range = self.value;
+        match (thumb, self.collision) {
+            (Thumb::Low, ThumbCollision::Block) => range.set_low(value),
+            (Thumb::High, ThumbCollision::Block) => range.set_high(value),
+            (Thumb::Low, ThumbCollision::Push) => range.push_low(value),
+            (Thumb::High, ThumbCollision::Push) => range.push_high(value),
+        }
+        if range == self.value {
+            return false;
+        }
+        self.value = range;
+        true
+    }
+}
+
+impl WidgetBuilder for RangeSlider {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let holder = self.holder;
+        let before = self.before;
+        let between = self.between;
+        let low_thumb = self.low_thumb;
+        let high_thumb = self.high_thumb;
+        // the arrows nudge the thumbs, not the focus
+        let listener = Listen::bubble(ctx.entity(), |ctx: &mut PropagationContext<KeyInput>| {
+            if ctx.event().direction().is_some() {
+                ctx.stop_propagation();
+            }
+        });
+        ctx.commands().add(listener);
+        ctx.render(eml! {
+            <span c:range-slider interactable>
+                <span c:range-slider-track/>
+                <span {holder} c:range-slider-holder s:flex-direction=managed()>
+                    <span {before} c:range-slider-before
+                        s:min-width=managed()
+                        s:min-height=managed()
+                    />
+                    <button {low_thumb} mode="instant" c:range-slider-thumb c:range-slider-low/>
+                    <span {between} c:range-slider-between
+                        s:min-width=managed()
+                        s:min-height=managed()
+                    >
+                        <span c:range-slider-fill/>
+                    </span>
+                    <button {high_thumb} mode="instant" c:range-slider-thumb c:range-slider-high/>
+                </span>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            rangeslider:horizontal {
+                padding: 5px 3px;
+            }
+            rangeslider:vertical {
+                padding: 3px 5px;
+            }
+            rangeslider .range-slider-track {
+                position-type: absolute;
+                background-color: #ffffff;
+            }
+            rangeslider:horizontal .range-slider-track {
+                left: 0px;
+                right: 0px;
+                top: 10px;
+                bottom: 9px;
+            }
+            rangeslider:vertical .range-slider-track {
+                left: 10px;
+                right: 9px;
+                top: 0px;
+                bottom: 0px;
+            }
+            rangeslider .range-slider-fill {
+                position-type: absolute;
+                background-color: #4f4f4fdf;
+            }
+            rangeslider:horizontal .range-slider-fill {
+                left: -2px;
+                right: -2px;
+                top: 6px;
+                bottom: 5px;
+            }
+            rangeslider:vertical .range-slider-fill {
+                top: -2px;
+                bottom: -2px;
+                left: 6px;
+                right: 5px;
+            }
+            rangeslider .range-slider-holder {
+                width: 100%;
+                height: 100%;
+            }
+            rangeslider:horizontal .range-slider-before,
+            rangeslider:horizontal .range-slider-between {
+                height: 100%;
+            }
+            rangeslider:vertical .range-slider-before,
+            rangeslider:vertical .range-slider-between {
+                width: 100%;
+            }
+            rangeslider .range-slider-thumb {
+                margin: 0px;
+                min-width: 16px;
+                min-height: 16px;
+                width: 16px;
+                height: 16px;
+            }
+        "#
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Thumb {
+    Low,
+    High,
+}
+
+/// The layout of the range slider along its axis.
+struct Track {
+    /// The start of the holder, the `low` thumb is at the start
+    /// for the `minimum` value.
+    start: f32,
+    /// The distance traveled by each thumb.
+    length: f32,
+    thumb: f32,
+}
+
+impl Track {
+    fn get(slider: &RangeSlider, nodes: &Query<(&GlobalTransform, &Node)>) -> Option<Track> {
+        let (transform, holder) = nodes.get(slider.holder).ok()?;
+        let (_, thumb) = nodes.get(slider.low_thumb).ok()?;
+        let center = transform.translation().truncate();
+        let (start, size, thumb) = match slider.mode {
+            LayoutMode::Horizontal => (
+                center.x - holder.size().x * 0.5,
+                holder.size().x,
+                thumb.size().x,
+            ),
+            // the vertical slider grows upward
+            LayoutMode::Vertical => (
+                center.y + holder.size().y * 0.5,
+                holder.size().y,
+                thumb.size().y,
+            ),
+        };
+        Some(Track {
+            start,
+            length: (size - thumb * 2.0).max(0.0),
+            thumb,
+        })
+    }
+
+    /// Returns the distance from the start to the pointer.
+    fn distance(&self, mode: LayoutMode, pos: Vec2) -> f32 {
+        match mode {
+            LayoutMode::Horizontal => pos.x - self.start,
+            LayoutMode::Vertical => self.start - pos.y,
+        }
+    }
+
+    /// Returns the distance from the start to the center of the thumb.
+    fn center(&self, slider: &RangeSlider, thumb: Thumb) -> f32 {
+        match thumb {
+            Thumb::Low => slider.value.relative_low() * self.length + self.thumb * 0.5,
+            Thumb::High => slider.value.relative_high() * self.length + self.thumb * 1.5,
+        }
+    }
+
+    /// Returns the value of the `thumb` centered at the `distance`.
+    fn value(&self, slider: &RangeSlider, thumb: Thumb, distance: f32) -> f32 {
+        let offset = match thumb {
+            Thumb::Low => distance - self.thumb * 0.5,
+            Thumb::High => distance - self.thumb * 1.5,
+        };
+        let relative = if self.length > 0.0 {
+            offset / self.length
+        } else {
+            0.0
+        };
+        let range = slider.value;
+        range.minimum() + relative.min(1.0).max(0.0) * (range.maximum() - range.minimum())
+    }
+}
+
+fn handle_pointer_input(
+    mut events: EventReader<PointerInput>,
+    mut sliders: Query<(Entity, &mut RangeSlider)>,
+    nodes: Query<(&GlobalTransform, &Node)>,
+    mut slider_events: EventWriter<RangeSliderEvent>,
+    mut active: Local<Option<(Entity, Thumb)>>,
+) {
+    for ev in events.iter() {
+        if ev.down() && active.is_none() {
+            let Some(&target) = ev.entities.first() else {
+                continue;
+            };
+            let hit = sliders.iter().find_map(|(entity, slider)| {
+                if target == slider.low_thumb {
+                    Some((entity, Some(Thumb::Low)))
+                } else if target == slider.high_thumb {
+                    Some((entity, Some(Thumb::High)))
+                } else if target == entity {
+                    Some((entity, None))
+                } else {
+                    None
+                }
+            });
+            let Some((entity, thumb)) = hit else {
+                continue;
+            };
+            if let Some(thumb) = thumb {
+                *active = Some((entity, thumb));
+                continue;
+            }
+            // the press on the track moves the nearest thumb to the pointer
+            let Ok((_, mut slider)) = sliders.get_mut(entity) else {
+                continue;
+            };
+            let Some(track) = Track::get(&slider, &nodes) else {
+                continue;
+            };
+            let distance = track.distance(slider.mode, ev.pos);
+            let low = (track.center(&slider, Thumb::Low) - distance).abs();
+            let high = (track.center(&slider, Thumb::High) - distance).abs();
+            let thumb = if low < high { Thumb::Low } else { Thumb::High };
+            *active = Some((entity, thumb));
+            let value = track.value(&slider, thumb, distance);
+            if slider.move_thumb(thumb, value) {
+                slider_events.send(RangeSliderEvent::Changed([entity]));
+            }
+        } else if let Some((entity, thumb)) = *active {
+            if ev.dragging() || ev.drag_stop() {
+                let Ok((_, mut slider)) = sliders.get_mut(entity) else {
+                    continue;
+                };
+                let Some(track) = Track::get(&slider, &nodes) else {
+                    continue;
+                };
+                let distance = track.distance(slider.mode, ev.pos);
+                let value = track.value(&slider, thumb, distance);
+                if slider.move_thumb(thumb, value) {
+                    slider_events.send(RangeSliderEvent::Changed([entity]));
+                }
+            }
+            if ev.up() || ev.drag_stop() {
+                *active = None;
+                slider_events.send(RangeSliderEvent::Released([entity]));
+            }
+        }
+    }
+}
+
+fn handle_keyboard_input(
+    focused: Res<Focused>,
+    mut keys: EventReader<KeyInput>,
+    mut sliders: Query<(Entity, &mut RangeSlider)>,
+    mut slider_events: EventWriter<RangeSliderEvent>,
+    mut feedback: EventWriter<FeedbackRequest>,
+) {
+    let Some(focused) = focused.get() else { return };
+    let Some((entity, mut slider, thumb)) = sliders.iter_mut().find_map(|(entity, slider)| {
+        if focused == slider.low_thumb || focused == entity {
+            Some((entity, slider, Thumb::Low))
+        } else if focused == slider.high_thumb {
+            Some((entity, slider, Thumb::High))
+        } else {
+            None
+        }
+    }) else {
+        return;
+    };
+    let range = slider.value;
+    let step = match range.step() {
+        step if step > 0.0 => step,
+        _ => (range.maximum() - range.minimum()) * 0.01,
+    };
+    let mut value = match thumb {
+        Thumb::Low => range.low(),
+        Thumb::High => range.high(),
+    };
+    let mut pressed = false;
+    let keys = keys
+        .iter()
+        .filter(|k| k.pressed && k.entities[0] == focused);
+    for key in keys.map(|k| k.key) {
+        pressed = true;
+        value = match key {
+            KeyCode::Left | KeyCode::Down => value - step,
+            KeyCode::Right | KeyCode::Up => value + step,
+            KeyCode::PageDown => value - step * 10.0,
+            KeyCode::PageUp => value + step * 10.0,
+            KeyCode::Home => range.minimum(),
+            KeyCode::End => range.maximum(),
+            _ => {
+                pressed = false;
+                continue;
+            }
+        }
+    }
+    if !pressed {
+        return;
+    }
+    if slider.bypass_change_detection().move_thumb(thumb, value) {
+        slider.set_changed();
+        slider_events.send(RangeSliderEvent::Changed([entity]));
+        feedback.send(FeedbackRequest::new(FeedbackAction::Adjust, entity));
+    }
+}
+
+pub fn update_range_slider_representation(
+    sliders: Query<&RangeSlider, Or<(Changed<RangeSlider>, Changed<Node>)>>,
+    nodes: Query<(&GlobalTransform, &Node)>,
+    mut styles: Query<&mut Style>,
+) {
+    for slider in sliders.iter() {
+        let Some(track) = Track::get(slider, &nodes) else {
+            continue;
+        };
+        let before = slider.value.relative_low() * track.length;
+        let between = (slider.value.relative_high() - slider.value.relative_low()) * track.length;
+        for (entity, size) in [(slider.before, before), (slider.between, between)] {
+            let Ok(mut style) = styles.get_mut(entity) else {
+                continue;
+            };
+            let size = Val::Px(size);
+            match slider.mode {
+                LayoutMode::Horizontal if style.min_size.width != size => {
+                    style.min_size.width = size
+                }
+                LayoutMode::Vertical if style.min_size.height != size => {
+                    style.min_size.height = size
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+pub fn configure_range_slider_layout(
+    mut elements: Elements,
+    sliders: Query<(Entity, &RangeSlider), Changed<RangeSlider>>,
+    mut styles: Query<&mut Style>,
+    mut configured_modes: Local<HashMap<Entity, LayoutMode>>,
+) {
+    for (entity, slider) in sliders.iter() {
+        let mode = slider.mode;
+        if configured_modes.get(&entity) == Some(&mode) {
+            continue;
+        }
+        configured_modes.insert(entity, mode);
+        let horizontal = mode == LayoutMode::Horizontal;
+        elements.set_state(entity, "horizontal".as_tag(), horizontal);
+        elements.set_state(entity, "vertical".as_tag(), !horizontal);
+        if let Ok(mut holder) = styles.get_mut(slider.holder) {
+            holder.flex_direction = match mode {
+                LayoutMode::Horizontal => FlexDirection::Row,
+                LayoutMode::Vertical => FlexDirection::ColumnReverse,
+            };
+        }
+        for span in [slider.before, slider.between] {
+            let Ok(mut style) = styles.get_mut(span) else {
+                continue;
+            };
+            match mode {
+                LayoutMode::Horizontal => style.min_size.height = Val::Undefined,
+                LayoutMode::Vertical => style.min_size.width = Val::Undefined,
+            }
+        }
+    }
+}
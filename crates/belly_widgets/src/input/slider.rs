@@ -12,19 +12,57 @@ enum Label {
 pub(crate) struct SliderPlugin;
 impl Plugin for SliderPlugin {
     fn build(&self, app: &mut App) {
+        app.add_event::<SliderEvent>();
         app.add_system_to_stage(
             CoreStage::PreUpdate,
             handle_grabber_input
                 .after(input::Label::Signals)
                 .label(Label::GrabberInput),
         );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_keyboard_input.after(input::Label::Focus),
+        );
         app.register_widget::<slider>();
     }
 }
 
+pub enum SliderEvent {
+    /// The value is changed by the user.
+    Changed([Entity; 1]),
+    /// The user released the thumb.
+    Released([Entity; 1]),
+}
+
+impl SliderEvent {
+    pub fn changed(&self) -> bool {
+        match self {
+            SliderEvent::Changed(_) => true,
+            _ => false,
+        }
+    }
+    pub fn released(&self) -> bool {
+        match self {
+            SliderEvent::Released(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Signal for SliderEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            SliderEvent::Changed(source) => source,
+            SliderEvent::Released(source) => source,
+        }
+    }
+}
+
 #[widget]
 #[extends(styles=Range)]
 #[extends(descriptor=Range)]
+#[signal(change, SliderEvent, changed)]
+#[signal(release, SliderEvent, released)]
 #[style(
     "slider .slider-grabber",
     "  margin: 0px",
@@ -33,78 +71,169 @@ impl Plugin for SliderPlugin {
     "  width: 16px",
     "  height: 16px"
 )]
+/// The `<slider>` tag is the `<range>` changed by the user: the thumb
+/// (`.slider-thumb`) is dragged, the press on the track (`.slider-track`)
+/// moves the thumb to the pointer, the arrow keys nudge the focused
+/// slider by the `step` (or by 1% of the range when the step isn't set),
+/// `PageUp`/`PageDown` by ten steps, `Home`/`End` move it to the limits.
 fn slider(ctx: &mut ElementContext) {
     let grabber = SliderGrabber {
         slider: ctx.entity(),
     };
     let params = ctx.params();
+    // the arrows nudge the value, not the focus
+    let listener = Listen::bubble(ctx.entity(), |ctx: &mut PropagationContext<KeyInput>| {
+        if ctx.event().direction().is_some() {
+            ctx.stop_propagation();
+        }
+    });
+    ctx.commands().add(listener);
+    ctx.insert(Slider);
     ctx.render(eml! {
-        <range c:slider params=params>
+        <range c:slider interactable params=params>
+            <slot track>
+                <span c:range-back c:slider-track/>
+            </slot>
             <slot separator>
-                <button with=grabber mode="instant" c:slider-grabber>
+                <button with=grabber mode="instant" c:slider-grabber c:slider-thumb>
                 </button>
             </slot>
         </range>
     })
 }
+
+#[derive(Component)]
+struct Slider;
+
 #[derive(Component)]
 struct SliderGrabber {
     slider: Entity,
 }
 
+/// Moves the thumb of the `range` to the pointer at `pos`. Returns `true`
+/// if the value is changed.
+fn slide(
+    range: &mut Range,
+    pos: Vec2,
+    grabber: Vec2,
+    holders: &Query<(&GlobalTransform, &Node)>,
+) -> bool {
+    let Ok((htr, holder_node)) = holders.get(range.holder) else {
+        return false;
+    };
+    let Ok((_, high_node)) = holders.get(range.high_span) else {
+        return false;
+    };
+    let Ok((_, low_node)) = holders.get(range.low_span) else {
+        return false;
+    };
+    let grabber_offset = grabber * 0.5;
+    let pos = pos - htr.translation().truncate() + holder_node.size() * 0.5;
+    let mut offset = (pos - grabber_offset).min(holder_node.size() - grabber);
+    offset.y = holder_node.size().y - offset.y - grabber.y;
+    offset.y = offset.y.min(holder_node.size().y - grabber.y);
+    let offset = offset.max(Vec2::ZERO);
+    let relative = offset / (low_node.size() + high_node.size());
+    let mut value = range.value;
+    match range.mode {
+        LayoutMode::Horizontal => value.set_relative(relative.x),
+        LayoutMode::Vertical => value.set_relative(relative.y),
+    }
+    if value == range.value {
+        return false;
+    }
+    range.value = value;
+    true
+}
+
 fn handle_grabber_input(
     mut events: EventReader<PointerInput>,
-    mut sliders: Query<&mut Range>,
+    mut sliders: Query<&mut Range, With<Slider>>,
     grabbers: Query<(Entity, &SliderGrabber, &Node)>,
-    mut styles: Query<&mut Style>,
     holders: Query<(&GlobalTransform, &Node)>,
+    mut slider_events: EventWriter<SliderEvent>,
 
-    mut active_grabber: Local<Option<Entity>>,
     mut active_slider: Local<Option<Entity>>,
 ) {
     for ev in events.iter() {
-        // info!("{ev:?}, {active_grabber:?}");
-        // if active_grabber.is_some() && ev.drag_stop() {
-        if ev.drag_start() && active_grabber.is_none() {
-            let Some((entity, grabber, _)) = ev.entities
-                .iter()
-                .find_map(|e| grabbers.get(*e).ok())
-                else { return };
-            *active_grabber = Some(entity);
-            *active_slider = Some(grabber.slider);
-            // if let Ok((mut slider, _)) = sliders.get_mut(active_slider.unwrap()) {
-            //     slider.sliding = true;
-            // }
-        } else if active_grabber.is_some() && (ev.dragging() || ev.drag_stop()) {
-            let entity = active_grabber.unwrap();
-            let Ok(mut range) = sliders.get_mut(active_slider.unwrap()) else { continue };
-            let Ok((_, _, gnode)) = grabbers.get(entity) else { continue };
-            let Ok((htr, holder_node)) = holders.get(range.holder) else { continue };
-            let Ok((_, high_node)) = holders.get(range.high_span) else { continue };
-            let Ok((_, low_node)) = holders.get(range.low_span) else { continue };
-            let Ok(mut style) = styles.get_mut(range.low_span) else { continue };
-            let grabber_offset = gnode.size() * 0.5;
-            let pos = ev.pos - htr.translation().truncate() + holder_node.size() * 0.5;
-            let mut offset = (pos - grabber_offset).min(holder_node.size() - gnode.size());
-            offset.y = holder_node.size().y - offset.y - gnode.size().y;
-            offset.y = offset.y.min(holder_node.size().y - gnode.size().y);
-            let offset = offset.max(Vec2::ZERO);
-            let relative = offset / (low_node.size() + high_node.size());
-            match range.mode {
-                LayoutMode::Horizontal => {
-                    style.min_size.width = Val::Px(offset.x);
-                    range.value.set_relative(relative.x);
-                }
-                LayoutMode::Vertical => {
-                    style.min_size.height = Val::Px(offset.y);
-                    range.value.set_relative(relative.y);
+        if ev.down() && active_slider.is_none() {
+            let Some(&target) = ev.entities.first() else {
+                continue;
+            };
+            if let Ok((_, grabber, _)) = grabbers.get(target) {
+                *active_slider = Some(grabber.slider);
+                continue;
+            }
+            if !sliders.contains(target) {
+                continue;
+            }
+            // the press on the track moves the thumb to the pointer
+            *active_slider = Some(target);
+            let Some((_, _, gnode)) = grabbers.iter().find(|(_, g, _)| g.slider == target) else {
+                continue;
+            };
+            let Ok(mut range) = sliders.get_mut(target) else {
+                continue;
+            };
+            if slide(&mut range, ev.pos, gnode.size(), &holders) {
+                slider_events.send(SliderEvent::Changed([target]));
+            }
+        } else if let Some(slider) = *active_slider {
+            if ev.dragging() || ev.drag_stop() {
+                let Some((_, _, gnode)) = grabbers.iter().find(|(_, g, _)| g.slider == slider)
+                else {
+                    continue;
+                };
+                let Ok(mut range) = sliders.get_mut(slider) else {
+                    continue;
+                };
+                if slide(&mut range, ev.pos, gnode.size(), &holders) {
+                    slider_events.send(SliderEvent::Changed([slider]));
                 }
             }
-            if ev.drag_stop() {
-                // slider.sliding = false;
+            if ev.up() || ev.drag_stop() {
                 *active_slider = None;
-                *active_grabber = None;
+                slider_events.send(SliderEvent::Released([slider]));
             }
         }
     }
 }
+
+fn handle_keyboard_input(
+    focused: Res<Focused>,
+    mut keys: EventReader<KeyInput>,
+    grabbers: Query<&SliderGrabber>,
+    mut sliders: Query<&mut Range, With<Slider>>,
+    mut slider_events: EventWriter<SliderEvent>,
+    mut feedback: EventWriter<FeedbackRequest>,
+) {
+    let Some(focused) = focused.get() else { return };
+    let slider = grabbers.get(focused).map_or(focused, |g| g.slider);
+    let Ok(mut range) = sliders.get_mut(slider) else {
+        return;
+    };
+    let mut value = range.value;
+    let step = match value.step() {
+        step if step > 0.0 => step,
+        _ => (value.maximum() - value.minimum()) * 0.01,
+    };
+    let pressed = keys
+        .iter()
+        .filter(|k| k.pressed && k.entities[0] == focused);
+    for key in pressed.map(|k| k.key) {
+        match key {
+            KeyCode::Left | KeyCode::Down => value.set_value(value.value() - step),
+            KeyCode::Right | KeyCode::Up => value.set_value(value.value() + step),
+            KeyCode::PageDown => value.set_value(value.value() - step * 10.0),
+            KeyCode::PageUp => value.set_value(value.value() + step * 10.0),
+            KeyCode::Home => value.set_value(value.minimum()),
+            KeyCode::End => value.set_value(value.maximum()),
+            _ => continue,
+        }
+    }
+    if value != range.value {
+        range.value = value;
+        slider_events.send(SliderEvent::Changed([slider]));
+        feedback.send(FeedbackRequest::new(FeedbackAction::Adjust, slider));
+    }
+}
@@ -4,7 +4,8 @@ use crate::common::*;
 use ab_glyph::ScaleFont;
 use belly_core::*;
 use belly_macro::*;
-use bevy::{input::keyboard::KeyboardInput, prelude::*};
+use bevy::{input::keyboard::KeyboardInput, prelude::*, winit::WinitWindows};
+use winit::dpi::LogicalPosition;
 
 const CURSOR_WIDTH: f32 = 2.;
 
@@ -19,7 +20,11 @@ pub struct TextInputPlugin;
 impl Plugin for TextInputPlugin {
     fn build(&self, app: &mut App) {
         app.register_widget::<TextInput>();
+        app.add_event::<TextInputEvent>();
+        app.init_resource::<Clipboard>();
         app.add_system(blink_cursor)
+            .add_system(show_placeholder)
+            .add_system(place_ime_window)
             .add_system_to_stage(
                 CoreStage::PreUpdate,
                 process_cursor_focus
@@ -36,22 +41,137 @@ impl Plugin for TextInputPlugin {
                 CoreStage::PreUpdate,
                 process_keyboard_input
                     .label(TextInputLabel::Keyboard)
-                    .after(TextInputLabel::Mouse),
+                    .after(TextInputLabel::Mouse)
+                    .after(belly_core::input::Label::KeySignals),
             );
     }
 }
 
+pub enum TextInputEvent {
+    Changed([Entity; 1]),
+    Submitted([Entity; 1]),
+}
+
+impl TextInputEvent {
+    pub fn changed(&self) -> bool {
+        match self {
+            TextInputEvent::Changed(_) => true,
+            _ => false,
+        }
+    }
+    pub fn submitted(&self) -> bool {
+        match self {
+            TextInputEvent::Submitted(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Signal for TextInputEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            TextInputEvent::Changed(source) => source,
+            TextInputEvent::Submitted(source) => source,
+        }
+    }
+}
+
+/// The characters accepted by the `<textinput>`, set by the `filter`
+/// param: `any`, `digits`, `number` (digits with the leading `-` and the
+/// single `.`) or `alphanumeric`. The custom filter is set from the code:
+/// ```rust,ignore
+/// <textinput filter=InputFilter::Custom(|ch| ch.is_ascii_hexdigit())/>
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub enum InputFilter {
+    #[default]
+    Any,
+    Digits,
+    Number,
+    Alphanumeric,
+    Custom(fn(char) -> bool),
+}
+
+impl InputFilter {
+    /// Returns `true` if the `ch` could be inserted into the `chars`
+    /// at the `index`.
+    pub fn accepts(&self, ch: char, chars: &[char], index: usize) -> bool {
+        match self {
+            InputFilter::Any => true,
+            InputFilter::Digits => ch.is_ascii_digit(),
+            InputFilter::Number => match ch {
+                '-' => index == 0 && chars.first() != Some(&'-'),
+                '.' => !chars.contains(&'.'),
+                ch => ch.is_ascii_digit(),
+            },
+            InputFilter::Alphanumeric => ch.is_alphanumeric(),
+            InputFilter::Custom(filter) => filter(ch),
+        }
+    }
+}
+
+impl TryFrom<&str> for InputFilter {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "any" => Ok(InputFilter::Any),
+            "digits" => Ok(InputFilter::Digits),
+            "number" => Ok(InputFilter::Number),
+            "alphanumeric" => Ok(InputFilter::Alphanumeric),
+            _ => Err(format!("Can't parse `{}` as InputFilter", value)),
+        }
+    }
+}
+
+impl TryFrom<Variant> for InputFilter {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        match value {
+            Variant::String(s) => InputFilter::try_from(s.as_str()),
+            variant => variant
+                .take::<InputFilter>()
+                .ok_or_else(|| "Invalid value for InputFilter".to_string()),
+        }
+    }
+}
+
+impl From<InputFilter> for Variant {
+    fn from(filter: InputFilter) -> Self {
+        Variant::boxed(filter)
+    }
+}
+
+/// The text copied or cut from the `<textinput>` with `Ctrl+C`/`Ctrl+X`
+/// (`Cmd` on macOS) and pasted with `Ctrl+V`. The clipboard is kept by the
+/// app, set it to paste the text from the system clipboard.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct Clipboard(pub String);
+
 #[derive(Component, Widget)]
+#[signal(change, TextInputEvent, changed)]
+#[signal(submit, TextInputEvent, submitted)]
 #[alias(textinput)]
 /// The `<inputtext>` tag specifies a text input field
-/// where the user can enter data.
+/// where the user can enter data. The `change` signal is emitted when
+/// the user edits the value, the `submit` signal is emitted by `Enter`.
+/// The `placeholder` text is shown while the value is empty, the
+/// `maxlength` param limits the number of characters and the `filter`
+/// param limits the characters themselves (see [`InputFilter`]).
 pub struct TextInput {
     #[param]
     #[bindto(text, Label:value)]
     pub value: String,
+    #[param]
+    #[bindto(hint, Label:value)]
+    pub placeholder: String,
+    #[param]
+    pub maxlength: i32,
+    #[param]
+    pub filter: InputFilter,
     index: usize,
     selected: Selection,
     text: Entity,
+    hint: Entity,
     container: Entity,
     selection: Entity,
     cursor: Entity,
@@ -61,13 +181,22 @@ impl WidgetBuilder for TextInput {
     fn setup(&mut self, ctx: &mut ElementContext) {
         let cursor = self.cursor;
         let text = self.text;
+        let hint = self.hint;
         let container = self.container;
         let selection = self.selection;
+        // the arrows move the cursor, not the focus
+        let listener = Listen::bubble(ctx.entity(), |ctx: &mut PropagationContext<KeyInput>| {
+            if ctx.event().direction().is_some() {
+                ctx.stop_propagation();
+            }
+        });
+        ctx.commands().add(listener);
         ctx.render(eml! {
             <div interactable="block" c:text-input c:text-input-border>
                 <div c:text-input-background>
                     <div {container} c:text-input-container>
                         <div {selection} c:text-input-selection s:display=managed()/>
+                        <label {hint} c:text-input-placeholder s:display=managed()/>
                         <label {text} c:text-input-value/>
                         <div {cursor} c:text-input-cursor
                             s:position-type="absolute"
@@ -109,6 +238,10 @@ impl WidgetBuilder for TextInput {
         .text-input-value {
             color: #2f2f2f;
         }
+        .text-input-placeholder {
+            position-type: absolute;
+            color: #8f8f8f;
+        }
         .text-input-cursor {
             top: 1px;
             bottom: 1px;
@@ -198,26 +331,79 @@ fn get_char_advance(ch: char, font: &Font, font_size: f32) -> f32 {
     font.h_advance(glyph)
 }
 
+/// Inserts the `text` at the `index` replacing the selection. The
+/// characters rejected by the `filter` and the characters above the
+/// `maxlength` are skipped. Returns `false` if nothing is inserted.
+fn insert_text(
+    chars: &mut Vec<char>,
+    index: &mut usize,
+    selected: &mut Selection,
+    text: impl Iterator<Item = char>,
+    filter: &InputFilter,
+    maxlength: i32,
+) -> bool {
+    let mut text = text.filter(|c| !c.is_control()).peekable();
+    if text.peek().is_none() {
+        return false;
+    }
+    let mut rest = chars.clone();
+    let mut at = *index;
+    if !selected.is_empty() {
+        rest.drain(selected.range());
+        at = selected.min;
+    }
+    let mut inserted = false;
+    for ch in text {
+        if maxlength > 0 && rest.len() >= maxlength as usize {
+            break;
+        }
+        if !filter.accepts(ch, &rest, at) {
+            continue;
+        }
+        rest.insert(at, ch);
+        at += 1;
+        inserted = true;
+    }
+    if inserted {
+        *chars = rest;
+        *index = at;
+        selected.stop();
+    }
+    inserted
+}
+
 fn process_keyboard_input(
     changed_elements: Query<(), Changed<Element>>,
     mut keyboard_input: EventReader<KeyboardInput>,
+    mut keys: EventReader<KeyInput>,
     keyboard: Res<Input<KeyCode>>,
     fonts: Res<Assets<Font>>,
     nodes: Query<&Node>,
     mut characters: EventReader<ReceivedCharacter>,
-    mut inputs: Query<(Entity, &mut TextInput, &Element)>,
+    mut inputs: Query<(Entity, &mut TextInput, &States)>,
     mut cursors: Query<&mut TextInputCursor>,
     mut styles: Query<&mut Style>,
     mut texts: Query<&Text>,
+    mut clipboard: ResMut<Clipboard>,
+    mut events: EventWriter<TextInputEvent>,
+    mut feedback: EventWriter<FeedbackRequest>,
 ) {
+    let pressed = keyboard_input.iter().any(|k| k.state.is_pressed());
     let Some((entity, mut input)) = inputs.iter_mut()
         .filter(|(_, _, e)| e.focused())
         .map(|(e, i, _)| (e, i))
         .next()
         else { return };
-    if characters.is_empty() && keyboard_input.is_empty() && !changed_elements.contains(entity) {
+    if characters.is_empty() && keys.is_empty() && !changed_elements.contains(entity) {
         return;
     }
+    let keys: Vec<_> = keys
+        .iter()
+        .filter(|k| k.pressed && k.entities[0] == entity)
+        .map(|k| k.key)
+        .collect();
+    // the characters of the keys swallowed by the listeners aren't typed
+    let swallowed = keys.is_empty() && pressed;
 
     let Ok(text) = texts.get_mut(input.text)
         else { return };
@@ -226,18 +412,52 @@ fn process_keyboard_input(
     // may be platform dependent compilation here?
     let cmd = keyboard.any_pressed([KeyCode::LWin, KeyCode::RWin]);
     let shift = keyboard.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    // AltGr is reported as Ctrl+Alt and types the characters
+    let ctrl = (cmd || keyboard.any_pressed([KeyCode::LControl, KeyCode::RControl]))
+        && !keyboard.any_pressed([KeyCode::LAlt, KeyCode::RAlt]);
+    let filter = input.filter;
+    let maxlength = input.maxlength;
+    let before = input.value.clone();
+    let mut submitted = false;
     let mut index = input.index;
     let mut selected = input.selected.clone();
 
     let mut chars: Vec<_> = input.value.chars().collect();
-    for ch in keyboard_input.iter() {
-        if !ch.state.is_pressed() {
-            continue;
-        }
-        let Some(code) = ch.key_code else {
-            continue
-        };
+    for code in keys {
         match code {
+            KeyCode::A if ctrl => {
+                selected.start(0);
+                selected.extend(chars.len());
+                index = chars.len();
+            }
+            KeyCode::C | KeyCode::X if ctrl => {
+                if selected.is_empty() {
+                    continue;
+                }
+                clipboard.0 = chars[selected.range()].iter().collect();
+                if code == KeyCode::X {
+                    chars.drain(selected.range());
+                    index = selected.min;
+                    selected.stop();
+                    input.value = chars.iter().collect();
+                }
+            }
+            KeyCode::V if ctrl => {
+                let text = clipboard.0.clone();
+                if insert_text(
+                    &mut chars,
+                    &mut index,
+                    &mut selected,
+                    text.chars(),
+                    &filter,
+                    maxlength,
+                ) {
+                    input.value = chars.iter().collect();
+                }
+            }
+            KeyCode::Return | KeyCode::NumpadEnter => {
+                submitted = true;
+            }
             KeyCode::Left if !cmd => {
                 if !shift {
                     selected.stop();
@@ -313,19 +533,24 @@ fn process_keyboard_input(
             _ => (),
         }
     }
-    for ch in characters
-        .iter()
-        .map(|c| c.char)
-        .filter(|c| !c.is_control())
-    {
-        if !selected.is_empty() {
-            chars.drain(selected.range());
-            index = selected.min;
-            selected.stop();
-        }
-        chars.insert(index, ch);
+    // the characters typed with Ctrl are the shortcuts
+    let typed = characters.iter().map(|c| c.char).filter(|_| !ctrl && !swallowed);
+    if insert_text(
+        &mut chars,
+        &mut index,
+        &mut selected,
+        typed,
+        &filter,
+        maxlength,
+    ) {
         input.value = chars.iter().collect();
-        index += 1;
+    }
+    if input.value != before {
+        events.send(TextInputEvent::Changed([entity]));
+    }
+    if submitted {
+        events.send(TextInputEvent::Submitted([entity]));
+        feedback.send(FeedbackRequest::new(FeedbackAction::Confirm, entity));
     }
 
     if let Ok(mut cursor) = cursors.get_mut(input.cursor) {
@@ -400,22 +625,22 @@ fn process_keyboard_input(
 
 fn process_cursor_focus(
     mut commands: Commands,
-    mut input: Query<(&mut TextInput, &Element), Changed<Element>>,
+    mut input: Query<(&mut TextInput, &States), Changed<States>>,
     cursors: Query<&TextInputCursor>,
     mut styles: Query<&mut Style>,
 ) {
-    for (mut input, element) in input.iter_mut() {
-        if element.focused() && !cursors.contains(input.cursor) {
+    for (mut input, states) in input.iter_mut() {
+        if states.focused() && !cursors.contains(input.cursor) {
             commands
                 .entity(input.cursor)
                 .insert(TextInputCursor::default());
         }
-        if !element.focused() && !cursors.contains(input.cursor) {
+        if !states.focused() && !cursors.contains(input.cursor) {
             if let Ok(mut style) = styles.get_mut(input.cursor) {
                 style.display = Display::None;
             }
         }
-        if !element.focused() && cursors.contains(input.cursor) {
+        if !states.focused() && cursors.contains(input.cursor) {
             input.index = 0;
             commands.entity(input.cursor).remove::<TextInputCursor>();
             if let Ok(mut style) = styles.get_mut(input.cursor) {
@@ -518,6 +743,64 @@ fn process_mouse(
     }
 }
 
+fn show_placeholder(
+    inputs: Query<&TextInput, Changed<TextInput>>,
+    mut styles: Query<&mut Style>,
+) {
+    for input in inputs.iter() {
+        let Ok(mut style) = styles.get_mut(input.hint) else {
+            continue;
+        };
+        let display = if input.value.is_empty() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
+
+/// Moves the IME candidate window under the caret of the focused input.
+/// The composition is shown by the platform in the candidate window, the
+/// composed text is committed as the regular characters.
+fn place_ime_window(
+    mut placed: Local<Option<Vec2>>,
+    inputs: Query<(&TextInput, &States)>,
+    containers: Query<(&Node, &GlobalTransform)>,
+    styles: Query<&Style>,
+    windows: Res<Windows>,
+    winit_windows: Option<NonSend<WinitWindows>>,
+) {
+    let Some(winit_windows) = winit_windows else {
+        return;
+    };
+    let Some((input, _)) = inputs.iter().find(|(_, states)| states.focused()) else {
+        *placed = None;
+        return;
+    };
+    let Ok((node, transform)) = containers.get(input.container) else {
+        return;
+    };
+    let caret = match styles.get(input.cursor).map(|s| s.position.left) {
+        Ok(Val::Px(left)) => left,
+        _ => 0.,
+    };
+    let min = transform.translation().truncate() - node.size() * 0.5;
+    let position = min + Vec2::new(caret, node.size().y);
+    if *placed == Some(position) {
+        return;
+    }
+    *placed = Some(position);
+    let window = windows
+        .get_primary()
+        .and_then(|window| winit_windows.get_window(window.id()));
+    if let Some(window) = window {
+        window.set_ime_position(LogicalPosition::new(position.x, position.y));
+    }
+}
+
 fn blink_cursor(time: Res<Time>, mut cursor: Query<(&mut TextInputCursor, &mut Style)>) {
     for (mut cursor, mut style) in cursor.iter_mut() {
         cursor.state -= time.delta_seconds();
@@ -0,0 +1,277 @@
+use crate::common::Label;
+use crate::input::{TextInput, TextInputEvent};
+use belly_core::ess::{ComputedStyles, EditStyleRule, MediaQuery, StyleSheet, StyleSource};
+use belly_core::*;
+use belly_macro::*;
+use bevy::prelude::*;
+use std::sync::Arc;
+
+/// Optional devtool plugin: registers the `<inspector>` widget showing
+/// the styles of the inspected element. It is not the part of the
+/// [`WidgetsPlugin`](crate::WidgetsPlugin) and should be added manually.
+pub struct InspectorPlugin;
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<Inspector>();
+        app.add_system(pick_inspected_element.after(input::Label::PointerInput));
+        app.add_system(invalidate_inspectors);
+        app.add_system(
+            render_inspectors
+                .after(pick_inspected_element)
+                .after(invalidate_inspectors),
+        );
+        app.add_system(edit_inspected_rules);
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(inspector)]
+/// The `<inspector>` tag lists the computed style of the inspected element:
+/// each row shows the property, its value and the rule (or the inline
+/// `style`) the value comes from. The element pressed with `Alt` held is
+/// inspected, the `target` could be set from the code as well. The values
+/// specified by the rules are editable: the submitted value is sent as the
+/// [`EditStyleRule`] event, so in dev builds it is written back to the
+/// `.ess` file and hot-reloaded.
+/// ```rust,ignore
+/// app.add_plugin(InspectorPlugin);
+/// commands.add(eml! {
+///     <body>
+///         <inspector s:position-type="absolute" s:right="0px"/>
+///     </body>
+/// });
+/// ```
+pub struct Inspector {
+    pub target: Option<Entity>,
+    pub title: Entity,
+    pub holder: Entity,
+    /// The element the rows are rendered for.
+    shown: Option<Entity>,
+}
+
+impl WidgetBuilder for Inspector {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let title = self.title;
+        let holder = self.holder;
+        ctx.render(eml! {
+            <span c:inspector>
+                <label {title} c:inspector-title value="Alt+click an element to inspect"/>
+                <span {holder} c:inspector-rows/>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            inspector {
+                flex-direction: column;
+                width: 360px;
+                max-height: 100%;
+                padding: 4px;
+                background-color: #2f2f2fdf;
+            }
+            inspector .inspector-title {
+                margin-bottom: 4px;
+                color: #bfbfbf;
+            }
+            inspector .inspector-rows {
+                flex-direction: column;
+                overflow: hidden;
+            }
+            inspector .inspector-row {
+                align-items: center;
+                height: 20px;
+            }
+            inspector .inspector-property {
+                width: 35%;
+                color: #9cdcfe;
+            }
+            inspector .inspector-value {
+                width: 35%;
+            }
+            inspector .inspector-source {
+                width: 30%;
+                padding-left: 4px;
+                color: #9f9f9f;
+            }
+        "#
+    }
+}
+
+/// The rule declaration edited by the `<textinput>` of the inspector row.
+#[derive(Component)]
+struct InspectedRule {
+    stylesheet: Handle<StyleSheet>,
+    selector: String,
+    media: Option<Arc<MediaQuery>>,
+    property: String,
+}
+
+/// Inspects the element pressed with `Alt` held. The elements of the
+/// inspector itself are not inspected.
+fn pick_inspected_element(
+    mut events: EventReader<PointerInput>,
+    keyboard: Res<Input<KeyCode>>,
+    mut inspectors: Query<(Entity, &mut Inspector)>,
+) {
+    if !keyboard.any_pressed([KeyCode::LAlt, KeyCode::RAlt]) {
+        events.clear();
+        return;
+    }
+    for event in events.iter().filter(|e| e.down()) {
+        let Some(target) = event.entities.first() else {
+            continue;
+        };
+        for (entity, mut inspector) in inspectors.iter_mut() {
+            if !event.contains(entity) && inspector.target != Some(*target) {
+                inspector.target = Some(*target);
+            }
+        }
+    }
+}
+
+/// Renders the inspectors again when the stylesheets are reloaded.
+fn invalidate_inspectors(
+    mut events: EventReader<AssetEvent<StyleSheet>>,
+    mut inspectors: Query<&mut Inspector>,
+) {
+    if events.iter().last().is_none() {
+        return;
+    }
+    for mut inspector in inspectors.iter_mut() {
+        inspector.shown = None;
+    }
+}
+
+fn render_inspectors(
+    mut commands: Commands,
+    mut inspectors: Query<&mut Inspector, Changed<Inspector>>,
+    mut labels: Query<&mut Label>,
+    elements: Query<ElementRef>,
+    computed: ComputedStyles,
+) {
+    for mut inspector in inspectors.iter_mut() {
+        if inspector.shown == inspector.target {
+            continue;
+        }
+        inspector.shown = inspector.target;
+        let holder = inspector.holder;
+        commands.entity(holder).despawn_descendants();
+        let Some(target) = inspector.target else {
+            continue;
+        };
+        if let Ok(mut label) = labels.get_mut(inspector.title) {
+            label.value = elements
+                .get(target)
+                .map(|element| describe(&element))
+                .unwrap_or_else(|_| format!("{target:?}"));
+        }
+        let Some(style) = computed.get(target) else {
+            continue;
+        };
+        let mut rows: Vec<_> = style
+            .iter()
+            .map(|(property, computed)| {
+                let property = property.to_string();
+                let value = match &computed.source {
+                    StyleSource::Initial => "initial".to_string(),
+                    StyleSource::Managed => "managed".to_string(),
+                    _ => computed.source_text().unwrap_or_default().to_string(),
+                };
+                let (source, rule) = match &computed.source {
+                    StyleSource::Inline => ("style".to_string(), None),
+                    StyleSource::Rule {
+                        stylesheet,
+                        selector,
+                        media,
+                    } => (
+                        match media {
+                            Some(_) => format!("@media {selector}"),
+                            None => selector.clone(),
+                        },
+                        Some(InspectedRule {
+                            stylesheet: stylesheet.clone(),
+                            selector: selector.clone(),
+                            media: media.clone(),
+                            property: property.clone(),
+                        }),
+                    ),
+                    _ => (String::new(), None),
+                };
+                (property, value, source, rule)
+            })
+            .collect();
+        rows.sort_by(|a, b| a.0.cmp(&b.0));
+        commands.add(move |world: &mut World| {
+            for (property, value, source, rule) in rows {
+                let row = match rule {
+                    Some(rule) => {
+                        let input = world.spawn(rule).id();
+                        eml! {
+                            <span c:inspector-row>
+                                <label c:inspector-property value=property/>
+                                <textinput {input} c:inspector-value value=value/>
+                                <label c:inspector-source value=source/>
+                            </span>
+                        }
+                    }
+                    None => eml! {
+                        <span c:inspector-row>
+                            <label c:inspector-property value=property/>
+                            <label c:inspector-value value=value/>
+                            <label c:inspector-source value=source/>
+                        </span>
+                    },
+                };
+                let roots = row.build(world);
+                world.entity_mut(holder).push_children(&roots);
+            }
+        });
+    }
+}
+
+/// Describes the element like the selector does: `button#ok.primary`.
+fn describe(element: &ElementRefItem) -> String {
+    let mut text = element
+        .tag
+        .and_then(|tag| tag.names.first())
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("{:?}", element.entity));
+    if let Some(id) = element.id {
+        text.push_str(&format!("#{}", id.0));
+    }
+    let mut classes: Vec<_> = element
+        .classes
+        .into_iter()
+        .flat_map(|classes| classes.iter())
+        .map(|class| class.to_string())
+        .collect();
+    classes.sort();
+    for class in classes {
+        text.push_str(&format!(".{class}"));
+    }
+    text
+}
+
+/// Sends the values submitted in the inspector rows as the rule edits.
+fn edit_inspected_rules(
+    mut inputs: EventReader<TextInputEvent>,
+    rules: Query<(&InspectedRule, &TextInput)>,
+    mut edits: EventWriter<EditStyleRule>,
+) {
+    for event in inputs.iter() {
+        let TextInputEvent::Submitted([entity]) = event else {
+            continue;
+        };
+        let Ok((rule, input)) = rules.get(*entity) else {
+            continue;
+        };
+        edits.send(EditStyleRule {
+            stylesheet: rule.stylesheet.clone(),
+            selector: rule.selector.clone(),
+            media: rule.media.clone(),
+            property: rule.property.clone(),
+            value: input.value.clone(),
+        });
+    }
+}
@@ -1,8 +1,28 @@
+pub mod animation;
 pub mod common;
+pub mod conditional;
+pub mod cooldown;
+pub mod dnd;
+pub mod dock;
+pub mod hyperlink;
 pub mod img;
 pub mod input;
+pub mod inspector;
+pub mod markdown;
+pub mod menu;
+pub mod modal;
+pub mod notifications;
+pub mod profiler;
 pub mod range;
+pub mod richtext;
+pub mod scroll;
+pub mod splitter;
+pub mod table;
+pub mod tabs;
+pub mod tooltip;
+pub mod tree;
 pub mod visible_progress;
+pub mod window;
 use bevy::prelude::Plugin;
 
 #[derive(Default)]
@@ -11,17 +31,75 @@ pub struct WidgetsPlugin;
 impl Plugin for WidgetsPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_plugin(img::ImgPlugin);
+        app.add_plugin(animation::AnimationPlugin);
         app.add_plugin(input::InputPlugins);
         app.add_plugin(common::CommonsPlugin);
+        app.add_plugin(richtext::RichTextPlugin);
+        app.add_plugin(hyperlink::HyperlinkPlugin);
+        app.add_plugin(markdown::MarkdownPlugin);
+        app.add_plugin(conditional::ConditionalPlugin);
         app.add_plugin(range::RangePlugin);
+        app.add_plugin(scroll::ScrollViewPlugin);
+        app.add_plugin(tabs::TabsPlugin);
+        app.add_plugin(splitter::SplitterPlugin);
+        app.add_plugin(window::FloatingWindowPlugin);
+        app.add_plugin(dock::DockPlugin);
+        app.add_plugin(modal::ModalPlugin);
+        app.add_plugin(notifications::NotificationsPlugin);
+        app.add_plugin(menu::MenuPlugin);
+        app.add_plugin(tree::TreePlugin);
+        app.add_plugin(table::TablePlugin);
+        app.add_plugin(cooldown::CooldownPlugin);
+        app.add_plugin(tooltip::TooltipPlugin);
+        app.add_plugin(dnd::DragGhostPlugin);
     }
 }
 
 pub mod prelude {
+    #[doc(inline)]
+    pub use crate::animation::*;
     #[doc(inline)]
     pub use crate::common::*;
     #[doc(inline)]
+    pub use crate::conditional::*;
+    #[doc(inline)]
+    pub use crate::cooldown::*;
+    #[doc(inline)]
+    pub use crate::dnd::*;
+    #[doc(inline)]
+    pub use crate::dock::*;
+    #[doc(inline)]
+    pub use crate::hyperlink::*;
+    #[doc(inline)]
     pub use crate::img::*;
     #[doc(inline)]
     pub use crate::input::*;
+    #[doc(inline)]
+    pub use crate::inspector::*;
+    #[doc(inline)]
+    pub use crate::markdown::*;
+    #[doc(inline)]
+    pub use crate::menu::*;
+    #[doc(inline)]
+    pub use crate::modal::*;
+    #[doc(inline)]
+    pub use crate::notifications::*;
+    #[doc(inline)]
+    pub use crate::profiler::*;
+    #[doc(inline)]
+    pub use crate::richtext::*;
+    #[doc(inline)]
+    pub use crate::scroll::*;
+    #[doc(inline)]
+    pub use crate::splitter::*;
+    #[doc(inline)]
+    pub use crate::table::*;
+    #[doc(inline)]
+    pub use crate::tabs::*;
+    #[doc(inline)]
+    pub use crate::tooltip::*;
+    #[doc(inline)]
+    pub use crate::tree::*;
+    #[doc(inline)]
+    pub use crate::window::*;
 }
@@ -0,0 +1,664 @@
+use crate::{hyperlink::LinkActivated, richtext::escape_markup};
+use belly_core::*;
+use belly_macro::*;
+use bevy::{
+    asset::{AssetLoader, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+};
+
+pub(crate) struct MarkdownPlugin;
+impl Plugin for MarkdownPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<MarkdownAsset>();
+        app.init_asset_loader::<MarkdownLoader>();
+        app.add_event::<MarkdownEvent>();
+        app.register_widget::<Markdown>();
+        app.add_system(handle_markdown_links);
+        app.add_system(load_markdown_sources);
+        app.add_system(reload_markdown_assets.after(load_markdown_sources));
+        app.add_system(render_markdown.after(reload_markdown_assets));
+    }
+}
+
+/// The text of the `.md` asset shown by the `<markdown>` tag.
+#[derive(TypeUuid)]
+#[uuid = "6d3a4f0e-2b8c-4c1e-9a57-3f1d8e4b7c21"]
+pub struct MarkdownAsset(pub String);
+
+#[derive(Default)]
+struct MarkdownLoader;
+
+impl AssetLoader for MarkdownLoader {
+    fn extensions(&self) -> &[&str] {
+        &["md"]
+    }
+
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut bevy::asset::LoadContext,
+    ) -> bevy::utils::BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let source = std::str::from_utf8(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(MarkdownAsset(source.to_string())));
+            Ok(())
+        })
+    }
+}
+
+pub enum MarkdownEvent {
+    /// The link of the document is pressed, the `href` is the target of the link.
+    LinkPressed([Entity; 1], String),
+}
+
+impl MarkdownEvent {
+    pub fn link_pressed(&self) -> bool {
+        match self {
+            MarkdownEvent::LinkPressed(..) => true,
+        }
+    }
+
+    pub fn href(&self) -> &str {
+        match self {
+            MarkdownEvent::LinkPressed(_, href) => href,
+        }
+    }
+}
+
+impl Signal for MarkdownEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            MarkdownEvent::LinkPressed(source, _) => source,
+        }
+    }
+}
+
+/// The block of the markdown document, the text of the block
+/// is split into the inlines when the block is rendered.
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    Quote(String),
+    Item {
+        depth: usize,
+        bullet: String,
+        text: String,
+    },
+    Code(String),
+    Rule,
+}
+
+/// Returns the bullet and the text of the list item line.
+fn parse_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(bullet) {
+            return Some(("•".to_string(), text));
+        }
+    }
+    let digits = line.find(|c: char| !c.is_ascii_digit())?;
+    if digits == 0 {
+        return None;
+    }
+    let rest = &line[digits..];
+    let text = rest
+        .strip_prefix(". ")
+        .or_else(|| rest.strip_prefix(") "))?;
+    Some((format!("{}.", &line[..digits]), text))
+}
+
+fn is_rule(line: &str) -> bool {
+    let marks: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|mark| marks.chars().all(|c| c == *mark))
+}
+
+/// Splits the markdown into the blocks: `#` headings, paragraphs, `>` quotes,
+/// `-`/`*`/`+`/`1.` list items (nested by the indentation), fenced code
+/// blocks and `---` rules.
+fn parse_blocks(source: &str) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let mut quote: Vec<&str> = vec![];
+    let mut code: Option<Vec<&str>> = None;
+    let flush = |blocks: &mut Vec<Block>, paragraph: &mut Vec<&str>, quote: &mut Vec<&str>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(paragraph.join(" ")));
+            paragraph.clear();
+        }
+        if !quote.is_empty() {
+            blocks.push(Block::Quote(quote.join(" ")));
+            quote.clear();
+        }
+    };
+    for line in source.lines() {
+        let trimmed = line.trim_start();
+        if let Some(lines) = code.as_mut() {
+            if trimmed.starts_with("```") {
+                blocks.push(Block::Code(lines.join("\n")));
+                code = None;
+            } else {
+                lines.push(line);
+            }
+            continue;
+        }
+        let indent: usize = line[..line.len() - trimmed.len()]
+            .chars()
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum();
+        let trimmed = trimmed.trim_end();
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if trimmed.is_empty() {
+            flush(&mut blocks, &mut paragraph, &mut quote);
+        } else if trimmed.starts_with("```") {
+            flush(&mut blocks, &mut paragraph, &mut quote);
+            code = Some(vec![]);
+        } else if (1..=6).contains(&level)
+            && trimmed[level..]
+                .chars()
+                .next()
+                .map_or(true, char::is_whitespace)
+        {
+            flush(&mut blocks, &mut paragraph, &mut quote);
+            let text = trimmed[level..].trim().trim_end_matches('#').trim_end();
+            blocks.push(Block::Heading(level, text.to_string()));
+        } else if is_rule(trimmed) {
+            flush(&mut blocks, &mut paragraph, &mut quote);
+            blocks.push(Block::Rule);
+        } else if let Some((bullet, text)) = parse_item(trimmed) {
+            flush(&mut blocks, &mut paragraph, &mut quote);
+            blocks.push(Block::Item {
+                depth: indent / 2,
+                bullet,
+                text: text.trim().to_string(),
+            });
+        } else if let Some(text) = trimmed.strip_prefix('>') {
+            if !paragraph.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut quote);
+            }
+            quote.push(text.trim());
+        } else if !quote.is_empty() {
+            quote.push(trimmed);
+        } else if let (true, Some(Block::Item { text, .. })) =
+            (paragraph.is_empty(), blocks.last_mut())
+        {
+            // the lazy continuation of the list item
+            text.push(' ');
+            text.push_str(trimmed);
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    if let Some(lines) = code {
+        blocks.push(Block::Code(lines.join("\n")));
+    }
+    flush(&mut blocks, &mut paragraph, &mut quote);
+    blocks
+}
+
+/// The piece of the block text: the [`RichText`](crate::richtext::RichText)
+/// markup or the pressable link.
+#[derive(Debug, Clone, PartialEq)]
+enum Inline {
+    Text(String),
+    Link { markup: String, href: String },
+}
+
+/// Opens or closes the emphasis `tag` of the markup.
+fn toggle(markup: &mut String, open: &mut Vec<&'static str>, tag: &'static str) {
+    if let Some(index) = open.iter().rposition(|t| *t == tag) {
+        markup.push_str(&format!("</{tag}>"));
+        open.remove(index);
+    } else {
+        markup.push_str(&format!("<{tag}>"));
+        open.push(tag);
+    }
+}
+
+/// Pushes the plain character to the markup, escaped for the `<richtext>`.
+fn push_text(markup: &mut String, c: char) {
+    match c {
+        '<' => markup.push_str("&lt;"),
+        '&' => markup.push_str("&amp;"),
+        c => markup.push(c),
+    }
+}
+
+/// Tells if the `rest` of the text has the `delimiter` closing the emphasis.
+fn has_closing(rest: &[char], delimiter: &[char]) -> bool {
+    (1..rest.len()).any(|i| rest[i..].starts_with(delimiter) && !rest[i - 1].is_whitespace())
+}
+
+/// Converts the `**strong**`, `*emphasis*`, `` `code` `` and `[text](href)`
+/// of the block text into the inlines. The delimiters without the pair
+/// are kept as a text.
+fn parse_inlines(text: &str) -> Vec<Inline> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut inlines = vec![];
+    let mut markup = String::new();
+    let mut open: Vec<&'static str> = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let prev = if i > 0 { chars[i - 1] } else { ' ' };
+        match c {
+            '\\' if i + 1 < chars.len() => {
+                push_text(&mut markup, chars[i + 1]);
+                i += 2;
+            }
+            '`' => match chars[i + 1..].iter().position(|c| *c == '`') {
+                Some(len) => {
+                    let code: String = chars[i + 1..i + 1 + len].iter().collect();
+                    let code = escape_markup(&code);
+                    markup.push_str(&format!("<span c:markdown-code>{code}</span>"));
+                    i += len + 2;
+                }
+                None => {
+                    push_text(&mut markup, c);
+                    i += 1;
+                }
+            },
+            '*' | '_' => {
+                let double = chars.get(i + 1) == Some(&c);
+                let len = if double { 2 } else { 1 };
+                let next = chars.get(i + len).copied().unwrap_or(' ');
+                let tag = if double { "b" } else { "i" };
+                let opening = !next.is_whitespace()
+                    && !open.contains(&tag)
+                    && has_closing(&chars[i + len..], &chars[i..i + len]);
+                let closing = !prev.is_whitespace() && open.contains(&tag);
+                // the underscores within the words are kept
+                let inner = c == '_' && prev.is_alphanumeric() && next.is_alphanumeric();
+                if (opening || closing) && !inner {
+                    toggle(&mut markup, &mut open, tag);
+                } else {
+                    markup.extend(&chars[i..i + len]);
+                }
+                i += len;
+            }
+            '[' => {
+                let rest: String = chars[i + 1..].iter().collect();
+                let link = rest.find("](").and_then(|label| {
+                    let href = &rest[label + 2..];
+                    let end = href.find(')')?;
+                    Some((label, &href[..end], label + 2 + end + 1))
+                });
+                let Some((label, href, consumed)) = link else {
+                    push_text(&mut markup, c);
+                    i += 1;
+                    continue;
+                };
+                for tag in open.iter().rev() {
+                    markup.push_str(&format!("</{tag}>"));
+                }
+                if !markup.is_empty() {
+                    inlines.push(Inline::Text(std::mem::take(&mut markup)));
+                }
+                let label = parse_inlines(&rest[..label])
+                    .into_iter()
+                    .map(|inline| match inline {
+                        Inline::Text(markup) | Inline::Link { markup, .. } => markup,
+                    })
+                    .collect();
+                inlines.push(Inline::Link {
+                    markup: label,
+                    href: href.trim().to_string(),
+                });
+                for tag in open.iter() {
+                    markup.push_str(&format!("<{tag}>"));
+                }
+                i += 1 + rest[..consumed].chars().count();
+            }
+            c => {
+                push_text(&mut markup, c);
+                i += 1;
+            }
+        }
+    }
+    for tag in open.iter().rev() {
+        markup.push_str(&format!("</{tag}>"));
+    }
+    if !markup.is_empty() {
+        inlines.push(Inline::Text(markup));
+    }
+    inlines
+}
+
+/// The `<a>` of the rendered markdown, holds the `<markdown>` entity.
+#[derive(Component)]
+struct MarkdownLink(Entity);
+
+fn render_inlines(world: &mut World, markdown: Entity, text: &str) -> Vec<ElementsBuilder> {
+    parse_inlines(text)
+        .into_iter()
+        .map(|inline| match inline {
+            Inline::Text(markup) => eml! {
+                <richtext value=markup/>
+            },
+            Inline::Link { markup, href } => {
+                let link = world.spawn(MarkdownLink(markdown)).id();
+                eml! {
+                    <a {link} c:markdown-link href=href>
+                        <richtext value=markup/>
+                    </a>
+                }
+            }
+        })
+        .collect()
+}
+
+fn render_block(world: &mut World, markdown: Entity, block: Block) -> ElementsBuilder {
+    match block {
+        Block::Heading(level, text) => {
+            let content = render_inlines(world, markdown, &text);
+            let class = format!("markdown-block markdown-h{level}");
+            eml! {
+                <span class=class>{content}</span>
+            }
+        }
+        Block::Paragraph(text) => {
+            let content = render_inlines(world, markdown, &text);
+            eml! {
+                <span c:markdown-block c:markdown-paragraph>{content}</span>
+            }
+        }
+        Block::Quote(text) => {
+            let content = render_inlines(world, markdown, &text);
+            eml! {
+                <span c:markdown-block c:markdown-quote>{content}</span>
+            }
+        }
+        Block::Item {
+            depth,
+            bullet,
+            text,
+        } => {
+            let content = render_inlines(world, markdown, &text);
+            let style = format!("margin-left: {}px", depth * 16);
+            eml! {
+                <span c:markdown-block c:markdown-item style=style>
+                    <label c:markdown-bullet value=bullet/>
+                    <span c:markdown-item-content>{content}</span>
+                </span>
+            }
+        }
+        Block::Code(code) => eml! {
+            <span c:markdown-block c:markdown-code-block>
+                <label value=code/>
+            </span>
+        },
+        Block::Rule => eml! {
+            <span c:markdown-block c:markdown-rule/>
+        },
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(markdown)]
+#[signal(link, MarkdownEvent, link_pressed)]
+/// The `<markdown>` tag renders the markdown document loaded from the `src`
+/// asset (reloaded with the asset) or the bindable `value` text. The
+/// headings, paragraphs, quotes, lists, rules and code blocks become the
+/// `.markdown-block` elements (`.markdown-h1`, `.markdown-paragraph`,
+/// `.markdown-item`, `.markdown-code-block`, ...) styled with `ess`, the
+/// emphasis and the inline code are rendered by the `<richtext>`, the links
+/// are the `<a>` tags. The pressed link emits the `link` signal with its `href`:
+/// ```rust,ignore
+/// eml! {
+///     <markdown src="docs/help.md" on:link=connect!(help, |ctx, h: Help| {
+///         h.open(ctx.event().href())
+///     })/>
+///     <markdown value=CHANGELOG/>
+/// }
+/// ```
+pub struct Markdown {
+    #[param]
+    pub src: String,
+    #[param]
+    pub value: String,
+    /// The path and the handle of the loaded `src`.
+    source: Option<(String, Handle<MarkdownAsset>)>,
+    /// The rendered markdown.
+    shown: Option<String>,
+}
+
+impl WidgetBuilder for Markdown {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        ctx.insert(ElementBundle::default());
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            markdown {
+                flex-direction: column;
+                align-items: stretch;
+            }
+            markdown .markdown-block {
+                flex-wrap: wrap;
+                margin-bottom: 8px;
+            }
+            markdown .markdown-h1 {
+                font: bold;
+                font-size: 32px;
+            }
+            markdown .markdown-h2 {
+                font: bold;
+                font-size: 26px;
+            }
+            markdown .markdown-h3 {
+                font: bold;
+                font-size: 22px;
+            }
+            markdown .markdown-h4,
+            markdown .markdown-h5,
+            markdown .markdown-h6 {
+                font: bold;
+                font-size: 18px;
+            }
+            markdown .markdown-quote {
+                padding-left: 8px;
+                border-left: 3px;
+                border-color: #7f7f7f;
+                color: #cfcfcf;
+            }
+            markdown .markdown-item {
+                margin-bottom: 2px;
+            }
+            markdown .markdown-bullet {
+                min-width: 20px;
+            }
+            markdown .markdown-item-content {
+                flex-grow: 1;
+                flex-shrink: 1;
+                flex-basis: 0px;
+                flex-wrap: wrap;
+            }
+            markdown .markdown-code-block {
+                padding: 6px;
+                background-color: #0000003f;
+            }
+            markdown .markdown-code {
+                color: #dfbf7f;
+            }
+            markdown .markdown-rule {
+                height: 1px;
+                background-color: #7f7f7f;
+            }
+        "#
+    }
+}
+
+fn load_markdown_sources(
+    asset_server: Res<AssetServer>,
+    mut markdowns: Query<&mut Markdown, Changed<Markdown>>,
+) {
+    for mut markdown in markdowns.iter_mut() {
+        if markdown.src.is_empty() {
+            if markdown.source.is_some() {
+                markdown.source = None;
+            }
+            continue;
+        }
+        if markdown.source.as_ref().map(|(src, _)| src) != Some(&markdown.src) {
+            let handle = asset_server.load(markdown.src.as_str());
+            markdown.source = Some((markdown.src.clone(), handle));
+        }
+    }
+}
+
+/// Re-renders the markdowns showing the loaded or the modified assets.
+fn reload_markdown_assets(
+    mut events: EventReader<AssetEvent<MarkdownAsset>>,
+    mut markdowns: Query<&mut Markdown>,
+) {
+    for ev in events.iter() {
+        let (AssetEvent::Created { handle } | AssetEvent::Modified { handle }) = ev else {
+            continue;
+        };
+        for mut markdown in markdowns.iter_mut() {
+            if markdown.source.as_ref().map(|(_, h)| h) == Some(handle) {
+                markdown.set_changed();
+            }
+        }
+    }
+}
+
+fn render_markdown(
+    mut commands: Commands,
+    assets: Res<Assets<MarkdownAsset>>,
+    mut markdowns: Query<(Entity, &mut Markdown), Changed<Markdown>>,
+) {
+    for (entity, mut markdown) in markdowns.iter_mut() {
+        let text = match &markdown.source {
+            Some((_, handle)) => match assets.get(handle) {
+                Some(asset) => asset.0.clone(),
+                None => continue,
+            },
+            None => markdown.value.clone(),
+        };
+        if markdown.shown.as_ref() == Some(&text) {
+            continue;
+        }
+        markdown.shown = Some(text.clone());
+        commands.entity(entity).despawn_descendants();
+        commands.add(move |world: &mut World| {
+            let blocks: Vec<_> = parse_blocks(&text)
+                .into_iter()
+                .map(|block| render_block(world, entity, block))
+                .collect();
+            for block in blocks {
+                let roots = block.build(world);
+                world.entity_mut(entity).push_children(&roots);
+            }
+        });
+    }
+}
+
+/// Forwards the activated links of the documents to the `<markdown>` tags.
+fn handle_markdown_links(
+    mut activations: EventReader<LinkActivated>,
+    links: Query<&MarkdownLink>,
+    mut events: EventWriter<MarkdownEvent>,
+) {
+    for ev in activations.iter() {
+        let Ok(MarkdownLink(markdown)) = links.get(ev.entities[0]) else {
+            continue;
+        };
+        events.send(MarkdownEvent::LinkPressed([*markdown], ev.href.clone()));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn text(markup: &str) -> Inline {
+        Inline::Text(markup.to_string())
+    }
+
+    #[test]
+    fn parse_document_blocks() {
+        let source = "# Title #\n\nline one\nline two\n- item\n  - nested\n```\n<code>\n```";
+        assert_eq!(
+            parse_blocks(source),
+            vec![
+                Block::Heading(1, "Title".to_string()),
+                Block::Paragraph("line one line two".to_string()),
+                Block::Item {
+                    depth: 0,
+                    bullet: "•".to_string(),
+                    text: "item".to_string(),
+                },
+                Block::Item {
+                    depth: 1,
+                    bullet: "•".to_string(),
+                    text: "nested".to_string(),
+                },
+                Block::Code("<code>".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn keep_unclosed_emphasis() {
+        assert_eq!(
+            parse_inlines("**bold** and *it*"),
+            vec![text("<b>bold</b> and <i>it</i>")]
+        );
+        assert_eq!(
+            parse_inlines("2 * 3 and *open"),
+            vec![text("2 * 3 and *open")]
+        );
+    }
+
+    #[test]
+    fn keep_underscores_inside_words() {
+        assert_eq!(
+            parse_inlines("snake_case_name and _em_"),
+            vec![text("snake_case_name and <i>em</i>")]
+        );
+    }
+
+    #[test]
+    fn parse_nested_link_labels() {
+        assert_eq!(
+            parse_inlines("see [a [b] *c*](x) now"),
+            vec![
+                text("see "),
+                Inline::Link {
+                    markup: "a [b] <i>c</i>".to_string(),
+                    href: "x".to_string(),
+                },
+                text(" now"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_non_ascii_inlines() {
+        assert_eq!(
+            parse_inlines("héllo *wörld* [ссылка](ü)!"),
+            vec![
+                text("héllo <i>wörld</i> "),
+                Inline::Link {
+                    markup: "ссылка".to_string(),
+                    href: "ü".to_string(),
+                },
+                text("!"),
+            ]
+        );
+    }
+
+    #[test]
+    fn escape_markup_in_text() {
+        assert_eq!(
+            parse_inlines("a <b> & `<c>` \\<"),
+            vec![text(
+                "a &lt;b> &amp; <span c:markdown-code>&lt;c></span> &lt;"
+            )]
+        );
+    }
+}
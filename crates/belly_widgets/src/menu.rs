@@ -0,0 +1,762 @@
+use super::common::*;
+use belly_core::*;
+use belly_macro::*;
+use bevy::{
+    ecs::{entity::Entities, system::SystemParam},
+    prelude::*,
+    ui::FocusPolicy,
+};
+
+pub(crate) struct MenuPlugin;
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MenuEvent>();
+        app.register_widget::<PopupMenu>();
+        app.register_widget::<MenuItem>();
+        app.register_widget::<separator>();
+        app.register_widget::<MenuBar>();
+        app.register_widget::<Menu>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            select_menu_items
+                .after(input::Label::Signals)
+                .after(input::Label::Shortcuts),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_menubar_input.after(input::Label::Signals),
+        );
+        app.add_system(attach_context_menus);
+        app.add_system(despawn_detached_menus);
+        app.add_system(reset_context_menus);
+        app.add_system(hover_menu_items.after(reset_context_menus));
+        app.add_system(navigate_context_menu.after(hover_menu_items));
+        app.add_system(hover_menubar.after(hover_menu_items));
+        app.add_system(navigate_menubar.after(hover_menubar));
+        app.add_system(reset_closed_menus.after(navigate_menubar));
+        app.add_system(update_menubars.after(navigate_menubar));
+        app.add_system(update_menu_items.after(reset_closed_menus));
+        app.add_system(update_shortcut_hints);
+    }
+}
+
+pub enum MenuEvent {
+    /// The item is chosen by the press, by the `Enter` key or
+    /// by its shortcut.
+    Selected([Entity; 1]),
+}
+
+impl MenuEvent {
+    pub fn selected(&self) -> bool {
+        match self {
+            MenuEvent::Selected(_) => true,
+        }
+    }
+}
+
+impl Signal for MenuEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            MenuEvent::Selected(source) => source,
+        }
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(contextmenu)]
+/// The `<contextmenu>` tag is the context menu of the element it is
+/// placed into. The menu is hidden until the secondary press over the
+/// element opens it at the pointer. The items are navigated by the arrow
+/// keys: `Right` opens the submenu, `Left` closes it, `Enter` selects
+/// the item and `Escape` closes the menu:
+/// ```rust,ignore
+/// eml! {
+///     <div c:file>
+///         "report.txt"
+///         <contextmenu>
+///             <menuitem label="Open" on:select=connect!(file, |f: File| f.open())/>
+///             <menuitem label="Send to">
+///                 <menuitem label="Desktop"/>
+///                 <menuitem label="Mail"/>
+///             </menuitem>
+///             <separator/>
+///             <menuitem label="Delete" on:select=connect!(file, |f: File| f.delete())/>
+///         </contextmenu>
+///     </div>
+/// }
+/// ```
+pub struct PopupMenu {
+    /// The element the menu is attached to.
+    owner: Option<Entity>,
+}
+
+impl PopupMenu {
+    pub fn owner(&self) -> Option<Entity> {
+        self.owner
+    }
+}
+
+impl WidgetBuilder for PopupMenu {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let content = ctx.content();
+        ctx.render(eml! {
+            <span c:menu
+                s:display=managed() s:position-type=managed()
+                s:left=managed() s:top=managed()
+            >
+                {content}
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            .menu {
+                flex-direction: column;
+                min-width: 160px;
+                padding: 4px 0px;
+                background-color: #2f2f2f;
+                color: #efefef;
+                font-size: 16px;
+            }
+            .menu-submenu {
+                display: none;
+                position-type: absolute;
+                left: 100%;
+                top: -4px;
+            }
+            .menu-submenu:open {
+                display: flex;
+            }
+            menuitem {
+                justify-content: space-between;
+                padding: 4px 12px;
+            }
+            menuitem:current {
+                background-color: #4f4f4f;
+            }
+            menuitem:open {
+                z-index: 1;
+            }
+            menuitem:disabled {
+                color: #7f7f7f;
+            }
+            menuitem .menu-item-arrow {
+                margin-left: 16px;
+            }
+            menuitem .menu-item-shortcut {
+                margin-left: 24px;
+                color: #9f9f9f;
+            }
+            separator {
+                height: 1px;
+                margin: 4px 0px;
+                background-color: #5f5f5f;
+            }
+        "#
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(menuitem)]
+#[signal(select, MenuEvent, selected)]
+/// The `<menuitem>` tag is the item of the `<contextmenu>` with the
+/// bindable `label`. The item with the children opens them as the
+/// submenu, the item without children emits the `select` signal.
+/// The highlighted item gets the `:current` state. The `shortcut` of
+/// the item is shown as the hint and selects the item when pressed.
+pub struct MenuItem {
+    #[param]
+    pub label: String,
+    /// The list of the nested items, if any.
+    submenu: Option<Entity>,
+    /// The label with the shortcut of the item.
+    hint: Option<Entity>,
+    open: bool,
+    current: bool,
+}
+
+impl MenuItem {
+    pub fn submenu(&self) -> Option<Entity> {
+        self.submenu
+    }
+}
+
+impl WidgetBuilder for MenuItem {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let this = ctx.entity();
+        let content = ctx.content();
+        if content.is_empty() {
+            let hint = ctx.commands().spawn_empty().id();
+            self.hint = Some(hint);
+            ctx.render(eml! {
+                <span c:menu-item interactable>
+                    <label c:menu-item-label bind:value=from!(this, MenuItem:label)/>
+                    <label {hint} c:menu-item-shortcut/>
+                </span>
+            });
+            return;
+        }
+        let submenu = ctx.commands().spawn_empty().id();
+        self.submenu = Some(submenu);
+        ctx.insert(KeepContextMenuOpen);
+        ctx.render(eml! {
+            <span c:menu-item interactable>
+                <label c:menu-item-label bind:value=from!(this, MenuItem:label)/>
+                <span c:menu-item-arrow>">"</span>
+                <span {submenu} c:menu c:menu-submenu>{content}</span>
+            </span>
+        })
+    }
+}
+
+#[widget]
+/// The `<separator>` tag is the line between the groups of the items.
+fn separator(ctx: &mut ElementContext) {
+    ctx.insert(ElementBundle::default());
+}
+
+#[derive(Component, Widget)]
+#[alias(menubar)]
+/// The `<menubar>` tag is the row of the `<menu>` titles. The pressed
+/// title opens its dropdown, the hovered title switches the open dropdown
+/// to its own. The items of the dropdown are the `<menuitem>` tags, the
+/// arrow keys move over the items and over the menus, `Escape` and the
+/// press outside of the dropdown close it:
+/// ```rust,ignore
+/// eml! {
+///     <menubar>
+///         <menu label="File">
+///             <menuitem label="Save" shortcut="Ctrl+S" on:select=connect!(editor, |e: Editor| e.save())/>
+///             <menuitem label="Export">
+///                 <menuitem label="PNG"/>
+///                 <menuitem label="SVG"/>
+///             </menuitem>
+///             <separator/>
+///             <menuitem label="Quit" shortcut="Ctrl+Q"/>
+///         </menu>
+///         <menu label="Edit">...</menu>
+///     </menubar>
+/// }
+/// ```
+pub struct MenuBar {
+    /// The menu with the open dropdown.
+    open: Option<Entity>,
+}
+
+impl MenuBar {
+    pub fn open(&self) -> Option<Entity> {
+        self.open
+    }
+
+    pub fn close(&mut self) {
+        self.open = None;
+    }
+}
+
+impl WidgetBuilder for MenuBar {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let content = ctx.content();
+        ctx.render(eml! {
+            <span c:menubar>{content}</span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            menubar {
+                width: 100%;
+                flex-shrink: 0;
+                background-color: #2f2f2f;
+                color: #efefef;
+                font-size: 16px;
+            }
+            menu {
+                padding: 4px 10px;
+            }
+            menu:hover {
+                background-color: #3f3f3f;
+            }
+            menu:open {
+                background-color: #4f4f4f;
+            }
+            .menu-dropdown {
+                display: none;
+                position-type: absolute;
+                left: 0px;
+                top: 100%;
+                z-index: global(1000);
+            }
+            .menu-dropdown:open {
+                display: flex;
+            }
+        "#
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(menu)]
+/// The `<menu>` tag is the title of the `<menubar>` with the bindable
+/// `label` and the dropdown of its items.
+pub struct Menu {
+    #[param]
+    pub label: String,
+    dropdown: Entity,
+}
+
+impl Menu {
+    pub fn dropdown(&self) -> Entity {
+        self.dropdown
+    }
+}
+
+impl WidgetBuilder for Menu {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let this = ctx.entity();
+        let dropdown = self.dropdown;
+        let content = ctx.content();
+        ctx.render(eml! {
+            <span c:menubar-menu interactable>
+                <label bind:value=from!(this, Menu:label)/>
+                <span {dropdown} c:menu c:menu-dropdown>{content}</span>
+            </span>
+        })
+    }
+}
+
+/// The menu items grouped into the lists: the list is the `<contextmenu>`
+/// or the submenu of the item.
+#[derive(SystemParam)]
+pub(crate) struct Menus<'w, 's> {
+    items: Query<'w, 's, (&'static mut MenuItem, Option<&'static States>)>,
+    parents: Query<'w, 's, &'static Parent>,
+    children: Query<'w, 's, &'static Children>,
+}
+
+impl<'w, 's> Menus<'w, 's> {
+    /// The items of the list.
+    pub(crate) fn items(&self, list: Entity) -> Vec<Entity> {
+        self.children
+            .get(list)
+            .map(|children| {
+                children
+                    .iter()
+                    .copied()
+                    .filter(|e| self.items.contains(*e))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The enabled items of the list.
+    pub(crate) fn enabled(&self, list: Entity) -> Vec<Entity> {
+        self.items(list)
+            .into_iter()
+            .filter(|e| {
+                let states = self.items.get(*e).ok().and_then(|(_, s)| s);
+                !states.map(|s| s.disabled()).unwrap_or(false)
+            })
+            .collect()
+    }
+
+    pub(crate) fn submenu(&self, item: Entity) -> Option<Entity> {
+        self.items.get(item).ok().and_then(|(i, _)| i.submenu)
+    }
+
+    pub(crate) fn is_current(&self, item: Entity) -> bool {
+        self.items
+            .get(item)
+            .map(|(i, _)| i.current)
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn is_open(&self, item: Entity) -> bool {
+        self.items.get(item).map(|(i, _)| i.open).unwrap_or(false)
+    }
+
+    /// The deepest open list starting from the `root` one.
+    pub(crate) fn active_list(&self, root: Entity) -> Entity {
+        let mut list = root;
+        while let Some(submenu) = self
+            .items(list)
+            .into_iter()
+            .find(|e| self.is_open(*e))
+            .and_then(|e| self.submenu(e))
+        {
+            list = submenu;
+        }
+        list
+    }
+
+    /// The item the submenu belongs to.
+    pub(crate) fn owner_item(&self, submenu: Entity) -> Option<Entity> {
+        self.parents
+            .get(submenu)
+            .ok()
+            .map(|p| p.get())
+            .filter(|e| self.submenu(*e) == Some(submenu))
+    }
+
+    /// Makes the item the current one of its list and closes the
+    /// submenus of the other items. The submenu of the item is opened
+    /// when `open` is `true`.
+    pub(crate) fn highlight(&mut self, item: Entity, open: bool) {
+        let Ok(list) = self.parents.get(item).map(|p| p.get()) else {
+            return;
+        };
+        for other in self.items(list) {
+            let current = other == item;
+            let open = current && open && self.submenu(other).is_some();
+            if !open {
+                self.close(other);
+            }
+            let Ok((mut state, _)) = self.items.get_mut(other) else {
+                continue;
+            };
+            if state.current != current {
+                state.current = current;
+            }
+            if state.open != open {
+                state.open = open;
+            }
+        }
+    }
+
+    /// Closes the submenu of the item along with the nested ones.
+    pub(crate) fn close(&mut self, item: Entity) {
+        let Some(submenu) = self.submenu(item) else {
+            return;
+        };
+        for nested in self.items(submenu) {
+            self.close(nested);
+            if let Ok((mut state, _)) = self.items.get_mut(nested) {
+                if state.current {
+                    state.current = false;
+                }
+            }
+        }
+        if let Ok((mut state, _)) = self.items.get_mut(item) {
+            if state.open {
+                state.open = false;
+            }
+        }
+    }
+
+    /// Closes all the submenus of the list and drops the highlight.
+    pub(crate) fn reset(&mut self, list: Entity) {
+        for item in self.items(list) {
+            self.close(item);
+            if let Ok((mut state, _)) = self.items.get_mut(item) {
+                if state.current {
+                    state.current = false;
+                }
+            }
+        }
+    }
+}
+
+fn attach_context_menus(
+    mut commands: Commands,
+    mut menus: Query<(Entity, &mut PopupMenu, &mut Style, &mut Visibility), Added<PopupMenu>>,
+    parents: Query<&Parent>,
+    interactable: Query<(), With<Interaction>>,
+) {
+    for (entity, mut menu, mut style, mut visibility) in menus.iter_mut() {
+        style.display = Display::None;
+        visibility.is_visible = false;
+        let Ok(parent) = parents.get(entity).map(|p| p.get()) else {
+            continue;
+        };
+        // the secondary press hits the interactable elements only
+        let owner = parents
+            .iter_ancestors(entity)
+            .find(|e| interactable.contains(*e))
+            .unwrap_or(parent);
+        if !interactable.contains(owner) {
+            commands
+                .entity(owner)
+                .insert((FocusPolicy::Block, Interaction::default()));
+        }
+        commands.entity(owner).insert(ContextMenuElement(entity));
+        // the popup is positioned relative to the window
+        commands.entity(entity).remove_parent();
+        menu.owner = Some(owner);
+    }
+}
+
+fn despawn_detached_menus(
+    mut commands: Commands,
+    entities: &Entities,
+    menus: Query<(Entity, &PopupMenu)>,
+) {
+    for (entity, menu) in menus.iter() {
+        if matches!(menu.owner, Some(owner) if !entities.contains(owner)) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn reset_context_menus(
+    open_menu: Res<OpenContextMenu>,
+    mut last: Local<Option<Entity>>,
+    popups: Query<(), With<PopupMenu>>,
+    mut menus: Menus,
+) {
+    if open_menu.get() == *last {
+        return;
+    }
+    for popup in [*last, open_menu.get()].into_iter().flatten() {
+        if popups.contains(popup) {
+            menus.reset(popup);
+        }
+    }
+    *last = open_menu.get();
+}
+
+fn hover_menu_items(
+    hovered: Query<(Entity, &Interaction), (Changed<Interaction>, With<MenuItem>)>,
+    mut menus: Menus,
+) {
+    for (item, interaction) in hovered.iter() {
+        if *interaction != Interaction::None {
+            menus.highlight(item, true);
+        }
+    }
+}
+
+/// The result of the keyboard navigation over the open menu.
+enum MenuNavigation {
+    /// The item is selected by `Enter`.
+    Selected(Entity),
+    /// `Left` is pressed over the top list.
+    Previous,
+    /// `Right` is pressed over the item without the submenu.
+    Next,
+}
+
+/// Moves the `:current` item over the lists of the menu by the arrow keys.
+fn navigate_menu(
+    keyboard: &Input<KeyCode>,
+    menus: &mut Menus,
+    root: Entity,
+) -> Option<MenuNavigation> {
+    let list = menus.active_list(root);
+    let items = menus.enabled(list);
+    if items.is_empty() {
+        return None;
+    }
+    let len = items.len();
+    let current = items.iter().position(|e| menus.is_current(*e));
+    let enter = keyboard.any_just_pressed([KeyCode::Return, KeyCode::NumpadEnter, KeyCode::Space]);
+    let right = keyboard.just_pressed(KeyCode::Right);
+    if keyboard.just_pressed(KeyCode::Down) {
+        let next = current.map(|i| (i + 1) % len).unwrap_or(0);
+        menus.highlight(items[next], false);
+    } else if keyboard.just_pressed(KeyCode::Up) {
+        let next = current.map(|i| (i + len - 1) % len).unwrap_or(len - 1);
+        menus.highlight(items[next], false);
+    } else if keyboard.just_pressed(KeyCode::Left) {
+        match menus.owner_item(list) {
+            Some(item) if list != root => menus.highlight(item, false),
+            _ => return Some(MenuNavigation::Previous),
+        }
+    } else if let Some(item) = current.map(|i| items[i]) {
+        match menus.submenu(item) {
+            Some(submenu) if enter || right => {
+                menus.highlight(item, true);
+                if let Some(first) = menus.enabled(submenu).first() {
+                    menus.highlight(*first, false);
+                }
+            }
+            None if enter => return Some(MenuNavigation::Selected(item)),
+            None if right => return Some(MenuNavigation::Next),
+            _ => {}
+        }
+    } else if right {
+        return Some(MenuNavigation::Next);
+    }
+    None
+}
+
+fn navigate_context_menu(
+    keyboard: Res<Input<KeyCode>>,
+    mut open_menu: ResMut<OpenContextMenu>,
+    popups: Query<(), With<PopupMenu>>,
+    mut menus: Menus,
+    mut events: EventWriter<MenuEvent>,
+    mut feedback: EventWriter<FeedbackRequest>,
+) {
+    let Some(root) = open_menu.get().filter(|p| popups.contains(*p)) else {
+        return;
+    };
+    if let Some(MenuNavigation::Selected(item)) = navigate_menu(&keyboard, &mut menus, root) {
+        events.send(MenuEvent::Selected([item]));
+        feedback.send(FeedbackRequest::new(FeedbackAction::Confirm, item));
+        open_menu.close();
+    }
+}
+
+fn select_menu_items(
+    mut pointer: EventReader<PointerInput>,
+    mut shortcuts: EventReader<ShortcutEvent>,
+    items: Query<(&MenuItem, Option<&States>)>,
+    mut events: EventWriter<MenuEvent>,
+    mut feedback: EventWriter<FeedbackRequest>,
+) {
+    let pressed = pointer
+        .iter()
+        .filter(|e| e.pressed())
+        .filter_map(|e| e.entities.first().copied());
+    let triggered = shortcuts.iter().flat_map(|e| e.sources()).copied();
+    for target in pressed.chain(triggered) {
+        let Ok((item, states)) = items.get(target) else {
+            continue;
+        };
+        let disabled = states.map(|s| s.disabled()).unwrap_or(false);
+        if item.submenu.is_none() && !disabled {
+            events.send(MenuEvent::Selected([target]));
+            feedback.send(FeedbackRequest::new(FeedbackAction::Confirm, target));
+        }
+    }
+}
+
+fn update_shortcut_hints(
+    items: Query<(&MenuItem, &Shortcut), Changed<Shortcut>>,
+    mut labels: Query<&mut Label>,
+) {
+    for (item, shortcut) in items.iter() {
+        let Some(mut label) = item.hint.and_then(|hint| labels.get_mut(hint).ok()) else {
+            continue;
+        };
+        let hint = shortcut.to_string();
+        if label.value != hint {
+            label.value = hint;
+        }
+    }
+}
+
+fn update_menu_items(mut elements: Elements, items: Query<(Entity, &MenuItem), Changed<MenuItem>>) {
+    for (entity, item) in items.iter() {
+        elements.set_state(entity, "current".as_tag(), item.current);
+        elements.set_state(entity, "open".as_tag(), item.open);
+        if let Some(submenu) = item.submenu {
+            elements.set_state(submenu, "open".as_tag(), item.open);
+        }
+    }
+}
+
+fn handle_menubar_input(
+    mut pointer: EventReader<PointerInput>,
+    mut bars: Query<(Entity, &mut MenuBar)>,
+    titles: Query<&Parent, With<Menu>>,
+    keep_open: Query<(), With<KeepContextMenuOpen>>,
+) {
+    for ev in pointer.iter().filter(|e| e.pressed()) {
+        let target = ev.entities.first().copied();
+        let title = target.and_then(|t| titles.get(t).ok().map(|p| (t, p.get())));
+        // the press on the item opening the submenu keeps the dropdown open
+        if title.is_none() && target.map(|t| keep_open.contains(t)).unwrap_or(false) {
+            continue;
+        }
+        for (entity, mut bar) in bars.iter_mut() {
+            let open = match title {
+                Some((menu, owner)) if owner == entity && bar.open != Some(menu) => Some(menu),
+                _ => None,
+            };
+            if bar.open != open {
+                bar.open = open;
+            }
+        }
+    }
+}
+
+fn hover_menubar(
+    hovered: Query<(Entity, &Interaction, &Parent), (Changed<Interaction>, With<Menu>)>,
+    mut bars: Query<&mut MenuBar>,
+) {
+    for (menu, interaction, parent) in hovered.iter() {
+        if *interaction != Interaction::Hovered {
+            continue;
+        }
+        let Ok(mut bar) = bars.get_mut(parent.get()) else {
+            continue;
+        };
+        if matches!(bar.open, Some(open) if open != menu) {
+            bar.open = Some(menu);
+        }
+    }
+}
+
+fn navigate_menubar(
+    keyboard: Res<Input<KeyCode>>,
+    mut bars: Query<(&mut MenuBar, &Children)>,
+    titles: Query<&Menu>,
+    mut menus: Menus,
+    mut events: EventWriter<MenuEvent>,
+    mut feedback: EventWriter<FeedbackRequest>,
+) {
+    for (mut bar, children) in bars.iter_mut() {
+        let Some(open) = bar.open else {
+            continue;
+        };
+        if keyboard.just_pressed(KeyCode::Escape) {
+            bar.open = None;
+            continue;
+        }
+        let Ok(menu) = titles.get(open) else {
+            continue;
+        };
+        let all: Vec<_> = children
+            .iter()
+            .copied()
+            .filter(|e| titles.contains(*e))
+            .collect();
+        let len = all.len();
+        let index = all.iter().position(|e| *e == open).unwrap_or(0);
+        match navigate_menu(&keyboard, &mut menus, menu.dropdown) {
+            Some(MenuNavigation::Selected(item)) => {
+                events.send(MenuEvent::Selected([item]));
+                feedback.send(FeedbackRequest::new(FeedbackAction::Confirm, item));
+                bar.open = None;
+            }
+            Some(MenuNavigation::Previous) => bar.open = Some(all[(index + len - 1) % len]),
+            Some(MenuNavigation::Next) => bar.open = Some(all[(index + 1) % len]),
+            None => {}
+        }
+    }
+}
+
+/// Drops the highlight and the open submenus of the closed dropdowns.
+fn reset_closed_menus(
+    bars: Query<(&MenuBar, &Children), Changed<MenuBar>>,
+    titles: Query<&Menu>,
+    mut menus: Menus,
+) {
+    for (bar, children) in bars.iter() {
+        for entity in children.iter().copied() {
+            let Ok(menu) = titles.get(entity) else {
+                continue;
+            };
+            if bar.open != Some(entity) {
+                menus.reset(menu.dropdown);
+            }
+        }
+    }
+}
+
+fn update_menubars(
+    mut elements: Elements,
+    bars: Query<(&MenuBar, &Children), Changed<MenuBar>>,
+    titles: Query<&Menu>,
+) {
+    for (bar, children) in bars.iter() {
+        for entity in children.iter().copied() {
+            let Ok(menu) = titles.get(entity) else {
+                continue;
+            };
+            let open = bar.open == Some(entity);
+            elements.set_state(entity, "open".as_tag(), open);
+            elements.set_state(menu.dropdown, "open".as_tag(), open);
+        }
+    }
+}
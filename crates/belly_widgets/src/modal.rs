@@ -0,0 +1,279 @@
+use belly_core::*;
+use belly_macro::*;
+use bevy::{
+    ecs::{entity::Entities, system::Command},
+    prelude::*,
+};
+
+/// The open modals are drawn over the rest of the UI.
+const MODAL_LAYER: i32 = i32::MAX - 1024;
+
+pub(crate) struct ModalPlugin;
+impl Plugin for ModalPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ModalEvent>();
+        app.init_resource::<ModalStack>();
+        app.register_widget::<Modal>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            close_modal_system.after(input::Label::Signals),
+        );
+        app.add_system(update_modals);
+    }
+}
+
+pub enum ModalEvent {
+    Opened([Entity; 1]),
+    Closed([Entity; 1]),
+}
+
+impl ModalEvent {
+    pub fn opened(&self) -> bool {
+        match self {
+            ModalEvent::Opened(_) => true,
+            _ => false,
+        }
+    }
+    pub fn closed(&self) -> bool {
+        match self {
+            ModalEvent::Closed(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Signal for ModalEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            ModalEvent::Opened(source) => source,
+            ModalEvent::Closed(source) => source,
+        }
+    }
+}
+
+/// The open modals from the bottom one to the topmost one.
+#[derive(Resource, Default)]
+pub struct ModalStack {
+    /// The modals with the elements focused before they were opened.
+    entries: Vec<(Entity, Option<Entity>)>,
+}
+
+impl ModalStack {
+    pub fn top(&self) -> Option<Entity> {
+        self.entries.last().map(|(modal, _)| *modal)
+    }
+
+    pub fn contains(&self, modal: Entity) -> bool {
+        self.entries.iter().any(|(e, _)| *e == modal)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entries.iter().map(|(modal, _)| *modal)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Opens the `<modal>` over the open ones.
+pub struct OpenModal(pub Entity);
+
+impl Command for OpenModal {
+    fn write(self, world: &mut World) {
+        if let Some(mut modal) = world.get_mut::<Modal>(self.0) {
+            modal.open = true;
+        }
+    }
+}
+
+/// Closes the `<modal>`.
+pub struct CloseModal(pub Entity);
+
+impl Command for CloseModal {
+    fn write(self, world: &mut World) {
+        if let Some(mut modal) = world.get_mut::<Modal>(self.0) {
+            modal.open = false;
+        }
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(modal)]
+#[signal(open, ModalEvent, opened)]
+#[signal(close, ModalEvent, closed)]
+/// The `<modal>` tag is the dialog (`.modal-window`) over the dimming
+/// backdrop, shown while the bindable `open` param is `true`. The modal
+/// opened over another one goes on top of the [`ModalStack`]. The open
+/// modal takes the focus, keeps the Tab navigation and the shortcuts
+/// inside and returns the focus back when closed. `Escape` and the press
+/// on the backdrop close the topmost modal unless it is `persistent`:
+/// ```rust,ignore
+/// eml! {
+///     <modal bind:open=from!(Menu:confirm_quit)>
+///         "Quit the game?"
+///         <button on:press=connect!(menu, |m: Menu| m.quit())>"Quit"</button>
+///     </modal>
+/// }
+/// ```
+/// The modals are opened and closed from the code with the [`OpenModal`]
+/// and [`CloseModal`] commands.
+pub struct Modal {
+    #[param]
+    pub open: bool,
+    #[param]
+    pub persistent: bool,
+    window: Entity,
+}
+
+impl WidgetBuilder for Modal {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let content = ctx.content();
+        let window = self.window;
+        ctx.insert((ShortcutScope, FocusTrap));
+        ctx.render(eml! {
+            <span c:modal interactable>
+                // the window keeps the presses from the backdrop
+                <span {window} c:modal-window interactable tab-index=-1>
+                    {content}
+                </span>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            modal {
+                display: none;
+                position-type: absolute;
+                left: 0px;
+                right: 0px;
+                top: 0px;
+                bottom: 0px;
+                justify-content: center;
+                align-items: center;
+                background-color: #0000007f;
+            }
+            modal:open {
+                display: flex;
+            }
+            modal .modal-window {
+                flex-direction: column;
+                align-items: center;
+                padding: 20px;
+                background-color: #efefef;
+                color: #2f2f2f;
+            }
+        "#
+    }
+}
+
+fn update_modals(
+    mut commands: Commands,
+    mut elements: Elements,
+    mut stack: ResMut<ModalStack>,
+    focused: Res<Focused>,
+    entities: &Entities,
+    mut modals: Query<(Entity, &Modal, &mut Visibility), Changed<Modal>>,
+    mut requests: EventWriter<RequestFocus>,
+    mut events: EventWriter<ModalEvent>,
+) {
+    stack.entries.retain(|(modal, _)| entities.contains(*modal));
+    for (entity, modal, mut visibility) in modals.iter_mut() {
+        // the closed modal is hidden, so its scopes are ignored
+        if visibility.is_visible != modal.open {
+            visibility.is_visible = modal.open;
+        }
+        if modal.open == stack.contains(entity) {
+            continue;
+        }
+        elements.set_state(entity, "open".as_tag(), modal.open);
+        if modal.open {
+            stack.entries.push((entity, focused.get()));
+            let layer = MODAL_LAYER + stack.len() as i32;
+            commands.entity(entity).insert(ZIndex::Global(layer));
+            requests.send(RequestFocus(entity));
+            events.send(ModalEvent::Opened([entity]));
+        } else {
+            let index = stack.entries.iter().position(|(e, _)| *e == entity);
+            let restore = index.and_then(|index| stack.entries.remove(index).1);
+            if let Some(restore) = restore.filter(|e| entities.contains(*e)) {
+                requests.send(RequestFocus(restore));
+            }
+            events.send(ModalEvent::Closed([entity]));
+        }
+    }
+}
+
+/// Closes the topmost modal by `Escape` and by the press on its backdrop.
+fn close_modal_system(
+    keyboard: Res<Input<KeyCode>>,
+    context_menu: Res<OpenContextMenu>,
+    stack: Res<ModalStack>,
+    mut pointer: EventReader<PointerInput>,
+    mut modals: Query<&mut Modal>,
+    mut feedback: EventWriter<FeedbackRequest>,
+) {
+    let Some(top) = stack.top() else {
+        return;
+    };
+    let backdrop = pointer
+        .iter()
+        .any(|e| e.pressed() && e.entities.first() == Some(&top));
+    // the context menu over the modal is closed first
+    let escape = keyboard.just_pressed(KeyCode::Escape) && context_menu.get().is_none();
+    if !backdrop && !escape {
+        return;
+    }
+    let Ok(mut modal) = modals.get_mut(top) else {
+        return;
+    };
+    if modal.open && !modal.persistent {
+        modal.open = false;
+        feedback.send(FeedbackRequest::new(FeedbackAction::Cancel, top));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cancel_feedback_on_escape() {
+        let mut app = App::new();
+        app.init_resource::<Input<KeyCode>>()
+            .init_resource::<OpenContextMenu>()
+            .add_event::<PointerInput>()
+            .add_event::<FeedbackRequest>()
+            .add_system(close_modal_system);
+        let window = app.world.spawn_empty().id();
+        let modal = app
+            .world
+            .spawn(Modal {
+                open: true,
+                persistent: false,
+                window,
+            })
+            .id();
+        app.insert_resource(ModalStack {
+            entries: vec![(modal, None)],
+        });
+        app.world
+            .resource_mut::<Input<KeyCode>>()
+            .press(KeyCode::Escape);
+        app.update();
+
+        assert!(!app.world.get::<Modal>(modal).unwrap().open);
+        let requests: Vec<_> = app
+            .world
+            .resource_mut::<Events<FeedbackRequest>>()
+            .drain()
+            .map(|request| (request.action, request.source))
+            .collect();
+        assert_eq!(requests, vec![(FeedbackAction::Cancel, modal)]);
+    }
+}
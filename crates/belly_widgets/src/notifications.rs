@@ -0,0 +1,319 @@
+use belly_core::*;
+use belly_macro::*;
+use bevy::prelude::*;
+use std::{collections::VecDeque, str::FromStr};
+
+/// How long the toast slides in and out, in seconds.
+const TOAST_TRANSITION: f32 = 0.25;
+/// How far the toast slides from the edge of the screen.
+const TOAST_SLIDE: f32 = 48.;
+/// The toasts are drawn over the modals, but below the tooltips.
+const TOAST_LAYER: i32 = i32::MAX - 512;
+
+pub(crate) struct NotificationsPlugin;
+impl Plugin for NotificationsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Notifications>();
+        app.register_widget::<Toast>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            dismiss_pressed_toasts.after(input::Label::Signals),
+        );
+        app.add_system(show_notifications);
+        app.add_system(animate_toasts.after(show_notifications));
+    }
+}
+
+/// The level of the notification, the toast gets the `toast-<level>` class.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Level {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn class(&self) -> &'static str {
+        match self {
+            Level::Info => "toast-info",
+            Level::Success => "toast-success",
+            Level::Warning => "toast-warning",
+            Level::Error => "toast-error",
+        }
+    }
+}
+
+impl From<Level> for Variant {
+    fn from(level: Level) -> Self {
+        Variant::boxed(level)
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "info" => Ok(Level::Info),
+            "success" => Ok(Level::Success),
+            "warning" => Ok(Level::Warning),
+            "error" => Ok(Level::Error),
+            s => Err(format!("Don't know how to parse '{s}' as Level")),
+        }
+    }
+}
+
+impl TryFrom<Variant> for Level {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value.get_or_parse()
+    }
+}
+
+/// The corner of the screen the toasts are stacked in.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl Corner {
+    fn left(&self) -> bool {
+        matches!(self, Corner::TopLeft | Corner::BottomLeft)
+    }
+
+    fn top(&self) -> bool {
+        matches!(self, Corner::TopLeft | Corner::TopRight)
+    }
+}
+
+/// The notification subsystem. The pushed notifications are shown as
+/// the toasts stacked in the `corner` of the screen, the oldest toast
+/// is the closest one to the corner. Each toast is dismissed after
+/// `duration` seconds or when pressed. No more than `limit` toasts are
+/// shown at once, the rest wait in the queue:
+/// ```rust,ignore
+/// fn save(mut notifications: ResMut<Notifications>) {
+///     notifications.push("Saved!", Level::Info);
+/// }
+/// ```
+#[derive(Resource)]
+pub struct Notifications {
+    pub corner: Corner,
+    /// How long the toast is shown, in seconds. The toasts stay until
+    /// pressed when it is zero.
+    pub duration: f32,
+    pub limit: usize,
+    queue: VecDeque<(String, Level)>,
+    container: Option<Entity>,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Notifications {
+            corner: Corner::default(),
+            duration: 3.,
+            limit: 5,
+            queue: VecDeque::new(),
+            container: None,
+        }
+    }
+}
+
+impl Notifications {
+    pub fn push(&mut self, text: impl Into<String>, level: Level) {
+        self.queue.push_back((text.into(), level));
+    }
+
+    /// The number of the notifications waiting for the free slot.
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Drops the notifications waiting in the queue, the shown
+    /// toasts stay until dismissed.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(toast)]
+/// The `<toast>` tag is the notification spawned by the [`Notifications`]
+/// subsystem, it is not meant to be used in the markup directly. The
+/// `level` of the toast is turned into the `toast-<level>` class.
+pub struct Toast {
+    #[param]
+    pub value: String,
+    #[param]
+    pub level: Level,
+    /// Seconds since the toast is shown.
+    age: f32,
+    /// Seconds since the toast is dismissed.
+    closing: Option<f32>,
+}
+
+impl Toast {
+    /// Slides the toast out and despawns it.
+    pub fn dismiss(&mut self) {
+        if self.closing.is_none() {
+            self.closing = Some(0.);
+        }
+    }
+}
+
+impl WidgetBuilder for Toast {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let this = ctx.entity();
+        let class = self.level.class();
+        ctx.render(eml! {
+            <span c:toast class=class interactable s:left=managed()>
+                <label bind:value=from!(this, Toast:value)/>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            .notifications {
+                position-type: absolute;
+                padding: 10px;
+            }
+            .toast {
+                min-width: 200px;
+                max-width: 400px;
+                margin: 4px 0px;
+                padding: 8px 12px;
+                background-color: #2f2f2fe8;
+                color: #efefef;
+                font-size: 16px;
+            }
+            .toast-success {
+                background-color: #2f6f3fe8;
+            }
+            .toast-warning {
+                background-color: #8f6f1fe8;
+            }
+            .toast-error {
+                background-color: #8f2f2fe8;
+            }
+        "#
+    }
+}
+
+fn show_notifications(
+    mut commands: Commands,
+    mut notifications: ResMut<Notifications>,
+    mut corner: Local<Option<Corner>>,
+    mut styles: Query<&mut Style>,
+    toasts: Query<&Toast>,
+    children: Query<&Children>,
+) {
+    let container = *notifications.container.get_or_insert_with(|| {
+        let container = commands.spawn_empty().id();
+        commands.add(
+            eml! {
+                <span c:notifications
+                    s:left=managed() s:right=managed()
+                    s:top=managed() s:bottom=managed()
+                    s:flex-direction=managed() s:align-items=managed()/>
+            }
+            .with_entity(container),
+        );
+        commands
+            .entity(container)
+            .insert(ZIndex::Global(TOAST_LAYER));
+        container
+    });
+    if *corner != Some(notifications.corner) {
+        if let Ok(mut style) = styles.get_mut(container) {
+            let value = notifications.corner;
+            let (edge, none) = (Val::Px(0.), Val::Undefined);
+            style.position.left = if value.left() { edge } else { none };
+            style.position.right = if value.left() { none } else { edge };
+            style.position.top = if value.top() { edge } else { none };
+            style.position.bottom = if value.top() { none } else { edge };
+            style.flex_direction = if value.top() {
+                FlexDirection::Column
+            } else {
+                FlexDirection::ColumnReverse
+            };
+            style.align_items = if value.left() {
+                AlignItems::FlexStart
+            } else {
+                AlignItems::FlexEnd
+            };
+            *corner = Some(value);
+        }
+    }
+    let shown = children
+        .get(container)
+        .map(|children| {
+            children
+                .iter()
+                .filter(|e| {
+                    toasts
+                        .get(**e)
+                        .map(|t| t.closing.is_none())
+                        .unwrap_or(false)
+                })
+                .count()
+        })
+        .unwrap_or(0);
+    for _ in shown..notifications.limit {
+        let Some((text, level)) = notifications.queue.pop_front() else {
+            break;
+        };
+        let toast = commands.spawn_empty().id();
+        commands.add(eml! { <toast value=text level=level/> }.with_entity(toast));
+        commands.entity(container).add_child(toast);
+    }
+}
+
+fn animate_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    notifications: Res<Notifications>,
+    mut toasts: Query<(Entity, &mut Toast, &mut Style)>,
+) {
+    let delta = time.delta_seconds();
+    let side = if notifications.corner.left() { -1. } else { 1. };
+    for (entity, mut toast, mut style) in toasts.iter_mut() {
+        toast.age += delta;
+        if notifications.duration > 0. && toast.age >= notifications.duration {
+            toast.dismiss();
+        }
+        let shown = match toast.closing.as_mut() {
+            Some(closing) => {
+                *closing += delta;
+                if *closing >= TOAST_TRANSITION {
+                    commands.entity(entity).despawn_recursive();
+                    continue;
+                }
+                1. - *closing / TOAST_TRANSITION
+            }
+            None => (toast.age / TOAST_TRANSITION).min(1.),
+        };
+        // smoothstep easing
+        let shown = shown * shown * (3. - 2. * shown);
+        let offset = Val::Px(side * TOAST_SLIDE * (1. - shown));
+        if style.position.left != offset {
+            style.position.left = offset;
+        }
+    }
+}
+
+fn dismiss_pressed_toasts(mut events: EventReader<PointerInput>, mut toasts: Query<&mut Toast>) {
+    for ev in events.iter().filter(|e| e.pressed()) {
+        let Some(&target) = ev.entities.first() else {
+            continue;
+        };
+        if let Ok(mut toast) = toasts.get_mut(target) {
+            toast.dismiss();
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use belly_core::*;
+use belly_macro::*;
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics},
+    prelude::*,
+    ui::UiSystem,
+    utils::{HashMap, Instant},
+};
+
+/// Optional devtool plugin: collects frame spans into [`ProfilerSpans`]
+/// and registers the `<profiler>` widget. The span durations are reported
+/// to the bevy [`Diagnostics`] as well, so the `LogDiagnosticsPlugin` and
+/// the other diagnostics consumers see them. It is not the part of the
+/// [`WidgetsPlugin`](crate::WidgetsPlugin) and should be added manually.
+pub struct ProfilerPlugin;
+impl Plugin for ProfilerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProfilerSpans>();
+        app.register_widget::<Profiler>();
+        app.add_system_to_stage(CoreStage::First, begin_frame);
+        app.add_system_to_stage(CoreStage::Last, end_frame);
+        app.add_system_to_stage(CoreStage::Last, measure_spans.after(end_frame));
+        app.add_stage_before(
+            CoreStage::Update,
+            ProfilerStage::BeforeUpdate,
+            SystemStage::single(|mut spans: ResMut<ProfilerSpans>| spans.begin("update")),
+        );
+        app.add_stage_after(
+            CoreStage::Update,
+            ProfilerStage::AfterUpdate,
+            SystemStage::single(|mut spans: ResMut<ProfilerSpans>| spans.end("update")),
+        );
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            (|mut spans: ResMut<ProfilerSpans>| spans.begin("ui-layout")).before(UiSystem::Flex),
+        );
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            (|mut spans: ResMut<ProfilerSpans>| spans.end("ui-layout")).after(UiSystem::Flex),
+        );
+        app.add_system(update_profiler_view);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+enum ProfilerStage {
+    BeforeUpdate,
+    AfterUpdate,
+}
+
+/// Single measured span of the frame. `start` and `duration` are
+/// in seconds relative to the frame start.
+#[derive(Debug, Clone)]
+pub struct ProfilerSpan {
+    pub name: String,
+    pub depth: usize,
+    pub start: f32,
+    pub duration: f32,
+}
+
+/// Collects spans of the current frame and keeps the spans of the
+/// previous complete frame for displaying. Besides the builtin
+/// `frame`, `update` and `ui-layout` spans any app span can be
+/// recorded with [`ProfilerSpans::begin`] and [`ProfilerSpans::end`]:
+/// ```ignore
+/// fn my_system(mut spans: ResMut<ProfilerSpans>) {
+///     spans.begin("my-system");
+///     // heavy work
+///     spans.end("my-system");
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct ProfilerSpans {
+    frame_start: Option<Instant>,
+    /// The started spans with their start time and depth.
+    stack: Vec<(String, Instant, usize)>,
+    current: Vec<ProfilerSpan>,
+    last: Vec<ProfilerSpan>,
+    last_frame: f32,
+}
+
+impl ProfilerSpans {
+    pub fn begin(&mut self, name: &str) {
+        let depth = self.stack.len();
+        self.stack.push((name.to_string(), Instant::now(), depth));
+    }
+
+    pub fn end(&mut self, name: &str) {
+        let Some(idx) = self.stack.iter().rposition(|(n, _, _)| n == name) else {
+            warn!("Trying to end profiler span `{name}` which was never started");
+            return;
+        };
+        let (name, started, depth) = self.stack.remove(idx);
+        let Some(frame_start) = self.frame_start else { return };
+        self.current.push(ProfilerSpan {
+            name,
+            depth,
+            start: started.saturating_duration_since(frame_start).as_secs_f32(),
+            duration: started.elapsed().as_secs_f32(),
+        });
+    }
+
+    /// Spans of the last complete frame.
+    pub fn spans(&self) -> &[ProfilerSpan] {
+        &self.last
+    }
+
+    /// Duration of the last complete frame in seconds.
+    pub fn frame_duration(&self) -> f32 {
+        self.last_frame
+    }
+
+    /// The id of the [`Diagnostic`] the durations of the spans with
+    /// the `name` are reported to, in milliseconds.
+    pub fn diagnostic_id(name: &str) -> DiagnosticId {
+        let hash = name
+            .bytes()
+            .fold(0u64, |h, b| h.wrapping_mul(31).wrapping_add(b as u64));
+        DiagnosticId::from_u128(PROFILER_DIAGNOSTICS ^ hash as u128)
+    }
+}
+
+/// The base of the profiler span diagnostic ids.
+const PROFILER_DIAGNOSTICS: u128 = 0x6b1d6e3a_25c4_4f0e_0000_0000_0000_0000;
+const PROFILER_HISTORY_LENGTH: usize = 20;
+
+fn begin_frame(mut spans: ResMut<ProfilerSpans>) {
+    spans.stack.clear();
+    spans.current.clear();
+    spans.frame_start = Some(Instant::now());
+    spans.begin("frame");
+}
+
+fn end_frame(mut spans: ResMut<ProfilerSpans>) {
+    spans.end("frame");
+    let Some(frame_start) = spans.frame_start else { return };
+    spans.last_frame = frame_start.elapsed().as_secs_f32();
+    let mut current = std::mem::take(&mut spans.current);
+    current.sort_by_key(|s| s.depth);
+    spans.last = current;
+}
+
+/// Reports the total duration of the spans of the last complete frame
+/// to the [`Diagnostics`], when the `DiagnosticsPlugin` is added.
+fn measure_spans(spans: Res<ProfilerSpans>, diagnostics: Option<ResMut<Diagnostics>>) {
+    let Some(mut diagnostics) = diagnostics else {
+        return;
+    };
+    if !spans.is_changed() {
+        return;
+    }
+    let mut totals: HashMap<&str, f32> = HashMap::default();
+    for span in spans.spans() {
+        *totals.entry(span.name.as_str()).or_default() += span.duration;
+    }
+    for (name, duration) in totals {
+        let id = ProfilerSpans::diagnostic_id(name);
+        if diagnostics.get(id).is_none() {
+            let diagnostic =
+                Diagnostic::new(id, format!("profiler/{name}"), PROFILER_HISTORY_LENGTH);
+            diagnostics.add(diagnostic.with_suffix("ms"));
+        }
+        diagnostics.add_measurement(id, duration as f64 * 1000.0);
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(profiler)]
+/// The `<profiler>` tag renders the spans collected by the
+/// [`ProfilerPlugin`] as a flame chart: each row represents
+/// the nesting level, each bar represents the single span
+/// of the last complete frame. The `interval` param specifies
+/// how often (in seconds) the chart is refreshed, `0.5` by default.
+pub struct Profiler {
+    #[param]
+    pub interval: f32,
+    pub holder: Entity,
+    elapsed: f32,
+}
+
+impl WidgetBuilder for Profiler {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let holder = self.holder;
+        ctx.render(eml! {
+            <span c:profiler>
+                <span {holder} c:profiler-chart/>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            profiler {
+                width: 100%;
+                min-height: 100px;
+                background-color: #2f2f2fdf;
+            }
+            profiler .profiler-chart {
+                width: 100%;
+                height: 100%;
+            }
+        "#
+    }
+}
+
+const PROFILER_ROW_HEIGHT: f32 = 18.0;
+const PROFILER_DEFAULT_INTERVAL: f32 = 0.5;
+
+fn update_profiler_view(
+    mut commands: Commands,
+    mut profilers: Query<&mut Profiler>,
+    spans: Res<ProfilerSpans>,
+    time: Res<Time>,
+    defaults: Res<Defaults>,
+) {
+    for mut profiler in profilers.iter_mut() {
+        let interval = if profiler.interval > 0.0 {
+            profiler.interval
+        } else {
+            PROFILER_DEFAULT_INTERVAL
+        };
+        profiler.elapsed += time.delta_seconds();
+        if profiler.elapsed < interval {
+            continue;
+        }
+        profiler.elapsed = 0.0;
+        let frame = spans.frame_duration();
+        if frame <= 0.0 {
+            continue;
+        }
+        commands.entity(profiler.holder).despawn_descendants();
+        for span in spans.spans() {
+            let left = 100.0 * span.start / frame;
+            let width = (100.0 * span.duration / frame).max(0.1);
+            let label = format!("{} {:.2}ms", span.name, span.duration * 1000.0);
+            let bar = commands
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position: UiRect {
+                            left: Val::Percent(left),
+                            top: Val::Px(span.depth as f32 * PROFILER_ROW_HEIGHT),
+                            ..default()
+                        },
+                        size: Size::new(Val::Percent(width), Val::Px(PROFILER_ROW_HEIGHT - 1.0)),
+                        overflow: Overflow::Hidden,
+                        ..default()
+                    },
+                    background_color: span_color(&span.name).into(),
+                    ..default()
+                })
+                .with_children(|bar| {
+                    bar.spawn(TextBundle::from_section(
+                        label,
+                        TextStyle {
+                            font: defaults.regular_font.clone(),
+                            font_size: PROFILER_ROW_HEIGHT - 4.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                })
+                .id();
+            commands.entity(profiler.holder).add_child(bar);
+        }
+    }
+}
+
+fn span_color(name: &str) -> Color {
+    let hash = name
+        .bytes()
+        .fold(0u32, |h, b| h.wrapping_mul(31).wrapping_add(b as u32));
+    Color::hsl((hash % 360) as f32, 0.5, 0.4)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keep_depth_of_spans_ended_out_of_order() {
+        let mut spans = ProfilerSpans::default();
+        spans.frame_start = Some(Instant::now());
+        spans.begin("outer");
+        spans.begin("inner");
+        spans.end("outer");
+        spans.begin("next");
+        spans.end("next");
+        spans.end("inner");
+        spans.end("never-started");
+        let depths: Vec<_> = spans
+            .current
+            .iter()
+            .map(|span| (span.name.as_str(), span.depth))
+            .collect();
+        assert_eq!(depths, vec![("outer", 0), ("next", 1), ("inner", 1)]);
+        assert!(spans.stack.is_empty());
+    }
+
+    #[test]
+    fn report_frame_spans() {
+        let mut app = App::new();
+        app.init_resource::<ProfilerSpans>()
+            .init_resource::<Diagnostics>()
+            .add_system_to_stage(CoreStage::First, begin_frame)
+            .add_system(|mut spans: ResMut<ProfilerSpans>| {
+                spans.begin("work");
+                spans.end("work");
+            })
+            .add_system_to_stage(CoreStage::Last, end_frame)
+            .add_system_to_stage(CoreStage::Last, measure_spans.after(end_frame));
+        app.update();
+
+        let spans = app.world.resource::<ProfilerSpans>();
+        let names: Vec<_> = spans.spans().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["frame", "work"]);
+        assert_eq!(spans.spans()[1].depth, 1);
+        let diagnostics = app.world.resource::<Diagnostics>();
+        for name in ["frame", "work"] {
+            let diagnostic = diagnostics.get(ProfilerSpans::diagnostic_id(name));
+            assert!(diagnostic.and_then(|d| d.value()).is_some());
+        }
+    }
+}
@@ -19,6 +19,9 @@ pub struct LimitedValue {
     value: f32,
     minimum: f32,
     maximum: f32,
+    /// The value is snapped to the `minimum + n * step`, the `0` step
+    /// keeps the value continuous.
+    step: f32,
 }
 
 impl FromWorldAndParam for LimitedValue {
@@ -28,11 +31,13 @@ impl FromWorldAndParam for LimitedValue {
                 minimum: 0.0,
                 value: 0.0,
                 maximum: 1.0,
+                step: 0.0,
             }
         };
         let minimum = params.try_get::<f32>("minimum");
         let value = params.try_get::<f32>("value");
         let maximum = params.try_get::<f32>("maximum");
+        let step = params.try_get::<f32>("step").unwrap_or(0.0).max(0.0);
         let (minimum, value, maximum) = match (minimum, value, maximum) {
             (Some(min), Some(val), Some(max)) => {
                 (min.min(max), val.max(min).min(max), max.max(min))
@@ -45,11 +50,14 @@ impl FromWorldAndParam for LimitedValue {
             (None, None, Some(max)) => (0.0f32.min(max), 0.0f32.min(max), max),
             (None, None, None) => (0.0, 0.0, 1.0),
         };
-        LimitedValue {
+        let mut limited = LimitedValue {
             value,
             minimum,
             maximum,
-        }
+            step,
+        };
+        limited.set_value(value);
+        limited
     }
 }
 
@@ -66,17 +74,35 @@ impl LimitedValue {
         self.maximum
     }
 
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
     pub fn relative(&self) -> f32 {
         (self.value - self.minimum) / (self.maximum - self.minimum)
     }
 
+    fn snap(&self, value: f32) -> f32 {
+        let value = value.min(self.maximum).max(self.minimum);
+        if self.step <= 0.0 {
+            return value;
+        }
+        let steps = ((value - self.minimum) / self.step).round();
+        (self.minimum + steps * self.step).min(self.maximum)
+    }
+
     pub fn set_value(&mut self, value: f32) {
-        self.value = value.min(self.maximum).max(self.minimum);
+        self.value = self.snap(value);
     }
 
     pub fn set_relative(&mut self, relative: f32) {
         let relative = relative.min(1.0).max(0.0);
-        self.value = self.minimum + relative * (self.maximum - self.minimum);
+        self.value = self.snap(self.minimum + relative * (self.maximum - self.minimum));
+    }
+
+    pub fn set_step(&mut self, step: f32) {
+        self.step = step.max(0.0);
+        self.value = self.snap(self.value);
     }
 
     pub fn set_minimum(&mut self, minimum: f32) {
@@ -123,6 +149,7 @@ impl LimitedValueTransformer {
     impl_transform! { minimum, set_minimum }
     impl_transform! { maximum, set_maximum }
     impl_transform! { relative, set_relative }
+    impl_transform! { step, set_step }
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -168,6 +195,7 @@ pub struct Range {
     #[param(value: f32)]
     #[param(relative: f32)]
     #[param(maximum: f32)]
+    #[param(step: f32)]
     pub value: LimitedValue,
 
     #[param]
@@ -185,7 +213,9 @@ impl WidgetBuilder for Range {
         let hight = self.high_span;
         ctx.render(eml! {
             <span c:range>
-                <span c:range-back/>
+                <slot define="track">
+                    <span c:range-back/>
+                </slot>
                 <span {holder} c:range-holder s:flex-direction=managed()>
                     <span {low} c:range-low-internals
                         s:min-height=managed()
@@ -0,0 +1,296 @@
+use belly_core::*;
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub(crate) struct RichTextPlugin;
+impl Plugin for RichTextPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<RichText>();
+        app.add_system(build_rich_text_markup);
+        app.add_system_to_stage(CoreStage::PostUpdate, merge_rich_text_sections);
+    }
+}
+
+/// The piece of the `<richtext>` markup styled by the tags around it.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct Run {
+    text: String,
+    classes: Vec<String>,
+    declarations: Vec<String>,
+}
+
+/// The open tag of the markup with the classes and the declarations it adds.
+struct Scope {
+    name: String,
+    classes: Vec<String>,
+    declarations: Vec<String>,
+}
+
+impl Scope {
+    fn parse(tag: &str) -> Option<Scope> {
+        let tag = tag.trim();
+        let name_end = tag.find(|c: char| c.is_whitespace() || c == '=');
+        let (name, attrs) = match name_end {
+            Some(end) => (&tag[..end], tag[end..].trim_start_matches('=').trim()),
+            None => (tag, ""),
+        };
+        let mut scope = Scope {
+            name: name.to_string(),
+            classes: vec![],
+            declarations: vec![],
+        };
+        match name {
+            "b" | "strong" => scope.declarations.push("font: bold".to_string()),
+            "i" | "em" => scope.declarations.push("font: italic".to_string()),
+            "color" if !attrs.is_empty() => {
+                let color = attrs.trim_matches(|c| c == '"' || c == '\'');
+                scope.declarations.push(format!("color: {color}"));
+            }
+            "span" => {
+                let mut rest = attrs;
+                while !rest.is_empty() {
+                    if let Some(class) = rest.strip_prefix("c:") {
+                        let end = class.find(char::is_whitespace).unwrap_or(class.len());
+                        scope.classes.push(class[..end].to_string());
+                        rest = class[end..].trim_start();
+                    } else if let Some(value) = rest.strip_prefix("class=") {
+                        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'');
+                        let value = value.trim_start_matches(|c| Some(c) == quote);
+                        let end = match quote {
+                            Some(quote) => value.find(quote),
+                            None => value.find(char::is_whitespace),
+                        }
+                        .unwrap_or(value.len());
+                        scope
+                            .classes
+                            .extend(value[..end].split_whitespace().map(str::to_string));
+                        rest = value[end..].trim_start_matches(|c| Some(c) == quote);
+                        rest = rest.trim_start();
+                    } else {
+                        return None;
+                    }
+                }
+            }
+            _ => return None,
+        }
+        Some(scope)
+    }
+}
+
+/// Escapes the plain `text` to be shown by the `<richtext>` markup as is:
+/// the `&` and `<` become `&amp;` and `&lt;`.
+pub fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;")
+}
+
+/// Restores the text escaped by the [`escape_markup`].
+fn unescape_markup(text: &str) -> String {
+    text.replace("&lt;", "<").replace("&amp;", "&")
+}
+
+/// Splits the markup into the runs. The known tags are `<b>`, `<strong>`,
+/// `<i>`, `<em>`, `<color=#hex>`, `<span c:class>`, `<span class="a b">`
+/// and `<br/>`, other tags are kept as a text. The `&lt;` and `&amp;`
+/// of the text are unescaped.
+fn parse_markup(markup: &str) -> Vec<Run> {
+    let mut runs: Vec<Run> = vec![];
+    let mut scopes: Vec<Scope> = vec![];
+    let mut text = String::new();
+    let mut rest = markup;
+    let flush = |text: &mut String, scopes: &[Scope], runs: &mut Vec<Run>| {
+        if text.is_empty() {
+            return;
+        }
+        let run = Run {
+            text: std::mem::take(text),
+            classes: scopes.iter().flat_map(|s| s.classes.clone()).collect(),
+            declarations: scopes.iter().flat_map(|s| s.declarations.clone()).collect(),
+        };
+        match runs.last_mut() {
+            Some(last) if last.classes == run.classes && last.declarations == run.declarations => {
+                last.text.push_str(&run.text)
+            }
+            _ => runs.push(run),
+        }
+    };
+    while let Some(start) = rest.find('<') {
+        text.push_str(&unescape_markup(&rest[..start]));
+        rest = &rest[start..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            if let Some(index) = scopes.iter().rposition(|s| s.name == name) {
+                flush(&mut text, &scopes, &mut runs);
+                scopes.truncate(index);
+            } else {
+                text.push_str(&rest[..=end]);
+            }
+        } else if tag.trim_end_matches('/').trim() == "br" {
+            text.push('\n');
+        } else if let Some(scope) = Scope::parse(tag) {
+            flush(&mut text, &scopes, &mut runs);
+            scopes.push(scope);
+        } else {
+            text.push_str(&rest[..=end]);
+        }
+        rest = &rest[end + 1..];
+    }
+    text.push_str(&unescape_markup(rest));
+    flush(&mut text, &scopes, &mut runs);
+    runs
+}
+
+#[derive(Component, Widget)]
+#[alias(richtext)]
+/// The `<richtext>` tag renders its content as a single text made of
+/// the styled sections, so the words of the different styles are wrapped
+/// as one paragraph. The content may contain `<span>`, `<strong>` and
+/// the bound values, each text keeps the style it gets from `ess`:
+/// ```rust,ignore
+/// eml! {
+///     <richtext c:dialogue>
+///         "Press "<span c:highlight>"E"</span>" to talk to "
+///         <strong>{from!(npc, Npc:name)}</strong>
+///         <span s:color="#ffcf3f">" (quest)"</span>
+///     </richtext>
+/// }
+/// ```
+/// The bindable `value` param replaces the content with the markup made of
+/// the `<span c:class>`, `<b>`, `<i>` and `<color=#hex>` tags, the plain
+/// text put into the markup is escaped with [`escape_markup`]:
+/// ```rust,ignore
+/// eml! {
+///     <richtext bind:value=from!(tutorial, Tutorial:hint)/>
+/// }
+/// ```
+pub struct RichText {
+    #[param]
+    pub value: String,
+    /// The markup the source is built from.
+    shown: Option<String>,
+    /// The hidden subtree of the styled texts.
+    source: Entity,
+    /// The leaf node drawing the merged sections.
+    text: Entity,
+}
+
+impl WidgetBuilder for RichText {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let this = ctx.entity();
+        let content = ctx.content();
+        let source = self.source;
+        ctx.commands().entity(self.text).insert(TextBundle {
+            text: Text::from_section("", Default::default()),
+            ..default()
+        });
+        ctx.render(eml! {
+            <span c:richtext>
+                <span {source} c:richtext-source>{content}</span>
+            </span>
+        });
+        ctx.commands().entity(this).add_child(self.text);
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            richtext .richtext-source {
+                display: none;
+            }
+        "#
+    }
+}
+
+/// Replaces the source of the `<richtext>` with the runs of the markup
+/// when the `value` is changed.
+fn build_rich_text_markup(
+    mut commands: Commands,
+    mut texts: Query<&mut RichText, Changed<RichText>>,
+) {
+    for mut rich_text in texts.iter_mut() {
+        if rich_text.shown.as_ref() == Some(&rich_text.value) {
+            continue;
+        }
+        let source = rich_text.source;
+        if rich_text.value.is_empty() {
+            // the content is kept until the markup is set
+            if rich_text.shown.take().is_some() {
+                commands.entity(source).despawn_descendants();
+            }
+            continue;
+        }
+        let value = rich_text.value.clone();
+        rich_text.shown = Some(value.clone());
+        commands.entity(source).despawn_descendants();
+        commands.add(move |world: &mut World| {
+            let runs: Vec<_> = parse_markup(&value)
+                .into_iter()
+                .map(|run| {
+                    let text = run.text;
+                    let classes = run.classes.join(" ");
+                    let style = run.declarations.join("; ");
+                    eml! {
+                        <span class=classes style=style>{text}</span>
+                    }
+                })
+                .collect();
+            let roots = eml! { <span>{runs}</span> }.build(world);
+            world.entity_mut(source).push_children(&roots);
+        });
+    }
+}
+
+fn same_sections(a: &[TextSection], b: &[TextSection]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b.iter()).all(|(a, b)| {
+            a.value == b.value
+                && a.style.font == b.style.font
+                && a.style.font_size == b.style.font_size
+                && a.style.color == b.style.color
+        })
+}
+
+/// Collects the sections of the texts in the subtree in the document order.
+fn collect_sections(
+    entity: Entity,
+    children: &Query<&Children>,
+    texts: &Query<&Text, With<Element>>,
+    sections: &mut Vec<TextSection>,
+) {
+    if let Ok(text) = texts.get(entity) {
+        sections.extend(
+            text.sections
+                .iter()
+                .filter(|s| !s.value.is_empty())
+                .cloned(),
+        );
+    }
+    let Ok(items) = children.get(entity) else {
+        return;
+    };
+    for child in items.iter() {
+        collect_sections(*child, children, texts, sections);
+    }
+}
+
+/// Copies the sections styled in the source of the `<richtext>` to
+/// its text, the leaf text isn't an element so `ess` keeps its sections.
+fn merge_rich_text_sections(
+    rich_texts: Query<&RichText>,
+    children: Query<&Children>,
+    texts: Query<&Text, With<Element>>,
+    mut leafs: Query<&mut Text, Without<Element>>,
+) {
+    for rich_text in rich_texts.iter() {
+        let mut sections = vec![];
+        collect_sections(rich_text.source, &children, &texts, &mut sections);
+        let Ok(mut text) = leafs.get_mut(rich_text.text) else {
+            continue;
+        };
+        if !same_sections(&text.sections, &sections) {
+            text.sections = sections;
+        }
+    }
+}
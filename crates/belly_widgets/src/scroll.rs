@@ -0,0 +1,382 @@
+use super::common::*;
+use belly_core::{
+    ess::{Scroll, ScrollSettings},
+    *,
+};
+use belly_macro::*;
+use bevy::{prelude::*, utils::HashMap};
+
+/// The shortest thumb, so it could be grabbed when the content is huge.
+const MIN_THUMB: f32 = 16.;
+
+pub(crate) struct ScrollViewPlugin;
+impl Plugin for ScrollViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<ScrollView>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_pointer_input.after(input::Label::Signals),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_keyboard_input.after(input::Label::Focus),
+        );
+        app.add_system(sync_scroll_position);
+        app.add_system(update_scrollbars.after(sync_scroll_position));
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(scroll)]
+/// The `<scroll>` tag clips its content and scrolls it by the mouse wheel,
+/// the touch, the scrollbars and the keyboard (the arrows, `PageUp`,
+/// `PageDown`, `Home` and `End` scroll the focused view). The scrollbars
+/// (`.scroll-bar`) with the thumbs (`.scroll-thumb`) are shown when the
+/// content doesn't fit, the view gets the `:overflow-x`/`:overflow-y`
+/// states then. The `x` and `y` params are the scroll position in pixels,
+/// bind to them to save or to restore it. With the `follow` param the view
+/// scrolled to the bottom stays there when the content grows.
+pub struct ScrollView {
+    #[param]
+    pub x: f32,
+    #[param]
+    pub y: f32,
+    #[param]
+    pub follow: bool,
+
+    viewport: Entity,
+    horizontal_bar: Entity,
+    horizontal_thumb: Entity,
+    vertical_bar: Entity,
+    vertical_thumb: Entity,
+}
+
+impl ScrollView {
+    fn bar(&self, axis: ScrollAxis) -> (Entity, Entity) {
+        match axis {
+            ScrollAxis::Horizontal => (self.horizontal_bar, self.horizontal_thumb),
+            ScrollAxis::Vertical => (self.vertical_bar, self.vertical_thumb),
+        }
+    }
+
+    /// Scrolls the view by `delta` pixels along the `axis`, the position
+    /// is limited by the `max` offset of the viewport.
+    fn scroll_by(&mut self, axis: ScrollAxis, delta: f32, max: Vec2) {
+        let (position, max) = match axis {
+            ScrollAxis::Horizontal => (&mut self.x, max.x),
+            ScrollAxis::Vertical => (&mut self.y, max.y),
+        };
+        let value = (*position + delta).min(max).max(0.);
+        if *position != value {
+            *position = value;
+        }
+    }
+}
+
+impl WidgetBuilder for ScrollView {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let content = ctx.content();
+        let viewport = self.viewport;
+        let hbar = self.horizontal_bar;
+        let hthumb = self.horizontal_thumb;
+        let vbar = self.vertical_bar;
+        let vthumb = self.vertical_thumb;
+        // the arrows scroll the focused view, not move the focus
+        let listener = Listen::bubble(ctx.entity(), |ctx: &mut PropagationContext<KeyInput>| {
+            if ctx.target() == ctx.current() && ctx.event().direction().is_some() {
+                ctx.stop_propagation();
+            }
+        });
+        ctx.commands().add(listener);
+        ctx.render(eml! {
+            <span c:scroll interactable>
+                <span {viewport} c:scroll-viewport>
+                    {content}
+                </span>
+                <span {vbar} c:scroll-bar c:scroll-vertical interactable>
+                    <span {vthumb} c:scroll-thumb interactable
+                        s:top=managed()
+                        s:height=managed()
+                    />
+                </span>
+                <span {hbar} c:scroll-bar c:scroll-horizontal interactable>
+                    <span {hthumb} c:scroll-thumb interactable
+                        s:left=managed()
+                        s:width=managed()
+                    />
+                </span>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            scroll .scroll-viewport {
+                width: 100%;
+                height: 100%;
+                overflow: auto;
+                flex-direction: column;
+                align-items: flex-start;
+            }
+            scroll:overflow-y .scroll-viewport {
+                padding-right: 10px;
+            }
+            scroll:overflow-x .scroll-viewport {
+                padding-bottom: 10px;
+            }
+            scroll .scroll-bar {
+                display: none;
+                position-type: absolute;
+                background-color: #0000001f;
+            }
+            scroll:overflow-y .scroll-vertical,
+            scroll:overflow-x .scroll-horizontal {
+                display: flex;
+            }
+            scroll .scroll-vertical {
+                top: 0px;
+                right: 0px;
+                bottom: 0px;
+                width: 10px;
+            }
+            scroll:overflow-x .scroll-vertical {
+                bottom: 10px;
+            }
+            scroll .scroll-horizontal {
+                left: 0px;
+                right: 0px;
+                bottom: 0px;
+                height: 10px;
+            }
+            scroll:overflow-y .scroll-horizontal {
+                right: 10px;
+            }
+            scroll .scroll-thumb {
+                position-type: absolute;
+                background-color: #7f7f7fbf;
+            }
+            scroll .scroll-vertical .scroll-thumb {
+                left: 2px;
+                right: 2px;
+            }
+            scroll .scroll-horizontal .scroll-thumb {
+                top: 2px;
+                bottom: 2px;
+            }
+            scroll .scroll-thumb:hover {
+                background-color: #5f5f5fdf;
+            }
+        "#
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ScrollAxis {
+    Horizontal,
+    Vertical,
+}
+
+impl ScrollAxis {
+    const ALL: [ScrollAxis; 2] = [ScrollAxis::Horizontal, ScrollAxis::Vertical];
+
+    fn pick(&self, value: Vec2) -> f32 {
+        match self {
+            ScrollAxis::Horizontal => value.x,
+            ScrollAxis::Vertical => value.y,
+        }
+    }
+}
+
+/// Returns the length and the position of the thumb on the `bar` showing
+/// the `view` part of the content scrolled by the `offset` of the `max`.
+fn thumb_geometry(bar: f32, view: f32, offset: f32, max: f32) -> (f32, f32) {
+    let content = view + max;
+    let length = if content > 0. {
+        (bar * view / content).max(MIN_THUMB).min(bar)
+    } else {
+        bar
+    };
+    let position = if max > 0. {
+        (bar - length) * offset / max
+    } else {
+        0.
+    };
+    (length, position)
+}
+
+/// Keeps the `x`/`y` params and the offset of the viewport in sync: the
+/// changed params scroll the viewport, the viewport scrolled by the wheel
+/// or by the touch updates the params.
+fn sync_scroll_position(
+    mut views: Query<(Entity, &mut ScrollView)>,
+    mut scrolls: Query<&mut Scroll>,
+    mut synced: Local<HashMap<Entity, (Vec2, Vec2)>>,
+) {
+    for (entity, mut view) in views.iter_mut() {
+        let Ok(mut scroll) = scrolls.get_mut(view.viewport) else {
+            continue;
+        };
+        let position = Vec2::new(view.x, view.y);
+        let (last_position, last_max) = synced.get(&entity).copied().unwrap_or_default();
+        if position != last_position {
+            // the offset is clamped to the content after the layout
+            scroll.offset = position.max(Vec2::ZERO);
+        } else if view.follow && scroll.max.y > last_max.y && scroll.offset.y >= last_max.y - 0.5 {
+            scroll.offset.y = scroll.max.y;
+        }
+        let offset = scroll.offset;
+        if offset != position {
+            view.x = offset.x;
+            view.y = offset.y;
+        }
+        synced.insert(entity, (offset, scroll.max));
+    }
+}
+
+fn update_scrollbars(
+    views: Query<(Entity, &ScrollView)>,
+    scrolls: Query<(&Scroll, &Node)>,
+    nodes: Query<&Node>,
+    mut styles: Query<&mut Style>,
+    mut elements: Elements,
+) {
+    for (entity, view) in views.iter() {
+        let Ok((scroll, viewport)) = scrolls.get(view.viewport) else {
+            continue;
+        };
+        let overflow = |max: f32| !scroll.auto || max > 0.;
+        elements.set_state(entity, "overflow-x".as_tag(), overflow(scroll.max.x));
+        elements.set_state(entity, "overflow-y".as_tag(), overflow(scroll.max.y));
+        for axis in ScrollAxis::ALL {
+            let (bar, thumb) = view.bar(axis);
+            let Ok(bar) = nodes.get(bar) else { continue };
+            let Ok(mut style) = styles.get_mut(thumb) else {
+                continue;
+            };
+            let (length, position) = thumb_geometry(
+                axis.pick(bar.size()),
+                axis.pick(viewport.size()),
+                axis.pick(scroll.offset),
+                axis.pick(scroll.max),
+            );
+            let (size, offset) = match axis {
+                ScrollAxis::Horizontal => (&mut style.size.width, &mut style.position.left),
+                ScrollAxis::Vertical => (&mut style.size.height, &mut style.position.top),
+            };
+            if *size != Val::Px(length) {
+                *size = Val::Px(length);
+            }
+            if *offset != Val::Px(position) {
+                *offset = Val::Px(position);
+            }
+        }
+    }
+}
+
+fn handle_pointer_input(
+    mut events: EventReader<PointerInput>,
+    mut views: Query<(Entity, &mut ScrollView)>,
+    scrolls: Query<(&Scroll, &Node)>,
+    nodes: Query<(&GlobalTransform, &Node)>,
+    mut active: Local<Option<(Entity, ScrollAxis)>>,
+) {
+    for ev in events.iter() {
+        if ev.down() && active.is_none() {
+            let Some(&target) = ev.entities.first() else {
+                continue;
+            };
+            let hit = views.iter().find_map(|(entity, view)| {
+                ScrollAxis::ALL
+                    .into_iter()
+                    .find_map(|axis| match view.bar(axis) {
+                        (_, thumb) if thumb == target => Some((entity, axis, true)),
+                        (bar, _) if bar == target => Some((entity, axis, false)),
+                        _ => None,
+                    })
+            });
+            let Some((entity, axis, grabbed)) = hit else {
+                continue;
+            };
+            if grabbed {
+                *active = Some((entity, axis));
+                continue;
+            }
+            // the press on the bar scrolls by the page toward the pointer
+            let Ok((_, mut view)) = views.get_mut(entity) else {
+                continue;
+            };
+            let Ok((scroll, viewport)) = scrolls.get(view.viewport) else {
+                continue;
+            };
+            let Ok((thumb, _)) = nodes.get(view.bar(axis).1) else {
+                continue;
+            };
+            let thumb = axis.pick(thumb.translation().truncate());
+            let page = axis.pick(viewport.size()) * (axis.pick(ev.pos) - thumb).signum();
+            view.scroll_by(axis, page, scroll.max);
+        } else if let Some((entity, axis)) = *active {
+            if ev.dragging() {
+                let Ok((_, mut view)) = views.get_mut(entity) else {
+                    continue;
+                };
+                let Ok((scroll, viewport)) = scrolls.get(view.viewport) else {
+                    continue;
+                };
+                let Ok((_, bar)) = nodes.get(view.bar(axis).0) else {
+                    continue;
+                };
+                let bar = axis.pick(bar.size());
+                let max = axis.pick(scroll.max);
+                let (length, _) = thumb_geometry(bar, axis.pick(viewport.size()), 0., max);
+                // the thumb follows the pointer, the content moves faster
+                if bar > length {
+                    let delta = axis.pick(ev.delta) * max / (bar - length);
+                    view.scroll_by(axis, delta, scroll.max);
+                }
+            }
+            if ev.up() || ev.drag_stop() {
+                *active = None;
+            }
+        }
+    }
+}
+
+fn handle_keyboard_input(
+    focused: Res<Focused>,
+    mut keys: EventReader<KeyInput>,
+    settings: Res<ScrollSettings>,
+    mut views: Query<&mut ScrollView>,
+    scrolls: Query<(&Scroll, &Node)>,
+) {
+    let Some(focused) = focused.get() else { return };
+    let Ok(mut view) = views.get_mut(focused) else {
+        return;
+    };
+    let Ok((scroll, viewport)) = scrolls.get(view.viewport) else {
+        return;
+    };
+    let line = settings.line_height;
+    let page = viewport.size().y;
+    let mut position = Vec2::new(view.x, view.y);
+    let pressed = keys
+        .iter()
+        .filter(|k| k.pressed && k.entities[0] == focused);
+    for key in pressed.map(|k| k.key) {
+        match key {
+            KeyCode::Up => position.y -= line,
+            KeyCode::Down => position.y += line,
+            KeyCode::Left => position.x -= line,
+            KeyCode::Right => position.x += line,
+            KeyCode::PageUp => position.y -= page,
+            KeyCode::PageDown => position.y += page,
+            KeyCode::Home => position.y = 0.,
+            KeyCode::End => position.y = scroll.max.y,
+            _ => continue,
+        }
+    }
+    let position = position.clamp(Vec2::ZERO, scroll.max);
+    if position != Vec2::new(view.x, view.y) {
+        view.x = position.x;
+        view.y = position.y;
+    }
+}
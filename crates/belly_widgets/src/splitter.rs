@@ -0,0 +1,318 @@
+use super::range::LayoutMode;
+use belly_core::{eml::build::FromWorldAndParam, relations::bind::AsTransformer, *};
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub(crate) struct SplitterPlugin;
+impl Plugin for SplitterPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<Splitter>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            drag_splitter_divider.after(input::Label::Signals),
+        );
+        app.add_system(configure_splitter_layout);
+        app.add_system(update_splitter_panes.after(configure_splitter_layout));
+    }
+}
+
+/// The position of the `<splitter>` divider: the `ratio` is the part of
+/// the space taken by the first pane, the `minimum` and the `maximum`
+/// limit the sizes of the panes in pixels.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SplitValue {
+    ratio: f32,
+    /// Neither of the panes is smaller.
+    minimum: f32,
+    /// The first pane isn't bigger, the `0` keeps it unlimited.
+    maximum: f32,
+}
+
+impl FromWorldAndParam for SplitValue {
+    fn from_world_and_param(_world: &mut World, param: Variant) -> Self {
+        let mut split = SplitValue {
+            ratio: 0.5,
+            minimum: 0.,
+            maximum: 0.,
+        };
+        let Variant::Params(mut params) = param else {
+            return split;
+        };
+        if let Some(minimum) = params.try_get::<f32>("minimum") {
+            split.set_minimum(minimum);
+        }
+        if let Some(maximum) = params.try_get::<f32>("maximum") {
+            split.set_maximum(maximum);
+        }
+        if let Some(ratio) = params.try_get::<f32>("ratio") {
+            split.set_ratio(ratio);
+        }
+        split
+    }
+}
+
+impl SplitValue {
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    pub fn minimum(&self) -> f32 {
+        self.minimum
+    }
+
+    pub fn maximum(&self) -> f32 {
+        self.maximum
+    }
+
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = ratio.min(1.).max(0.);
+    }
+
+    pub fn set_minimum(&mut self, minimum: f32) {
+        self.minimum = minimum.max(0.);
+    }
+
+    pub fn set_maximum(&mut self, maximum: f32) {
+        self.maximum = maximum.max(0.);
+    }
+
+    /// The size of the first pane when the panes share the `space`.
+    pub fn first(&self, space: f32) -> f32 {
+        let mut size = space * self.ratio;
+        if self.maximum > 0. {
+            size = size.min(self.maximum);
+        }
+        size.min(space - self.minimum).max(self.minimum.min(space))
+    }
+}
+
+impl AsTransformer for SplitValue {
+    type Transformer = SplitValueTransformer;
+    fn as_transformer() -> Self::Transformer {
+        SplitValueTransformer
+    }
+}
+
+pub struct SplitValueTransformer;
+
+macro_rules! impl_transform {
+    ($method:ident, $setter:ident) => {
+        pub fn $method<T: TransformableTo<f32>>(
+            &self,
+        ) -> fn(&T, Prop<SplitValue>) -> TransformationResult {
+            |source, mut split| {
+                let val = T::transform(source)?;
+                if val != split.$method() {
+                    split.$setter(val);
+                }
+                return Ok(());
+            }
+        }
+    };
+}
+impl SplitValueTransformer {
+    impl_transform! { ratio, set_ratio }
+    impl_transform! { minimum, set_minimum }
+    impl_transform! { maximum, set_maximum }
+}
+
+#[derive(Component, Widget)]
+#[alias(splitter)]
+/// The `<splitter>` tag shares its space between the first two children
+/// (the panes) divided by the draggable `.splitter-divider`. The panes
+/// are placed side by side in the `horizontal` mode (the default one) and
+/// one above the other in the `vertical` mode. The `ratio` is the part of
+/// the space taken by the first pane, bind to it to save or to restore
+/// the split. The `min` param limits the sizes of both panes and the `max`
+/// param limits the size of the first pane, in pixels:
+/// ```rust,ignore
+/// eml! {
+///     <splitter min=100. bind:ratio=to!(layout, Layout:ratio) bind:ratio=from!(layout, Layout:ratio)>
+///         <span c:outline>...</span>
+///         <splitter mode="vertical" ratio=0.7>
+///             <span c:viewport>...</span>
+///             <span c:console>...</span>
+///         </splitter>
+///     </splitter>
+/// }
+/// ```
+pub struct Splitter {
+    #[param(ratio: f32)]
+    #[param(min: f32 => minimum)]
+    #[param(max: f32 => maximum)]
+    pub split: SplitValue,
+    #[param]
+    pub mode: LayoutMode,
+    divider: Entity,
+}
+
+impl Splitter {
+    /// Returns the position along the split axis.
+    fn pick(&self, value: Vec2) -> f32 {
+        match self.mode {
+            LayoutMode::Horizontal => value.x,
+            LayoutMode::Vertical => value.y,
+        }
+    }
+}
+
+impl WidgetBuilder for Splitter {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let content = ctx.content();
+        let divider = self.divider;
+        ctx.render(eml! {
+            <span c:splitter>
+                {content}
+                <span {divider} c:splitter-divider interactable
+                    s:left=managed()
+                    s:top=managed()
+                />
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            splitter {
+                width: 100%;
+                height: 100%;
+                overflow: hidden;
+            }
+            splitter:vertical {
+                flex-direction: column;
+            }
+            splitter .splitter-divider {
+                position-type: absolute;
+                background-color: #0000003f;
+            }
+            splitter .splitter-divider:hover {
+                background-color: #4f9fdf7f;
+            }
+            splitter:horizontal .splitter-divider {
+                top: 0px;
+                bottom: 0px;
+                width: 6px;
+                cursor: col-resize;
+            }
+            splitter:vertical .splitter-divider {
+                left: 0px;
+                right: 0px;
+                height: 6px;
+                cursor: row-resize;
+            }
+        "#
+    }
+}
+
+fn configure_splitter_layout(
+    mut elements: Elements,
+    splitters: Query<(Entity, &Splitter), Changed<Splitter>>,
+) {
+    for (entity, splitter) in splitters.iter() {
+        let vertical = splitter.mode == LayoutMode::Vertical;
+        elements.set_state(entity, "horizontal".as_tag(), !vertical);
+        elements.set_state(entity, "vertical".as_tag(), vertical);
+    }
+}
+
+/// Sizes the panes by the split and places the divider between them,
+/// the first pane keeps its size and the second one takes the rest.
+fn update_splitter_panes(
+    splitters: Query<(&Splitter, &Node, &Children)>,
+    nodes: Query<&Node>,
+    mut styles: Query<&mut Style>,
+) {
+    for (splitter, node, children) in splitters.iter() {
+        let space = splitter.pick(node.size());
+        let first = splitter.split.first(space);
+        let mut panes = children.iter().filter(|e| **e != splitter.divider);
+        let horizontal = splitter.mode == LayoutMode::Horizontal;
+        if let Some(&pane) = panes.next() {
+            if let Ok(mut style) = styles.get_mut(pane) {
+                let (size, other) = match splitter.mode {
+                    LayoutMode::Horizontal => (Val::Px(first), Val::Undefined),
+                    LayoutMode::Vertical => (Val::Undefined, Val::Px(first)),
+                };
+                if style.size.width != size || style.size.height != other {
+                    style.size.width = size;
+                    style.size.height = other;
+                }
+                if style.flex_grow != 0. || style.flex_shrink != 0. {
+                    style.flex_grow = 0.;
+                    style.flex_shrink = 0.;
+                }
+            }
+        }
+        if let Some(&pane) = panes.next() {
+            if let Ok(mut style) = styles.get_mut(pane) {
+                if style.flex_grow != 1. || style.flex_basis != Val::Px(0.) {
+                    style.flex_grow = 1.;
+                    style.flex_shrink = 1.;
+                    style.flex_basis = Val::Px(0.);
+                }
+            }
+        }
+        let Ok(mut style) = styles.get_mut(splitter.divider) else {
+            continue;
+        };
+        let thickness = nodes
+            .get(splitter.divider)
+            .map(|n| splitter.pick(n.size()))
+            .unwrap_or(0.);
+        let offset = Val::Px(first - thickness / 2.);
+        let (position, other) = if horizontal {
+            (&mut style.position.left, &mut style.position.top)
+        } else {
+            (&mut style.position.top, &mut style.position.left)
+        };
+        if *position != offset {
+            *position = offset;
+        }
+        if *other != Val::Undefined {
+            *other = Val::Undefined;
+        }
+    }
+}
+
+fn drag_splitter_divider(
+    mut events: EventReader<PointerInput>,
+    mut splitters: Query<(Entity, &mut Splitter)>,
+    nodes: Query<(&GlobalTransform, &Node)>,
+    mut active: Local<Option<Entity>>,
+) {
+    for ev in events.iter() {
+        if ev.down() && active.is_none() {
+            let Some(&target) = ev.entities.first() else {
+                continue;
+            };
+            *active = splitters
+                .iter()
+                .find(|(_, splitter)| splitter.divider == target)
+                .map(|(entity, _)| entity);
+        } else if let Some(entity) = *active {
+            if ev.dragging() {
+                let Ok((transform, node)) = nodes.get(entity) else {
+                    continue;
+                };
+                let Ok((_, mut splitter)) = splitters.get_mut(entity) else {
+                    continue;
+                };
+                let space = splitter.pick(node.size());
+                if space <= 0. {
+                    continue;
+                }
+                // the node is positioned by its center
+                let start = splitter.pick(transform.translation().truncate()) - space / 2.;
+                let mut split = splitter.split;
+                split.set_ratio((splitter.pick(ev.pos) - start) / space);
+                split.set_ratio(split.first(space) / space);
+                if splitter.split != split {
+                    splitter.split = split;
+                }
+            }
+            if ev.up() || ev.drag_stop() {
+                *active = None;
+            }
+        }
+    }
+}
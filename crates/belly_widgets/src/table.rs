@@ -0,0 +1,456 @@
+use super::common::*;
+use belly_core::*;
+use belly_macro::*;
+use bevy::prelude::*;
+use std::{cmp::Ordering, str::FromStr, sync::Arc};
+
+pub(crate) struct TablePlugin;
+impl Plugin for TablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TableEvent>();
+        app.register_widget::<Table>();
+        app.register_widget::<Column>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_table_input.after(input::Label::Signals),
+        );
+        app.add_system(build_table_rows);
+        app.add_system(update_table_states.after(build_table_rows));
+    }
+}
+
+pub enum TableEvent {
+    /// The sorting is changed by the press on the column.
+    Sorted([Entity; 1]),
+    /// The row is selected by the user.
+    Selected([Entity; 1]),
+}
+
+impl TableEvent {
+    pub fn sorted(&self) -> bool {
+        match self {
+            TableEvent::Sorted(_) => true,
+            _ => false,
+        }
+    }
+    pub fn selected(&self) -> bool {
+        match self {
+            TableEvent::Selected(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Signal for TableEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            TableEvent::Sorted(source) => source,
+            TableEvent::Selected(source) => source,
+        }
+    }
+}
+
+/// The width of the `<column>`: `width="120px"` is fixed,
+/// `width="2fr"` takes the share of the space left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnWidth {
+    Fixed(f32),
+    Fraction(f32),
+}
+
+impl Default for ColumnWidth {
+    fn default() -> Self {
+        ColumnWidth::Fraction(1.)
+    }
+}
+
+impl ColumnWidth {
+    /// The inline style of the cells of the column.
+    fn style(&self) -> String {
+        match self {
+            ColumnWidth::Fixed(px) => {
+                format!("width: {px}px; flex-grow: 0; flex-shrink: 0;")
+            }
+            ColumnWidth::Fraction(fr) => {
+                format!("flex-basis: 0px; flex-grow: {fr}; flex-shrink: 1;")
+            }
+        }
+    }
+}
+
+impl From<ColumnWidth> for Variant {
+    fn from(width: ColumnWidth) -> Self {
+        Variant::boxed(width)
+    }
+}
+
+impl FromStr for ColumnWidth {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let parse = |value: &str| value.trim().parse::<f32>().ok();
+        if let Some(px) = s.strip_suffix("px").and_then(parse) {
+            Ok(ColumnWidth::Fixed(px))
+        } else if let Some(fr) = s.strip_suffix("fr").and_then(parse) {
+            Ok(ColumnWidth::Fraction(fr))
+        } else {
+            Err(format!("Don't know how to parse '{s}' as ColumnWidth"))
+        }
+    }
+}
+
+impl TryFrom<Variant> for ColumnWidth {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value.get_or_parse()
+    }
+}
+
+/// The rows of the `<table>`, each row is the list of the cell values.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TableRows(pub Vec<Vec<String>>);
+
+impl From<Vec<Vec<String>>> for TableRows {
+    fn from(rows: Vec<Vec<String>>) -> Self {
+        TableRows(rows)
+    }
+}
+
+impl From<TableRows> for Variant {
+    fn from(rows: TableRows) -> Self {
+        Variant::boxed(rows)
+    }
+}
+
+impl TryFrom<Variant> for TableRows {
+    type Error = String;
+    fn try_from(value: Variant) -> Result<Self, Self::Error> {
+        value
+            .take::<TableRows>()
+            .ok_or_else(|| "Invalid value for TableRows".to_string())
+    }
+}
+
+/// Builds the content of the cell from its value, set by the `cell`
+/// param of the `<column>`. The cell shows the plain `<label>` without
+/// the template.
+#[derive(Clone)]
+pub struct CellTemplate(Arc<dyn Fn(String) -> ElementsBuilder + Send + Sync>);
+
+impl CellTemplate {
+    pub fn new(template: impl Fn(String) -> ElementsBuilder + Send + Sync + 'static) -> Self {
+        CellTemplate(Arc::new(template))
+    }
+
+    pub fn build(&self, value: String) -> ElementsBuilder {
+        (self.0)(value)
+    }
+}
+
+impl From<CellTemplate> for Variant {
+    fn from(template: CellTemplate) -> Self {
+        Variant::boxed(template)
+    }
+}
+
+/// Orders the values as the numbers when both of them are numbers.
+fn compare(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(table)]
+#[signal(sort, TableEvent, sorted)]
+#[signal(select, TableEvent, selected)]
+/// The `<table>` tag shows the bindable `rows` under the header of its
+/// `<column>` children. The pressed `sortable` column sorts the rows by
+/// its values (as the numbers, when they are the numbers), the next press
+/// reverses the order, the `sort` signal is emitted anyway. The bindable
+/// `selected` param is the index of the selected row in the `rows`,
+/// the selected row gets the `:selected` state:
+/// ```rust,ignore
+/// eml! {
+///     <table bind:rows=from!(Stats:rows) on:select=connect!(...)>
+///         <column title="Player" width="2fr"/>
+///         <column title="Kills" width="80px" sortable/>
+///         <column title="Health" width="1fr" cell=CellTemplate::new(health_bar)/>
+///     </table>
+/// }
+/// ```
+pub struct Table {
+    #[param]
+    pub rows: TableRows,
+    #[param]
+    pub selected: i32,
+    /// The index of the sorting column and the descending order flag.
+    sort: Option<(usize, bool)>,
+    header: Entity,
+    body: Entity,
+    /// The rows and the sorting the body is built for.
+    shown: Option<(TableRows, Option<(usize, bool)>)>,
+}
+
+impl Table {
+    pub fn sort(&self) -> Option<(usize, bool)> {
+        self.sort
+    }
+
+    /// Sorts the rows by the column, `None` keeps the order of the `rows`.
+    pub fn sort_by(&mut self, sort: Option<(usize, bool)>) {
+        self.sort = sort;
+    }
+
+    /// The order the `rows` are shown in.
+    fn order(&self) -> Vec<usize> {
+        let rows = &self.rows.0;
+        let mut order: Vec<_> = (0..rows.len()).collect();
+        if let Some((column, descending)) = self.sort {
+            let value = |row: usize| rows[row].get(column).map(|v| v.as_str()).unwrap_or("");
+            order.sort_by(|a, b| compare(value(*a), value(*b)));
+            if descending {
+                order.reverse();
+            }
+        }
+        order
+    }
+}
+
+impl WidgetBuilder for Table {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let columns = ctx.content();
+        let header = self.header;
+        let body = self.body;
+        ctx.render(eml! {
+            <span c:table>
+                <span {header} c:table-header>{columns}</span>
+                <span {body} c:table-body/>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            table {
+                flex-direction: column;
+                color: #2f2f2f;
+            }
+            table .table-header {
+                flex-shrink: 0;
+                background-color: #bfbfbf;
+            }
+            table .table-body {
+                flex-direction: column;
+                background-color: #efefef;
+            }
+            table .table-row:hover {
+                background-color: #0000001f;
+            }
+            table .table-row:selected {
+                background-color: #4f9fdf7f;
+            }
+            table .table-cell {
+                padding: 4px 8px;
+                overflow: hidden;
+            }
+            column {
+                padding: 4px 8px;
+                justify-content: space-between;
+                overflow: hidden;
+            }
+            column:sortable:hover {
+                background-color: #ffffff3f;
+            }
+        "#
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(column)]
+/// The `<column>` tag declares the column of the `<table>` with the
+/// bindable `title`. The `width` is fixed (`120px`) or fractional (`2fr`),
+/// the `sortable` column sorts the rows when pressed. The `cell` param
+/// is the [`CellTemplate`] building the content of the cells.
+pub struct Column {
+    #[param]
+    pub title: String,
+    #[param]
+    pub width: ColumnWidth,
+    #[param]
+    pub sortable: bool,
+    template: Option<CellTemplate>,
+    arrow: Entity,
+}
+
+impl WidgetBuilder for Column {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        self.template = ctx.param("cell".as_tag()).and_then(|v| v.take());
+        let this = ctx.entity();
+        let arrow = self.arrow;
+        let style = self.width.style();
+        ctx.render(eml! {
+            <span c:table-column style=style interactable>
+                <label bind:value=from!(this, Column:title)/>
+                <label {arrow} c:table-sort-arrow/>
+            </span>
+        })
+    }
+}
+
+/// The row of the `<table>` built from the `rows[index]`.
+#[derive(Component)]
+struct TableRow {
+    table: Entity,
+    index: usize,
+}
+
+fn build_table_rows(
+    mut commands: Commands,
+    mut tables: Query<(Entity, &mut Table), Changed<Table>>,
+    columns: Query<&Column>,
+    children: Query<&Children>,
+) {
+    for (entity, mut table) in tables.iter_mut() {
+        let shown = Some((table.rows.clone(), table.sort));
+        if table.shown == shown {
+            continue;
+        }
+        table.shown = shown;
+        let columns: Vec<_> = children
+            .get(table.header)
+            .map(|c| c.iter().filter_map(|e| columns.get(*e).ok()).collect())
+            .unwrap_or_default();
+        let templates: Vec<_> = columns
+            .iter()
+            .map(|c| (c.width.style(), c.template.clone()))
+            .collect();
+        let rows: Vec<_> = table
+            .order()
+            .into_iter()
+            .map(|index| (index, table.rows.0[index].clone()))
+            .collect();
+        let body = table.body;
+        commands.entity(body).despawn_descendants();
+        commands.add(move |world: &mut World| {
+            for (index, values) in rows {
+                let cells: Vec<_> = templates
+                    .iter()
+                    .zip(values.into_iter().chain(std::iter::repeat(String::new())))
+                    .map(|((style, template), value)| {
+                        let content = match template {
+                            Some(template) => template.build(value),
+                            None => eml! { <label value=value/> },
+                        };
+                        let style = style.clone();
+                        eml! {
+                            <span c:table-cell style=style>{content}</span>
+                        }
+                    })
+                    .collect();
+                let roots = eml! {
+                    <span c:table-row interactable>{cells}</span>
+                }
+                .build(world);
+                for row in roots.iter() {
+                    world.entity_mut(*row).insert(TableRow {
+                        table: entity,
+                        index,
+                    });
+                }
+                world.entity_mut(body).push_children(&roots);
+            }
+        });
+    }
+}
+
+fn update_table_states(
+    mut elements: Elements,
+    tables: Query<&Table>,
+    changed: Query<Entity, Changed<Table>>,
+    added: Query<&TableRow, Added<TableRow>>,
+    columns: Query<(Entity, &Column)>,
+    rows: Query<&TableRow>,
+    children: Query<&Children>,
+    mut labels: Query<&mut Label>,
+) {
+    let mut updated: Vec<_> = changed.iter().collect();
+    updated.extend(added.iter().map(|row| row.table));
+    updated.sort();
+    updated.dedup();
+    for table in updated.into_iter().filter_map(|e| tables.get(e).ok()) {
+        let headers = children.get(table.header).map(|c| c.to_vec());
+        for (index, (entity, column)) in headers
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|e| columns.get(e).ok())
+            .enumerate()
+        {
+            elements.set_state(entity, "sortable".as_tag(), column.sortable);
+            let arrow = match table.sort {
+                Some((sorted, false)) if sorted == index => "^",
+                Some((sorted, true)) if sorted == index => "v",
+                _ => "",
+            };
+            if let Ok(mut label) = labels.get_mut(column.arrow) {
+                if label.value != arrow {
+                    label.value = arrow.to_string();
+                }
+            }
+        }
+        let body = children.get(table.body).map(|c| c.to_vec());
+        for row in body.unwrap_or_default() {
+            let Ok(state) = rows.get(row) else {
+                continue;
+            };
+            let selected = state.index as i32 == table.selected;
+            elements.set_state(row, "selected".as_tag(), selected);
+        }
+    }
+}
+
+fn handle_table_input(
+    mut pointer: EventReader<PointerInput>,
+    mut tables: Query<&mut Table>,
+    columns: Query<&Column>,
+    rows: Query<&TableRow>,
+    parents: Query<&Parent>,
+    children: Query<&Children>,
+    mut events: EventWriter<TableEvent>,
+) {
+    for ev in pointer.iter().filter(|e| e.pressed()) {
+        let Some(&target) = ev.entities.first() else {
+            continue;
+        };
+        let Some(entity) = parents.iter_ancestors(target).find(|e| tables.contains(*e)) else {
+            continue;
+        };
+        let Ok(mut table) = tables.get_mut(entity) else {
+            continue;
+        };
+        if let Ok(row) = rows.get(target) {
+            if table.selected != row.index as i32 {
+                table.selected = row.index as i32;
+                events.send(TableEvent::Selected([entity]));
+            }
+            continue;
+        }
+        if !columns.get(target).map(|c| c.sortable).unwrap_or(false) {
+            continue;
+        }
+        let Some(index) = children.get(table.header).ok().and_then(|headers| {
+            headers
+                .iter()
+                .filter(|e| columns.contains(**e))
+                .position(|e| *e == target)
+        }) else {
+            continue;
+        };
+        table.sort = match table.sort {
+            Some((sorted, descending)) if sorted == index => Some((index, !descending)),
+            _ => Some((index, false)),
+        };
+        events.send(TableEvent::Sorted([entity]));
+    }
+}
@@ -0,0 +1,223 @@
+use super::common::*;
+use belly_core::*;
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub(crate) struct TabsPlugin;
+impl Plugin for TabsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TabsEvent>();
+        app.register_widget::<Tabs>();
+        app.register_widget::<Tab>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_title_input.after(input::Label::Signals),
+        );
+        app.add_system(update_tabs);
+    }
+}
+
+pub enum TabsEvent {
+    /// The tab is selected by the user.
+    Changed([Entity; 1]),
+}
+
+impl TabsEvent {
+    pub fn changed(&self) -> bool {
+        match self {
+            TabsEvent::Changed(_) => true,
+        }
+    }
+}
+
+impl Signal for TabsEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            TabsEvent::Changed(source) => source,
+        }
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(tabs)]
+#[signal(change, TabsEvent, changed)]
+/// The `<tabs>` tag shows one of its `<tab>` children at a time. The
+/// titles of the tabs are rendered into the header bar (`.tabs-header`),
+/// the pressed title selects its tab. The bindable `selected` param is
+/// the index of the selected tab, the selected tab and its title get
+/// the `:active` state:
+/// ```rust,ignore
+/// eml! {
+///     <tabs selected=bind!(Settings:tab)>
+///         <tab title="Video">...</tab>
+///         <tab title="Audio">...</tab>
+///     </tabs>
+/// }
+/// ```
+pub struct Tabs {
+    #[param]
+    pub selected: i32,
+    tabs: Vec<Entity>,
+    header: Entity,
+}
+
+impl WidgetBuilder for Tabs {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let tabs = ctx.content();
+        let titles = tabs.clone();
+        let header = self.header;
+        self.tabs = tabs.clone();
+        ctx.render(eml! {
+            <span c:tabs>
+                <span {header} c:tabs-header>
+                    <for tab in=titles>
+                        <span c:tabs-title>
+                            <span c:tabs-title-button interactable>
+                                <label bind:value=from!(tab, Tab:title)/>
+                            </span>
+                        </span>
+                    </for>
+                </span>
+                <span c:tabs-panels>{tabs}</span>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            tabs {
+                flex-direction: column;
+                width: 100%;
+                height: 100%;
+            }
+            tabs .tabs-header {
+                flex-shrink: 0;
+            }
+            tabs .tabs-title {
+                background-color: #bfbfbf;
+                margin-right: 1px;
+            }
+            tabs .tabs-title:active {
+                background-color: #efefef;
+            }
+            tabs .tabs-title-button {
+                padding: 5px 10px;
+                color: #2f2f2f;
+            }
+            tabs .tabs-title-button:hover {
+                background-color: #ffffff3f;
+            }
+            tabs .tabs-panels {
+                flex-grow: 1;
+                background-color: #efefef;
+            }
+            tab {
+                display: none;
+                width: 100%;
+            }
+            tab:active {
+                display: flex;
+            }
+        "#
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(tab)]
+/// The `<tab>` tag is the page of the `<tabs>` with the bindable `title`.
+/// Its children are built along with the `<tabs>`, the content passed by
+/// the `content` param is built the first time the tab is selected:
+/// ```rust,ignore
+/// eml! {
+///     <tabs>
+///         <tab title="Summary">...</tab>
+///         <tab title="Stats" content=stats_page()/>
+///     </tabs>
+/// }
+/// ```
+pub struct Tab {
+    #[param]
+    pub title: String,
+    /// The content built on the first selection.
+    content: Option<ElementsBuilder>,
+}
+
+impl WidgetBuilder for Tab {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        self.content = ctx.param("content".as_tag()).and_then(|v| v.take());
+        let content = ctx.content();
+        ctx.insert(ElementBundle::default()).push_children(&content);
+    }
+}
+
+fn update_tabs(
+    mut commands: Commands,
+    mut elements: Elements,
+    mut tabs: Query<&mut Tabs, Changed<Tabs>>,
+    mut pages: Query<&mut Tab>,
+    children: Query<&Children>,
+) {
+    for mut tabs in tabs.iter_mut() {
+        let count = tabs.tabs.len() as i32;
+        let selected = tabs.selected.min(count - 1).max(0);
+        if tabs.selected != selected {
+            tabs.selected = selected;
+        }
+        let titles: Vec<_> = children
+            .get(tabs.header)
+            .map(|titles| titles.iter().copied().collect())
+            .unwrap_or_default();
+        for (index, tab) in tabs.tabs.iter().copied().enumerate() {
+            let active = index as i32 == selected;
+            elements.set_state(tab, tags::active(), active);
+            if let Some(title) = titles.get(index) {
+                elements.set_state(*title, tags::active(), active);
+            }
+            if !active {
+                continue;
+            }
+            let Some(content) = pages.get_mut(tab).ok().and_then(|mut p| p.content.take()) else {
+                continue;
+            };
+            commands.add(move |world: &mut World| {
+                let roots = content.build(world);
+                world.entity_mut(tab).push_children(&roots);
+            });
+        }
+    }
+}
+
+fn handle_title_input(
+    mut events: EventReader<PointerInput>,
+    mut tabs: Query<(Entity, &mut Tabs)>,
+    parents: Query<&Parent>,
+    children: Query<&Children>,
+    mut tab_events: EventWriter<TabsEvent>,
+) {
+    for ev in events.iter().filter(|e| e.pressed()) {
+        let Some(&target) = ev.entities.first() else {
+            continue;
+        };
+        // the pressed button is inside of the title, the title keeps
+        // the `:active` state of the selected tab
+        let Ok(title) = parents.get(target).map(|p| p.get()) else {
+            continue;
+        };
+        let Ok(header) = parents.get(title).map(|p| p.get()) else {
+            continue;
+        };
+        let Some((entity, mut tabs)) = tabs.iter_mut().find(|(_, t)| t.header == header) else {
+            continue;
+        };
+        let Ok(titles) = children.get(header) else {
+            continue;
+        };
+        let Some(index) = titles.iter().position(|t| *t == title) else {
+            continue;
+        };
+        if tabs.selected != index as i32 {
+            tabs.selected = index as i32;
+            tab_events.send(TabsEvent::Changed([entity]));
+        }
+    }
+}
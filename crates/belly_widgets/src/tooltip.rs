@@ -0,0 +1,232 @@
+use super::common::*;
+use belly_core::*;
+use belly_macro::*;
+use bevy::{
+    prelude::*,
+    ui::{CalculatedClip, FocusPolicy, UiStack},
+};
+
+/// How long the pointer should rest over the element before the tooltip appears.
+const TOOLTIP_DELAY: f32 = 0.5;
+/// The offset of the tooltip from the pointer.
+const TOOLTIP_OFFSET: Vec2 = Vec2::new(12., 16.);
+
+pub(crate) struct TooltipPlugin;
+impl Plugin for TooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_widget::<TooltipPopup>();
+        app.add_system(update_tooltip_text);
+        app.add_system(show_tooltips);
+        app.add_system_to_stage(CoreStage::PostUpdate, mark_truncated_text);
+    }
+}
+
+#[derive(Component, Widget)]
+/// The `<tooltip>` tag is the popup spawned by the tooltip subsystem,
+/// it is not meant to be used in the markup directly. The single popup
+/// shows the text of the element under the pointer:
+/// - the `disabled-reason` param of the `:disabled` element
+/// - the `tooltip` param of the element
+/// - the full text of the `:truncated` text element
+///
+/// The text element is `:truncated` when it is clipped by the
+/// ancestor with the `overflow: hidden` style.
+#[alias(tooltip)]
+pub struct TooltipPopup {
+    #[param]
+    pub value: String,
+    text: Entity,
+}
+
+impl WidgetBuilder for TooltipPopup {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let text = self.text;
+        ctx.render(eml! {
+            <span c:tooltip s:left=managed() s:top=managed() s:display=managed()>
+                <label {text}/>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            tooltip {
+                position-type: absolute;
+                padding: 3px 6px;
+                background-color: #2f2f2fe8;
+                color: #efefef;
+                font-size: 16px;
+            }
+        "#
+    }
+}
+
+#[derive(Default)]
+struct TooltipState {
+    popup: Option<Entity>,
+    target: Option<Entity>,
+    hovered_since: f32,
+}
+
+fn update_tooltip_text(
+    popups: Query<&TooltipPopup, Changed<TooltipPopup>>,
+    mut labels: Query<&mut Label>,
+) {
+    for popup in popups.iter() {
+        let Ok(mut label) = labels.get_mut(popup.text) else { continue };
+        if label.value != popup.value {
+            label.value = popup.value.clone();
+        }
+    }
+}
+
+fn tooltip_text(
+    tooltip: Option<&Tooltip>,
+    states: Option<&States>,
+    text: Option<&Text>,
+) -> Option<String> {
+    let disabled = states.map(|s| s.disabled()).unwrap_or(false);
+    let truncated = states.map(|s| s.truncated()).unwrap_or(false);
+    if let Some(reason) = tooltip.and_then(|t| t.disabled_reason.as_ref()) {
+        if disabled {
+            return Some(reason.clone());
+        }
+    }
+    if let Some(text) = tooltip.and_then(|t| t.text.as_ref()) {
+        return Some(text.clone());
+    }
+    match text {
+        Some(text) if truncated => Some(text.sections.iter().map(|s| s.value.as_str()).collect()),
+        _ => None,
+    }
+}
+
+fn show_tooltips(
+    mut commands: Commands,
+    mut state: Local<TooltipState>,
+    time: Res<Time>,
+    windows: Res<Windows>,
+    ui_stack: Res<UiStack>,
+    nodes: Query<(
+        &Node,
+        &GlobalTransform,
+        &ComputedVisibility,
+        Option<&CalculatedClip>,
+        Option<&Interaction>,
+        Option<&FocusPolicy>,
+    )>,
+    sources: Query<(Option<&Tooltip>, Option<&States>, Option<&Text>)>,
+    mut popups: Query<(&mut TooltipPopup, &Node, &mut Style)>,
+) {
+    let popup = *state.popup.get_or_insert_with(|| {
+        let popup = commands.spawn_empty().id();
+        commands.add(eml! { <tooltip/> }.with_entity(popup));
+        commands.entity(popup).insert(ZIndex::Global(i32::MAX));
+        popup
+    });
+    let Ok((mut tooltip, popup_node, mut style)) = popups.get_mut(popup) else { return };
+    let cursor = windows.get_primary().and_then(|window| {
+        window
+            .cursor_position()
+            .map(|pos| Vec2::new(pos.x, window.height() - pos.y))
+            .map(|pos| (pos, Vec2::new(window.width(), window.height())))
+    });
+    let Some((cursor, window_size)) = cursor else {
+        state.target = None;
+        if style.display != Display::None {
+            style.display = Display::None;
+        }
+        return;
+    };
+
+    let mut target = None;
+    for entity in ui_stack.uinodes.iter().rev() {
+        if *entity == popup || *entity == tooltip.text {
+            continue;
+        }
+        let Ok((node, transform, visibility, clip, interaction, policy)) = nodes.get(*entity)
+        else {
+            continue;
+        };
+        if !visibility.is_visible() {
+            continue;
+        }
+        let position = transform.translation().truncate();
+        let mut min = position - node.size() * 0.5;
+        let mut max = position + node.size() * 0.5;
+        if let Some(clip) = clip {
+            min = min.max(clip.clip.min);
+            max = max.min(clip.clip.max);
+        }
+        if !(min.x..max.x).contains(&cursor.x) || !(min.y..max.y).contains(&cursor.y) {
+            continue;
+        }
+        if let Ok((source, states, text)) = sources.get(*entity) {
+            if let Some(text) = tooltip_text(source, states, text) {
+                target = Some((*entity, text));
+                break;
+            }
+        }
+        if interaction.is_some() && policy == Some(&FocusPolicy::Block) {
+            break;
+        }
+    }
+
+    let Some((entity, text)) = target else {
+        state.target = None;
+        if style.display != Display::None {
+            style.display = Display::None;
+        }
+        return;
+    };
+    if state.target != Some(entity) {
+        state.target = Some(entity);
+        state.hovered_since = time.elapsed_seconds();
+    }
+    if time.elapsed_seconds() - state.hovered_since < TOOLTIP_DELAY {
+        return;
+    }
+    if tooltip.value != text {
+        tooltip.value = text;
+    }
+    let position = (cursor + TOOLTIP_OFFSET)
+        .min(window_size - popup_node.size())
+        .max(Vec2::ZERO);
+    let (left, top) = (Val::Px(position.x), Val::Px(position.y));
+    if style.display != Display::Flex || style.position.left != left || style.position.top != top {
+        style.display = Display::Flex;
+        style.position.left = left;
+        style.position.top = top;
+    }
+}
+
+fn mark_truncated_text(
+    mut elements: Elements,
+    texts: Query<
+        (Entity, &Node, &GlobalTransform, Option<&CalculatedClip>),
+        (
+            With<Text>,
+            Or<(
+                Changed<Node>,
+                Changed<GlobalTransform>,
+                Changed<CalculatedClip>,
+            )>,
+        ),
+    >,
+) {
+    for (entity, node, transform, clip) in texts.iter() {
+        let position = transform.translation().truncate();
+        let min = position - node.size() * 0.5;
+        let max = position + node.size() * 0.5;
+        // allow the subpixel overflow produced by the layout rounding
+        let truncated = clip
+            .map(|clip| {
+                min.x < clip.clip.min.x - 0.5
+                    || min.y < clip.clip.min.y - 0.5
+                    || max.x > clip.clip.max.x + 0.5
+                    || max.y > clip.clip.max.y + 0.5
+            })
+            .unwrap_or(false);
+        elements.set_state(entity, tags::truncated(), truncated);
+    }
+}
@@ -0,0 +1,392 @@
+use super::common::*;
+use belly_core::*;
+use belly_macro::*;
+use bevy::prelude::*;
+
+pub(crate) struct TreePlugin;
+impl Plugin for TreePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TreeEvent>();
+        app.register_widget::<Tree>();
+        app.register_widget::<TreeNode>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_tree_input.after(input::Label::Signals),
+        );
+        app.add_system(update_tree_nodes);
+        app.add_system(update_tree_arrows);
+        app.add_system(update_tree_selection);
+    }
+}
+
+/// The sources of the event are the node and its tree, so the signal
+/// could be connected to either of them.
+pub enum TreeEvent {
+    Expanded([Entity; 2]),
+    Collapsed([Entity; 2]),
+    /// The node is selected by the user.
+    Selected([Entity; 2]),
+}
+
+impl TreeEvent {
+    pub fn expanded(&self) -> bool {
+        match self {
+            TreeEvent::Expanded(_) => true,
+            _ => false,
+        }
+    }
+    pub fn collapsed(&self) -> bool {
+        match self {
+            TreeEvent::Collapsed(_) => true,
+            _ => false,
+        }
+    }
+    pub fn selected(&self) -> bool {
+        match self {
+            TreeEvent::Selected(_) => true,
+            _ => false,
+        }
+    }
+    /// The node the event is sent for.
+    pub fn node(&self) -> Entity {
+        self.sources()[0]
+    }
+}
+
+impl Signal for TreeEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            TreeEvent::Expanded(sources) => sources,
+            TreeEvent::Collapsed(sources) => sources,
+            TreeEvent::Selected(sources) => sources,
+        }
+    }
+}
+
+/// The hierarchical data shown by the `<tree>`, see [`tree_items`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TreeItem {
+    pub label: String,
+    pub value: String,
+    pub expanded: bool,
+    pub children: Vec<TreeItem>,
+}
+
+impl TreeItem {
+    pub fn new(label: impl Into<String>) -> TreeItem {
+        TreeItem {
+            label: label.into(),
+            ..default()
+        }
+    }
+    pub fn value(mut self, value: impl Into<String>) -> TreeItem {
+        self.value = value.into();
+        self
+    }
+    pub fn expanded(mut self) -> TreeItem {
+        self.expanded = true;
+        self
+    }
+    pub fn with_children(mut self, children: Vec<TreeItem>) -> TreeItem {
+        self.children = children;
+        self
+    }
+}
+
+/// Builds the `<treenode>` tags from the items. The children of the
+/// item are built when its node is expanded the first time:
+/// ```rust,ignore
+/// let items = vec![
+///     TreeItem::new("assets").expanded().with_children(vec![
+///         TreeItem::new("player.png").value("assets/player.png"),
+///     ]),
+/// ];
+/// eml! {
+///     <tree>{tree_items(items)}</tree>
+/// }
+/// ```
+pub fn tree_items(items: Vec<TreeItem>) -> ElementsBuilder {
+    let nodes: Vec<_> = items
+        .into_iter()
+        .map(|item| {
+            let TreeItem {
+                label,
+                value,
+                expanded,
+                children,
+            } = item;
+            if children.is_empty() {
+                return eml! {
+                    <treenode label=label value=value expanded=expanded/>
+                };
+            }
+            let content = tree_items(children);
+            eml! {
+                <treenode label=label value=value expanded=expanded content=content/>
+            }
+        })
+        .collect();
+    eml! {
+        <fragment>{nodes}</fragment>
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(tree)]
+#[signal(expand, TreeEvent, expanded)]
+#[signal(collapse, TreeEvent, collapsed)]
+#[signal(select, TreeEvent, selected)]
+/// The `<tree>` tag shows the hierarchy of the `<treenode>` tags. The
+/// arrow of the node (or the double press on it) expands and collapses
+/// its children, the pressed node is selected and gets the `:selected`
+/// state. The nodes are written in the markup or built from the data
+/// with [`tree_items`]:
+/// ```rust,ignore
+/// eml! {
+///     <tree on:select=connect!(editor, |e: Editor, ev: TreeEvent| e.open(ev.node()))>
+///         <treenode label="scenes" expanded>
+///             <treenode label="menu.scn"/>
+///             <treenode label="level.scn"/>
+///         </treenode>
+///         <treenode label="textures" branch on:expand=connect!(...)/>
+///     </tree>
+/// }
+/// ```
+pub struct Tree {
+    selected: Option<Entity>,
+    /// The node with the `:selected` state.
+    highlighted: Option<Entity>,
+}
+
+impl Tree {
+    pub fn selected(&self) -> Option<Entity> {
+        self.selected
+    }
+
+    pub fn select(&mut self, node: Option<Entity>) {
+        self.selected = node;
+    }
+}
+
+impl WidgetBuilder for Tree {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let content = ctx.content();
+        ctx.render(eml! {
+            <span c:tree>{content}</span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            tree {
+                flex-direction: column;
+                color: #2f2f2f;
+            }
+            treenode {
+                flex-direction: column;
+                align-items: stretch;
+            }
+            treenode .tree-row {
+                padding: 2px 4px;
+            }
+            treenode .tree-row:hover {
+                background-color: #0000001f;
+            }
+            treenode .tree-row:selected {
+                background-color: #4f9fdf7f;
+            }
+            treenode .tree-arrow {
+                width: 16px;
+                flex-shrink: 0;
+            }
+            treenode .tree-children {
+                display: none;
+                flex-direction: column;
+                padding-left: 16px;
+            }
+            treenode .tree-children:expanded {
+                display: flex;
+            }
+        "#
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(treenode)]
+#[signal(expand, TreeEvent, expanded)]
+#[signal(collapse, TreeEvent, collapsed)]
+#[signal(select, TreeEvent, selected)]
+/// The `<treenode>` tag is the node of the `<tree>` with the bindable
+/// `label`, `value` (the data the node stands for) and `expanded` params.
+/// The children of the node are built along with the tree, the content
+/// passed by the `content` param is built the first time the node is
+/// expanded. The node with the `branch` param shows the arrow even without
+/// children, so they could be loaded by the `expand` signal handler into
+/// the [`TreeNode::children`] container.
+pub struct TreeNode {
+    #[param]
+    pub label: String,
+    #[param]
+    pub value: String,
+    #[param]
+    pub expanded: bool,
+    #[param]
+    pub branch: bool,
+    /// The content built on the first expansion.
+    content: Option<ElementsBuilder>,
+    row: Entity,
+    arrow: Entity,
+    children: Entity,
+    /// The last expansion the events are sent for.
+    shown: bool,
+}
+
+impl TreeNode {
+    /// The element holding the child nodes.
+    pub fn children(&self) -> Entity {
+        self.children
+    }
+}
+
+impl WidgetBuilder for TreeNode {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        self.content = ctx.param("content".as_tag()).and_then(|v| v.take());
+        let this = ctx.entity();
+        let row = self.row;
+        let arrow = self.arrow;
+        let children = self.children;
+        let content = ctx.content();
+        ctx.render(eml! {
+            <span c:tree-node>
+                <span {row} c:tree-row interactable>
+                    <label {arrow} c:tree-arrow interactable/>
+                    <label c:tree-label bind:value=from!(this, TreeNode:label)/>
+                </span>
+                <span {children} c:tree-children>{content}</span>
+            </span>
+        })
+    }
+}
+
+fn update_tree_nodes(
+    mut commands: Commands,
+    mut elements: Elements,
+    mut nodes: Query<(Entity, &mut TreeNode), Changed<TreeNode>>,
+    trees: Query<(), With<Tree>>,
+    parents: Query<&Parent>,
+    mut events: EventWriter<TreeEvent>,
+) {
+    for (entity, mut node) in nodes.iter_mut() {
+        let expanded = node.expanded;
+        elements.set_state(entity, "expanded".as_tag(), expanded);
+        elements.set_state(node.children, "expanded".as_tag(), expanded);
+        if node.shown == expanded {
+            continue;
+        }
+        node.shown = expanded;
+        if let Some(content) = node.content.take().filter(|_| expanded) {
+            let children = node.children;
+            commands.add(move |world: &mut World| {
+                let roots = content.build(world);
+                world.entity_mut(children).push_children(&roots);
+            });
+        }
+        let tree = parents
+            .iter_ancestors(entity)
+            .find(|e| trees.contains(*e))
+            .unwrap_or(entity);
+        if expanded {
+            events.send(TreeEvent::Expanded([entity, tree]));
+        } else {
+            events.send(TreeEvent::Collapsed([entity, tree]));
+        }
+    }
+}
+
+fn update_tree_arrows(
+    nodes: Query<&TreeNode>,
+    children: Query<&Children>,
+    mut labels: Query<&mut Label>,
+) {
+    for node in nodes.iter() {
+        let empty = children
+            .get(node.children)
+            .map(|c| c.is_empty())
+            .unwrap_or(true);
+        let arrow = match node.expanded {
+            _ if empty && !node.branch && node.content.is_none() => "",
+            true => "v",
+            false => ">",
+        };
+        let Ok(mut label) = labels.get_mut(node.arrow) else {
+            continue;
+        };
+        if label.value != arrow {
+            label.value = arrow.to_string();
+        }
+    }
+}
+
+fn handle_tree_input(
+    mut pointer: EventReader<PointerInput>,
+    mut nodes: Query<&mut TreeNode>,
+    mut trees: Query<&mut Tree>,
+    parents: Query<&Parent>,
+    mut events: EventWriter<TreeEvent>,
+) {
+    for ev in pointer.iter() {
+        let Some(&target) = ev.entities.first() else {
+            continue;
+        };
+        // the arrow is inside of the row, the row is inside of the node
+        let Some((entity, arrow)) = parents
+            .iter_ancestors(target)
+            .take(2)
+            .find(|e| nodes.contains(*e))
+            .and_then(|e| nodes.get(e).ok().map(|n| (e, n.arrow == target)))
+        else {
+            continue;
+        };
+        let Ok(mut node) = nodes.get_mut(entity) else {
+            continue;
+        };
+        if !arrow && node.row != target {
+            continue;
+        }
+        if (arrow && ev.pressed()) || (!arrow && ev.down() && ev.presses() == 2) {
+            node.expanded = !node.expanded;
+        }
+        if !ev.pressed() || arrow {
+            continue;
+        }
+        let Some(tree) = parents.iter_ancestors(entity).find(|e| trees.contains(*e)) else {
+            continue;
+        };
+        let Ok(mut state) = trees.get_mut(tree) else {
+            continue;
+        };
+        if state.selected != Some(entity) {
+            state.selected = Some(entity);
+            events.send(TreeEvent::Selected([entity, tree]));
+        }
+    }
+}
+
+fn update_tree_selection(
+    mut elements: Elements,
+    mut trees: Query<&mut Tree, Changed<Tree>>,
+    nodes: Query<&TreeNode>,
+) {
+    for mut tree in trees.iter_mut() {
+        if tree.highlighted == tree.selected {
+            continue;
+        }
+        if let Some(node) = tree.highlighted.and_then(|e| nodes.get(e).ok()) {
+            elements.set_state(node.row, "selected".as_tag(), false);
+        }
+        if let Some(node) = tree.selected.and_then(|e| nodes.get(e).ok()) {
+            elements.set_state(node.row, "selected".as_tag(), true);
+        }
+        tree.highlighted = tree.selected;
+    }
+}
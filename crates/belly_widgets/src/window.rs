@@ -0,0 +1,513 @@
+use belly_core::*;
+use belly_macro::*;
+use bevy::{ecs::system::Command, prelude::*};
+
+/// The window isn't resized to be narrower.
+const MIN_WIDTH: f32 = 120.;
+/// The window isn't resized to be lower.
+const MIN_HEIGHT: f32 = 60.;
+
+pub(crate) struct FloatingWindowPlugin;
+impl Plugin for FloatingWindowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<FloatingWindowEvent>();
+        app.init_resource::<WindowOrder>();
+        app.register_widget::<FloatingWindow>();
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_window_input.after(input::Label::Signals),
+        );
+        app.add_system(update_windows);
+    }
+}
+
+pub enum FloatingWindowEvent {
+    /// The window is shown again after it was closed.
+    Opened([Entity; 1]),
+    /// The window is closed by the close button or from the code.
+    Closed([Entity; 1]),
+    /// The window is collapsed to its title bar.
+    Minimized([Entity; 1]),
+    /// The minimized window is expanded back.
+    Restored([Entity; 1]),
+    /// The user has finished dragging the window.
+    Moved([Entity; 1]),
+    /// The user has finished resizing the window.
+    Resized([Entity; 1]),
+}
+
+impl FloatingWindowEvent {
+    pub fn opened(&self) -> bool {
+        match self {
+            FloatingWindowEvent::Opened(_) => true,
+            _ => false,
+        }
+    }
+    pub fn closed(&self) -> bool {
+        match self {
+            FloatingWindowEvent::Closed(_) => true,
+            _ => false,
+        }
+    }
+    pub fn minimized(&self) -> bool {
+        match self {
+            FloatingWindowEvent::Minimized(_) => true,
+            _ => false,
+        }
+    }
+    pub fn restored(&self) -> bool {
+        match self {
+            FloatingWindowEvent::Restored(_) => true,
+            _ => false,
+        }
+    }
+    pub fn moved(&self) -> bool {
+        match self {
+            FloatingWindowEvent::Moved(_) => true,
+            _ => false,
+        }
+    }
+    pub fn resized(&self) -> bool {
+        match self {
+            FloatingWindowEvent::Resized(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Signal for FloatingWindowEvent {
+    fn sources(&self) -> &[Entity] {
+        match self {
+            FloatingWindowEvent::Opened(source) => source,
+            FloatingWindowEvent::Closed(source) => source,
+            FloatingWindowEvent::Minimized(source) => source,
+            FloatingWindowEvent::Restored(source) => source,
+            FloatingWindowEvent::Moved(source) => source,
+            FloatingWindowEvent::Resized(source) => source,
+        }
+    }
+}
+
+/// The stacking of the `<window>`s: the window brought to the front
+/// gets the next local [`ZIndex`], so it is drawn over its siblings.
+#[derive(Resource, Default)]
+pub struct WindowOrder {
+    last: i32,
+    front: Option<Entity>,
+}
+
+impl WindowOrder {
+    /// The window brought to the front the last.
+    pub fn front(&self) -> Option<Entity> {
+        self.front
+    }
+}
+
+/// Brings the `<window>` to the front.
+pub struct RaiseWindow(pub Entity);
+
+impl Command for RaiseWindow {
+    fn write(self, world: &mut World) {
+        if !world
+            .get::<FloatingWindow>(self.0)
+            .map_or(false, |w| !w.closed)
+        {
+            return;
+        }
+        let mut order = world.resource_mut::<WindowOrder>();
+        if order.front == Some(self.0) {
+            return;
+        }
+        order.last += 1;
+        order.front = Some(self.0);
+        let layer = order.last;
+        world.entity_mut(self.0).insert(ZIndex::Local(layer));
+    }
+}
+
+#[derive(Component, Widget)]
+#[alias(window)]
+#[signal(open, FloatingWindowEvent, opened)]
+#[signal(close, FloatingWindowEvent, closed)]
+#[signal(minimize, FloatingWindowEvent, minimized)]
+#[signal(restore, FloatingWindowEvent, restored)]
+#[signal(moveend, FloatingWindowEvent, moved)]
+#[signal(resizeend, FloatingWindowEvent, resized)]
+/// The `<window>` tag is the floating panel with the `title` bar dragging
+/// it around its parent, the grips at the right, the bottom and the corner
+/// resizing it, and the minimize and close buttons. The pressed window is
+/// brought to the front of its sibling windows and gets the `:front` state.
+/// The bindable `x`, `y`, `width` and `height` params keep the geometry
+/// in pixels (the zero size is the size of the content), the `moveend` and
+/// the `resizeend` signals are emitted when the user is done, so the layout
+/// could be saved and restored. The `closed` and the `minimized` params
+/// are bindable as well:
+/// ```rust,ignore
+/// eml! {
+///     <window title="Inventory" x=40. y=80. width=320.
+///         bind:x=to!(layout, Layout:inventory_x) bind:y=to!(layout, Layout:inventory_y)
+///         bind:closed=from!(layout, Layout:inventory_closed)
+///         on:close=connect!(layout, |l: Layout| l.save())>
+///         <span c:inventory>...</span>
+///     </window>
+/// }
+/// ```
+pub struct FloatingWindow {
+    #[param]
+    pub title: String,
+    #[param]
+    pub x: f32,
+    #[param]
+    pub y: f32,
+    #[param]
+    pub width: f32,
+    #[param]
+    pub height: f32,
+    #[param]
+    pub closed: bool,
+    #[param]
+    pub minimized: bool,
+    /// The `closed` and the `minimized` flags the events are sent for.
+    shown: Option<(bool, bool)>,
+    /// The window is laid out by the `<dock>` it is docked into.
+    pub(crate) docked: bool,
+    titlebar: Entity,
+    grip_right: Entity,
+    grip_bottom: Entity,
+    grip_corner: Entity,
+}
+
+impl FloatingWindow {
+    pub fn open(&mut self) {
+        self.closed = false;
+    }
+
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    pub fn toggle_minimized(&mut self) {
+        self.minimized = !self.minimized;
+    }
+
+    pub fn docked(&self) -> bool {
+        self.docked
+    }
+
+    pub(crate) fn titlebar(&self) -> Entity {
+        self.titlebar
+    }
+}
+
+/// What the pointer does with the window.
+#[derive(Clone, Copy)]
+enum WindowDrag {
+    /// Moves the window, the `grab` is the pointer offset from its corner.
+    Move { grab: Vec2 },
+    /// Resizes the window, the flags tell which sides follow the pointer.
+    Resize { width: bool, height: bool },
+}
+
+impl WidgetBuilder for FloatingWindow {
+    fn setup(&mut self, ctx: &mut ElementContext) {
+        let this = ctx.entity();
+        let content = ctx.content();
+        let titlebar = self.titlebar;
+        let grip_right = self.grip_right;
+        let grip_bottom = self.grip_bottom;
+        let grip_corner = self.grip_corner;
+        ctx.render(eml! {
+            <span c:window interactable
+                s:left=managed() s:top=managed()
+                s:width=managed() s:height=managed()
+                s:z-index=managed()
+            >
+                <span {titlebar} c:window-titlebar interactable>
+                    <label c:window-title bind:value=from!(this, FloatingWindow:title)/>
+                    <button c:window-minimize
+                        on:press=connect!(this, |w: FloatingWindow| w.toggle_minimized())>
+                        "_"
+                    </button>
+                    <button c:window-close on:press=connect!(this, |w: FloatingWindow| w.close())>
+                        "x"
+                    </button>
+                </span>
+                <span c:window-body>{content}</span>
+                <span {grip_right} c:window-grip c:window-grip-right interactable/>
+                <span {grip_bottom} c:window-grip c:window-grip-bottom interactable/>
+                <span {grip_corner} c:window-grip c:window-grip-corner interactable/>
+            </span>
+        })
+    }
+
+    fn styles() -> &'static str {
+        r#"
+            window {
+                position-type: absolute;
+                flex-direction: column;
+                min-width: 120px;
+                background-color: #dfdfdf;
+                border: 1px solid #2f2f2f;
+                color: #2f2f2f;
+                overflow: hidden;
+            }
+            window:closed {
+                display: none;
+            }
+            window .window-titlebar {
+                align-items: center;
+                padding: 2px 2px 2px 8px;
+                background-color: #7f7f7f;
+                color: #efefef;
+                cursor: move;
+            }
+            window:front .window-titlebar {
+                background-color: #3f6f9f;
+            }
+            window .window-title {
+                flex-grow: 1;
+            }
+            window .window-titlebar button {
+                min-width: 24px;
+                margin: 0px 0px 0px 2px;
+            }
+            window .window-body {
+                flex-direction: column;
+                flex-grow: 1;
+                flex-shrink: 1;
+                padding: 6px;
+            }
+            window:minimized .window-body,
+            window:minimized .window-grip {
+                display: none;
+            }
+            window .window-grip {
+                position-type: absolute;
+            }
+            window .window-grip-right {
+                top: 0px;
+                bottom: 0px;
+                right: 0px;
+                width: 6px;
+                cursor: ew-resize;
+            }
+            window .window-grip-bottom {
+                left: 0px;
+                right: 0px;
+                bottom: 0px;
+                height: 6px;
+                cursor: ns-resize;
+            }
+            window .window-grip-corner {
+                right: 0px;
+                bottom: 0px;
+                width: 12px;
+                height: 12px;
+                cursor: se-resize;
+            }
+        "#
+    }
+}
+
+fn px_or_auto(value: f32) -> Val {
+    if value > 0. {
+        Val::Px(value)
+    } else {
+        Val::Undefined
+    }
+}
+
+fn update_windows(
+    mut commands: Commands,
+    mut elements: Elements,
+    order: Res<WindowOrder>,
+    mut windows: Query<(Entity, &mut FloatingWindow, &mut Style)>,
+    mut events: EventWriter<FloatingWindowEvent>,
+) {
+    for (entity, mut window, mut style) in windows.iter_mut() {
+        if order.is_changed() {
+            elements.set_state(entity, "front".as_tag(), order.front == Some(entity));
+        }
+        if !window.is_changed() {
+            continue;
+        }
+        // the docked window is laid out by the dock area
+        let (left, top) = if window.docked {
+            (Val::Undefined, Val::Undefined)
+        } else {
+            (Val::Px(window.x), Val::Px(window.y))
+        };
+        if style.position.left != left || style.position.top != top {
+            style.position.left = left;
+            style.position.top = top;
+        }
+        let width = if window.docked {
+            Val::Undefined
+        } else {
+            px_or_auto(window.width)
+        };
+        // the minimized window is as high as its title bar
+        let height = if window.minimized || window.docked {
+            Val::Undefined
+        } else {
+            px_or_auto(window.height)
+        };
+        if style.size.width != width || style.size.height != height {
+            style.size.width = width;
+            style.size.height = height;
+        }
+        elements.set_state(entity, "docked".as_tag(), window.docked);
+        let flags = (window.closed, window.minimized);
+        if window.shown == Some(flags) {
+            continue;
+        }
+        if window.shown.is_none() && !window.closed {
+            // the new window is shown over the present ones
+            commands.add(RaiseWindow(entity));
+        }
+        let (closed, minimized) = window.shown.unwrap_or((false, false));
+        window.shown = Some(flags);
+        elements.set_state(entity, "closed".as_tag(), window.closed);
+        elements.set_state(entity, "minimized".as_tag(), window.minimized);
+        if closed != window.closed {
+            if window.closed {
+                events.send(FloatingWindowEvent::Closed([entity]));
+            } else {
+                commands.add(RaiseWindow(entity));
+                events.send(FloatingWindowEvent::Opened([entity]));
+            }
+        }
+        if minimized != window.minimized {
+            if window.minimized {
+                events.send(FloatingWindowEvent::Minimized([entity]));
+            } else {
+                events.send(FloatingWindowEvent::Restored([entity]));
+            }
+        }
+    }
+}
+
+/// The top left corner of the node on the screen.
+fn top_left(nodes: &Query<(&GlobalTransform, &Node)>, entity: Entity) -> Option<Vec2> {
+    let (transform, node) = nodes.get(entity).ok()?;
+    Some(transform.translation().truncate() - node.size() / 2.)
+}
+
+fn handle_window_input(
+    mut commands: Commands,
+    mut pointer: EventReader<PointerInput>,
+    mut windows: Query<(Entity, &mut FloatingWindow)>,
+    nodes: Query<(&GlobalTransform, &Node)>,
+    parents: Query<&Parent>,
+    mut active: Local<Option<(Entity, WindowDrag, Vec2, Rect)>>,
+    mut events: EventWriter<FloatingWindowEvent>,
+) {
+    for ev in pointer.iter() {
+        if ev.down() && active.is_none() {
+            let Some(&target) = ev.entities.first() else {
+                continue;
+            };
+            let Some(entity) = std::iter::once(target)
+                .chain(parents.iter_ancestors(target))
+                .find(|e| windows.contains(*e))
+            else {
+                continue;
+            };
+            commands.add(RaiseWindow(entity));
+            let Ok((_, window)) = windows.get(entity) else {
+                continue;
+            };
+            let drag = if target == window.titlebar {
+                let corner = top_left(&nodes, entity).unwrap_or(ev.pos);
+                WindowDrag::Move {
+                    grab: ev.pos - corner,
+                }
+            } else if window.docked {
+                // the dock area sizes the docked window
+                continue;
+            } else if target == window.grip_right {
+                WindowDrag::Resize {
+                    width: true,
+                    height: false,
+                }
+            } else if target == window.grip_bottom {
+                WindowDrag::Resize {
+                    width: false,
+                    height: true,
+                }
+            } else if target == window.grip_corner {
+                WindowDrag::Resize {
+                    width: true,
+                    height: true,
+                }
+            } else {
+                continue;
+            };
+            // the auto sized window starts resizing from its actual size
+            let size = nodes.get(entity).map(|(_, n)| n.size()).unwrap_or_default();
+            let start = Rect::from_corners(
+                Vec2::new(window.x, window.y),
+                Vec2::new(window.x, window.y) + size,
+            );
+            *active = Some((entity, drag, ev.pos, start));
+            continue;
+        }
+        let Some((entity, drag, from, start)) = *active else {
+            continue;
+        };
+        if ev.dragging() {
+            let Ok((_, mut window)) = windows.get_mut(entity) else {
+                continue;
+            };
+            // the space of the parent keeps the title bar reachable
+            let parent = parents.get(entity).ok().map(|p| p.get());
+            let space = parent
+                .and_then(|p| nodes.get(p).ok())
+                .map(|(_, n)| n.size());
+            match drag {
+                // the window dragged out of the dock follows the pointer
+                // from the moment it is undocked
+                WindowDrag::Move { .. } if window.docked => {}
+                WindowDrag::Move { grab } => {
+                    let origin = parent.and_then(|p| top_left(&nodes, p)).unwrap_or_default();
+                    let mut position = ev.pos - grab - origin;
+                    if let Some(space) = space {
+                        let size = nodes.get(entity).map(|(_, n)| n.size()).unwrap_or_default();
+                        let limit = (space - size).max(Vec2::ZERO);
+                        position = position.clamp(Vec2::ZERO, limit);
+                    }
+                    if window.x != position.x || window.y != position.y {
+                        window.x = position.x;
+                        window.y = position.y;
+                    }
+                }
+                WindowDrag::Resize { width, height } => {
+                    let delta = ev.pos - from;
+                    let size = (start.size() + delta).max(Vec2::new(MIN_WIDTH, MIN_HEIGHT));
+                    if width && window.width != size.x {
+                        window.width = size.x;
+                    }
+                    if height && window.height != size.y {
+                        window.height = size.y;
+                    }
+                }
+            }
+        }
+        if ev.up() || ev.drag_stop() {
+            *active = None;
+            let moved = windows.get(entity).map_or(false, |(_, w)| match drag {
+                WindowDrag::Move { .. } => !w.docked && (w.x != start.min.x || w.y != start.min.y),
+                WindowDrag::Resize { .. } => {
+                    (w.width > 0. && w.width != start.width())
+                        || (w.height > 0. && w.height != start.height())
+                }
+            });
+            if moved {
+                events.send(match drag {
+                    WindowDrag::Move { .. } => FloatingWindowEvent::Moved([entity]),
+                    WindowDrag::Resize { .. } => FloatingWindowEvent::Resized([entity]),
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use bevy::{
+    math::Rect,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension},
+    utils::{HashMap, HashSet},
+};
+
+use crate::{compute_stylebox_configuration, Stylebox};
+
+/// Optional plugin which packs small images used by [`Stylebox`]es (icons,
+/// nine-patches, backgrounds) into runtime atlases, so the screen with a lot
+/// of them binds a few textures instead of one texture per image:
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy_stylebox::*;
+///
+/// fn main() {
+///    let mut app = App::new();
+///    app.add_plugins(DefaultPlugins);
+///    app.add_plugin(StyleboxPlugin);
+///    app.add_plugin(StyleboxAtlasPlugin::default());
+/// }
+/// ```
+/// Once the image is loaded it is packed with the rest of images loaded
+/// during the same frame, and the [`Stylebox::texture`] & [`Stylebox::region`]
+/// of every stylebox using it are rewritten to point to the atlas region.
+/// The original values are kept in the [`AtlasedStylebox`] component.
+///
+/// Only styleboxes are affected: `bevy_ui` can't render the region of the
+/// `UiImage`, so plain image nodes keep using the original textures.
+pub struct StyleboxAtlasPlugin {
+    /// Images larger than this size are never packed.
+    pub max_image_size: Vec2,
+    /// The maximum size of the single atlas texture.
+    pub max_atlas_size: Vec2,
+    /// The edge pixels of every packed image are repeated this many times
+    /// around it, so the filtering of the scaled nine-patches doesn't pick
+    /// the pixels of the neighbouring images.
+    pub padding: u32,
+}
+
+impl Default for StyleboxAtlasPlugin {
+    fn default() -> Self {
+        StyleboxAtlasPlugin {
+            max_image_size: Vec2::splat(256.),
+            max_atlas_size: Vec2::splat(2048.),
+            padding: 2,
+        }
+    }
+}
+
+impl Plugin for StyleboxAtlasPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StyleboxAtlases {
+            max_image_size: self.max_image_size,
+            max_atlas_size: self.max_atlas_size,
+            padding: self.padding,
+            regions: Default::default(),
+            skipped: Default::default(),
+            atlases: Default::default(),
+        })
+        .add_system(pack_stylebox_images.before(compute_stylebox_configuration));
+    }
+}
+
+/// The place of the packed image inside the atlas.
+#[derive(Clone, Debug)]
+pub struct AtlasRegion {
+    /// The atlas texture.
+    pub texture: Handle<Image>,
+    /// The size of the atlas texture.
+    pub atlas_size: Vec2,
+    /// The region of the atlas occupied by the image in pixels, without
+    /// the padding.
+    pub rect: Rect,
+}
+
+/// Keeps track of the packed images.
+#[derive(Resource)]
+pub struct StyleboxAtlases {
+    max_image_size: Vec2,
+    max_atlas_size: Vec2,
+    padding: u32,
+    regions: HashMap<Handle<Image>, AtlasRegion>,
+    skipped: HashSet<Handle<Image>>,
+    atlases: Vec<Handle<TextureAtlas>>,
+}
+
+impl StyleboxAtlases {
+    /// Returns the atlas region the `image` was packed into.
+    pub fn region(&self, image: &Handle<Image>) -> Option<&AtlasRegion> {
+        self.regions.get(image)
+    }
+
+    /// All the atlases built so far.
+    pub fn atlases(&self) -> &[Handle<TextureAtlas>] {
+        &self.atlases
+    }
+}
+
+/// The [`Stylebox`] values before the texture was replaced by the atlas.
+#[derive(Component, Clone, Debug)]
+pub struct AtlasedStylebox {
+    pub texture: Handle<Image>,
+    pub region: UiRect,
+    atlas: Handle<Image>,
+    atlas_region: UiRect,
+}
+
+fn pack_stylebox_images(
+    mut commands: Commands,
+    mut atlases: ResMut<StyleboxAtlases>,
+    mut images: ResMut<Assets<Image>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut styleboxes: Query<(Entity, &mut Stylebox, Option<&AtlasedStylebox>)>,
+) {
+    let mut pending = HashSet::default();
+    for (_, stylebox, atlased) in styleboxes.iter() {
+        let texture = &stylebox.texture;
+        if atlased.map(|a| &a.atlas == texture).unwrap_or(false)
+            || texture == &Handle::<Image>::default()
+            || atlases.regions.contains_key(texture)
+            || atlases.skipped.contains(texture)
+        {
+            continue;
+        }
+        let Some(image) = images.get(texture) else { continue };
+        let size = image.size();
+        if size.x > atlases.max_image_size.x
+            || size.y > atlases.max_image_size.y
+            || size.x < 1.
+            || size.y < 1.
+        {
+            atlases.skipped.insert(texture.clone_weak());
+        } else {
+            pending.insert(texture.clone_weak());
+        }
+    }
+
+    if !pending.is_empty() {
+        let mut builder = TextureAtlasBuilder::default()
+            .initial_size(atlases.max_image_size)
+            .max_size(atlases.max_atlas_size);
+        for handle in pending.iter() {
+            let padded = extrude(images.get(handle).unwrap(), atlases.padding);
+            builder.add_texture(handle.clone_weak(), &padded);
+        }
+        match builder.finish(&mut images) {
+            Ok(atlas) => {
+                let padding = Vec2::splat(atlases.padding as f32);
+                for (handle, idx) in atlas.texture_handles.iter().flatten() {
+                    let rect = atlas.textures[*idx];
+                    atlases.regions.insert(
+                        handle.clone_weak(),
+                        AtlasRegion {
+                            texture: atlas.texture.clone(),
+                            atlas_size: atlas.size,
+                            rect: Rect {
+                                min: rect.min + padding,
+                                max: rect.max - padding,
+                            },
+                        },
+                    );
+                }
+                let atlas = texture_atlases.add(atlas);
+                atlases.atlases.push(atlas);
+            }
+            Err(e) => {
+                warn!("Unable to pack {} stylebox images: {:?}", pending.len(), e);
+                atlases.skipped.extend(pending);
+            }
+        }
+    }
+
+    for (entity, mut stylebox, atlased) in styleboxes.iter_mut() {
+        let (source, region) = match atlased {
+            Some(atlased) if atlased.atlas == stylebox.texture => continue,
+            // only the texture was restored by the restyling
+            Some(atlased)
+                if atlased.texture == stylebox.texture
+                    && atlased.atlas_region == stylebox.region =>
+            {
+                (atlased.texture.clone(), atlased.region)
+            }
+            _ => (stylebox.texture.clone(), stylebox.region),
+        };
+        let Some(atlas) = atlases.regions.get(&source) else { continue };
+        let Some(image) = images.get(&source) else { continue };
+        let size = image.size();
+        let px = |val: Val, size: f32| match val {
+            Val::Px(px) => px,
+            Val::Percent(percent) => size * percent / 100.,
+            _ => 0.,
+        };
+        let atlas_region = UiRect {
+            left: Val::Px(atlas.rect.min.x + px(region.left, size.x)),
+            right: Val::Px(atlas.atlas_size.x - atlas.rect.max.x + px(region.right, size.x)),
+            top: Val::Px(atlas.rect.min.y + px(region.top, size.y)),
+            bottom: Val::Px(atlas.atlas_size.y - atlas.rect.max.y + px(region.bottom, size.y)),
+        };
+        commands.entity(entity).insert(AtlasedStylebox {
+            texture: source,
+            region,
+            atlas: atlas.texture.clone(),
+            atlas_region,
+        });
+        stylebox.texture = atlas.texture.clone();
+        stylebox.region = atlas_region;
+    }
+}
+
+/// Copies the `image` with its edge pixels repeated `padding` times around.
+fn extrude(image: &Image, padding: u32) -> Image {
+    let Extent3d { width, height, .. } = image.texture_descriptor.size;
+    let pixel = image.data.len() / (width * height) as usize;
+    let size = Extent3d {
+        width: width + padding * 2,
+        height: height + padding * 2,
+        depth_or_array_layers: 1,
+    };
+    let mut data = Vec::with_capacity((size.width * size.height) as usize * pixel);
+    for y in 0..size.height {
+        let y = y.saturating_sub(padding).min(height - 1);
+        for x in 0..size.width {
+            let x = x.saturating_sub(padding).min(width - 1);
+            let offset = (y * width + x) as usize * pixel;
+            data.extend_from_slice(&image.data[offset..offset + pixel]);
+        }
+    }
+    Image::new(
+        size,
+        TextureDimension::D2,
+        data,
+        image.texture_descriptor.format,
+    )
+}
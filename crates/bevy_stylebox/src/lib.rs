@@ -1,5 +1,9 @@
 #![doc = include_str!("../README.md")]
 
+mod atlas;
+
+pub use atlas::*;
+
 use bevy::{
     math::Rect,
     prelude::*,
@@ -14,7 +14,30 @@ pub mod prelude {
     // structs
     pub use belly_core::eml::asset::EmlAsset;
     pub use belly_core::eml::asset::EmlScene;
+    pub use belly_core::input::FocusEvent;
+    pub use belly_core::input::FocusTrap;
+    pub use belly_core::input::Focused;
+    pub use belly_core::input::Navigation;
+    pub use belly_core::input::PointerSettings;
+    pub use belly_core::shortcut::Shortcut;
+    pub use belly_core::shortcut::ShortcutScope;
+    pub use belly_core::context_menu::ContextMenuElement;
+    pub use belly_core::context_menu::ContextMenuPopup;
+    pub use belly_core::context_menu::KeepContextMenuOpen;
+    pub use belly_core::context_menu::OpenContextMenu;
+    pub use belly_core::dnd::DragEvent;
+    pub use belly_core::dnd::DragPayload;
+    pub use belly_core::touch::PinchEvent;
+    pub use belly_core::input::RequestFocus;
+    pub use belly_core::eml::asset::EmlWhitespace;
+    pub use belly_core::eml::asset::Whitespace;
+    pub use belly_core::ess::ScrollSettings;
     pub use belly_core::ess::StyleSheet;
+    pub use belly_core::relations::bind::BindingStats;
+    pub use belly_core::relations::bind::Unbind;
+    pub use belly_core::relations::Disconnect;
+    pub use belly_core::propagation::Listen;
+    pub use belly_core::propagation::PropagationContext;
 
     // macros
     pub use belly_core::bind;
@@ -26,8 +49,14 @@ pub mod prelude {
     // traits
     pub use belly_core::eml::build::WidgetBuilder;
     pub use belly_core::eml::content::IntoContent;
+    pub use belly_core::eml::export::ToEmlExtension;
+    pub use belly_core::eml::handlers::RegisterHandlerExtension;
     pub use belly_core::ess::ColorFromHexExtension;
+    pub use belly_core::ess::StyleCommandsExtension;
+    pub use belly_core::relations::bind::RateLimitExtension;
+    pub use belly_core::relations::reflect::ReflectBindExtension;
     pub use belly_core::relations::transform::ColorTransformerExtension;
+    pub use belly_core::relations::transform::ValTransformerExtension;
     pub use belly_core::ExpandElementsExt;
     pub use belly_core::WithElements;
 
@@ -45,6 +74,7 @@ pub mod prelude {
 
 pub mod build {
     pub use super::prelude::*;
+    pub use belly_core::build::*;
     pub use belly_core::ElementBuilder;
     pub use belly_core::ElementContext;
     pub use belly_core::RegisterWidgetExtension;